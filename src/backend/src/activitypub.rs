@@ -0,0 +1,610 @@
+use crate::http::{http_date, iso8601_date};
+use crate::rsa::{RsaPrivateKey, RsaPublicKey};
+use crate::types::{Post, RemoteActor, UserProfile};
+use data_encoding::BASE64;
+use ic_cdk::api::management_canister::http_request::{
+    http_request, CanisterHttpRequestArgument, HttpHeader, HttpMethod,
+    HttpResponse as OutcallResponse, TransformArgs, TransformContext,
+};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+
+/// Media type of ActivityPub documents
+pub const ACTIVITY_JSON: &str = "application/activity+json";
+
+/// Largest inbox body accepted from remote servers
+pub const MAX_INBOX_BODY_BYTES: usize = 64 * 1024;
+
+/// Maximum clock skew accepted on signed requests (seconds)
+pub const MAX_SIGNATURE_AGE_SECONDS: u64 = 12 * 3_600;
+
+/// Maximum number of inboxes a single post is delivered to
+pub const MAX_POST_DELIVERIES: usize = 100;
+
+/// Canister query used to strip non-deterministic headers from outcall responses
+pub const TRANSFORM_FUNCTION: &str = "transform_federation_response";
+
+const ACTIVITY_STREAMS_CONTEXT: &str = "https://www.w3.org/ns/activitystreams";
+const SECURITY_CONTEXT: &str = "https://w3id.org/security/v1";
+const MASTODON_NAMESPACE: &str = "http://joinmastodon.org/ns#";
+const PUBLIC_COLLECTION: &str = "https://www.w3.org/ns/activitystreams#Public";
+
+/// Largest remote response read by outcalls
+const MAX_REMOTE_RESPONSE_BYTES: u64 = 64 * 1024;
+
+/// Cycles attached to each outcall (unused cycles are refunded)
+const FEDERATION_OUTCALL_CYCLES: u128 = 20_000_000_000;
+
+/// Headers every inbound signature must cover
+const REQUIRED_SIGNED_HEADERS: [&str; 4] = ["(request-target)", "host", "date", "digest"];
+
+// ============================================================================
+// ACTIVITY DOCUMENTS
+// ============================================================================
+
+/// Whether an Accept header asks for ActivityPub JSON rather than HTML
+pub fn is_activity_request(accept: Option<&str>) -> bool {
+    accept
+        .map(|accept| accept.contains(ACTIVITY_JSON) || accept.contains("application/ld+json"))
+        .unwrap_or(false)
+}
+
+pub fn actor_id(base_url: &str, handle: &str) -> String {
+    format!("{base_url}/users/{handle}")
+}
+
+fn key_id(base_url: &str, handle: &str) -> String {
+    format!("{}#main-key", actor_id(base_url, handle))
+}
+
+/// Person document for a local profile
+pub fn actor_document(base_url: &str, profile: &UserProfile, public_key_pem: &str) -> Value {
+    let id = actor_id(base_url, &profile.handle);
+    let mut actor = json!({
+        "@context": [
+            ACTIVITY_STREAMS_CONTEXT,
+            SECURITY_CONTEXT,
+            {
+                "toot": MASTODON_NAMESPACE,
+                "discoverable": "toot:discoverable",
+                "indexable": "toot:indexable",
+            },
+        ],
+        "id": id,
+        "type": "Person",
+        "preferredUsername": profile.handle,
+        "name": profile.display_name,
+        "summary": profile.bio,
+        "url": id,
+        "inbox": format!("{id}/inbox"),
+        "outbox": format!("{id}/outbox"),
+        "followers": format!("{id}/followers"),
+        "manuallyApprovesFollowers": false,
+        // Remote servers leave users who opted out of search out of their
+        // directories and search indexes
+        "discoverable": profile.privacy_settings.searchable,
+        "indexable": profile.privacy_settings.searchable,
+        "published": iso8601_date(profile.created_at),
+        "publicKey": {
+            "id": key_id(base_url, &profile.handle),
+            "owner": id,
+            "publicKeyPem": public_key_pem,
+        },
+    });
+
+    if let Some(asset_id) = crate::avatar_store::asset_id(&profile.avatar) {
+        actor["icon"] = json!({ "type": "Image", "url": format!("{base_url}/avatars/{asset_id}") });
+    }
+    if profile.banner.starts_with("https://") {
+        actor["image"] = json!({ "type": "Image", "url": profile.banner });
+    }
+    actor
+}
+
+/// Note object for a public post
+pub fn note_object(base_url: &str, handle: &str, post: &Post) -> Value {
+    let actor = actor_id(base_url, handle);
+    json!({
+        "id": format!("{base_url}/posts/{}", post.id.0),
+        "type": "Note",
+        "attributedTo": actor,
+        "content": crate::http::xml_escape(&post.content),
+        "published": iso8601_date(post.created_at),
+        "url": format!("{base_url}/posts/{}", post.id.0),
+        "to": [PUBLIC_COLLECTION],
+        "cc": [format!("{actor}/followers")],
+    })
+}
+
+/// Create activity wrapping a public post
+pub fn create_activity(base_url: &str, handle: &str, post: &Post) -> Value {
+    let note = note_object(base_url, handle, post);
+    json!({
+        "@context": ACTIVITY_STREAMS_CONTEXT,
+        "id": format!("{}/activity", note["id"].as_str().unwrap_or_default()),
+        "type": "Create",
+        "actor": note["attributedTo"],
+        "published": note["published"],
+        "to": note["to"],
+        "cc": note["cc"],
+        "object": note,
+    })
+}
+
+/// Outbox collection listing the most recent public posts inline
+pub fn outbox_collection(base_url: &str, handle: &str, total: usize, posts: &[&Post]) -> Value {
+    json!({
+        "@context": ACTIVITY_STREAMS_CONTEXT,
+        "id": format!("{}/outbox", actor_id(base_url, handle)),
+        "type": "OrderedCollection",
+        "totalItems": total,
+        "orderedItems": posts
+            .iter()
+            .map(|post| create_activity(base_url, handle, post))
+            .collect::<Vec<_>>(),
+    })
+}
+
+/// Followers collection; only the count is published
+pub fn followers_collection(base_url: &str, handle: &str, total: u64) -> Value {
+    json!({
+        "@context": ACTIVITY_STREAMS_CONTEXT,
+        "id": format!("{}/followers", actor_id(base_url, handle)),
+        "type": "OrderedCollection",
+        "totalItems": total,
+    })
+}
+
+/// Accept activity answering a remote Follow
+pub fn accept_activity(base_url: &str, handle: &str, follow: &Value) -> Value {
+    let actor = actor_id(base_url, handle);
+    let follow_id = follow["id"].as_str().unwrap_or_default();
+    let suffix: String = Sha256::digest(follow_id.as_bytes())[..8]
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect();
+    json!({
+        "@context": ACTIVITY_STREAMS_CONTEXT,
+        "id": format!("{actor}#accepts/{suffix}"),
+        "type": "Accept",
+        "actor": actor,
+        "object": follow,
+    })
+}
+
+/// Extracts the local handle from a WebFinger `resource` parameter
+///
+/// Accepts `acct:handle@domain` (optionally `@`-prefixed) and actor URLs on
+/// `domain`; the domain comparison is case-insensitive.
+pub fn parse_webfinger_resource(resource: &str, domain: &str) -> Option<String> {
+    let resource = resource.trim();
+    let handle = if let Some(account) = resource.strip_prefix("acct:") {
+        let (handle, host) = account.trim_start_matches('@').rsplit_once('@')?;
+        host.eq_ignore_ascii_case(domain).then_some(handle)?
+    } else {
+        let rest = resource.strip_prefix("https://")?;
+        let (host, path) = rest.split_once('/')?;
+        if !host.eq_ignore_ascii_case(domain) {
+            return None;
+        }
+        path.strip_prefix("users/")?
+    };
+
+    (!handle.is_empty() && !handle.contains('/')).then(|| handle.to_string())
+}
+
+/// JSON Resource Descriptor answering a WebFinger lookup
+pub fn webfinger_document(base_url: &str, domain: &str, handle: &str) -> Value {
+    let actor = actor_id(base_url, handle);
+    json!({
+        "subject": format!("acct:{handle}@{domain}"),
+        "aliases": [actor],
+        "links": [
+            {
+                "rel": "self",
+                "type": ACTIVITY_JSON,
+                "href": actor,
+            },
+            {
+                "rel": "http://webfinger.net/rel/profile-page",
+                "type": "text/html",
+                "href": actor,
+            },
+        ],
+    })
+}
+
+// ============================================================================
+// HTTP SIGNATURES
+// ============================================================================
+
+/// Parsed `Signature` header (draft-cavage-http-signatures)
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SignatureParams {
+    pub key_id: String,
+    pub headers: Vec<String>,
+    pub signature: Vec<u8>,
+}
+
+impl SignatureParams {
+    /// Parses a `Signature` header, accepting only RSA-SHA256 signatures
+    pub fn parse(header: &str) -> Result<Self, String> {
+        let mut key_id = None;
+        let mut headers = vec!["date".to_string()];
+        let mut signature = None;
+
+        for field in header.split(',') {
+            let (name, value) = field
+                .trim()
+                .split_once('=')
+                .ok_or("Malformed signature header")?;
+            let value = value.trim_matches('"');
+            match name {
+                "keyId" => key_id = Some(value.to_string()),
+                "algorithm" if value != "rsa-sha256" && value != "hs2019" => {
+                    return Err(format!("Unsupported signature algorithm: {value}"));
+                }
+                "headers" => {
+                    headers = value.split_whitespace().map(str::to_lowercase).collect();
+                }
+                "signature" => {
+                    signature = Some(
+                        BASE64
+                            .decode(value.as_bytes())
+                            .map_err(|_| "Invalid signature encoding".to_string())?,
+                    );
+                }
+                _ => {}
+            }
+        }
+
+        Ok(Self {
+            key_id: key_id.ok_or("Signature is missing keyId")?,
+            headers,
+            signature: signature.ok_or("Signature is missing signature")?,
+        })
+    }
+
+    /// Checks that the signature covers the headers required for inbox deliveries
+    pub fn covers_required_headers(&self) -> bool {
+        REQUIRED_SIGNED_HEADERS
+            .iter()
+            .all(|required| self.headers.iter().any(|header| header == required))
+    }
+}
+
+/// Builds the string covered by an HTTP signature
+///
+/// `header` looks up request header values by lowercase name.
+pub fn signing_string<'a>(
+    method: &str,
+    target: &str,
+    names: &[String],
+    header: impl Fn(&str) -> Option<&'a str>,
+) -> Result<String, String> {
+    names
+        .iter()
+        .map(|name| match name.as_str() {
+            "(request-target)" => Ok(format!(
+                "(request-target): {} {target}",
+                method.to_lowercase()
+            )),
+            name => header(name)
+                .map(|value| format!("{name}: {}", value.trim()))
+                .ok_or_else(|| format!("Signed header missing: {name}")),
+        })
+        .collect::<Result<Vec<_>, _>>()
+        .map(|lines| lines.join("\n"))
+}
+
+/// `Digest` header value for a request body
+pub fn digest_header(body: &[u8]) -> String {
+    format!("SHA-256={}", BASE64.encode(&Sha256::digest(body)))
+}
+
+/// Whether a `Digest` header matches the body (any listed SHA-256 digest)
+pub fn digest_matches(header: &str, body: &[u8]) -> bool {
+    let expected = BASE64.encode(&Sha256::digest(body));
+    header.split(',').any(|digest| {
+        digest
+            .trim()
+            .split_once('=')
+            .map(|(algorithm, value)| {
+                algorithm.eq_ignore_ascii_case("SHA-256") && value == expected
+            })
+            .unwrap_or(false)
+    })
+}
+
+/// Verifies a signature against a PEM public key
+pub fn verify_signature(public_key_pem: &str, signing_string: &str, signature: &[u8]) -> bool {
+    RsaPublicKey::from_pem(public_key_pem)
+        .map(|key| key.verify_sha256(signing_string.as_bytes(), signature))
+        .unwrap_or(false)
+}
+
+/// Splits an https URL into host and path (including the query string)
+fn split_url(url: &str) -> Option<(&str, &str)> {
+    let rest = url.strip_prefix("https://")?;
+    match rest.find('/') {
+        Some(index) => Some((&rest[..index], &rest[index..])),
+        None => Some((rest, "/")),
+    }
+}
+
+// ============================================================================
+// OUTCALLS
+// ============================================================================
+
+/// Fetches the actor owning `key_id` and extracts its signing key and inboxes
+///
+/// # Security
+/// * The key must be declared on the actor document and owned by that actor
+pub async fn fetch_remote_actor(key_id: &str, now: u64) -> Result<RemoteActor, String> {
+    let document_url = key_id.split('#').next().unwrap_or(key_id);
+    if split_url(document_url).is_none() {
+        return Err("Remote key must be served over https".to_string());
+    }
+
+    let request = CanisterHttpRequestArgument {
+        url: document_url.to_string(),
+        max_response_bytes: Some(MAX_REMOTE_RESPONSE_BYTES),
+        method: HttpMethod::GET,
+        headers: vec![HttpHeader {
+            name: "Accept".to_string(),
+            value: ACTIVITY_JSON.to_string(),
+        }],
+        body: None,
+        transform: Some(TransformContext::from_name(
+            TRANSFORM_FUNCTION.to_string(),
+            Vec::new(),
+        )),
+    };
+
+    let (response,) = http_request(request, FEDERATION_OUTCALL_CYCLES)
+        .await
+        .map_err(|(code, msg)| format!("Actor fetch failed: {code:?} {msg}"))?;
+    if response.status != 200u16 {
+        return Err(format!("Actor fetch returned status {}", response.status));
+    }
+
+    let actor: Value =
+        serde_json::from_slice(&response.body).map_err(|_| "Invalid actor document")?;
+    parse_remote_actor(&actor, key_id, now)
+}
+
+/// Extracts a verified signing key from a fetched actor document
+fn parse_remote_actor(actor: &Value, key_id: &str, now: u64) -> Result<RemoteActor, String> {
+    let id = actor["id"].as_str().ok_or("Actor document has no id")?;
+    let inbox = actor["inbox"]
+        .as_str()
+        .ok_or("Actor document has no inbox")?;
+    let key = &actor["publicKey"];
+
+    if key["id"].as_str() != Some(key_id) || key["owner"].as_str() != Some(id) {
+        return Err("Signing key is not owned by the actor".to_string());
+    }
+    let public_key_pem = key["publicKeyPem"]
+        .as_str()
+        .ok_or("Actor document has no public key")?;
+    RsaPublicKey::from_pem(public_key_pem)?;
+
+    Ok(RemoteActor {
+        id: id.to_string(),
+        inbox: inbox.to_string(),
+        shared_inbox: actor["endpoints"]["sharedInbox"]
+            .as_str()
+            .map(str::to_string),
+        key_id: key_id.to_string(),
+        public_key_pem: public_key_pem.to_string(),
+        fetched_at: now,
+    })
+}
+
+/// Delivers a signed activity to a remote inbox
+///
+/// # Arguments
+/// * `inbox` - Remote inbox URL (https only)
+/// * `activity` - Activity to POST
+/// * `base_url` / `handle` - Local actor the delivery is signed as
+/// * `private_key_pem` - Instance signing key
+/// * `now` - Consensus time, so every replica sends identical headers
+pub async fn deliver(
+    inbox: &str,
+    activity: &Value,
+    base_url: &str,
+    handle: &str,
+    private_key_pem: &str,
+    now: u64,
+) -> Result<(), String> {
+    let (host, path) = split_url(inbox).ok_or("Inbox must be served over https")?;
+    let body = serde_json::to_vec(activity).map_err(|e| e.to_string())?;
+    let date = http_date(now);
+    let digest = digest_header(&body);
+
+    let names: Vec<String> = REQUIRED_SIGNED_HEADERS
+        .iter()
+        .map(|name| name.to_string())
+        .collect();
+    let to_sign = signing_string("post", path, &names, |name| match name {
+        "host" => Some(host),
+        "date" => Some(date.as_str()),
+        "digest" => Some(digest.as_str()),
+        _ => None,
+    })?;
+    let signature = RsaPrivateKey::from_pem(private_key_pem)?.sign_sha256(to_sign.as_bytes())?;
+    let signature_header = format!(
+        "keyId=\"{}\",algorithm=\"rsa-sha256\",headers=\"{}\",signature=\"{}\"",
+        key_id(base_url, handle),
+        names.join(" "),
+        BASE64.encode(&signature)
+    );
+
+    let header = |name: &str, value: &str| HttpHeader {
+        name: name.to_string(),
+        value: value.to_string(),
+    };
+    let request = CanisterHttpRequestArgument {
+        url: inbox.to_string(),
+        max_response_bytes: Some(4 * 1024),
+        method: HttpMethod::POST,
+        headers: vec![
+            header("Content-Type", ACTIVITY_JSON),
+            header("Host", host),
+            header("Date", &date),
+            header("Digest", &digest),
+            header("Signature", &signature_header),
+        ],
+        body: Some(body),
+        transform: Some(TransformContext::from_name(
+            TRANSFORM_FUNCTION.to_string(),
+            Vec::new(),
+        )),
+    };
+
+    let (response,) = http_request(request, FEDERATION_OUTCALL_CYCLES)
+        .await
+        .map_err(|(code, msg)| format!("Delivery failed: {code:?} {msg}"))?;
+    if response.status >= 300u16 {
+        return Err(format!("Delivery returned status {}", response.status));
+    }
+    Ok(())
+}
+
+/// Drops headers so replicas agree on outcall responses
+pub fn transform_response(args: TransformArgs) -> OutcallResponse {
+    OutcallResponse {
+        status: args.response.status,
+        headers: Vec::new(),
+        body: args.response.body,
+    }
+}
+
+// ============================================================================
+// ACTIVITYPUB TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_actor_discoverability() {
+        use crate::types::{PrivacySettings, ProfileTheme, UserId, VerificationStatus};
+
+        let mut profile = UserProfile {
+            id: UserId(candid::Principal::anonymous()),
+            handle: "alice".to_string(),
+            display_name: "Alice".to_string(),
+            bio: String::new(),
+            avatar: String::new(),
+            banner: String::new(),
+            theme: ProfileTheme::default(),
+            created_at: 0,
+            updated_at: 0,
+            follower_count: 0,
+            following_count: 0,
+            post_count: 0,
+            privacy_settings: PrivacySettings::default(),
+            verification_status: VerificationStatus::Unverified,
+            personhood: None,
+            nft_avatar: None,
+            version: 0,
+        };
+        let actor = actor_document("https://decentra.example", &profile, "");
+        assert_eq!(actor["discoverable"], json!(true));
+        assert!(profile.privacy_settings.is_discoverable());
+
+        profile.privacy_settings.searchable = false;
+        let actor = actor_document("https://decentra.example", &profile, "");
+        assert_eq!(actor["discoverable"], json!(false));
+        assert_eq!(actor["indexable"], json!(false));
+        assert!(!profile.privacy_settings.is_discoverable());
+    }
+
+    #[test]
+    fn test_parse_webfinger_resource() {
+        let domain = "decentra.example";
+        assert_eq!(
+            parse_webfinger_resource("acct:alice@decentra.example", domain).as_deref(),
+            Some("alice")
+        );
+        assert_eq!(
+            parse_webfinger_resource("acct:@alice@DeCentra.Example", domain).as_deref(),
+            Some("alice")
+        );
+        assert_eq!(
+            parse_webfinger_resource("https://decentra.example/users/alice", domain).as_deref(),
+            Some("alice")
+        );
+        assert_eq!(
+            parse_webfinger_resource("acct:alice@elsewhere.example", domain),
+            None
+        );
+        assert_eq!(parse_webfinger_resource("acct:alice", domain), None);
+        assert_eq!(
+            parse_webfinger_resource("https://decentra.example/posts/1", domain),
+            None
+        );
+    }
+
+    #[test]
+    fn test_parse_signature_header() -> Result<(), String> {
+        let params = SignatureParams::parse(
+            "keyId=\"https://remote.example/users/bob#main-key\",algorithm=\"rsa-sha256\",headers=\"(request-target) host date digest\",signature=\"AAEC\"",
+        )?;
+        assert_eq!(params.key_id, "https://remote.example/users/bob#main-key");
+        assert_eq!(params.signature, vec![0, 1, 2]);
+        assert!(params.covers_required_headers());
+
+        let date_only = SignatureParams::parse("keyId=\"k\",signature=\"AAEC\"")?;
+        assert!(!date_only.covers_required_headers());
+
+        assert!(
+            SignatureParams::parse("keyId=\"k\",algorithm=\"hmac-sha256\",signature=\"AAEC\"")
+                .is_err()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_signing_string_and_digest() -> Result<(), String> {
+        let names: Vec<String> = REQUIRED_SIGNED_HEADERS
+            .iter()
+            .map(|name| name.to_string())
+            .collect();
+        let signed = signing_string("POST", "/users/alice/inbox", &names, |name| match name {
+            "host" => Some("decentra.example"),
+            "date" => Some("Fri, 01 Mar 2024 00:00:00 GMT"),
+            "digest" => Some("SHA-256=abc"),
+            _ => None,
+        })?;
+        assert_eq!(
+            signed,
+            "(request-target): post /users/alice/inbox\nhost: decentra.example\ndate: Fri, 01 Mar 2024 00:00:00 GMT\ndigest: SHA-256=abc"
+        );
+
+        let body = br#"{"type":"Follow"}"#;
+        assert!(digest_matches(&digest_header(body), body));
+        assert!(!digest_matches(&digest_header(b"other"), body));
+        assert!(!digest_matches("SHA", body));
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_remote_actor_requires_owned_key() {
+        let actor = json!({
+            "id": "https://remote.example/users/bob",
+            "inbox": "https://remote.example/users/bob/inbox",
+            "endpoints": { "sharedInbox": "https://remote.example/inbox" },
+            "publicKey": {
+                "id": "https://remote.example/users/bob#main-key",
+                "owner": "https://remote.example/users/mallory",
+                "publicKeyPem": "",
+            },
+        });
+        assert!(
+            parse_remote_actor(&actor, "https://remote.example/users/bob#main-key", 0).is_err()
+        );
+    }
+}
@@ -0,0 +1,89 @@
+use crate::types::{ArchiveDeletion, ArchivedPost, Post, PostId, NANOS_PER_DAY};
+use candid::Principal;
+
+// ============================================================================
+// ARCHIVE CANISTER INTERFACE
+// ============================================================================
+
+/// Hands posts and their comments to the archive canister
+pub async fn store(archive: Principal, posts: Vec<ArchivedPost>) -> Result<(), String> {
+    let (result,): (Result<(), String>,) =
+        ic_cdk::call(archive, "archive_posts", (posts,))
+            .await
+            .map_err(|(code, message)| format!("Archive call failed: {code:?} {message}"))?;
+    result.map_err(|error| format!("Archiving failed: {error}"))
+}
+
+/// Fetches an archived post and its comments
+pub async fn fetch(archive: Principal, post_id: PostId) -> Result<Option<ArchivedPost>, String> {
+    let (archived,): (Option<ArchivedPost>,) =
+        ic_cdk::call(archive, "get_archived_post", (post_id.0,))
+            .await
+            .map_err(|(code, message)| format!("Archive call failed: {code:?} {message}"))?;
+    Ok(archived.filter(|archived| archived.post.id == post_id))
+}
+
+/// Asks the archive canister to delete its copy of a post or comment
+pub async fn delete(archive: Principal, deletion: &ArchiveDeletion) -> Result<(), String> {
+    let call = match *deletion {
+        ArchiveDeletion::Post(post_id) => {
+            ic_cdk::call(archive, "delete_archived_post", (post_id.0,)).await
+        }
+        ArchiveDeletion::Comment(post_id, comment_id) => {
+            ic_cdk::call(
+                archive,
+                "delete_archived_comment",
+                (post_id.0, comment_id.0),
+            )
+            .await
+        }
+    };
+    let (result,): (Result<(), String>,) =
+        call.map_err(|(code, message)| format!("Archive call failed: {code:?} {message}"))?;
+    result.map_err(|error| format!("Archive deletion failed: {error}"))
+}
+
+/// Whether a post created at `created_at` is old enough to archive
+pub fn is_cold(created_at: u64, now: u64, archive_after_days: u64) -> bool {
+    now.saturating_sub(created_at) >= archive_after_days.saturating_mul(NANOS_PER_DAY)
+}
+
+/// Strips an archived post down to the stub kept in this canister
+///
+/// Counters, visibility and metadata stay so feeds and stats are unaffected;
+/// `archived` tells clients where the content went.
+pub fn stub(post: &mut Post) {
+    post.content = String::new();
+    post.entities = Vec::new();
+    post.link_warnings = Vec::new();
+    post.archived = true;
+}
+
+/// Fills a stub back in from its archived copy, keeping the stub's live counters
+pub fn restore(stub: &Post, archived: Post) -> Post {
+    Post {
+        content: archived.content,
+        entities: archived.entities,
+        link_warnings: archived.link_warnings,
+        archived: false,
+        ..stub.clone()
+    }
+}
+
+// ============================================================================
+// ARCHIVE TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_cold() {
+        let now = 100 * NANOS_PER_DAY;
+        assert!(is_cold(10 * NANOS_PER_DAY, now, 90));
+        assert!(is_cold(0, now, 90));
+        assert!(!is_cold(10 * NANOS_PER_DAY + 1, now, 90));
+        assert!(!is_cold(0, now, u64::MAX));
+    }
+}
@@ -0,0 +1,118 @@
+use crate::avatar_store::{self, Memory};
+use crate::types::UserId;
+use candid::Principal;
+use ic_stable_structures::memory_manager::MemoryId;
+use ic_stable_structures::storable::Bound;
+use ic_stable_structures::{StableBTreeMap, Storable};
+use std::borrow::Cow;
+use std::cell::RefCell;
+
+/// Stable memory region holding audio clips
+const AUDIO_MEMORY_ID: MemoryId = MemoryId::new(1);
+
+/// Audio formats accepted for voice notes, identified by their leading bytes
+const AUDIO_SIGNATURES: [(&str, &[u8]); 5] = [
+    ("audio/mpeg", b"ID3"),
+    ("audio/mpeg", b"\xff\xfb"),
+    ("audio/ogg", b"OggS"),
+    ("audio/webm", b"\x1a\x45\xdf\xa3"),
+    ("audio/wav", b"RIFF"),
+];
+
+thread_local! {
+    /// Uploaded clips by asset id; kept in stable memory so audio survives upgrades
+    static CLIPS: RefCell<StableBTreeMap<u64, StoredAudio, Memory>> =
+        RefCell::new(StableBTreeMap::init(avatar_store::memory(AUDIO_MEMORY_ID)));
+}
+
+/// Audio clip stored on-chain
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StoredAudio {
+    pub owner: UserId,
+    pub content_type: String,
+    pub data: Vec<u8>,
+}
+
+impl Storable for StoredAudio {
+    /// Layout: owner length, owner bytes, content type length, content type, audio data
+    fn to_bytes(&self) -> Cow<'_, [u8]> {
+        let owner = self.owner.0.as_slice();
+        let mut bytes =
+            Vec::with_capacity(2 + owner.len() + self.content_type.len() + self.data.len());
+        bytes.push(owner.len() as u8);
+        bytes.extend_from_slice(owner);
+        bytes.push(self.content_type.len() as u8);
+        bytes.extend_from_slice(self.content_type.as_bytes());
+        bytes.extend_from_slice(&self.data);
+        Cow::Owned(bytes)
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        let owner_end = 1 + bytes[0] as usize;
+        let type_end = owner_end + 1 + bytes[owner_end] as usize;
+        Self {
+            owner: UserId(Principal::from_slice(&bytes[1..owner_end])),
+            content_type: String::from_utf8_lossy(&bytes[owner_end + 1..type_end]).into_owned(),
+            data: bytes[type_end..].to_vec(),
+        }
+    }
+
+    // Clips are far larger than avatars, so entries are not padded to a fixed slot
+    const BOUND: Bound = Bound::Unbounded;
+}
+
+/// Detects the content type of an audio upload from its leading bytes
+///
+/// # Returns
+/// The audio content type, or `None` for unsupported formats
+pub fn sniff_audio_type(data: &[u8]) -> Option<&'static str> {
+    let (content_type, _) = AUDIO_SIGNATURES
+        .iter()
+        .find(|(_, signature)| data.starts_with(signature))?;
+
+    // RIFF is a container; only the WAVE form is audio
+    if *content_type == "audio/wav" && data.get(8..12) != Some(b"WAVE".as_slice()) {
+        return None;
+    }
+    Some(content_type)
+}
+
+pub fn insert(id: u64, clip: StoredAudio) {
+    CLIPS.with(|clips| clips.borrow_mut().insert(id, clip));
+}
+
+pub fn get(id: u64) -> Option<StoredAudio> {
+    CLIPS.with(|clips| clips.borrow().get(&id))
+}
+
+pub fn remove(id: u64) {
+    CLIPS.with(|clips| clips.borrow_mut().remove(&id));
+}
+
+// ============================================================================
+// AUDIO STORE TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sniff_audio_type() {
+        assert_eq!(sniff_audio_type(b"ID3\x04\0\0"), Some("audio/mpeg"));
+        assert_eq!(sniff_audio_type(b"OggS\0\x02"), Some("audio/ogg"));
+        assert_eq!(sniff_audio_type(b"RIFF\0\0\0\0WAVEfmt "), Some("audio/wav"));
+        assert_eq!(sniff_audio_type(b"RIFF\0\0\0\0WEBPVP8 "), None);
+        assert_eq!(sniff_audio_type(b"\x89PNG\r\n\x1a\n"), None);
+    }
+
+    #[test]
+    fn test_stored_audio_round_trip() {
+        let clip = StoredAudio {
+            owner: UserId(Principal::from_slice(&[7; 29])),
+            content_type: "audio/ogg".to_string(),
+            data: b"OggS...".to_vec(),
+        };
+        assert_eq!(StoredAudio::from_bytes(clip.to_bytes()), clip);
+    }
+}
@@ -1,4 +1,9 @@
-use crate::types::UserId;
+use crate::errors::SocialNetworkError;
+use crate::types::{
+    ActionGate, AppScope, AppThrottle, Capability, EngagementCounts, EntropyPool, LogCategory,
+    LogLevel, OrgRole, TrustTier, UserId, VerificationStatus, APP_RATE_LIMIT_PERCENT,
+    ESTABLISHED_ACCOUNT_DAYS, MAX_REPUTATION_SCORE, NEW_ACCOUNT_DAYS,
+};
 use candid::Principal;
 use ic_cdk::api::{caller, time};
 
@@ -6,6 +11,7 @@ use ic_cdk::api::{caller, time};
 ///
 /// # Security
 /// * Rejects anonymous callers
+/// * Rejects registered applications, which only pass `authenticate_for`
 /// * Validates Principal format
 /// * Checks for suspended/banned users (future implementation)
 ///
@@ -17,7 +23,23 @@ pub fn authenticate_user() -> Result<UserId, String> {
 
     // Reject anonymous callers
     if caller_principal == Principal::anonymous() {
-        return Err("Authentication required. Please log in with Internet Identity.".to_string());
+        log_auth_failure(None, "anonymous caller rejected");
+        let error = SocialNetworkError::AuthenticationRequired;
+        crate::record_error("authenticate_user", error.category());
+        return Err(error.into());
+    }
+
+    // Read replicas only serve queries; writes go to the primary
+    if let Some(primary) = crate::with_state(|state| state.replica_of) {
+        return Err(format!("This canister is a read-only replica of {primary}"));
+    }
+
+    if crate::with_state(|state| state.apps.contains_key(&caller_principal)) {
+        log_auth_failure(None, "application called a method outside its scope");
+        let error =
+            SocialNetworkError::Unauthorized("applications cannot call this method".to_string());
+        crate::record_error("authenticate_user", error.category());
+        return Err(error.into());
     }
 
     // Additional validation could be added here:
@@ -25,7 +47,49 @@ pub fn authenticate_user() -> Result<UserId, String> {
     // - Validate principal format
     // - Check rate limiting
 
-    Ok(UserId(caller_principal))
+    let user_id = UserId(caller_principal);
+
+    // Update calls count towards active-user metrics; queries cannot persist state
+    if ic_cdk::api::in_replicated_execution() {
+        crate::with_state_mut(|state| crate::record_active_user(state, user_id, time()));
+    }
+
+    Ok(user_id)
+}
+
+/// Authenticates the caller for a method open to applications with `scope`
+///
+/// Calls from a registered application are attributed to its owner if the
+/// application's scope includes `scope`; other callers are authenticated as
+/// by `authenticate_user`.
+///
+/// # Returns
+/// * `Ok(UserId)` - The caller, or the owner of the calling application
+/// * `Err(String)` - Authentication error or insufficient application scope
+pub fn authenticate_for(scope: AppScope) -> Result<UserId, String> {
+    let Some(app) = crate::with_state(|state| state.apps.get(&caller()).cloned()) else {
+        return authenticate_user();
+    };
+
+    if let Some(primary) = crate::with_state(|state| state.replica_of) {
+        return Err(format!("This canister is a read-only replica of {primary}"));
+    }
+
+    if app.scope < scope {
+        log_auth_failure(Some(&app.owner), "application scope too narrow");
+        let error = SocialNetworkError::Unauthorized(format!(
+            "application scope {:?} does not allow {scope:?} calls",
+            app.scope
+        ));
+        crate::record_error("authenticate_for", error.category());
+        return Err(error.into());
+    }
+
+    if ic_cdk::api::in_replicated_execution() {
+        crate::with_state_mut(|state| crate::record_active_user(state, app.owner, time()));
+    }
+
+    Ok(app.owner)
 }
 
 /// Returns the authenticated user ID if the caller is not anonymous
@@ -46,38 +110,306 @@ pub fn get_authenticated_user() -> Option<UserId> {
 
 /// Rate limiting implementation to prevent spam and DoS attacks
 ///
+/// Uses a sliding window over the action timestamps stored in
+/// `SocialNetworkState::rate_limits`. The base limit is scaled by the
+/// account's standing (see [`rate_limit_percent`]): new and struck accounts
+/// get tighter limits, verified and long-standing ones higher limits.
+/// Actions that admins restricted to proven humans are rejected for
+/// accounts without a valid personhood proof.
+///
+/// Calls made by one of the user's registered applications count against
+/// separate buckets, so throttling an application leaves the user's direct
+/// access alone; they are recorded in its usage stats. All of a user's
+/// applications together get APP_RATE_LIMIT_PERCENT of the user's limit,
+/// and each application is further held to any throttle its owner or an
+/// admin set, so registering more applications does not raise the limit.
+///
 /// # Arguments
 /// * `user_id` - User attempting the action
 /// * `action` - Type of action (e.g., "create_post", "like_post")
-/// * `max_actions` - Maximum actions allowed in the time window
+/// * `max_actions` - Maximum actions allowed in the time window for a Standard, unverified account
 /// * `window_seconds` - Time window in seconds
 ///
 /// # Returns
-/// * `Ok(())` - Action is allowed
+/// * `Ok(())` - Action is allowed and has been recorded
 /// * `Err(String)` - Rate limit exceeded
 pub fn check_rate_limit(
-    _user_id: &UserId,
-    _action: &str,
-    _max_actions: u32,
+    user_id: &UserId,
+    action: &str,
+    max_actions: u32,
     window_seconds: u64,
 ) -> Result<(), String> {
-    // Get current time
+    check_rate_limit_calls(user_id, action, max_actions, window_seconds, 1)
+}
+
+/// Charges `calls` actions at once against a rate limit (see
+/// [`check_rate_limit`])
+///
+/// Either every action fits in the window and all are recorded, or none is.
+pub fn check_rate_limit_calls(
+    user_id: &UserId,
+    action: &str,
+    max_actions: u32,
+    window_seconds: u64,
+    calls: u32,
+) -> Result<(), String> {
+    let now = time();
+    let window_ns = window_seconds.saturating_mul(1_000_000_000); // Convert to nanoseconds
+    let window_start = now.saturating_sub(window_ns);
+
+    crate::with_state_mut(|state| {
+        if state.personhood_required_actions.contains(action)
+            && !crate::has_personhood(state, user_id, now)
+        {
+            let error = SocialNetworkError::Unauthorized(format!(
+                "proof of personhood required for {action}"
+            ));
+            crate::count_error(state, action, error.category());
+            return Err(error.into());
+        }
+
+        let percent = crate::reputation_for(state, user_id, now)
+            .map(|reputation| reputation.rate_limit_percent)
+            .unwrap_or(100);
+        let limit = (max_actions.saturating_mul(percent) / 100).max(1);
+
+        let app = Some(caller()).filter(|principal| {
+            state
+                .apps
+                .get(principal)
+                .is_some_and(|app| app.owner == *user_id)
+        });
+        // (bucket, limit) pairs the call must fit in
+        let buckets = match app {
+            Some(app) => {
+                let shared = (limit.saturating_mul(APP_RATE_LIMIT_PERCENT) / 100).max(1);
+                let throttle = state
+                    .app_throttles
+                    .get(&app)
+                    .map_or(100, AppThrottle::percent);
+                vec![
+                    (format!("{action}@apps"), shared),
+                    (
+                        format!("{action}@{app}"),
+                        shared.saturating_mul(u32::from(throttle)) / 100,
+                    ),
+                ]
+            }
+            None => vec![(action.to_string(), limit)],
+        };
+
+        // Bucket without room that frees up last, as (timestamp whose expiry
+        // makes room, limit); calls over the limit never fit, so wait a window
+        let mut blocking: Option<(u64, u32)> = None;
+        for (bucket, bucket_limit) in &buckets {
+            let timestamps = state
+                .rate_limits
+                .entry((*user_id, bucket.clone()))
+                .or_default();
+            timestamps.retain(|&timestamp| timestamp > window_start);
+            let needed = (timestamps.len() + calls as usize).saturating_sub(*bucket_limit as usize);
+            if needed > 0 {
+                let frees_at = timestamps.get(needed - 1).copied().unwrap_or(now);
+                if blocking.is_none_or(|(other, _)| frees_at > other) {
+                    blocking = Some((frees_at, *bucket_limit));
+                }
+            }
+        }
+
+        if let Some((frees_at, limit)) = blocking {
+            let retry_after =
+                frees_at.saturating_add(window_ns).saturating_sub(now) / 1_000_000_000;
+            crate::log_event(
+                state,
+                LogLevel::Warn,
+                LogCategory::RateLimit,
+                Some(user_id.0),
+                format!("{action}: limit of {limit} per {window_seconds}s reached"),
+            );
+            let error = SocialNetworkError::RateLimitExceeded {
+                limit,
+                window_seconds,
+                retry_after,
+            };
+            crate::count_error(state, action, error.category());
+            if let Some(app) = app {
+                crate::record_app_call(state, app, action, false, now);
+            }
+            return Err(error.into());
+        }
+
+        for (bucket, _) in buckets {
+            if let Some(timestamps) = state.rate_limits.get_mut(&(*user_id, bucket)) {
+                timestamps.extend(std::iter::repeat_n(now, calls as usize));
+            }
+        }
+        if let Some(app) = app {
+            crate::record_app_call(state, app, action, true, now);
+        }
+        Ok(())
+    })
+}
+
+/// Charges one call of an expensive query against its quota
+///
+/// Endpoints without a quota (see `set_query_quota`) are unlimited. Each
+/// signed-in principal has its own allowance; anonymous callers share one.
+///
+/// Plain query calls cannot persist state, so they are only refused once
+/// usage recorded by replicated calls (update-mode calls, replicated queries,
+/// calls from other canisters) reaches the limit; those are the calls counted.
+///
+/// # Returns
+/// * `Ok(())` - Call is allowed
+/// * `Err(String)` - `ResourceLimitExceeded` once the quota is used up
+pub fn check_query_quota(endpoint: &str) -> Result<(), String> {
+    let caller = caller();
     let now = time();
-    let _window_start = now.saturating_sub(window_seconds.saturating_mul(1_000_000_000)); // Convert to nanoseconds
+    let counted = cfg!(target_arch = "wasm32") && ic_cdk::api::in_replicated_execution();
 
-    // For now, implement basic in-memory rate limiting
-    // In a production system, this would be persisted in stable storage
+    crate::with_state_mut(|state| {
+        let Some(quota) = state.query_quotas.get(endpoint).cloned() else {
+            return Ok(());
+        };
+        let limit = if caller == Principal::anonymous() {
+            quota.anonymous_total
+        } else {
+            quota.per_principal
+        };
+        let window_start = now.saturating_sub(quota.window_seconds.saturating_mul(1_000_000_000));
 
-    // This is a simplified implementation - in a real system you'd want to:
-    // 1. Store rate limiting data in stable storage
-    // 2. Implement sliding window rate limiting
-    // 3. Have different limits for different user types
-    // 4. Implement IP-based rate limiting as well
+        let calls = state
+            .query_usage
+            .entry((caller, endpoint.to_string()))
+            .or_default();
+        calls.retain(|&timestamp| timestamp > window_start);
+        if calls.len() >= limit as usize {
+            let error = SocialNetworkError::ResourceLimitExceeded(format!(
+                "{endpoint} allows {limit} calls per {} seconds",
+                quota.window_seconds
+            ));
+            crate::count_error(state, endpoint, error.category());
+            return Err(error.into());
+        }
+        if counted {
+            calls.push(now);
+        }
+        Ok(())
+    })
+}
+
+/// Rate limit multiplier granted by each verification tier
+///
+/// Verified identities get more headroom than anonymous accounts, and
+/// organizations/journalists (who publish on behalf of others) get the most.
+pub fn rate_limit_multiplier(status: &VerificationStatus) -> u32 {
+    match status {
+        VerificationStatus::Unverified | VerificationStatus::Whistleblower => 1,
+        VerificationStatus::Verified => 2,
+        VerificationStatus::Organization | VerificationStatus::Journalist => 3,
+    }
+}
+
+/// Classifies an account's standing for rate limiting
+///
+/// Active moderation strikes override everything else; otherwise high
+/// reputation or age promotes an account, and young unverified accounts
+/// start out on probation.
+pub fn trust_tier(
+    account_age_days: u64,
+    status: &VerificationStatus,
+    reputation: u32,
+    strikes: u32,
+) -> TrustTier {
+    let unverified = matches!(
+        status,
+        VerificationStatus::Unverified | VerificationStatus::Whistleblower
+    );
+    if strikes > 0 {
+        TrustTier::Restricted
+    } else if reputation >= 75 {
+        TrustTier::Trusted
+    } else if account_age_days >= ESTABLISHED_ACCOUNT_DAYS || reputation >= 40 {
+        TrustTier::Established
+    } else if account_age_days < NEW_ACCOUNT_DAYS && unverified {
+        TrustTier::New
+    } else {
+        TrustTier::Standard
+    }
+}
 
-    // For MVP, we'll do basic validation without persistent storage
-    // since rate limiting state would be lost on canister upgrades
+/// Names the first requirement of an action gate an account misses
+///
+/// # Returns
+/// `None` when the account meets the gate
+pub fn gate_shortfall(
+    gate: &ActionGate,
+    account_age_days: u64,
+    post_count: u64,
+    tier: &TrustTier,
+) -> Option<String> {
+    if account_age_days < gate.min_account_age_days {
+        return Some(format!(
+            "an account at least {} days old",
+            gate.min_account_age_days
+        ));
+    }
+    if post_count < gate.min_posts {
+        return Some(format!("at least {} published posts", gate.min_posts));
+    }
+    match &gate.min_trust_tier {
+        Some(min_tier) if tier < min_tier => Some(format!("trust tier {min_tier:?} or higher")),
+        _ => None,
+    }
+}
 
-    Ok(())
+/// Percentage of the base rate limit an account receives
+///
+/// The trust tier's share is multiplied by the verification multiplier,
+/// and a valid proof of personhood adds another half of the base limit.
+pub fn rate_limit_percent(tier: &TrustTier, status: &VerificationStatus, human: bool) -> u32 {
+    let tier_percent = match tier {
+        TrustTier::Restricted | TrustTier::New => 50,
+        TrustTier::Standard => 100,
+        TrustTier::Established => 150,
+        TrustTier::Trusted => 200,
+    };
+    tier_percent * rate_limit_multiplier(status) + if human { 50 } else { 0 }
+}
+
+/// Computes a 0-100 reputation score from account signals
+///
+/// # Components
+/// * Account age - up to 25 points, linear over the first year
+/// * Verification - up to 25 points depending on tier
+/// * Received engagement - up to 30 points, logarithmic in weighted
+///   likes, comments and reposts (views are not counted)
+/// * Followers - up to 20 points, logarithmic
+/// * Moderation strikes - minus `strike_penalty` each (see `ModerationPolicy`)
+pub fn reputation_score(
+    account_age_days: u64,
+    status: &VerificationStatus,
+    received: &EngagementCounts,
+    follower_count: u64,
+    strikes: u32,
+    strike_penalty: u32,
+) -> u32 {
+    let age_points = (account_age_days.min(365) * 25 / 365) as u32;
+    let verification_points = match status {
+        VerificationStatus::Unverified | VerificationStatus::Whistleblower => 0,
+        VerificationStatus::Verified => 20,
+        VerificationStatus::Organization | VerificationStatus::Journalist => 25,
+    };
+    let weighted_engagement = received
+        .likes
+        .saturating_add(received.comments.saturating_mul(2))
+        .saturating_add(received.reposts.saturating_mul(3));
+    let engagement_points = (5 * weighted_engagement.saturating_add(1).ilog2()).min(30);
+    let follower_points = (4 * follower_count.saturating_add(1).ilog2()).min(20);
+
+    (age_points + verification_points + engagement_points + follower_points)
+        .saturating_sub(strikes.saturating_mul(strike_penalty))
+        .min(MAX_REPUTATION_SCORE)
 }
 
 /// Records an action for rate limiting purposes
@@ -95,20 +427,44 @@ pub fn record_action(_user_id: &UserId, _action: &str) {
 /// # Arguments
 /// * `user_id` - User requesting the action
 /// * `action` - Action being requested
-/// * `target_resource` - Optional target resource (e.g., post ID)
+/// * `on_behalf_of` - Account the action is performed as, if not the user's own
 ///
 /// # Returns
 /// * `Ok(())` - Permission granted
 /// * `Err(String)` - Permission denied
-#[allow(dead_code)]
+///
+/// # Security
+/// * Acting as another account requires an unexpired `CapabilityGrant`
+///   from it covering the action; other actions cannot be delegated
 pub fn check_permission(
-    _user_id: &UserId,
+    user_id: &UserId,
     action: &str,
-    _target_resource: Option<&str>,
+    on_behalf_of: Option<&UserId>,
 ) -> Result<(), String> {
+    if let Some(owner) = on_behalf_of.filter(|owner| *owner != user_id) {
+        let capability = delegable_capability(action)
+            .ok_or_else(|| format!("{action} cannot be performed on behalf of others"))?;
+        let granted = crate::with_state(|state| {
+            state
+                .capability_grants
+                .get(&(*owner, *user_id))
+                .is_some_and(|grant| grant.allows(capability, time()))
+        });
+        if !granted {
+            log_auth_failure(Some(user_id), "no capability grant for delegated action");
+            let error = SocialNetworkError::Unauthorized(format!(
+                "no active grant for {action} from this user"
+            ));
+            crate::record_error(action, error.category());
+            return Err(error.into());
+        }
+        return Ok(());
+    }
+
     // Basic permission checking
     match action {
-        "create_post" | "create_comment" | "like_post" | "unlike_post" => {
+        "create_post" | "create_comment" | "like_post" | "unlike_post" | "send_message"
+        | "read_messages" => {
             // Any authenticated user can perform these basic actions
             Ok(())
         }
@@ -116,14 +472,8 @@ pub fn check_permission(
             // For now, allow (would check ownership in full implementation)
             Ok(())
         }
-        "moderate_content" => {
-            // Future: Check if user is a moderator
-            Err("Insufficient permissions for content moderation".to_string())
-        }
-        "admin_action" => {
-            // Future: Check if user is an admin
-            Err("Insufficient permissions for admin actions".to_string())
-        }
+        "moderate_content" => require_moderator(user_id),
+        "admin_action" => require_admin(user_id),
         _ => {
             // Unknown action, deny by default
             Err(format!("Unknown action: {action}"))
@@ -131,6 +481,194 @@ pub fn check_permission(
     }
 }
 
+/// Capability a grant must include to perform `action` as its owner
+fn delegable_capability(action: &str) -> Option<Capability> {
+    match action {
+        "create_post" => Some(Capability::Post),
+        "create_comment" => Some(Capability::Comment),
+        "read_messages" => Some(Capability::ReadMessages),
+        "send_message" => Some(Capability::SendMessages),
+        _ => None,
+    }
+}
+
+/// Resolves the account the caller acts as for `action`
+///
+/// # Returns
+/// * `Ok(UserId)` - The caller, or `acting_as` if it granted the caller a
+///   capability covering the action
+/// * `Err(String)` - No such grant
+pub fn act_as(
+    caller_id: UserId,
+    acting_as: Option<Principal>,
+    action: &str,
+) -> Result<UserId, String> {
+    match acting_as.map(UserId) {
+        Some(owner) => {
+            check_permission(&caller_id, action, Some(&owner))?;
+            Ok(owner)
+        }
+        None => Ok(caller_id),
+    }
+}
+
+/// Returns whether the user is a platform administrator
+///
+/// The SNS governance canister, once configured, has admin rights too.
+pub fn is_admin(user_id: &UserId) -> bool {
+    crate::with_state(|state| {
+        state.admins.contains(user_id) || state.sns_governance == Some(user_id.0)
+    })
+}
+
+/// Returns whether the user can moderate content (moderators and admins)
+pub fn is_moderator(user_id: &UserId) -> bool {
+    crate::with_state(|state| state.moderators.contains(user_id) || state.admins.contains(user_id))
+}
+
+/// Ensures the user is a platform administrator
+pub fn require_admin(user_id: &UserId) -> Result<(), String> {
+    if is_admin(user_id) {
+        Ok(())
+    } else {
+        log_auth_failure(Some(user_id), "admin rights required");
+        let error = SocialNetworkError::Unauthorized("admin rights required".to_string());
+        crate::record_error("require_admin", error.category());
+        Err(error.into())
+    }
+}
+
+/// Ensures the caller is the configured SNS governance canister
+pub fn require_governance(user_id: &UserId) -> Result<(), String> {
+    if crate::with_state(|state| state.sns_governance == Some(user_id.0)) {
+        Ok(())
+    } else {
+        log_auth_failure(Some(user_id), "governance caller required");
+        let error = SocialNetworkError::Unauthorized(
+            "only SNS governance may execute proposals".to_string(),
+        );
+        crate::record_error("require_governance", error.category());
+        Err(error.into())
+    }
+}
+
+/// Ensures the user is a moderator or administrator
+pub fn require_moderator(user_id: &UserId) -> Result<(), String> {
+    if is_moderator(user_id) {
+        Ok(())
+    } else {
+        log_auth_failure(Some(user_id), "moderation rights required");
+        let error = SocialNetworkError::InsufficientModerationRights;
+        crate::record_error("require_moderator", error.category());
+        Err(error.into())
+    }
+}
+
+/// Records a rejected caller in the canister log
+///
+/// Query calls cannot persist state, so only update calls are recorded;
+/// native builds (unit tests) have no replica to record to.
+fn log_auth_failure(user_id: Option<&UserId>, reason: &str) {
+    if cfg!(target_arch = "wasm32") && ic_cdk::api::in_replicated_execution() {
+        crate::with_state_mut(|state| {
+            crate::log_event(
+                state,
+                LogLevel::Warn,
+                LogCategory::Auth,
+                user_id.map(|user_id| user_id.0),
+                reason.to_string(),
+            )
+        });
+    }
+}
+
+/// Returns the caller's role in an organization, if the account is a
+/// verified organization and the caller is one of its members
+pub fn org_role(org_id: &UserId, member_id: &UserId) -> Option<OrgRole> {
+    crate::with_state(|state| {
+        let is_org = state
+            .users
+            .get(org_id)
+            .map(|profile| profile.verification_status == VerificationStatus::Organization)
+            .unwrap_or(false);
+        if !is_org {
+            return None;
+        }
+
+        state
+            .org_members
+            .get(org_id)
+            .and_then(|members| members.get(member_id))
+            .map(|member| member.role.clone())
+    })
+}
+
+/// Ensures the user can manage an organization's members
+///
+/// The organization account itself and members with the Admin role qualify.
+pub fn require_org_admin(org_id: &UserId, user_id: &UserId) -> Result<(), String> {
+    if org_id == user_id || org_role(org_id, user_id) == Some(OrgRole::Admin) {
+        Ok(())
+    } else {
+        Err(
+            SocialNetworkError::Unauthorized("organization admin rights required".to_string())
+                .into(),
+        )
+    }
+}
+
+/// Resolves the account a piece of content will be authored as
+///
+/// # Arguments
+/// * `caller_id` - Authenticated caller
+/// * `acting_as` - Optional organization account to author as
+///
+/// # Returns
+/// * `Ok(UserId)` - The caller, or the organization if the caller holds an authoring role
+/// * `Err(String)` - Caller is not allowed to author as the requested account
+///
+/// Accounts other than organizations can be authored as through a capability
+/// grant; see `resolve_author_for`.
+pub fn resolve_author(caller_id: UserId, acting_as: Option<Principal>) -> Result<UserId, String> {
+    let author_id = match acting_as {
+        Some(principal) => UserId(principal),
+        None => return Ok(caller_id),
+    };
+
+    if author_id == caller_id {
+        return Ok(caller_id);
+    }
+
+    match org_role(&author_id, &caller_id) {
+        Some(role) if role.can_author() => Ok(author_id),
+        Some(_) => Err(SocialNetworkError::Unauthorized(
+            "role does not allow authoring as this organization".to_string(),
+        )
+        .into()),
+        None => Err(SocialNetworkError::Unauthorized(
+            "not a member of this organization".to_string(),
+        )
+        .into()),
+    }
+}
+
+/// Resolves the author of content created by `action`
+///
+/// Organizations the caller belongs to are resolved as by `resolve_author`;
+/// any other account must have granted the caller a capability for `action`.
+pub fn resolve_author_for(
+    caller_id: UserId,
+    acting_as: Option<Principal>,
+    action: &str,
+) -> Result<UserId, String> {
+    match acting_as.map(UserId) {
+        Some(owner) if owner != caller_id && org_role(&owner, &caller_id).is_none() => {
+            act_as(caller_id, acting_as, action)
+        }
+        _ => resolve_author(caller_id, acting_as),
+    }
+}
+
 /// Checks if a user has access to a specific resource
 ///
 /// # Arguments
@@ -177,38 +715,102 @@ pub fn check_resource_access(
 /// Security utilities for enhanced protection
 pub mod security_utils {
     use super::*;
+    use sha2::{Digest, Sha256};
 
-    /// Generates a secure random ID using IC's random number generation
+    /// Generates a secure random ID from the canister entropy pool
     ///
     /// # Returns
-    /// A cryptographically secure 64-bit random number
+    /// A 64-bit identifier derived from `raw_rand` seed material
     #[allow(dead_code)]
     pub fn generate_secure_id() -> u64 {
-        // For now, use timestamp + some entropy
-        // In production, use proper cryptographic randomness
-        time()
+        let bytes = crate::with_state_mut(|state| next_random_bytes(&mut state.entropy_pool));
+        let mut id = [0u8; 8];
+        id.copy_from_slice(&bytes[..8]);
+        u64::from_be_bytes(id)
+    }
+
+    /// Generates an unguessable URL-safe token (256 bits of output)
+    ///
+    /// # Returns
+    /// Base64url-encoded token suitable for share links, exports and invites
+    pub fn generate_secure_token() -> String {
+        let bytes = crate::with_state_mut(|state| next_random_bytes(&mut state.entropy_pool));
+        data_encoding::BASE64URL_NOPAD.encode(&bytes)
+    }
+
+    /// Draws the next 32 bytes from the pool
+    ///
+    /// Each draw hashes the seed with a monotonically increasing counter, so
+    /// outputs never repeat and do not reveal the seed. Until the first
+    /// `raw_rand` refresh lands, the current time is mixed in as well.
+    pub fn next_random_bytes(pool: &mut EntropyPool) -> [u8; 32] {
+        pool.counter = pool.counter.wrapping_add(1);
+        let mut hasher = Sha256::new();
+        hasher.update(b"decentra-entropy");
+        hasher.update(&pool.seed);
+        hasher.update(pool.counter.to_be_bytes());
+        if pool.last_refreshed.is_none() {
+            hasher.update(time().to_be_bytes());
+        }
+        hasher.finalize().into()
     }
 
-    /// Sanitizes text input to prevent injection attacks
+    /// Mixes fresh randomness into the pool seed
+    ///
+    /// The previous seed is kept in the hash so a weak refresh never
+    /// reduces the pool's entropy.
+    pub fn reseed(pool: &mut EntropyPool, fresh: &[u8], now: u64) {
+        let mut hasher = Sha256::new();
+        hasher.update(&pool.seed);
+        hasher.update(fresh);
+        pool.seed = hasher.finalize().to_vec();
+        pool.last_refreshed = Some(now);
+    }
+
+    /// Escapes user text for safe embedding in HTML
+    ///
+    /// Content is stored exactly as written; this produces the rendering
+    /// exposed alongside it, so "1 < 2" or code snippets survive intact
+    /// while markup cannot execute.
     ///
     /// # Arguments
-    /// * `input` - The input string to sanitize
+    /// * `input` - The original user text
     ///
     /// # Returns
-    /// Sanitized string safe for storage and display
-    #[allow(dead_code)]
-    pub fn sanitize_text_input(input: &str) -> String {
-        // Basic sanitization - remove dangerous characters
-        input
-            .chars()
-            .filter(|c| {
-                // Allow alphanumeric, basic punctuation, and unicode
-                c.is_alphanumeric()
-                    || " .,!?;:()[]{}\"'-_@#$%^&*+=|\\/<>~`\n\r\t".contains(*c)
-                    || (*c as u32) > 127 // Allow unicode characters
-            })
-            .take(10_000) // Prevent extremely long inputs
-            .collect()
+    /// Text with `& < > " '` replaced by HTML entities
+    pub fn escape_html(input: &str) -> String {
+        let mut escaped = String::with_capacity(input.len());
+        for c in input.chars() {
+            match c {
+                '&' => escaped.push_str("&amp;"),
+                '<' => escaped.push_str("&lt;"),
+                '>' => escaped.push_str("&gt;"),
+                '"' => escaped.push_str("&quot;"),
+                '\'' => escaped.push_str("&#39;"),
+                _ => escaped.push(c),
+            }
+        }
+        escaped
+    }
+
+    /// Checks a proof of work bound to the subject of a request
+    ///
+    /// The SHA-256 of `subject` followed by the big-endian `nonce` must start
+    /// with `difficulty_bits` zero bits; clients try nonces until one does,
+    /// which costs about 2^difficulty_bits hashes per subject.
+    pub fn verify_proof_of_work(subject: &str, nonce: u64, difficulty_bits: u8) -> bool {
+        let mut hasher = Sha256::new();
+        hasher.update(subject.as_bytes());
+        hasher.update(nonce.to_be_bytes());
+
+        let mut zero_bits = 0;
+        for byte in hasher.finalize() {
+            zero_bits += byte.leading_zeros();
+            if byte != 0 {
+                break;
+            }
+        }
+        zero_bits >= u32::from(difficulty_bits)
     }
 
     /// Validates that a principal is not anonymous and follows security rules
@@ -239,13 +841,110 @@ pub mod security_utils {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::types::REPUTATION_STRIKE_PENALTY as PENALTY;
     use candid::Principal;
 
     #[test]
     fn test_security_utils() {
         let text = "Hello <script>alert('xss')</script> World!";
-        let sanitized = security_utils::sanitize_text_input(text);
-        assert_eq!(sanitized, "Hello scriptalert('xss')/script World!");
+        let escaped = security_utils::escape_html(text);
+        assert_eq!(
+            escaped,
+            "Hello &lt;script&gt;alert(&#39;xss&#39;)&lt;/script&gt; World!"
+        );
+
+        assert_eq!(
+            security_utils::escape_html("1 < 2 && 3 > 2"),
+            "1 &lt; 2 &amp;&amp; 3 &gt; 2"
+        );
+        assert_eq!(security_utils::escape_html("plain text"), "plain text");
+    }
+
+    #[test]
+    fn test_gate_shortfall() {
+        let gate = ActionGate {
+            min_account_age_days: 7,
+            min_posts: 3,
+            min_trust_tier: Some(TrustTier::Standard),
+        };
+        assert_eq!(gate_shortfall(&gate, 30, 10, &TrustTier::Established), None);
+        assert_eq!(
+            gate_shortfall(&gate, 2, 10, &TrustTier::Standard).as_deref(),
+            Some("an account at least 7 days old")
+        );
+        assert_eq!(
+            gate_shortfall(&gate, 30, 1, &TrustTier::Standard).as_deref(),
+            Some("at least 3 published posts")
+        );
+        assert_eq!(
+            gate_shortfall(&gate, 30, 10, &TrustTier::Restricted).as_deref(),
+            Some("trust tier Standard or higher")
+        );
+    }
+
+    #[test]
+    fn test_verify_proof_of_work() {
+        // 3987 is the first nonce giving "alice" 12 leading zero bits
+        assert!(security_utils::verify_proof_of_work("alice", 3987, 12));
+        assert!(!security_utils::verify_proof_of_work("alice", 3987, 13));
+        assert!(!security_utils::verify_proof_of_work("alice", 3988, 12));
+        assert!(!security_utils::verify_proof_of_work("bob", 3987, 12));
+        assert!(security_utils::verify_proof_of_work("bob", 0, 0));
+    }
+
+    #[test]
+    fn test_reputation_score() {
+        let none = EngagementCounts::default();
+        assert_eq!(
+            reputation_score(0, &VerificationStatus::Unverified, &none, 0, 0, PENALTY),
+            0
+        );
+
+        let engaged = EngagementCounts {
+            likes: 1_000,
+            comments: 500,
+            reposts: 200,
+            views: 1_000_000,
+        };
+        let established = reputation_score(
+            730,
+            &VerificationStatus::Journalist,
+            &engaged,
+            10_000,
+            0,
+            PENALTY,
+        );
+        assert_eq!(established, MAX_REPUTATION_SCORE);
+
+        let struck = reputation_score(
+            730,
+            &VerificationStatus::Journalist,
+            &engaged,
+            10_000,
+            2,
+            PENALTY,
+        );
+        assert_eq!(struck, MAX_REPUTATION_SCORE - 2 * PENALTY);
+
+        let new_account =
+            reputation_score(1, &VerificationStatus::Unverified, &none, 0, 3, PENALTY);
+        assert_eq!(new_account, 0);
+    }
+
+    #[test]
+    fn test_entropy_pool_draws_are_distinct_after_reseed() {
+        let mut pool = EntropyPool::default();
+        security_utils::reseed(&mut pool, &[7u8; 32], 1);
+        assert_eq!(pool.seed.len(), 32);
+
+        let first = security_utils::next_random_bytes(&mut pool);
+        let second = security_utils::next_random_bytes(&mut pool);
+        assert_ne!(first, second);
+        assert_eq!(pool.counter, 2);
+
+        let mut other = EntropyPool::default();
+        security_utils::reseed(&mut other, &[8u8; 32], 1);
+        assert_ne!(security_utils::next_random_bytes(&mut other), first);
     }
 
     #[test]
@@ -264,6 +963,76 @@ mod tests {
 
         assert!(check_permission(&user_id, "create_post", None).is_ok());
         assert!(check_permission(&user_id, "admin_action", None).is_err());
+        assert!(check_permission(&user_id, "moderate_content", None).is_err());
         Ok(())
     }
+
+    #[test]
+    fn test_delegated_permissions() -> Result<(), Box<dyn std::error::Error>> {
+        let user = UserId(
+            Principal::from_text("rdmx6-jaaaa-aaaaa-aaadq-cai")
+                .map_err(|_| "Invalid test principal")?,
+        );
+        let owner = UserId(
+            Principal::from_text("ryjl3-tyaaa-aaaaa-aaaba-cai")
+                .map_err(|_| "Invalid test principal")?,
+        );
+
+        assert!(check_permission(&user, "create_post", Some(&user)).is_ok());
+        assert!(check_permission(&user, "create_post", Some(&owner)).is_err()); // No grant
+        assert!(check_permission(&user, "admin_action", Some(&owner)).is_err());
+        assert_eq!(act_as(user, None, "send_message"), Ok(user));
+        assert!(act_as(user, Some(owner.0), "send_message").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_resolve_author() -> Result<(), Box<dyn std::error::Error>> {
+        let user = UserId(
+            Principal::from_text("rdmx6-jaaaa-aaaaa-aaadq-cai")
+                .map_err(|_| "Invalid test principal")?,
+        );
+        let org = Principal::from_text("ryjl3-tyaaa-aaaaa-aaaba-cai")
+            .map_err(|_| "Invalid test principal")?;
+
+        assert_eq!(resolve_author(user, None), Ok(user));
+        assert_eq!(resolve_author(user, Some(user.0)), Ok(user));
+        assert!(resolve_author(user, Some(org)).is_err()); // Not a verified org member
+        Ok(())
+    }
+
+    #[test]
+    fn test_rate_limit_tiers() {
+        let unverified = rate_limit_multiplier(&VerificationStatus::Unverified);
+        let verified = rate_limit_multiplier(&VerificationStatus::Verified);
+        let journalist = rate_limit_multiplier(&VerificationStatus::Journalist);
+
+        assert_eq!(unverified, 1);
+        assert!(verified > unverified);
+        assert!(journalist >= verified);
+    }
+
+    #[test]
+    fn test_trust_tiers() {
+        let unverified = VerificationStatus::Unverified;
+        let verified = VerificationStatus::Verified;
+
+        assert_eq!(trust_tier(1, &unverified, 0, 0), TrustTier::New);
+        assert_eq!(trust_tier(1, &verified, 20, 0), TrustTier::Standard);
+        assert_eq!(trust_tier(30, &unverified, 10, 0), TrustTier::Standard);
+        assert_eq!(trust_tier(120, &unverified, 10, 0), TrustTier::Established);
+        assert_eq!(trust_tier(400, &verified, 90, 0), TrustTier::Trusted);
+        assert_eq!(trust_tier(400, &verified, 90, 1), TrustTier::Restricted);
+
+        let new = rate_limit_percent(&TrustTier::New, &unverified, false);
+        let standard = rate_limit_percent(&TrustTier::Standard, &unverified, false);
+        let trusted = rate_limit_percent(&TrustTier::Trusted, &verified, false);
+        assert!(new < standard);
+        assert_eq!(standard, 100);
+        assert!(trusted > standard);
+        assert_eq!(
+            rate_limit_percent(&TrustTier::Standard, &unverified, true),
+            150
+        );
+    }
 }
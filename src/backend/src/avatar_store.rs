@@ -0,0 +1,157 @@
+use crate::types::{UserId, MAX_AVATAR_IMAGE_BYTES};
+use candid::Principal;
+use ic_stable_structures::memory_manager::{MemoryId, MemoryManager, VirtualMemory};
+use ic_stable_structures::storable::Bound;
+use ic_stable_structures::{DefaultMemoryImpl, StableBTreeMap, Storable};
+use std::borrow::Cow;
+use std::cell::RefCell;
+
+/// Prefix of a profile `avatar` value that refers to an uploaded image
+pub const ASSET_PREFIX: &str = "asset:";
+
+/// Stable memory region holding avatar images
+const AVATAR_MEMORY_ID: MemoryId = MemoryId::new(0);
+
+/// Longest content type string stored with an image
+const MAX_CONTENT_TYPE_LENGTH: usize = 16;
+
+/// Image formats accepted for avatars, identified by their leading bytes
+const IMAGE_SIGNATURES: [(&str, &[u8]); 4] = [
+    ("image/png", b"\x89PNG\r\n\x1a\n"),
+    ("image/jpeg", b"\xff\xd8\xff"),
+    ("image/gif", b"GIF8"),
+    ("image/webp", b"RIFF"),
+];
+
+pub type Memory = VirtualMemory<DefaultMemoryImpl>;
+
+thread_local! {
+    static MEMORY_MANAGER: RefCell<MemoryManager<DefaultMemoryImpl>> =
+        RefCell::new(MemoryManager::init(DefaultMemoryImpl::default()));
+
+    /// Uploaded avatars by asset id; kept in stable memory so images survive upgrades
+    static AVATARS: RefCell<StableBTreeMap<u64, StoredAvatar, Memory>> =
+        RefCell::new(StableBTreeMap::init(
+            memory(AVATAR_MEMORY_ID),
+        ));
+}
+
+/// Region of the stable memory shared by the on-chain asset stores
+pub fn memory(id: MemoryId) -> Memory {
+    MEMORY_MANAGER.with(|manager| manager.borrow().get(id))
+}
+
+/// Avatar image stored on-chain
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StoredAvatar {
+    pub owner: UserId,
+    pub content_type: String,
+    pub data: Vec<u8>,
+}
+
+impl Storable for StoredAvatar {
+    /// Layout: owner length, owner bytes, content type length, content type, image data
+    fn to_bytes(&self) -> Cow<'_, [u8]> {
+        let owner = self.owner.0.as_slice();
+        let mut bytes =
+            Vec::with_capacity(2 + owner.len() + self.content_type.len() + self.data.len());
+        bytes.push(owner.len() as u8);
+        bytes.extend_from_slice(owner);
+        bytes.push(self.content_type.len() as u8);
+        bytes.extend_from_slice(self.content_type.as_bytes());
+        bytes.extend_from_slice(&self.data);
+        Cow::Owned(bytes)
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        let owner_end = 1 + bytes[0] as usize;
+        let type_end = owner_end + 1 + bytes[owner_end] as usize;
+        Self {
+            owner: UserId(Principal::from_slice(&bytes[1..owner_end])),
+            content_type: String::from_utf8_lossy(&bytes[owner_end + 1..type_end]).into_owned(),
+            data: bytes[type_end..].to_vec(),
+        }
+    }
+
+    const BOUND: Bound = Bound::Bounded {
+        max_size: (2
+            + Principal::MAX_LENGTH_IN_BYTES
+            + MAX_CONTENT_TYPE_LENGTH
+            + MAX_AVATAR_IMAGE_BYTES) as u32,
+        is_fixed_size: false,
+    };
+}
+
+/// Detects the content type of an avatar upload from its leading bytes
+///
+/// # Returns
+/// The image content type, or `None` for unsupported formats
+pub fn sniff_image_type(data: &[u8]) -> Option<&'static str> {
+    let (content_type, _) = IMAGE_SIGNATURES
+        .iter()
+        .find(|(_, signature)| data.starts_with(signature))?;
+
+    // RIFF is a container; only the WEBP form is an image
+    if *content_type == "image/webp" && data.get(8..12) != Some(b"WEBP".as_slice()) {
+        return None;
+    }
+    Some(content_type)
+}
+
+/// Asset id referenced by a profile avatar value, if it is an uploaded image
+pub fn asset_id(avatar: &str) -> Option<u64> {
+    avatar.strip_prefix(ASSET_PREFIX)?.parse().ok()
+}
+
+/// Profile avatar value referring to an uploaded image
+pub fn asset_reference(id: u64) -> String {
+    format!("{ASSET_PREFIX}{id}")
+}
+
+pub fn insert(id: u64, avatar: StoredAvatar) {
+    AVATARS.with(|avatars| avatars.borrow_mut().insert(id, avatar));
+}
+
+pub fn get(id: u64) -> Option<StoredAvatar> {
+    AVATARS.with(|avatars| avatars.borrow().get(&id))
+}
+
+pub fn remove(id: u64) {
+    AVATARS.with(|avatars| avatars.borrow_mut().remove(&id));
+}
+
+// ============================================================================
+// AVATAR STORE TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sniff_image_type() {
+        assert_eq!(
+            sniff_image_type(b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR"),
+            Some("image/png")
+        );
+        assert_eq!(sniff_image_type(b"\xff\xd8\xff\xe0"), Some("image/jpeg"));
+        assert_eq!(
+            sniff_image_type(b"RIFF\0\0\0\0WEBPVP8 "),
+            Some("image/webp")
+        );
+        assert_eq!(sniff_image_type(b"RIFF\0\0\0\0WAVEfmt "), None);
+        assert_eq!(sniff_image_type(b"<svg xmlns="), None);
+    }
+
+    #[test]
+    fn test_stored_avatar_round_trip() {
+        let avatar = StoredAvatar {
+            owner: UserId(Principal::from_slice(&[7; 29])),
+            content_type: "image/gif".to_string(),
+            data: b"GIF89a...".to_vec(),
+        };
+        assert_eq!(StoredAvatar::from_bytes(avatar.to_bytes()), avatar);
+        assert_eq!(asset_id(&asset_reference(42)), Some(42));
+        assert_eq!(asset_id("🦀"), None);
+    }
+}
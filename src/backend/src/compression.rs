@@ -0,0 +1,48 @@
+use crate::types::COMPRESSION_THRESHOLD_BYTES;
+
+/// Compresses a post body worth storing compressed
+///
+/// # Returns
+/// `None` when the text is below `COMPRESSION_THRESHOLD_BYTES` or does not shrink
+pub fn compress(text: &str) -> Option<Vec<u8>> {
+    if text.len() < COMPRESSION_THRESHOLD_BYTES {
+        return None;
+    }
+    let compressed = lz4_flex::compress_prepend_size(text.as_bytes());
+    (compressed.len() < text.len()).then_some(compressed)
+}
+
+/// Restores a body produced by [`compress`]
+///
+/// Corrupt data yields an empty string rather than failing the read.
+pub fn decompress(bytes: &[u8]) -> String {
+    lz4_flex::decompress_size_prepended(bytes)
+        .map(|text| String::from_utf8_lossy(&text).into_owned())
+        .unwrap_or_default()
+}
+
+// ============================================================================
+// COMPRESSION TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_long_bodies_round_trip() {
+        let article = "Decentralized speech needs durable storage. ".repeat(100);
+        let compressed = compress(&article);
+        assert!(compressed
+            .as_ref()
+            .is_some_and(|bytes| bytes.len() < article.len()));
+        assert_eq!(compressed.map(|bytes| decompress(&bytes)), Some(article));
+    }
+
+    #[test]
+    fn test_short_bodies_are_stored_as_is() {
+        assert_eq!(compress("gm ☀️"), None);
+        assert_eq!(compress(&"a".repeat(COMPRESSION_THRESHOLD_BYTES - 1)), None);
+        assert_eq!(decompress(b"not lz4"), "");
+    }
+}
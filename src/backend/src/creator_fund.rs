@@ -0,0 +1,81 @@
+use crate::types::{EngagementCounts, UserId};
+use std::collections::BTreeMap;
+
+/// Subaccount of the canister holding the creator fund treasury
+pub const TREASURY_SUBACCOUNT: [u8; 32] = *b"decentra-creator-fund-treasury\0\0";
+
+/// Reward points for a creator's engagement in one period
+///
+/// Weighted like reputation: comments and reposts take more effort to fake
+/// than likes, and views are not counted at all.
+pub fn engagement_points(counts: &EngagementCounts) -> u64 {
+    counts
+        .likes
+        .saturating_add(counts.comments.saturating_mul(2))
+        .saturating_add(counts.reposts.saturating_mul(3))
+}
+
+/// Splits `pool` among creators in proportion to their points
+///
+/// No creator receives more than `max_share_percent` of the pool; amounts
+/// trimmed by the cap and rounding remainders stay in the treasury.
+pub fn allocate(
+    pool: u64,
+    points: &BTreeMap<UserId, u64>,
+    max_share_percent: u8,
+) -> BTreeMap<UserId, u64> {
+    let total: u128 = points.values().map(|&p| p as u128).sum();
+    if total == 0 {
+        return BTreeMap::new();
+    }
+
+    let cap = pool as u128 * max_share_percent.min(100) as u128 / 100;
+    points
+        .iter()
+        .filter_map(|(&creator, &creator_points)| {
+            let share = (pool as u128 * creator_points as u128 / total).min(cap) as u64;
+            (share > 0).then_some((creator, share))
+        })
+        .collect()
+}
+
+// ============================================================================
+// CREATOR FUND TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use candid::Principal;
+
+    fn creator(byte: u8) -> UserId {
+        UserId(Principal::from_slice(&[byte; 29]))
+    }
+
+    #[test]
+    fn test_allocate_is_proportional_and_capped() {
+        let points = BTreeMap::from([(creator(1), 300), (creator(2), 100), (creator(3), 0)]);
+
+        let shares = allocate(1_000, &points, 100);
+        assert_eq!(shares.get(&creator(1)), Some(&750));
+        assert_eq!(shares.get(&creator(2)), Some(&250));
+        assert_eq!(shares.get(&creator(3)), None);
+
+        let capped = allocate(1_000, &points, 50);
+        assert_eq!(capped.get(&creator(1)), Some(&500));
+        assert_eq!(capped.get(&creator(2)), Some(&250));
+
+        assert!(allocate(1_000, &BTreeMap::new(), 100).is_empty());
+    }
+
+    #[test]
+    fn test_engagement_points_ignore_views() {
+        let counts = EngagementCounts {
+            likes: 10,
+            comments: 2,
+            reposts: 1,
+            views: 10_000,
+        };
+        assert_eq!(engagement_points(&counts), 17);
+    }
+}
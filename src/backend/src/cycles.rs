@@ -0,0 +1,55 @@
+use crate::types::{CyclesSample, NANOS_PER_DAY};
+
+/// Average cycles burned per day over the sampled period
+///
+/// Only drops between consecutive samples count, so top-ups do not hide usage.
+pub fn daily_burn(samples: &[CyclesSample]) -> Option<u128> {
+    let (first, last) = (samples.first()?, samples.last()?);
+    let elapsed = last.timestamp.saturating_sub(first.timestamp);
+    if elapsed == 0 {
+        return None;
+    }
+
+    let burned: u128 = samples
+        .windows(2)
+        .map(|pair| pair[0].balance.saturating_sub(pair[1].balance))
+        .sum();
+    Some(burned.saturating_mul(NANOS_PER_DAY as u128) / elapsed as u128)
+}
+
+/// Whole days until `balance` runs out at `daily_burn` cycles per day
+pub fn days_remaining(balance: u128, daily_burn: u128) -> Option<u64> {
+    (daily_burn > 0).then(|| u64::try_from(balance / daily_burn).unwrap_or(u64::MAX))
+}
+
+// ============================================================================
+// CYCLES TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(hours: u64, balance: u128) -> CyclesSample {
+        CyclesSample {
+            timestamp: hours * 3_600 * 1_000_000_000,
+            balance,
+        }
+    }
+
+    #[test]
+    fn test_daily_burn_ignores_top_ups() {
+        let samples = [
+            sample(0, 5_000),
+            sample(12, 4_000),
+            sample(18, 9_000),
+            sample(24, 8_000),
+        ];
+        assert_eq!(daily_burn(&samples), Some(2_000));
+        assert_eq!(days_remaining(8_000, 2_000), Some(4));
+
+        assert_eq!(daily_burn(&samples[..1]), None);
+        assert_eq!(daily_burn(&[]), None);
+        assert_eq!(days_remaining(8_000, 0), None);
+    }
+}
@@ -0,0 +1,126 @@
+use crate::types::{PostId, UserId, MAX_DELETION_LOG_ENTRIES};
+use candid::{CandidType, Deserialize};
+use std::collections::VecDeque;
+
+/// Deleted post as recorded for feed syncs
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct DeletionEntry {
+    pub post_id: PostId,
+    pub author_id: UserId,
+    pub deleted_at: u64,
+}
+
+/// Recent post deletions, oldest first, so syncing clients can drop them
+///
+/// Entries outlive the purge of the post itself; only the latest
+/// `MAX_DELETION_LOG_ENTRIES` are held.
+#[derive(CandidType, Deserialize, Clone, Debug, Default)]
+pub struct DeletionLog {
+    entries: VecDeque<DeletionEntry>,
+
+    /// Deletion time of the newest entry dropped to make room
+    dropped_until: u64,
+}
+
+impl DeletionLog {
+    /// Records a deletion, dropping the oldest beyond `MAX_DELETION_LOG_ENTRIES`
+    pub fn record(&mut self, post_id: PostId, author_id: UserId, deleted_at: u64) {
+        self.entries.push_back(DeletionEntry {
+            post_id,
+            author_id,
+            deleted_at,
+        });
+        if self.entries.len() > MAX_DELETION_LOG_ENTRIES {
+            if let Some(dropped) = self.entries.pop_front() {
+                self.dropped_until = self.dropped_until.max(dropped.deleted_at);
+            }
+        }
+    }
+
+    /// Whether every deletion after `since` is still held
+    pub fn is_complete_since(&self, since: u64) -> bool {
+        since >= self.dropped_until
+    }
+
+    /// Posts deleted after `since` that `is_live` does not report restored
+    pub fn deleted_since<'a>(
+        &'a self,
+        since: u64,
+        is_live: impl Fn(PostId) -> bool + 'a,
+    ) -> impl Iterator<Item = &'a DeletionEntry> + 'a {
+        let start = self
+            .entries
+            .partition_point(|entry| entry.deleted_at <= since);
+        self.entries
+            .range(start..)
+            .filter(move |entry| !is_live(entry.post_id))
+    }
+}
+
+// ============================================================================
+// DELETION LOG TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use candid::Principal;
+    use std::collections::BTreeSet;
+
+    fn author() -> UserId {
+        UserId(Principal::anonymous())
+    }
+
+    fn deleted(log: &DeletionLog, since: u64, live: &BTreeSet<PostId>) -> Vec<u64> {
+        log.deleted_since(since, |post_id| live.contains(&post_id))
+            .map(|entry| entry.post_id.0)
+            .collect()
+    }
+
+    #[test]
+    fn test_deleted_since() {
+        let mut log = DeletionLog::default();
+        log.record(PostId(1), author(), 10);
+        log.record(PostId(2), author(), 20);
+        let live = BTreeSet::new();
+        assert_eq!(deleted(&log, 0, &live), vec![1, 2]);
+        assert_eq!(deleted(&log, 10, &live), vec![2]);
+        assert!(deleted(&log, 20, &live).is_empty());
+        assert!(log.is_complete_since(0));
+    }
+
+    #[test]
+    fn test_restored_posts_not_reported() {
+        let mut log = DeletionLog::default();
+        log.record(PostId(1), author(), 10);
+        log.record(PostId(2), author(), 20);
+        let live = BTreeSet::from([PostId(1)]);
+        assert_eq!(deleted(&log, 0, &live), vec![2]);
+
+        // Deleted again after the restore
+        log.record(PostId(1), author(), 30);
+        assert_eq!(deleted(&log, 20, &BTreeSet::new()), vec![1]);
+    }
+
+    #[test]
+    fn test_purged_posts_still_reported() {
+        // Purging removes the post for good, so it is never live again
+        let mut log = DeletionLog::default();
+        log.record(PostId(1), author(), 10);
+        assert_eq!(deleted(&log, 5, &BTreeSet::new()), vec![1]);
+    }
+
+    #[test]
+    fn test_dropped_entries_mark_sync_incomplete() {
+        let mut log = DeletionLog::default();
+        for deleted_at in 1..=MAX_DELETION_LOG_ENTRIES as u64 + 1 {
+            log.record(PostId(deleted_at), author(), deleted_at);
+        }
+        assert!(!log.is_complete_since(0));
+        assert!(log.is_complete_since(1));
+        assert_eq!(
+            log.deleted_since(0, |_| false).count(),
+            MAX_DELETION_LOG_ENTRIES
+        );
+    }
+}
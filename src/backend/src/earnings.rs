@@ -0,0 +1,45 @@
+use crate::types::UserId;
+use sha2::{Digest, Sha256};
+
+/// Domain separator for per-creator earnings subaccounts
+const EARNINGS_DOMAIN: &[u8] = b"decentra-creator-earnings";
+
+/// Subaccount of the canister where `creator`'s token tips and sales accrue
+pub fn earnings_subaccount(creator: &UserId) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(EARNINGS_DOMAIN);
+    hasher.update(creator.0.as_slice());
+    hasher.finalize().into()
+}
+
+/// Amount a withdrawal can send from `balance` after the ledger fee
+pub fn withdrawable(balance: u64, fee: u64) -> u64 {
+    balance.saturating_sub(fee)
+}
+
+// ============================================================================
+// EARNINGS TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use candid::Principal;
+
+    #[test]
+    fn test_earnings_subaccounts_are_per_creator() {
+        let alice = UserId(Principal::from_slice(&[1; 29]));
+        let bob = UserId(Principal::from_slice(&[2; 29]));
+        assert_ne!(earnings_subaccount(&alice), earnings_subaccount(&bob));
+        assert_ne!(
+            earnings_subaccount(&alice),
+            crate::points::deposit_subaccount(&alice)
+        );
+    }
+
+    #[test]
+    fn test_withdrawable_leaves_room_for_fee() {
+        assert_eq!(withdrawable(1_000, 10), 990);
+        assert_eq!(withdrawable(5, 10), 0);
+    }
+}
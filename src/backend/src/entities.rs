@@ -0,0 +1,161 @@
+use crate::types::{EntityKind, PostEntity};
+use std::collections::BTreeSet;
+
+/// Punctuation trimmed from the end of a link, mention or hashtag
+const TRAILING_PUNCTUATION: &str = ".,;:!?)]}>\"'*_`";
+
+/// Extracts link, mention and hashtag entities from post content
+///
+/// Offsets are character (Unicode scalar) positions, so they survive the
+/// length-preserving word filter. Mention entities are returned unresolved;
+/// the caller fills in `user_id` from the handle index.
+pub fn extract_entities(content: &str) -> Vec<PostEntity> {
+    let mut entities = Vec::new();
+    let mut token_start = None;
+
+    let chars: Vec<char> = content.chars().collect();
+    for (index, c) in chars.iter().chain(std::iter::once(&' ')).enumerate() {
+        match (c.is_whitespace(), token_start) {
+            (false, None) => token_start = Some(index),
+            (true, Some(start)) => {
+                let token: String = chars[start..index].iter().collect();
+                if let Some(entity) = token_entity(&token, start) {
+                    entities.push(entity);
+                }
+                token_start = None;
+            }
+            _ => {}
+        }
+    }
+    entities
+}
+
+/// Extracts distinct custom emoji shortcodes written as `:shortcode:`
+pub fn extract_emoji_codes(content: &str) -> BTreeSet<String> {
+    let mut codes = BTreeSet::new();
+    let mut rest = content;
+    while let Some(open) = rest.find(':') {
+        let after = &rest[open + 1..];
+        let Some(close) = after.find(':') else {
+            break;
+        };
+
+        let code = &after[..close];
+        let is_shortcode = !code.is_empty()
+            && code
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+        if is_shortcode {
+            codes.insert(code.to_string());
+            rest = &after[close + 1..];
+        } else {
+            rest = after;
+        }
+    }
+    codes
+}
+
+/// Internal function to classify a single whitespace-delimited token
+fn token_entity(token: &str, token_start: usize) -> Option<PostEntity> {
+    // Links may be wrapped in markdown syntax, e.g. `[docs](https://...)`
+    if let Some(byte_start) = token.find("https://").or_else(|| token.find("http://")) {
+        let value = token[byte_start..].trim_end_matches(|c| TRAILING_PUNCTUATION.contains(c));
+        let start = token_start + token[..byte_start].chars().count();
+        return Some(entity(EntityKind::Link, start, value));
+    }
+
+    let leading = token.chars().take_while(|c| "([*_".contains(*c)).count();
+    let rest: String = token.chars().skip(leading).collect();
+    let (kind, body) = if let Some(handle) = rest.strip_prefix('@') {
+        (EntityKind::Mention, handle)
+    } else if let Some(tag) = rest.strip_prefix('#') {
+        (EntityKind::Hashtag, tag)
+    } else {
+        return None;
+    };
+
+    let name: String = body
+        .chars()
+        .take_while(|c| {
+            c.is_alphanumeric() || *c == '_' || (*c == '-' && kind == EntityKind::Mention)
+        })
+        .collect();
+    if name.is_empty() {
+        return None;
+    }
+    let value = format!("{}{name}", &rest[..1]);
+    Some(entity(kind, token_start + leading, &value))
+}
+
+/// Internal function to build an entity spanning `value` from `start`
+fn entity(kind: EntityKind, start: usize, value: &str) -> PostEntity {
+    PostEntity {
+        kind,
+        start: start as u32,
+        end: (start + value.chars().count()) as u32,
+        value: value.to_string(),
+        user_id: None,
+    }
+}
+
+// ============================================================================
+// ENTITY TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn summary(content: &str) -> Vec<(EntityKind, u32, u32, String)> {
+        extract_entities(content)
+            .into_iter()
+            .map(|entity| (entity.kind, entity.start, entity.end, entity.value))
+            .collect()
+    }
+
+    #[test]
+    fn test_extract_entities() {
+        assert_eq!(
+            summary("hi @alice_b, see https://example.com/a. #ICP!"),
+            vec![
+                (EntityKind::Mention, 3, 11, "@alice_b".to_string()),
+                (
+                    EntityKind::Link,
+                    17,
+                    38,
+                    "https://example.com/a".to_string()
+                ),
+                (EntityKind::Hashtag, 40, 44, "#ICP".to_string()),
+            ]
+        );
+        assert!(summary("email me at a@b or #").is_empty());
+    }
+
+    #[test]
+    fn test_entities_inside_markdown() {
+        assert_eq!(
+            summary("**@bob** [docs](https://x.io/d)"),
+            vec![
+                (EntityKind::Mention, 2, 6, "@bob".to_string()),
+                (EntityKind::Link, 16, 30, "https://x.io/d".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_extract_emoji_codes() {
+        let codes = extract_emoji_codes("gm :wave: :party_parrot::wave: not: a code :Bad: ::");
+        assert_eq!(
+            codes.into_iter().collect::<Vec<_>>(),
+            vec!["party_parrot".to_string(), "wave".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_offsets_count_characters() {
+        assert_eq!(
+            summary("héllo #café"),
+            vec![(EntityKind::Hashtag, 6, 11, "#café".to_string())]
+        );
+    }
+}
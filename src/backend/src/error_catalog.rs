@@ -0,0 +1,239 @@
+use crate::errors::SocialNetworkError;
+
+/// Error message in `locale`, if its language has a translation
+///
+/// Spanish and French are translated. Only the primary subtag is matched, so
+/// `es-MX` gets the Spanish message.
+pub fn message(error: &SocialNetworkError, locale: &str) -> Option<String> {
+    let language = locale.split('-').next().unwrap_or_default();
+    match language.to_ascii_lowercase().as_str() {
+        "es" => Some(spanish(error)),
+        "fr" => Some(french(error)),
+        _ => None,
+    }
+}
+
+fn spanish(error: &SocialNetworkError) -> String {
+    use SocialNetworkError::*;
+    match error {
+        AuthenticationRequired => {
+            "Autenticación requerida. Inicia sesión con Internet Identity.".to_string()
+        }
+        Unauthorized(msg) => format!("No autorizado: {msg}"),
+        IdentityInvalid => "Identidad no válida. Vuelve a iniciar sesión.".to_string(),
+        AccountSuspended(reason) => format!("Cuenta suspendida: {reason}"),
+
+        UserNotFound(user_id) => format!("Usuario no encontrado: {}", user_id.0.to_text()),
+        UsernameAlreadyTaken(username) => {
+            format!("El nombre de usuario '{username}' ya está en uso")
+        }
+        ProfileAlreadyExists => "El perfil de usuario ya existe".to_string(),
+        InvalidUsername(msg) => format!("Nombre de usuario no válido: {msg}"),
+        InvalidDisplayName(msg) => format!("Nombre visible no válido: {msg}"),
+        InvalidBio(msg) => format!("Biografía no válida: {msg}"),
+        InvalidAvatar(msg) => format!("Avatar no válido: {msg}"),
+        VersionConflict { expected, current } => format!(
+            "El perfil se modificó en otro lugar (versión {current}, se esperaba {expected}). Recarga e inténtalo de nuevo."
+        ),
+
+        PostNotFound(post_id) => format!("Publicación no encontrada: {}", post_id.0),
+        ContentTooLong { max, actual } => {
+            format!("Contenido demasiado largo: {actual} caracteres (máximo: {max})")
+        }
+        ContentEmpty => "El contenido no puede estar vacío".to_string(),
+        ContentProhibited(reason) => format!("Contenido prohibido: {reason}"),
+        InvalidMediaUrl(url) => format!("URL de medios no válida: {url}"),
+        ContentUnderReview => "El contenido está en revisión de moderación".to_string(),
+        ContentRemoved(reason) => format!("Contenido eliminado: {reason}"),
+
+        CommentNotFound(comment_id) => format!("Comentario no encontrado: {}", comment_id.0),
+        InvalidCommentContent(msg) => format!("Comentario no válido: {msg}"),
+        CommentDepthExceeded => "Se superó la profundidad de respuestas".to_string(),
+        CommentsDisabled => "Los comentarios están desactivados en esta publicación".to_string(),
+
+        CannotFollowSelf => "No puedes seguirte a ti mismo".to_string(),
+        AlreadyFollowing(user_id) => format!("Ya sigues a: {}", user_id.0.to_text()),
+        NotFollowing(user_id) => format!("No sigues a: {}", user_id.0.to_text()),
+        UserBlocked(user_id) => format!("Este usuario te ha bloqueado: {}", user_id.0.to_text()),
+        PrivacyRestriction(msg) => format!("Restricción de privacidad: {msg}"),
+        AlreadyLiked => "Ya te gusta esta publicación".to_string(),
+        NotLiked => "No te gusta esta publicación".to_string(),
+
+        RateLimitExceeded {
+            limit,
+            window_seconds,
+            retry_after,
+        } => format!(
+            "Límite superado: {limit} acciones cada {window_seconds} segundos. Inténtalo de nuevo en {retry_after} segundos."
+        ),
+        ResourceLimitExceeded(resource) => format!("Límite de recursos superado: {resource}"),
+        BatchSizeTooLarge { max, requested } => {
+            format!("Lote demasiado grande: {requested} elementos (máximo: {max})")
+        }
+        StorageQuotaExceeded => "Cuota de almacenamiento superada".to_string(),
+        CycleLimitExceeded => {
+            "Límite de ciclos superado. Inténtalo de nuevo más tarde.".to_string()
+        }
+
+        ContentFlagged(reason) => format!("Contenido denunciado: {reason}"),
+        SpamDetected(reason) => format!("Spam detectado: {reason}"),
+        MaliciousContentDetected(reason) => format!("Contenido malicioso detectado: {reason}"),
+        ModerationProposalNotFound(id) => {
+            format!("Propuesta de moderación no encontrada: {id}")
+        }
+        InsufficientModerationRights => "Permisos de moderación insuficientes".to_string(),
+
+        InsufficientBalance {
+            required,
+            available,
+        } => format!(
+            "Saldo insuficiente: se necesitan {} ICP, tienes {} ICP",
+            icp(*required),
+            icp(*available)
+        ),
+        InvalidTipAmount(msg) => format!("Importe de propina no válido: {msg}"),
+        TipTransactionFailed(reason) => format!("La propina falló: {reason}"),
+        SubscriptionNotFound(id) => format!("Suscripción no encontrada: {id}"),
+        PaymentError(msg) => format!("Error de pago: {msg}"),
+
+        StateCorrupted(msg) => format!("Estado del sistema dañado: {msg}"),
+        StorageError(msg) => format!("Error de almacenamiento: {msg}"),
+        InvalidRequest(msg) => format!("Solicitud no válida: {msg}"),
+        NetworkError(msg) => format!("Error de red: {msg}"),
+        UpgradeInProgress => {
+            "Actualización del sistema en curso. Inténtalo de nuevo más tarde.".to_string()
+        }
+        NotImplemented(feature) => format!("Función aún no disponible: {feature}"),
+        InternalError(msg) => format!("Error interno: {msg}"),
+    }
+}
+
+fn french(error: &SocialNetworkError) -> String {
+    use SocialNetworkError::*;
+    match error {
+        AuthenticationRequired => {
+            "Authentification requise. Connectez-vous avec Internet Identity.".to_string()
+        }
+        Unauthorized(msg) => format!("Non autorisé : {msg}"),
+        IdentityInvalid => "Identité invalide. Veuillez vous reconnecter.".to_string(),
+        AccountSuspended(reason) => format!("Compte suspendu : {reason}"),
+
+        UserNotFound(user_id) => format!("Utilisateur introuvable : {}", user_id.0.to_text()),
+        UsernameAlreadyTaken(username) => {
+            format!("Le nom d'utilisateur « {username} » est déjà pris")
+        }
+        ProfileAlreadyExists => "Le profil existe déjà".to_string(),
+        InvalidUsername(msg) => format!("Nom d'utilisateur invalide : {msg}"),
+        InvalidDisplayName(msg) => format!("Nom affiché invalide : {msg}"),
+        InvalidBio(msg) => format!("Biographie invalide : {msg}"),
+        InvalidAvatar(msg) => format!("Avatar invalide : {msg}"),
+        VersionConflict { expected, current } => format!(
+            "Le profil a été modifié ailleurs (version {current}, {expected} attendue). Rechargez et réessayez."
+        ),
+
+        PostNotFound(post_id) => format!("Publication introuvable : {}", post_id.0),
+        ContentTooLong { max, actual } => {
+            format!("Contenu trop long : {actual} caractères (maximum : {max})")
+        }
+        ContentEmpty => "Le contenu ne peut pas être vide".to_string(),
+        ContentProhibited(reason) => format!("Contenu interdit : {reason}"),
+        InvalidMediaUrl(url) => format!("URL de média invalide : {url}"),
+        ContentUnderReview => "Le contenu est en cours de modération".to_string(),
+        ContentRemoved(reason) => format!("Contenu supprimé : {reason}"),
+
+        CommentNotFound(comment_id) => format!("Commentaire introuvable : {}", comment_id.0),
+        InvalidCommentContent(msg) => format!("Commentaire invalide : {msg}"),
+        CommentDepthExceeded => "Profondeur de réponses dépassée".to_string(),
+        CommentsDisabled => "Les commentaires sont désactivés pour cette publication".to_string(),
+
+        CannotFollowSelf => "Vous ne pouvez pas vous suivre vous-même".to_string(),
+        AlreadyFollowing(user_id) => format!("Vous suivez déjà : {}", user_id.0.to_text()),
+        NotFollowing(user_id) => format!("Vous ne suivez pas : {}", user_id.0.to_text()),
+        UserBlocked(user_id) => {
+            format!("Cet utilisateur vous a bloqué : {}", user_id.0.to_text())
+        }
+        PrivacyRestriction(msg) => format!("Restriction de confidentialité : {msg}"),
+        AlreadyLiked => "Vous aimez déjà cette publication".to_string(),
+        NotLiked => "Vous n'aimez pas cette publication".to_string(),
+
+        RateLimitExceeded {
+            limit,
+            window_seconds,
+            retry_after,
+        } => format!(
+            "Limite atteinte : {limit} actions par {window_seconds} secondes. Réessayez dans {retry_after} secondes."
+        ),
+        ResourceLimitExceeded(resource) => format!("Limite de ressources dépassée : {resource}"),
+        BatchSizeTooLarge { max, requested } => {
+            format!("Lot trop grand : {requested} éléments (maximum : {max})")
+        }
+        StorageQuotaExceeded => "Quota de stockage dépassé".to_string(),
+        CycleLimitExceeded => "Limite de cycles dépassée. Réessayez plus tard.".to_string(),
+
+        ContentFlagged(reason) => format!("Contenu signalé : {reason}"),
+        SpamDetected(reason) => format!("Spam détecté : {reason}"),
+        MaliciousContentDetected(reason) => format!("Contenu malveillant détecté : {reason}"),
+        ModerationProposalNotFound(id) => {
+            format!("Proposition de modération introuvable : {id}")
+        }
+        InsufficientModerationRights => "Droits de modération insuffisants".to_string(),
+
+        InsufficientBalance {
+            required,
+            available,
+        } => format!(
+            "Solde insuffisant : {} ICP requis, {} ICP disponibles",
+            icp(*required),
+            icp(*available)
+        ),
+        InvalidTipAmount(msg) => format!("Montant de pourboire invalide : {msg}"),
+        TipTransactionFailed(reason) => format!("Échec du pourboire : {reason}"),
+        SubscriptionNotFound(id) => format!("Abonnement introuvable : {id}"),
+        PaymentError(msg) => format!("Erreur de paiement : {msg}"),
+
+        StateCorrupted(msg) => format!("État du système corrompu : {msg}"),
+        StorageError(msg) => format!("Erreur de stockage : {msg}"),
+        InvalidRequest(msg) => format!("Requête invalide : {msg}"),
+        NetworkError(msg) => format!("Erreur réseau : {msg}"),
+        UpgradeInProgress => {
+            "Mise à jour du système en cours. Réessayez plus tard.".to_string()
+        }
+        NotImplemented(feature) => format!("Fonctionnalité pas encore disponible : {feature}"),
+        InternalError(msg) => format!("Erreur interne : {msg}"),
+    }
+}
+
+/// Formats e8s as ICP with eight decimals, without floating point
+fn icp(e8s: u64) -> String {
+    format!("{}.{:08}", e8s / 100_000_000, e8s % 100_000_000)
+}
+
+// ============================================================================
+// ERROR CATALOG TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_message_by_locale() {
+        let error = SocialNetworkError::CannotFollowSelf;
+        assert_eq!(
+            message(&error, "es-MX").as_deref(),
+            Some("No puedes seguirte a ti mismo")
+        );
+        assert_eq!(
+            message(&error, "FR").as_deref(),
+            Some("Vous ne pouvez pas vous suivre vous-même")
+        );
+        assert_eq!(message(&error, "en"), None);
+        assert_eq!(message(&error, "de"), None);
+    }
+
+    #[test]
+    fn test_icp_formatting() {
+        assert_eq!(icp(150_000_000), "1.50000000");
+        assert_eq!(icp(1), "0.00000001");
+    }
+}
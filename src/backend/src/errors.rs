@@ -1,3 +1,4 @@
+use crate::error_catalog;
 use crate::types::{CommentId, PostId, UserId};
 use candid::{CandidType, Deserialize};
 
@@ -43,6 +44,9 @@ pub enum SocialNetworkError {
     /// Avatar content doesn't meet requirements
     InvalidAvatar(String),
 
+    /// Profile changed since the client read it (optimistic concurrency)
+    VersionConflict { expected: u64, current: u64 },
+
     // ============================================================================
     // CONTENT MANAGEMENT ERRORS
     // ============================================================================
@@ -190,8 +194,24 @@ pub enum SocialNetworkError {
 }
 
 impl From<SocialNetworkError> for String {
+    /// Error text in the caller's locale, prefixed with its stable code
+    /// (e.g. `[POST_NOT_FOUND] Post not found: 7`)
     fn from(error: SocialNetworkError) -> String {
-        match error {
+        let locale = crate::caller_locale();
+        format!("[{}] {}", error.code(), error.message(locale.as_deref()))
+    }
+}
+
+impl SocialNetworkError {
+    /// Message in `locale`, falling back to English without a translation
+    pub fn message(&self, locale: Option<&str>) -> String {
+        locale
+            .and_then(|locale| error_catalog::message(self, locale))
+            .unwrap_or_else(|| self.english())
+    }
+
+    fn english(&self) -> String {
+        match self {
             // Authentication & Authorization
             SocialNetworkError::AuthenticationRequired => {
                 "Authentication required. Please log in with Internet Identity.".to_string()
@@ -216,6 +236,9 @@ impl From<SocialNetworkError> for String {
             SocialNetworkError::InvalidDisplayName(msg) => format!("Invalid display name: {msg}"),
             SocialNetworkError::InvalidBio(msg) => format!("Invalid bio: {msg}"),
             SocialNetworkError::InvalidAvatar(msg) => format!("Invalid avatar: {msg}"),
+            SocialNetworkError::VersionConflict { expected, current } => format!(
+                "Profile was modified elsewhere (version {current}, expected {expected}). Reload and try again."
+            ),
 
             // Content Management
             SocialNetworkError::PostNotFound(post_id) => format!("Post not found: {}", post_id.0),
@@ -298,9 +321,9 @@ impl From<SocialNetworkError> for String {
             } => {
                 // Allow precision loss for ICP balance display (acceptable trade-off)
                 #[allow(clippy::cast_precision_loss)]
-                let required_icp = required as f64 / 100_000_000.0;
+                let required_icp = *required as f64 / 100_000_000.0;
                 #[allow(clippy::cast_precision_loss)]
-                let available_icp = available as f64 / 100_000_000.0;
+                let available_icp = *available as f64 / 100_000_000.0;
                 format!(
                     "Insufficient balance: need {required_icp:.8} ICP, have {available_icp:.8} ICP"
                 )
@@ -335,7 +358,7 @@ impl From<SocialNetworkError> for String {
 pub type SocialResult<T> = Result<T, SocialNetworkError>;
 
 /// Error categorization for metrics and monitoring
-#[derive(Debug, Clone, CandidType, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, CandidType, Deserialize)]
 pub enum ErrorCategory {
     Authentication,
     Authorization,
@@ -348,6 +371,64 @@ pub enum ErrorCategory {
 }
 
 impl SocialNetworkError {
+    /// Stable identifier of the error, the same in every language
+    pub fn code(&self) -> &'static str {
+        match self {
+            SocialNetworkError::AuthenticationRequired => "AUTHENTICATION_REQUIRED",
+            SocialNetworkError::Unauthorized(_) => "UNAUTHORIZED",
+            SocialNetworkError::IdentityInvalid => "IDENTITY_INVALID",
+            SocialNetworkError::AccountSuspended(_) => "ACCOUNT_SUSPENDED",
+            SocialNetworkError::UserNotFound(_) => "USER_NOT_FOUND",
+            SocialNetworkError::UsernameAlreadyTaken(_) => "USERNAME_ALREADY_TAKEN",
+            SocialNetworkError::ProfileAlreadyExists => "PROFILE_ALREADY_EXISTS",
+            SocialNetworkError::InvalidUsername(_) => "INVALID_USERNAME",
+            SocialNetworkError::InvalidDisplayName(_) => "INVALID_DISPLAY_NAME",
+            SocialNetworkError::InvalidBio(_) => "INVALID_BIO",
+            SocialNetworkError::InvalidAvatar(_) => "INVALID_AVATAR",
+            SocialNetworkError::VersionConflict { .. } => "VERSION_CONFLICT",
+            SocialNetworkError::PostNotFound(_) => "POST_NOT_FOUND",
+            SocialNetworkError::ContentTooLong { .. } => "CONTENT_TOO_LONG",
+            SocialNetworkError::ContentEmpty => "CONTENT_EMPTY",
+            SocialNetworkError::ContentProhibited(_) => "CONTENT_PROHIBITED",
+            SocialNetworkError::InvalidMediaUrl(_) => "INVALID_MEDIA_URL",
+            SocialNetworkError::ContentUnderReview => "CONTENT_UNDER_REVIEW",
+            SocialNetworkError::ContentRemoved(_) => "CONTENT_REMOVED",
+            SocialNetworkError::CommentNotFound(_) => "COMMENT_NOT_FOUND",
+            SocialNetworkError::InvalidCommentContent(_) => "INVALID_COMMENT_CONTENT",
+            SocialNetworkError::CommentDepthExceeded => "COMMENT_DEPTH_EXCEEDED",
+            SocialNetworkError::CommentsDisabled => "COMMENTS_DISABLED",
+            SocialNetworkError::CannotFollowSelf => "CANNOT_FOLLOW_SELF",
+            SocialNetworkError::AlreadyFollowing(_) => "ALREADY_FOLLOWING",
+            SocialNetworkError::NotFollowing(_) => "NOT_FOLLOWING",
+            SocialNetworkError::UserBlocked(_) => "USER_BLOCKED",
+            SocialNetworkError::PrivacyRestriction(_) => "PRIVACY_RESTRICTION",
+            SocialNetworkError::AlreadyLiked => "ALREADY_LIKED",
+            SocialNetworkError::NotLiked => "NOT_LIKED",
+            SocialNetworkError::RateLimitExceeded { .. } => "RATE_LIMIT_EXCEEDED",
+            SocialNetworkError::ResourceLimitExceeded(_) => "RESOURCE_LIMIT_EXCEEDED",
+            SocialNetworkError::BatchSizeTooLarge { .. } => "BATCH_SIZE_TOO_LARGE",
+            SocialNetworkError::StorageQuotaExceeded => "STORAGE_QUOTA_EXCEEDED",
+            SocialNetworkError::CycleLimitExceeded => "CYCLE_LIMIT_EXCEEDED",
+            SocialNetworkError::ContentFlagged(_) => "CONTENT_FLAGGED",
+            SocialNetworkError::SpamDetected(_) => "SPAM_DETECTED",
+            SocialNetworkError::MaliciousContentDetected(_) => "MALICIOUS_CONTENT_DETECTED",
+            SocialNetworkError::ModerationProposalNotFound(_) => "MODERATION_PROPOSAL_NOT_FOUND",
+            SocialNetworkError::InsufficientModerationRights => "INSUFFICIENT_MODERATION_RIGHTS",
+            SocialNetworkError::InsufficientBalance { .. } => "INSUFFICIENT_BALANCE",
+            SocialNetworkError::InvalidTipAmount(_) => "INVALID_TIP_AMOUNT",
+            SocialNetworkError::TipTransactionFailed(_) => "TIP_TRANSACTION_FAILED",
+            SocialNetworkError::SubscriptionNotFound(_) => "SUBSCRIPTION_NOT_FOUND",
+            SocialNetworkError::PaymentError(_) => "PAYMENT_ERROR",
+            SocialNetworkError::StateCorrupted(_) => "STATE_CORRUPTED",
+            SocialNetworkError::StorageError(_) => "STORAGE_ERROR",
+            SocialNetworkError::InvalidRequest(_) => "INVALID_REQUEST",
+            SocialNetworkError::NetworkError(_) => "NETWORK_ERROR",
+            SocialNetworkError::UpgradeInProgress => "UPGRADE_IN_PROGRESS",
+            SocialNetworkError::NotImplemented(_) => "NOT_IMPLEMENTED",
+            SocialNetworkError::InternalError(_) => "INTERNAL_ERROR",
+        }
+    }
+
     /// Categorizes errors for metrics and monitoring
     pub fn category(&self) -> ErrorCategory {
         match self {
@@ -452,6 +533,27 @@ mod tests {
         assert!(message.contains("Authentication required"));
     }
 
+    #[test]
+    fn test_codes_are_locale_independent() {
+        let error = SocialNetworkError::PostNotFound(PostId(7));
+        assert_eq!(error.code(), "POST_NOT_FOUND");
+        assert_eq!(error.message(None), "Post not found: 7");
+        assert_eq!(error.message(Some("en-GB")), "Post not found: 7");
+        assert_eq!(error.message(Some("es")), "Publicación no encontrada: 7");
+        let message: String = error.into();
+        assert_eq!(message, "[POST_NOT_FOUND] Post not found: 7");
+    }
+
+    #[test]
+    fn test_version_conflict_message() {
+        let message: String = SocialNetworkError::VersionConflict {
+            expected: 3,
+            current: 5,
+        }
+        .into();
+        assert!(message.contains("version 5, expected 3"));
+    }
+
     #[test]
     fn test_error_categorization() {
         let auth_error = SocialNetworkError::AuthenticationRequired;
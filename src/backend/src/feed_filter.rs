@@ -0,0 +1,186 @@
+use crate::types::FeedPreferences;
+
+/// File extensions treated as embedded media when linked from a post
+const MEDIA_EXTENSIONS: &[&str] = &[
+    "jpg", "jpeg", "png", "gif", "webp", "avif", "svg", "mp4", "webm", "mov", "mp3", "ogg", "wav",
+    "m4a",
+];
+
+/// Media extensions that link previews can show as images
+const IMAGE_EXTENSIONS: &[&str] = &["jpg", "jpeg", "png", "gif", "webp", "avif"];
+
+/// Whether a post links to an image, video or audio file
+pub fn has_media(content: &str) -> bool {
+    media_links(content).next().is_some()
+}
+
+/// Image, video and audio links in a post, without trailing punctuation
+pub fn media_links(content: &str) -> impl Iterator<Item = &str> + '_ {
+    content.split_whitespace().filter_map(|token| {
+        let start = token.find("https://")?;
+        let link = token[start..].trim_end_matches(|c: char| ".,;:!)]}>\"'".contains(c));
+        MEDIA_EXTENSIONS
+            .contains(&link_extension(link)?.as_str())
+            .then_some(link)
+    })
+}
+
+/// First image link in a post, for link previews
+pub fn first_image_link(content: &str) -> Option<&str> {
+    media_links(content).find(|link| {
+        link_extension(link).is_some_and(|extension| IMAGE_EXTENSIONS.contains(&extension.as_str()))
+    })
+}
+
+/// Lowercase file extension of an `https://` link's path
+fn link_extension(link: &str) -> Option<String> {
+    let (_, path) = link["https://".len()..].split_once('/')?;
+    let path = path.split(['?', '#']).next().unwrap_or_default();
+    let (_, extension) = path.rsplit_once('.')?;
+    Some(extension.to_ascii_lowercase())
+}
+
+/// Lowercase hosts of the image, video and audio links in a post
+pub fn media_hosts(content: &str) -> impl Iterator<Item = String> + '_ {
+    media_links(content).map(|link| {
+        link["https://".len()..]
+            .split(['/', ':'])
+            .next()
+            .unwrap_or_default()
+            .to_lowercase()
+    })
+}
+
+/// Whether a post re-shares someone else's post ("RT @handle ...")
+pub fn is_repost(content: &str) -> bool {
+    content.trim_start().starts_with("RT @")
+}
+
+/// Whether a post passes the viewer's feed preferences
+///
+/// # Arguments
+/// * `preferences` - Active feed filters
+/// * `content` - Post content
+/// * `own_post` - Whether the viewer wrote the post
+pub fn matches(preferences: &FeedPreferences, content: &str, own_post: bool) -> bool {
+    if preferences.direct_follows_only && (own_post || is_repost(content)) {
+        return false;
+    }
+    if preferences.exclude_reposts && is_repost(content) {
+        return false;
+    }
+    !preferences.media_only || has_media(content)
+}
+
+/// Merges promoted items into a ranked feed
+///
+/// Promoted items take every `interval`-th slot starting with the first;
+/// once either list runs out the other fills the remaining slots.
+///
+/// # Returns
+/// Items in feed order, each paired with whether it is promoted
+pub fn interleave_promoted<T>(
+    organic: Vec<T>,
+    promoted: Vec<T>,
+    interval: usize,
+) -> Vec<(T, bool)> {
+    let mut organic = organic.into_iter().map(|item| (item, false)).peekable();
+    let mut promoted = promoted.into_iter().map(|item| (item, true)).peekable();
+    let mut feed = Vec::new();
+
+    loop {
+        let promoted_slot = feed.len() % interval.max(1) == 0;
+        let next = if promoted_slot || organic.peek().is_none() {
+            promoted.next().or_else(|| organic.next())
+        } else {
+            organic.next()
+        };
+        match next {
+            Some(item) => feed.push(item),
+            None => return feed,
+        }
+    }
+}
+
+// ============================================================================
+// FEED FILTER TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_has_media() {
+        assert!(has_media("Look https://cdn.example/cat.JPG!"));
+        assert!(has_media("https://cdn.example/clip.mp4?t=3"));
+        assert!(!has_media("https://news.example/article"));
+        assert!(!has_media("https://example.png"));
+        assert!(!has_media("photo.jpg without a link"));
+        assert_eq!(
+            media_hosts("a https://I.Imgur.com:443/x.png b https://x.io/page").collect::<Vec<_>>(),
+            vec!["i.imgur.com".to_string()]
+        );
+        assert_eq!(
+            media_links("See (https://cdn.example/cat.png), https://cdn.example/a.mp4?t=3.")
+                .collect::<Vec<_>>(),
+            vec![
+                "https://cdn.example/cat.png",
+                "https://cdn.example/a.mp4?t=3"
+            ]
+        );
+        assert_eq!(
+            first_image_link("https://cdn.example/a.mp4 https://cdn.example/cat.PNG"),
+            Some("https://cdn.example/cat.PNG")
+        );
+        assert_eq!(first_image_link("https://cdn.example/logo.svg"), None);
+    }
+
+    #[test]
+    fn test_matches_preferences() {
+        let all = FeedPreferences::default();
+        assert!(matches(&all, "RT @alice hello", false));
+
+        let no_reposts = FeedPreferences {
+            exclude_reposts: true,
+            ..FeedPreferences::default()
+        };
+        assert!(!matches(&no_reposts, "RT @alice hello", false));
+        assert!(matches(&no_reposts, "original thought", true));
+
+        let media = FeedPreferences {
+            media_only: true,
+            ..FeedPreferences::default()
+        };
+        assert!(!matches(&media, "text only", false));
+        assert!(matches(&media, "https://cdn.example/a.png", false));
+
+        let direct = FeedPreferences {
+            direct_follows_only: true,
+            ..FeedPreferences::default()
+        };
+        assert!(!matches(&direct, "my own post", true));
+        assert!(!matches(&direct, "RT @bob boosted", false));
+        assert!(matches(&direct, "from someone I follow", false));
+    }
+
+    #[test]
+    fn test_interleave_promoted() {
+        let feed = interleave_promoted(vec![1, 2, 3, 4, 5], vec![10, 20], 3);
+        assert_eq!(
+            feed,
+            vec![
+                (10, true),
+                (1, false),
+                (2, false),
+                (20, true),
+                (3, false),
+                (4, false),
+                (5, false)
+            ]
+        );
+
+        let sparse = interleave_promoted(vec![1], vec![10, 20, 30], 3);
+        assert_eq!(sparse, vec![(10, true), (1, false), (20, true), (30, true)]);
+    }
+}
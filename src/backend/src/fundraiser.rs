@@ -0,0 +1,79 @@
+use crate::types::{FundraiserStatus, PostId, UserId};
+use sha2::{Digest, Sha256};
+
+/// Domain separator for per-fundraiser escrow subaccounts
+const ESCROW_DOMAIN: &[u8] = b"decentra-fundraiser-escrow";
+
+/// Domain separator for escrow transfer memos
+const TRANSFER_MEMO_DOMAIN: &[u8] = b"decentra-fundraiser-transfer";
+
+/// Subaccount of the canister holding a fundraiser's contributions
+pub fn escrow_subaccount(post_id: PostId) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(ESCROW_DOMAIN);
+    hasher.update(post_id.0.to_be_bytes());
+    hasher.finalize().into()
+}
+
+/// Memo identifying an escrow transfer: the payout to the author (`None`)
+/// or the refund of one contributor
+///
+/// Every attempt at the same transfer carries the same memo, so the ledger
+/// can tell a retry from a new transfer.
+pub fn transfer_memo(post_id: PostId, refunded: Option<UserId>) -> Vec<u8> {
+    let mut hasher = Sha256::new();
+    hasher.update(TRANSFER_MEMO_DOMAIN);
+    hasher.update(post_id.0.to_be_bytes());
+    if let Some(contributor) = refunded {
+        hasher.update(contributor.0.as_slice());
+    }
+    hasher.finalize().to_vec()
+}
+
+/// Status a fundraiser moves to once its deadline has passed
+///
+/// Reaching the goal releases the escrow to the author; otherwise every
+/// contribution is refunded.
+pub fn outcome(raised: u64, goal: u64) -> FundraiserStatus {
+    if raised >= goal {
+        FundraiserStatus::Releasing
+    } else {
+        FundraiserStatus::Refunding
+    }
+}
+
+// ============================================================================
+// FUNDRAISER TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escrow_subaccounts_are_per_post() {
+        assert_eq!(escrow_subaccount(PostId(1)), escrow_subaccount(PostId(1)));
+        assert_ne!(escrow_subaccount(PostId(1)), escrow_subaccount(PostId(2)));
+    }
+
+    #[test]
+    fn test_transfer_memos_are_per_transfer() {
+        let contributor = UserId(candid::Principal::anonymous());
+        let payout = transfer_memo(PostId(1), None);
+        assert_eq!(payout.len(), 32);
+        assert_eq!(payout, transfer_memo(PostId(1), None));
+        assert_ne!(payout, transfer_memo(PostId(2), None));
+        assert_ne!(payout, transfer_memo(PostId(1), Some(contributor)));
+        assert_ne!(
+            transfer_memo(PostId(1), Some(contributor)),
+            transfer_memo(PostId(2), Some(contributor))
+        );
+    }
+
+    #[test]
+    fn test_outcome_depends_on_goal() {
+        assert_eq!(outcome(1_000, 1_000), FundraiserStatus::Releasing);
+        assert_eq!(outcome(999, 1_000), FundraiserStatus::Refunding);
+        assert_eq!(outcome(0, 1), FundraiserStatus::Refunding);
+    }
+}
@@ -0,0 +1,99 @@
+use crate::types::{FeedPage, PostId, UserId, UserProfile};
+use candid::{CandidType, Deserialize};
+use std::collections::BTreeMap;
+use std::ops::Range;
+
+/// Responses for anonymous viewers, precomputed by a timer
+///
+/// Queries cannot record which profiles are read, so the most followed
+/// profiles stand in for the most requested ones.
+#[derive(CandidType, Deserialize, Clone, Debug, Default)]
+pub struct HotCache {
+    /// Leading page of the explore feed
+    explore: Option<FeedPage>,
+
+    /// Anonymous view of popular profiles
+    profiles: BTreeMap<UserId, UserProfile>,
+}
+
+impl HotCache {
+    /// Cache holding the first explore page for anonymous viewers
+    pub fn new(explore: FeedPage, profiles: Vec<UserProfile>) -> Self {
+        Self {
+            explore: Some(explore),
+            profiles: profiles
+                .into_iter()
+                .map(|profile| (profile.id, profile))
+                .collect(),
+        }
+    }
+
+    /// A page of the explore feed, if the cache reaches that far
+    ///
+    /// Entries for which `still_listed` fails (e.g. posts gated since the
+    /// refresh) are left out.
+    pub fn explore_page(
+        &self,
+        offset: usize,
+        limit: usize,
+        still_listed: impl Fn(PostId) -> bool,
+    ) -> Option<FeedPage> {
+        let explore = self.explore.as_ref()?;
+        let cached = explore.posts.len();
+        let range = cached_range(cached, !explore.has_more, offset, limit)?;
+        let has_more = range.end < cached;
+        Some(FeedPage {
+            next_cursor: has_more.then_some(range.end as u64),
+            posts: explore.posts[range]
+                .iter()
+                .filter(|entry| still_listed(entry.post.id))
+                .cloned()
+                .collect(),
+            total: explore.total,
+            has_more,
+        })
+    }
+
+    /// A cached profile, if it was not edited since the refresh
+    pub fn profile(&self, current: &UserProfile) -> Option<&UserProfile> {
+        self.profiles
+            .get(&current.id)
+            .filter(|cached| cached.version == current.version)
+    }
+}
+
+/// Indices of a page within `cached` leading entries, if the cache covers it
+///
+/// A complete cache covers every page; pages past its end are empty. An
+/// incomplete one must hold an entry past the page to tell whether more follow.
+fn cached_range(
+    cached: usize,
+    complete: bool,
+    offset: usize,
+    limit: usize,
+) -> Option<Range<usize>> {
+    let end = offset.saturating_add(limit);
+    if end >= cached && !complete {
+        return None;
+    }
+    Some(offset.min(cached)..end.min(cached))
+}
+
+// ============================================================================
+// HOT CACHE TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cached_range() {
+        assert_eq!(cached_range(100, false, 20, 20), Some(20..40));
+        assert_eq!(cached_range(100, false, 90, 20), None);
+        assert_eq!(cached_range(100, false, 80, 20), None);
+        assert_eq!(cached_range(30, true, 20, 20), Some(20..30));
+        assert_eq!(cached_range(30, true, 50, 20), Some(30..30));
+        assert_eq!(cached_range(0, false, 0, 20), None);
+    }
+}
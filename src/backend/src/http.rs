@@ -0,0 +1,695 @@
+use candid::{CandidType, Deserialize, Principal};
+use std::collections::BTreeSet;
+
+/// Maximum number of items included in a syndication feed
+pub const FEED_ITEMS_LIMIT: usize = 20;
+
+/// Maximum number of recent posts scanned when building a tag feed
+pub const TAG_FEED_SCAN_LIMIT: usize = 5_000;
+
+/// Maximum characters of post content used as a feed item title
+const FEED_TITLE_LENGTH: usize = 80;
+
+/// Maximum number of URLs listed in the sitemap
+pub const SITEMAP_URL_LIMIT: usize = 5_000;
+
+/// Maximum characters of text used as a link-preview description
+const PAGE_DESCRIPTION_LENGTH: usize = 200;
+
+// ============================================================================
+// HTTP GATEWAY TYPES
+// ============================================================================
+
+/// Request received through the HTTP gateway
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct HttpRequest {
+    pub method: String,
+    pub url: String,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+/// Response returned to the HTTP gateway
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct HttpResponse {
+    pub status_code: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+    pub upgrade: Option<bool>,
+}
+
+impl HttpRequest {
+    /// Request path without the query string
+    pub fn path(&self) -> &str {
+        self.url.split(['?', '#']).next().unwrap_or("/")
+    }
+
+    /// Decoded value of the first query-string parameter named `name`
+    pub fn query_param(&self, name: &str) -> Option<String> {
+        let (_, query) = self.url.split_once('?')?;
+        let query = query.split('#').next().unwrap_or_default();
+        query
+            .split('&')
+            .filter_map(|pair| pair.split_once('='))
+            .find(|(key, _)| *key == name)
+            .map(|(_, value)| percent_decode(&value.replace('+', " ")))
+    }
+
+    /// Value of the first header matching `name` (case-insensitive)
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Public base URL (`https://host`) derived from the Host header
+    ///
+    /// Falls back to the canister's raw icp0.io domain when the header is
+    /// missing or contains anything but host characters.
+    pub fn base_url(&self, canister_id: Principal) -> String {
+        let host = self.header("host").filter(|host| {
+            !host.is_empty()
+                && host
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | ':'))
+        });
+
+        match host {
+            Some(host) => format!("https://{host}"),
+            None => format!("https://{}.raw.icp0.io", canister_id.to_text()),
+        }
+    }
+}
+
+impl HttpResponse {
+    /// 200 response with the given content type
+    pub fn ok(content_type: &str, body: Vec<u8>) -> Self {
+        Self {
+            status_code: 200,
+            headers: vec![
+                ("Content-Type".to_string(), content_type.to_string()),
+                (
+                    "Cache-Control".to_string(),
+                    "public, max-age=300".to_string(),
+                ),
+            ],
+            body,
+            upgrade: None,
+        }
+    }
+
+    /// 200 response for content that never changes at its URL (uploaded assets)
+    pub fn immutable(content_type: &str, body: Vec<u8>) -> Self {
+        Self {
+            status_code: 200,
+            headers: vec![
+                ("Content-Type".to_string(), content_type.to_string()),
+                (
+                    "Cache-Control".to_string(),
+                    "public, max-age=31536000, immutable".to_string(),
+                ),
+                ("X-Content-Type-Options".to_string(), "nosniff".to_string()),
+            ],
+            body,
+            upgrade: None,
+        }
+    }
+
+    /// Plain-text error response
+    pub fn error(status_code: u16, message: &str) -> Self {
+        Self {
+            status_code,
+            headers: vec![(
+                "Content-Type".to_string(),
+                "text/plain; charset=utf-8".to_string(),
+            )],
+            body: message.as_bytes().to_vec(),
+            upgrade: None,
+        }
+    }
+
+    pub fn not_found() -> Self {
+        Self::error(404, "Not found")
+    }
+
+    /// Empty response asking the gateway to retry the request as an update call
+    pub fn upgrade() -> Self {
+        Self {
+            status_code: 200,
+            headers: Vec::new(),
+            body: Vec::new(),
+            upgrade: Some(true),
+        }
+    }
+}
+
+// ============================================================================
+// ROUTING
+// ============================================================================
+
+/// Resources served over `http_request`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Route {
+    /// `/users/{handle}/feed.xml`
+    UserFeed(String),
+
+    /// `/tags/{tag}/feed.xml`
+    TagFeed(String),
+
+    /// `/users/{handle}` - ActivityPub actor, or an HTML profile page
+    Actor(String),
+
+    /// `/users/{handle}/outbox`
+    Outbox(String),
+
+    /// `/users/{handle}/followers`
+    Followers(String),
+
+    /// `/users/{handle}/inbox`
+    Inbox(String),
+
+    /// `/posts/{id}` (or `/post/{id}`) - ActivityPub note, or an HTML page
+    /// with link-preview tags for other clients
+    Post(u64),
+
+    /// `/avatars/{id}` - uploaded avatar image
+    Avatar(u64),
+
+    /// `/audio/{id}` - voice note of a public post
+    Audio(u64),
+
+    /// `/.well-known/webfinger`
+    WebFinger,
+
+    /// `/sitemap.xml` - public profiles and posts for search engines
+    Sitemap,
+
+    NotFound,
+}
+
+/// Maps a request path to a route
+pub fn route(path: &str) -> Route {
+    let segments: Vec<String> = path
+        .trim_matches('/')
+        .split('/')
+        .map(percent_decode)
+        .collect();
+
+    match segments
+        .iter()
+        .map(String::as_str)
+        .collect::<Vec<_>>()
+        .as_slice()
+    {
+        ["users", handle, "feed.xml"] if !handle.is_empty() => Route::UserFeed(handle.to_string()),
+        ["tags", tag, "feed.xml"] if !tag.is_empty() => Route::TagFeed(tag.to_lowercase()),
+        ["users", handle] if !handle.is_empty() => Route::Actor(handle.to_string()),
+        ["users", handle, "outbox"] if !handle.is_empty() => Route::Outbox(handle.to_string()),
+        ["users", handle, "followers"] if !handle.is_empty() => {
+            Route::Followers(handle.to_string())
+        }
+        ["users", handle, "inbox"] if !handle.is_empty() => Route::Inbox(handle.to_string()),
+        ["posts" | "post", id] => id.parse().map(Route::Post).unwrap_or(Route::NotFound),
+        ["avatars", id] => id.parse().map(Route::Avatar).unwrap_or(Route::NotFound),
+        ["audio", id] => id.parse().map(Route::Audio).unwrap_or(Route::NotFound),
+        [".well-known", "webfinger"] => Route::WebFinger,
+        ["sitemap.xml"] => Route::Sitemap,
+        _ => Route::NotFound,
+    }
+}
+
+/// Decodes `%XX` escapes in a path segment (invalid escapes are kept verbatim)
+fn percent_decode(segment: &str) -> String {
+    let bytes = segment.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let escaped = (bytes[i] == b'%')
+            .then(|| bytes.get(i + 1..i + 3))
+            .flatten()
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+
+        match escaped {
+            Some(byte) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+// ============================================================================
+// SYNDICATION FEEDS
+// ============================================================================
+
+/// Channel-level metadata of an RSS feed
+pub struct FeedChannel {
+    pub title: String,
+    pub link: String,
+    pub self_link: String,
+    pub description: String,
+}
+
+/// Single entry of an RSS feed
+pub struct FeedItem {
+    pub link: String,
+    pub content: String,
+    pub published_at: u64,
+}
+
+/// Renders an RSS 2.0 document
+pub fn render_rss(channel: &FeedChannel, items: &[FeedItem]) -> String {
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str("<rss version=\"2.0\" xmlns:atom=\"http://www.w3.org/2005/Atom\">\n<channel>\n");
+    xml.push_str(&format!("<title>{}</title>\n", xml_escape(&channel.title)));
+    xml.push_str(&format!("<link>{}</link>\n", xml_escape(&channel.link)));
+    xml.push_str(&format!(
+        "<description>{}</description>\n",
+        xml_escape(&channel.description)
+    ));
+    xml.push_str(&format!(
+        "<atom:link href=\"{}\" rel=\"self\" type=\"application/rss+xml\"/>\n",
+        xml_escape(&channel.self_link)
+    ));
+    if let Some(latest) = items.iter().map(|item| item.published_at).max() {
+        xml.push_str(&format!(
+            "<lastBuildDate>{}</lastBuildDate>\n",
+            rfc2822_date(latest)
+        ));
+    }
+
+    for item in items {
+        let title: String = item.content.chars().take(FEED_TITLE_LENGTH).collect();
+        xml.push_str("<item>\n");
+        xml.push_str(&format!("<title>{}</title>\n", xml_escape(&title)));
+        xml.push_str(&format!("<link>{}</link>\n", xml_escape(&item.link)));
+        xml.push_str(&format!(
+            "<guid isPermaLink=\"true\">{}</guid>\n",
+            xml_escape(&item.link)
+        ));
+        xml.push_str(&format!(
+            "<pubDate>{}</pubDate>\n",
+            rfc2822_date(item.published_at)
+        ));
+        xml.push_str(&format!(
+            "<description>{}</description>\n",
+            xml_escape(&item.content)
+        ));
+        xml.push_str("</item>\n");
+    }
+
+    xml.push_str("</channel>\n</rss>\n");
+    xml
+}
+
+/// Escapes text for use in XML element content and attribute values
+pub fn xml_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            // Control characters are not allowed in XML 1.0
+            c if c.is_control() && !matches!(c, '\n' | '\r' | '\t') => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+// ============================================================================
+// HTML PAGES AND SITEMAP
+// ============================================================================
+
+/// Link-preview metadata of a server-rendered page
+pub struct PageMeta {
+    pub title: String,
+    pub description: String,
+
+    /// Canonical URL of the page
+    pub url: String,
+
+    /// OpenGraph type: `article` for posts, `profile` for users
+    pub kind: &'static str,
+
+    pub image: Option<String>,
+    pub published_at: Option<u64>,
+
+    /// Asks search engines not to index the page
+    pub noindex: bool,
+}
+
+/// Single URL of a sitemap
+pub struct SitemapEntry {
+    pub url: String,
+    pub modified_at: u64,
+}
+
+/// Renders a minimal HTML page with OpenGraph and Twitter card tags
+///
+/// `body` is shown as preformatted text; every value is escaped.
+pub fn render_html_page(meta: &PageMeta, body: &str) -> String {
+    let mut description: String = meta
+        .description
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+    if description.chars().count() > PAGE_DESCRIPTION_LENGTH {
+        description = description
+            .chars()
+            .take(PAGE_DESCRIPTION_LENGTH - 1)
+            .collect::<String>()
+            + "…";
+    }
+
+    let mut tags = vec![
+        format!("<title>{}</title>", xml_escape(&meta.title)),
+        format!(
+            "<link rel=\"canonical\" href=\"{}\">",
+            xml_escape(&meta.url)
+        ),
+        meta_tag("name", "description", &description),
+        meta_tag("property", "og:site_name", "deCentra"),
+        meta_tag("property", "og:type", meta.kind),
+        meta_tag("property", "og:title", &meta.title),
+        meta_tag("property", "og:description", &description),
+        meta_tag("property", "og:url", &meta.url),
+        meta_tag("name", "twitter:title", &meta.title),
+        meta_tag("name", "twitter:description", &description),
+    ];
+    match &meta.image {
+        Some(image) => {
+            tags.push(meta_tag("property", "og:image", image));
+            tags.push(meta_tag("name", "twitter:card", "summary_large_image"));
+            tags.push(meta_tag("name", "twitter:image", image));
+        }
+        None => tags.push(meta_tag("name", "twitter:card", "summary")),
+    }
+    if let Some(published_at) = meta.published_at {
+        tags.push(meta_tag(
+            "property",
+            "article:published_time",
+            &iso8601_date(published_at),
+        ));
+    }
+    if meta.noindex {
+        tags.push(meta_tag("name", "robots", "noindex"));
+    }
+
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n\
+         <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n\
+         {}\n</head>\n<body>\n<main>\n<h1>{}</h1>\n\
+         <p style=\"white-space: pre-wrap\">{}</p>\n</main>\n</body>\n</html>\n",
+        tags.join("\n"),
+        xml_escape(&meta.title),
+        xml_escape(body)
+    )
+}
+
+fn meta_tag(attribute: &str, key: &str, content: &str) -> String {
+    format!(
+        "<meta {attribute}=\"{key}\" content=\"{}\">",
+        xml_escape(content)
+    )
+}
+
+/// Renders a sitemaps.org URL set
+pub fn render_sitemap(entries: &[SitemapEntry]) -> String {
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str("<urlset xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\">\n");
+    for entry in entries {
+        xml.push_str(&format!(
+            "<url><loc>{}</loc><lastmod>{}</lastmod></url>\n",
+            xml_escape(&entry.url),
+            iso8601_date(entry.modified_at)
+        ));
+    }
+    xml.push_str("</urlset>\n");
+    xml
+}
+
+const WEEKDAYS: [&str; 7] = ["Thu", "Fri", "Sat", "Sun", "Mon", "Tue", "Wed"];
+const MONTHS: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+
+/// Formats a nanosecond timestamp as an RFC 2822 date (always UTC)
+pub fn rfc2822_date(timestamp_ns: u64) -> String {
+    format_date(timestamp_ns, "+0000")
+}
+
+/// Formats a nanosecond timestamp as an HTTP date (RFC 7231 IMF-fixdate)
+pub fn http_date(timestamp_ns: u64) -> String {
+    format_date(timestamp_ns, "GMT")
+}
+
+/// Formats a nanosecond timestamp as an ISO 8601 UTC date-time
+pub fn iso8601_date(timestamp_ns: u64) -> String {
+    let secs = timestamp_ns / 1_000_000_000;
+    let (year, month, day) = civil_from_days(secs / 86_400);
+    let seconds_of_day = secs % 86_400;
+
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}Z",
+        seconds_of_day / 3_600,
+        (seconds_of_day % 3_600) / 60,
+        seconds_of_day % 60
+    )
+}
+
+/// Parses an HTTP date (`Sun, 06 Nov 1994 08:49:37 GMT`) into nanoseconds
+pub fn parse_http_date(date: &str) -> Option<u64> {
+    let (_, rest) = date.trim().split_once(", ")?;
+    let parts: Vec<&str> = rest.split(' ').collect();
+    let [day, month, year, time, "GMT"] = parts.as_slice() else {
+        return None;
+    };
+
+    let day: u64 = day.parse().ok()?;
+    let month = MONTHS.iter().position(|name| name == month)? as u64 + 1;
+    let year: u64 = year.parse().ok()?;
+    let time: Vec<u64> = time
+        .split(':')
+        .map(|part| part.parse().ok())
+        .collect::<Option<_>>()?;
+    let [hours, minutes, seconds] = time.as_slice() else {
+        return None;
+    };
+
+    if !(1..=31).contains(&day) || year < 1970 || *hours > 23 || *minutes > 59 || *seconds > 60 {
+        return None;
+    }
+
+    let secs = days_from_civil(year, month, day) * 86_400 + hours * 3_600 + minutes * 60 + seconds;
+    secs.checked_mul(1_000_000_000)
+}
+
+fn format_date(timestamp_ns: u64, zone: &str) -> String {
+    let secs = timestamp_ns / 1_000_000_000;
+    let days = secs / 86_400;
+    let (year, month, day) = civil_from_days(days);
+    let seconds_of_day = secs % 86_400;
+
+    format!(
+        "{}, {:02} {} {} {:02}:{:02}:{:02} {zone}",
+        WEEKDAYS[(days % 7) as usize],
+        day,
+        MONTHS[(month - 1) as usize],
+        year,
+        seconds_of_day / 3_600,
+        (seconds_of_day % 3_600) / 60,
+        seconds_of_day % 60
+    )
+}
+
+/// Converts days since the Unix epoch to a (year, month, day) civil date
+pub fn civil_from_days(days: u64) -> (u64, u64, u64) {
+    // Howard Hinnant's civil_from_days, restricted to dates after 1970
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + u64::from(month <= 2);
+    (year, month, day)
+}
+
+/// Converts a (year, month, day) civil date to days since the Unix epoch
+pub fn days_from_civil(year: u64, month: u64, day: u64) -> u64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year / 400;
+    let yoe = year - era * 400;
+    let mp = if month > 2 { month - 3 } else { month + 9 };
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    (era * 146_097 + doe).saturating_sub(719_468)
+}
+
+/// Extracts lowercase hashtags (`#word`) from post content
+pub fn extract_hashtags(content: &str) -> BTreeSet<String> {
+    content
+        .split(|c: char| c.is_whitespace())
+        .filter_map(|word| word.strip_prefix('#'))
+        .map(|tag| {
+            tag.chars()
+                .take_while(|c| c.is_alphanumeric() || *c == '_')
+                .collect::<String>()
+                .to_lowercase()
+        })
+        .filter(|tag| !tag.is_empty())
+        .collect()
+}
+
+// ============================================================================
+// HTTP TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_route() {
+        assert_eq!(
+            route("/users/alice/feed.xml"),
+            Route::UserFeed("alice".to_string())
+        );
+        assert_eq!(
+            route("/tags/Caf%C3%A9/feed.xml"),
+            Route::TagFeed("café".to_string())
+        );
+        assert_eq!(route("/users/alice"), Route::Actor("alice".to_string()));
+        assert_eq!(
+            route("/users/alice/inbox"),
+            Route::Inbox("alice".to_string())
+        );
+        assert_eq!(route("/posts/42"), Route::Post(42));
+        assert_eq!(route("/post/42"), Route::Post(42));
+        assert_eq!(route("/sitemap.xml"), Route::Sitemap);
+        assert_eq!(route("/posts/latest"), Route::NotFound);
+        assert_eq!(route("/avatars/7"), Route::Avatar(7));
+        assert_eq!(route("/audio/9"), Route::Audio(9));
+        assert_eq!(route("/users//feed.xml"), Route::NotFound);
+    }
+
+    #[test]
+    fn test_request_helpers() {
+        let request = HttpRequest {
+            method: "GET".to_string(),
+            url: "/users/alice/feed.xml?format=rss".to_string(),
+            headers: vec![("Host".to_string(), "decentra.example".to_string())],
+            body: Vec::new(),
+        };
+        assert_eq!(request.path(), "/users/alice/feed.xml");
+        assert_eq!(request.query_param("format").as_deref(), Some("rss"));
+        assert_eq!(request.query_param("missing"), None);
+        assert_eq!(
+            request.base_url(Principal::anonymous()),
+            "https://decentra.example"
+        );
+    }
+
+    #[test]
+    fn test_rss_escaping_and_dates() {
+        assert_eq!(
+            xml_escape("<b>\"Tom\" & 'Jerry'</b>"),
+            "&lt;b&gt;&quot;Tom&quot; &amp; &apos;Jerry&apos;&lt;/b&gt;"
+        );
+        assert_eq!(rfc2822_date(0), "Thu, 01 Jan 1970 00:00:00 +0000");
+        assert_eq!(http_date(0), "Thu, 01 Jan 1970 00:00:00 GMT");
+        assert_eq!(
+            iso8601_date(1_709_251_200_000_000_000),
+            "2024-03-01T00:00:00Z"
+        );
+        assert_eq!(
+            parse_http_date("Fri, 01 Mar 2024 00:00:00 GMT"),
+            Some(1_709_251_200_000_000_000)
+        );
+        assert_eq!(
+            parse_http_date(&http_date(1_760_000_123_000_000_000)),
+            Some(1_760_000_123_000_000_000)
+        );
+        assert_eq!(parse_http_date("yesterday"), None);
+        assert_eq!(
+            rfc2822_date(1_709_251_200_000_000_000),
+            "Fri, 01 Mar 2024 00:00:00 +0000"
+        );
+
+        let channel = FeedChannel {
+            title: "alice".to_string(),
+            link: "https://decentra.example/users/alice".to_string(),
+            self_link: "https://decentra.example/users/alice/feed.xml".to_string(),
+            description: "Posts by alice".to_string(),
+        };
+        let items = [FeedItem {
+            link: "https://decentra.example/posts/1".to_string(),
+            content: "<script>alert(1)</script>".to_string(),
+            published_at: 0,
+        }];
+        let xml = render_rss(&channel, &items);
+        assert!(xml.contains("&lt;script&gt;"));
+        assert!(!xml.contains("<script>"));
+    }
+
+    #[test]
+    fn test_html_page_and_sitemap() {
+        let meta = PageMeta {
+            title: "Alice (@alice) on deCentra".to_string(),
+            description: format!("\"Hello\"\n{}", "x".repeat(300)),
+            url: "https://decentra.example/posts/1".to_string(),
+            kind: "article",
+            image: Some("https://cdn.example/a.png".to_string()),
+            published_at: Some(0),
+            noindex: false,
+        };
+        let html = render_html_page(&meta, "<script>alert(1)</script>");
+        assert!(
+            html.contains("<meta property=\"og:title\" content=\"Alice (@alice) on deCentra\">")
+        );
+        assert!(html.contains("content=\"&quot;Hello&quot; xxx"));
+        assert!(html.contains("<meta name=\"twitter:card\" content=\"summary_large_image\">"));
+        assert!(html.contains("1970-01-01T00:00:00Z"));
+        assert!(html.contains("&lt;script&gt;"));
+        assert!(!html.contains("<script>"));
+        assert!(!html.contains("noindex"));
+        let description = html
+            .split("<meta name=\"description\" content=\"")
+            .nth(1)
+            .and_then(|rest| rest.split('"').next())
+            .unwrap();
+        assert!(description.ends_with('…'));
+
+        let sitemap = render_sitemap(&[SitemapEntry {
+            url: "https://decentra.example/users/alice".to_string(),
+            modified_at: 1_709_251_200_000_000_000,
+        }]);
+        assert!(sitemap.contains(
+            "<url><loc>https://decentra.example/users/alice</loc><lastmod>2024-03-01T00:00:00Z</lastmod></url>"
+        ));
+    }
+
+    #[test]
+    fn test_extract_hashtags() {
+        let tags = extract_hashtags("Free press #FreeSpeech, #icp and #ICP! not#tag #");
+        assert_eq!(
+            tags.into_iter().collect::<Vec<_>>(),
+            vec!["freespeech".to_string(), "icp".to_string()]
+        );
+    }
+}
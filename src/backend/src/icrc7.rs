@@ -0,0 +1,84 @@
+use crate::ledger::Account;
+use crate::types::NftMintConfig;
+use candid::{CandidType, Deserialize, Nat, Principal};
+
+// ============================================================================
+// ICRC-7 LEDGER INTERFACE
+// ============================================================================
+
+/// Looks up the owners of `token_ids` in an ICRC-7 collection
+///
+/// # Returns
+/// One entry per token id, in order; `None` for tokens that do not exist
+pub async fn owners_of(
+    collection: Principal,
+    token_ids: Vec<Nat>,
+) -> Result<Vec<Option<Account>>, String> {
+    let expected = token_ids.len();
+    let (owners,): (Vec<Option<Account>>,) =
+        ic_cdk::call(collection, "icrc7_owner_of", (token_ids,))
+            .await
+            .map_err(|(code, message)| {
+                format!("ICRC-7 collection call failed: {code:?} {message}")
+            })?;
+
+    if owners.len() != expected {
+        return Err("ICRC-7 collection returned a malformed owner list".to_string());
+    }
+    Ok(owners)
+}
+
+/// Mint request sent to the configured collection for a post
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct PostMintRequest {
+    /// Account receiving the token (the post author)
+    pub to: Account,
+    pub post_id: u64,
+    pub content_hash: Vec<u8>,
+
+    /// Public URL of the post
+    pub uri: String,
+    pub created_at: u64,
+}
+
+/// Mints a post on the configured collection
+///
+/// # Returns
+/// The id of the new token
+pub async fn mint_post(config: &NftMintConfig, request: PostMintRequest) -> Result<Nat, String> {
+    let (result,): (Result<Nat, String>,) =
+        ic_cdk::call(config.collection, &config.method, (request,))
+            .await
+            .map_err(|(code, message)| {
+                format!("ICRC-7 collection call failed: {code:?} {message}")
+            })?;
+    result.map_err(|error| format!("Minting failed: {error}"))
+}
+
+/// Whether `principal` holds the token in any of its subaccounts
+pub fn is_owned_by(owner: &Option<Account>, principal: Principal) -> bool {
+    owner
+        .as_ref()
+        .is_some_and(|account| account.owner == principal)
+}
+
+// ============================================================================
+// ICRC-7 TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_owned_by() {
+        let holder = Principal::from_slice(&[1; 29]);
+        let account = Some(Account {
+            owner: holder,
+            subaccount: Some(vec![0; 32]),
+        });
+        assert!(is_owned_by(&account, holder));
+        assert!(!is_owned_by(&account, Principal::anonymous()));
+        assert!(!is_owned_by(&None, holder));
+    }
+}
@@ -0,0 +1,106 @@
+use crate::types::{JournalEventKind, MAX_JOURNAL_ENTRIES};
+use candid::{CandidType, Deserialize};
+use std::collections::VecDeque;
+
+/// Journal entry as stored; public data is looked up when the entry is read
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct JournalEntry {
+    pub kind: JournalEventKind,
+    pub timestamp: u64,
+}
+
+/// Append-only log of changes to public data, numbered in order
+///
+/// Sequence numbers are never reused; only the latest `MAX_JOURNAL_ENTRIES`
+/// entries are held.
+#[derive(CandidType, Deserialize, Clone, Debug, Default)]
+pub struct Journal {
+    entries: VecDeque<JournalEntry>,
+
+    /// Sequence number of the first entry held
+    first_seq: u64,
+}
+
+impl Journal {
+    /// Appends an entry, dropping the oldest beyond `MAX_JOURNAL_ENTRIES`
+    pub fn push(&mut self, kind: JournalEventKind, timestamp: u64) {
+        self.entries.push_back(JournalEntry { kind, timestamp });
+        if self.entries.len() > MAX_JOURNAL_ENTRIES {
+            self.entries.pop_front();
+            self.first_seq += 1;
+        }
+    }
+
+    /// Sequence number of the oldest entry still held
+    pub fn first_seq(&self) -> u64 {
+        self.first_seq
+    }
+
+    /// Sequence number the next entry will get
+    pub fn next_seq(&self) -> u64 {
+        self.first_seq + self.entries.len() as u64
+    }
+
+    /// Up to `limit` entries with their sequence numbers, from `seq` on (or
+    /// from the oldest held, if `seq` was already dropped)
+    pub fn since(&self, seq: u64, limit: usize) -> impl Iterator<Item = (u64, &JournalEntry)> {
+        let start = seq.max(self.first_seq);
+        self.entries
+            .iter()
+            .skip(start.saturating_sub(self.first_seq) as usize)
+            .take(limit)
+            .zip(start..)
+            .map(|(entry, seq)| (seq, entry))
+    }
+}
+
+// ============================================================================
+// EVENT JOURNAL TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::PostId;
+
+    fn seqs(journal: &Journal, seq: u64, limit: usize) -> Vec<(u64, u64)> {
+        journal
+            .since(seq, limit)
+            .map(|(seq, entry)| (seq, entry.timestamp))
+            .collect()
+    }
+
+    #[test]
+    fn test_journal_since() {
+        let mut journal = Journal::default();
+        for timestamp in 0..5 {
+            journal.push(
+                JournalEventKind::PostDeleted {
+                    post_id: PostId(timestamp),
+                },
+                timestamp,
+            );
+        }
+        assert_eq!(journal.next_seq(), 5);
+        assert_eq!(seqs(&journal, 3, 10), vec![(3, 3), (4, 4)]);
+        assert_eq!(seqs(&journal, 0, 2), vec![(0, 0), (1, 1)]);
+        assert!(seqs(&journal, 5, 10).is_empty());
+    }
+
+    #[test]
+    fn test_journal_drops_oldest() {
+        let mut journal = Journal::default();
+        for timestamp in 0..=MAX_JOURNAL_ENTRIES as u64 {
+            journal.push(
+                JournalEventKind::PostDeleted {
+                    post_id: PostId(timestamp),
+                },
+                timestamp,
+            );
+        }
+        assert_eq!(journal.first_seq(), 1);
+        assert_eq!(journal.next_seq(), MAX_JOURNAL_ENTRIES as u64 + 1);
+        // Dropped sequence numbers resume at the oldest entry held
+        assert_eq!(seqs(&journal, 0, 1), vec![(1, 1)]);
+    }
+}
@@ -0,0 +1,106 @@
+/// Script ranges whose writing system identifies a single language
+///
+/// Latin and Cyrillic are shared by too many languages to guess from the
+/// script alone, so posts written in them stay untagged unless declared.
+const SCRIPT_LANGUAGES: &[(char, char, &str)] = &[
+    ('\u{0370}', '\u{03FF}', "el"),
+    ('\u{0530}', '\u{058F}', "hy"),
+    ('\u{0590}', '\u{05FF}', "he"),
+    ('\u{0600}', '\u{06FF}', "ar"),
+    ('\u{0900}', '\u{097F}', "hi"),
+    ('\u{0E00}', '\u{0E7F}', "th"),
+    ('\u{10A0}', '\u{10FF}', "ka"),
+    ('\u{3040}', '\u{30FF}', "ja"),
+    ('\u{AC00}', '\u{D7AF}', "ko"),
+    ('\u{1100}', '\u{11FF}', "ko"),
+    ('\u{4E00}', '\u{9FFF}', "zh"),
+];
+
+/// Guesses a post's language from the dominant writing system
+///
+/// Returns a language tag only when more than half of the letters belong to
+/// a script used by a single language. Any kana marks Japanese, since
+/// Japanese text mixes kana with Han characters.
+pub fn detect_language(text: &str) -> Option<&'static str> {
+    let mut letters = 0usize;
+    let mut counts: Vec<(&'static str, usize)> = Vec::new();
+
+    for c in text.chars().filter(|c| c.is_alphabetic()) {
+        letters += 1;
+        if let Some(&(_, _, lang)) = SCRIPT_LANGUAGES
+            .iter()
+            .find(|(start, end, _)| (*start..=*end).contains(&c))
+        {
+            match counts.iter_mut().find(|(code, _)| *code == lang) {
+                Some((_, count)) => *count += 1,
+                None => counts.push((lang, 1)),
+            }
+        }
+    }
+
+    let count_of = |lang: &str| {
+        counts
+            .iter()
+            .find(|(code, _)| *code == lang)
+            .map(|(_, count)| *count)
+            .unwrap_or(0)
+    };
+    if count_of("ja") > 0 && count_of("ja") + count_of("zh") > letters / 2 {
+        return Some("ja");
+    }
+
+    counts
+        .into_iter()
+        .max_by_key(|(_, count)| *count)
+        .filter(|(_, count)| *count > letters / 2)
+        .map(|(lang, _)| lang)
+}
+
+/// Whether a post language satisfies a viewer's preferred languages
+///
+/// Untagged posts and viewers without preferences always match; otherwise
+/// the primary subtags are compared (`pt-br` matches a `pt` preference).
+pub fn matches_preferences(post_language: Option<&str>, preferred: &[String]) -> bool {
+    let Some(language) = post_language else {
+        return true;
+    };
+    if preferred.is_empty() {
+        return true;
+    }
+
+    let primary = |tag: &str| tag.split('-').next().unwrap_or(tag).to_ascii_lowercase();
+    let post_primary = primary(language);
+    preferred.iter().any(|tag| primary(tag) == post_primary)
+}
+
+// ============================================================================
+// LANGUAGE TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_language() {
+        assert_eq!(detect_language("Hello world"), None);
+        assert_eq!(detect_language("Привет, мир"), None);
+        assert_eq!(detect_language("こんにちは世界"), Some("ja"));
+        assert_eq!(detect_language("你好世界"), Some("zh"));
+        assert_eq!(detect_language("안녕하세요 세계"), Some("ko"));
+        assert_eq!(detect_language("Γειά σου Κόσμε"), Some("el"));
+        assert_eq!(detect_language("مرحبا بالعالم"), Some("ar"));
+        assert_eq!(detect_language("Check out 東京 today"), None);
+        assert_eq!(detect_language("🎉🎉"), None);
+    }
+
+    #[test]
+    fn test_matches_preferences() {
+        let preferred = vec!["en".to_string(), "pt".to_string()];
+        assert!(matches_preferences(None, &preferred));
+        assert!(matches_preferences(Some("en"), &preferred));
+        assert!(matches_preferences(Some("pt-br"), &preferred));
+        assert!(!matches_preferences(Some("ja"), &preferred));
+        assert!(matches_preferences(Some("ja"), &[]));
+    }
+}
@@ -0,0 +1,182 @@
+use candid::{CandidType, Deserialize, Nat, Principal};
+
+// ============================================================================
+// ICRC-1 LEDGER INTERFACE
+// ============================================================================
+
+/// ICRC account: a principal plus an optional 32-byte subaccount
+#[derive(CandidType, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Account {
+    pub owner: Principal,
+    pub subaccount: Option<Vec<u8>>,
+}
+
+/// Argument of `icrc1_transfer`
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct TransferArg {
+    pub from_subaccount: Option<Vec<u8>>,
+    pub to: Account,
+    pub amount: Nat,
+    pub fee: Option<Nat>,
+    pub memo: Option<Vec<u8>>,
+    pub created_at_time: Option<u64>,
+}
+
+/// Error returned by `icrc1_transfer`
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub enum TransferError {
+    BadFee { expected_fee: Nat },
+    BadBurn { min_burn_amount: Nat },
+    InsufficientFunds { balance: Nat },
+    TooOld,
+    CreatedInFuture { ledger_time: u64 },
+    Duplicate { duplicate_of: Nat },
+    TemporarilyUnavailable,
+    GenericError { error_code: Nat, message: String },
+}
+
+/// Argument of `icrc2_transfer_from`
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct TransferFromArgs {
+    pub spender_subaccount: Option<Vec<u8>>,
+    pub from: Account,
+    pub to: Account,
+    pub amount: Nat,
+    pub fee: Option<Nat>,
+    pub memo: Option<Vec<u8>>,
+    pub created_at_time: Option<u64>,
+}
+
+/// Error returned by `icrc2_transfer_from`
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub enum TransferFromError {
+    BadFee { expected_fee: Nat },
+    BadBurn { min_burn_amount: Nat },
+    InsufficientFunds { balance: Nat },
+    InsufficientAllowance { allowance: Nat },
+    TooOld,
+    CreatedInFuture { ledger_time: u64 },
+    Duplicate { duplicate_of: Nat },
+    TemporarilyUnavailable,
+    GenericError { error_code: Nat, message: String },
+}
+
+/// Transfers `amount` from one of the canister's subaccounts
+///
+/// The ledger's default fee is charged to the sending subaccount.
+///
+/// # Returns
+/// The ledger block index of the transfer
+pub async fn transfer(
+    ledger: Principal,
+    from_subaccount: [u8; 32],
+    to: Account,
+    amount: u64,
+    memo: Option<Vec<u8>>,
+) -> Result<Nat, String> {
+    let arg = TransferArg {
+        from_subaccount: Some(from_subaccount.to_vec()),
+        to,
+        amount: Nat::from(amount),
+        fee: None,
+        memo,
+        created_at_time: None,
+    };
+    let (result,): (Result<Nat, TransferError>,) = ic_cdk::call(ledger, "icrc1_transfer", (arg,))
+        .await
+        .map_err(|(code, message)| format!("Ledger call failed: {code:?} {message}"))?;
+    result.map_err(|error| format!("Ledger transfer failed: {error:?}"))
+}
+
+/// Transfers `amount` from one of the canister's subaccounts at most once
+///
+/// Retries must pass the same `memo` and `created_at_time`, so the ledger
+/// rejects a repeat of a transfer it already executed as a duplicate; that
+/// counts as success. Ledgers only deduplicate within their transaction
+/// window (see LEDGER_DEDUP_WINDOW_SECONDS).
+///
+/// # Returns
+/// The ledger block index of the transfer
+pub async fn transfer_once(
+    ledger: Principal,
+    from_subaccount: [u8; 32],
+    to: Account,
+    amount: u64,
+    memo: Vec<u8>,
+    created_at_time: u64,
+) -> Result<Nat, String> {
+    let arg = TransferArg {
+        from_subaccount: Some(from_subaccount.to_vec()),
+        to,
+        amount: Nat::from(amount),
+        fee: None,
+        memo: Some(memo),
+        created_at_time: Some(created_at_time),
+    };
+    let (result,): (Result<Nat, TransferError>,) = ic_cdk::call(ledger, "icrc1_transfer", (arg,))
+        .await
+        .map_err(|(code, message)| format!("Ledger call failed: {code:?} {message}"))?;
+    match result {
+        Ok(block_index)
+        | Err(TransferError::Duplicate {
+            duplicate_of: block_index,
+        }) => Ok(block_index),
+        Err(error) => Err(format!("Ledger transfer failed: {error:?}")),
+    }
+}
+
+/// Charges `amount` to `payer`'s default account under an ICRC-2 approval
+///
+/// The payer must have approved this canister for `amount` plus the ledger fee.
+///
+/// # Returns
+/// The ledger block index of the transfer
+pub async fn transfer_from(
+    ledger: Principal,
+    payer: Principal,
+    to: Account,
+    amount: u64,
+    memo: Option<Vec<u8>>,
+) -> Result<Nat, String> {
+    let arg = TransferFromArgs {
+        spender_subaccount: None,
+        from: Account {
+            owner: payer,
+            subaccount: None,
+        },
+        to,
+        amount: Nat::from(amount),
+        fee: None,
+        memo,
+        created_at_time: None,
+    };
+    let (result,): (Result<Nat, TransferFromError>,) =
+        ic_cdk::call(ledger, "icrc2_transfer_from", (arg,))
+            .await
+            .map_err(|(code, message)| format!("Ledger call failed: {code:?} {message}"))?;
+    result.map_err(|error| format!("Payment failed: {error:?}"))
+}
+
+/// Balance of an account, saturated to `u64`
+pub async fn balance_of(ledger: Principal, account: Account) -> Result<u64, String> {
+    let (balance,): (Nat,) = ic_cdk::call(ledger, "icrc1_balance_of", (account,))
+        .await
+        .map_err(|(code, message)| format!("Ledger call failed: {code:?} {message}"))?;
+    Ok(u64::try_from(balance.0).unwrap_or(u64::MAX))
+}
+
+/// Fee the ledger charges per transfer, saturated to `u64`
+pub async fn fee(ledger: Principal) -> Result<u64, String> {
+    let (fee,): (Nat,) = ic_cdk::call(ledger, "icrc1_fee", ())
+        .await
+        .map_err(|(code, message)| format!("Ledger call failed: {code:?} {message}"))?;
+    Ok(u64::try_from(fee.0).unwrap_or(u64::MAX))
+}
+
+/// Account owned by this canister under `subaccount`
+pub fn canister_account(subaccount: [u8; 32]) -> Account {
+    Account {
+        owner: ic_cdk::id(),
+        subaccount: Some(subaccount.to_vec()),
+    }
+}
@@ -1,15 +1,53 @@
-use candid::{CandidType, Deserialize, Principal};
+use candid::{CandidType, Deserialize, Nat, Principal};
 use ic_cdk::api::time;
-use ic_cdk::{caller, query, update};
+use ic_cdk::{caller, init, inspect_message, post_upgrade, pre_upgrade, query, update};
+use std::borrow::Cow;
 use std::cell::RefCell;
-use std::collections::{BTreeMap, BTreeSet};
+use std::collections::{btree_map, BTreeMap, BTreeSet, VecDeque};
+use std::time::Duration;
 
+mod activitypub;
+mod archive;
+mod audio_store;
 mod auth;
+mod avatar_store;
+mod compression;
+mod creator_fund;
+mod cycles;
+mod deletion_log;
+mod earnings;
+mod entities;
+mod error_catalog;
 mod errors;
+mod feed_filter;
+mod fundraiser;
+mod hot_cache;
+mod http;
+mod icrc7;
+mod journal;
+mod language;
+mod ledger;
+mod link_safety;
+mod local_time;
+mod logging;
+mod permanence;
+mod points;
+mod polls;
+mod push;
+mod replica;
+mod rsa;
+mod token_gate;
+mod translation;
+mod transparency;
 mod types;
 mod validation;
+mod vetkd;
+mod webhook;
+mod word_filter;
 
 use auth::*;
+use errors::{ErrorCategory, SocialNetworkError};
+use http::{HttpRequest, HttpResponse, Route};
 use types::*;
 use validation::*;
 
@@ -24,6 +62,15 @@ pub struct SocialNetworkState {
     /// User profiles indexed by user ID
     pub users: BTreeMap<UserId, UserProfile>,
 
+    /// Index: lowercase handle -> user, for uniqueness checks and lookups
+    pub handle_index: BTreeMap<String, UserId>,
+
+    /// Vacated handles (lowercase) that temporarily redirect to their previous owner
+    pub handle_redirects: BTreeMap<String, HandleRedirect>,
+
+    /// Handle change history for each user (oldest first)
+    pub handle_history: BTreeMap<UserId, Vec<HandleChange>>,
+
     /// All posts indexed by post ID
     pub posts: BTreeMap<PostId, Post>,
 
@@ -62,6 +109,520 @@ pub struct SocialNetworkState {
 
     /// Index: who is followed by whom for efficient lookup
     pub followers_index: BTreeMap<UserId, BTreeSet<UserId>>,
+
+    /// Platform administrators (seeded with the installing principal)
+    pub admins: BTreeSet<UserId>,
+
+    /// Content moderators appointed by admins
+    pub moderators: BTreeSet<UserId>,
+
+    /// Verified organizations and journalists appointed by admins to label posts
+    pub fact_checkers: BTreeSet<UserId>,
+
+    /// Verification applications indexed by request ID
+    pub verification_requests: BTreeMap<u64, VerificationRequest>,
+
+    /// Next available verification request ID
+    pub next_verification_request_id: u64,
+
+    /// Append-only log of privileged actions
+    pub audit_log: Vec<AuditLogEntry>,
+
+    /// Organization team members: org account -> member -> membership
+    pub org_members: BTreeMap<UserId, BTreeMap<UserId, OrgMember>>,
+
+    /// Custom emoji per organization: org account -> shortcode -> emoji
+    pub custom_emoji: BTreeMap<UserId, BTreeMap<String, CustomEmoji>>,
+
+    /// Posting delegations indexed by (owner, delegate)
+    pub posting_delegations: BTreeMap<(UserId, UserId), PostingDelegation>,
+
+    /// Drafts awaiting owner approval, indexed by draft ID
+    pub drafts: BTreeMap<u64, Draft>,
+
+    /// Capability grants indexed by (owner, grantee)
+    pub capability_grants: BTreeMap<(UserId, UserId), CapabilityGrant>,
+
+    /// Next available draft ID
+    pub next_draft_id: u64,
+
+    /// Journalists accepting whistleblower submissions
+    pub whistleblower_inbox_journalists: BTreeSet<UserId>,
+
+    /// Per-journalist secure inboxes: journalist -> submission ID -> submission
+    pub whistleblower_inboxes: BTreeMap<UserId, BTreeMap<u64, WhistleblowerSubmission>>,
+
+    /// Next available whistleblower submission ID
+    pub next_submission_id: u64,
+
+    /// Direct message conversations indexed by conversation ID
+    pub conversations: BTreeMap<u64, Conversation>,
+
+    /// Index: participant pair (ascending) -> conversation ID
+    pub conversation_index: BTreeMap<(UserId, UserId), u64>,
+
+    /// Index: user -> conversations they participate in
+    pub user_conversations: BTreeMap<UserId, BTreeSet<u64>>,
+
+    /// Messages for each conversation in send order
+    pub conversation_messages: BTreeMap<u64, Vec<DirectMessage>>,
+
+    /// Next available conversation ID
+    pub next_conversation_id: u64,
+
+    /// Next available direct message ID
+    pub next_message_id: u64,
+
+    /// Last message ID each participant has read: (conversation, user) -> message ID
+    pub conversation_read_markers: BTreeMap<(u64, UserId), u64>,
+
+    /// Group conversation members and roles: conversation -> member -> membership
+    pub group_members: BTreeMap<u64, BTreeMap<UserId, GroupMember>>,
+
+    /// vetKD master key name override (defaults to DEFAULT_VETKD_KEY_NAME)
+    pub vetkd_key_name: Option<String>,
+
+    /// Deduplicated view counts for each post
+    pub post_view_counts: BTreeMap<PostId, u64>,
+
+    /// Views counted per day as (post, viewer), used for per-day dedup
+    pub recent_post_views: BTreeMap<u64, BTreeSet<(PostId, UserId)>>,
+
+    /// Per-post engagement by day: post -> day -> counters
+    pub post_engagement_daily: BTreeMap<PostId, BTreeMap<u64, EngagementCounts>>,
+
+    /// Per-post lifetime engagement totals, indexed by author for top-post queries
+    pub author_post_totals: BTreeMap<UserId, BTreeMap<PostId, EngagementCounts>>,
+
+    /// Daily follower count samples per user, oldest first
+    pub follower_history: BTreeMap<UserId, Vec<FollowerSample>>,
+
+    /// Principals that made update calls, bucketed by day (rolling window)
+    pub daily_active_users: BTreeMap<u64, BTreeSet<UserId>>,
+
+    /// New profiles per day (rolling window)
+    pub daily_signups: BTreeMap<u64, u64>,
+
+    /// New posts per day (rolling window)
+    pub daily_posts: BTreeMap<u64, u64>,
+
+    /// ActivityPub settings; federation is disabled while unset
+    pub federation: Option<FederationConfig>,
+
+    /// Remote ActivityPub followers: local user -> remote actor URI -> follower
+    pub remote_followers: BTreeMap<UserId, BTreeMap<String, RemoteFollower>>,
+
+    /// Remote actors whose keys verified inbound signatures, indexed by key ID
+    pub remote_actors: BTreeMap<String, RemoteActor>,
+
+    /// Active moderation strikes per user (feeds the reputation score)
+    pub moderation_strikes: BTreeMap<UserId, u32>,
+
+    /// Canister consulted by `verify_personhood`
+    pub personhood_provider: Option<PersonhoodProvider>,
+
+    /// Actions that require a valid proof of personhood
+    pub personhood_required_actions: BTreeSet<String>,
+
+    /// Account standing required for gated actions, by action
+    pub action_gates: BTreeMap<String, ActionGate>,
+
+    /// When each user followed someone in the last day, for the
+    /// `follow_user` gate
+    pub follows_made: BTreeMap<UserId, Vec<u64>>,
+
+    /// Moderator-maintained domains whose links are rejected
+    pub blocked_domains: BTreeMap<String, BlockedDomain>,
+
+    /// Cached token gate lookups by (user, gate)
+    pub token_gate_checks: BTreeMap<(UserId, TokenGate), TokenGateCheck>,
+
+    /// Collection used by `mint_post_nft` (None disables minting)
+    pub nft_mint_config: Option<NftMintConfig>,
+
+    /// Posts with a mint call in flight, to prevent minting twice
+    pub posts_being_minted: BTreeSet<PostId>,
+
+    /// Admin-maintained image hosts (None uses DEFAULT_TRUSTED_IMAGE_DOMAINS)
+    pub trusted_image_domains: Option<BTreeSet<String>>,
+
+    /// Existing posts found linking to blocked domains
+    pub flagged_posts: BTreeMap<PostId, FlaggedPost>,
+
+    /// Safety service for links in new posts (None disables checks)
+    pub link_safety_config: Option<LinkSafetyConfig>,
+
+    /// Next post ID to check while a blocklist retro-scan is running
+    pub blocklist_scan_cursor: Option<u64>,
+
+    /// Comments written by each user, for the replies tab
+    pub user_comments: BTreeMap<UserId, BTreeSet<CommentId>>,
+
+    /// Posts linking to media, per author, for the media tab
+    pub user_media_posts: BTreeMap<UserId, BTreeSet<PostId>>,
+
+    /// Posts with coordinates by location grid cell
+    pub located_posts: BTreeMap<(i32, i32), BTreeSet<PostId>>,
+
+    /// Posts by the sources they cite, keyed by `citation_key`
+    pub cited_sources: BTreeMap<String, BTreeSet<PostId>>,
+
+    /// Fact-check labels by post, one per fact-checker
+    pub fact_checks: BTreeMap<PostId, Vec<FactCheckLabel>>,
+
+    /// Fact-checked posts by author
+    pub fact_checked_posts: BTreeMap<UserId, BTreeSet<PostId>>,
+
+    /// Ordered posts of each thread, indexed by the thread's first post
+    pub threads: BTreeMap<PostId, Vec<PostId>>,
+
+    /// Index: thread member -> first post of its thread
+    pub thread_roots: BTreeMap<PostId, PostId>,
+
+    /// Posts each user has liked, for the likes tab
+    pub liked_posts: BTreeMap<UserId, LikedPostsIndex>,
+
+    /// Saved social feed filters per user
+    pub feed_preferences: BTreeMap<UserId, FeedPreferences>,
+
+    /// Display and accessibility settings per user (defaults are not stored)
+    pub user_settings: BTreeMap<UserId, UserSettings>,
+
+    /// Opt-in word filter settings per user
+    pub word_filters: BTreeMap<UserId, WordFilterSettings>,
+
+    /// Languages each user wants to see in feeds (empty means all)
+    pub preferred_languages: BTreeMap<UserId, Vec<String>>,
+
+    /// Translation service configuration (None disables `translate_post`)
+    pub translation_config: Option<TranslationConfig>,
+
+    /// Cached translations indexed by (post, lowercase language tag)
+    pub post_translations: BTreeMap<(PostId, String), PostTranslation>,
+
+    /// Seed material for secure IDs and tokens, refreshed from `raw_rand`
+    pub entropy_pool: EntropyPool,
+
+    /// SNS governance canister allowed to execute proposals (also an admin)
+    pub sns_governance: Option<Principal>,
+
+    /// Moderation parameters controlled by governance
+    pub moderation_policy: ModerationPolicy,
+
+    /// Creator fund settings (None disables distributions)
+    pub creator_fund_config: Option<CreatorFundConfig>,
+
+    /// Last day covered by a creator fund distribution
+    pub creator_fund_last_day: Option<u64>,
+
+    /// Set while a distribution waits on the treasury balance
+    pub creator_fund_distributing: bool,
+
+    /// Rewards credited but not yet claimed, per creator
+    pub creator_fund_claimable: BTreeMap<UserId, u64>,
+
+    /// Rewards reserved by claims whose transfer is in flight, per creator;
+    /// still owed until the transfer settles
+    pub creator_fund_pending_claims: BTreeMap<UserId, u64>,
+
+    /// Reward history per creator, oldest first
+    pub creator_fund_rewards: BTreeMap<UserId, Vec<CreatorFundReward>>,
+
+    /// Claim history per creator, oldest first
+    pub creator_fund_claims: BTreeMap<UserId, Vec<CreatorFundClaim>>,
+
+    /// Points balance per user
+    pub points_balances: BTreeMap<UserId, u64>,
+
+    /// Points history per user, oldest first
+    pub points_history: BTreeMap<UserId, Vec<PointsTransaction>>,
+
+    /// Points earned and tipped today, per user
+    pub points_daily_usage: BTreeMap<UserId, points::DailyUsage>,
+
+    /// Point sale settings (None disables purchases)
+    pub points_sale_config: Option<PointsSaleConfig>,
+
+    /// Users whose purchase is waiting on the ledger
+    pub points_purchases_in_progress: BTreeSet<UserId>,
+
+    /// Creator earnings settings (None disables withdrawals)
+    pub earnings_config: Option<EarningsConfig>,
+
+    /// Creators whose withdrawal is waiting on the ledger
+    pub withdrawals_in_progress: BTreeSet<UserId>,
+
+    /// Withdrawal history per creator, oldest first
+    pub withdrawal_history: BTreeMap<UserId, Vec<Withdrawal>>,
+
+    /// Subscription tiers per creator, in creation order
+    pub subscription_tiers: BTreeMap<UserId, Vec<SubscriptionTier>>,
+
+    /// Subscriptions by (subscriber, creator), lapsed ones included
+    pub subscriptions: BTreeMap<(UserId, UserId), Subscription>,
+
+    /// Subscriptions with a payment waiting on the ledger
+    pub subscription_payments_in_progress: BTreeSet<(UserId, UserId)>,
+
+    /// Paid posts each viewer has bought, by (viewer, post)
+    pub post_unlocks: BTreeSet<(UserId, PostId)>,
+
+    /// Post purchases waiting on the ledger
+    pub post_unlocks_in_progress: BTreeSet<(UserId, PostId)>,
+
+    /// Promoted post settings (None disables promotions)
+    pub promotion_config: Option<PromotionConfig>,
+
+    /// Latest promotion of each post
+    pub promotions: BTreeMap<PostId, Promotion>,
+
+    /// Posts whose token payment is waiting on the ledger
+    pub promotions_in_progress: BTreeSet<PostId>,
+
+    /// Posts admins have barred from promotion
+    pub unpromotable_posts: BTreeSet<PostId>,
+
+    /// Escrowed contributions by (fundraiser post, contributor)
+    pub fundraiser_contributions: BTreeMap<(PostId, UserId), FundraiserContribution>,
+
+    /// Contributions waiting on the ledger
+    pub contributions_in_progress: BTreeSet<(PostId, UserId)>,
+
+    /// Fundraisers with a payout or refunds waiting on the ledger
+    pub fundraisers_settling: BTreeSet<PostId>,
+
+    /// Fundraisers not yet paid out or fully refunded
+    pub unsettled_fundraisers: BTreeSet<PostId>,
+
+    /// `created_at_time` of escrow transfers not yet confirmed, by fundraiser
+    /// post and refunded contributor (`None` for the payout); retries reuse
+    /// it so the ledger deduplicates them
+    pub escrow_transfer_times: BTreeMap<(PostId, Option<UserId>), u64>,
+
+    /// Monetary events per user, oldest first
+    pub transaction_log: BTreeMap<UserId, Vec<TransactionRecord>>,
+
+    /// Balances of canister-held accounts according to completed transfers
+    pub recorded_balances: BTreeMap<(Principal, ReconciledAccount), u64>,
+
+    /// Transfers per account that are waiting on the ledger
+    pub ledger_transfers_in_flight: BTreeMap<(Principal, ReconciledAccount), u32>,
+
+    /// Discrepancies found by reconciliation, by alert ID
+    pub ledger_alerts: BTreeMap<u64, LedgerAlert>,
+
+    /// Counter for generating unique ledger alert IDs
+    pub next_ledger_alert_id: u64,
+
+    /// Whether a reconciliation run is in progress
+    pub reconciliation_running: bool,
+
+    /// Sampled cycle balances, oldest first
+    pub cycles_history: Vec<CyclesSample>,
+
+    /// Balance below which optional features are paused (default: DEFAULT_LOW_CYCLES_THRESHOLD)
+    pub low_cycles_threshold: Option<u128>,
+
+    /// Bounded log of security-relevant events
+    pub logs: logging::LogBuffer,
+
+    /// Failed update calls by (endpoint, error category)
+    pub error_counts: BTreeMap<(String, ErrorCategory), u64>,
+
+    /// Long post bodies stored compressed; the post's `content` is left empty
+    pub compressed_post_bodies: BTreeMap<PostId, Vec<u8>>,
+
+    /// Totals for `compressed_post_bodies`
+    pub compression_stats: CompressionStats,
+
+    /// Archive canister cold posts are moved to, if archiving is enabled
+    pub archive_config: Option<ArchiveConfig>,
+
+    /// Archived posts, left here as stubs, and the archive canister holding each
+    pub archived_posts: BTreeMap<PostId, Principal>,
+
+    /// Posts with an archive call in flight
+    pub posts_being_archived: BTreeSet<PostId>,
+
+    /// Comments moved to the archive with their post, as (post, author)
+    pub archived_comments: BTreeMap<CommentId, (PostId, UserId)>,
+
+    /// Copies archive canisters still have to delete
+    pub pending_archive_deletions: Vec<(Principal, ArchiveDeletion)>,
+
+    /// Permanence service opted-in authors' public posts are exported to
+    pub export_config: Option<ExportConfig>,
+
+    /// Authors who opted in to exporting their public posts
+    pub export_authors: BTreeSet<UserId>,
+
+    /// Public posts waiting to be exported
+    pub pending_exports: BTreeSet<PostId>,
+
+    /// Encrypted notes-to-self by owner and note ID
+    pub private_notes: BTreeMap<UserId, BTreeMap<u64, PrivateNote>>,
+
+    /// Next ID assigned to a private note
+    pub next_note_id: u64,
+
+    /// Time capsules by ID, sealed and published
+    pub time_capsules: BTreeMap<u64, TimeCapsule>,
+
+    /// Content of sealed time capsules, dropped once published or cancelled
+    pub sealed_contents: BTreeMap<u64, String>,
+
+    /// Sealed time capsules by (reveal time, ID)
+    pub time_capsule_reveals: BTreeSet<(u64, u64)>,
+
+    /// Time capsule IDs by author
+    pub user_time_capsules: BTreeMap<UserId, BTreeSet<u64>>,
+
+    /// Next ID assigned to a time capsule
+    pub next_time_capsule_id: u64,
+
+    /// Ballots by poll post and voter, options in the voter's order
+    pub poll_ballots: BTreeMap<PostId, BTreeMap<UserId, Vec<u32>>>,
+
+    /// Scheduled live sessions by ID
+    pub live_sessions: BTreeMap<u64, LiveSession>,
+
+    /// (start time, session ID) of every live session, for listing soonest first
+    pub live_session_schedule: BTreeSet<(u64, u64)>,
+
+    /// IDs of the live sessions each user hosts or co-hosts
+    pub user_live_sessions: BTreeMap<UserId, BTreeSet<u64>>,
+
+    /// Users who RSVPed to each live session
+    pub live_session_rsvps: BTreeMap<u64, BTreeSet<UserId>>,
+
+    /// Next ID assigned to a live session
+    pub next_live_session_id: u64,
+
+    /// Audio clip each user is uploading in chunks
+    pub audio_uploads: BTreeMap<UserId, AudioUpload>,
+
+    /// Finished audio clip each user has not attached to a post yet
+    pub unattached_audio: BTreeMap<UserId, AudioClip>,
+
+    /// Post each attached audio clip belongs to, by asset ID
+    pub audio_posts: BTreeMap<u64, PostId>,
+
+    /// Community rule requiring alt text on all media
+    pub alt_text_required: bool,
+
+    /// Users who require alt text on their own media
+    pub alt_text_required_users: BTreeSet<UserId>,
+
+    /// Proof-of-work bits anonymous username availability checks must carry;
+    /// 0 turns the proof off
+    pub username_check_difficulty: u8,
+
+    /// Call quotas of expensive queries by endpoint
+    pub query_quotas: BTreeMap<String, QueryQuota>,
+
+    /// Posts deleted by their authors and still restorable
+    pub deleted_posts: BTreeMap<PostId, DeletedPost>,
+
+    /// Comments deleted by their authors and still restorable
+    pub deleted_comments: BTreeMap<CommentId, DeletedComment>,
+
+    /// Recent post deletions, reported by `get_feed_updates`
+    pub deletion_log: deletion_log::DeletionLog,
+
+    /// Recent counted calls per (caller, endpoint); all anonymous callers
+    /// share the anonymous principal's entry
+    pub query_usage: BTreeMap<(Principal, String), Vec<u64>>,
+
+    /// Primary canister, when this canister runs as a read replica
+    pub replica_of: Option<Principal>,
+
+    /// Read replicas spawned by this canister
+    pub read_replicas: BTreeMap<Principal, ReadReplica>,
+
+    /// Changes not yet applied by every read replica
+    pub replica_deltas: replica::DeltaLog,
+
+    /// Standby replica on another subnet, also listed in `read_replicas`
+    pub mirror_config: Option<MirrorConfig>,
+
+    /// Wasm module installed on newly spawned read replicas
+    pub replica_wasm: Option<Vec<u8>>,
+
+    /// Whether a replica sync round is running
+    pub replica_sync_in_flight: bool,
+
+    /// Precomputed explore feed and profiles for anonymous viewers
+    pub hot_cache: hot_cache::HotCache,
+
+    /// Latest follows gained per user as (time, follower), oldest first
+    pub recent_follows: BTreeMap<UserId, VecDeque<(u64, UserId)>>,
+
+    /// Latest likes, comments and mentions each user is notified about,
+    /// oldest first
+    pub recent_post_activity: BTreeMap<UserId, VecDeque<PostActivity>>,
+
+    /// Posts whose likes and comments each user no longer hears about
+    pub muted_post_activity: BTreeSet<(UserId, PostId)>,
+
+    /// Relay push notifications are delivered through; `None` disables push
+    pub push_config: Option<PushConfig>,
+
+    /// Devices each user receives push notifications on
+    pub push_endpoints: BTreeMap<UserId, Vec<PushEndpoint>>,
+
+    /// Push notifications awaiting delivery by id
+    pub pending_pushes: BTreeMap<u64, PendingPush>,
+
+    /// Next push notification id
+    pub next_push_id: u64,
+
+    /// Times push notifications were queued for each user in the last hour
+    pub recent_pushes: BTreeMap<UserId, Vec<u64>>,
+
+    /// Webhooks registered by each verified creator or organization
+    pub webhooks: BTreeMap<UserId, Vec<Webhook>>,
+
+    /// Next webhook id
+    pub next_webhook_id: u64,
+
+    /// Latest deliveries of each webhook by webhook id, oldest first
+    pub webhook_deliveries: BTreeMap<u64, VecDeque<WebhookDelivery>>,
+
+    /// Webhook deliveries awaiting an attempt by delivery id
+    pub pending_webhook_deliveries: BTreeMap<u64, PendingWebhookDelivery>,
+
+    /// Next webhook delivery id
+    pub next_webhook_delivery_id: u64,
+
+    /// Changes to public data, for external indexers
+    pub journal: journal::Journal,
+
+    /// Archive import totals per user (`follows_pending` is filled in
+    /// responses)
+    pub imports: BTreeMap<UserId, ImportStatus>,
+
+    /// Archived posts already imported, as (user, platform, original id)
+    pub imported_posts: BTreeSet<(UserId, ImportPlatform, String)>,
+
+    /// Followed handles from archives awaiting a match or the importer's
+    /// confirmation, by (importer, normalized handle)
+    pub pending_import_follows: BTreeMap<(UserId, String), PendingImportFollow>,
+
+    /// Last entry checked by the previous import follow run
+    pub import_follow_cursor: Option<(UserId, String)>,
+
+    /// Registered developer applications by the principal they call with
+    pub apps: BTreeMap<Principal, DeveloperApp>,
+
+    /// Applications proposed by their owners, by (application principal,
+    /// owner), until the application principal accepts
+    pub pending_apps: BTreeMap<(Principal, UserId), DeveloperApp>,
+
+    /// Usage of each registered application
+    pub app_usage: BTreeMap<Principal, AppUsage>,
+
+    /// Throttles owners and admins placed on applications
+    pub app_throttles: BTreeMap<Principal, AppThrottle>,
 }
 
 /// Utility function to work with state
@@ -74,6 +635,204 @@ fn with_state_mut<T>(f: impl FnOnce(&mut SocialNetworkState) -> T) -> T {
     STATE.with(|state| f(&mut state.borrow_mut()))
 }
 
+// ============================================================================
+// CANISTER LIFECYCLE
+// ============================================================================
+
+/// Seeds the installing principal as the first platform administrator
+#[init]
+fn init(args: Option<CanisterArgs>) {
+    bootstrap_admin();
+    apply_canister_args(args.unwrap_or_default());
+    start_timers();
+}
+
+/// Refuses upgrades while fundraisers hold escrowed funds
+///
+/// Escrow bookkeeping lives in the heap state, which upgrades clear; losing
+/// it would strand contributions, and restarted post IDs would reuse the
+/// escrow subaccounts of earlier fundraisers.
+#[pre_upgrade]
+fn pre_upgrade() {
+    let unsettled = with_state(|state| state.unsettled_fundraisers.len());
+    if unsettled > 0 {
+        ic_cdk::api::trap(&format!(
+            "{unsettled} fundraisers are not settled yet; upgrade once they are"
+        ));
+    }
+}
+
+/// State is not persisted across upgrades yet, so re-seed the upgrading
+/// principal as administrator to keep the canister manageable
+///
+/// Upgrade args can configure SNS governance and hand admin rights to it.
+#[post_upgrade]
+fn post_upgrade(args: Option<CanisterArgs>) {
+    bootstrap_admin();
+    apply_canister_args(args.unwrap_or_default());
+    start_timers();
+}
+
+/// Drops ingress calls that would be rejected anyway, before they use cycles
+///
+/// Only username availability checks are screened, against the caller's
+/// quota and proof of work as of the last committed state; every other call
+/// is accepted.
+#[inspect_message]
+fn inspect_message() {
+    let method = ic_cdk::api::call::method_name();
+    if method == "check_username_availability" {
+        let Ok((username, proof_of_work)) =
+            candid::decode_args::<(String, Option<u64>)>(&ic_cdk::api::call::arg_data_raw())
+        else {
+            return;
+        };
+        if screen_username_check(caller(), &username, proof_of_work, time()).is_err() {
+            return;
+        }
+    }
+    ic_cdk::api::call::accept_message();
+}
+
+/// Applies governance and replica settings from init or upgrade args
+///
+/// Handing over admin rights removes every other admin, so from then on
+/// admin changes go through SNS proposals.
+fn apply_canister_args(args: CanisterArgs) {
+    if let Some(primary) = args.replica_of {
+        with_state_mut(|state| state.replica_of = Some(primary));
+    }
+
+    let Some(governance) = args.sns_governance else {
+        return;
+    };
+
+    with_state_mut(|state| {
+        state.sns_governance = Some(governance);
+        let transfer = args.transfer_admin_to_governance.unwrap_or(false);
+        if transfer {
+            state.admins.clear();
+            state.admins.insert(UserId(governance));
+        }
+        record_audit_event(
+            state,
+            UserId(caller()),
+            AuditAction::GovernanceConfigured,
+            Some(UserId(governance)),
+            format!("admin transferred: {transfer}"),
+        );
+    });
+}
+
+/// Registers periodic background jobs (timers do not survive upgrades)
+///
+/// Read replicas apply deltas from their primary and only refresh their cache.
+fn start_timers() {
+    ic_cdk_timers::set_timer_interval(Duration::from_secs(HOT_CACHE_REFRESH_SECONDS), || {
+        with_state_mut(|state| refresh_hot_cache(state, time()))
+    });
+    if with_state(|state| state.replica_of.is_some()) {
+        return;
+    }
+
+    ic_cdk_timers::set_timer_interval(Duration::from_secs(86_400), || {
+        with_state_mut(|state| sample_follower_counts(state, time()));
+    });
+
+    ic_cdk_timers::set_timer_interval(Duration::from_secs(86_400), || {
+        ic_cdk::spawn(distribute_creator_fund())
+    });
+    ic_cdk_timers::set_timer_interval(
+        Duration::from_secs(NFT_AVATAR_REVERIFY_INTERVAL_SECONDS),
+        || ic_cdk::spawn(reverify_nft_avatars()),
+    );
+    ic_cdk_timers::set_timer_interval(
+        Duration::from_secs(SUBSCRIPTION_RENEWAL_INTERVAL_SECONDS),
+        || ic_cdk::spawn(renew_subscriptions()),
+    );
+    ic_cdk_timers::set_timer_interval(
+        Duration::from_secs(FUNDRAISER_SETTLEMENT_INTERVAL_SECONDS),
+        || ic_cdk::spawn(settle_fundraisers()),
+    );
+    ic_cdk_timers::set_timer_interval(Duration::from_secs(CYCLES_SAMPLE_INTERVAL_SECONDS), || {
+        with_state_mut(|state| sample_cycles_balance(state, time()))
+    });
+    ic_cdk_timers::set_timer_interval(
+        Duration::from_secs(LEDGER_RECONCILIATION_INTERVAL_SECONDS),
+        || {
+            ic_cdk::spawn(async {
+                reconcile_ledgers().await;
+            })
+        },
+    );
+    ic_cdk_timers::set_timer_interval(Duration::from_secs(ARCHIVE_INTERVAL_SECONDS), || {
+        ic_cdk::spawn(archive_cold_posts())
+    });
+    ic_cdk_timers::set_timer_interval(Duration::from_secs(EXPORT_INTERVAL_SECONDS), || {
+        ic_cdk::spawn(export_public_posts())
+    });
+    ic_cdk_timers::set_timer_interval(
+        Duration::from_secs(TIME_CAPSULE_CHECK_INTERVAL_SECONDS),
+        reveal_time_capsules,
+    );
+    ic_cdk_timers::set_timer_interval(Duration::from_secs(REPLICA_SYNC_INTERVAL_SECONDS), || {
+        ic_cdk::spawn(sync_read_replicas())
+    });
+    ic_cdk_timers::set_timer_interval(
+        Duration::from_secs(USERNAME_CHECK_PRUNE_INTERVAL_SECONDS),
+        || with_state_mut(|state| prune_username_check_limits(state, time())),
+    );
+    ic_cdk_timers::set_timer_interval(Duration::from_secs(DELETION_PURGE_INTERVAL_SECONDS), || {
+        with_state_mut(|state| purge_deleted_content(state, time()));
+        ic_cdk::spawn(delete_archived_copies());
+    });
+    ic_cdk_timers::set_timer_interval(Duration::from_secs(PUSH_DISPATCH_INTERVAL_SECONDS), || {
+        ic_cdk::spawn(dispatch_push_notifications())
+    });
+    ic_cdk_timers::set_timer_interval(
+        Duration::from_secs(WEBHOOK_DISPATCH_INTERVAL_SECONDS),
+        || ic_cdk::spawn(dispatch_webhook_deliveries()),
+    );
+    ic_cdk_timers::set_timer_interval(
+        Duration::from_secs(IMPORT_FOLLOW_INTERVAL_SECONDS),
+        resolve_import_follows,
+    );
+
+    // Seed immediately, then keep mixing in fresh randomness
+    ic_cdk_timers::set_timer(Duration::ZERO, || ic_cdk::spawn(refresh_entropy_pool()));
+    ic_cdk_timers::set_timer_interval(
+        Duration::from_secs(ENTROPY_REFRESH_INTERVAL_SECONDS),
+        || ic_cdk::spawn(refresh_entropy_pool()),
+    );
+}
+
+/// Internal function to mix `raw_rand` output into the entropy pool
+async fn refresh_entropy_pool() {
+    match ic_cdk::api::management_canister::main::raw_rand().await {
+        Ok((bytes,)) => with_state_mut(|state| {
+            auth::security_utils::reseed(&mut state.entropy_pool, &bytes, time());
+        }),
+        Err((code, message)) => with_state_mut(|state| {
+            log_event(
+                state,
+                LogLevel::Error,
+                LogCategory::System,
+                None,
+                format!("raw_rand failed: {code:?} {message}"),
+            );
+        }),
+    }
+}
+
+fn bootstrap_admin() {
+    let installer = caller();
+    if installer != Principal::anonymous() {
+        with_state_mut(|state| {
+            state.admins.insert(UserId(installer));
+        });
+    }
+}
+
 // ============================================================================
 // USER PROFILE MANAGEMENT
 // ============================================================================
@@ -85,16 +844,17 @@ fn with_state_mut<T>(f: impl FnOnce(&mut SocialNetworkState) -> T) -> T {
 /// This is required before users can post content or interact socially.
 ///
 /// # Arguments
-/// * `username` - Unique identifier (3-50 chars, alphanumeric + _ -)
+/// * `handle` - Unique handle used for mentions and lookups (3-50 chars, alphanumeric + _ -)
 /// * `bio` - Optional biography (max 500 chars)
-/// * `avatar` - Optional avatar URL or emoji
+/// * `avatar` - Optional avatar emoji; images are uploaded afterwards with `upload_avatar`
+/// * `display_name` - Optional display name (1-64 chars, unicode); defaults to the handle
 ///
 /// # Returns
 /// * `Ok(UserProfile)` - Successfully created profile with default privacy settings
-/// * `Err(String)` - Validation error or username conflict
+/// * `Err(String)` - Validation error or handle conflict
 ///
 /// # Errors
-/// - "Username already taken" - Duplicate username
+/// - "Username already taken" - Duplicate handle (case-insensitive)
 /// - "Username must be between 3 and 50 characters" - Invalid length
 /// - "User profile already exists" - User already has profile
 /// - "Authentication required" - Anonymous caller
@@ -111,11 +871,12 @@ fn with_state_mut<T>(f: impl FnOnce(&mut SocialNetworkState) -> T) -> T {
 /// let result = create_user_profile(
 ///     "alice_doe".to_string(),
 ///     Some("Digital rights activist and journalist".to_string()),
-///     Some("👩‍💻".to_string())
+///     Some("👩‍💻".to_string()),
+///     Some("Alice Doe".to_string())
 /// ).await;
 ///
 /// match result {
-///     Ok(profile) => println!("Profile created for {}", profile.username),
+///     Ok(profile) => println!("Profile created for @{}", profile.handle),
 ///     Err(error) => println!("Failed to create profile: {}", error),
 /// }
 /// ```
@@ -126,9 +887,10 @@ fn with_state_mut<T>(f: impl FnOnce(&mut SocialNetworkState) -> T) -> T {
 /// - Bio and avatar are optional for enhanced privacy
 #[update]
 pub async fn create_user_profile(
-    username: String,
+    handle: String,
     bio: Option<String>,
     avatar: Option<String>,
+    display_name: Option<String>,
 ) -> Result<UserProfile, String> {
     let user_id = authenticate_user()?;
 
@@ -138,32 +900,26 @@ pub async fn create_user_profile(
     }
 
     // Validate inputs
-    validate_username(&username)?;
+    validate_username(&handle).map_err(invalid("create_user_profile"))?;
+    if let Some(ref name) = display_name {
+        validate_display_name(name).map_err(invalid("create_user_profile"))?;
+    }
     if let Some(ref bio_text) = bio {
-        validate_bio(bio_text)?;
+        validate_bio(bio_text).map_err(invalid("create_user_profile"))?;
     }
     if let Some(ref avatar_text) = avatar {
-        validate_avatar(avatar_text)?;
-    }
-
-    // Check for username uniqueness
-    let username_taken = with_state(|state| {
-        state
-            .users
-            .values()
-            .any(|profile| profile.username == username)
-    });
-
-    if username_taken {
-        return Err("Username already taken".to_string());
+        validate_avatar(avatar_text).map_err(invalid("create_user_profile"))?;
     }
 
     let now = time();
     let profile = UserProfile {
         id: user_id,
-        username,
+        display_name: display_name.unwrap_or_else(|| handle.clone()),
+        handle,
         bio: bio.unwrap_or_default(),
         avatar: avatar.unwrap_or_default(),
+        banner: String::new(),
+        theme: ProfileTheme::default(),
         created_at: now,
         updated_at: now,
         follower_count: 0,
@@ -171,87 +927,639 @@ pub async fn create_user_profile(
         post_count: 0,
         privacy_settings: PrivacySettings::default(),
         verification_status: VerificationStatus::Unverified,
+        personhood: None,
+        nft_avatar: None,
+        version: 0,
     };
 
-    with_state_mut(|state| {
+    replicate_profile(with_state_mut(|state| {
+        // Check for handle uniqueness (including recently vacated handles)
+        let handle_key = normalize_handle(&profile.handle);
+        if !is_handle_available(state, &handle_key, user_id, now) {
+            return Err("Username already taken".to_string());
+        }
+
+        state.handle_index.insert(handle_key, user_id);
         state.users.insert(user_id, profile.clone());
         state.user_posts.insert(user_id, Vec::new());
-    });
-
-    Ok(profile)
+        bump_daily_counter(&mut state.daily_signups, now);
+        Ok(profile)
+    }))
 }
 
 /// Updates an existing user profile
 ///
+/// # Arguments
+/// * `display_name` - New display name (1-64 chars, unicode); `None` leaves it unchanged
+/// * `bio` - New biography (max 500 chars); `None` leaves it unchanged, `Some("")` clears it
+/// * `avatar` - New avatar emoji; `None` leaves it unchanged, `Some("")` clears it.
+///   Images are set with `upload_avatar`
+/// * `expected_version` - Profile `version` the edit is based on; the update is
+///   rejected with a conflict if the profile changed since
+///
+/// The handle is not editable here; it stays stable so mentions and links keep resolving.
+///
 /// # Security
 /// * Only the profile owner can update their profile
 /// * Validates all input parameters
 /// * Maintains creation timestamp
 #[update]
 pub async fn update_user_profile(
-    username: String,
+    display_name: Option<String>,
     bio: Option<String>,
     avatar: Option<String>,
+    expected_version: Option<u64>,
 ) -> Result<UserProfile, String> {
     let user_id = authenticate_user()?;
 
     // Validate inputs
-    validate_username(&username)?;
+    if let Some(ref name) = display_name {
+        validate_display_name(name).map_err(invalid("update_user_profile"))?;
+    }
     if let Some(ref bio_text) = bio {
-        validate_bio(bio_text)?;
+        validate_bio(bio_text).map_err(invalid("update_user_profile"))?;
     }
     if let Some(ref avatar_text) = avatar {
-        validate_avatar(avatar_text)?;
+        // Clients resubmitting the whole profile send back the current asset reference
+        let unchanged = with_state(|state| {
+            state
+                .users
+                .get(&user_id)
+                .is_some_and(|profile| &profile.avatar == avatar_text)
+        });
+        if !unchanged {
+            validate_avatar(avatar_text).map_err(invalid("update_user_profile"))?;
+        }
     }
 
-    with_state_mut(|state| {
-        // First check username uniqueness (excluding current user)
-        let username_taken = state
-            .users
-            .values()
-            .any(|p| p.username == username && p.id != user_id);
-
-        if username_taken {
-            return Err("Username already taken".to_string());
-        }
+    let mut replaced_asset = None;
+    let result = with_state_mut(|state| match state.users.get_mut(&user_id) {
+        Some(profile) => {
+            if let Some(expected) = expected_version {
+                if expected != profile.version {
+                    return Err(SocialNetworkError::VersionConflict {
+                        expected,
+                        current: profile.version,
+                    }
+                    .into());
+                }
+            }
 
-        // Now get mutable reference to update the profile
-        match state.users.get_mut(&user_id) {
-            Some(profile) => {
-                profile.username = username;
-                profile.bio = bio.unwrap_or_default();
-                profile.avatar = avatar.unwrap_or_default();
-                profile.updated_at = time();
+            if let Some(name) = display_name {
+                profile.display_name = name;
+            }
+            if let Some(bio_text) = bio {
+                profile.bio = bio_text;
+            }
+            if let Some(avatar_text) = avatar {
+                if avatar_text != profile.avatar {
+                    replaced_asset = avatar_store::asset_id(&profile.avatar);
+                }
+                profile.avatar = avatar_text;
+            }
+            profile.record_edit(time());
+
+            Ok(profile.clone())
+        }
+        None => Err("Profile not found".to_string()),
+    });
+
+    if let Some(asset_id) = replaced_asset {
+        avatar_store::remove(asset_id);
+    }
+    replicate_profile(result)
+}
+
+/// Replaces the caller's privacy settings
+///
+/// # Security
+/// * Only the profile owner can change their settings
+/// * Settings apply immediately to messaging, comments and social graph queries
+#[update]
+pub async fn update_privacy_settings(settings: PrivacySettings) -> Result<UserProfile, String> {
+    let user_id = authenticate_user()?;
+
+    replicate_profile(with_state_mut(|state| {
+        let profile = state.users.get_mut(&user_id).ok_or("Profile not found")?;
+        profile.privacy_settings = settings;
+        profile.record_edit(time());
+        Ok(profile.clone())
+    }))
+}
+
+/// Sets the languages the caller wants to see in feeds
+///
+/// # Arguments
+/// * `languages` - Language tags (e.g. "en", "pt-BR"); empty shows all languages
+///
+/// # Security
+/// * At most MAX_PREFERRED_LANGUAGES entries, each a valid language tag
+#[update]
+pub async fn set_preferred_languages(languages: Vec<String>) -> Result<Vec<String>, String> {
+    let user_id = authenticate_user()?;
+    if languages.len() > MAX_PREFERRED_LANGUAGES {
+        return Err(format!(
+            "At most {MAX_PREFERRED_LANGUAGES} preferred languages allowed"
+        ));
+    }
+    for code in &languages {
+        validate_language_code(code)?;
+    }
+
+    let mut languages: Vec<String> = languages.iter().map(|code| code.to_lowercase()).collect();
+    let mut seen = BTreeSet::new();
+    languages.retain(|code| seen.insert(code.clone()));
+
+    with_state_mut(|state| {
+        if languages.is_empty() {
+            state.preferred_languages.remove(&user_id);
+        } else {
+            state.preferred_languages.insert(user_id, languages.clone());
+        }
+    });
+    Ok(languages)
+}
+
+/// Gets the caller's preferred feed languages (empty means all)
+#[query]
+pub fn get_my_preferred_languages() -> Result<Vec<String>, String> {
+    let user_id = authenticate_user()?;
+    Ok(with_state(|state| {
+        state
+            .preferred_languages
+            .get(&user_id)
+            .cloned()
+            .unwrap_or_default()
+    }))
+}
+
+/// Saves the caller's default social feed filters
+///
+/// # Purpose
+/// Applied by `get_social_feed` whenever the call does not pass explicit filters.
+#[update]
+pub async fn set_feed_preferences(preferences: FeedPreferences) -> Result<FeedPreferences, String> {
+    let user_id = authenticate_user()?;
+    with_state_mut(|state| {
+        if preferences == FeedPreferences::default() {
+            state.feed_preferences.remove(&user_id);
+        } else {
+            state.feed_preferences.insert(user_id, preferences.clone());
+        }
+    });
+    Ok(preferences)
+}
+
+/// Gets the caller's saved social feed filters
+#[query]
+pub fn get_my_feed_preferences() -> Result<FeedPreferences, String> {
+    let user_id = authenticate_user()?;
+    Ok(with_state(|state| {
+        state
+            .feed_preferences
+            .get(&user_id)
+            .cloned()
+            .unwrap_or_default()
+    }))
+}
+
+/// Saves the caller's display and accessibility settings
+///
+/// # Purpose
+/// Keeps preferences such as reduced motion and large text in the canister so
+/// they follow the user across devices; clients apply them.
+#[update]
+pub async fn update_my_settings(mut settings: UserSettings) -> Result<UserSettings, String> {
+    let user_id = authenticate_user()?;
+    if let Some(locale) = &settings.locale {
+        validate_language_code(locale)?;
+    }
+    validate_timezone(settings.utc_offset_minutes, settings.digest_hour)?;
+    settings.locale = settings.locale.map(|locale| locale.to_lowercase());
+    with_state_mut(|state| {
+        if settings == UserSettings::default() {
+            state.user_settings.remove(&user_id);
+        } else {
+            state.user_settings.insert(user_id, settings.clone());
+        }
+    });
+    Ok(settings)
+}
+
+/// Gets the caller's display and accessibility settings
+#[query]
+pub fn get_my_settings() -> Result<UserSettings, String> {
+    let user_id = authenticate_user()?;
+    Ok(with_state(|state| {
+        state
+            .user_settings
+            .get(&user_id)
+            .cloned()
+            .unwrap_or_default()
+    }))
+}
+
+/// Converts a time on the caller's clock to a timestamp
+///
+/// # Arguments
+/// * `local` - `YYYY-MM-DDTHH:MM` in the caller's `utc_offset_minutes`
+///
+/// # Returns
+/// * `Ok(u64)` - Nanoseconds since the epoch, e.g. a `reveal_at` for
+///   `create_time_capsule` or a `scheduled_at` for `schedule_live_session`
+/// * `Err(String)` - Authentication error or malformed date
+#[query]
+pub fn resolve_local_time(local: String) -> Result<u64, String> {
+    let user_id = authenticate_user()?;
+    let utc_offset = with_state(|state| utc_offset_of(state, user_id));
+    local_time::parse_local_date_time(&local, utc_offset)
+        .ok_or_else(|| "Local time must be a valid YYYY-MM-DDTHH:MM".to_string())
+}
+
+/// Configures the caller's word filter
+///
+/// # Purpose
+/// When enabled, other users' posts and comments containing words from the
+/// default dictionary or `custom_words` are masked or collapsed in the
+/// caller's responses. Stored content is never modified.
+///
+/// # Security
+/// * Custom words are validated (count, length, single words)
+#[update]
+pub async fn set_word_filter(settings: WordFilterSettings) -> Result<WordFilterSettings, String> {
+    let user_id = authenticate_user()?;
+    validate_filter_words(&settings.custom_words)?;
+
+    let mut custom_words: Vec<String> = settings
+        .custom_words
+        .iter()
+        .map(|word| word.to_lowercase())
+        .collect();
+    custom_words.sort();
+    custom_words.dedup();
+    let settings = WordFilterSettings {
+        custom_words,
+        ..settings
+    };
+
+    with_state_mut(|state| {
+        state.word_filters.insert(user_id, settings.clone());
+    });
+    Ok(settings)
+}
+
+/// Gets the caller's word filter settings (disabled by default)
+#[query]
+pub fn get_my_word_filter() -> Result<WordFilterSettings, String> {
+    let user_id = authenticate_user()?;
+    Ok(with_state(|state| {
+        state
+            .word_filters
+            .get(&user_id)
+            .cloned()
+            .unwrap_or_default()
+    }))
+}
+
+/// Updates the caller's profile banner and theme
+///
+/// # Arguments
+/// * `banner` - New banner URL (`Some("")` clears it, `None` leaves it unchanged)
+/// * `theme` - New theme options (`None` leaves them unchanged)
+///
+/// # Security
+/// * Banner URLs are validated like avatar URLs (https, trusted domains)
+#[update]
+pub async fn update_profile_appearance(
+    banner: Option<String>,
+    theme: Option<ProfileTheme>,
+) -> Result<UserProfile, String> {
+    let user_id = authenticate_user()?;
+
+    if let Some(ref banner_url) = banner {
+        validate_banner(banner_url)?;
+    }
+
+    replicate_profile(with_state_mut(|state| {
+        let profile = state.users.get_mut(&user_id).ok_or("Profile not found")?;
+        if let Some(banner_url) = banner {
+            profile.banner = banner_url;
+        }
+        if let Some(theme) = theme {
+            profile.theme = theme;
+        }
+        profile.record_edit(time());
+        Ok(profile.clone())
+    }))
+}
+
+/// Uploads an avatar image and sets it as the caller's avatar
+///
+/// # Arguments
+/// * `image` - PNG, JPEG, GIF or WebP data (max MAX_AVATAR_IMAGE_BYTES)
+///
+/// # Behavior
+/// - The image is stored in stable memory and served at `/avatars/{id}`
+/// - The profile avatar becomes `asset:{id}`; the previous upload is deleted
+/// - The format is detected from the data, not trusted from the client
+#[update]
+pub async fn upload_avatar(image: Vec<u8>) -> Result<UserProfile, String> {
+    let user_id = authenticate_user()?;
+    check_rate_limit(&user_id, "upload_avatar", 5, 3600)?;
+    require_cycles_headroom("Avatar upload")?;
+
+    if image.len() > MAX_AVATAR_IMAGE_BYTES {
+        return Err(format!(
+            "Avatar image must be at most {} KB",
+            MAX_AVATAR_IMAGE_BYTES / 1024
+        ));
+    }
+    let content_type = avatar_store::sniff_image_type(&image)
+        .ok_or("Avatar must be a PNG, JPEG, GIF or WebP image")?;
+    if !with_state(|state| state.users.contains_key(&user_id)) {
+        return Err("Profile not found".to_string());
+    }
 
-                Ok(profile.clone())
+    let asset_id = security_utils::generate_secure_id();
+    avatar_store::insert(
+        asset_id,
+        avatar_store::StoredAvatar {
+            owner: user_id,
+            content_type: content_type.to_string(),
+            data: image,
+        },
+    );
+
+    let (profile, previous) = with_state_mut(|state| {
+        let profile = state.users.get_mut(&user_id).ok_or("Profile not found")?;
+        let previous =
+            std::mem::replace(&mut profile.avatar, avatar_store::asset_reference(asset_id));
+        profile.record_edit(time());
+        Ok::<_, String>((profile.clone(), previous))
+    })?;
+
+    if let Some(previous_id) = avatar_store::asset_id(&previous) {
+        avatar_store::remove(previous_id);
+    }
+    Ok(profile)
+}
+
+/// Sets an ICRC-7 token the caller owns as their avatar
+///
+/// # Arguments
+/// * `collection` - ICRC-7 collection canister
+/// * `token_id` - Token in that collection
+///
+/// # Behavior
+/// - Ownership is checked with `icrc7_owner_of` before the avatar is set
+/// - Ownership is re-checked every NFT_AVATAR_REVERIFY_INTERVAL_SECONDS; the
+///   verified marker is dropped if the token changes hands
+///
+/// # Security
+/// * Rate limited (10 attempts per hour)
+#[update]
+pub async fn set_nft_avatar(collection: Principal, token_id: Nat) -> Result<UserProfile, String> {
+    let user_id = authenticate_user()?;
+    if !with_state(|state| state.users.contains_key(&user_id)) {
+        return Err("Profile not found".to_string());
+    }
+    check_rate_limit(&user_id, "set_nft_avatar", 10, 3_600)?;
+
+    let owners = icrc7::owners_of(collection, vec![token_id.clone()]).await?;
+    if !icrc7::is_owned_by(&owners[0], user_id.0) {
+        return Err("You do not own this NFT".to_string());
+    }
+
+    with_state_mut(|state| {
+        let profile = state.users.get_mut(&user_id).ok_or("Profile not found")?;
+        let now = time();
+        profile.nft_avatar = Some(NftAvatar {
+            collection,
+            token_id,
+            verified: true,
+            checked_at: now,
+        });
+        profile.record_edit(now);
+        Ok(profile.clone())
+    })
+}
+
+/// Removes the caller's NFT avatar
+#[update]
+pub async fn clear_nft_avatar() -> Result<UserProfile, String> {
+    let user_id = authenticate_user()?;
+    with_state_mut(|state| {
+        let profile = state.users.get_mut(&user_id).ok_or("Profile not found")?;
+        profile.nft_avatar = None;
+        profile.record_edit(time());
+        Ok(profile.clone())
+    })
+}
+
+/// Internal function to re-check ownership of every NFT avatar
+///
+/// Tokens are queried per collection in batches of ICRC7_QUERY_BATCH_SIZE.
+/// Collections that cannot be reached keep their previous verification state.
+async fn reverify_nft_avatars() {
+    let mut by_collection: BTreeMap<Principal, Vec<(UserId, Nat)>> = BTreeMap::new();
+    with_state(|state| {
+        for profile in state.users.values() {
+            if let Some(nft) = &profile.nft_avatar {
+                by_collection
+                    .entry(nft.collection)
+                    .or_default()
+                    .push((profile.id, nft.token_id.clone()));
             }
-            None => Err("Profile not found".to_string()),
         }
+    });
+
+    for (collection, avatars) in by_collection {
+        for batch in avatars.chunks(ICRC7_QUERY_BATCH_SIZE) {
+            let token_ids = batch.iter().map(|(_, token_id)| token_id.clone()).collect();
+            let Ok(owners) = icrc7::owners_of(collection, token_ids).await else {
+                continue;
+            };
+
+            let now = time();
+            with_state_mut(|state| {
+                for ((user_id, token_id), owner) in batch.iter().zip(&owners) {
+                    // Skip avatars changed while the call was in flight
+                    let Some(nft) = state
+                        .users
+                        .get_mut(user_id)
+                        .and_then(|profile| profile.nft_avatar.as_mut())
+                        .filter(|nft| nft.collection == collection && &nft.token_id == token_id)
+                    else {
+                        continue;
+                    };
+                    nft.verified = icrc7::is_owned_by(owner, user_id.0);
+                    nft.checked_at = now;
+                }
+            });
+        }
+    }
+}
+
+/// Changes the caller's handle
+///
+/// # Arguments
+/// * `new_handle` - New handle (3-50 chars, alphanumeric + _ -)
+///
+/// # Behavior
+/// - Allowed once every HANDLE_CHANGE_COOLDOWN_DAYS days
+/// - The old handle keeps resolving to this user for HANDLE_REDIRECT_DAYS days
+///   and cannot be registered by anyone else during that time
+/// - A user may reclaim their own vacated handle
+///
+/// # Errors
+/// - "Username already taken" - Handle in use or reserved by someone else
+/// - "Handle can only be changed once every N days" - Cooldown active
+#[update]
+pub async fn change_handle(new_handle: String) -> Result<UserProfile, String> {
+    let user_id = authenticate_user()?;
+    validate_username(&new_handle)?;
+
+    replicate_profile(with_state_mut(|state| {
+        let now = time();
+
+        let current_handle = state
+            .users
+            .get(&user_id)
+            .map(|profile| profile.handle.clone())
+            .ok_or("Profile not found")?;
+        let old_key = normalize_handle(&current_handle);
+        let new_key = normalize_handle(&new_handle);
+
+        if old_key == new_key {
+            // Case-only change keeps the same index entry and skips the cooldown
+            if let Some(profile) = state.users.get_mut(&user_id) {
+                profile.handle = new_handle;
+                profile.record_edit(now);
+                return Ok(profile.clone());
+            }
+        }
+
+        let last_change = state
+            .handle_history
+            .get(&user_id)
+            .and_then(|history| history.last())
+            .map(|change| change.changed_at);
+        if let Some(changed_at) = last_change {
+            let cooldown_ends = changed_at
+                .saturating_add(HANDLE_CHANGE_COOLDOWN_DAYS.saturating_mul(NANOS_PER_DAY));
+            if now < cooldown_ends {
+                return Err(format!(
+                    "Handle can only be changed once every {HANDLE_CHANGE_COOLDOWN_DAYS} days"
+                ));
+            }
+        }
+
+        if !is_handle_available(state, &new_key, user_id, now) {
+            return Err("Username already taken".to_string());
+        }
+
+        state.handle_index.remove(&old_key);
+        state.handle_index.insert(new_key.clone(), user_id);
+        state.handle_redirects.remove(&new_key);
+        state.handle_redirects.insert(
+            old_key,
+            HandleRedirect {
+                user_id,
+                expires_at: now.saturating_add(HANDLE_REDIRECT_DAYS.saturating_mul(NANOS_PER_DAY)),
+            },
+        );
+        state
+            .handle_history
+            .entry(user_id)
+            .or_default()
+            .push(HandleChange {
+                old_handle: current_handle,
+                new_handle: new_handle.clone(),
+                changed_at: now,
+            });
+
+        let profile = state.users.get_mut(&user_id).ok_or("Profile not found")?;
+        profile.handle = new_handle;
+        profile.record_edit(now);
+        Ok(profile.clone())
+    }))
+}
+
+/// Gets the caller's handle change history (oldest first)
+#[query]
+pub fn get_my_handle_history() -> Result<Vec<HandleChange>, String> {
+    let user_id = authenticate_user()?;
+
+    Ok(with_state(|state| {
+        state
+            .handle_history
+            .get(&user_id)
+            .cloned()
+            .unwrap_or_default()
+    }))
+}
+
+/// Looks up a user profile by handle (case-insensitive)
+///
+/// Recently vacated handles resolve to their previous owner until the redirect lapses.
+#[query]
+pub fn get_user_by_username(handle: String) -> Option<UserProfile> {
+    let viewer = current_viewer();
+
+    with_state(|state| {
+        resolve_handle(state, &handle, time())
+            .and_then(|user_id| state.users.get(&user_id))
+            .map(|profile| profile_for_viewer(state, profile, viewer))
     })
 }
 
 /// Retrieves a user profile by user ID
 ///
 /// # Privacy
-/// * Respects privacy settings
-/// * Anonymous users can only see public profiles
+/// * `Private` profiles return a minimal shell to everyone but the owner
+/// * `FollowersOnly` profiles return a minimal shell to non-followers
+/// * Blocked users only ever see the shell
+/// * Anonymous viewers never receive privacy settings
 #[query]
 pub fn get_user_profile(user_id: UserId) -> Option<UserProfile> {
-    let viewer = caller();
+    let viewer = current_viewer();
 
     with_state(|state| {
-        state.users.get(&user_id).cloned().map(|profile| {
-            // Apply privacy filters based on viewer
-            if viewer == Principal::anonymous() || viewer != user_id.0 {
-                // For now, return full profile (privacy filtering to be enhanced)
-                profile
-            } else {
-                profile
-            }
-        })
+        let profile = state.users.get(&user_id)?;
+        match viewer.is_none().then(|| state.hot_cache.profile(profile)) {
+            Some(Some(cached)) => Some(cached.clone()),
+            _ => Some(profile_for_viewer(state, profile, viewer)),
+        }
     })
 }
 
+/// Retrieves several user profiles in one call
+///
+/// # Returns
+/// * `Ok(Vec<Option<UserProfile>>)` - One entry per requested ID, in request order;
+///   `None` for unknown users
+/// * `Err(String)` - More than `MAX_BATCH_FETCH_SIZE` IDs requested
+///
+/// # Privacy
+/// * Each profile is filtered exactly as in `get_user_profile`
+#[query]
+pub fn get_profiles_by_ids(user_ids: Vec<Principal>) -> Result<Vec<Option<UserProfile>>, String> {
+    check_batch_size(user_ids.len())?;
+    let viewer = current_viewer();
+
+    Ok(with_state(|state| {
+        user_ids
+            .into_iter()
+            .map(|user_id| {
+                state
+                    .users
+                    .get(&UserId(user_id))
+                    .map(|profile| profile_for_viewer(state, profile, viewer))
+            })
+            .collect()
+    }))
+}
+
 /// Get the authenticated user's own profile
 #[query]
 pub fn get_my_profile() -> Option<UserProfile> {
@@ -276,6 +1584,16 @@ pub fn get_my_profile() -> Option<UserProfile> {
 /// # Arguments
 /// * `content` - Post content (1-10,000 characters)
 /// * `visibility` - Who can see this post (Public, FollowersOnly, Unlisted)
+/// * `as_org` - Optional organization account, or a user who granted the
+///   caller the Post capability, to post as
+/// * `language` - Optional language tag (e.g. "en"); detected from the content when omitted
+/// * `format` - Optional content format (defaults to Plain); Markdown content is validated
+///   against the sanctioned subset
+/// * `location` - Optional place name and/or coordinates; coordinates are rounded to
+///   LOCATION_GRID_DEGREES
+/// * `sources` - Optional cited sources (at most MAX_POST_SOURCES https links)
+/// * `alt_texts` - Optional descriptions of the media links in `content`; required
+///   for every link when the community rule or the author's preference demands it
 ///
 /// # Returns
 /// * `Ok(PostId)` - Successfully created post ID
@@ -283,18 +1601,45 @@ pub fn get_my_profile() -> Option<UserProfile> {
 ///
 /// # Security
 /// * Requires authenticated user
+/// * Posting as an organization requires an Admin or Editor role
+/// * Posting as another user requires their Post capability grant
 /// * Validates content length and safety
 /// * Rate limited to prevent spam
 /// * Auto-creates profile if needed
 #[update]
+#[allow(clippy::too_many_arguments)]
 pub async fn create_post(
     content: String,
     visibility: Option<PostVisibility>,
+    as_org: Option<Principal>,
+    language: Option<String>,
+    format: Option<TextFormat>,
+    location: Option<PostLocation>,
+    sources: Option<Vec<Citation>>,
+    alt_texts: Option<Vec<MediaAltText>>,
 ) -> Result<PostId, String> {
-    let user_id = authenticate_user()?;
+    let user_id = resolve_author_for(authenticate_for(AppScope::Post)?, as_org, "create_post")?;
+    let format = format.unwrap_or_default();
 
     // Validate content
-    validate_post_content(&content)?;
+    validate_post_content(&content).map_err(invalid("create_post"))?;
+    if format == TextFormat::Markdown {
+        validate_markdown(&content).map_err(invalid("create_post"))?;
+    }
+    if let Some(ref code) = language {
+        validate_language_code(code).map_err(invalid("create_post"))?;
+    }
+    if let Some(ref location) = location {
+        validate_post_location(location).map_err(invalid("create_post"))?;
+    }
+    let sources = sources.unwrap_or_default();
+    validate_citations(&sources).map_err(invalid("create_post"))?;
+    let alt_texts = alt_texts.unwrap_or_default();
+    let media_links: Vec<&str> = feed_filter::media_links(&content).collect();
+    validate_media_alt_texts(&alt_texts, &media_links, alt_text_required(user_id))
+        .map_err(invalid("create_post"))?;
+
+    check_link_gate(user_id, &content)?;
 
     // Check rate limiting
     check_rate_limit(&user_id, "create_post", 10, 300)?; // 10 posts per 5 minutes
@@ -303,101 +1648,424 @@ pub async fn create_post(
     ensure_user_profile(user_id).await?;
 
     let post_id = with_state_mut(|state| {
-        let post_id = PostId(state.next_post_id);
-        state.next_post_id = state.next_post_id.saturating_add(1);
-
-        let now = time();
-        let post = Post {
-            id: post_id,
-            author_id: user_id,
+        insert_post(
+            state,
+            user_id,
             content,
-            created_at: now,
-            updated_at: now,
-            likes_count: 0u32,
-            comments_count: 0u32,
-            reposts_count: 0u32,
-            tips_received: 0u64,
-            edited_at: None,
-            visibility: visibility.unwrap_or(PostVisibility::Public),
-            like_count: 0u64,
-            comment_count: 0u64,
-        };
-
-        state.posts.insert(post_id, post);
-        state.post_likes.insert(post_id, BTreeSet::new());
-        state.post_comments.insert(post_id, Vec::new());
-
-        // Add to user's posts
-        state.user_posts.entry(user_id).or_default().push(post_id);
-
-        // Update user's post count
-        if let Some(profile) = state.users.get_mut(&user_id) {
-            profile.post_count = profile.post_count.saturating_add(1);
-            profile.updated_at = now;
-        }
-
-        post_id
+            visibility.unwrap_or(PostVisibility::Public),
+            language,
+            format,
+            PostExtras {
+                location,
+                sources,
+                alt_texts,
+                ..PostExtras::default()
+            },
+        )
     });
+    federate_post(post_id);
+    check_post_links(post_id);
 
     Ok(post_id)
 }
 
-/// Retrieves a post by ID with privacy checks
-#[query]
-pub fn get_post(post_id: PostId) -> Option<Post> {
-    let viewer = caller();
-
-    with_state(|state| {
-        state.posts.get(&post_id).cloned().filter(|post| {
-            // Apply visibility filters
-            match post.visibility {
-                PostVisibility::Public => true,
-                PostVisibility::FollowersOnly => {
-                    // For now, allow all (following system to be implemented)
-                    viewer != Principal::anonymous()
-                }
-                PostVisibility::Unlisted => {
-                    // Only author can see unlisted posts
-                    viewer == post.author_id.0
-                }
-            }
-        })
+/// Creates a thread of ordered, linked posts in one call
+///
+/// # Arguments
+/// * `parts` - Content of each post in order (2-25 parts, each 1-10,000 characters)
+/// * `visibility` - Who can see the thread (Public, FollowersOnly, Unlisted)
+///
+/// # Returns
+/// * `Ok(Vec<PostId>)` - IDs of the created posts, first post first
+/// * `Err(String)` - Validation or security error
+///
+/// # Security
+/// * Requires authenticated user
+/// * Every part is validated before anything is stored
+/// * Each part counts as one post against the `create_post` rate limit; the
+///   whole thread is rejected if they do not all fit
+#[update]
+pub async fn create_thread(
+    parts: Vec<String>,
+    visibility: Option<PostVisibility>,
+) -> Result<Vec<PostId>, String> {
+    let user_id = authenticate_user()?;
+
+    if parts.len() < 2 || parts.len() > MAX_THREAD_PARTS {
+        return Err(format!(
+            "A thread must have between 2 and {MAX_THREAD_PARTS} parts"
+        ));
+    }
+    for content in &parts {
+        validate_post_content(content).map_err(invalid("create_thread"))?;
+        check_link_gate(user_id, content)?;
+    }
+
+    check_rate_limit_calls(&user_id, "create_post", 10, 300, parts.len() as u32)?;
+    ensure_user_profile(user_id).await?;
+
+    let visibility = visibility.unwrap_or(PostVisibility::Public);
+    let post_ids: Vec<PostId> = with_state_mut(|state| {
+        let post_ids: Vec<PostId> = parts
+            .into_iter()
+            .map(|content| {
+                insert_post(
+                    state,
+                    user_id,
+                    content,
+                    visibility.clone(),
+                    None,
+                    TextFormat::Plain,
+                    PostExtras::default(),
+                )
+            })
+            .collect();
+
+        let root_id = post_ids[0];
+        for &post_id in &post_ids {
+            state.thread_roots.insert(post_id, root_id);
+        }
+        state.threads.insert(root_id, post_ids.clone());
+        post_ids
+    });
+    for &post_id in &post_ids {
+        federate_post(post_id);
+        check_post_links(post_id);
+    }
+
+    Ok(post_ids)
+}
+
+/// Replies to one of the caller's own posts, extending its thread
+///
+/// The new post is appended to the end of the thread the replied-to post
+/// belongs to (starting a thread if it has none) and inherits its visibility.
+/// Comments (`add_comment`) on one's own post stay comments and never join
+/// a thread; this call is the only way to extend one.
+///
+/// # Arguments
+/// * `post_id` - Any post of the caller's thread
+/// * `content` - Post content (1-10,000 characters)
+///
+/// # Returns
+/// * `Ok(PostId)` - ID of the appended post
+/// * `Err(String)` - Post not found, not the caller's, thread full, first post
+///   deleted, or validation error
+#[update]
+pub async fn extend_thread(post_id: PostId, content: String) -> Result<PostId, String> {
+    let user_id = authenticate_user()?;
+    validate_post_content(&content).map_err(invalid("extend_thread"))?;
+
+    let (root_id, visibility) = with_state(|state| {
+        let post = state.posts.get(&post_id).ok_or("Post not found")?;
+        if post.author_id != user_id {
+            return Err("Only the author can extend a thread".to_string());
+        }
+
+        let root_id = state.thread_roots.get(&post_id).copied().unwrap_or(post_id);
+        let length = state.threads.get(&root_id).map_or(1, Vec::len);
+        if length >= MAX_THREAD_LENGTH {
+            return Err(format!("Threads are limited to {MAX_THREAD_LENGTH} posts"));
+        }
+        let root = state
+            .posts
+            .get(&root_id)
+            .ok_or("The first post of this thread was deleted")?;
+        Ok((root_id, root.visibility.clone()))
+    })?;
+
+    check_link_gate(user_id, &content)?;
+    check_rate_limit(&user_id, "create_post", 10, 300)?;
+
+    let new_post_id = with_state_mut(|state| {
+        let new_post_id = insert_post(
+            state,
+            user_id,
+            content,
+            visibility,
+            None,
+            TextFormat::Plain,
+            PostExtras::default(),
+        );
+        state.thread_roots.insert(root_id, root_id);
+        state.thread_roots.insert(new_post_id, root_id);
+        state
+            .threads
+            .entry(root_id)
+            .or_insert_with(|| vec![root_id])
+            .push(new_post_id);
+        new_post_id
+    });
+    federate_post(new_post_id);
+    check_post_links(new_post_id);
+
+    Ok(new_post_id)
+}
+
+/// Gets the full ordered thread containing a post
+///
+/// # Returns
+/// * `Ok(Vec<Post>)` - Thread posts, first post first (just the post itself if it is not part of a thread)
+/// * `Err(String)` - Post not found or not visible to the caller
+///
+/// # Privacy
+/// * Posts the viewer may not see are omitted
+#[query]
+pub fn get_thread(post_id: PostId) -> Result<Vec<Post>, String> {
+    let viewer = current_viewer();
+
+    with_state(|state| {
+        let post = state
+            .posts
+            .get(&post_id)
+            .filter(|post| can_view_post(state, viewer, post))
+            .ok_or("Post not found")?;
+
+        let Some(thread) = state
+            .thread_roots
+            .get(&post_id)
+            .and_then(|root_id| state.threads.get(root_id))
+        else {
+            return Ok(vec![post_for_viewer(state, viewer, post)]);
+        };
+
+        Ok(thread
+            .iter()
+            .filter_map(|id| state.posts.get(id))
+            .filter(|post| can_view_post(state, viewer, post))
+            .map(|post| post_for_viewer(state, viewer, post))
+            .collect())
+    })
+}
+
+/// Retrieves a post by ID with privacy checks
+///
+/// Archived posts are fetched from their archive canister; if it cannot be
+/// reached the stub is returned with empty content and `archived` set.
+#[query(composite = true)]
+pub async fn get_post(post_id: PostId) -> Option<Post> {
+    let viewer = current_viewer();
+
+    let archive = with_state(|state| {
+        let post = state.posts.get(&post_id)?;
+        if !can_view_post(state, viewer, post) {
+            return None;
+        }
+        state.archived_posts.get(&post_id).copied()
+    });
+    let archived = match archive {
+        Some(archive) => archive::fetch(archive, post_id).await.ok().flatten(),
+        None => None,
+    };
+
+    with_state(|state| {
+        let post = state
+            .posts
+            .get(&post_id)
+            .filter(|post| can_view_post(state, viewer, post))?;
+        Some(match archived {
+            Some(archived) => {
+                post_for_viewer(state, viewer, &archive::restore(post, archived.post))
+            }
+            None => post_for_viewer(state, viewer, post),
+        })
     })
 }
 
+/// Retrieves several posts in one call
+///
+/// # Returns
+/// * `Ok(Vec<Option<Post>>)` - One entry per requested ID, in request order;
+///   `None` for missing posts and posts the caller may not see
+/// * `Err(String)` - More than `MAX_BATCH_FETCH_SIZE` IDs requested
+///
+/// # Privacy
+/// * Each post is filtered exactly as in `get_post`
+#[query]
+pub fn get_posts_by_ids(post_ids: Vec<PostId>) -> Result<Vec<Option<Post>>, String> {
+    check_batch_size(post_ids.len())?;
+    let viewer = current_viewer();
+
+    Ok(with_state(|state| {
+        post_ids
+            .iter()
+            .map(|post_id| {
+                state
+                    .posts
+                    .get(post_id)
+                    .filter(|post| can_view_post(state, viewer, post))
+                    .map(|post| post_for_viewer(state, viewer, post))
+            })
+            .collect()
+    }))
+}
+
 /// Gets all posts by a specific user
+///
+/// `total` is only reported to the author; others may not see every post.
 #[query]
-pub fn get_user_posts(user_id: UserId, limit: Option<usize>, offset: Option<usize>) -> Vec<Post> {
-    let viewer = caller();
+pub fn get_user_posts(
+    user_id: UserId,
+    limit: Option<usize>,
+    offset: Option<usize>,
+) -> UserPostsPage {
+    let viewer = current_viewer();
     let limit = limit.unwrap_or(10).min(50); // Cap at 50 posts
     let offset = offset.unwrap_or(0);
 
     with_state(|state| {
-        state
+        let post_ids = state
             .user_posts
             .get(&user_id)
-            .map(|post_ids| {
-                post_ids
-                    .iter()
-                    .rev() // Most recent first
-                    .skip(offset)
-                    .take(limit)
-                    .filter_map(|&post_id| state.posts.get(&post_id))
-                    .filter(|post| {
-                        // Apply visibility filters
-                        match post.visibility {
-                            PostVisibility::Public => true,
-                            PostVisibility::FollowersOnly => viewer != Principal::anonymous(),
-                            PostVisibility::Unlisted => viewer == post.author_id.0,
-                        }
-                    })
-                    .cloned()
-                    .collect()
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        // Most recent first
+        let (page, next_cursor) = page_at(post_ids.iter().rev(), offset, limit);
+        let posts = page
+            .into_iter()
+            .filter_map(|post_id| state.posts.get(post_id))
+            .filter(|post| can_view_post(state, viewer, post))
+            .map(|post| post_for_viewer(state, viewer, post))
+            .collect();
+
+        UserPostsPage {
+            posts,
+            total: (viewer == Some(user_id)).then_some(post_ids.len() as u64),
+            has_more: next_cursor.is_some(),
+            next_cursor,
+        }
+    })
+}
+
+/// Gets a user's comments for the profile Replies tab (newest first)
+///
+/// # Arguments
+/// * `user_id` - Profile owner
+/// * `cursor` - `next_cursor` from the previous page (None for the first page)
+/// * `limit` - Page size (default 10, max 50)
+///
+/// # Privacy
+/// * Comments on posts the viewer may not see are omitted
+#[query]
+pub fn get_user_comments(
+    user_id: Principal,
+    cursor: Option<CommentId>,
+    limit: Option<usize>,
+) -> CommentsPage {
+    let viewer = current_viewer();
+    let limit = limit.unwrap_or(DEFAULT_FEED_LIMIT).min(MAX_FEED_LIMIT);
+
+    with_state(|state| {
+        let Some(index) = state.user_comments.get(&UserId(user_id)) else {
+            return CommentsPage {
+                comments: Vec::new(),
+                total: Some(0),
+                has_more: false,
+                next_cursor: None,
+            };
+        };
+
+        let (ids, next_cursor) = page_before(index, cursor, limit);
+        let comments = ids
+            .into_iter()
+            .filter_map(|comment_id| state.comments.get(&comment_id))
+            .filter(|comment| {
+                state
+                    .posts
+                    .get(&comment.post_id)
+                    .is_some_and(|post| can_view_post(state, viewer, post))
             })
-            .unwrap_or_default()
+            .map(|comment| comment_for_viewer(state, viewer, comment))
+            .collect();
+
+        CommentsPage {
+            comments,
+            total: None,
+            has_more: next_cursor.is_some(),
+            next_cursor,
+        }
+    })
+}
+
+/// Gets a user's posts that link to images, video or audio (newest first)
+///
+/// # Arguments
+/// * `user_id` - Profile owner
+/// * `cursor` - `next_cursor` from the previous page (None for the first page)
+/// * `limit` - Page size (default 10, max 50)
+///
+/// # Privacy
+/// * Posts the viewer may not see are omitted
+#[query]
+pub fn get_user_media_posts(
+    user_id: Principal,
+    cursor: Option<PostId>,
+    limit: Option<usize>,
+) -> PostsPage {
+    let viewer = current_viewer();
+    let limit = limit.unwrap_or(DEFAULT_FEED_LIMIT).min(MAX_FEED_LIMIT);
+
+    with_state(|state| {
+        let Some(index) = state.user_media_posts.get(&UserId(user_id)) else {
+            return PostsPage {
+                posts: Vec::new(),
+                total: Some(0),
+                has_more: false,
+                next_cursor: None,
+            };
+        };
+
+        let (ids, next_cursor) = page_before(index, cursor, limit);
+        let posts = ids
+            .into_iter()
+            .filter_map(|post_id| state.posts.get(&post_id))
+            .filter(|post| can_view_post(state, viewer, post))
+            .map(|post| post_for_viewer(state, viewer, post))
+            .collect();
+
+        PostsPage {
+            posts,
+            total: (viewer == Some(UserId(user_id))).then_some(index.len() as u64),
+            has_more: next_cursor.is_some(),
+            next_cursor,
+        }
     })
 }
 
+/// Internal function to take up to `limit` IDs below `cursor`, newest first
+///
+/// Returns the page and the cursor for the following page, if any remain.
+fn page_before<T: Ord + Copy>(
+    index: &BTreeSet<T>,
+    cursor: Option<T>,
+    limit: usize,
+) -> (Vec<T>, Option<T>) {
+    let mut entries = match cursor {
+        Some(cursor) => index.range(..cursor),
+        None => index.range(..),
+    }
+    .rev();
+    let page: Vec<T> = entries.by_ref().take(limit).copied().collect();
+    let next_cursor = page.last().copied().filter(|_| entries.next().is_some());
+    (page, next_cursor)
+}
+
+/// Internal function to take up to `limit` entries starting at `offset`
+///
+/// Returns the page and the offset of the following page, if any remain.
+fn page_at<T>(
+    entries: impl Iterator<Item = T>,
+    offset: usize,
+    limit: usize,
+) -> (Vec<T>, Option<u64>) {
+    let mut entries = entries.skip(offset);
+    let page: Vec<T> = entries.by_ref().take(limit).collect();
+    let next_cursor = entries
+        .next()
+        .map(|_| offset.saturating_add(page.len()) as u64);
+    (page, next_cursor)
+}
+
 /// Retrieves the authenticated user's personalized social feed
 ///
 /// # Purpose
@@ -409,7 +2077,7 @@ pub fn get_user_posts(user_id: UserId, limit: Option<usize>, offset: Option<usiz
 /// * `limit` - Maximum posts to return (capped at 50)
 ///
 /// # Returns
-/// * `Ok(Vec<FeedPost>)` - List of posts with author info sorted by creation time (newest first)
+/// * `Ok(CanisterPostsPage)` - Posts sorted by creation time (newest first)
 /// * `Err(String)` - Authentication or validation error
 ///
 /// # Feed Algorithm
@@ -429,41 +2097,54 @@ pub fn get_user_posts(user_id: UserId, limit: Option<usize>, offset: Option<usiz
 /// - Pagination prevents memory exhaustion
 /// - Efficient indexing for large user bases
 /// - Cycle cost scales with following count
+///
+/// # Deprecated
+/// Superseded by `get_social_feed`; removed in API version 2
 #[query]
-pub fn get_user_feed(offset: Option<u64>, limit: Option<u64>) -> Result<Vec<CanisterPost>, String> {
-    let _caller = authenticate_user()?;
+pub fn get_user_feed(offset: Option<u64>, limit: Option<u64>) -> Result<CanisterPostsPage, String> {
+    let caller = authenticate_user()?;
 
     let safe_offset: usize = offset.unwrap_or(0u64) as usize;
     let safe_limit: usize = std::cmp::min(limit.unwrap_or(10u64) as usize, MAX_FEED_LIMIT);
 
     with_state(|state| {
-        let user_posts: Vec<CanisterPost> = state
-            .posts
-            .values()
-            .filter(|post| {
+        let (page, next_cursor) = page_at(
+            state.posts.values().filter(|post| {
                 // For now, show all public posts (will add following filter later)
                 matches!(post.visibility, PostVisibility::Public)
-            })
-            .skip(safe_offset)
-            .take(safe_limit)
+                    && matches_language_preference(state, Some(caller), post)
+            }),
+            safe_offset,
+            safe_limit,
+        );
+        let user_posts: Vec<CanisterPost> = page
+            .into_iter()
+            .map(|post| post_for_viewer(state, Some(caller), post))
             .map(|post| CanisterPost {
                 id: post.id,
                 author_id: post.author_id,
                 content: post.content.clone(),
                 created_at: post.created_at,
-                likes_count: post.likes_count,
-                comments_count: post.comments_count,
-                reposts_count: post.reposts_count,
+                like_count: post.like_count,
+                comment_count: post.comment_count,
+                repost_count: post.repost_count,
                 tips_received: post.tips_received,
                 edited_at: post.edited_at,
                 visibility: post.visibility.clone(),
+                thread: post.thread.clone(),
+                nft: post.nft.clone(),
             })
             .collect::<Vec<_>>()
             .into_iter()
             .rev() // Newest first
             .collect();
 
-        Ok(user_posts)
+        Ok(CanisterPostsPage {
+            posts: user_posts,
+            total: None,
+            has_more: next_cursor.is_some(),
+            next_cursor,
+        })
     })
 }
 
@@ -474,12 +2155,25 @@ pub struct CanisterPost {
     pub author_id: UserId,
     pub content: String,
     pub created_at: u64,
-    pub likes_count: u32,
-    pub comments_count: u32,
-    pub reposts_count: u32,
+    pub like_count: u64,
+    pub comment_count: u64,
+    pub repost_count: u64,
     pub tips_received: u64,
     pub edited_at: Option<u64>,
     pub visibility: PostVisibility,
+    pub thread: Option<ThreadPosition>,
+    pub nft: Option<PostNft>,
+}
+
+/// Page of `get_user_feed`
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct CanisterPostsPage {
+    pub posts: Vec<CanisterPost>,
+    pub total: Option<u64>,
+    pub has_more: bool,
+
+    /// Offset of the next page; `None` when exhausted
+    pub next_cursor: Option<u64>,
 }
 
 // ============================================================================
@@ -494,13 +2188,21 @@ pub struct CanisterPost {
 /// * Rate limited to prevent spam
 #[update]
 pub async fn like_post(post_id: PostId) -> Result<(), String> {
-    let user_id = authenticate_user()?;
+    let user_id = authenticate_for(AppScope::Post)?;
 
     // Check rate limiting
     check_rate_limit(&user_id, "like_post", 60, 60)?; // 60 likes per minute
 
     with_state_mut(|state| {
-        // Check if post exists
+        // Check if post exists and is visible to the caller
+        let visible = state
+            .posts
+            .get(&post_id)
+            .map(|post| can_view_post(state, Some(user_id), post))
+            .unwrap_or(false);
+        if !visible {
+            return Err("Post not found".to_string());
+        }
         let post = state.posts.get_mut(&post_id).ok_or("Post not found")?;
 
         // Check if already liked
@@ -514,7 +2216,14 @@ pub async fn like_post(post_id: PostId) -> Result<(), String> {
         likes.insert(user_id);
         post.like_count = post.like_count.saturating_add(1);
         post.updated_at = time();
+        state
+            .liked_posts
+            .entry(user_id)
+            .or_default()
+            .insert(post_id, time());
 
+        record_engagement(state, post_id, user_id, EngagementEvent::Like, time());
+        notify_post_activity(state, post_id, user_id, PostActivityKind::Like, time());
         Ok(())
     })
 }
@@ -522,7 +2231,7 @@ pub async fn like_post(post_id: PostId) -> Result<(), String> {
 /// Unlikes a post
 #[update]
 pub async fn unlike_post(post_id: PostId) -> Result<(), String> {
-    let user_id = authenticate_user()?;
+    let user_id = authenticate_for(AppScope::Post)?;
 
     with_state_mut(|state| {
         // Check if post exists
@@ -537,82 +2246,533 @@ pub async fn unlike_post(post_id: PostId) -> Result<(), String> {
 
         post.like_count = post.like_count.saturating_sub(1);
         post.updated_at = time();
+        if let Some(index) = state.liked_posts.get_mut(&user_id) {
+            index.remove(post_id);
+        }
 
+        record_engagement(state, post_id, user_id, EngagementEvent::Unlike, time());
         Ok(())
     })
 }
 
+/// Gets posts the caller has liked, newest like first
+///
+/// # Arguments
+/// * `cursor` - `next_cursor` from the previous page (None for the first page)
+/// * `limit` - Page size (default 10, max 50)
+#[query]
+pub fn get_my_liked_posts(
+    cursor: Option<LikeCursor>,
+    limit: Option<usize>,
+) -> Result<LikedPostsPage, String> {
+    let user_id = authenticate_user()?;
+    Ok(with_state(|state| {
+        liked_posts_page(state, user_id, Some(user_id), cursor, limit)
+    }))
+}
+
+/// Gets posts another user has liked, newest like first
+///
+/// # Privacy
+/// * Governed by the user's `likes_visibility` setting (blocked users never see it)
+/// * Posts the viewer may not see are omitted
+#[query]
+pub fn get_user_liked_posts(
+    user_id: Principal,
+    cursor: Option<LikeCursor>,
+    limit: Option<usize>,
+) -> Result<LikedPostsPage, String> {
+    let owner = UserId(user_id);
+    let viewer = current_viewer();
+
+    with_state(|state| {
+        match viewer {
+            Some(viewer_id) => {
+                check_interaction_audience(state, owner, viewer_id, |s| &s.likes_visibility)?
+            }
+            None => {
+                let profile = state.users.get(&owner).ok_or("User does not exist")?;
+                if profile.privacy_settings.likes_visibility != InteractionAudience::Everyone {
+                    return Err("This user's likes are not public".to_string());
+                }
+            }
+        }
+        Ok(liked_posts_page(state, owner, viewer, cursor, limit))
+    })
+}
+
+/// Internal function to page through a user's liked-posts index
+///
+/// Entries the viewer cannot see are skipped but still advance the cursor.
+fn liked_posts_page(
+    state: &SocialNetworkState,
+    owner: UserId,
+    viewer: Option<UserId>,
+    cursor: Option<LikeCursor>,
+    limit: Option<usize>,
+) -> LikedPostsPage {
+    let limit = limit.unwrap_or(DEFAULT_FEED_LIMIT).min(MAX_FEED_LIMIT);
+    let Some(index) = state.liked_posts.get(&owner) else {
+        return LikedPostsPage {
+            posts: Vec::new(),
+            total: Some(0),
+            has_more: false,
+            next_cursor: None,
+        };
+    };
+
+    let mut entries = match cursor {
+        Some(cursor) => index.by_time.range(..(cursor.liked_at, cursor.post_id)),
+        None => index.by_time.range(..),
+    }
+    .rev();
+
+    let mut posts = Vec::new();
+    let mut last = None;
+    for &(liked_at, post_id) in entries.by_ref().take(limit) {
+        last = Some(LikeCursor { liked_at, post_id });
+        if let Some(post) = state
+            .posts
+            .get(&post_id)
+            .filter(|post| can_view_post(state, viewer, post))
+        {
+            posts.push(post_for_viewer(state, viewer, post));
+        }
+    }
+
+    let next_cursor = last.filter(|_| entries.next().is_some());
+    LikedPostsPage {
+        posts,
+        total: None,
+        has_more: next_cursor.is_some(),
+        next_cursor,
+    }
+}
+
 // ============================================================================
-// COMMENT SYSTEM
+// POST VIEWS
 // ============================================================================
 
-/// Adds a comment to a post
+/// Records that the caller has seen a batch of posts
+///
+/// # Arguments
+/// * `post_ids` - Posts rendered to the caller (at most `MAX_BATCH_FETCH_SIZE`)
+///
+/// # Returns
+/// * `Ok(u64)` - Number of views newly counted
+/// * `Err(String)` - Authentication, batch size or rate limit error
+///
+/// # Behavior
+/// - Each viewer counts at most once per post per UTC day
+/// - Authors viewing their own posts and posts the caller cannot see are ignored
 #[update]
-pub async fn add_comment(post_id: PostId, content: String) -> Result<Comment, String> {
-    let user_id = authenticate_user()?;
-
-    // Validate content
-    validate_comment_content(&content)?;
+pub async fn record_post_view(post_ids: Vec<PostId>) -> Result<u64, String> {
+    let viewer_id = authenticate_user()?;
+    check_batch_size(post_ids.len())?;
 
-    // Check rate limiting
-    check_rate_limit(&user_id, "add_comment", 30, 60)?; // 30 comments per minute
+    check_rate_limit(&viewer_id, "record_post_view", 30, 60)?; // 30 batches per minute
 
     with_state_mut(|state| {
-        // Check if post exists
-        let post = state.posts.get_mut(&post_id).ok_or("Post not found")?;
-
-        let comment_id = CommentId(state.next_comment_id);
-        state.next_comment_id = state.next_comment_id.saturating_add(1);
-
         let now = time();
-        let comment = Comment {
-            id: comment_id,
-            post_id,
-            author_id: user_id,
-            content,
-            created_at: now,
-            updated_at: now,
-        };
+        let today = now / NANOS_PER_DAY;
 
-        state.comments.insert(comment_id, comment.clone());
-        state
-            .post_comments
-            .entry(post_id)
-            .or_default()
-            .push(comment_id);
+        // Dedup entries from previous days are no longer needed
+        state.recent_post_views.retain(|&day, _| day >= today);
 
-        // Update post comment count
-        post.comment_count = post.comment_count.saturating_add(1);
-        post.updated_at = now;
+        let mut recorded = 0u64;
+        for post_id in post_ids {
+            let countable = state
+                .posts
+                .get(&post_id)
+                .map(|post| {
+                    post.author_id != viewer_id && can_view_post(state, Some(viewer_id), post)
+                })
+                .unwrap_or(false);
 
-        Ok(comment)
+            let first_today = countable
+                && state
+                    .recent_post_views
+                    .entry(today)
+                    .or_default()
+                    .insert((post_id, viewer_id));
+            if first_today {
+                let count = state.post_view_counts.entry(post_id).or_insert(0);
+                *count = count.saturating_add(1);
+                record_engagement(state, post_id, viewer_id, EngagementEvent::View, now);
+                recorded = recorded.saturating_add(1);
+            }
+        }
+
+        Ok(recorded)
     })
 }
 
-/// Gets comments for a post
+/// Gets the deduplicated view count of one of the caller's posts
+///
+/// # Security
+/// * Only the post author can see view counts
 #[query]
-pub fn get_post_comments(
-    post_id: PostId,
-    limit: Option<usize>,
-    offset: Option<usize>,
-) -> Vec<Comment> {
-    let limit = limit.unwrap_or(20).min(100); // Cap at 100 comments
-    let offset = offset.unwrap_or(0);
+pub fn get_post_view_count(post_id: PostId) -> Result<u64, String> {
+    let user_id = authenticate_user()?;
 
     with_state(|state| {
-        state
-            .post_comments
-            .get(&post_id)
-            .map(|comment_ids| {
-                comment_ids
+        let post = state.posts.get(&post_id).ok_or("Post not found")?;
+        if post.author_id != user_id {
+            return Err("Only the post author can see view counts".to_string());
+        }
+        Ok(state.post_view_counts.get(&post_id).copied().unwrap_or(0))
+    })
+}
+
+// ============================================================================
+// CREATOR ANALYTICS
+// ============================================================================
+
+/// Gets the daily engagement of one of the caller's posts
+///
+/// # Arguments
+/// * `post_id` - Post owned by the caller
+/// * `days` - Number of most recent days to return (default 30, max 365)
+///
+/// # Returns
+/// * `Ok(Vec<DailyEngagement>)` - Days with activity, oldest first
+///
+/// # Security
+/// * Only the post author can read its analytics
+#[query]
+pub fn get_post_performance(
+    post_id: PostId,
+    days: Option<u64>,
+) -> Result<Vec<DailyEngagement>, String> {
+    let user_id = authenticate_user()?;
+
+    with_state(|state| {
+        let post = state.posts.get(&post_id).ok_or("Post not found")?;
+        if post.author_id != user_id {
+            return Err("Only the post author can see post analytics".to_string());
+        }
+        let first_day = analytics_window_start(days, utc_offset_of(state, user_id));
+
+        Ok(state
+            .post_engagement_daily
+            .get(&post_id)
+            .map(|daily| {
+                daily
+                    .range(first_day..)
+                    .map(|(&day, counts)| DailyEngagement {
+                        day,
+                        counts: counts.clone(),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default())
+    })
+}
+
+/// Gets the caller's follower count sampled once per day
+///
+/// # Arguments
+/// * `days` - Number of most recent days to return (default 30, max 365)
+#[query]
+pub fn get_my_follower_growth(days: Option<u64>) -> Result<Vec<FollowerSample>, String> {
+    let user_id = authenticate_user()?;
+
+    Ok(with_state(|state| {
+        let first_day = analytics_window_start(days, utc_offset_of(state, user_id));
+        state
+            .follower_history
+            .get(&user_id)
+            .map(|samples| {
+                samples
                     .iter()
-                    .skip(offset)
-                    .take(limit)
-                    .filter_map(|&comment_id| state.comments.get(&comment_id))
+                    .filter(|sample| sample.day >= first_day)
                     .cloned()
                     .collect()
             })
             .unwrap_or_default()
+    }))
+}
+
+/// Gets the caller's best performing posts by lifetime engagement
+///
+/// Posts are ranked by likes + comments + reposts, with views breaking ties.
+#[query]
+pub fn get_my_top_posts(limit: Option<usize>) -> Result<Vec<PostPerformance>, String> {
+    let user_id = authenticate_user()?;
+    let limit = limit.unwrap_or(10).min(MAX_TOP_POSTS_LIMIT);
+
+    Ok(with_state(|state| {
+        let mut ranked: Vec<PostPerformance> = state
+            .author_post_totals
+            .get(&user_id)
+            .map(|totals| {
+                totals
+                    .iter()
+                    .filter(|(post_id, _)| state.posts.contains_key(post_id))
+                    .map(|(&post_id, totals)| PostPerformance {
+                        post_id,
+                        totals: totals.clone(),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        ranked.sort_by_key(|entry| {
+            let totals = &entry.totals;
+            std::cmp::Reverse((
+                totals
+                    .likes
+                    .saturating_add(totals.comments)
+                    .saturating_add(totals.reposts),
+                totals.views,
+            ))
+        });
+        ranked.truncate(limit);
+        ranked
+    }))
+}
+
+/// Internal function to fold an engagement event into the analytics aggregates
+///
+/// Authors engaging with their own posts are ignored, so they can neither
+/// inflate their stats nor earn points from them.
+fn record_engagement(
+    state: &mut SocialNetworkState,
+    post_id: PostId,
+    actor: UserId,
+    event: EngagementEvent,
+    now: u64,
+) {
+    let Some(author_id) = state
+        .posts
+        .get(&post_id)
+        .map(|post| post.author_id)
+        .filter(|author_id| *author_id != actor)
+    else {
+        return;
+    };
+
+    // Bucket by the author's local day
+    let today = local_time::local_day(now, utc_offset_of(state, author_id));
+    let daily = state.post_engagement_daily.entry(post_id).or_default();
+    daily.entry(today).or_default().apply(event);
+
+    // Keep the per-post series bounded
+    while daily.len() as u64 > MAX_ANALYTICS_DAYS {
+        daily.pop_first();
+    }
+
+    state
+        .author_post_totals
+        .entry(author_id)
+        .or_default()
+        .entry(post_id)
+        .or_default()
+        .apply(event);
+
+    let reward = points::engagement_reward(event);
+    if reward > 0 {
+        earn_points(state, author_id, reward, now);
+    }
+}
+
+/// Internal function to append today's follower count for every user
+///
+/// Runs from the daily timer; repeated calls on the same day are no-ops.
+/// Days follow each user's own calendar.
+fn sample_follower_counts(state: &mut SocialNetworkState, now: u64) {
+    for (user_id, profile) in &state.users {
+        let utc_offset = state
+            .user_settings
+            .get(user_id)
+            .and_then(|settings| settings.utc_offset_minutes)
+            .unwrap_or(0);
+        let today = local_time::local_day(now, utc_offset);
+        let samples = state.follower_history.entry(*user_id).or_default();
+        if samples.last().map(|sample| sample.day) == Some(today) {
+            continue;
+        }
+
+        samples.push(FollowerSample {
+            day: today,
+            follower_count: profile.follower_count,
+        });
+        if samples.len() as u64 > MAX_ANALYTICS_DAYS {
+            samples.remove(0);
+        }
+    }
+}
+
+/// Internal function to mark a user as active today
+///
+/// Called for every authenticated update call; buckets older than the
+/// activity window are dropped as new days start.
+fn record_active_user(state: &mut SocialNetworkState, user_id: UserId, now: u64) {
+    let today = now / NANOS_PER_DAY;
+    let first_day = today.saturating_sub(ACTIVITY_WINDOW_DAYS.saturating_sub(1));
+
+    state.daily_active_users.retain(|&day, _| day >= first_day);
+    state
+        .daily_active_users
+        .entry(today)
+        .or_default()
+        .insert(user_id);
+}
+
+/// Internal function to increment today's bucket of a rolling daily counter
+fn bump_daily_counter(counters: &mut BTreeMap<u64, u64>, now: u64) {
+    let today = now / NANOS_PER_DAY;
+    let first_day = today.saturating_sub(ACTIVITY_WINDOW_DAYS.saturating_sub(1));
+
+    counters.retain(|&day, _| day >= first_day);
+    let count = counters.entry(today).or_insert(0);
+    *count = count.saturating_add(1);
+}
+
+/// Internal function to compute the first day included in an analytics window
+fn analytics_window_start(days: Option<u64>, utc_offset_minutes: i16) -> u64 {
+    let days = days
+        .unwrap_or(DEFAULT_ANALYTICS_DAYS)
+        .clamp(1, MAX_ANALYTICS_DAYS);
+    local_time::local_day(time(), utc_offset_minutes).saturating_sub(days.saturating_sub(1))
+}
+
+/// Internal function to look up a user's UTC offset (0 when unset)
+fn utc_offset_of(state: &SocialNetworkState, user_id: UserId) -> i16 {
+    state
+        .user_settings
+        .get(&user_id)
+        .and_then(|settings| settings.utc_offset_minutes)
+        .unwrap_or(0)
+}
+
+// ============================================================================
+// COMMENT SYSTEM
+// ============================================================================
+
+/// Adds a comment to a post
+///
+/// # Security
+/// * Commenting as an organization (`as_org`) requires an Admin or Editor role
+/// * Commenting as another user requires their Comment capability grant
+/// * Enforces the post author's `comment_audience` setting and blocks
+#[update]
+pub async fn add_comment(
+    post_id: PostId,
+    content: String,
+    as_org: Option<Principal>,
+) -> Result<Comment, String> {
+    let user_id = resolve_author_for(authenticate_for(AppScope::Post)?, as_org, "create_comment")?;
+
+    // Validate content
+    validate_comment_content(&content).map_err(invalid("add_comment"))?;
+
+    // Check rate limiting
+    check_rate_limit(&user_id, "add_comment", 30, 60)?; // 30 comments per minute
+
+    with_state_mut(|state| {
+        // Check if post exists, is visible, and the author accepts comments from this user
+        let author_id = state
+            .posts
+            .get(&post_id)
+            .filter(|post| can_view_post(state, Some(user_id), post))
+            .ok_or("Post not found")?
+            .author_id;
+        check_interaction_audience(state, author_id, user_id, |settings| {
+            &settings.comment_audience
+        })
+        .map_err(|_| "You cannot comment on this post".to_string())?;
+        if is_archived(state, post_id) {
+            return Err("Archived posts cannot be commented on".to_string());
+        }
+
+        let post = state.posts.get_mut(&post_id).ok_or("Post not found")?;
+
+        let comment_id = CommentId(state.next_comment_id);
+        state.next_comment_id = state.next_comment_id.saturating_add(1);
+
+        let now = time();
+        let comment = Comment {
+            id: comment_id,
+            post_id,
+            author_id: user_id,
+            content,
+            rendered_content: String::new(),
+            created_at: now,
+            updated_at: now,
+            filtered: None,
+        };
+
+        state.comments.insert(comment_id, comment.clone());
+        state
+            .post_comments
+            .entry(post_id)
+            .or_default()
+            .push(comment_id);
+        state
+            .user_comments
+            .entry(user_id)
+            .or_default()
+            .insert(comment_id);
+
+        // Update post comment count
+        post.comment_count = post.comment_count.saturating_add(1);
+        post.updated_at = now;
+
+        record_engagement(state, post_id, user_id, EngagementEvent::Comment, now);
+        notify_post_activity(state, post_id, user_id, PostActivityKind::Comment, now);
+        if author_id != user_id {
+            queue_webhook_event(
+                state,
+                author_id,
+                webhook::EventData::Comment {
+                    post_id,
+                    comment_id,
+                    author: user_id,
+                },
+                now,
+            );
+        }
+        Ok(comment_for_viewer(state, Some(user_id), &comment))
+    })
+}
+
+/// Gets comments for a post
+#[query]
+pub fn get_post_comments(
+    post_id: PostId,
+    limit: Option<usize>,
+    offset: Option<usize>,
+) -> PostCommentsPage {
+    let limit = limit.unwrap_or(20).min(100); // Cap at 100 comments
+    let offset = offset.unwrap_or(0);
+    let viewer = current_viewer();
+
+    with_state(|state| {
+        // Comments on restricted posts are as restricted as the post itself
+        let post_visible = state
+            .posts
+            .get(&post_id)
+            .map(|post| can_view_post(state, viewer, post))
+            .unwrap_or(false);
+        let comment_ids = state
+            .post_comments
+            .get(&post_id)
+            .filter(|_| post_visible)
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+
+        let (page, next_cursor) = page_at(comment_ids.iter(), offset, limit);
+        PostCommentsPage {
+            comments: page
+                .into_iter()
+                .filter_map(|comment_id| state.comments.get(comment_id))
+                .map(|comment| comment_for_viewer(state, viewer, comment))
+                .collect(),
+            total: Some(comment_ids.len() as u64),
+            has_more: next_cursor.is_some(),
+            next_cursor,
+        }
     })
 }
 
@@ -636,10 +2796,168 @@ pub fn get_platform_stats() -> PlatformStats {
     })
 }
 
+/// Gets platform totals plus rolling activity metrics
+///
+/// # Returns
+/// * Daily/weekly/monthly active users (principals making update calls)
+/// * New signups and posts per day over the last 30 days
+#[query]
+pub fn get_extended_platform_stats() -> ExtendedPlatformStats {
+    let today = time() / NANOS_PER_DAY;
+    let totals = get_platform_stats();
+
+    with_state(|state| {
+        let active_since = |days: u64| -> u64 {
+            let first_day = today.saturating_sub(days.saturating_sub(1));
+            state
+                .daily_active_users
+                .range(first_day..)
+                .flat_map(|(_, users)| users.iter())
+                .collect::<BTreeSet<_>>()
+                .len() as u64
+        };
+        let series = |counters: &BTreeMap<u64, u64>| -> Vec<DailyCount> {
+            counters
+                .iter()
+                .map(|(&day, &count)| DailyCount { day, count })
+                .collect()
+        };
+
+        ExtendedPlatformStats {
+            totals,
+            daily_active_users: active_since(1),
+            weekly_active_users: active_since(7),
+            monthly_active_users: active_since(ACTIVITY_WINDOW_DAYS),
+            signups_per_day: series(&state.daily_signups),
+            posts_per_day: series(&state.daily_posts),
+        }
+    })
+}
+
 /// Health check endpoint
+///
+/// Reports when the canister is low on cycles and optional features are paused.
 #[query]
 pub fn health_check() -> String {
-    "deCentra backend is healthy".to_string()
+    if is_low_on_cycles() {
+        "deCentra backend is low on cycles; optional features are paused".to_string()
+    } else {
+        "deCentra backend is healthy".to_string()
+    }
+}
+
+/// Gets the canister's cycle balance, burn rate and balance history
+#[query]
+pub fn get_canister_metrics() -> CanisterMetrics {
+    let cycles_balance = ic_cdk::api::canister_balance128();
+    with_state(|state| {
+        let low_cycles_threshold = low_cycles_threshold(state);
+        let daily_cycles_burn = cycles::daily_burn(&state.cycles_history);
+        CanisterMetrics {
+            cycles_balance,
+            low_cycles_threshold,
+            low_cycles: cycles_balance < low_cycles_threshold,
+            daily_cycles_burn,
+            estimated_days_remaining: daily_cycles_burn
+                .and_then(|burn| cycles::days_remaining(cycles_balance, burn)),
+            cycles_history: state.cycles_history.clone(),
+            error_counts: state
+                .error_counts
+                .iter()
+                .map(|((endpoint, category), &count)| ErrorCount {
+                    endpoint: endpoint.clone(),
+                    category: *category,
+                    count,
+                })
+                .collect(),
+            compression: state.compression_stats.clone(),
+        }
+    })
+}
+
+/// Sets the cycle balance below which optional features are paused
+///
+/// While the balance is below the threshold, outcalls (translation, link
+/// safety, federation) and avatar uploads are refused or skipped.
+///
+/// # Security
+/// * Admins only
+/// * Recorded in the audit log
+#[update]
+pub async fn set_low_cycles_threshold(threshold: u128) -> Result<(), String> {
+    let admin_id = authenticate_user()?;
+    require_admin(&admin_id)?;
+
+    with_state_mut(|state| {
+        state.low_cycles_threshold = Some(threshold);
+        record_audit_event(
+            state,
+            admin_id,
+            AuditAction::CyclesThresholdChanged,
+            None,
+            threshold.to_string(),
+        );
+    });
+    Ok(())
+}
+
+/// Internal function to record the current cycle balance
+fn sample_cycles_balance(state: &mut SocialNetworkState, now: u64) {
+    state.cycles_history.push(CyclesSample {
+        timestamp: now,
+        balance: ic_cdk::api::canister_balance128(),
+    });
+    if state.cycles_history.len() > MAX_CYCLES_SAMPLES {
+        state.cycles_history.remove(0);
+    }
+}
+
+/// Internal function to count a failed update call in the error metrics
+///
+/// Query calls cannot persist state, so only update calls are counted;
+/// native builds (unit tests) have no canister state to count in.
+fn record_error(endpoint: &str, category: ErrorCategory) {
+    if cfg!(target_arch = "wasm32") && ic_cdk::api::in_replicated_execution() {
+        with_state_mut(|state| count_error(state, endpoint, category));
+    }
+}
+
+/// Internal function to increment an error counter
+fn count_error(state: &mut SocialNetworkState, endpoint: &str, category: ErrorCategory) {
+    *state
+        .error_counts
+        .entry((endpoint.to_string(), category))
+        .or_default() += 1;
+}
+
+/// Internal function to count a validation failure of `endpoint` and pass the error on
+fn invalid(endpoint: &'static str) -> impl FnOnce(String) -> String {
+    move |error| {
+        record_error(endpoint, ErrorCategory::Validation);
+        error
+    }
+}
+
+/// Internal function to read the configured low cycles threshold
+fn low_cycles_threshold(state: &SocialNetworkState) -> u128 {
+    state
+        .low_cycles_threshold
+        .unwrap_or(DEFAULT_LOW_CYCLES_THRESHOLD)
+}
+
+/// Internal function to check whether optional features should be paused
+fn is_low_on_cycles() -> bool {
+    ic_cdk::api::canister_balance128() < with_state(low_cycles_threshold)
+}
+
+/// Internal function to refuse a cycle-hungry optional feature while cycles are low
+fn require_cycles_headroom(feature: &str) -> Result<(), String> {
+    if is_low_on_cycles() {
+        return Err(format!(
+            "{feature} is temporarily unavailable while the platform is low on cycles"
+        ));
+    }
+    Ok(())
 }
 
 // ============================================================================
@@ -651,32 +2969,89 @@ async fn ensure_user_profile(user_id: UserId) -> Result<(), String> {
     let has_profile = with_state(|state| state.users.contains_key(&user_id));
 
     if !has_profile {
-        let default_profile = UserProfile {
-            id: user_id,
-            username: format!(
-                "user_{}",
-                user_id.0.to_text().chars().take(8).collect::<String>()
-            ),
-            bio: "New deCentra user".to_string(),
-            avatar: "👤".to_string(),
-            created_at: time(),
-            updated_at: time(),
-            follower_count: 0,
-            following_count: 0,
-            post_count: 0,
-            privacy_settings: PrivacySettings::default(),
-            verification_status: VerificationStatus::Unverified,
-        };
-
         with_state_mut(|state| {
+            let handle = default_handle(state, user_id);
+            let default_profile = UserProfile {
+                id: user_id,
+                display_name: handle.clone(),
+                handle: handle.clone(),
+                bio: "New deCentra user".to_string(),
+                avatar: "👤".to_string(),
+                banner: String::new(),
+                theme: ProfileTheme::default(),
+                created_at: time(),
+                updated_at: time(),
+                follower_count: 0,
+                following_count: 0,
+                post_count: 0,
+                privacy_settings: PrivacySettings::default(),
+                verification_status: VerificationStatus::Unverified,
+                personhood: None,
+                nft_avatar: None,
+                version: 0,
+            };
+
+            state
+                .handle_index
+                .insert(normalize_handle(&handle), user_id);
             state.users.insert(user_id, default_profile);
             state.user_posts.insert(user_id, Vec::new());
+            bump_daily_counter(&mut state.daily_signups, time());
         });
     }
 
     Ok(())
 }
 
+/// Normalizes a handle for case-insensitive index lookups
+fn normalize_handle(handle: &str) -> String {
+    handle.trim().trim_start_matches('@').to_lowercase()
+}
+
+/// Resolves a handle to its current owner, following unexpired redirects
+fn resolve_handle(state: &SocialNetworkState, handle: &str, now: u64) -> Option<UserId> {
+    let key = normalize_handle(handle);
+    state.handle_index.get(&key).copied().or_else(|| {
+        state
+            .handle_redirects
+            .get(&key)
+            .filter(|redirect| now < redirect.expires_at)
+            .map(|redirect| redirect.user_id)
+    })
+}
+
+/// Whether `user_id` may claim a (normalized) handle: it must be unused and not
+/// reserved by an unexpired redirect to someone else
+fn is_handle_available(state: &SocialNetworkState, key: &str, user_id: UserId, now: u64) -> bool {
+    if state.handle_index.contains_key(key) {
+        return false;
+    }
+
+    state
+        .handle_redirects
+        .get(key)
+        .map(|redirect| redirect.user_id == user_id || now >= redirect.expires_at)
+        .unwrap_or(true)
+}
+
+/// Picks an unused `user_<principal prefix>` handle for auto-created profiles
+fn default_handle(state: &SocialNetworkState, user_id: UserId) -> String {
+    let principal_text: String = user_id
+        .0
+        .to_text()
+        .chars()
+        .filter(|c| c.is_alphanumeric())
+        .collect();
+
+    (8..=principal_text.len())
+        .map(|len| format!("user_{}", &principal_text[..len]))
+        .find(|handle| {
+            let key = normalize_handle(handle);
+            !state.handle_index.contains_key(&key) && !state.handle_redirects.contains_key(&key)
+        })
+        .unwrap_or_else(|| format!("user_{principal_text}"))
+}
+
 // ============================================================================
 // SOCIAL GRAPH MANAGEMENT (FOLLOW/UNFOLLOW SYSTEM)
 // ============================================================================
@@ -735,8 +3110,35 @@ async fn ensure_user_profile(user_id: UserId) -> Result<(), String> {
 #[update]
 pub async fn follow_user(target_user_id: Principal) -> Result<(), String> {
     let follower_id = authenticate_user()?;
-    let target_id = UserId(target_user_id);
+    request_follow(follower_id, UserId(target_user_id), None)
+}
+
+/// Follows a user, attaching a message for private profiles
+///
+/// # Arguments
+/// * `target_user_id` - Principal of the user to follow
+/// * `message` - Introduction shown to the target with the follow request (1-280 chars)
+///
+/// # Behavior
+/// Same as `follow_user`. Public profiles are followed immediately and the
+/// message is dropped; otherwise it is stored on the pending request and
+/// returned by `get_pending_follow_requests`.
+#[update]
+pub async fn follow_user_with_message(
+    target_user_id: Principal,
+    message: String,
+) -> Result<(), String> {
+    let follower_id = authenticate_user()?;
+    validate_follow_request_message(&message)?;
+    request_follow(follower_id, UserId(target_user_id), Some(message))
+}
 
+/// Internal function to follow a user or request to follow them
+fn request_follow(
+    follower_id: UserId,
+    target_id: UserId,
+    message: Option<String>,
+) -> Result<(), String> {
     // Prevent self-following
     if follower_id == target_id {
         return Err("Cannot follow yourself".to_string());
@@ -781,6 +3183,18 @@ pub async fn follow_user(target_user_id: Principal) -> Result<(), String> {
         return Err("Following limit exceeded".to_string());
     }
 
+    // Gate mass following once the daily allowance is used up
+    let now = time();
+    let follows_today = with_state_mut(|state| {
+        let follows = state.follows_made.entry(follower_id).or_default();
+        follows.retain(|&at| now.saturating_sub(at) < NANOS_PER_DAY);
+        follows.len()
+    });
+    if follows_today >= UNGATED_DAILY_FOLLOWS {
+        check_action_gate(follower_id, "follow_user")?;
+    }
+    with_state_mut(|state| state.follows_made.entry(follower_id).or_default().push(now));
+
     // Handle follow based on target user's privacy settings
     match target_profile.privacy_settings.profile_visibility {
         ProfileVisibility::Public => {
@@ -789,7 +3203,7 @@ pub async fn follow_user(target_user_id: Principal) -> Result<(), String> {
         }
         ProfileVisibility::FollowersOnly | ProfileVisibility::Private => {
             // Send follow request for private profiles
-            create_follow_request(follower_id, target_id, None)?;
+            create_follow_request(follower_id, target_id, message)?;
         }
     }
 
@@ -851,21 +3265,53 @@ pub async fn unfollow_user(target_user_id: Principal) -> Result<(), String> {
     Ok(())
 }
 
-/// Approves a pending follow request
-///
-/// # Purpose
-/// Allows users with private profiles to approve follow requests,
-/// converting them into actual follow relationships.
+/// Removes a user from the caller's followers without blocking them
 ///
 /// # Arguments
-/// * `request_id` - ID of the follow request to approve
+/// * `follower` - Principal of the follower to remove
 ///
 /// # Returns
-/// * `Ok(())` - Successfully approved request and created follow relationship
+/// * `Ok(())` - Follower removed; both connection sets and counters updated
 /// * `Err(String)` - Validation error or operation failure
 ///
+/// # Errors
+/// - "User does not exist" - Follower not found
+/// - "User is not following you" - No existing follow relationship
+///
 /// # Security
-/// * Only the target user can approve their own follow requests
+/// * Only affects relationships targeting the caller
+/// * The removed user may follow again (or request to, for private profiles)
+#[update]
+pub async fn remove_follower(follower: Principal) -> Result<(), String> {
+    let user_id = authenticate_user()?;
+    let follower_id = UserId(follower);
+
+    if !with_state(|state| state.users.contains_key(&follower_id)) {
+        return Err("User does not exist".to_string());
+    }
+
+    if !with_state(|state| is_follower_of(state, follower_id, user_id)) {
+        return Err("User is not following you".to_string());
+    }
+
+    execute_unfollow(follower_id, user_id)
+}
+
+/// Approves a pending follow request
+///
+/// # Purpose
+/// Allows users with private profiles to approve follow requests,
+/// converting them into actual follow relationships.
+///
+/// # Arguments
+/// * `request_id` - ID of the follow request to approve
+///
+/// # Returns
+/// * `Ok(())` - Successfully approved request and created follow relationship
+/// * `Err(String)` - Validation error or operation failure
+///
+/// # Security
+/// * Only the target user can approve their own follow requests
 /// * Validates request exists and is still pending
 /// * Atomically converts request to follow relationship
 #[update]
@@ -926,6 +3372,33 @@ pub async fn reject_follow_request(request_id: u64) -> Result<(), String> {
     Ok(())
 }
 
+/// Cancels a pending follow request sent by the caller
+///
+/// # Security
+/// * Only the requester can cancel their own follow requests
+#[update]
+pub async fn cancel_follow_request(request_id: u64) -> Result<(), String> {
+    let requester_id = authenticate_user()?;
+
+    with_state_mut(|state| {
+        let request = state
+            .follow_requests
+            .get_mut(&request_id)
+            .ok_or("Follow request not found")?;
+
+        if request.requester != requester_id {
+            return Err("Not authorized to cancel this request".to_string());
+        }
+
+        if !matches!(request.status, FollowRequestStatus::Pending) {
+            return Err("Follow request is not pending".to_string());
+        }
+
+        request.status = FollowRequestStatus::Cancelled;
+        Ok(())
+    })
+}
+
 /// Gets the list of users that the specified user follows
 ///
 /// # Arguments
@@ -934,7 +3407,7 @@ pub async fn reject_follow_request(request_id: u64) -> Result<(), String> {
 /// * `offset` - Number of results to skip for pagination (optional)
 ///
 /// # Returns
-/// * `Ok(Vec<UserProfile>)` - List of user profiles that the user follows
+/// * `Ok(ProfilesPage)` - User profiles that the user follows
 /// * `Err(String)` - Error if user not found or privacy restrictions
 ///
 /// # Privacy
@@ -945,7 +3418,8 @@ pub fn get_following(
     user_id: Principal,
     limit: Option<usize>,
     offset: Option<usize>,
-) -> Result<Vec<UserProfile>, String> {
+) -> Result<ProfilesPage, String> {
+    check_query_quota("get_following")?;
     let user_id = UserId(user_id);
     let caller_id = UserId(caller());
 
@@ -963,21 +3437,13 @@ pub fn get_following(
         .min(MAX_CONNECTIONS_LIMIT);
     let offset = offset.unwrap_or(0);
 
-    let following_profiles = with_state(|state| {
-        let connections = state.social_connections.get(&user_id);
-        match connections {
-            Some(conn) => conn
-                .following
-                .iter()
-                .skip(offset)
-                .take(limit)
-                .filter_map(|&following_id| state.users.get(&following_id).cloned())
-                .collect(),
-            None => Vec::new(),
-        }
-    });
-
-    Ok(following_profiles)
+    Ok(with_state(|state| {
+        let following = state
+            .social_connections
+            .get(&user_id)
+            .map(|conn| &conn.following);
+        profiles_page(state, following, offset, limit)
+    }))
 }
 
 /// Gets the list of users that follow the specified user
@@ -994,7 +3460,8 @@ pub fn get_followers(
     user_id: Principal,
     limit: Option<usize>,
     offset: Option<usize>,
-) -> Result<Vec<UserProfile>, String> {
+) -> Result<ProfilesPage, String> {
+    check_query_quota("get_followers")?;
     let user_id = UserId(user_id);
     let caller_id = UserId(caller());
 
@@ -1010,24 +3477,45 @@ pub fn get_followers(
         .min(MAX_CONNECTIONS_LIMIT);
     let offset = offset.unwrap_or(0);
 
-    let followers_profiles = with_state(|state| {
-        let connections = state.social_connections.get(&user_id);
-        match connections {
-            Some(conn) => conn
-                .followers
-                .iter()
-                .skip(offset)
-                .take(limit)
-                .filter_map(|&follower_id| state.users.get(&follower_id).cloned())
-                .collect(),
-            None => Vec::new(),
-        }
-    });
+    Ok(with_state(|state| {
+        let followers = state
+            .social_connections
+            .get(&user_id)
+            .map(|conn| &conn.followers);
+        profiles_page(state, followers, offset, limit)
+    }))
+}
+
+/// Internal function to page through a following or followers list
+fn profiles_page(
+    state: &SocialNetworkState,
+    user_ids: Option<&BTreeSet<UserId>>,
+    offset: usize,
+    limit: usize,
+) -> ProfilesPage {
+    let Some(user_ids) = user_ids else {
+        return ProfilesPage {
+            profiles: Vec::new(),
+            total: Some(0),
+            has_more: false,
+            next_cursor: None,
+        };
+    };
 
-    Ok(followers_profiles)
+    let (page, next_cursor) = page_at(user_ids.iter(), offset, limit);
+    ProfilesPage {
+        profiles: page
+            .into_iter()
+            .filter_map(|user_id| state.users.get(user_id).cloned())
+            .collect(),
+        total: Some(user_ids.len() as u64),
+        has_more: next_cursor.is_some(),
+        next_cursor,
+    }
 }
 
-/// Gets pending follow requests for the authenticated user
+/// Gets pending follow requests for the authenticated user, including any
+/// message the requester attached
 ///
 /// # Returns
 /// * `Ok(Vec<FollowRequest>)` - List of pending follow requests
@@ -1053,6 +3541,32 @@ pub fn get_pending_follow_requests() -> Result<Vec<FollowRequest>, String> {
     Ok(pending_requests)
 }
 
+/// Gets pending follow requests sent by the authenticated user
+///
+/// # Returns
+/// * `Ok(Vec<FollowRequest>)` - Pending outgoing requests, oldest first
+/// * `Err(String)` - Authentication error
+///
+/// # Security
+/// * Only returns requests where the caller is the requester
+#[query]
+pub fn get_my_sent_follow_requests() -> Result<Vec<FollowRequest>, String> {
+    let user_id = authenticate_user()?;
+
+    let sent_requests = with_state(|state| {
+        state
+            .follow_requests
+            .values()
+            .filter(|req| {
+                req.requester == user_id && matches!(req.status, FollowRequestStatus::Pending)
+            })
+            .cloned()
+            .collect()
+    });
+
+    Ok(sent_requests)
+}
+
 /// Checks if user A follows user B
 ///
 /// # Arguments
@@ -1077,127 +3591,942 @@ pub fn is_following(follower_id: Principal, target_id: Principal) -> Result<bool
     Ok(is_following)
 }
 
-/// Checks if a username is available for registration
+/// Gets accounts the caller follows that also follow the target
+///
+/// # Arguments
+/// * `target_id` - Profile being viewed
+/// * `limit` - Maximum number of profiles returned (optional, defaults to DEFAULT_CONNECTIONS_LIMIT)
+///
+/// # Returns
+/// * `Ok(FollowersYouKnow)` - First matching profiles and the total match count
+/// * `Err(String)` - Authentication error, unknown user or private social graph
+///
+/// # Privacy
+/// * Requires the target to show their social graph (unless the caller is the target)
+/// * Accounts that hide their own social graph are never listed or counted
+#[query]
+pub fn get_followers_you_know(
+    target_id: Principal,
+    limit: Option<usize>,
+) -> Result<FollowersYouKnow, String> {
+    let caller_id = authenticate_user()?;
+    let target_id = UserId(target_id);
+    let limit = limit
+        .unwrap_or(DEFAULT_CONNECTIONS_LIMIT)
+        .min(MAX_CONNECTIONS_LIMIT);
+
+    with_state(|state| {
+        check_social_graph_visible(state, caller_id, target_id)?;
+
+        let mut known = followers_you_know(state, caller_id, target_id);
+        let profiles = known
+            .by_ref()
+            .take(limit)
+            .filter_map(|user_id| state.users.get(&user_id))
+            .map(|profile| profile_for_viewer(state, profile, Some(caller_id)))
+            .collect::<Vec<_>>();
+        let total = (profiles.len() + known.count()) as u64;
+
+        Ok(FollowersYouKnow { profiles, total })
+    })
+}
+
+/// Gets how much of the target's social graph overlaps with the caller's
+///
+/// # Arguments
+/// * `target_id` - Profile being viewed
+///
+/// # Returns
+/// * `Ok(CommonFollowCounts)` - Followers the caller knows and accounts both follow
+/// * `Err(String)` - Authentication error, unknown user or private social graph
+///
+/// # Privacy
+/// * Requires the target to show their social graph (unless the caller is the target)
+#[query]
+pub fn get_common_follow_counts(target_id: Principal) -> Result<CommonFollowCounts, String> {
+    let caller_id = authenticate_user()?;
+    let target_id = UserId(target_id);
+
+    with_state(|state| {
+        check_social_graph_visible(state, caller_id, target_id)?;
+
+        let common_following = match (
+            state.following_index.get(&caller_id),
+            state.following_index.get(&target_id),
+        ) {
+            (Some(mine), Some(theirs)) => mine.intersection(theirs).count() as u64,
+            _ => 0,
+        };
+
+        Ok(CommonFollowCounts {
+            followers_you_know: followers_you_know(state, caller_id, target_id).count() as u64,
+            common_following,
+        })
+    })
+}
+
+/// Checks if a username (handle) is available for registration
 ///
 /// # Purpose
 /// Validates username format and checks availability for real-time frontend validation.
 /// Used by profile creation forms to provide immediate feedback to users.
 ///
 /// # Arguments
-/// * `username` - Username to check (3-50 chars, alphanumeric + _ -)
+/// * `username` - Handle to check (3-50 chars, alphanumeric + _ -, case-insensitive)
+/// * `proof_of_work` - Nonce for the subject `"{caller}:{bucket}:{username}"`,
+///   required from anonymous callers while `get_username_check_challenge`
+///   asks for one
 ///
 /// # Returns
 /// * `Ok(true)` - Username is available and valid
-/// * `Ok(false)` - Username is taken but format is valid
-/// * `Err(String)` - Username format is invalid
+/// * `Ok(false)` - Username is taken or reserved
+/// * `Err(String)` - Username format is invalid, proof of work missing or quota reached
 ///
 /// # Security
-/// * No authentication required (public query)
-/// * Validates format before checking availability
-/// * Rate limited to prevent username enumeration attacks
+/// * No authentication required, but an update call so checks can be counted
+/// * USERNAME_CHECKS_PER_MINUTE per signed-in caller; `inspect_message`
+///   drops over-quota calls and calls without a valid proof before they
+///   use cycles
+/// * Anonymous callers share one principal, so they have no quota; admins
+///   can instead make them carry a proof of work (see
+///   `security_utils::verify_proof_of_work`). Proofs are bound to the caller
+///   and a USERNAME_CHECK_POW_BUCKET_SECONDS time bucket, so they cannot be
+///   computed ahead of time or replayed later
+/// * Reserved words and handles held by a redirect answer exactly like taken
+///   handles, and every answer is delayed by a random number of rounds, so
+///   probing cannot tell them apart
 ///
 /// # Example
 /// ```rust
-/// let available = check_username_availability("alice_doe".to_string())?;
+/// let available = check_username_availability("alice_doe".to_string(), Some(nonce)).await?;
 /// if available {
 ///     println!("Username is available!");
 /// }
 /// ```
+#[update]
+pub async fn check_username_availability(
+    username: String,
+    proof_of_work: Option<u64>,
+) -> Result<bool, String> {
+    let caller_id = UserId(caller());
+    screen_username_check(caller_id.0, &username, proof_of_work, time())?;
+    if caller_id.0 != Principal::anonymous() {
+        check_rate_limit(
+            &caller_id,
+            "check_username_availability",
+            USERNAME_CHECKS_PER_MINUTE,
+            60,
+        )?;
+    }
+
+    let available = if is_reserved_username(&username) {
+        false
+    } else {
+        validate_username(&username)?;
+        with_state(|state| resolve_handle(state, &username, time()).is_none())
+    };
+    username_check_jitter().await;
+    Ok(available)
+}
+
+/// Internal function to delay a username check answer by a random number of
+/// rounds drawn from the entropy pool
+///
+/// Each round is a call to the canister's own `health_check`, which costs
+/// far less than a management canister call.
+async fn username_check_jitter() {
+    let rounds = with_state_mut(|state| {
+        security_utils::next_random_bytes(&mut state.entropy_pool)[0]
+            % (MAX_USERNAME_CHECK_JITTER_ROUNDS + 1)
+    });
+    for _ in 0..rounds {
+        let _ = ic_cdk::call::<(), (String,)>(ic_cdk::id(), "health_check", ()).await;
+    }
+}
+
+/// Gets the proof of work anonymous username checks must carry
 #[query]
-pub fn check_username_availability(username: String) -> Result<bool, String> {
-    // Validate username format first
-    validate_username(&username)?;
-    
+pub fn get_username_check_challenge() -> UsernameCheckChallenge {
+    UsernameCheckChallenge {
+        difficulty: with_state(|state| state.username_check_difficulty),
+        bucket: username_check_bucket(time()),
+    }
+}
+
+/// Sets the proof-of-work bits anonymous username checks must carry
+///
+/// # Arguments
+/// * `bits` - Up to MAX_USERNAME_CHECK_DIFFICULTY; each bit doubles the work
+///   per check. 0 turns the proof off
+///
+/// # Security
+/// * Admins only
+/// * Recorded in the audit log
+#[update]
+pub async fn set_username_check_difficulty(bits: u8) -> Result<(), String> {
+    let admin_id = authenticate_user()?;
+    require_admin(&admin_id)?;
+    if bits > MAX_USERNAME_CHECK_DIFFICULTY {
+        return Err(format!(
+            "Difficulty must be at most {MAX_USERNAME_CHECK_DIFFICULTY} bits"
+        ));
+    }
+
+    with_state_mut(|state| {
+        state.username_check_difficulty = bits;
+        record_audit_event(
+            state,
+            admin_id,
+            AuditAction::UsernameCheckDifficultyChanged,
+            None,
+            format!("bits: {bits}"),
+        );
+    });
+    Ok(())
+}
+
+/// Internal function to get the proof-of-work time bucket of a time
+fn username_check_bucket(now: u64) -> u64 {
+    now / (USERNAME_CHECK_POW_BUCKET_SECONDS * 1_000_000_000)
+}
+
+/// Internal function to build the subject a username check proof of work is
+/// computed over
+fn username_check_subject(caller: Principal, bucket: u64, username: &str) -> String {
+    format!("{caller}:{bucket}:{}", normalize_handle(username))
+}
+
+/// Internal function to screen a username check against the caller's proof
+/// of work and quota without recording it
+///
+/// Shared by `check_username_availability` and `inspect_message`, so calls
+/// that would be rejected are dropped before they use cycles.
+fn screen_username_check(
+    caller: Principal,
+    username: &str,
+    proof_of_work: Option<u64>,
+    now: u64,
+) -> Result<(), String> {
+    let window_start = now.saturating_sub(60 * 1_000_000_000);
     with_state(|state| {
-        let available = !state.users.values()
-            .any(|profile| profile.username == username);
-        Ok(available)
+        let difficulty = state.username_check_difficulty;
+        if caller == Principal::anonymous() && difficulty > 0 {
+            let bucket = username_check_bucket(now);
+            let solved = proof_of_work.is_some_and(|nonce| {
+                [bucket, bucket.saturating_sub(1)].iter().any(|&bucket| {
+                    let subject = username_check_subject(caller, bucket, username);
+                    security_utils::verify_proof_of_work(&subject, nonce, difficulty)
+                })
+            });
+            if !solved {
+                return Err(format!(
+                    "Proof of work of {difficulty} bits required for anonymous callers"
+                ));
+            }
+        }
+
+        let exhausted = state
+            .rate_limits
+            .get(&(UserId(caller), "check_username_availability".to_string()))
+            .is_some_and(|timestamps| {
+                timestamps
+                    .iter()
+                    .filter(|&&timestamp| timestamp > window_start)
+                    .count()
+                    >= USERNAME_CHECKS_PER_MINUTE as usize
+            });
+        if exhausted && caller != Principal::anonymous() {
+            return Err("Too many username checks; try again in a minute".to_string());
+        }
+        Ok(())
     })
 }
 
+/// Internal function to drop username check quota entries with no check in
+/// the current window
+///
+/// Every principal that checks a username gets an entry, and principals are
+/// free to create, so they would otherwise pile up.
+fn prune_username_check_limits(state: &mut SocialNetworkState, now: u64) {
+    let window_start = now.saturating_sub(60 * 1_000_000_000);
+    state.rate_limits.retain(|(_, action), timestamps| {
+        action != "check_username_availability"
+            || timestamps
+                .last()
+                .is_some_and(|&timestamp| timestamp > window_start)
+    });
+}
+
 // ============================================================================
 // INTERNAL HELPER FUNCTIONS
 // ============================================================================
 
-/// Internal function to execute a follow relationship
-fn execute_follow(follower_id: UserId, target_id: UserId) -> Result<(), String> {
-    with_state_mut(|state| {
-        // Initialize social connections if they don't exist
-        state.social_connections.entry(follower_id).or_default();
-        state.social_connections.entry(target_id).or_default();
+/// Optional attachments of a new post
+#[derive(Default)]
+struct PostExtras {
+    location: Option<PostLocation>,
+    sources: Vec<Citation>,
+    alt_texts: Vec<MediaAltText>,
+    contributors: Vec<UserId>,
 
-        // Add to follower's following list
-        if let Some(follower_conn) = state.social_connections.get_mut(&follower_id) {
-            follower_conn.following.insert(target_id);
-        }
+    /// Original platform of an archived post
+    imported: Option<ImportSource>,
 
-        // Add to target's followers list
-        if let Some(target_conn) = state.social_connections.get_mut(&target_id) {
-            target_conn.followers.insert(follower_id);
+    /// Original publication time of an archived post
+    created_at: Option<u64>,
+}
+
+/// Internal function to store a new post and update the author's indices
+fn insert_post(
+    state: &mut SocialNetworkState,
+    author_id: UserId,
+    content: String,
+    visibility: PostVisibility,
+    language: Option<String>,
+    format: TextFormat,
+    extras: PostExtras,
+) -> PostId {
+    let post_id = PostId(state.next_post_id);
+    state.next_post_id = state.next_post_id.saturating_add(1);
+
+    let now = time();
+    let created_at = extras.created_at.unwrap_or(now);
+    let imported = extras.imported.is_some();
+    let language = language
+        .map(|code| code.to_lowercase())
+        .or_else(|| language::detect_language(&content).map(str::to_string));
+    let mut entities = entities::extract_entities(&content);
+    for entity in &mut entities {
+        // Mentions in archives name accounts on the original platform
+        if entity.kind == EntityKind::Mention && !imported {
+            entity.user_id = resolve_handle(state, &entity.value[1..], now);
+        }
+    }
+    // Mentions the mentioned user's `mention_audience` excludes stay plain text
+    entities.retain(|entity| {
+        entity.user_id.is_none_or(|user_id| {
+            check_interaction_audience(state, user_id, author_id, |settings| {
+                &settings.mention_audience
+            })
+            .is_ok()
+        })
+    });
+    let has_media = feed_filter::has_media(&content);
+    let content = match compression::compress(&content) {
+        Some(compressed) => {
+            let stats = &mut state.compression_stats;
+            stats.compressed_posts = stats.compressed_posts.saturating_add(1);
+            stats.original_bytes = stats.original_bytes.saturating_add(content.len() as u64);
+            stats.stored_bytes = stats.stored_bytes.saturating_add(compressed.len() as u64);
+            state.compressed_post_bodies.insert(post_id, compressed);
+            String::new()
         }
+        None => content,
+    };
+    let post = Post {
+        id: post_id,
+        author_id,
+        content,
+        language,
+        rendered_content: String::new(),
+        filtered: None,
+        link_warnings: Vec::new(),
+        thread: None,
+        format,
+        entities,
+        custom_emoji: Vec::new(),
+        fact_checks: Vec::new(),
+        nft: None,
+        token_gate: None,
+        subscriber_tier: None,
+        unlock_price: None,
+        fundraiser: None,
+        poll: None,
+        audio: None,
+        media_alt_texts: extras.alt_texts,
+        permanent_copy: None,
+        location: extras.location.map(|location| PostLocation {
+            coordinates: location.coordinates.map(GeoPoint::coarse),
+            ..location
+        }),
+        sources: extras.sources,
+        contributors: extras.contributors,
+        imported: extras.imported,
+        archived: false,
+        created_at,
+        updated_at: created_at,
+        repost_count: 0,
+        tips_received: 0,
+        edited_at: None,
+        visibility,
+        like_count: 0,
+        comment_count: 0,
+    };
 
-        // Update indices
-        state
-            .following_index
-            .entry(follower_id)
-            .or_default()
-            .insert(target_id);
+    index_post_location(state, &post);
+    index_post_sources(state, &post);
+    state.posts.insert(post_id, post);
+    state.post_likes.insert(post_id, BTreeSet::new());
+    state.post_comments.insert(post_id, Vec::new());
+
+    // Add to user's posts
+    state.user_posts.entry(author_id).or_default().push(post_id);
+    if has_media {
         state
-            .followers_index
-            .entry(target_id)
+            .user_media_posts
+            .entry(author_id)
             .or_default()
-            .insert(follower_id);
+            .insert(post_id);
+    }
+    if !imported {
+        bump_daily_counter(&mut state.daily_posts, now);
+    }
+    if state.export_authors.contains(&author_id) && state.posts[&post_id].is_syndicated() {
+        state.pending_exports.insert(post_id);
+    }
 
-        // Update user profile counts
-        if let Some(follower_profile) = state.users.get_mut(&follower_id) {
-            follower_profile.following_count = follower_profile.following_count.saturating_add(1);
-            follower_profile.updated_at = time();
-        }
-        if let Some(target_profile) = state.users.get_mut(&target_id) {
-            target_profile.follower_count = target_profile.follower_count.saturating_add(1);
-            target_profile.updated_at = time();
-        }
-    });
+    // Update user's post count
+    if let Some(profile) = state.users.get_mut(&author_id) {
+        profile.post_count = profile.post_count.saturating_add(1);
+        profile.updated_at = now;
+    }
 
-    Ok(())
+    if state.posts[&post_id].is_syndicated() {
+        record_replica_delta(state, |state| {
+            StateDelta::PostCreated(Box::new(inflated_post(state, &state.posts[&post_id])))
+        });
+    }
+    if state.posts[&post_id].is_syndicated() {
+        state
+            .journal
+            .push(JournalEventKind::PostCreated { post_id }, now);
+    }
+    notify_mentions(state, post_id, now);
+
+    post_id
 }
 
-/// Internal function to execute an unfollow relationship
-fn execute_unfollow(follower_id: UserId, target_id: UserId) -> Result<(), String> {
-    with_state_mut(|state| {
-        // Remove from follower's following list
-        if let Some(follower_conn) = state.social_connections.get_mut(&follower_id) {
-            follower_conn.following.remove(&target_id);
-        }
+/// Internal function to list a post with coordinates in the location index
+fn index_post_location(state: &mut SocialNetworkState, post: &Post) {
+    if let Some(point) = post
+        .location
+        .as_ref()
+        .and_then(|location| location.coordinates)
+    {
+        state
+            .located_posts
+            .entry(point.cell())
+            .or_default()
+            .insert(post.id);
+    }
+}
 
-        // Remove from target's followers list
-        if let Some(target_conn) = state.social_connections.get_mut(&target_id) {
-            target_conn.followers.remove(&follower_id);
-        }
+/// Internal function to list a post under each source it cites
+fn index_post_sources(state: &mut SocialNetworkState, post: &Post) {
+    for source in &post.sources {
+        state
+            .cited_sources
+            .entry(citation_key(&source.url))
+            .or_default()
+            .insert(post.id);
+    }
+}
 
-        // Update indices
-        if let Some(following_set) = state.following_index.get_mut(&follower_id) {
-            following_set.remove(&target_id);
-        }
-        if let Some(followers_set) = state.followers_index.get_mut(&target_id) {
-            followers_set.remove(&follower_id);
-        }
+/// Internal function to normalize a source URL for matching citations
+///
+/// Drops the scheme, fragment and trailing slashes and lowercases the host, so
+/// links differing only in those cite the same source.
+fn citation_key(url: &str) -> String {
+    let url = url.split('#').next().unwrap_or_default();
+    let url = url
+        .strip_prefix("https://")
+        .unwrap_or(url)
+        .trim_end_matches('/');
+    match url.split_once('/') {
+        Some((host, path)) => format!("{}/{path}", host.to_lowercase()),
+        None => url.to_lowercase(),
+    }
+}
 
-        // Update user profile counts
-        if let Some(follower_profile) = state.users.get_mut(&follower_id) {
-            follower_profile.following_count = follower_profile.following_count.saturating_sub(1);
-            follower_profile.updated_at = time();
+/// Internal function to reject batch requests above `MAX_BATCH_FETCH_SIZE`
+fn check_batch_size(requested: usize) -> Result<(), String> {
+    if requested > MAX_BATCH_FETCH_SIZE {
+        return Err(SocialNetworkError::BatchSizeTooLarge {
+            max: MAX_BATCH_FETCH_SIZE,
+            requested,
         }
-        if let Some(target_profile) = state.users.get_mut(&target_id) {
-            target_profile.follower_count = target_profile.follower_count.saturating_sub(1);
-            target_profile.updated_at = time();
-        }
-    });
-
+        .into());
+    }
     Ok(())
 }
 
-/// Internal function to create a follow request
+/// Internal function to resolve the caller as a viewer (`None` when anonymous)
+fn current_viewer() -> Option<UserId> {
+    match caller() {
+        caller if caller == Principal::anonymous() => None,
+        // Every application scope allows reading as the owner
+        caller => Some(
+            with_state(|state| state.apps.get(&caller).map(|app| app.owner))
+                .unwrap_or(UserId(caller)),
+        ),
+    }
+}
+
+/// Internal function to look up the caller's error-message locale
+///
+/// Errors are often raised while the state is mutably borrowed; those fall
+/// back to English rather than panicking on a second borrow.
+pub(crate) fn caller_locale() -> Option<String> {
+    if !cfg!(target_arch = "wasm32") {
+        return None;
+    }
+    let caller = caller();
+    STATE.with(|state| {
+        let state = state.try_borrow().ok()?;
+        let user_id = state
+            .apps
+            .get(&caller)
+            .map_or(UserId(caller), |app| app.owner);
+        state.user_settings.get(&user_id)?.locale.clone()
+    })
+}
+
+/// Internal function to filter a profile down to what `viewer` may see
+///
+/// Restricted viewers get a shell with identity fields (handle, display name,
+/// verification) and the profile's visibility, so clients can render a
+/// "this account is private" page. Follower counts are dropped when the owner
+/// hides their social graph.
+fn profile_for_viewer(
+    state: &SocialNetworkState,
+    profile: &UserProfile,
+    viewer: Option<UserId>,
+) -> UserProfile {
+    if viewer == Some(profile.id) {
+        return profile.clone();
+    }
+
+    let settings = &profile.privacy_settings;
+    let full_access = match settings.profile_visibility {
+        ProfileVisibility::Public => true,
+        ProfileVisibility::FollowersOnly => viewer
+            .map(|viewer_id| is_follower_of(state, viewer_id, profile.id))
+            .unwrap_or(false),
+        ProfileVisibility::Private => false,
+    } && !viewer
+        .map(|viewer_id| is_blocked_between(state, profile.id, viewer_id))
+        .unwrap_or(false);
+
+    let mut visible = profile.clone();
+    if !full_access {
+        visible.bio = String::new();
+        visible.avatar = String::new();
+        visible.nft_avatar = None;
+        visible.banner = String::new();
+        visible.theme = ProfileTheme::default();
+        visible.post_count = 0;
+        visible.updated_at = visible.created_at;
+    }
+    if !full_access || !settings.show_social_graph {
+        visible.follower_count = 0;
+        visible.following_count = 0;
+    }
+    if !full_access || viewer.is_none() {
+        visible.privacy_settings = PrivacySettings {
+            profile_visibility: settings.profile_visibility.clone(),
+            ..PrivacySettings::default()
+        };
+    }
+    visible
+}
+
+/// Internal function to read a post's text, decompressing a stored body
+fn post_content<'a>(state: &SocialNetworkState, post: &'a Post) -> Cow<'a, str> {
+    match state.compressed_post_bodies.get(&post.id) {
+        Some(compressed) => Cow::Owned(compression::decompress(compressed)),
+        None => Cow::Borrowed(&post.content),
+    }
+}
+
+/// Internal function to copy a stored post with its text filled in
+fn inflated_post(state: &SocialNetworkState, post: &Post) -> Post {
+    let mut inflated = post.clone();
+    if let Cow::Owned(content) = post_content(state, post) {
+        inflated.content = content;
+    }
+    inflated
+}
+
+/// Internal function to prepare a visible post for the viewer
+///
+/// Applies the viewer's word filter and count hiding, withholds gated content
+/// (including audio and alt texts) the viewer has not unlocked, and fills in the escaped rendering.
+fn post_for_viewer(state: &SocialNetworkState, viewer: Option<UserId>, post: &Post) -> Post {
+    let mut post = inflated_post(state, post);
+    if let Some((content, mode)) = apply_word_filter(state, viewer, post.author_id, &post.content) {
+        if mode == WordFilterMode::Collapse {
+            post.entities.clear();
+        }
+        post.content = content;
+        post.filtered = Some(mode);
+    }
+    if !has_unlocked_post(state, viewer, &post, time()) {
+        post.content = String::new();
+        post.entities.clear();
+        post.sources.clear();
+        post.audio = None;
+        post.media_alt_texts.clear();
+    }
+    if !can_see_post_location(state, viewer, post.author_id) {
+        post.location = None;
+    }
+    if hides_engagement_counts(state, viewer) {
+        post.like_count = 0;
+        post.repost_count = 0;
+    }
+    post.rendered_content = security_utils::escape_html(&post.content);
+    post.thread = thread_position(state, post.id);
+    post.custom_emoji = resolve_custom_emoji(state, post.author_id, &post.content);
+    post.fact_checks = state
+        .fact_checks
+        .get(&post.id)
+        .map(|labels| {
+            labels
+                .iter()
+                .filter(|label| label.in_force())
+                .cloned()
+                .collect()
+        })
+        .unwrap_or_default();
+    post
+}
+
+/// Internal function to check whether a viewer asked not to see like and
+/// repost counts
+fn hides_engagement_counts(state: &SocialNetworkState, viewer: Option<UserId>) -> bool {
+    viewer
+        .and_then(|viewer_id| state.user_settings.get(&viewer_id))
+        .is_some_and(|settings| settings.hide_engagement_counts)
+}
+
+/// Internal function to check the author's `location_audience` for a viewer
+fn can_see_post_location(
+    state: &SocialNetworkState,
+    viewer: Option<UserId>,
+    author_id: UserId,
+) -> bool {
+    match viewer {
+        Some(viewer_id) => {
+            check_interaction_audience(state, author_id, viewer_id, |s| &s.location_audience)
+                .is_ok()
+        }
+        None => state.users.get(&author_id).is_some_and(|profile| {
+            profile.privacy_settings.location_audience == InteractionAudience::Everyone
+        }),
+    }
+}
+
+/// Internal function to look up the author's custom emoji used in `content`
+///
+/// Only verified organizations have emoji; codes without a registered emoji
+/// are left for clients to show as text.
+fn resolve_custom_emoji(
+    state: &SocialNetworkState,
+    author_id: UserId,
+    content: &str,
+) -> Vec<CustomEmoji> {
+    let Some(emoji) = state.custom_emoji.get(&author_id).filter(|_| {
+        state
+            .users
+            .get(&author_id)
+            .is_some_and(|profile| profile.verification_status == VerificationStatus::Organization)
+    }) else {
+        return Vec::new();
+    };
+
+    entities::extract_emoji_codes(content)
+        .iter()
+        .filter_map(|code| emoji.get(code))
+        .cloned()
+        .collect()
+}
+
+/// Internal function to locate a post within its thread, if it belongs to one
+fn thread_position(state: &SocialNetworkState, post_id: PostId) -> Option<ThreadPosition> {
+    let root_id = *state.thread_roots.get(&post_id)?;
+    let thread = state.threads.get(&root_id)?;
+    let index = thread.iter().position(|id| *id == post_id)?;
+
+    Some(ThreadPosition {
+        root_id,
+        index: index as u32,
+        length: thread.len() as u32,
+    })
+}
+
+/// Internal function to prepare a visible comment for the viewer
+///
+/// Applies the viewer's word filter and fills in the escaped rendering.
+fn comment_for_viewer(
+    state: &SocialNetworkState,
+    viewer: Option<UserId>,
+    comment: &Comment,
+) -> Comment {
+    let mut comment = comment.clone();
+    if let Some((content, mode)) =
+        apply_word_filter(state, viewer, comment.author_id, &comment.content)
+    {
+        comment.content = content;
+        comment.filtered = Some(mode);
+    }
+    comment.rendered_content = security_utils::escape_html(&comment.content);
+    comment
+}
+
+/// Internal function to mask or collapse content matched by the viewer's word filter
+///
+/// Returns `None` when the filter is disabled, the viewer wrote the content,
+/// or nothing matched. Collapsed content is withheld (returned empty).
+fn apply_word_filter(
+    state: &SocialNetworkState,
+    viewer: Option<UserId>,
+    author_id: UserId,
+    content: &str,
+) -> Option<(String, WordFilterMode)> {
+    let viewer_id = viewer.filter(|viewer_id| *viewer_id != author_id)?;
+    let settings = state
+        .word_filters
+        .get(&viewer_id)
+        .filter(|settings| settings.enabled)?;
+    let masked = word_filter::mask_filtered_words(content, &settings.custom_words)?;
+
+    Some(match settings.mode {
+        WordFilterMode::Mask => (masked, WordFilterMode::Mask),
+        WordFilterMode::Collapse => (String::new(), WordFilterMode::Collapse),
+    })
+}
+
+/// Internal function to apply the viewer's preferred languages to a post
+///
+/// The viewer's own posts always pass.
+fn matches_language_preference(
+    state: &SocialNetworkState,
+    viewer: Option<UserId>,
+    post: &Post,
+) -> bool {
+    let Some(viewer_id) = viewer else {
+        return true;
+    };
+    if post.author_id == viewer_id {
+        return true;
+    }
+    state
+        .preferred_languages
+        .get(&viewer_id)
+        .is_none_or(|preferred| language::matches_preferences(post.language.as_deref(), preferred))
+}
+
+/// Internal function to check whether `viewer` may see `post`
+///
+/// - Public: everyone
+/// - FollowersOnly: the author and users in the author's `followers_index`
+/// - Unlisted: the author only
+fn can_view_post(state: &SocialNetworkState, viewer: Option<UserId>, post: &Post) -> bool {
+    match post.visibility {
+        PostVisibility::Public => true,
+        PostVisibility::FollowersOnly => viewer
+            .map(|viewer_id| {
+                viewer_id == post.author_id || is_follower_of(state, viewer_id, post.author_id)
+            })
+            .unwrap_or(false),
+        PostVisibility::Unlisted => viewer == Some(post.author_id),
+    }
+}
+
+/// Internal function to check whether `actor` may interact with `owner`
+///
+/// The owner always passes. Everyone else is rejected if either side has blocked
+/// the other, then checked against the audience picked from the owner's settings.
+fn check_interaction_audience(
+    state: &SocialNetworkState,
+    owner: UserId,
+    actor: UserId,
+    audience: impl FnOnce(&PrivacySettings) -> &InteractionAudience,
+) -> Result<(), String> {
+    if owner == actor {
+        return Ok(());
+    }
+
+    let profile = state.users.get(&owner).ok_or("User does not exist")?;
+    if is_blocked_between(state, owner, actor) {
+        return Err("Interaction not allowed".to_string());
+    }
+
+    match audience(&profile.privacy_settings) {
+        InteractionAudience::Everyone => Ok(()),
+        InteractionAudience::FollowersOnly if is_follower_of(state, actor, owner) => Ok(()),
+        InteractionAudience::FollowersOnly => {
+            Err("Only followers can interact with this user".to_string())
+        }
+        InteractionAudience::Nobody => Err("Interaction not allowed".to_string()),
+    }
+}
+
+/// Internal function to check whether either user has blocked the other
+fn is_blocked_between(state: &SocialNetworkState, a: UserId, b: UserId) -> bool {
+    state
+        .social_connections
+        .get(&a)
+        .map(|conn| conn.blocked.contains(&b) || conn.blocked_by.contains(&b))
+        .unwrap_or(false)
+}
+
+/// Internal function to check whether `follower` follows `target`
+fn is_follower_of(state: &SocialNetworkState, follower: UserId, target: UserId) -> bool {
+    state
+        .followers_index
+        .get(&target)
+        .map(|followers| followers.contains(&follower))
+        .unwrap_or(false)
+}
+
+/// Internal function to reject graph queries against a hidden social graph
+fn check_social_graph_visible(
+    state: &SocialNetworkState,
+    viewer: UserId,
+    target: UserId,
+) -> Result<(), String> {
+    let profile = state.users.get(&target).ok_or("User does not exist")?;
+    if !profile.privacy_settings.show_social_graph && viewer != target {
+        return Err("Social graph is private".to_string());
+    }
+    Ok(())
+}
+
+/// Internal function to list accounts `viewer` follows that follow `target`
+///
+/// `BTreeSet::intersection` walks the smaller set, so work is bounded by the
+/// viewer's following list (capped at `MAX_FOLLOWING_LIMIT`). Accounts hiding
+/// their social graph are skipped since their follows are not public.
+fn followers_you_know(
+    state: &SocialNetworkState,
+    viewer: UserId,
+    target: UserId,
+) -> impl Iterator<Item = UserId> + '_ {
+    let following = state.following_index.get(&viewer);
+    let followers = state.followers_index.get(&target);
+
+    following
+        .zip(followers)
+        .into_iter()
+        .flat_map(|(following, followers)| following.intersection(followers))
+        .copied()
+        .filter(move |user_id| *user_id != viewer && *user_id != target)
+        .filter(move |user_id| {
+            state
+                .users
+                .get(user_id)
+                .is_some_and(|profile| profile.privacy_settings.show_social_graph)
+        })
+}
+
+/// Internal function to execute a follow relationship
+fn execute_follow(follower_id: UserId, target_id: UserId) -> Result<(), String> {
+    with_state_mut(|state| {
+        // Initialize social connections if they don't exist
+        state.social_connections.entry(follower_id).or_default();
+        state.social_connections.entry(target_id).or_default();
+
+        // Add to follower's following list
+        if let Some(follower_conn) = state.social_connections.get_mut(&follower_id) {
+            follower_conn.following.insert(target_id);
+        }
+
+        // Add to target's followers list
+        if let Some(target_conn) = state.social_connections.get_mut(&target_id) {
+            target_conn.followers.insert(follower_id);
+        }
+
+        // Update indices
+        state
+            .following_index
+            .entry(follower_id)
+            .or_default()
+            .insert(target_id);
+        state
+            .followers_index
+            .entry(target_id)
+            .or_default()
+            .insert(follower_id);
+
+        // Update user profile counts
+        if let Some(follower_profile) = state.users.get_mut(&follower_id) {
+            follower_profile.following_count = follower_profile.following_count.saturating_add(1);
+            follower_profile.updated_at = time();
+        }
+        if let Some(target_profile) = state.users.get_mut(&target_id) {
+            target_profile.follower_count = target_profile.follower_count.saturating_add(1);
+            target_profile.updated_at = time();
+        }
+
+        let recent = state.recent_follows.entry(target_id).or_default();
+        recent.push_back((time(), follower_id));
+        if recent.len() > MAX_RECENT_FOLLOWS {
+            recent.pop_front();
+        }
+        queue_webhook_event(
+            state,
+            target_id,
+            webhook::EventData::Follower {
+                follower: follower_id,
+            },
+            time(),
+        );
+    });
+
+    Ok(())
+}
+
+/// Internal function to execute an unfollow relationship
+fn execute_unfollow(follower_id: UserId, target_id: UserId) -> Result<(), String> {
+    with_state_mut(|state| {
+        // Remove from follower's following list
+        if let Some(follower_conn) = state.social_connections.get_mut(&follower_id) {
+            follower_conn.following.remove(&target_id);
+        }
+
+        // Remove from target's followers list
+        if let Some(target_conn) = state.social_connections.get_mut(&target_id) {
+            target_conn.followers.remove(&follower_id);
+        }
+
+        // Update indices
+        if let Some(following_set) = state.following_index.get_mut(&follower_id) {
+            following_set.remove(&target_id);
+        }
+        if let Some(followers_set) = state.followers_index.get_mut(&target_id) {
+            followers_set.remove(&follower_id);
+        }
+
+        // Update user profile counts
+        if let Some(follower_profile) = state.users.get_mut(&follower_id) {
+            follower_profile.following_count = follower_profile.following_count.saturating_sub(1);
+            follower_profile.updated_at = time();
+        }
+        if let Some(target_profile) = state.users.get_mut(&target_id) {
+            target_profile.follower_count = target_profile.follower_count.saturating_sub(1);
+            target_profile.updated_at = time();
+        }
+
+        if let Some(recent) = state.recent_follows.get_mut(&target_id) {
+            recent.retain(|&(_, follower)| follower != follower_id);
+        }
+    });
+
+    Ok(())
+}
+
+/// Internal function to create a follow request
 fn create_follow_request(
     requester_id: UserId,
     target_id: UserId,
@@ -1254,9 +4583,10 @@ fn create_follow_request(
 /// # Arguments
 /// * `limit` - Maximum number of posts to return (optional)
 /// * `offset` - Number of posts to skip for pagination (optional)
+/// * `filters` - Feed filters for this call; defaults to the caller's saved preferences
 ///
 /// # Returns
-/// * `Ok(Vec<FeedPost>)` - Personalized feed of posts with author information
+/// * `Ok(FeedPage)` - Personalized feed of posts with author information
 /// * `Err(String)` - Error in feed generation
 ///
 /// # Feed Algorithm
@@ -1273,110 +4603,12038 @@ fn create_follow_request(
 pub fn get_social_feed(
     limit: Option<usize>,
     offset: Option<usize>,
-) -> Result<Vec<FeedPost>, String> {
+    filters: Option<FeedPreferences>,
+) -> Result<FeedPage, String> {
+    check_query_quota("get_social_feed")?;
     let limit = limit.unwrap_or(DEFAULT_FEED_LIMIT).min(MAX_FEED_LIMIT);
     let offset = offset.unwrap_or(0);
 
-    let caller_id = match caller() {
-        caller if caller == Principal::anonymous() => None,
-        caller => Some(UserId(caller)),
-    };
+    let caller_id = current_viewer();
 
     let feed_posts = with_state(|state| {
-        let mut visible_posts: Vec<(u64, &Post, &UserProfile)> = Vec::new();
-
-        // Determine which users' posts to include
-        let relevant_users: BTreeSet<UserId> = match caller_id {
-            Some(user_id) => {
-                // For authenticated users: own posts + followed users' posts
-                let mut users = BTreeSet::new();
-                users.insert(user_id); // Include own posts
-
-                // Add followed users
-                if let Some(connections) = state.social_connections.get(&user_id) {
-                    for &followed_id in &connections.following {
-                        // Don't include blocked users
-                        if !connections.blocked.contains(&followed_id) {
-                            users.insert(followed_id);
-                        }
-                    }
-                }
-                users
-            }
-            None => {
-                // For anonymous users: all users (but only public posts will be shown)
-                state.users.keys().copied().collect()
-            }
-        };
-
-        // Collect posts from relevant users
-        for &user_id in &relevant_users {
-            if let Some(user_profile) = state.users.get(&user_id) {
-                if let Some(user_posts) = state.user_posts.get(&user_id) {
-                    for &post_id in user_posts {
-                        if let Some(post) = state.posts.get(&post_id) {
-                            // Check if post is visible to the caller
-                            let is_visible = match &post.visibility {
-                                PostVisibility::Public => true,
-                                PostVisibility::FollowersOnly => {
-                                    if let Some(caller_user_id) = caller_id {
-                                        // Post author or followers can see
-                                        post.author_id == caller_user_id
-                                            || state
-                                                .social_connections
-                                                .get(&post.author_id)
-                                                .map(|conn| {
-                                                    conn.followers.contains(&caller_user_id)
-                                                })
-                                                .unwrap_or(false)
-                                    } else {
-                                        false // Anonymous users can't see followers-only posts
-                                    }
-                                }
-                                PostVisibility::Unlisted => {
-                                    // Only the author can see unlisted posts in feed
-                                    caller_id.map(|id| id == post.author_id).unwrap_or(false)
-                                }
-                            };
-
-                            if is_visible {
-                                visible_posts.push((post.created_at, post, user_profile));
-                            }
-                        }
-                    }
-                }
-            }
-        }
-
-        // Sort by creation time (newest first)
-        visible_posts.sort_by(|a, b| b.0.cmp(&a.0));
+        let filters = filters
+            .or_else(|| caller_id.and_then(|user_id| state.feed_preferences.get(&user_id).cloned()))
+            .unwrap_or_default();
+        let visible_posts = social_feed_posts(state, caller_id, &filters);
 
         // Apply pagination and convert to FeedPost
-        visible_posts
+        let total = visible_posts.len() as u64;
+        let (page, next_cursor) = page_at(visible_posts.into_iter(), offset, limit);
+        let posts = page
             .into_iter()
-            .skip(offset)
-            .take(limit)
-            .map(|(_, post, author)| {
-                let is_liked = caller_id
-                    .and_then(|user_id| {
-                        state
-                            .post_likes
-                            .get(&post.id)
-                            .map(|likes| likes.contains(&user_id))
-                    })
-                    .unwrap_or(false);
+            .map(|(post, author)| feed_post(state, caller_id, post, author))
+            .collect();
 
-                FeedPost {
-                    post: post.clone(),
-                    author: author.clone(),
-                    is_liked,
-                }
-            })
-            .collect()
+        FeedPage {
+            posts,
+            total: Some(total),
+            has_more: next_cursor.is_some(),
+            next_cursor,
+        }
     });
 
     Ok(feed_posts)
 }
 
-// Export Candid interface
-ic_cdk::export_candid!();
+/// Gets public posts located within a region, newest first
+///
+/// # Arguments
+/// * `region` - Area to search (at most MAX_REGION_DEGREES across)
+/// * `limit` - Maximum number of posts to return (optional)
+/// * `offset` - Number of posts to skip for pagination (optional)
+///
+/// # Privacy
+/// * Only public, ungated posts whose authors set `location_audience` to Everyone
+/// * Posts carrying only a place name are never listed
+/// * Posts by users who turned off `searchable` are left out
+/// * Blocked users' posts are left out
+#[query]
+pub fn get_posts_near(
+    region: GeoRegion,
+    limit: Option<usize>,
+    offset: Option<usize>,
+) -> Result<FeedPage, String> {
+    validate_geo_region(&region)?;
+    let limit = limit.unwrap_or(DEFAULT_FEED_LIMIT).min(MAX_FEED_LIMIT);
+    let offset = offset.unwrap_or(0);
+    let viewer = current_viewer();
+
+    Ok(with_state(|state| {
+        let listed = posts_near(state, &region, viewer);
+        let (page, next_cursor) = page_at(listed.into_iter(), offset, limit);
+        FeedPage {
+            posts: page
+                .into_iter()
+                .map(|(post, author)| feed_post(state, viewer, post, author))
+                .collect(),
+            total: None,
+            has_more: next_cursor.is_some(),
+            next_cursor,
+        }
+    }))
+}
+
+/// Internal function to list the posts `get_posts_near` may show in a
+/// region, with their authors, newest first
+fn posts_near<'a>(
+    state: &'a SocialNetworkState,
+    region: &GeoRegion,
+    viewer: Option<UserId>,
+) -> Vec<(&'a Post, &'a UserProfile)> {
+    let (south, west) = region.south_west.cell();
+    let (north, east) = region.north_east.cell();
+    let mut post_ids: Vec<PostId> = (south..=north)
+        .flat_map(|latitude| {
+            state
+                .located_posts
+                .range((latitude, west)..=(latitude, east))
+                .flat_map(|(_, post_ids)| post_ids.iter().copied())
+        })
+        .collect();
+    post_ids.sort_unstable_by(|a, b| b.cmp(a));
+
+    post_ids
+        .into_iter()
+        .filter_map(|post_id| {
+            let post = state.posts.get(&post_id)?;
+            let author = state.users.get(&post.author_id)?;
+            let opted_in =
+                author.privacy_settings.location_audience == InteractionAudience::Everyone;
+            let blocked = viewer
+                .is_some_and(|viewer_id| is_blocked_between(state, post.author_id, viewer_id));
+            (opted_in
+                && author.privacy_settings.is_discoverable()
+                && post.is_syndicated()
+                && !blocked)
+                .then_some((post, author))
+        })
+        .collect()
+}
+
+/// Gets posts citing a source, newest first
+///
+/// Lets readers and community note writers cross-reference reports that rely
+/// on the same document. Links match regardless of scheme, host case,
+/// fragment or trailing slash.
+///
+/// # Arguments
+/// * `url` - Link to the source
+/// * `limit` - Maximum number of posts to return (optional)
+/// * `offset` - Number of posts to skip for pagination (optional)
+///
+/// # Privacy
+/// * Only posts the caller can view; gated posts are listed with content withheld
+/// * Blocked users' posts are left out
+#[query]
+pub fn get_posts_citing(url: String, limit: Option<usize>, offset: Option<usize>) -> FeedPage {
+    let limit = limit.unwrap_or(DEFAULT_FEED_LIMIT).min(MAX_FEED_LIMIT);
+    let offset = offset.unwrap_or(0);
+    let viewer = current_viewer();
+
+    with_state(|state| {
+        let post_ids = state.cited_sources.get(&citation_key(&url));
+        let listed = post_ids
+            .into_iter()
+            .flat_map(|post_ids| post_ids.iter().rev())
+            .filter_map(|post_id| {
+                let post = state.posts.get(post_id)?;
+                let author = state.users.get(&post.author_id)?;
+                let blocked = viewer
+                    .is_some_and(|viewer_id| is_blocked_between(state, post.author_id, viewer_id));
+                (can_view_post(state, viewer, post) && !blocked).then_some((post, author))
+            });
+        let (page, next_cursor) = page_at(listed, offset, limit);
+        FeedPage {
+            posts: page
+                .into_iter()
+                .map(|(post, author)| feed_post(state, viewer, post, author))
+                .collect(),
+            total: None,
+            has_more: next_cursor.is_some(),
+            next_cursor,
+        }
+    })
+}
+
+/// Changes to the caller's social feed since an earlier sync
+///
+/// # Arguments
+/// * `since` - `sync_cursor` from the previous call, or 0 to start
+/// * `limit` - Maximum number of new posts to return (optional)
+/// * `filters` - Feed filters for this call; defaults to the caller's saved preferences
+///
+/// # Returns
+/// * `Ok(FeedUpdates)` - Posts created since the cursor, ids of older posts
+///   updated since then and ids of posts deleted since then
+///
+/// # Behavior
+/// - Updates cover edits, access changes, engagement counts and restored
+///   posts; refetch them with `get_posts_by_ids`
+/// - Deleted posts are reported whether or not they have been purged yet
+/// - When more changed than fits, `truncated` is set and only the newest are
+///   listed; reload the feed with `get_social_feed` instead
+#[query]
+pub fn get_feed_updates(
+    since: u64,
+    limit: Option<usize>,
+    filters: Option<FeedPreferences>,
+) -> Result<FeedUpdates, String> {
+    let limit = limit.unwrap_or(DEFAULT_FEED_LIMIT).min(MAX_FEED_LIMIT);
+    let caller_id = current_viewer();
+    let now = time();
+
+    Ok(with_state(|state| {
+        let filters = filters
+            .or_else(|| caller_id.and_then(|user_id| state.feed_preferences.get(&user_id).cloned()))
+            .unwrap_or_default();
+        let visible_posts = social_feed_posts(state, caller_id, &filters);
+
+        let mut new_posts = visible_posts
+            .iter()
+            .take_while(|(post, _)| post.created_at > since);
+        let posts: Vec<FeedPost> = new_posts
+            .by_ref()
+            .take(limit)
+            .map(|&(post, author)| feed_post(state, caller_id, post, author))
+            .collect();
+        let mut truncated = new_posts.next().is_some();
+
+        let mut updated_posts = visible_posts
+            .iter()
+            .skip_while(|(post, _)| post.created_at > since)
+            .filter(|(post, _)| post.updated_at > since)
+            .map(|(post, _)| post.id);
+        let updated: Vec<PostId> = updated_posts.by_ref().take(MAX_FEED_LIMIT).collect();
+        truncated |= updated_posts.next().is_some();
+
+        let connections = caller_id.and_then(|user_id| state.social_connections.get(&user_id));
+        let mut deleted_posts = state
+            .deletion_log
+            .deleted_since(since, |post_id| state.posts.contains_key(&post_id))
+            .filter(|entry| match caller_id {
+                Some(user_id) => {
+                    entry.author_id == user_id
+                        || connections.is_some_and(|connections| {
+                            connections.following.contains(&entry.author_id)
+                                && !connections.blocked.contains(&entry.author_id)
+                        })
+                }
+                None => true,
+            })
+            .map(|entry| entry.post_id);
+        let deleted: Vec<PostId> = deleted_posts.by_ref().take(MAX_FEED_LIMIT).collect();
+        truncated |= deleted_posts.next().is_some() || !state.deletion_log.is_complete_since(since);
+
+        FeedUpdates {
+            posts,
+            updated,
+            deleted,
+            truncated,
+            sync_cursor: now,
+        }
+    }))
+}
+
+/// Counts new activity for the caller since an earlier poll
+///
+/// # Arguments
+/// * `since` - `cursor` from the previous call, or 0 to start
+///
+/// # Returns
+/// * `Ok(ActivitySummary)` - Unread messages, new followers, fact-check labels on
+///   the caller's posts, new feed posts and live sessions scheduled by followed users
+/// * `Err(String)` - Authentication error
+///
+/// # Behavior
+/// - Cheap enough to poll: reads only unread messages, the bounded lists of
+///   recent follows and post activity, and the newest posts of each followed user
+/// - Likes and comments are listed by `get_post_activity`; unread direct
+///   messages are the only unread items reported
+#[query]
+pub fn get_activity_summary(since: u64) -> Result<ActivitySummary, String> {
+    let user_id = authenticate_for(AppScope::ReadOnly)?;
+    let now = time();
+    Ok(with_state(|state| {
+        activity_summary(state, user_id, since, now, now)
+    }))
+}
+
+/// Summarizes the caller's activity over their last completed local day
+///
+/// # Returns
+/// * `Ok(ActivityDigest)` - Counts between the two most recent times the
+///   caller's clock read their digest hour (DEFAULT_DIGEST_HOUR unless set)
+/// * `Err(String)` - Authentication error
+///
+/// # Behavior
+/// - Uses `utc_offset_minutes` and `digest_hour` from the caller's settings;
+///   clients schedule their digest notification at `next_digest_at`
+#[query]
+pub fn get_activity_digest() -> Result<ActivityDigest, String> {
+    let user_id = authenticate_for(AppScope::ReadOnly)?;
+    let now = time();
+
+    Ok(with_state(|state| {
+        let settings = state.user_settings.get(&user_id);
+        let digest_hour = settings
+            .and_then(|settings| settings.digest_hour)
+            .unwrap_or(DEFAULT_DIGEST_HOUR);
+        let period_end =
+            local_time::last_local_hour(now, utc_offset_of(state, user_id), digest_hour);
+        let period_start = period_end.saturating_sub(NANOS_PER_DAY);
+
+        ActivityDigest {
+            period_start,
+            period_end,
+            summary: activity_summary(state, user_id, period_start, period_end, now),
+            next_digest_at: period_end.saturating_add(NANOS_PER_DAY),
+        }
+    }))
+}
+
+/// Internal function to count a user's activity after `since` up to `until`
+///
+/// Unread messages are always counted as of now.
+fn activity_summary(
+    state: &SocialNetworkState,
+    user_id: UserId,
+    since: u64,
+    until: u64,
+    now: u64,
+) -> ActivitySummary {
+    let unread_messages = state
+        .user_conversations
+        .get(&user_id)
+        .map(|ids| {
+            ids.iter()
+                .map(|&id| unread_in_conversation(state, id, user_id))
+                .sum()
+        })
+        .unwrap_or(0);
+
+    let new_followers = state
+        .recent_follows
+        .get(&user_id)
+        .map(|recent| {
+            recent
+                .iter()
+                .rev()
+                .skip_while(|(at, _)| *at > until)
+                .take_while(|(at, _)| *at > since)
+                .count() as u64
+        })
+        .unwrap_or(0);
+
+    let new_fact_checks = state
+        .fact_checked_posts
+        .get(&user_id)
+        .into_iter()
+        .flatten()
+        .filter_map(|post_id| state.fact_checks.get(post_id))
+        .flatten()
+        .filter(|label| label.applied_at > since && label.applied_at <= until)
+        .count() as u64;
+
+    let new_feed_posts = state
+        .social_connections
+        .get(&user_id)
+        .map(|connections| {
+            connections
+                .following
+                .iter()
+                .filter(|followed_id| !connections.blocked.contains(followed_id))
+                .filter_map(|followed_id| state.user_posts.get(followed_id))
+                .map(|post_ids| {
+                    post_ids
+                        .iter()
+                        .rev()
+                        .map_while(|post_id| state.posts.get(post_id))
+                        .skip_while(|post| post.created_at > until)
+                        .take_while(|post| post.created_at > since)
+                        .filter(|post| can_view_post(state, Some(user_id), post))
+                        .count() as u64
+                })
+                .sum()
+        })
+        .unwrap_or(0);
+
+    let listed_since = live_session_listing_start(now);
+    let new_live_sessions = state
+        .social_connections
+        .get(&user_id)
+        .into_iter()
+        .flat_map(|connections| &connections.following)
+        .filter_map(|followed_id| state.user_live_sessions.get(followed_id))
+        .flatten()
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .filter_map(|id| state.live_sessions.get(id))
+        .filter(|session| session.created_at > since && session.created_at <= until)
+        .filter(|session| session.scheduled_at >= listed_since)
+        .filter(|session| !is_blocked_between(state, session.host, user_id))
+        .count() as u64;
+
+    let (mut new_likes, mut new_comments, mut new_mentions) = (0, 0, 0);
+    for activity in state
+        .recent_post_activity
+        .get(&user_id)
+        .into_iter()
+        .flat_map(|recent| recent.iter().rev())
+        .skip_while(|activity| activity.created_at > until)
+        .take_while(|activity| activity.created_at > since)
+    {
+        match activity.kind {
+            PostActivityKind::Like => new_likes += 1,
+            PostActivityKind::Comment => new_comments += 1,
+            PostActivityKind::Mention => new_mentions += 1,
+        }
+    }
+
+    ActivitySummary {
+        unread_messages,
+        new_followers,
+        new_fact_checks,
+        new_feed_posts,
+        new_live_sessions,
+        new_likes,
+        new_comments,
+        new_mentions,
+        cursor: until,
+    }
+}
+
+/// Internal function to collect the posts of a viewer's social feed, newest first
+///
+/// Authenticated viewers see their own posts and those of users they follow
+/// and have not blocked; anonymous viewers see every user's public posts.
+fn social_feed_posts<'a>(
+    state: &'a SocialNetworkState,
+    caller_id: Option<UserId>,
+    filters: &FeedPreferences,
+) -> Vec<(&'a Post, &'a UserProfile)> {
+    let mut visible_posts: Vec<(&Post, &UserProfile)> = Vec::new();
+
+    // Determine which users' posts to include
+    let relevant_users: BTreeSet<UserId> = match caller_id {
+        Some(user_id) => {
+            // For authenticated users: own posts + followed users' posts
+            let mut users = BTreeSet::new();
+            users.insert(user_id); // Include own posts
+
+            // Add followed users
+            if let Some(connections) = state.social_connections.get(&user_id) {
+                for &followed_id in &connections.following {
+                    // Don't include blocked users
+                    if !connections.blocked.contains(&followed_id) {
+                        users.insert(followed_id);
+                    }
+                }
+            }
+            users
+        }
+        None => {
+            // For anonymous users: all users (but only public posts will be shown)
+            state.users.keys().copied().collect()
+        }
+    };
+
+    // Collect posts from relevant users
+    for &user_id in &relevant_users {
+        if let Some(user_profile) = state.users.get(&user_id) {
+            if let Some(user_posts) = state.user_posts.get(&user_id) {
+                for &post_id in user_posts {
+                    if let Some(post) = state.posts.get(&post_id) {
+                        // Check if post is visible to the caller
+                        let is_visible = can_view_post(state, caller_id, post)
+                            && matches_language_preference(state, caller_id, post)
+                            && feed_filter::matches(
+                                filters,
+                                &post_content(state, post),
+                                caller_id == Some(post.author_id),
+                            );
+
+                        if is_visible {
+                            visible_posts.push((post, user_profile));
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    // Sort by creation time (newest first)
+    visible_posts.sort_by_key(|(post, _)| std::cmp::Reverse(post.created_at));
+    visible_posts
+}
+
+/// Internal function to present a social feed post to the caller
+fn feed_post(
+    state: &SocialNetworkState,
+    caller_id: Option<UserId>,
+    post: &Post,
+    author: &UserProfile,
+) -> FeedPost {
+    let is_liked = caller_id
+        .and_then(|user_id| {
+            state
+                .post_likes
+                .get(&post.id)
+                .map(|likes| likes.contains(&user_id))
+        })
+        .unwrap_or(false);
+
+    let post = post_for_viewer(state, caller_id, post);
+    FeedPost {
+        kind: feed_item_kind(&post),
+        post,
+        author: author.clone(),
+        is_liked,
+        promoted: false,
+    }
+}
+
+// ============================================================================
+// VERIFICATION & MODERATION
+// ============================================================================
+
+/// Applies for a verified account status
+///
+/// # Arguments
+/// * `kind` - Requested status (Verified, Organization or Journalist)
+/// * `evidence` - Supporting evidence for reviewers (max 2,000 chars)
+///
+/// # Returns
+/// * `Ok(VerificationRequest)` - The pending application
+/// * `Err(String)` - Validation error or an application is already pending
+///
+/// # Security
+/// * Requires an existing profile
+/// * Only one pending application per user
+/// * Rate limited to prevent queue flooding
+#[update]
+pub async fn apply_for_verification(
+    kind: VerificationStatus,
+    evidence: String,
+) -> Result<VerificationRequest, String> {
+    let user_id = authenticate_user()?;
+
+    if !matches!(
+        kind,
+        VerificationStatus::Verified
+            | VerificationStatus::Organization
+            | VerificationStatus::Journalist
+    ) {
+        return Err(
+            "Only Verified, Organization or Journalist status can be applied for".to_string(),
+        );
+    }
+
+    validate_verification_evidence(&evidence)?;
+
+    let profile =
+        with_state(|state| state.users.get(&user_id).cloned()).ok_or("Profile not found")?;
+    if profile.verification_status == kind {
+        return Err("Account already has the requested verification status".to_string());
+    }
+
+    check_rate_limit(&user_id, "apply_for_verification", 3, 86_400)?; // 3 applications per day
+
+    with_state_mut(|state| {
+        let already_pending = state.verification_requests.values().any(|req| {
+            req.applicant == user_id && req.status == VerificationRequestStatus::Pending
+        });
+        if already_pending {
+            return Err("A verification application is already pending".to_string());
+        }
+
+        let request_id = state.next_verification_request_id;
+        state.next_verification_request_id = state.next_verification_request_id.saturating_add(1);
+
+        let request = VerificationRequest {
+            id: request_id,
+            applicant: user_id,
+            requested_status: kind,
+            evidence,
+            created_at: time(),
+            status: VerificationRequestStatus::Pending,
+            reviewed_by: None,
+            reviewed_at: None,
+            review_note: None,
+        };
+
+        state
+            .verification_requests
+            .insert(request_id, request.clone());
+        Ok(request)
+    })
+}
+
+/// Gets the authenticated user's verification applications (newest first)
+#[query]
+pub fn get_my_verification_requests() -> Result<Vec<VerificationRequest>, String> {
+    let user_id = authenticate_user()?;
+
+    Ok(with_state(|state| {
+        state
+            .verification_requests
+            .values()
+            .rev()
+            .filter(|req| req.applicant == user_id)
+            .cloned()
+            .collect()
+    }))
+}
+
+/// Gets the review queue of pending verification applications (oldest first)
+///
+/// # Security
+/// * Moderators and admins only
+#[query]
+pub fn get_pending_verification_requests(
+    limit: Option<usize>,
+    offset: Option<usize>,
+) -> Result<VerificationRequestsPage, String> {
+    let user_id = authenticate_for(AppScope::Moderate)?;
+    require_moderator(&user_id)?;
+
+    let limit = limit
+        .unwrap_or(DEFAULT_CONNECTIONS_LIMIT)
+        .min(MAX_CONNECTIONS_LIMIT);
+    let offset = offset.unwrap_or(0);
+
+    Ok(with_state(|state| {
+        let pending = state
+            .verification_requests
+            .values()
+            .filter(|req| req.status == VerificationRequestStatus::Pending);
+        let (page, next_cursor) = page_at(pending, offset, limit);
+        VerificationRequestsPage {
+            requests: page.into_iter().cloned().collect(),
+            total: None,
+            has_more: next_cursor.is_some(),
+            next_cursor,
+        }
+    }))
+}
+
+/// Approves a pending verification application and updates the applicant's status
+///
+/// # Security
+/// * Moderators and admins only
+/// * Recorded in the audit log
+#[update]
+pub async fn approve_verification_request(
+    request_id: u64,
+    note: Option<String>,
+) -> Result<(), String> {
+    review_verification_request(request_id, note, true)
+}
+
+/// Rejects a pending verification application
+///
+/// # Security
+/// * Moderators and admins only
+/// * Recorded in the audit log
+#[update]
+pub async fn reject_verification_request(
+    request_id: u64,
+    note: Option<String>,
+) -> Result<(), String> {
+    review_verification_request(request_id, note, false)
+}
+
+/// Directly grants a verification status to a user, bypassing the queue
+///
+/// # Security
+/// * Admins only
+/// * Recorded in the audit log
+#[update]
+pub async fn grant_verification(
+    target_user_id: Principal,
+    status: VerificationStatus,
+) -> Result<UserProfile, String> {
+    let admin_id = authenticate_user()?;
+    require_admin(&admin_id)?;
+    let target_id = UserId(target_user_id);
+
+    with_state_mut(|state| {
+        let profile = state
+            .users
+            .get_mut(&target_id)
+            .ok_or("User does not exist")?;
+        profile.verification_status = status.clone();
+        profile.updated_at = time();
+        let profile = profile.clone();
+
+        record_audit_event(
+            state,
+            admin_id,
+            AuditAction::VerificationGranted,
+            Some(target_id),
+            format!("{status:?}"),
+        );
+
+        Ok(profile)
+    })
+}
+
+/// Revokes a user's verification status, returning them to Unverified
+///
+/// # Security
+/// * Admins only
+/// * Recorded in the audit log with the supplied reason
+#[update]
+pub async fn revoke_verification(
+    target_user_id: Principal,
+    reason: String,
+) -> Result<UserProfile, String> {
+    let admin_id = authenticate_user()?;
+    require_admin(&admin_id)?;
+    validate_review_note(&reason)?;
+    let target_id = UserId(target_user_id);
+
+    with_state_mut(|state| {
+        let profile = state
+            .users
+            .get_mut(&target_id)
+            .ok_or("User does not exist")?;
+        if profile.verification_status == VerificationStatus::Unverified {
+            return Err("User is not verified".to_string());
+        }
+        let previous = std::mem::replace(
+            &mut profile.verification_status,
+            VerificationStatus::Unverified,
+        );
+        profile.updated_at = time();
+        let profile = profile.clone();
+
+        record_audit_event(
+            state,
+            admin_id,
+            AuditAction::VerificationRevoked,
+            Some(target_id),
+            format!("{previous:?}: {reason}"),
+        );
+
+        Ok(profile)
+    })
+}
+
+/// Appoints a content moderator
+///
+/// # Security
+/// * Admins only
+/// * Recorded in the audit log
+#[update]
+pub async fn add_moderator(user_id: Principal) -> Result<(), String> {
+    let admin_id = authenticate_user()?;
+    require_admin(&admin_id)?;
+    let moderator_id = UserId(user_id);
+
+    with_state_mut(|state| {
+        if !state.users.contains_key(&moderator_id) {
+            return Err("User does not exist".to_string());
+        }
+        if !state.moderators.insert(moderator_id) {
+            return Err("User is already a moderator".to_string());
+        }
+
+        record_audit_event(
+            state,
+            admin_id,
+            AuditAction::ModeratorAdded,
+            Some(moderator_id),
+            String::new(),
+        );
+        Ok(())
+    })
+}
+
+/// Removes a content moderator
+///
+/// # Security
+/// * Admins only
+/// * Recorded in the audit log
+#[update]
+pub async fn remove_moderator(user_id: Principal) -> Result<(), String> {
+    let admin_id = authenticate_user()?;
+    require_admin(&admin_id)?;
+    let moderator_id = UserId(user_id);
+
+    with_state_mut(|state| {
+        if !state.moderators.remove(&moderator_id) {
+            return Err("User is not a moderator".to_string());
+        }
+
+        record_audit_event(
+            state,
+            admin_id,
+            AuditAction::ModeratorRemoved,
+            Some(moderator_id),
+            String::new(),
+        );
+        Ok(())
+    })
+}
+
+/// Gets audit log entries (newest first)
+///
+/// # Security
+/// * Admins only
+#[query]
+pub fn get_audit_log(limit: Option<usize>, offset: Option<usize>) -> Result<AuditLogPage, String> {
+    let user_id = authenticate_user()?;
+    require_admin(&user_id)?;
+
+    let limit = limit
+        .unwrap_or(DEFAULT_CONNECTIONS_LIMIT)
+        .min(MAX_CONNECTIONS_LIMIT);
+    let offset = offset.unwrap_or(0);
+
+    Ok(with_state(|state| {
+        let (page, next_cursor) = page_at(state.audit_log.iter().rev(), offset, limit);
+        AuditLogPage {
+            entries: page.into_iter().cloned().collect(),
+            total: Some(state.audit_log.len() as u64),
+            has_more: next_cursor.is_some(),
+            next_cursor,
+        }
+    }))
+}
+
+/// Gets the caller's reputation score and its components
+#[query]
+pub fn get_my_reputation() -> Result<ReputationScore, String> {
+    let user_id = authenticate_user()?;
+    with_state(|state| reputation_for(state, &user_id, time()))
+        .ok_or_else(|| SocialNetworkError::UserNotFound(user_id).into())
+}
+
+/// Gets any user's reputation score
+///
+/// # Security
+/// * Moderators and admins only
+#[query]
+pub fn get_user_reputation(user_id: Principal) -> Result<ReputationScore, String> {
+    let moderator_id = authenticate_for(AppScope::Moderate)?;
+    require_moderator(&moderator_id)?;
+    let target_id = UserId(user_id);
+    with_state(|state| reputation_for(state, &target_id, time()))
+        .ok_or_else(|| SocialNetworkError::UserNotFound(target_id).into())
+}
+
+/// Issues a moderation strike against a user, lowering their reputation
+///
+/// # Security
+/// * Moderators and admins only
+/// * Recorded in the audit log
+#[update]
+pub async fn issue_moderation_strike(user_id: Principal, reason: String) -> Result<u32, String> {
+    let moderator_id = authenticate_for(AppScope::Moderate)?;
+    require_moderator(&moderator_id)?;
+    validate_review_note(&reason)?;
+    let target_id = UserId(user_id);
+
+    with_state_mut(|state| {
+        if !state.users.contains_key(&target_id) {
+            return Err("User does not exist".to_string());
+        }
+        let strikes = state.moderation_strikes.entry(target_id).or_default();
+        *strikes = strikes.saturating_add(1);
+        let strikes = *strikes;
+
+        record_audit_event(
+            state,
+            moderator_id,
+            AuditAction::StrikeIssued,
+            Some(target_id),
+            reason,
+        );
+        Ok(strikes)
+    })
+}
+
+/// Revokes one moderation strike (e.g. after a successful appeal)
+///
+/// # Security
+/// * Moderators and admins only
+/// * Recorded in the audit log
+#[update]
+pub async fn revoke_moderation_strike(user_id: Principal, reason: String) -> Result<u32, String> {
+    let moderator_id = authenticate_for(AppScope::Moderate)?;
+    require_moderator(&moderator_id)?;
+    validate_review_note(&reason)?;
+    let target_id = UserId(user_id);
+
+    with_state_mut(|state| {
+        let Some(strikes) = state.moderation_strikes.get_mut(&target_id) else {
+            return Err("User has no active strikes".to_string());
+        };
+        *strikes -= 1;
+        let remaining = *strikes;
+        if remaining == 0 {
+            state.moderation_strikes.remove(&target_id);
+        }
+
+        record_audit_event(
+            state,
+            moderator_id,
+            AuditAction::StrikeRevoked,
+            Some(target_id),
+            reason,
+        );
+        Ok(remaining)
+    })
+}
+
+/// Internal function to compute a user's reputation from current state
+fn reputation_for(
+    state: &SocialNetworkState,
+    user_id: &UserId,
+    now: u64,
+) -> Option<ReputationScore> {
+    let profile = state.users.get(user_id)?;
+    let account_age_days = now.saturating_sub(profile.created_at) / NANOS_PER_DAY;
+    let received_engagement = state
+        .author_post_totals
+        .get(user_id)
+        .map(|posts| {
+            posts
+                .values()
+                .fold(EngagementCounts::default(), |mut total, counts| {
+                    total.likes = total.likes.saturating_add(counts.likes);
+                    total.comments = total.comments.saturating_add(counts.comments);
+                    total.reposts = total.reposts.saturating_add(counts.reposts);
+                    total.views = total.views.saturating_add(counts.views);
+                    total
+                })
+        })
+        .unwrap_or_default();
+    let moderation_strikes = state.moderation_strikes.get(user_id).copied().unwrap_or(0);
+
+    let score = reputation_score(
+        account_age_days,
+        &profile.verification_status,
+        &received_engagement,
+        profile.follower_count,
+        moderation_strikes,
+        state.moderation_policy.strike_penalty,
+    );
+
+    let trust_tier = trust_tier(
+        account_age_days,
+        &profile.verification_status,
+        score,
+        moderation_strikes,
+    );
+
+    Some(ReputationScore {
+        user_id: *user_id,
+        score,
+        account_age_days,
+        verification_status: profile.verification_status.clone(),
+        received_engagement,
+        follower_count: profile.follower_count,
+        moderation_strikes,
+        rate_limit_percent: rate_limit_percent(
+            &trust_tier,
+            &profile.verification_status,
+            has_personhood(state, user_id, now),
+        ),
+        trust_tier,
+        community_notes_eligible: moderation_strikes == 0
+            && score >= state.moderation_policy.min_reputation_for_community_notes,
+    })
+}
+
+/// Internal function to approve or reject a pending verification application
+fn review_verification_request(
+    request_id: u64,
+    note: Option<String>,
+    approve: bool,
+) -> Result<(), String> {
+    let reviewer_id = authenticate_for(AppScope::Moderate)?;
+    require_moderator(&reviewer_id)?;
+    if let Some(ref note_text) = note {
+        validate_review_note(note_text)?;
+    }
+
+    with_state_mut(|state| {
+        let request = state
+            .verification_requests
+            .get_mut(&request_id)
+            .ok_or("Verification request not found")?;
+
+        if request.status != VerificationRequestStatus::Pending {
+            return Err("Verification request is not pending".to_string());
+        }
+
+        let now = time();
+        request.status = if approve {
+            VerificationRequestStatus::Approved
+        } else {
+            VerificationRequestStatus::Rejected
+        };
+        request.reviewed_by = Some(reviewer_id);
+        request.reviewed_at = Some(now);
+        request.review_note = note;
+        let request = request.clone();
+
+        if approve {
+            if let Some(profile) = state.users.get_mut(&request.applicant) {
+                profile.verification_status = request.requested_status.clone();
+                profile.updated_at = now;
+            }
+        }
+
+        record_audit_event(
+            state,
+            reviewer_id,
+            if approve {
+                AuditAction::VerificationApproved
+            } else {
+                AuditAction::VerificationRejected
+            },
+            Some(request.applicant),
+            format!("request {}: {:?}", request.id, request.requested_status),
+        );
+
+        Ok(())
+    })
+}
+
+/// Internal function to append an entry to the audit log
+fn record_audit_event(
+    state: &mut SocialNetworkState,
+    actor: UserId,
+    action: AuditAction,
+    target: Option<UserId>,
+    details: String,
+) {
+    log_event(
+        state,
+        LogLevel::Info,
+        logging::audit_category(&action),
+        Some(actor.0),
+        format!("{action:?} {details}"),
+    );
+    let id = state.audit_log.len() as u64;
+    state.audit_log.push(AuditLogEntry {
+        id,
+        actor,
+        action,
+        target,
+        details,
+        timestamp: time(),
+    });
+}
+
+// ============================================================================
+// ORGANIZATION ACCOUNTS
+// ============================================================================
+
+/// Adds a team member to an organization account
+///
+/// # Arguments
+/// * `org_id` - Organization account (must be Organization-verified)
+/// * `member` - Principal to add
+/// * `role` - Admin, Editor or Analyst
+///
+/// # Security
+/// * Only the organization account or its Admin members
+/// * Capped at MAX_ORG_MEMBERS members
+#[update]
+pub async fn add_org_member(
+    org_id: Principal,
+    member: Principal,
+    role: OrgRole,
+) -> Result<OrgMember, String> {
+    let user_id = authenticate_user()?;
+    let org_id = UserId(org_id);
+    let member_id = UserId(member);
+
+    ensure_organization(&org_id)?;
+    require_org_admin(&org_id, &user_id)?;
+    security_utils::validate_principal(&member)?;
+
+    if member_id == org_id {
+        return Err("Organization cannot be a member of itself".to_string());
+    }
+
+    with_state_mut(|state| {
+        if !state.users.contains_key(&member_id) {
+            return Err("User does not exist".to_string());
+        }
+
+        let members = state.org_members.entry(org_id).or_default();
+        if members.contains_key(&member_id) {
+            return Err("User is already a member of this organization".to_string());
+        }
+        if members.len() >= MAX_ORG_MEMBERS {
+            return Err("Organization member limit reached".to_string());
+        }
+
+        let membership = OrgMember {
+            org_id,
+            member_id,
+            role,
+            added_by: user_id,
+            added_at: time(),
+        };
+        members.insert(member_id, membership.clone());
+        Ok(membership)
+    })
+}
+
+/// Changes a team member's role
+///
+/// # Security
+/// * Only the organization account or its Admin members
+#[update]
+pub async fn update_org_member_role(
+    org_id: Principal,
+    member: Principal,
+    role: OrgRole,
+) -> Result<OrgMember, String> {
+    let user_id = authenticate_user()?;
+    let org_id = UserId(org_id);
+    let member_id = UserId(member);
+
+    ensure_organization(&org_id)?;
+    require_org_admin(&org_id, &user_id)?;
+
+    with_state_mut(|state| {
+        let membership = state
+            .org_members
+            .get_mut(&org_id)
+            .and_then(|members| members.get_mut(&member_id))
+            .ok_or("User is not a member of this organization")?;
+        membership.role = role;
+        Ok(membership.clone())
+    })
+}
+
+/// Removes a team member from an organization account
+///
+/// # Security
+/// * The organization account or its Admin members can remove anyone
+/// * Members can always remove themselves
+#[update]
+pub async fn remove_org_member(org_id: Principal, member: Principal) -> Result<(), String> {
+    let user_id = authenticate_user()?;
+    let org_id = UserId(org_id);
+    let member_id = UserId(member);
+
+    if user_id != member_id {
+        require_org_admin(&org_id, &user_id)?;
+    }
+
+    with_state_mut(|state| {
+        let removed = state
+            .org_members
+            .get_mut(&org_id)
+            .and_then(|members| members.remove(&member_id));
+        match removed {
+            Some(_) => Ok(()),
+            None => Err("User is not a member of this organization".to_string()),
+        }
+    })
+}
+
+/// Gets an organization's team members
+///
+/// # Security
+/// * Only the organization account and its members
+#[query]
+pub fn get_org_members(org_id: Principal) -> Result<Vec<OrgMember>, String> {
+    let user_id = authenticate_user()?;
+    let org_id = UserId(org_id);
+
+    if user_id != org_id && org_role(&org_id, &user_id).is_none() {
+        return Err("Not a member of this organization".to_string());
+    }
+
+    Ok(with_state(|state| {
+        state
+            .org_members
+            .get(&org_id)
+            .map(|members| members.values().cloned().collect())
+            .unwrap_or_default()
+    }))
+}
+
+/// Gets the organizations the authenticated user belongs to
+#[query]
+pub fn get_my_organizations() -> Result<Vec<OrgMember>, String> {
+    let user_id = authenticate_user()?;
+
+    Ok(with_state(|state| {
+        state
+            .org_members
+            .values()
+            .filter_map(|members| members.get(&user_id))
+            .cloned()
+            .collect()
+    }))
+}
+
+/// Registers a custom emoji for an organization's posts
+///
+/// # Arguments
+/// * `org_id` - Verified organization account
+/// * `shortcode` - Code used in posts as `:shortcode:` (2-32 lowercase letters, digits or `_`)
+/// * `asset_url` - Image asset (https URL from a trusted domain)
+///
+/// # Security
+/// * Only the organization account or its Admin members
+/// * Capped at MAX_CUSTOM_EMOJI_PER_ORG emoji per organization
+#[update]
+pub async fn add_custom_emoji(
+    org_id: Principal,
+    shortcode: String,
+    asset_url: String,
+) -> Result<CustomEmoji, String> {
+    let user_id = authenticate_user()?;
+    let org_id = UserId(org_id);
+
+    ensure_organization(&org_id)?;
+    require_org_admin(&org_id, &user_id)?;
+    validate_custom_emoji(&shortcode, &asset_url)?;
+
+    with_state_mut(|state| {
+        let emoji = state.custom_emoji.entry(org_id).or_default();
+        if emoji.contains_key(&shortcode) {
+            return Err(format!("Emoji :{shortcode}: is already registered"));
+        }
+        if emoji.len() >= MAX_CUSTOM_EMOJI_PER_ORG {
+            return Err("Organization custom emoji limit reached".to_string());
+        }
+
+        let registered = CustomEmoji {
+            org_id,
+            shortcode: shortcode.clone(),
+            asset_url,
+            added_by: user_id,
+            added_at: time(),
+        };
+        emoji.insert(shortcode, registered.clone());
+        Ok(registered)
+    })
+}
+
+/// Removes an organization's custom emoji
+///
+/// # Security
+/// * The organization account, its Admin members, or a moderator
+/// * Moderator removals are recorded in the audit log
+#[update]
+pub async fn remove_custom_emoji(org_id: Principal, shortcode: String) -> Result<(), String> {
+    let user_id = authenticate_user()?;
+    let org_id = UserId(org_id);
+
+    let as_moderator = require_org_admin(&org_id, &user_id).is_err();
+    if as_moderator {
+        require_moderator(&user_id)?;
+    }
+
+    with_state_mut(|state| {
+        state
+            .custom_emoji
+            .get_mut(&org_id)
+            .and_then(|emoji| emoji.remove(&shortcode))
+            .ok_or("Emoji not found")?;
+        if as_moderator {
+            record_audit_event(
+                state,
+                user_id,
+                AuditAction::CustomEmojiRemoved,
+                Some(org_id),
+                shortcode,
+            );
+        }
+        Ok(())
+    })
+}
+
+/// Lists an organization's custom emoji
+#[query]
+pub fn get_custom_emoji(org_id: Principal) -> Vec<CustomEmoji> {
+    with_state(|state| {
+        state
+            .custom_emoji
+            .get(&UserId(org_id))
+            .map(|emoji| emoji.values().cloned().collect())
+            .unwrap_or_default()
+    })
+}
+
+/// Internal function to check that an account is a verified organization
+fn ensure_organization(org_id: &UserId) -> Result<(), String> {
+    let status = with_state(|state| {
+        state
+            .users
+            .get(org_id)
+            .map(|profile| profile.verification_status.clone())
+    })
+    .ok_or("Organization does not exist")?;
+
+    if status != VerificationStatus::Organization {
+        return Err("Account is not a verified organization".to_string());
+    }
+
+    Ok(())
+}
+
+// ============================================================================
+// DELEGATED POSTING
+// ============================================================================
+
+/// Grants another user permission to submit drafts on the caller's behalf
+///
+/// # Arguments
+/// * `delegate` - Principal allowed to submit drafts
+/// * `expires_at` - Optional expiry timestamp (nanoseconds)
+///
+/// # Security
+/// * Drafts never publish without the owner's approval
+/// * Capped at MAX_POSTING_DELEGATES delegates per user
+#[update]
+pub async fn grant_posting_delegation(
+    delegate: Principal,
+    expires_at: Option<u64>,
+) -> Result<PostingDelegation, String> {
+    let owner_id = authenticate_user()?;
+    let delegate_id = UserId(delegate);
+    security_utils::validate_principal(&delegate)?;
+
+    if owner_id == delegate_id {
+        return Err("Cannot delegate to yourself".to_string());
+    }
+
+    let now = time();
+    if expires_at.is_some_and(|expiry| expiry <= now) {
+        return Err("Delegation expiry must be in the future".to_string());
+    }
+
+    with_state_mut(|state| {
+        if !state.users.contains_key(&delegate_id) {
+            return Err("User does not exist".to_string());
+        }
+
+        let delegate_count = state
+            .posting_delegations
+            .keys()
+            .filter(|(owner, _)| *owner == owner_id)
+            .count();
+        let is_update = state
+            .posting_delegations
+            .contains_key(&(owner_id, delegate_id));
+        if !is_update && delegate_count >= MAX_POSTING_DELEGATES {
+            return Err("Posting delegate limit reached".to_string());
+        }
+
+        let delegation = PostingDelegation {
+            owner: owner_id,
+            delegate: delegate_id,
+            granted_at: now,
+            expires_at,
+        };
+        state
+            .posting_delegations
+            .insert((owner_id, delegate_id), delegation.clone());
+        Ok(delegation)
+    })
+}
+
+/// Revokes a posting delegation; pending drafts from the delegate are rejected
+#[update]
+pub async fn revoke_posting_delegation(delegate: Principal) -> Result<(), String> {
+    let owner_id = authenticate_user()?;
+    let delegate_id = UserId(delegate);
+
+    with_state_mut(|state| {
+        if state
+            .posting_delegations
+            .remove(&(owner_id, delegate_id))
+            .is_none()
+        {
+            return Err("No delegation for this user".to_string());
+        }
+
+        let now = time();
+        for draft in state.drafts.values_mut() {
+            if draft.owner == owner_id
+                && draft.submitted_by == delegate_id
+                && draft.status == DraftStatus::PendingApproval
+            {
+                draft.status = DraftStatus::Rejected;
+                draft.updated_at = now;
+            }
+        }
+        Ok(())
+    })
+}
+
+/// Gets delegations the caller has granted and those granted to the caller
+#[query]
+pub fn get_my_posting_delegations() -> Result<Vec<PostingDelegation>, String> {
+    let user_id = authenticate_user()?;
+
+    Ok(with_state(|state| {
+        state
+            .posting_delegations
+            .values()
+            .filter(|delegation| delegation.owner == user_id || delegation.delegate == user_id)
+            .cloned()
+            .collect()
+    }))
+}
+
+/// Submits a draft on behalf of a user who granted the caller a posting delegation
+///
+/// # Arguments
+/// * `owner` - Account the post will be published as
+/// * `content` - Post content (1-10,000 characters)
+/// * `visibility` - Requested visibility (defaults to Public)
+///
+/// # Security
+/// * Requires an active, unexpired delegation from the owner
+/// * Validated like a regular post
+/// * Rate limited per delegate
+#[update]
+pub async fn submit_delegated_draft(
+    owner: Principal,
+    content: String,
+    visibility: Option<PostVisibility>,
+) -> Result<Draft, String> {
+    let delegate_id = authenticate_user()?;
+    let owner_id = UserId(owner);
+
+    validate_post_content(&content)?;
+
+    let now = time();
+    let has_delegation = with_state(|state| {
+        state
+            .posting_delegations
+            .get(&(owner_id, delegate_id))
+            .map(|delegation| delegation.is_active(now))
+            .unwrap_or(false)
+    });
+    if !has_delegation {
+        return Err("No active posting delegation from this user".to_string());
+    }
+
+    check_rate_limit(&delegate_id, "submit_delegated_draft", 10, 300)?; // 10 drafts per 5 minutes
+
+    with_state_mut(|state| {
+        let pending_count = state
+            .drafts
+            .values()
+            .filter(|draft| draft.owner == owner_id && draft.status == DraftStatus::PendingApproval)
+            .count();
+        if pending_count >= MAX_PENDING_DRAFTS {
+            return Err("Too many drafts awaiting approval".to_string());
+        }
+
+        let draft_id = state.next_draft_id;
+        state.next_draft_id = state.next_draft_id.saturating_add(1);
+
+        let draft = Draft {
+            id: draft_id,
+            owner: owner_id,
+            submitted_by: delegate_id,
+            content,
+            collaborators: BTreeMap::new(),
+            version: 0,
+            contributors: vec![delegate_id],
+            visibility: visibility.unwrap_or(PostVisibility::Public),
+            created_at: now,
+            updated_at: now,
+            status: DraftStatus::PendingApproval,
+            published_post_id: None,
+        };
+        state.drafts.insert(draft_id, draft.clone());
+        Ok(draft)
+    })
+}
+
+/// Gets drafts awaiting the caller's approval (oldest first)
+#[query]
+pub fn get_pending_drafts() -> Result<Vec<Draft>, String> {
+    let owner_id = authenticate_user()?;
+
+    Ok(with_state(|state| {
+        state
+            .drafts
+            .values()
+            .filter(|draft| draft.owner == owner_id && draft.status == DraftStatus::PendingApproval)
+            .cloned()
+            .collect()
+    }))
+}
+
+/// Gets drafts the caller has submitted on behalf of others (newest first)
+#[query]
+pub fn get_my_submitted_drafts() -> Result<Vec<Draft>, String> {
+    let delegate_id = authenticate_user()?;
+
+    Ok(with_state(|state| {
+        state
+            .drafts
+            .values()
+            .rev()
+            .filter(|draft| draft.submitted_by == delegate_id)
+            .cloned()
+            .collect()
+    }))
+}
+
+/// Approves a pending draft and publishes it as a post by the owner
+///
+/// Everyone else who edited the draft is credited in the post's `contributors`.
+///
+/// # Arguments
+/// * `draft_id` - Draft to publish
+/// * `expected_version` - Draft `version` the owner reviewed; the approval is
+///   rejected with a conflict if a collaborator edited the draft since
+///
+/// # Security
+/// * Only the draft owner can approve
+/// * Content is re-validated at publish time
+#[update]
+pub async fn approve_draft(draft_id: u64, expected_version: u64) -> Result<PostId, String> {
+    let owner_id = authenticate_user()?;
+
+    let draft =
+        with_state(|state| state.drafts.get(&draft_id).cloned()).ok_or("Draft not found")?;
+    if draft.owner != owner_id {
+        return Err("Not authorized to approve this draft".to_string());
+    }
+    if draft.status != DraftStatus::PendingApproval {
+        return Err("Draft is not pending approval".to_string());
+    }
+    check_draft_version(&draft, expected_version)?;
+
+    validate_post_content(&draft.content)?;
+    check_link_gate(owner_id, &draft.content)?;
+    check_rate_limit(&owner_id, "create_post", 10, 300)?;
+    ensure_user_profile(owner_id).await?;
+
+    let post_id = with_state_mut(|state| {
+        // The draft may have been edited while the profile was being created
+        let current = state.drafts.get(&draft_id).ok_or("Draft not found")?;
+        if current.status != DraftStatus::PendingApproval {
+            return Err("Draft is not pending approval".to_string());
+        }
+        check_draft_version(current, expected_version)?;
+
+        let post_id = insert_post(
+            state,
+            owner_id,
+            draft.content,
+            draft.visibility,
+            None,
+            TextFormat::Plain,
+            PostExtras {
+                contributors: draft
+                    .contributors
+                    .into_iter()
+                    .filter(|&user_id| user_id != owner_id)
+                    .collect(),
+                ..PostExtras::default()
+            },
+        );
+        if let Some(stored) = state.drafts.get_mut(&draft_id) {
+            stored.status = DraftStatus::Published;
+            stored.published_post_id = Some(post_id);
+            stored.updated_at = time();
+        }
+        Ok(post_id)
+    })?;
+    federate_post(post_id);
+    check_post_links(post_id);
+
+    Ok(post_id)
+}
+
+/// Internal function to reject acting on a draft changed since the caller read it
+fn check_draft_version(draft: &Draft, expected: u64) -> Result<(), String> {
+    if draft.version != expected {
+        return Err(SocialNetworkError::VersionConflict {
+            expected,
+            current: draft.version,
+        }
+        .into());
+    }
+    Ok(())
+}
+
+/// Rejects a pending draft without publishing it
+///
+/// # Security
+/// * Only the draft owner can reject
+#[update]
+pub async fn reject_draft(draft_id: u64) -> Result<(), String> {
+    let owner_id = authenticate_user()?;
+
+    with_state_mut(|state| {
+        let draft = state.drafts.get_mut(&draft_id).ok_or("Draft not found")?;
+        if draft.owner != owner_id {
+            return Err("Not authorized to reject this draft".to_string());
+        }
+        if draft.status != DraftStatus::PendingApproval {
+            return Err("Draft is not pending approval".to_string());
+        }
+
+        draft.status = DraftStatus::Rejected;
+        draft.updated_at = time();
+        Ok(())
+    })
+}
+
+/// Starts a draft of the caller's own, to prepare with invited collaborators
+///
+/// # Arguments
+/// * `content` - Post content (1-10,000 characters)
+/// * `visibility` - Requested visibility (defaults to Public)
+///
+/// # Security
+/// * Validated like a regular post; published with `approve_draft`
+/// * Counts towards MAX_PENDING_DRAFTS
+#[update]
+pub async fn create_draft(
+    content: String,
+    visibility: Option<PostVisibility>,
+) -> Result<Draft, String> {
+    let owner_id = authenticate_user()?;
+    validate_post_content(&content)?;
+    check_rate_limit(&owner_id, "create_draft", 10, 300)?;
+
+    with_state_mut(|state| {
+        let pending_count = state
+            .drafts
+            .values()
+            .filter(|draft| draft.owner == owner_id && draft.status == DraftStatus::PendingApproval)
+            .count();
+        if pending_count >= MAX_PENDING_DRAFTS {
+            return Err("Too many drafts awaiting approval".to_string());
+        }
+
+        let draft_id = state.next_draft_id;
+        state.next_draft_id = state.next_draft_id.saturating_add(1);
+
+        let now = time();
+        let draft = Draft {
+            id: draft_id,
+            owner: owner_id,
+            submitted_by: owner_id,
+            content,
+            collaborators: BTreeMap::new(),
+            version: 0,
+            contributors: vec![owner_id],
+            visibility: visibility.unwrap_or(PostVisibility::Public),
+            created_at: now,
+            updated_at: now,
+            status: DraftStatus::PendingApproval,
+            published_post_id: None,
+        };
+        state.drafts.insert(draft_id, draft.clone());
+        Ok(draft)
+    })
+}
+
+/// Invites a user to view or edit a pending draft, or withdraws their access
+///
+/// # Arguments
+/// * `draft_id` - One of the caller's pending drafts
+/// * `user` - User to invite
+/// * `role` - Viewer or Editor; `None` removes the user
+///
+/// # Security
+/// * Only the draft owner can share
+/// * At most MAX_DRAFT_COLLABORATORS collaborators per draft
+#[update]
+pub async fn share_draft(
+    draft_id: u64,
+    user: Principal,
+    role: Option<DraftRole>,
+) -> Result<Draft, String> {
+    let owner_id = authenticate_user()?;
+    let user_id = UserId(user);
+
+    with_state_mut(|state| {
+        if role.is_some() && !state.users.contains_key(&user_id) {
+            return Err("User does not exist".to_string());
+        }
+        let draft = state.drafts.get_mut(&draft_id).ok_or("Draft not found")?;
+        if draft.owner != owner_id {
+            return Err("Not authorized to share this draft".to_string());
+        }
+        if draft.status != DraftStatus::PendingApproval {
+            return Err("Draft is not pending approval".to_string());
+        }
+        if user_id == owner_id {
+            return Err("You already own this draft".to_string());
+        }
+
+        match role {
+            Some(role) => {
+                if !draft.collaborators.contains_key(&user_id)
+                    && draft.collaborators.len() >= MAX_DRAFT_COLLABORATORS
+                {
+                    return Err(format!(
+                        "Drafts are limited to {MAX_DRAFT_COLLABORATORS} collaborators"
+                    ));
+                }
+                draft.collaborators.insert(user_id, role);
+            }
+            None => {
+                draft
+                    .collaborators
+                    .remove(&user_id)
+                    .ok_or("User is not a collaborator")?;
+            }
+        }
+        draft.updated_at = time();
+        Ok(draft.clone())
+    })
+}
+
+/// Replaces the content of a pending draft
+///
+/// Edits are last-writer-wins: each one bumps `version`, so clients can tell
+/// when their copy was overwritten by someone else.
+///
+/// # Security
+/// * The owner, the submitting delegate and invited editors only
+/// * Validated like a regular post; rate limited per editor
+#[update]
+pub async fn edit_draft(draft_id: u64, content: String) -> Result<Draft, String> {
+    let user_id = authenticate_user()?;
+    validate_post_content(&content)?;
+    check_rate_limit(&user_id, "edit_draft", 60, 300)?;
+
+    with_state_mut(|state| {
+        let draft = state.drafts.get_mut(&draft_id).ok_or("Draft not found")?;
+        if draft_role(draft, user_id) != Some(DraftRole::Editor) {
+            return Err("Not authorized to edit this draft".to_string());
+        }
+        if draft.status != DraftStatus::PendingApproval {
+            return Err("Draft is not pending approval".to_string());
+        }
+
+        draft.content = content;
+        draft.version = draft.version.saturating_add(1);
+        draft.updated_at = time();
+        if !draft.contributors.contains(&user_id) {
+            draft.contributors.push(user_id);
+        }
+        Ok(draft.clone())
+    })
+}
+
+/// Gets a draft the caller owns, submitted, or was invited to
+#[query]
+pub fn get_draft(draft_id: u64) -> Result<Draft, String> {
+    let user_id = authenticate_user()?;
+
+    with_state(|state| {
+        state
+            .drafts
+            .get(&draft_id)
+            .filter(|draft| draft_role(draft, user_id).is_some())
+            .cloned()
+            .ok_or_else(|| "Draft not found".to_string())
+    })
+}
+
+/// Gets drafts the caller was invited to view or edit (newest first)
+#[query]
+pub fn get_shared_drafts() -> Result<Vec<Draft>, String> {
+    let user_id = authenticate_user()?;
+
+    Ok(with_state(|state| {
+        state
+            .drafts
+            .values()
+            .rev()
+            .filter(|draft| draft.collaborators.contains_key(&user_id))
+            .cloned()
+            .collect()
+    }))
+}
+
+/// Internal function to resolve a user's access to a draft
+///
+/// The owner and the submitting delegate can always edit.
+fn draft_role(draft: &Draft, user_id: UserId) -> Option<DraftRole> {
+    if draft.owner == user_id || draft.submitted_by == user_id {
+        return Some(DraftRole::Editor);
+    }
+    draft.collaborators.get(&user_id).copied()
+}
+
+// ============================================================================
+// CAPABILITY GRANTS
+// ============================================================================
+
+/// Lets another principal perform specific actions as the caller
+///
+/// # Arguments
+/// * `grantee` - Principal allowed to act
+/// * `capabilities` - Actions allowed; replaces any earlier grant to the grantee
+/// * `expires_at` - Optional expiry timestamp (nanoseconds)
+///
+/// # Security
+/// * Only the listed actions are allowed; everything else, including reading
+///   messages unless ReadMessages is granted, stays with the caller
+/// * Capped at MAX_CAPABILITY_GRANTS grantees per user
+#[update]
+pub async fn grant_capabilities(
+    grantee: Principal,
+    capabilities: Vec<Capability>,
+    expires_at: Option<u64>,
+) -> Result<CapabilityGrant, String> {
+    let owner_id = authenticate_user()?;
+    let grantee_id = UserId(grantee);
+    security_utils::validate_principal(&grantee)?;
+
+    if owner_id == grantee_id {
+        return Err("Cannot grant capabilities to yourself".to_string());
+    }
+    if capabilities.is_empty() {
+        return Err("At least one capability is required".to_string());
+    }
+
+    let now = time();
+    if expires_at.is_some_and(|expiry| expiry <= now) {
+        return Err("Grant expiry must be in the future".to_string());
+    }
+
+    with_state_mut(|state| {
+        let grant_count = state
+            .capability_grants
+            .keys()
+            .filter(|(owner, _)| *owner == owner_id)
+            .count();
+        let is_update = state
+            .capability_grants
+            .contains_key(&(owner_id, grantee_id));
+        if !is_update && grant_count >= MAX_CAPABILITY_GRANTS {
+            return Err("Capability grant limit reached".to_string());
+        }
+
+        let grant = CapabilityGrant {
+            owner: owner_id,
+            grantee: grantee_id,
+            capabilities: capabilities.into_iter().collect(),
+            granted_at: now,
+            expires_at,
+        };
+        state
+            .capability_grants
+            .insert((owner_id, grantee_id), grant.clone());
+        Ok(grant)
+    })
+}
+
+/// Revokes every capability granted to a principal
+#[update]
+pub async fn revoke_capabilities(grantee: Principal) -> Result<(), String> {
+    let owner_id = authenticate_user()?;
+
+    with_state_mut(|state| {
+        state
+            .capability_grants
+            .remove(&(owner_id, UserId(grantee)))
+            .map(|_| ())
+            .ok_or_else(|| "No capabilities granted to this principal".to_string())
+    })
+}
+
+/// Gets capability grants the caller has made and those made to the caller
+#[query]
+pub fn get_my_capability_grants() -> Result<Vec<CapabilityGrant>, String> {
+    let user_id = authenticate_user()?;
+
+    Ok(with_state(|state| {
+        state
+            .capability_grants
+            .values()
+            .filter(|grant| grant.owner == user_id || grant.grantee == user_id)
+            .cloned()
+            .collect()
+    }))
+}
+
+// ============================================================================
+// WHISTLEBLOWER SECURE INBOX
+// ============================================================================
+
+/// Switches the caller's account to an anonymous whistleblower account
+///
+/// # Security
+/// * Only unverified accounts can switch, so verified identities are never
+///   linked to submissions
+#[update]
+pub async fn enable_whistleblower_mode() -> Result<UserProfile, String> {
+    let user_id = authenticate_user()?;
+
+    with_state_mut(|state| {
+        let profile = state.users.get_mut(&user_id).ok_or("Profile not found")?;
+        if profile.verification_status != VerificationStatus::Unverified {
+            return Err("Only unverified accounts can become whistleblower accounts".to_string());
+        }
+
+        profile.verification_status = VerificationStatus::Whistleblower;
+        profile.updated_at = time();
+        Ok(profile.clone())
+    })
+}
+
+/// Opts a journalist in or out of receiving whistleblower submissions
+///
+/// # Security
+/// * Journalist-verified accounts only
+#[update]
+pub async fn set_whistleblower_inbox(accepting: bool) -> Result<(), String> {
+    let user_id = authenticate_user()?;
+    ensure_journalist(&user_id)?;
+
+    with_state_mut(|state| {
+        if accepting {
+            state.whistleblower_inbox_journalists.insert(user_id);
+        } else {
+            state.whistleblower_inbox_journalists.remove(&user_id);
+        }
+    });
+
+    Ok(())
+}
+
+/// Lists journalists currently accepting whistleblower submissions
+#[query]
+pub fn get_whistleblower_journalists() -> Vec<UserProfile> {
+    with_state(|state| {
+        state
+            .whistleblower_inbox_journalists
+            .iter()
+            .filter_map(|journalist_id| state.users.get(journalist_id))
+            .filter(|profile| profile.verification_status == VerificationStatus::Journalist)
+            .cloned()
+            .collect()
+    })
+}
+
+/// Delivers an anonymous submission to a journalist's secure inbox
+///
+/// # Arguments
+/// * `journalist` - Opted-in, Journalist-verified recipient
+/// * `message` - Message body (1-10,000 characters)
+/// * `attachments` - Up to 5 documents totalling 1.5 MB
+///
+/// # Returns
+/// * `Ok(u64)` - Submission ID
+/// * `Err(String)` - Validation error or recipient unavailable
+///
+/// # Privacy
+/// * Only whistleblower accounts can submit
+/// * The sender principal is never stored with the submission
+/// * Submission time is rounded to the hour
+#[update]
+pub async fn submit_to_journalist(
+    journalist: Principal,
+    message: String,
+    attachments: Vec<SubmissionAttachment>,
+) -> Result<u64, String> {
+    let user_id = authenticate_user()?;
+    let journalist_id = UserId(journalist);
+
+    let is_whistleblower = with_state(|state| {
+        state
+            .users
+            .get(&user_id)
+            .map(|profile| profile.verification_status == VerificationStatus::Whistleblower)
+            .unwrap_or(false)
+    });
+    if !is_whistleblower {
+        return Err("Only whistleblower accounts can submit to journalists".to_string());
+    }
+
+    validate_whistleblower_submission(&message, &attachments)?;
+
+    let accepting = with_state(|state| {
+        state
+            .whistleblower_inbox_journalists
+            .contains(&journalist_id)
+    });
+    if !accepting {
+        return Err("Journalist is not accepting submissions".to_string());
+    }
+    ensure_journalist(&journalist_id)?;
+
+    check_rate_limit(&user_id, "submit_to_journalist", 5, 3_600)?; // 5 submissions per hour
+
+    with_state_mut(|state| {
+        let inbox = state
+            .whistleblower_inboxes
+            .entry(journalist_id)
+            .or_default();
+        if inbox.len() >= MAX_INBOX_SUBMISSIONS {
+            return Err("Journalist's inbox is full".to_string());
+        }
+
+        let submission_id = state.next_submission_id;
+        state.next_submission_id = state.next_submission_id.saturating_add(1);
+
+        const HOUR_NS: u64 = 3_600 * 1_000_000_000;
+        let submission = WhistleblowerSubmission {
+            id: submission_id,
+            journalist: journalist_id,
+            message,
+            attachments,
+            submitted_at: time() / HOUR_NS * HOUR_NS,
+            read: false,
+        };
+        inbox.insert(submission_id, submission);
+        Ok(submission_id)
+    })
+}
+
+/// Gets submissions in the caller's secure inbox (newest first)
+///
+/// # Security
+/// * Journalist-verified accounts only, and only their own inbox
+#[query]
+pub fn get_whistleblower_inbox(
+    limit: Option<usize>,
+    offset: Option<usize>,
+) -> Result<WhistleblowerInboxPage, String> {
+    let user_id = authenticate_user()?;
+    ensure_journalist(&user_id)?;
+
+    let limit = limit
+        .unwrap_or(DEFAULT_CONNECTIONS_LIMIT)
+        .min(MAX_CONNECTIONS_LIMIT);
+    let offset = offset.unwrap_or(0);
+
+    Ok(with_state(|state| {
+        let inbox = state.whistleblower_inboxes.get(&user_id);
+        let submissions = inbox.into_iter().flat_map(|inbox| inbox.values().rev());
+        let (page, next_cursor) = page_at(submissions, offset, limit);
+        WhistleblowerInboxPage {
+            submissions: page.into_iter().cloned().collect(),
+            total: Some(inbox.map_or(0, |inbox| inbox.len() as u64)),
+            has_more: next_cursor.is_some(),
+            next_cursor,
+        }
+    }))
+}
+
+/// Marks a submission in the caller's inbox as read
+#[update]
+pub async fn mark_submission_read(submission_id: u64) -> Result<(), String> {
+    let user_id = authenticate_user()?;
+    ensure_journalist(&user_id)?;
+
+    with_state_mut(|state| {
+        let submission = state
+            .whistleblower_inboxes
+            .get_mut(&user_id)
+            .and_then(|inbox| inbox.get_mut(&submission_id))
+            .ok_or("Submission not found")?;
+        submission.read = true;
+        Ok(())
+    })
+}
+
+/// Permanently deletes a submission from the caller's inbox
+#[update]
+pub async fn delete_submission(submission_id: u64) -> Result<(), String> {
+    let user_id = authenticate_user()?;
+
+    with_state_mut(|state| {
+        state
+            .whistleblower_inboxes
+            .get_mut(&user_id)
+            .and_then(|inbox| inbox.remove(&submission_id))
+            .map(|_| ())
+            .ok_or_else(|| "Submission not found".to_string())
+    })
+}
+
+/// Internal function to check that an account is a verified journalist
+fn ensure_journalist(user_id: &UserId) -> Result<(), String> {
+    let is_journalist = with_state(|state| {
+        state
+            .users
+            .get(user_id)
+            .map(|profile| profile.verification_status == VerificationStatus::Journalist)
+            .unwrap_or(false)
+    });
+
+    if is_journalist {
+        Ok(())
+    } else {
+        Err("Journalist verification required".to_string())
+    }
+}
+
+// ============================================================================
+// DIRECT MESSAGES
+// ============================================================================
+
+/// Returns the vetKD public key used to verify direct message conversation keys
+#[update]
+pub async fn get_dm_public_key() -> Result<Vec<u8>, String> {
+    authenticate_user()?;
+    vetkd::dm_public_key(vetkd_key_name()).await
+}
+
+/// Derives the caller's encryption key for a conversation with `peer`
+///
+/// # Arguments
+/// * `peer` - The other conversation participant
+/// * `transport_public_key` - Caller's ephemeral transport public key
+///
+/// # Returns
+/// * `Ok(Vec<u8>)` - Conversation key encrypted under the transport key
+/// * `Err(String)` - Messaging not permitted or key derivation failed
+///
+/// # Security
+/// * Both participants derive the same key; nobody else can request it
+/// * Requires that the caller may message the peer (or already shares a conversation)
+/// * Rate limited since each derivation costs cycles
+#[update]
+pub async fn get_conversation_key(
+    peer: Principal,
+    transport_public_key: Vec<u8>,
+) -> Result<Vec<u8>, String> {
+    let user_id = authenticate_user()?;
+    let peer_id = UserId(peer);
+
+    if user_id == peer_id {
+        return Err("Cannot message yourself".to_string());
+    }
+
+    let has_conversation = with_state(|state| {
+        state
+            .conversation_index
+            .contains_key(&participant_pair(user_id, peer_id))
+    });
+    if !has_conversation {
+        with_state(|state| check_message_privacy(state, user_id, peer_id))?;
+    }
+
+    check_rate_limit(&user_id, "get_conversation_key", 20, 3_600)?; // 20 derivations per hour
+
+    vetkd::derive_conversation_key(
+        vetkd_key_name(),
+        vetkd::conversation_key_input(&user_id, &peer_id),
+        transport_public_key,
+    )
+    .await
+}
+
+/// Sends a plain text direct message
+///
+/// # Arguments
+/// * `recipient` - Principal of the recipient
+/// * `content` - Message text (1-2,000 characters)
+/// * `as_user` - Optional user who granted the caller the SendMessages capability, to send as
+///
+/// # Returns
+/// * `Ok(DirectMessage)` - The stored message
+/// * `Err(String)` - Privacy restriction, block, or validation error
+///
+/// # Privacy
+/// * Enforces the recipient's `MessagePrivacy` setting on every send
+/// * Blocked users cannot message each other
+#[update]
+pub async fn send_message(
+    recipient: Principal,
+    content: String,
+    as_user: Option<Principal>,
+) -> Result<DirectMessage, String> {
+    let sender_id = act_as(authenticate_user()?, as_user, "send_message")?;
+    deliver_message(sender_id, UserId(recipient), MessageContent::Text(content))
+}
+
+/// Sends an end-to-end encrypted direct message
+///
+/// # Arguments
+/// * `recipient` - Principal of the recipient
+/// * `ciphertext` - Payload encrypted with the conversation key (max 16 KB)
+///
+/// # Privacy
+/// * Same `MessagePrivacy` and block enforcement as `send_message`
+#[update]
+pub async fn send_encrypted_message(
+    recipient: Principal,
+    ciphertext: Vec<u8>,
+) -> Result<DirectMessage, String> {
+    let sender_id = authenticate_user()?;
+    deliver_message(
+        sender_id,
+        UserId(recipient),
+        MessageContent::Encrypted(ciphertext),
+    )
+}
+
+/// Gets the caller's conversations, most recently active first
+///
+/// # Arguments
+/// * `as_user` - Optional user who granted the caller the ReadMessages capability, to read as
+///
+/// # Returns
+/// * `Ok(ConversationsPage)` - Conversations with last message and unread count
+#[query]
+pub fn get_conversations(
+    limit: Option<usize>,
+    offset: Option<usize>,
+    as_user: Option<Principal>,
+) -> Result<ConversationsPage, String> {
+    let user_id = act_as(authenticate_user()?, as_user, "read_messages")?;
+
+    let limit = limit
+        .unwrap_or(DEFAULT_CONNECTIONS_LIMIT)
+        .min(MAX_CONNECTIONS_LIMIT);
+    let offset = offset.unwrap_or(0);
+
+    Ok(with_state(|state| {
+        let mut conversations: Vec<&Conversation> = state
+            .user_conversations
+            .get(&user_id)
+            .map(|ids| {
+                ids.iter()
+                    .filter_map(|id| state.conversations.get(id))
+                    .collect()
+            })
+            .unwrap_or_default();
+        conversations.sort_by_key(|conversation| std::cmp::Reverse(conversation.last_message_at));
+
+        let total = conversations.len() as u64;
+        let (page, next_cursor) = page_at(conversations.into_iter(), offset, limit);
+        let conversations = page
+            .into_iter()
+            .map(|conversation| ConversationSummary {
+                conversation: conversation.clone(),
+                peer: match conversation.kind {
+                    ConversationKind::Direct => conversation
+                        .participants
+                        .iter()
+                        .copied()
+                        .find(|participant| *participant != user_id),
+                    ConversationKind::Group => None,
+                },
+                last_message: state
+                    .conversation_messages
+                    .get(&conversation.id)
+                    .and_then(|messages| messages.last())
+                    .cloned(),
+                unread_count: unread_in_conversation(state, conversation.id, user_id),
+            })
+            .collect();
+
+        ConversationsPage {
+            conversations,
+            total: Some(total),
+            has_more: next_cursor.is_some(),
+            next_cursor,
+        }
+    }))
+}
+
+/// Gets the total number of unread direct messages across all conversations
+#[query]
+pub fn get_unread_message_count() -> Result<u64, String> {
+    let user_id = authenticate_user()?;
+
+    Ok(with_state(|state| {
+        state
+            .user_conversations
+            .get(&user_id)
+            .map(|ids| {
+                ids.iter()
+                    .map(|&id| unread_in_conversation(state, id, user_id))
+                    .sum()
+            })
+            .unwrap_or(0)
+    }))
+}
+
+/// Marks all messages in a conversation as read by the caller
+#[update]
+pub async fn mark_conversation_read(conversation_id: u64) -> Result<(), String> {
+    let user_id = authenticate_user()?;
+
+    with_state_mut(|state| {
+        let conversation = state
+            .conversations
+            .get(&conversation_id)
+            .ok_or("Conversation not found")?;
+        if !conversation.participants.contains(&user_id) {
+            return Err("Not a participant in this conversation".to_string());
+        }
+
+        if let Some(last) = state
+            .conversation_messages
+            .get(&conversation_id)
+            .and_then(|messages| messages.last())
+        {
+            state
+                .conversation_read_markers
+                .insert((conversation_id, user_id), last.id);
+        }
+        Ok(())
+    })
+}
+
+/// Gets messages in a conversation, newest first
+///
+/// # Arguments
+/// * `conversation_id` - Conversation to read
+/// * `before` - Only return messages with an ID lower than this (pagination cursor)
+/// * `limit` - Maximum messages to return (capped at MAX_MESSAGES_LIMIT)
+/// * `as_user` - Optional user who granted the caller the ReadMessages capability, to read as
+///
+/// # Security
+/// * Only conversation participants can read messages
+#[query]
+pub fn get_messages(
+    conversation_id: u64,
+    before: Option<u64>,
+    limit: Option<usize>,
+    as_user: Option<Principal>,
+) -> Result<MessagesPage, String> {
+    let user_id = act_as(authenticate_user()?, as_user, "read_messages")?;
+    let limit = limit
+        .unwrap_or(DEFAULT_MESSAGES_LIMIT)
+        .min(MAX_MESSAGES_LIMIT);
+
+    with_state(|state| {
+        let conversation = state
+            .conversations
+            .get(&conversation_id)
+            .ok_or("Conversation not found")?;
+        if !conversation.participants.contains(&user_id) {
+            return Err("Not a participant in this conversation".to_string());
+        }
+
+        let messages = state
+            .conversation_messages
+            .get(&conversation_id)
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        let mut older = messages
+            .iter()
+            .rev()
+            .filter(|message| before.map(|cursor| message.id < cursor).unwrap_or(true));
+        let page: Vec<DirectMessage> = older.by_ref().take(limit).cloned().collect();
+        let next_cursor = page
+            .last()
+            .map(|message| message.id)
+            .filter(|_| older.next().is_some());
+
+        Ok(MessagesPage {
+            messages: page,
+            total: Some(messages.len() as u64),
+            has_more: next_cursor.is_some(),
+            next_cursor,
+        })
+    })
+}
+
+/// Sets the vetKD master key name (e.g. `dfx_test_key` on local replicas)
+///
+/// # Security
+/// * Admins only
+#[update]
+pub async fn set_vetkd_key_name(key_name: String) -> Result<(), String> {
+    let admin_id = authenticate_user()?;
+    require_admin(&admin_id)?;
+
+    if key_name.trim().is_empty() || key_name.len() > 64 {
+        return Err("Key name must be 1-64 characters".to_string());
+    }
+
+    with_state_mut(|state| state.vetkd_key_name = Some(key_name));
+    Ok(())
+}
+
+/// Internal function to validate and store a direct message
+fn deliver_message(
+    sender_id: UserId,
+    recipient_id: UserId,
+    content: MessageContent,
+) -> Result<DirectMessage, String> {
+    if sender_id == recipient_id {
+        return Err("Cannot message yourself".to_string());
+    }
+    validate_message_content(&content)?;
+
+    check_rate_limit(&sender_id, "send_message", 60, 60)?; // 60 messages per minute
+
+    with_state_mut(|state| {
+        check_message_privacy(state, sender_id, recipient_id)?;
+
+        let now = time();
+        let conversation_id = get_or_create_conversation(state, sender_id, recipient_id, now);
+
+        Ok(append_message(
+            state,
+            conversation_id,
+            sender_id,
+            content,
+            now,
+        ))
+    })
+}
+
+/// Internal function to append a message to a conversation
+///
+/// Updates conversation metadata, advances the sender's read marker and prunes
+/// the oldest messages beyond the conversation's retention limit.
+fn append_message(
+    state: &mut SocialNetworkState,
+    conversation_id: u64,
+    sender_id: UserId,
+    content: MessageContent,
+    now: u64,
+) -> DirectMessage {
+    let message_id = state.next_message_id;
+    state.next_message_id = state.next_message_id.saturating_add(1);
+
+    let message = DirectMessage {
+        id: message_id,
+        conversation_id,
+        sender: sender_id,
+        content,
+        sent_at: now,
+    };
+
+    let retention_limit = state
+        .conversations
+        .get_mut(&conversation_id)
+        .and_then(|conversation| {
+            conversation.last_message_at = now;
+            conversation.message_count = conversation.message_count.saturating_add(1);
+            conversation.retention_limit
+        });
+
+    let messages = state
+        .conversation_messages
+        .entry(conversation_id)
+        .or_default();
+    messages.push(message.clone());
+    if let Some(limit) = retention_limit {
+        let excess = messages.len().saturating_sub(limit as usize);
+        messages.drain(..excess);
+    }
+
+    // Sending implies the sender has seen everything before their own message
+    state
+        .conversation_read_markers
+        .insert((conversation_id, sender_id), message_id);
+
+    let recipients: Vec<UserId> = state
+        .conversations
+        .get(&conversation_id)
+        .map(|conversation| conversation.participants.clone())
+        .unwrap_or_default();
+    for recipient in recipients
+        .into_iter()
+        .filter(|&user_id| user_id != sender_id)
+    {
+        queue_push(
+            state,
+            recipient,
+            PushNotification {
+                kind: PushKind::DirectMessage,
+                actor: sender_id,
+                post_id: None,
+                conversation_id: Some(conversation_id),
+                created_at: now,
+            },
+        );
+    }
+
+    message
+}
+
+/// Internal function to count a participant's unread messages in a conversation
+///
+/// Walks backwards from the newest message, so cost is bounded by the unread count.
+fn unread_in_conversation(
+    state: &SocialNetworkState,
+    conversation_id: u64,
+    user_id: UserId,
+) -> u64 {
+    let read_marker = state
+        .conversation_read_markers
+        .get(&(conversation_id, user_id))
+        .copied();
+
+    state
+        .conversation_messages
+        .get(&conversation_id)
+        .map(|messages| {
+            messages
+                .iter()
+                .rev()
+                .take_while(|message| {
+                    read_marker
+                        .map(|marker| message.id > marker)
+                        .unwrap_or(true)
+                })
+                .filter(|message| message.sender != user_id)
+                .count() as u64
+        })
+        .unwrap_or(0)
+}
+
+/// Internal function to resolve the configured vetKD key name
+fn vetkd_key_name() -> String {
+    with_state(|state| state.vetkd_key_name.clone())
+        .unwrap_or_else(|| vetkd::DEFAULT_VETKD_KEY_NAME.to_string())
+}
+
+/// Internal function to order a participant pair for index lookups
+fn participant_pair(a: UserId, b: UserId) -> (UserId, UserId) {
+    if a <= b {
+        (a, b)
+    } else {
+        (b, a)
+    }
+}
+
+/// Internal function to check whether `sender` may message `recipient`
+///
+/// Enforces blocks in both directions and the recipient's `MessagePrivacy`.
+fn check_message_privacy(
+    state: &SocialNetworkState,
+    sender: UserId,
+    recipient: UserId,
+) -> Result<(), String> {
+    let recipient_profile = state.users.get(&recipient).ok_or("User does not exist")?;
+
+    if is_blocked_between(state, sender, recipient) {
+        return Err("Cannot message this user".to_string());
+    }
+
+    match recipient_profile.privacy_settings.message_privacy {
+        MessagePrivacy::Everyone => Ok(()),
+        MessagePrivacy::FollowersOnly => {
+            if is_follower_of(state, sender, recipient) {
+                Ok(())
+            } else {
+                Err("User only accepts messages from followers".to_string())
+            }
+        }
+        MessagePrivacy::Nobody => Err("User does not accept direct messages".to_string()),
+    }
+}
+
+/// Internal function to find or start the conversation between two users
+fn get_or_create_conversation(
+    state: &mut SocialNetworkState,
+    a: UserId,
+    b: UserId,
+    now: u64,
+) -> u64 {
+    let pair = participant_pair(a, b);
+    if let Some(&conversation_id) = state.conversation_index.get(&pair) {
+        return conversation_id;
+    }
+
+    let conversation_id = state.next_conversation_id;
+    state.next_conversation_id = state.next_conversation_id.saturating_add(1);
+
+    state.conversations.insert(
+        conversation_id,
+        Conversation {
+            id: conversation_id,
+            kind: ConversationKind::Direct,
+            participants: vec![pair.0, pair.1],
+            title: None,
+            retention_limit: None,
+            created_at: now,
+            last_message_at: now,
+            message_count: 0,
+            token_gate: None,
+        },
+    );
+    state.conversation_index.insert(pair, conversation_id);
+    for participant in [pair.0, pair.1] {
+        state
+            .user_conversations
+            .entry(participant)
+            .or_default()
+            .insert(conversation_id);
+    }
+
+    conversation_id
+}
+
+// ============================================================================
+// GROUP CONVERSATIONS
+// ============================================================================
+
+/// Creates a group conversation owned by the caller
+///
+/// # Arguments
+/// * `title` - Group title (1-100 characters)
+/// * `members` - Initial members to invite (besides the caller)
+/// * `retention_limit` - Messages to retain (10-10,000, defaults to 10,000)
+///
+/// # Security
+/// * Every invitee must be eligible: not blocked in either direction and
+///   accepting messages from the caller under their `MessagePrivacy`
+/// * Capped at MAX_GROUP_MEMBERS members
+#[update]
+pub async fn create_group_conversation(
+    title: String,
+    members: Vec<Principal>,
+    retention_limit: Option<u64>,
+) -> Result<Conversation, String> {
+    let owner_id = authenticate_user()?;
+
+    validate_group_title(&title)?;
+    let retention_limit = retention_limit.unwrap_or(MAX_GROUP_RETENTION);
+    validate_group_retention(retention_limit)?;
+
+    let invitees: BTreeSet<UserId> = members
+        .into_iter()
+        .map(UserId)
+        .filter(|member| *member != owner_id)
+        .collect();
+    if invitees.len().saturating_add(1) > MAX_GROUP_MEMBERS {
+        return Err(SocialNetworkError::BatchSizeTooLarge {
+            max: MAX_GROUP_MEMBERS.saturating_sub(1),
+            requested: invitees.len(),
+        }
+        .into());
+    }
+
+    check_action_gate(owner_id, "create_group_conversation")?;
+    check_rate_limit(&owner_id, "create_group_conversation", 5, 3_600)?; // 5 groups per hour
+
+    with_state_mut(|state| {
+        for &invitee in &invitees {
+            check_message_privacy(state, owner_id, invitee)?;
+        }
+
+        let now = time();
+        let conversation_id = state.next_conversation_id;
+        state.next_conversation_id = state.next_conversation_id.saturating_add(1);
+
+        let mut roster = BTreeMap::new();
+        roster.insert(
+            owner_id,
+            GroupMember {
+                user_id: owner_id,
+                role: GroupRole::Owner,
+                joined_at: now,
+            },
+        );
+        for &invitee in &invitees {
+            roster.insert(
+                invitee,
+                GroupMember {
+                    user_id: invitee,
+                    role: GroupRole::Member,
+                    joined_at: now,
+                },
+            );
+        }
+
+        let conversation = Conversation {
+            id: conversation_id,
+            kind: ConversationKind::Group,
+            participants: roster.keys().copied().collect(),
+            title: Some(title),
+            retention_limit: Some(retention_limit),
+            created_at: now,
+            last_message_at: now,
+            message_count: 0,
+            token_gate: None,
+        };
+
+        for &member in roster.keys() {
+            state
+                .user_conversations
+                .entry(member)
+                .or_default()
+                .insert(conversation_id);
+        }
+        state.group_members.insert(conversation_id, roster);
+        state
+            .conversations
+            .insert(conversation_id, conversation.clone());
+
+        Ok(conversation)
+    })
+}
+
+/// Adds a member to a group conversation
+///
+/// # Security
+/// * Group owner and admins only
+/// * The new member must be eligible (no blocks, `MessagePrivacy` allows the caller)
+/// * In token-gated groups the new member must hold the required asset
+#[update]
+pub async fn add_group_member(conversation_id: u64, member: Principal) -> Result<(), String> {
+    let user_id = authenticate_user()?;
+    let member_id = UserId(member);
+
+    let gate = with_state(|state| {
+        require_group_manager(state, conversation_id, user_id)?;
+        Ok::<_, String>(
+            state
+                .conversations
+                .get(&conversation_id)
+                .and_then(|conversation| conversation.token_gate.clone()),
+        )
+    })?;
+    if let Some(gate) = gate {
+        if !passes_token_gate(member_id, &gate).await? {
+            return Err("User does not hold the asset required by this group".to_string());
+        }
+    }
+
+    with_state_mut(|state| {
+        require_group_manager(state, conversation_id, user_id)?;
+
+        let roster = state
+            .group_members
+            .get(&conversation_id)
+            .ok_or("Group not found")?;
+        if roster.contains_key(&member_id) {
+            return Err("User is already a group member".to_string());
+        }
+        if roster.len() >= MAX_GROUP_MEMBERS {
+            return Err("Group member limit reached".to_string());
+        }
+
+        check_message_privacy(state, user_id, member_id)?;
+
+        let now = time();
+        if let Some(roster) = state.group_members.get_mut(&conversation_id) {
+            roster.insert(
+                member_id,
+                GroupMember {
+                    user_id: member_id,
+                    role: GroupRole::Member,
+                    joined_at: now,
+                },
+            );
+        }
+        state
+            .user_conversations
+            .entry(member_id)
+            .or_default()
+            .insert(conversation_id);
+        sync_group_participants(state, conversation_id);
+        Ok(())
+    })
+}
+
+/// Removes a member from a group conversation
+///
+/// # Security
+/// * Group owner and admins only; admins cannot remove the owner or other admins
+#[update]
+pub async fn remove_group_member(conversation_id: u64, member: Principal) -> Result<(), String> {
+    let user_id = authenticate_user()?;
+    let member_id = UserId(member);
+
+    with_state_mut(|state| {
+        let caller_role = require_group_manager(state, conversation_id, user_id)?;
+
+        let target_role = state
+            .group_members
+            .get(&conversation_id)
+            .and_then(|roster| roster.get(&member_id))
+            .map(|membership| membership.role.clone())
+            .ok_or("User is not a group member")?;
+        if target_role == GroupRole::Owner
+            || (target_role == GroupRole::Admin && caller_role != GroupRole::Owner)
+        {
+            return Err("Cannot remove this member".to_string());
+        }
+
+        remove_from_group(state, conversation_id, member_id);
+        Ok(())
+    })
+}
+
+/// Changes a group member's role
+///
+/// # Security
+/// * Group owner only; ownership cannot be granted (the owner can leave to hand it over)
+#[update]
+pub async fn set_group_member_role(
+    conversation_id: u64,
+    member: Principal,
+    role: GroupRole,
+) -> Result<(), String> {
+    let user_id = authenticate_user()?;
+    let member_id = UserId(member);
+
+    if role == GroupRole::Owner {
+        return Err("Ownership cannot be assigned".to_string());
+    }
+
+    with_state_mut(|state| {
+        let roster = state
+            .group_members
+            .get_mut(&conversation_id)
+            .ok_or("Group not found")?;
+        let is_owner = roster
+            .get(&user_id)
+            .map(|membership| membership.role == GroupRole::Owner)
+            .unwrap_or(false);
+        if !is_owner {
+            return Err("Only the group owner can change roles".to_string());
+        }
+        if member_id == user_id {
+            return Err("Cannot change your own role".to_string());
+        }
+
+        let membership = roster
+            .get_mut(&member_id)
+            .ok_or("User is not a group member")?;
+        membership.role = role;
+        Ok(())
+    })
+}
+
+/// Leaves a group conversation
+///
+/// If the owner leaves, ownership passes to the longest-standing admin (or
+/// member); the group is deleted when its last member leaves.
+#[update]
+pub async fn leave_group(conversation_id: u64) -> Result<(), String> {
+    let user_id = authenticate_user()?;
+
+    with_state_mut(|state| {
+        let was_owner = state
+            .group_members
+            .get(&conversation_id)
+            .and_then(|roster| roster.get(&user_id))
+            .map(|membership| membership.role == GroupRole::Owner)
+            .ok_or("Not a member of this group")?;
+
+        remove_from_group(state, conversation_id, user_id);
+
+        let roster = state
+            .group_members
+            .get_mut(&conversation_id)
+            .ok_or("Group not found")?;
+        if roster.is_empty() {
+            state.group_members.remove(&conversation_id);
+            state.conversations.remove(&conversation_id);
+            state.conversation_messages.remove(&conversation_id);
+            return Ok(());
+        }
+
+        if was_owner {
+            let successor = roster
+                .values()
+                .min_by_key(|membership| {
+                    (membership.role != GroupRole::Admin, membership.joined_at)
+                })
+                .map(|membership| membership.user_id);
+            if let Some(successor) = successor.and_then(|id| roster.get_mut(&id)) {
+                successor.role = GroupRole::Owner;
+            }
+        }
+
+        Ok(())
+    })
+}
+
+/// Changes a group's message retention limit, pruning older messages immediately
+///
+/// # Security
+/// * Group owner and admins only
+#[update]
+pub async fn set_group_retention(conversation_id: u64, retention_limit: u64) -> Result<(), String> {
+    let user_id = authenticate_user()?;
+    validate_group_retention(retention_limit)?;
+
+    with_state_mut(|state| {
+        require_group_manager(state, conversation_id, user_id)?;
+
+        if let Some(conversation) = state.conversations.get_mut(&conversation_id) {
+            conversation.retention_limit = Some(retention_limit);
+        }
+        if let Some(messages) = state.conversation_messages.get_mut(&conversation_id) {
+            let excess = messages.len().saturating_sub(retention_limit as usize);
+            messages.drain(..excess);
+        }
+        Ok(())
+    })
+}
+
+/// Sends a message to a group conversation
+///
+/// # Security
+/// * Group members only
+/// * Shares the direct message rate limit
+#[update]
+pub async fn send_group_message(
+    conversation_id: u64,
+    content: String,
+) -> Result<DirectMessage, String> {
+    let sender_id = authenticate_user()?;
+    let content = MessageContent::Text(content);
+    validate_message_content(&content)?;
+
+    check_rate_limit(&sender_id, "send_message", 60, 60)?;
+
+    with_state_mut(|state| {
+        let is_member = state
+            .group_members
+            .get(&conversation_id)
+            .map(|roster| roster.contains_key(&sender_id))
+            .unwrap_or(false);
+        if !is_member {
+            return Err("Not a member of this group".to_string());
+        }
+
+        Ok(append_message(
+            state,
+            conversation_id,
+            sender_id,
+            content,
+            time(),
+        ))
+    })
+}
+
+/// Gets the members of a group conversation
+///
+/// # Security
+/// * Group members only
+#[query]
+pub fn get_group_members(conversation_id: u64) -> Result<Vec<GroupMember>, String> {
+    let user_id = authenticate_user()?;
+
+    with_state(|state| {
+        let roster = state
+            .group_members
+            .get(&conversation_id)
+            .ok_or("Group not found")?;
+        if !roster.contains_key(&user_id) {
+            return Err("Not a member of this group".to_string());
+        }
+        Ok(roster.values().cloned().collect())
+    })
+}
+
+/// Internal function to ensure the user can manage a group; returns their role
+fn require_group_manager(
+    state: &SocialNetworkState,
+    conversation_id: u64,
+    user_id: UserId,
+) -> Result<GroupRole, String> {
+    let role = state
+        .group_members
+        .get(&conversation_id)
+        .ok_or("Group not found")?
+        .get(&user_id)
+        .map(|membership| membership.role.clone())
+        .ok_or("Not a member of this group")?;
+
+    if role.can_manage() {
+        Ok(role)
+    } else {
+        Err("Group admin rights required".to_string())
+    }
+}
+
+/// Internal function to remove a member from a group and its indices
+fn remove_from_group(state: &mut SocialNetworkState, conversation_id: u64, member_id: UserId) {
+    if let Some(roster) = state.group_members.get_mut(&conversation_id) {
+        roster.remove(&member_id);
+    }
+    if let Some(conversations) = state.user_conversations.get_mut(&member_id) {
+        conversations.remove(&conversation_id);
+    }
+    state
+        .conversation_read_markers
+        .remove(&(conversation_id, member_id));
+    sync_group_participants(state, conversation_id);
+}
+
+/// Internal function to mirror the group roster into the conversation's participants
+fn sync_group_participants(state: &mut SocialNetworkState, conversation_id: u64) {
+    let participants: Vec<UserId> = state
+        .group_members
+        .get(&conversation_id)
+        .map(|roster| roster.keys().copied().collect())
+        .unwrap_or_default();
+    if let Some(conversation) = state.conversations.get_mut(&conversation_id) {
+        conversation.participants = participants;
+    }
+}
+
+// ============================================================================
+// HTTP GATEWAY
+// ============================================================================
+
+/// Serves public resources over the HTTP gateway
+///
+/// # Routes
+/// - `/users/{handle}/feed.xml` - RSS feed of a public profile's public posts
+/// - `/tags/{tag}/feed.xml` - RSS feed of recent public posts using `#tag`
+/// - `/users/{handle}`, `/users/{handle}/outbox`, `/users/{handle}/followers`,
+///   `/posts/{id}` - ActivityPub documents (when federation is configured)
+/// - `POST /users/{handle}/inbox` - upgraded to `http_request_update`
+/// - `/.well-known/webfinger?resource=acct:{handle}@{domain}` - handle discovery
+///
+/// # Privacy
+/// * Only public posts by authors with public profiles are ever served
+/// * Responses are uncertified, so they are served from the `raw` domain
+#[query]
+pub fn http_request(request: HttpRequest) -> HttpResponse {
+    let route = http::route(request.path());
+
+    if request.method == "POST" && matches!(route, Route::Inbox(_)) {
+        return HttpResponse::upgrade();
+    }
+    if request.method != "GET" && request.method != "HEAD" {
+        return HttpResponse::error(405, "Method not allowed");
+    }
+
+    let base_url = request.base_url(ic_cdk::id());
+    let wants_activity = activitypub::is_activity_request(request.header("accept"));
+    match route {
+        Route::UserFeed(handle) => user_feed_response(&handle, &base_url),
+        Route::TagFeed(tag) => tag_feed_response(&tag, &base_url),
+        Route::WebFinger => webfinger_response(&request),
+        Route::Avatar(asset_id) => avatar_store::get(asset_id)
+            .map(|avatar| HttpResponse::immutable(&avatar.content_type, avatar.data))
+            .unwrap_or_else(HttpResponse::not_found),
+        Route::Audio(asset_id) => audio_response(asset_id),
+        Route::Sitemap => sitemap_response(&base_url),
+        Route::Actor(_) | Route::Outbox(_) | Route::Followers(_) | Route::Post(_)
+            if wants_activity =>
+        {
+            activitypub_response(&route)
+        }
+        Route::Actor(handle) => profile_page_response(&handle, &base_url),
+        Route::Post(id) => post_page_response(PostId(id), &base_url),
+        _ => HttpResponse::not_found(),
+    }
+}
+
+/// Handles gateway requests that need to change state
+///
+/// Only ActivityPub inbox deliveries are upgraded to update calls.
+#[update]
+pub async fn http_request_update(request: HttpRequest) -> HttpResponse {
+    match http::route(request.path()) {
+        Route::Inbox(handle) if request.method == "POST" => {
+            handle_inbox_delivery(&handle, &request).await
+        }
+        _ => HttpResponse::not_found(),
+    }
+}
+
+/// Internal function to render the RSS feed of a user's public posts
+fn user_feed_response(handle: &str, base_url: &str) -> HttpResponse {
+    with_state(|state| {
+        let Some(profile) = resolve_handle(state, handle, time())
+            .and_then(|user_id| state.users.get(&user_id))
+            .filter(|profile| {
+                matches!(
+                    profile.privacy_settings.profile_visibility,
+                    ProfileVisibility::Public
+                )
+            })
+        else {
+            return HttpResponse::not_found();
+        };
+
+        let items: Vec<http::FeedItem> = state
+            .user_posts
+            .get(&profile.id)
+            .map(|post_ids| {
+                post_ids
+                    .iter()
+                    .rev()
+                    .filter_map(|post_id| state.posts.get(post_id))
+                    .filter(|post| post.is_syndicated())
+                    .take(http::FEED_ITEMS_LIMIT)
+                    .map(|post| feed_item(state, post, base_url))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let channel = http::FeedChannel {
+            title: format!("{} (@{})", profile.display_name, profile.handle),
+            link: format!("{base_url}/users/{}", profile.handle),
+            self_link: format!("{base_url}/users/{}/feed.xml", profile.handle),
+            description: if profile.bio.is_empty() {
+                format!("Public posts by @{} on deCentra", profile.handle)
+            } else {
+                profile.bio.clone()
+            },
+        };
+
+        HttpResponse::ok(
+            "application/rss+xml; charset=utf-8",
+            http::render_rss(&channel, &items).into_bytes(),
+        )
+    })
+}
+
+/// Internal function to render the RSS feed of recent public posts with a hashtag
+///
+/// Posts by users who turned off `searchable` are left out.
+fn tag_feed_response(tag: &str, base_url: &str) -> HttpResponse {
+    with_state(|state| {
+        let items: Vec<http::FeedItem> = state
+            .posts
+            .values()
+            .rev() // Post IDs are sequential, so this is newest first
+            .take(http::TAG_FEED_SCAN_LIMIT)
+            .filter(|post| post.is_syndicated() && is_discoverable(state, post.author_id))
+            .filter(|post| http::extract_hashtags(&post_content(state, post)).contains(tag))
+            .take(http::FEED_ITEMS_LIMIT)
+            .map(|post| feed_item(state, post, base_url))
+            .collect();
+
+        let channel = http::FeedChannel {
+            title: format!("#{tag} on deCentra"),
+            link: format!("{base_url}/tags/{tag}"),
+            self_link: format!("{base_url}/tags/{tag}/feed.xml"),
+            description: format!("Recent public posts tagged #{tag}"),
+        };
+
+        HttpResponse::ok(
+            "application/rss+xml; charset=utf-8",
+            http::render_rss(&channel, &items).into_bytes(),
+        )
+    })
+}
+
+/// Internal function to convert a post into a feed entry
+fn feed_item(state: &SocialNetworkState, post: &Post, base_url: &str) -> http::FeedItem {
+    http::FeedItem {
+        link: format!("{base_url}/posts/{}", post.id.0),
+        content: post_content(state, post).into_owned(),
+        published_at: post.created_at,
+    }
+}
+
+/// Internal function to render a public post as HTML with link-preview tags
+///
+/// Shared links unfurl from these tags; the page body is a plain-text
+/// fallback for crawlers and browsers without the app.
+fn post_page_response(post_id: PostId, base_url: &str) -> HttpResponse {
+    with_state(|state| {
+        let Some((post, author)) = state
+            .posts
+            .get(&post_id)
+            .filter(|post| post.is_syndicated())
+            .and_then(|post| Some((post, public_profile(state, post.author_id)?)))
+        else {
+            return HttpResponse::not_found();
+        };
+
+        let content = post_content(state, post);
+        let meta = http::PageMeta {
+            title: format!("{} (@{}) on deCentra", author.display_name, author.handle),
+            description: content.to_string(),
+            url: format!("{base_url}/posts/{}", post.id.0),
+            kind: "article",
+            image: feed_filter::first_image_link(&content).map(str::to_string),
+            published_at: Some(post.created_at),
+            noindex: !author.privacy_settings.searchable,
+        };
+        HttpResponse::ok(
+            "text/html; charset=utf-8",
+            http::render_html_page(&meta, &content).into_bytes(),
+        )
+    })
+}
+
+/// Internal function to render a public profile as HTML with link-preview tags
+fn profile_page_response(handle: &str, base_url: &str) -> HttpResponse {
+    with_state(|state| {
+        let Some(profile) = resolve_handle(state, handle, time())
+            .and_then(|user_id| public_profile(state, user_id))
+        else {
+            return HttpResponse::not_found();
+        };
+
+        let description = if profile.bio.is_empty() {
+            format!("Public posts by @{} on deCentra", profile.handle)
+        } else {
+            profile.bio.clone()
+        };
+        let meta = http::PageMeta {
+            title: format!("{} (@{})", profile.display_name, profile.handle),
+            description: description.clone(),
+            url: format!("{base_url}/users/{}", profile.handle),
+            kind: "profile",
+            image: profile
+                .avatar
+                .strip_prefix("asset:")
+                .map(|asset_id| format!("{base_url}/avatars/{asset_id}")),
+            published_at: None,
+            noindex: !profile.privacy_settings.searchable,
+        };
+        HttpResponse::ok(
+            "text/html; charset=utf-8",
+            http::render_html_page(&meta, &description).into_bytes(),
+        )
+    })
+}
+
+/// Internal function to list public profiles and posts for search engines
+///
+/// Users who turned off `searchable` are left out along with their posts.
+/// Profiles come first, then the newest posts, up to SITEMAP_URL_LIMIT URLs.
+fn sitemap_response(base_url: &str) -> HttpResponse {
+    with_state(|state| {
+        let profiles = state
+            .users
+            .values()
+            .filter(|profile| profile.privacy_settings.is_discoverable())
+            .map(|profile| http::SitemapEntry {
+                url: format!("{base_url}/users/{}", profile.handle),
+                modified_at: profile.updated_at,
+            });
+        let posts = state
+            .posts
+            .values()
+            .rev() // Post IDs are sequential, so this is newest first
+            .filter(|post| post.is_syndicated() && is_discoverable(state, post.author_id))
+            .map(|post| http::SitemapEntry {
+                url: format!("{base_url}/posts/{}", post.id.0),
+                modified_at: post.edited_at.unwrap_or(post.created_at),
+            });
+        let entries: Vec<http::SitemapEntry> = profiles
+            .chain(posts)
+            .take(http::SITEMAP_URL_LIMIT)
+            .collect();
+
+        HttpResponse::ok(
+            "application/xml; charset=utf-8",
+            http::render_sitemap(&entries).into_bytes(),
+        )
+    })
+}
+
+/// Internal function to check whether discovery surfaces may list a user
+///
+/// See `PrivacySettings::is_discoverable`.
+fn is_discoverable(state: &SocialNetworkState, user_id: UserId) -> bool {
+    state
+        .users
+        .get(&user_id)
+        .is_some_and(|profile| profile.privacy_settings.is_discoverable())
+}
+
+/// Internal function to look up a profile whose visibility is public
+fn public_profile(state: &SocialNetworkState, user_id: UserId) -> Option<&UserProfile> {
+    state.users.get(&user_id).filter(|profile| {
+        matches!(
+            profile.privacy_settings.profile_visibility,
+            ProfileVisibility::Public
+        )
+    })
+}
+
+// ============================================================================
+// ACTIVITYPUB FEDERATION
+// ============================================================================
+
+/// Enables ActivityPub federation for the instance
+///
+/// # Arguments
+/// * `domain` - Public domain routed to this canister's HTTP gateway (e.g. `decentra.social`)
+/// * `private_key_pem` - RSA key (PKCS#1 or PKCS#8 PEM) used to sign deliveries
+/// * `public_key_pem` - Matching public key advertised on actor documents
+///
+/// # Security
+/// * Admins only; the private key is never returned by any endpoint
+#[update]
+pub async fn set_federation_config(
+    domain: String,
+    private_key_pem: String,
+    public_key_pem: String,
+) -> Result<(), String> {
+    let admin_id = authenticate_user()?;
+    require_admin(&admin_id)?;
+
+    let domain = domain.trim().to_lowercase();
+    if domain.is_empty()
+        || domain.len() > 253
+        || !domain
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '-')
+    {
+        return Err("Invalid federation domain".to_string());
+    }
+
+    let private_key = rsa::RsaPrivateKey::from_pem(&private_key_pem)?;
+    if private_key.public_key() != rsa::RsaPublicKey::from_pem(&public_key_pem)? {
+        return Err("Public key does not match the private key".to_string());
+    }
+
+    with_state_mut(|state| {
+        state.federation = Some(FederationConfig {
+            domain: domain.clone(),
+            private_key_pem,
+            public_key_pem,
+        });
+        record_audit_event(
+            state,
+            admin_id,
+            AuditAction::FederationConfigured,
+            None,
+            domain,
+        );
+    });
+    Ok(())
+}
+
+/// Disables ActivityPub federation; remote followers are kept for re-enabling
+///
+/// # Security
+/// * Admins only
+#[update]
+pub async fn disable_federation() -> Result<(), String> {
+    let admin_id = authenticate_user()?;
+    require_admin(&admin_id)?;
+
+    with_state_mut(|state| {
+        state.federation = None;
+        record_audit_event(
+            state,
+            admin_id,
+            AuditAction::FederationDisabled,
+            None,
+            String::new(),
+        );
+    });
+    Ok(())
+}
+
+/// Gets the federation domain, if federation is enabled
+#[query]
+pub fn get_federation_domain() -> Option<String> {
+    with_state(|state| {
+        state
+            .federation
+            .as_ref()
+            .map(|config| config.domain.clone())
+    })
+}
+
+/// Gets the caller's followers on other ActivityPub servers
+#[query]
+pub fn get_my_remote_followers(
+    limit: Option<usize>,
+    offset: Option<usize>,
+) -> Result<RemoteFollowersPage, String> {
+    let user_id = authenticate_user()?;
+    let limit = limit
+        .unwrap_or(DEFAULT_CONNECTIONS_LIMIT)
+        .min(MAX_CONNECTIONS_LIMIT);
+    let offset = offset.unwrap_or(0);
+
+    Ok(with_state(|state| {
+        let followers = state.remote_followers.get(&user_id);
+        let (page, next_cursor) = page_at(
+            followers
+                .into_iter()
+                .flat_map(|followers| followers.values()),
+            offset,
+            limit,
+        );
+        RemoteFollowersPage {
+            followers: page.into_iter().cloned().collect(),
+            total: Some(followers.map_or(0, |followers| followers.len() as u64)),
+            has_more: next_cursor.is_some(),
+            next_cursor,
+        }
+    }))
+}
+
+/// Strips headers from outcall responses (federation, translation) so replicas reach consensus
+#[query]
+fn transform_federation_response(
+    args: ic_cdk::api::management_canister::http_request::TransformArgs,
+) -> ic_cdk::api::management_canister::http_request::HttpResponse {
+    activitypub::transform_response(args)
+}
+
+/// Internal function to resolve a handle to a federated (public) profile
+fn federated_profile(state: &SocialNetworkState, handle: &str) -> Option<UserProfile> {
+    resolve_handle(state, handle, time())
+        .and_then(|user_id| state.users.get(&user_id))
+        .filter(|profile| {
+            matches!(
+                profile.privacy_settings.profile_visibility,
+                ProfileVisibility::Public
+            )
+        })
+        .cloned()
+}
+
+/// Internal function to render ActivityPub documents for GET routes
+fn activitypub_response(route: &Route) -> HttpResponse {
+    with_state(|state| {
+        let Some(config) = state.federation.as_ref() else {
+            return HttpResponse::not_found();
+        };
+        let base_url = format!("https://{}", config.domain);
+
+        let document = match route {
+            Route::Actor(handle) => federated_profile(state, handle).map(|profile| {
+                activitypub::actor_document(&base_url, &profile, &config.public_key_pem)
+            }),
+            Route::Outbox(handle) => federated_profile(state, handle).map(|profile| {
+                let public_posts: Vec<&Post> = state
+                    .user_posts
+                    .get(&profile.id)
+                    .map(|post_ids| {
+                        post_ids
+                            .iter()
+                            .rev()
+                            .filter_map(|post_id| state.posts.get(post_id))
+                            .filter(|post| post.is_syndicated())
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                let recent: Vec<Post> = public_posts
+                    .iter()
+                    .take(http::FEED_ITEMS_LIMIT)
+                    .map(|post| inflated_post(state, post))
+                    .collect();
+                activitypub::outbox_collection(
+                    &base_url,
+                    &profile.handle,
+                    public_posts.len(),
+                    &recent.iter().collect::<Vec<_>>(),
+                )
+            }),
+            Route::Followers(handle) => federated_profile(state, handle).map(|profile| {
+                let remote = state
+                    .remote_followers
+                    .get(&profile.id)
+                    .map(|followers| followers.len() as u64)
+                    .unwrap_or(0);
+                activitypub::followers_collection(
+                    &base_url,
+                    &profile.handle,
+                    profile.follower_count.saturating_add(remote),
+                )
+            }),
+            Route::Post(id) => state
+                .posts
+                .get(&PostId(*id))
+                .filter(|post| post.is_syndicated())
+                .and_then(|post| {
+                    let author = state.users.get(&post.author_id)?;
+                    federated_profile(state, &author.handle).map(|profile| {
+                        activitypub::note_object(
+                            &base_url,
+                            &profile.handle,
+                            &inflated_post(state, post),
+                        )
+                    })
+                }),
+            _ => None,
+        };
+
+        match document {
+            Some(document) => HttpResponse::ok(
+                activitypub::ACTIVITY_JSON,
+                document.to_string().into_bytes(),
+            ),
+            None => HttpResponse::not_found(),
+        }
+    })
+}
+
+/// Internal function to answer WebFinger lookups for local handles
+///
+/// Resolves `acct:handle@domain` against the federation domain, or against
+/// the request's Host when federation is not configured. Only profiles that
+/// are public and searchable are found, and vacated handles redirect to their
+/// current owner.
+fn webfinger_response(request: &HttpRequest) -> HttpResponse {
+    let Some(resource) = request.query_param("resource") else {
+        return HttpResponse::error(400, "Missing resource parameter");
+    };
+
+    with_state(|state| {
+        let domain = match state.federation.as_ref() {
+            Some(config) => config.domain.clone(),
+            None => request
+                .base_url(ic_cdk::id())
+                .trim_start_matches("https://")
+                .to_string(),
+        };
+
+        let Some(profile) = webfinger_profile(state, &resource, &domain, time()) else {
+            return HttpResponse::not_found();
+        };
+
+        let document =
+            activitypub::webfinger_document(&format!("https://{domain}"), &domain, &profile.handle);
+        let mut response =
+            HttpResponse::ok("application/jrd+json", document.to_string().into_bytes());
+        response
+            .headers
+            .push(("Access-Control-Allow-Origin".to_string(), "*".to_string()));
+        response
+    })
+}
+
+/// Internal function to resolve a WebFinger resource to the profile it names
+///
+/// Remote servers find accounts through WebFinger, so users who turned off
+/// `searchable` can only be followed from a link to their actor document.
+fn webfinger_profile<'a>(
+    state: &'a SocialNetworkState,
+    resource: &str,
+    domain: &str,
+    now: u64,
+) -> Option<&'a UserProfile> {
+    let handle = activitypub::parse_webfinger_resource(resource, domain)?;
+    let user_id = resolve_handle(state, &handle, now)?;
+    state
+        .users
+        .get(&user_id)
+        .filter(|profile| profile.privacy_settings.is_discoverable())
+}
+
+/// Internal function to verify and apply an activity POSTed to a local inbox
+///
+/// # Security
+/// * Body digest, date freshness and an RSA-SHA256 HTTP signature covering
+///   `(request-target) host date digest` are all required
+/// * The signing key must belong to the activity's actor
+async fn handle_inbox_delivery(handle: &str, request: &HttpRequest) -> HttpResponse {
+    let Some((config, profile)) = with_state(|state| {
+        let config = state.federation.clone()?;
+        federated_profile(state, handle).map(|profile| (config, profile))
+    }) else {
+        return HttpResponse::not_found();
+    };
+    let base_url = format!("https://{}", config.domain);
+    let now = time();
+
+    if request.body.len() > activitypub::MAX_INBOX_BODY_BYTES {
+        return HttpResponse::error(413, "Activity too large");
+    }
+
+    let digest_ok = request
+        .header("digest")
+        .map(|digest| activitypub::digest_matches(digest, &request.body))
+        .unwrap_or(false);
+    let date_ok = request
+        .header("date")
+        .and_then(http::parse_http_date)
+        .map(|date| date.abs_diff(now) <= activitypub::MAX_SIGNATURE_AGE_SECONDS * 1_000_000_000)
+        .unwrap_or(false);
+    if !digest_ok || !date_ok {
+        return HttpResponse::error(401, "Invalid digest or date");
+    }
+
+    let params = match request
+        .header("signature")
+        .ok_or_else(|| "Missing signature".to_string())
+        .and_then(activitypub::SignatureParams::parse)
+    {
+        Ok(params) if params.covers_required_headers() => params,
+        _ => return HttpResponse::error(401, "Invalid signature"),
+    };
+    let Ok(signing_string) =
+        activitypub::signing_string("post", &request.url, &params.headers, |name| {
+            request.header(name)
+        })
+    else {
+        return HttpResponse::error(401, "Invalid signature");
+    };
+
+    let Ok(activity) = serde_json::from_slice::<serde_json::Value>(&request.body) else {
+        return HttpResponse::error(400, "Invalid activity");
+    };
+    let Some(actor) = activity["actor"].as_str().map(str::to_string) else {
+        return HttpResponse::error(400, "Activity has no actor");
+    };
+
+    // Try the cached key first and refetch once in case the actor rotated keys
+    let verifies = |remote: &RemoteActor| {
+        remote.id == actor
+            && activitypub::verify_signature(
+                &remote.public_key_pem,
+                &signing_string,
+                &params.signature,
+            )
+    };
+    let cached = with_state(|state| state.remote_actors.get(&params.key_id).cloned());
+    let remote = match cached.filter(|remote| verifies(remote)) {
+        Some(remote) => remote,
+        None if is_low_on_cycles() => {
+            return HttpResponse::error(503, "Temporarily unavailable");
+        }
+        None => match activitypub::fetch_remote_actor(&params.key_id, now).await {
+            Ok(fresh) if verifies(&fresh) => {
+                with_state_mut(|state| {
+                    state
+                        .remote_actors
+                        .insert(fresh.key_id.clone(), fresh.clone())
+                });
+                fresh
+            }
+            _ => return HttpResponse::error(401, "Signature verification failed"),
+        },
+    };
+
+    let local_actor = activitypub::actor_id(&base_url, &profile.handle);
+    match activity["type"].as_str() {
+        Some("Follow") if activity["object"].as_str() == Some(local_actor.as_str()) => {
+            // The Accept is an outcall; the remote server retries the Follow later
+            if is_low_on_cycles() {
+                return HttpResponse::error(503, "Temporarily unavailable");
+            }
+            with_state_mut(|state| {
+                state
+                    .remote_followers
+                    .entry(profile.id)
+                    .or_default()
+                    .insert(
+                        remote.id.clone(),
+                        RemoteFollower {
+                            actor_id: remote.id.clone(),
+                            inbox: remote.shared_inbox.clone().unwrap_or(remote.inbox.clone()),
+                            followed_at: now,
+                        },
+                    );
+            });
+
+            let accept = activitypub::accept_activity(&base_url, &profile.handle, &activity);
+            ic_cdk::spawn(async move {
+                // Best effort: the remote server retries the Follow if the Accept is lost
+                let _ = activitypub::deliver(
+                    &remote.inbox,
+                    &accept,
+                    &base_url,
+                    &profile.handle,
+                    &config.private_key_pem,
+                    now,
+                )
+                .await;
+            });
+        }
+        Some("Undo")
+            if activity["object"]["type"].as_str() == Some("Follow")
+                && activity["object"]["actor"].as_str() == Some(actor.as_str()) =>
+        {
+            with_state_mut(|state| {
+                if let Some(followers) = state.remote_followers.get_mut(&profile.id) {
+                    followers.remove(&actor);
+                }
+            });
+        }
+        // Other activities (replies, likes, boosts) are not imported yet
+        _ => {}
+    }
+
+    HttpResponse::error(202, "Accepted")
+}
+
+/// Internal function to push a new public post to remote followers' inboxes
+///
+/// Deliveries run in the background and are best effort; at most
+/// `MAX_POST_DELIVERIES` distinct inboxes are contacted per post.
+fn federate_post(post_id: PostId) {
+    if is_low_on_cycles() {
+        return;
+    }
+    let Some((config, handle, activity, inboxes)) = with_state(|state| {
+        let config = state.federation.clone()?;
+        let post = state
+            .posts
+            .get(&post_id)
+            .filter(|post| post.is_syndicated())?;
+        let author = state.users.get(&post.author_id)?;
+        let profile = federated_profile(state, &author.handle)?;
+        let inboxes: BTreeSet<String> = state
+            .remote_followers
+            .get(&profile.id)?
+            .values()
+            .map(|follower| follower.inbox.clone())
+            .take(activitypub::MAX_POST_DELIVERIES)
+            .collect();
+
+        let base_url = format!("https://{}", config.domain);
+        let activity =
+            activitypub::create_activity(&base_url, &profile.handle, &inflated_post(state, post));
+        Some((config, profile.handle, activity, inboxes))
+    }) else {
+        return;
+    };
+
+    let base_url = format!("https://{}", config.domain);
+    let now = time();
+    for inbox in inboxes {
+        let (activity, base_url, handle, key) = (
+            activity.clone(),
+            base_url.clone(),
+            handle.clone(),
+            config.private_key_pem.clone(),
+        );
+        ic_cdk::spawn(async move {
+            let _ = activitypub::deliver(&inbox, &activity, &base_url, &handle, &key, now).await;
+        });
+    }
+}
+
+// ============================================================================
+// PROOF OF PERSONHOOD
+// ============================================================================
+
+/// Configures the canister used to attest that accounts are unique humans
+///
+/// # Arguments
+/// * `canister_id` - Personhood provider canister
+/// * `method` - Method taking `(principal)` and replying `(bool)`
+///
+/// # Security
+/// * Admins only
+/// * Recorded in the audit log
+#[update]
+pub async fn set_personhood_provider(canister_id: Principal, method: String) -> Result<(), String> {
+    let admin_id = authenticate_user()?;
+    require_admin(&admin_id)?;
+
+    let method = method.trim().to_string();
+    if method.is_empty() || method.len() > MAX_PERSONHOOD_METHOD_LENGTH {
+        return Err(format!(
+            "Method name must be 1-{MAX_PERSONHOOD_METHOD_LENGTH} characters"
+        ));
+    }
+
+    with_state_mut(|state| {
+        let details = format!("{canister_id}.{method}");
+        state.personhood_provider = Some(PersonhoodProvider {
+            canister_id,
+            method,
+        });
+        record_audit_event(
+            state,
+            admin_id,
+            AuditAction::PersonhoodProviderConfigured,
+            None,
+            details,
+        );
+    });
+    Ok(())
+}
+
+/// Requires (or stops requiring) proof of personhood for an action
+///
+/// # Arguments
+/// * `action` - One of PERSONHOOD_GATEABLE_ACTIONS
+/// * `required` - Whether accounts need a valid proof to perform it
+///
+/// # Security
+/// * Admins only
+/// * Recorded in the audit log
+#[update]
+pub async fn set_personhood_requirement(action: String, required: bool) -> Result<(), String> {
+    let admin_id = authenticate_user()?;
+    require_admin(&admin_id)?;
+
+    if !PERSONHOOD_GATEABLE_ACTIONS.contains(&action.as_str()) {
+        return Err(format!("Unknown action: {action}"));
+    }
+
+    with_state_mut(|state| {
+        if required {
+            state.personhood_required_actions.insert(action.clone());
+        } else {
+            state.personhood_required_actions.remove(&action);
+        }
+        record_audit_event(
+            state,
+            admin_id,
+            AuditAction::PersonhoodRequirementChanged,
+            None,
+            format!("{action}: {required}"),
+        );
+    });
+    Ok(())
+}
+
+/// Lists the actions that currently require proof of personhood
+#[query]
+pub fn get_personhood_requirements() -> Vec<String> {
+    with_state(|state| state.personhood_required_actions.iter().cloned().collect())
+}
+
+/// Proves the caller is a unique human via the configured provider
+///
+/// # Returns
+/// * `Ok(PersonhoodProof)` - Proof stored on the caller's profile
+/// * `Err(String)` - No provider configured, call failed, or not attested
+///
+/// # Security
+/// * Authenticated users with a profile
+/// * Rate limited (5 attempts per day)
+/// * Proofs expire after PERSONHOOD_PROOF_VALIDITY_DAYS and can be renewed
+#[update]
+pub async fn verify_personhood() -> Result<PersonhoodProof, String> {
+    let user_id = authenticate_user()?;
+    if !with_state(|state| state.users.contains_key(&user_id)) {
+        return Err(SocialNetworkError::UserNotFound(user_id).into());
+    }
+    check_rate_limit(&user_id, "verify_personhood", 5, 86_400)?;
+
+    let provider = with_state(|state| state.personhood_provider.clone())
+        .ok_or("Proof of personhood is not configured")?;
+
+    let (attested,): (bool,) = ic_cdk::call(provider.canister_id, &provider.method, (user_id.0,))
+        .await
+        .map_err(|(code, message)| {
+            format!("Personhood provider call failed: {code:?} {message}")
+        })?;
+    if !attested {
+        return Err("Personhood provider did not attest this account".to_string());
+    }
+
+    let now = time();
+    let proof = PersonhoodProof {
+        provider: provider.canister_id,
+        verified_at: now,
+        expires_at: now.saturating_add(PERSONHOOD_PROOF_VALIDITY_DAYS * NANOS_PER_DAY),
+    };
+
+    with_state_mut(|state| {
+        let profile = state.users.get_mut(&user_id).ok_or("User does not exist")?;
+        profile.personhood = Some(proof.clone());
+        profile.updated_at = now;
+        Ok(proof)
+    })
+}
+
+/// Internal function to check for a valid proof of personhood
+fn has_personhood(state: &SocialNetworkState, user_id: &UserId, now: u64) -> bool {
+    state
+        .users
+        .get(user_id)
+        .and_then(|profile| profile.personhood.as_ref())
+        .is_some_and(|proof| proof.is_valid(now))
+}
+
+// ============================================================================
+// TRANSLATION
+// ============================================================================
+
+/// Configures the translation service used by `translate_post`
+///
+/// # Arguments
+/// * `endpoint` - HTTPS URL of a LibreTranslate-compatible `/translate` API
+/// * `api_key` - Key sent with each request (empty if not required)
+///
+/// # Security
+/// * Admins only
+/// * Recorded in the audit log (without the API key)
+#[update]
+pub async fn set_translation_config(endpoint: String, api_key: String) -> Result<(), String> {
+    let admin_id = authenticate_user()?;
+    require_admin(&admin_id)?;
+
+    if !endpoint.starts_with("https://") || endpoint.len() > MAX_OUTCALL_ENDPOINT_LENGTH {
+        return Err(format!(
+            "Endpoint must be an https URL of at most {MAX_OUTCALL_ENDPOINT_LENGTH} characters"
+        ));
+    }
+
+    with_state_mut(|state| {
+        record_audit_event(
+            state,
+            admin_id,
+            AuditAction::TranslationConfigured,
+            None,
+            endpoint.clone(),
+        );
+        state.translation_config = Some(TranslationConfig { endpoint, api_key });
+    });
+    Ok(())
+}
+
+/// Translates a post into the requested language
+///
+/// # Arguments
+/// * `post_id` - Post to translate
+/// * `target_lang` - Language tag such as `en` or `pt-BR`
+///
+/// # Returns
+/// * `Ok(TranslatedPost)` - The original post alongside its translation
+/// * `Err(String)` - Post not visible, invalid language, or service failure
+///
+/// # Security
+/// * Authenticated users who can view the post; gated or paid posts must be
+///   unlocked for the caller
+/// * The caller's word filter applies to the original and the translation
+/// * Translations are cached per (post, language); only cache misses
+///   are rate limited (20 per hour) and cost an outcall
+#[update]
+pub async fn translate_post(
+    post_id: PostId,
+    target_lang: String,
+) -> Result<TranslatedPost, String> {
+    let user_id = authenticate_user()?;
+    validate_language_code(&target_lang)?;
+    let target_lang = target_lang.to_lowercase();
+    let cache_key = (post_id, target_lang.clone());
+
+    let (post, content, cached, config) = with_state(|state| {
+        let post = state
+            .posts
+            .get(&post_id)
+            .filter(|post| can_view_post(state, Some(user_id), post))
+            .ok_or(SocialNetworkError::PostNotFound(post_id))?;
+        if !has_unlocked_post(state, Some(user_id), post, time()) {
+            return Err(SocialNetworkError::Unauthorized(
+                "unlock the post before translating it".to_string(),
+            ));
+        }
+        Ok((
+            post_for_viewer(state, Some(user_id), post),
+            post_content(state, post).into_owned(),
+            state.post_translations.get(&cache_key).cloned(),
+            state.translation_config.clone(),
+        ))
+    })?;
+
+    if let Some(translation) = cached {
+        return Ok(TranslatedPost {
+            original: post,
+            translation: filtered_translation(user_id, translation),
+        });
+    }
+
+    let config = config.ok_or("Translation is not configured")?;
+    require_cycles_headroom("Translation")?;
+    check_rate_limit(&user_id, "translate_post", 20, 3_600)?;
+    let content = translation::translate(&config, &content, &target_lang).await?;
+
+    let translation = PostTranslation {
+        post_id,
+        target_lang,
+        content,
+        translated_at: time(),
+    };
+    with_state_mut(|state| {
+        state
+            .post_translations
+            .insert(cache_key, translation.clone());
+    });
+
+    Ok(TranslatedPost {
+        original: post,
+        translation: filtered_translation(user_id, translation),
+    })
+}
+
+/// Internal function to apply the viewer's word filter to a cached translation
+fn filtered_translation(viewer: UserId, mut translation: PostTranslation) -> PostTranslation {
+    with_state(|state| {
+        let author_id = state
+            .posts
+            .get(&translation.post_id)
+            .map(|post| post.author_id);
+        if let Some((content, _)) = author_id.and_then(|author_id| {
+            apply_word_filter(state, Some(viewer), author_id, &translation.content)
+        }) {
+            translation.content = content;
+        }
+    });
+    translation
+}
+
+// ============================================================================
+// URL BLOCKLIST
+// ============================================================================
+
+/// Blocks links to a domain (and its subdomains)
+///
+/// # Purpose
+/// New posts and comments linking to the domain are rejected, and a
+/// background job re-scans existing posts, flagging matches for review.
+///
+/// # Security
+/// * Moderators and admins only
+/// * Recorded in the audit log
+#[update]
+pub async fn add_blocked_domain(domain: String, reason: String) -> Result<BlockedDomain, String> {
+    let moderator_id = authenticate_for(AppScope::Moderate)?;
+    require_moderator(&moderator_id)?;
+    let domain = domain.trim().trim_end_matches('.').to_lowercase();
+    validate_domain(&domain)?;
+    validate_review_note(&reason)?;
+
+    let entry = with_state_mut(|state| {
+        if state.blocked_domains.contains_key(&domain) {
+            return Err("Domain is already blocked".to_string());
+        }
+        let entry = BlockedDomain {
+            domain: domain.clone(),
+            reason: reason.clone(),
+            added_by: moderator_id,
+            added_at: time(),
+        };
+        state.blocked_domains.insert(domain.clone(), entry.clone());
+        record_audit_event(
+            state,
+            moderator_id,
+            AuditAction::BlockedDomainAdded,
+            None,
+            format!("{domain}: {reason}"),
+        );
+
+        // (Re)start the retro-scan from the oldest post
+        let scan_running = state.blocklist_scan_cursor.is_some();
+        state.blocklist_scan_cursor = Some(0);
+        Ok((entry, scan_running))
+    });
+
+    let (entry, scan_running) = entry?;
+    if !scan_running {
+        ic_cdk_timers::set_timer(Duration::ZERO, scan_posts_for_blocked_domains);
+    }
+    Ok(entry)
+}
+
+/// Unblocks a domain and clears flags raised for it
+///
+/// # Security
+/// * Moderators and admins only
+/// * Recorded in the audit log
+#[update]
+pub async fn remove_blocked_domain(domain: String) -> Result<(), String> {
+    let moderator_id = authenticate_for(AppScope::Moderate)?;
+    require_moderator(&moderator_id)?;
+    let domain = domain.trim().to_lowercase();
+
+    with_state_mut(|state| {
+        if state.blocked_domains.remove(&domain).is_none() {
+            return Err("Domain is not blocked".to_string());
+        }
+        state.flagged_posts.retain(|_, flag| flag.domain != domain);
+        record_audit_event(
+            state,
+            moderator_id,
+            AuditAction::BlockedDomainRemoved,
+            None,
+            domain,
+        );
+        Ok(())
+    })
+}
+
+/// Lists blocked domains
+///
+/// # Security
+/// * Moderators and admins only
+#[query]
+pub fn get_blocked_domains() -> Result<Vec<BlockedDomain>, String> {
+    let user_id = authenticate_for(AppScope::Moderate)?;
+    require_moderator(&user_id)?;
+    Ok(with_state(|state| {
+        state.blocked_domains.values().cloned().collect()
+    }))
+}
+
+/// Lists existing posts flagged by the blocklist retro-scan (newest first)
+///
+/// # Security
+/// * Moderators and admins only
+#[query]
+pub fn get_flagged_posts(
+    limit: Option<usize>,
+    offset: Option<usize>,
+) -> Result<FlaggedPostsPage, String> {
+    let user_id = authenticate_for(AppScope::Moderate)?;
+    require_moderator(&user_id)?;
+
+    let limit = limit
+        .unwrap_or(DEFAULT_CONNECTIONS_LIMIT)
+        .min(MAX_CONNECTIONS_LIMIT);
+    let offset = offset.unwrap_or(0);
+
+    Ok(with_state(|state| {
+        let (page, next_cursor) = page_at(state.flagged_posts.values().rev(), offset, limit);
+        FlaggedPostsPage {
+            posts: page.into_iter().cloned().collect(),
+            total: Some(state.flagged_posts.len() as u64),
+            has_more: next_cursor.is_some(),
+            next_cursor,
+        }
+    }))
+}
+
+/// Configures the safety service used to check links in new posts
+///
+/// # Arguments
+/// * `endpoint` - HTTPS Safe Browsing v4 compatible `threatMatches:find` URL
+/// * `api_key` - Key appended to requests
+///
+/// # Security
+/// * Admins only
+/// * Recorded in the audit log (without the API key)
+#[update]
+pub async fn set_link_safety_config(endpoint: String, api_key: String) -> Result<(), String> {
+    let admin_id = authenticate_user()?;
+    require_admin(&admin_id)?;
+
+    if !endpoint.starts_with("https://") || endpoint.len() > MAX_OUTCALL_ENDPOINT_LENGTH {
+        return Err(format!(
+            "Endpoint must be an https URL of at most {MAX_OUTCALL_ENDPOINT_LENGTH} characters"
+        ));
+    }
+
+    with_state_mut(|state| {
+        record_audit_event(
+            state,
+            admin_id,
+            AuditAction::LinkSafetyConfigured,
+            None,
+            endpoint.clone(),
+        );
+        state.link_safety_config = Some(LinkSafetyConfig { endpoint, api_key });
+    });
+    Ok(())
+}
+
+/// Reduces link safety outcall responses to their deterministic part
+#[query]
+fn transform_link_safety_response(
+    args: ic_cdk::api::management_canister::http_request::TransformArgs,
+) -> ic_cdk::api::management_canister::http_request::HttpResponse {
+    link_safety::transform_response(args)
+}
+
+/// Internal function to check a new post's links in the background
+///
+/// Flagged links are attached to the post as warning labels; failures of
+/// the safety service leave the post unlabelled.
+fn check_post_links(post_id: PostId) {
+    if is_low_on_cycles() {
+        return;
+    }
+    let Some((config, urls)) = with_state(|state| {
+        let config = state.link_safety_config.clone()?;
+        let urls = link_safety::extract_urls(&post_content(state, state.posts.get(&post_id)?));
+        (!urls.is_empty()).then_some((config, urls))
+    }) else {
+        return;
+    };
+
+    ic_cdk::spawn(async move {
+        let Ok(warnings) = link_safety::check_urls(&config, &urls).await else {
+            return;
+        };
+        if warnings.is_empty() {
+            return;
+        }
+        with_state_mut(|state| {
+            if let Some(post) = state.posts.get_mut(&post_id) {
+                post.link_warnings = warnings;
+            }
+        });
+    });
+}
+
+/// Internal function to check one batch of posts against the blocklist
+///
+/// Runs as a self-rescheduling timer so large post sets are scanned in
+/// bounded chunks of BLOCKLIST_SCAN_BATCH_SIZE posts.
+fn scan_posts_for_blocked_domains() {
+    let finished = with_state_mut(|state| {
+        let Some(cursor) = state.blocklist_scan_cursor else {
+            return true;
+        };
+
+        let now = time();
+        let mut last_scanned = None;
+        let mut matches = Vec::new();
+        for (post_id, post) in state
+            .posts
+            .range(PostId(cursor)..)
+            .take(BLOCKLIST_SCAN_BATCH_SIZE)
+        {
+            last_scanned = Some(post_id.0);
+            if let Some(domain) =
+                find_blocked_domain(&post_content(state, post), &state.blocked_domains)
+            {
+                matches.push(FlaggedPost {
+                    post_id: *post_id,
+                    domain,
+                    flagged_at: now,
+                });
+            }
+        }
+        for flag in matches {
+            state.flagged_posts.entry(flag.post_id).or_insert(flag);
+        }
+
+        state.blocklist_scan_cursor = last_scanned.map(|id| id.saturating_add(1));
+        state.blocklist_scan_cursor.is_none()
+    });
+
+    if !finished {
+        ic_cdk_timers::set_timer(Duration::ZERO, scan_posts_for_blocked_domains);
+    }
+}
+
+// ============================================================================
+// TRUSTED IMAGE DOMAINS
+// ============================================================================
+
+/// Trusts an image host for banners, emoji assets and post media
+///
+/// # Arguments
+/// * `domain` - Lowercase domain; its subdomains are trusted too
+///
+/// # Returns
+/// The updated list of trusted domains
+///
+/// # Security
+/// * Admins only
+/// * Recorded in the audit log
+#[update]
+pub async fn add_trusted_image_domain(domain: String) -> Result<Vec<String>, String> {
+    let admin_id = authenticate_user()?;
+    require_admin(&admin_id)?;
+    let domain = domain.trim().trim_end_matches('.').to_lowercase();
+    validate_domain(&domain)?;
+
+    with_state_mut(|state| {
+        let domains = trusted_image_domains_mut(state);
+        if domains.len() >= MAX_TRUSTED_IMAGE_DOMAINS {
+            return Err(format!(
+                "At most {MAX_TRUSTED_IMAGE_DOMAINS} image domains can be trusted"
+            ));
+        }
+        if !domains.insert(domain.clone()) {
+            return Err("Domain is already trusted".to_string());
+        }
+        let domains = domains.iter().cloned().collect();
+        record_audit_event(
+            state,
+            admin_id,
+            AuditAction::TrustedImageDomainAdded,
+            None,
+            domain,
+        );
+        Ok(domains)
+    })
+}
+
+/// Stops trusting an image host
+///
+/// Existing banners, emoji and posts are kept; only new ones are checked.
+///
+/// # Returns
+/// The updated list of trusted domains
+///
+/// # Security
+/// * Admins only
+/// * Recorded in the audit log
+#[update]
+pub async fn remove_trusted_image_domain(domain: String) -> Result<Vec<String>, String> {
+    let admin_id = authenticate_user()?;
+    require_admin(&admin_id)?;
+    let domain = domain.trim().to_lowercase();
+
+    with_state_mut(|state| {
+        let domains = trusted_image_domains_mut(state);
+        if !domains.remove(&domain) {
+            return Err("Domain is not trusted".to_string());
+        }
+        let domains = domains.iter().cloned().collect();
+        record_audit_event(
+            state,
+            admin_id,
+            AuditAction::TrustedImageDomainRemoved,
+            None,
+            domain,
+        );
+        Ok(domains)
+    })
+}
+
+/// Lists the image hosts accepted for banners, emoji assets and post media
+///
+/// Public so clients can check URLs before submitting them.
+#[query]
+pub fn get_trusted_image_domains() -> Vec<String> {
+    with_state(|state| match &state.trusted_image_domains {
+        Some(domains) => domains.iter().cloned().collect(),
+        None => DEFAULT_TRUSTED_IMAGE_DOMAINS
+            .iter()
+            .map(|domain| domain.to_string())
+            .collect(),
+    })
+}
+
+/// Internal function to get the editable domain list, starting from the defaults
+fn trusted_image_domains_mut(state: &mut SocialNetworkState) -> &mut BTreeSet<String> {
+    state.trusted_image_domains.get_or_insert_with(|| {
+        DEFAULT_TRUSTED_IMAGE_DOMAINS
+            .iter()
+            .map(|domain| domain.to_string())
+            .collect()
+    })
+}
+
+// ============================================================================
+// TOKEN GATING
+// ============================================================================
+
+/// Requires viewers of one of the caller's posts to hold an asset
+///
+/// # Arguments
+/// * `gate` - ICRC-1 balance or ICRC-7 holding to require; `None` removes the gate
+///
+/// # Behavior
+/// - Gated posts are left out of RSS feeds, ActivityPub and read replicas
+/// - Other viewers see the post without content until `unlock_post` succeeds
+#[update]
+pub async fn set_post_token_gate(post_id: PostId, gate: Option<TokenGate>) -> Result<(), String> {
+    let user_id = authenticate_user()?;
+    with_state_mut(|state| {
+        let post = state.posts.get_mut(&post_id).ok_or("Post not found")?;
+        if post.author_id != user_id {
+            return Err("Only the author can gate a post".to_string());
+        }
+        let was_syndicated = post.is_syndicated();
+        post.token_gate = gate;
+        post.updated_at = time();
+        sync_replica_post(state, post_id, was_syndicated);
+        Ok(())
+    })
+}
+
+/// Unlocks a gated or paid post for the caller and returns it
+///
+/// # Arguments
+/// * `expected_price` - Price the caller agreed to pay; ignored when no charge
+///   is due
+///
+/// # Behavior
+/// - Token gates: lookups are cached for TOKEN_GATE_CACHE_SECONDS, during
+///   which the content is also included in query responses for the caller
+/// - Paid posts: the first unlock charges `unlock_price` to the caller via an
+///   ICRC-2 approval, paid into the author's earnings account; the purchase
+///   grants permanent access. Subscribers of a post's tier are not charged.
+/// - Paid posts are only charged when `unlock_price` still equals
+///   `expected_price`, so a price raised after the caller looked is rejected
+///
+/// # Security
+/// * Rate limited (30 unlocks per hour)
+#[update]
+pub async fn unlock_post(post_id: PostId, expected_price: u64) -> Result<Post, String> {
+    let user_id = authenticate_user()?;
+    check_rate_limit(&user_id, "unlock_post", 30, 3_600)?;
+
+    let gate = with_state(|state| {
+        state
+            .posts
+            .get(&post_id)
+            .filter(|post| can_view_post(state, Some(user_id), post))
+            .map(|post| post.token_gate.clone())
+    })
+    .ok_or("Post not found")?;
+
+    if let Some(gate) = gate {
+        let is_author = with_state(|state| state.posts[&post_id].author_id == user_id);
+        if !is_author && !passes_token_gate(user_id, &gate).await? {
+            return Err("You do not hold the asset required to view this post".to_string());
+        }
+    }
+
+    let purchase = with_state_mut(|state| {
+        let post = state.posts.get(&post_id).ok_or("Post not found")?;
+        let Some(price) = post.unlock_price else {
+            return Ok(None);
+        };
+        if post.author_id == user_id || has_paid_access(state, user_id, post, time()) {
+            return Ok(None);
+        }
+        if price != expected_price {
+            return Err(format!(
+                "The unlock price is now {price}; confirm the new price to unlock this post"
+            ));
+        }
+        let author_id = post.author_id;
+        if !state.post_unlocks_in_progress.insert((user_id, post_id)) {
+            return Err("A purchase of this post is already in progress".to_string());
+        }
+        Ok(Some((author_id, price)))
+    })?;
+
+    if let Some((author_id, price)) = purchase {
+        let payment = match earnings_ledger() {
+            Ok(ledger) => charge_to_earnings(ledger, user_id, author_id, price)
+                .await
+                .map(|block_index| (ledger, block_index)),
+            Err(error) => Err(error),
+        };
+        with_state_mut(|state| {
+            state.post_unlocks_in_progress.remove(&(user_id, post_id));
+            let (ledger, block_index) = payment?;
+            state.post_unlocks.insert((user_id, post_id));
+            record_transfer(
+                state,
+                user_id,
+                author_id,
+                TransactionKind::PostSold,
+                TransactionRecord {
+                    post_id: Some(post_id),
+                    block_index,
+                    ..TransactionRecord::new(
+                        TransactionKind::PostUnlocked,
+                        TransactionAsset::Token { ledger },
+                        price,
+                        time(),
+                    )
+                },
+            );
+            Ok::<(), String>(())
+        })?;
+    }
+
+    with_state(|state| {
+        state
+            .posts
+            .get(&post_id)
+            .map(|post| post_for_viewer(state, Some(user_id), post))
+            .ok_or_else(|| "Post not found".to_string())
+    })
+}
+
+/// Sets a one-off price for unlocking one of the caller's posts
+///
+/// # Arguments
+/// * `price` - Price in earnings ledger base units; `None` makes the post free
+///
+/// # Behavior
+/// - Paid posts are left out of RSS feeds, ActivityPub and read replicas
+/// - Viewers who already bought the post keep access
+#[update]
+pub async fn set_post_price(post_id: PostId, price: Option<u64>) -> Result<(), String> {
+    let user_id = authenticate_user()?;
+    if price == Some(0) {
+        return Err("Price must be positive".to_string());
+    }
+    if price.is_some() {
+        earnings_ledger()?;
+    }
+
+    with_state_mut(|state| {
+        let post = state.posts.get_mut(&post_id).ok_or("Post not found")?;
+        if post.author_id != user_id {
+            return Err("Only the author can price a post".to_string());
+        }
+        let was_syndicated = post.is_syndicated();
+        post.unlock_price = price;
+        post.updated_at = time();
+        sync_replica_post(state, post_id, was_syndicated);
+        Ok(())
+    })
+}
+
+/// Requires new members of a group conversation to hold an asset
+///
+/// # Security
+/// * Group owner and admins only
+/// * Existing members are not re-checked
+#[update]
+pub async fn set_group_token_gate(
+    conversation_id: u64,
+    gate: Option<TokenGate>,
+) -> Result<Conversation, String> {
+    let user_id = authenticate_user()?;
+    with_state_mut(|state| {
+        require_group_manager(state, conversation_id, user_id)?;
+        let conversation = state
+            .conversations
+            .get_mut(&conversation_id)
+            .ok_or("Group not found")?;
+        conversation.token_gate = gate;
+        Ok(conversation.clone())
+    })
+}
+
+/// Internal function to check a token gate, reusing recent lookups
+async fn passes_token_gate(user_id: UserId, gate: &TokenGate) -> Result<bool, String> {
+    if let Some(passed) = with_state(|state| cached_gate_check(state, user_id, gate, time())) {
+        return Ok(passed);
+    }
+
+    let passed = token_gate::holds(gate, user_id.0).await?;
+    with_state_mut(|state| {
+        let now = time();
+        state
+            .token_gate_checks
+            .retain(|_, check| is_fresh_gate_check(check, now));
+        state.token_gate_checks.insert(
+            (user_id, gate.clone()),
+            TokenGateCheck {
+                passed,
+                checked_at: now,
+            },
+        );
+    });
+    Ok(passed)
+}
+
+/// Internal function to look up a still-valid cached gate check
+fn cached_gate_check(
+    state: &SocialNetworkState,
+    user_id: UserId,
+    gate: &TokenGate,
+    now: u64,
+) -> Option<bool> {
+    state
+        .token_gate_checks
+        .get(&(user_id, gate.clone()))
+        .filter(|check| is_fresh_gate_check(check, now))
+        .map(|check| check.passed)
+}
+
+/// Whether a cached check is younger than TOKEN_GATE_CACHE_SECONDS
+fn is_fresh_gate_check(check: &TokenGateCheck, now: u64) -> bool {
+    now.saturating_sub(check.checked_at) < TOKEN_GATE_CACHE_SECONDS * 1_000_000_000
+}
+
+/// Internal function to decide whether the viewer may see a post's content
+fn has_unlocked_post(
+    state: &SocialNetworkState,
+    viewer: Option<UserId>,
+    post: &Post,
+    now: u64,
+) -> bool {
+    if viewer == Some(post.author_id) {
+        return true;
+    }
+
+    let passes_gate = match &post.token_gate {
+        Some(gate) => viewer.is_some_and(|viewer_id| {
+            cached_gate_check(state, viewer_id, gate, now).unwrap_or(false)
+        }),
+        None => true,
+    };
+    let is_paid_for = match (post.subscriber_tier, post.unlock_price) {
+        (None, None) => true,
+        _ => viewer.is_some_and(|viewer_id| has_paid_access(state, viewer_id, post, now)),
+    };
+    passes_gate && is_paid_for
+}
+
+// ============================================================================
+// CREATOR FUND
+// ============================================================================
+
+/// Configures periodic creator fund distributions
+///
+/// # Behavior
+/// - Every `period_days`, up to `pool_per_period` from the treasury
+///   subaccount is split among creators by the engagement their posts received
+/// - The first distribution happens one period after the fund is first configured
+/// - Distributions never promise more than the treasury holds
+///
+/// # Security
+/// * Admins only
+/// * Recorded in the audit log
+#[update]
+pub async fn set_creator_fund_config(config: CreatorFundConfig) -> Result<(), String> {
+    let admin_id = authenticate_user()?;
+    require_admin(&admin_id)?;
+
+    if !(1..=MAX_CREATOR_FUND_PERIOD_DAYS).contains(&config.period_days) {
+        return Err(format!(
+            "Period must be 1-{MAX_CREATOR_FUND_PERIOD_DAYS} days"
+        ));
+    }
+    if !(1..=100).contains(&config.max_share_percent) {
+        return Err("Maximum share must be 1-100 percent".to_string());
+    }
+
+    with_state_mut(|state| {
+        let details = format!(
+            "{}: {} every {} days",
+            config.ledger, config.pool_per_period, config.period_days
+        );
+        state
+            .creator_fund_last_day
+            .get_or_insert(time() / NANOS_PER_DAY);
+        state.creator_fund_config = Some(config);
+        record_audit_event(
+            state,
+            admin_id,
+            AuditAction::CreatorFundConfigured,
+            None,
+            details,
+        );
+    });
+    Ok(())
+}
+
+/// Gets the creator fund settings, if the fund is enabled
+#[query]
+pub fn get_creator_fund_config() -> Option<CreatorFundConfig> {
+    with_state(|state| state.creator_fund_config.clone())
+}
+
+/// Gets the ledger account that funds creator rewards
+#[query]
+pub fn get_creator_fund_treasury() -> ledger::Account {
+    ledger::canister_account(creator_fund::TREASURY_SUBACCOUNT)
+}
+
+/// Gets the caller's unclaimed rewards and fund history
+#[query]
+pub fn get_my_creator_fund_statement() -> Result<CreatorFundStatement, String> {
+    let user_id = authenticate_user()?;
+    Ok(with_state(|state| CreatorFundStatement {
+        claimable: state
+            .creator_fund_claimable
+            .get(&user_id)
+            .copied()
+            .unwrap_or(0),
+        rewards: state
+            .creator_fund_rewards
+            .get(&user_id)
+            .map(|rewards| rewards.iter().rev().cloned().collect())
+            .unwrap_or_default(),
+        claims: state
+            .creator_fund_claims
+            .get(&user_id)
+            .map(|claims| claims.iter().rev().cloned().collect())
+            .unwrap_or_default(),
+    }))
+}
+
+/// Transfers the caller's unclaimed creator fund rewards
+///
+/// # Arguments
+/// * `to` - Destination account (defaults to the caller's default account)
+///
+/// # Behavior
+/// The ledger fee is deducted from the rewards, so the destination receives
+/// the claimable balance minus the fee.
+///
+/// # Security
+/// * Rate limited (5 claims per hour)
+/// * One claim per creator at a time
+/// * The balance is reserved before the transfer and restored if it fails
+#[update]
+pub async fn claim_creator_fund_rewards(
+    to: Option<ledger::Account>,
+) -> Result<CreatorFundClaim, String> {
+    let user_id = authenticate_user()?;
+    check_rate_limit(&user_id, "claim_creator_fund_rewards", 5, 3_600)?;
+    let config = with_state(|state| state.creator_fund_config.clone())
+        .ok_or("Creator fund is not configured")?;
+    let to = to.unwrap_or(ledger::Account {
+        owner: user_id.0,
+        subaccount: None,
+    });
+
+    let fee = ledger::fee(config.ledger).await?;
+
+    let reserved = with_state_mut(|state| {
+        if state.creator_fund_pending_claims.contains_key(&user_id) {
+            return Err("A claim is already in progress".to_string());
+        }
+        let claimable = state
+            .creator_fund_claimable
+            .get(&user_id)
+            .copied()
+            .filter(|amount| *amount > 0)
+            .ok_or("No creator fund rewards to claim")?;
+        if claimable <= fee {
+            return Err(format!(
+                "Rewards of {claimable} do not cover the ledger fee of {fee}"
+            ));
+        }
+        state.creator_fund_claimable.remove(&user_id);
+        state.creator_fund_pending_claims.insert(user_id, claimable);
+        Ok(claimable)
+    })?;
+    let amount = reserved - fee;
+
+    let transfer = ledger::transfer(
+        config.ledger,
+        creator_fund::TREASURY_SUBACCOUNT,
+        to.clone(),
+        amount,
+        None,
+    )
+    .await;
+
+    with_state_mut(|state| match transfer {
+        Ok(block_index) => {
+            state.creator_fund_pending_claims.remove(&user_id);
+            let claim = CreatorFundClaim {
+                amount,
+                fee,
+                to,
+                block_index,
+                claimed_at: time(),
+            };
+            record_transaction(
+                state,
+                user_id,
+                TransactionRecord {
+                    block_index: Some(claim.block_index.clone()),
+                    ..TransactionRecord::new(
+                        TransactionKind::CreatorFundClaim,
+                        TransactionAsset::Token {
+                            ledger: config.ledger,
+                        },
+                        amount,
+                        claim.claimed_at,
+                    )
+                },
+            );
+            let claims = state.creator_fund_claims.entry(user_id).or_default();
+            claims.push(claim.clone());
+            if claims.len() > MAX_CREATOR_FUND_HISTORY {
+                claims.remove(0);
+            }
+            Ok(claim)
+        }
+        Err(error) => {
+            state.creator_fund_pending_claims.remove(&user_id);
+            let claimable = state.creator_fund_claimable.entry(user_id).or_default();
+            *claimable = claimable.saturating_add(reserved);
+            Err(error)
+        }
+    })
+}
+
+/// Internal function to run a creator fund distribution once a period has passed
+///
+/// The pool is capped by the treasury balance minus rewards still unclaimed
+/// or being claimed.
+async fn distribute_creator_fund() {
+    let today = time() / NANOS_PER_DAY;
+    let Some(config) = with_state_mut(|state| {
+        let config = state.creator_fund_config.clone()?;
+        let last_day = state.creator_fund_last_day.unwrap_or(today);
+        if state.creator_fund_distributing || today < last_day.saturating_add(config.period_days) {
+            return None;
+        }
+        state.creator_fund_distributing = true;
+        Some(config)
+    }) else {
+        return;
+    };
+
+    let balance = ledger::balance_of(
+        config.ledger,
+        ledger::canister_account(creator_fund::TREASURY_SUBACCOUNT),
+    )
+    .await;
+
+    with_state_mut(|state| {
+        state.creator_fund_distributing = false;
+        let Ok(balance) = balance else {
+            return;
+        };
+
+        let owed = state
+            .creator_fund_claimable
+            .values()
+            .chain(state.creator_fund_pending_claims.values())
+            .fold(0u64, |total, amount| total.saturating_add(*amount));
+        let pool = config.pool_per_period.min(balance.saturating_sub(owed));
+        let last_day = state.creator_fund_last_day.unwrap_or(today);
+        let points = creator_fund_points(state, &config, last_day, today);
+        let shares = creator_fund::allocate(pool, &points, config.max_share_percent);
+
+        let distributed = shares.values().sum::<u64>();
+        for (creator, amount) in shares {
+            let claimable = state.creator_fund_claimable.entry(creator).or_default();
+            *claimable = claimable.saturating_add(amount);
+
+            let rewards = state.creator_fund_rewards.entry(creator).or_default();
+            rewards.push(CreatorFundReward {
+                period_end_day: today,
+                points: points[&creator],
+                amount,
+            });
+            if rewards.len() > MAX_CREATOR_FUND_HISTORY {
+                rewards.remove(0);
+            }
+        }
+
+        state.creator_fund_last_day = Some(today);
+        record_audit_event(
+            state,
+            UserId(ic_cdk::id()),
+            AuditAction::CreatorFundDistributed,
+            None,
+            format!("{distributed} to {} creators", points.len()),
+        );
+    });
+}
+
+/// Internal function to total each eligible creator's engagement points
+/// for the days after `after_day` up to and including `through_day`
+///
+/// # Anti-gaming
+/// * Only deduplicated engagement counts; views earn nothing
+/// * Creators with active strikes or below `min_reputation` are skipped
+/// * Creators under MIN_CREATOR_FUND_POINTS are skipped
+fn creator_fund_points(
+    state: &SocialNetworkState,
+    config: &CreatorFundConfig,
+    after_day: u64,
+    through_day: u64,
+) -> BTreeMap<UserId, u64> {
+    let now = time();
+    state
+        .author_post_totals
+        .iter()
+        .filter(|(author_id, _)| {
+            state
+                .moderation_strikes
+                .get(author_id)
+                .copied()
+                .unwrap_or(0)
+                == 0
+                && reputation_for(state, author_id, now)
+                    .is_some_and(|reputation| reputation.score >= config.min_reputation)
+        })
+        .filter_map(|(author_id, posts)| {
+            let points = posts
+                .keys()
+                .filter_map(|post_id| state.post_engagement_daily.get(post_id))
+                .flat_map(|daily| daily.range(after_day.saturating_add(1)..=through_day))
+                .fold(0u64, |total, (_, counts)| {
+                    total.saturating_add(creator_fund::engagement_points(counts))
+                });
+            (points >= MIN_CREATOR_FUND_POINTS).then_some((*author_id, points))
+        })
+        .collect()
+}
+
+// ============================================================================
+// SNS GOVERNANCE
+// ============================================================================
+
+/// Validates a governance action for an SNS generic-function proposal
+///
+/// # Returns
+/// * `Ok(String)` - Human-readable rendering shown on the proposal
+/// * `Err(String)` - The action would fail if executed
+#[query]
+pub fn validate_governance_action(action: GovernanceAction) -> Result<String, String> {
+    with_state(|state| check_governance_action(state, &action))
+}
+
+/// Executes a governance action adopted by an SNS proposal
+///
+/// # Security
+/// * SNS governance canister only
+/// * Recorded in the audit log
+#[update]
+pub async fn execute_governance_action(action: GovernanceAction) -> Result<(), String> {
+    let governance_id = authenticate_user()?;
+    require_governance(&governance_id)?;
+
+    with_state_mut(|state| {
+        let summary = check_governance_action(state, &action)?;
+        match action {
+            GovernanceAction::SetModerationPolicy(policy) => state.moderation_policy = policy,
+            GovernanceAction::AddAdmin(principal) => {
+                state.admins.insert(UserId(principal));
+            }
+            GovernanceAction::RemoveAdmin(principal) => {
+                state.admins.remove(&UserId(principal));
+            }
+            GovernanceAction::AddModerator(principal) => {
+                state.moderators.insert(UserId(principal));
+            }
+            GovernanceAction::RemoveModerator(principal) => {
+                state.moderators.remove(&UserId(principal));
+            }
+            GovernanceAction::SetPersonhoodRequirement { action, required } => {
+                if required {
+                    state.personhood_required_actions.insert(action);
+                } else {
+                    state.personhood_required_actions.remove(&action);
+                }
+            }
+        }
+        record_audit_event(
+            state,
+            governance_id,
+            AuditAction::GovernanceActionExecuted,
+            None,
+            summary,
+        );
+        Ok(())
+    })
+}
+
+/// Gets the moderation parameters currently in force
+#[query]
+pub fn get_moderation_policy() -> ModerationPolicy {
+    with_state(|state| state.moderation_policy.clone())
+}
+
+/// Gets the SNS governance canister, if the canister has been decentralized
+#[query]
+pub fn get_sns_governance() -> Option<Principal> {
+    with_state(|state| state.sns_governance)
+}
+
+/// Internal function to check a governance action and describe it
+fn check_governance_action(
+    state: &SocialNetworkState,
+    action: &GovernanceAction,
+) -> Result<String, String> {
+    match action {
+        GovernanceAction::SetModerationPolicy(policy) => {
+            if policy.strike_penalty > MAX_REPUTATION_SCORE
+                || policy.min_reputation_for_community_notes > MAX_REPUTATION_SCORE
+            {
+                return Err(format!(
+                    "Policy values must be at most {MAX_REPUTATION_SCORE}"
+                ));
+            }
+            Ok(format!(
+                "Set moderation policy: strike penalty {}, community notes minimum reputation {}",
+                policy.strike_penalty, policy.min_reputation_for_community_notes
+            ))
+        }
+        GovernanceAction::AddAdmin(principal) => Ok(format!("Add admin {principal}")),
+        GovernanceAction::RemoveAdmin(principal) => {
+            if !state.admins.contains(&UserId(*principal)) {
+                return Err("Principal is not an admin".to_string());
+            }
+            Ok(format!("Remove admin {principal}"))
+        }
+        GovernanceAction::AddModerator(principal) => {
+            if !state.users.contains_key(&UserId(*principal)) {
+                return Err("User does not exist".to_string());
+            }
+            Ok(format!("Add moderator {principal}"))
+        }
+        GovernanceAction::RemoveModerator(principal) => {
+            if !state.moderators.contains(&UserId(*principal)) {
+                return Err("User is not a moderator".to_string());
+            }
+            Ok(format!("Remove moderator {principal}"))
+        }
+        GovernanceAction::SetPersonhoodRequirement { action, required } => {
+            if !PERSONHOOD_GATEABLE_ACTIONS.contains(&action.as_str()) {
+                return Err(format!("Unknown action: {action}"));
+            }
+            Ok(format!("Require personhood for {action}: {required}"))
+        }
+    }
+}
+
+// ============================================================================
+// POST NFTS
+// ============================================================================
+
+/// Configures the ICRC-7 collection posts are minted into
+///
+/// # Arguments
+/// * `collection` - ICRC-7 collection canister
+/// * `method` - Mint method taking a `PostMintRequest` (see `NftMintConfig`)
+///
+/// # Security
+/// * Admins only
+/// * Recorded in the audit log
+#[update]
+pub async fn set_nft_mint_config(collection: Principal, method: String) -> Result<(), String> {
+    let admin_id = authenticate_user()?;
+    require_admin(&admin_id)?;
+
+    let method = method.trim().to_string();
+    if method.is_empty() || method.len() > MAX_NFT_MINT_METHOD_LENGTH {
+        return Err(format!(
+            "Method name must be 1-{MAX_NFT_MINT_METHOD_LENGTH} characters"
+        ));
+    }
+
+    with_state_mut(|state| {
+        let details = format!("{collection}.{method}");
+        state.nft_mint_config = Some(NftMintConfig { collection, method });
+        record_audit_event(
+            state,
+            admin_id,
+            AuditAction::NftMintingConfigured,
+            None,
+            details,
+        );
+    });
+    Ok(())
+}
+
+/// Mints one of the caller's posts as an NFT
+///
+/// # Behavior
+/// - The token is minted to the author on the configured collection
+/// - The token id and a hash of the content are recorded on the post and
+///   returned with it in feeds as provenance
+///
+/// # Errors
+/// - "Only the author can mint a post" / "Only public posts can be minted"
+/// - "Post is already minted" - A token exists or a mint is in progress
+///
+/// # Security
+/// * Rate limited (5 mints per hour)
+#[update]
+pub async fn mint_post_nft(post_id: PostId) -> Result<PostNft, String> {
+    let user_id = authenticate_user()?;
+    check_rate_limit(&user_id, "mint_post_nft", 5, 3_600)?;
+    let config =
+        with_state(|state| state.nft_mint_config.clone()).ok_or("NFT minting is not configured")?;
+
+    let request = with_state_mut(|state| {
+        let post = state.posts.get(&post_id).ok_or("Post not found")?;
+        if post.author_id != user_id {
+            return Err("Only the author can mint a post".to_string());
+        }
+        if !matches!(post.visibility, PostVisibility::Public) {
+            return Err("Only public posts can be minted".to_string());
+        }
+        if post.nft.is_some() || state.posts_being_minted.contains(&post_id) {
+            return Err("Post is already minted".to_string());
+        }
+        if is_archived(state, post_id) {
+            return Err("Archived posts cannot be minted".to_string());
+        }
+
+        let request = icrc7::PostMintRequest {
+            to: ledger::Account {
+                owner: user_id.0,
+                subaccount: None,
+            },
+            post_id: post_id.0,
+            content_hash: content_hash(&post_content(state, post)),
+            uri: format!("https://{}.icp0.io/posts/{}", ic_cdk::id(), post_id.0),
+            created_at: post.created_at,
+        };
+        state.posts_being_minted.insert(post_id);
+        Ok(request)
+    })?;
+
+    let content_hash = request.content_hash.clone();
+    let minted = icrc7::mint_post(&config, request).await;
+
+    with_state_mut(|state| {
+        state.posts_being_minted.remove(&post_id);
+        let nft = PostNft {
+            collection: config.collection,
+            token_id: minted?,
+            content_hash,
+            minted_at: time(),
+        };
+        if let Some(post) = state.posts.get_mut(&post_id) {
+            post.nft = Some(nft.clone());
+        }
+        Ok(nft)
+    })
+}
+
+/// Internal function to hash post content for NFT provenance
+fn content_hash(content: &str) -> Vec<u8> {
+    use sha2::{Digest, Sha256};
+    Sha256::digest(content.as_bytes()).to_vec()
+}
+
+// ============================================================================
+// POINTS
+// ============================================================================
+
+/// Gets the caller's points balance, today's usage and history
+#[query]
+pub fn get_my_points() -> Result<PointsAccount, String> {
+    let user_id = authenticate_user()?;
+    let today = time() / NANOS_PER_DAY;
+    Ok(with_state(|state| {
+        let usage = state
+            .points_daily_usage
+            .get(&user_id)
+            .filter(|usage| usage.day == today)
+            .cloned()
+            .unwrap_or_default();
+        PointsAccount {
+            balance: points_balance(state, user_id),
+            earned_today: usage.earned,
+            sent_today: usage.sent,
+            transactions: state
+                .points_history
+                .get(&user_id)
+                .map(|history| history.iter().rev().cloned().collect())
+                .unwrap_or_default(),
+        }
+    }))
+}
+
+/// Tips a post's author in points
+///
+/// # Returns
+/// The caller's remaining balance
+///
+/// # Errors
+/// - "Tip must be 1-{MAX_POINTS_TIP} points"
+/// - "Insufficient points"
+/// - "Daily tipping limit of {POINTS_DAILY_SEND_LIMIT} points reached"
+///
+/// # Security
+/// * Rate limited (30 tips per hour)
+/// * Honors blocks and the author's `tip_audience` setting
+#[update]
+pub async fn tip_post(post_id: PostId, amount: u64) -> Result<u64, String> {
+    let user_id = authenticate_user()?;
+    check_rate_limit(&user_id, "tip_post", 30, 3_600)?;
+    if !(1..=MAX_POINTS_TIP).contains(&amount) {
+        return Err(format!("Tip must be 1-{MAX_POINTS_TIP} points"));
+    }
+
+    with_state_mut(|state| {
+        let post = state
+            .posts
+            .get(&post_id)
+            .filter(|post| can_view_post(state, Some(user_id), post))
+            .ok_or("Post not found")?;
+        let author_id = post.author_id;
+        if author_id == user_id {
+            return Err("You cannot tip your own post".to_string());
+        }
+        check_interaction_audience(state, author_id, user_id, |settings| &settings.tip_audience)?;
+        if points_balance(state, user_id) < amount {
+            return Err("Insufficient points".to_string());
+        }
+
+        let now = time();
+        let usage = state
+            .points_daily_usage
+            .entry(user_id)
+            .or_default()
+            .on(now / NANOS_PER_DAY);
+        if usage.sent.saturating_add(amount) > POINTS_DAILY_SEND_LIMIT {
+            return Err(format!(
+                "Daily tipping limit of {POINTS_DAILY_SEND_LIMIT} points reached"
+            ));
+        }
+        usage.sent += amount;
+
+        let sent = debit_points(
+            state,
+            user_id,
+            amount,
+            PointsTransactionKind::TipSent {
+                post_id,
+                to: author_id,
+            },
+            now,
+        );
+        credit_points(
+            state,
+            author_id,
+            amount,
+            PointsTransactionKind::TipReceived {
+                post_id,
+                from: user_id,
+            },
+            now,
+        );
+        if let Some(post) = state.posts.get_mut(&post_id) {
+            post.tips_received = post.tips_received.saturating_add(amount);
+        }
+        queue_webhook_event(
+            state,
+            author_id,
+            webhook::EventData::Tip {
+                post_id,
+                from: user_id,
+                amount,
+            },
+            now,
+        );
+        record_transfer(
+            state,
+            user_id,
+            author_id,
+            TransactionKind::TipReceived,
+            TransactionRecord {
+                post_id: Some(post_id),
+                ..TransactionRecord::new(
+                    TransactionKind::TipSent,
+                    TransactionAsset::Points,
+                    amount,
+                    now,
+                )
+            },
+        );
+        Ok(sent.balance)
+    })
+}
+
+/// Grants promotional points to a user
+///
+/// # Returns
+/// The user's new balance
+///
+/// # Security
+/// * Admins only
+/// * Recorded in the audit log
+#[update]
+pub async fn grant_points(user_id: Principal, amount: u64, memo: String) -> Result<u64, String> {
+    let admin_id = authenticate_user()?;
+    require_admin(&admin_id)?;
+    let user_id = UserId(user_id);
+
+    if !(1..=MAX_POINTS_GRANT).contains(&amount) {
+        return Err(format!("Grant must be 1-{MAX_POINTS_GRANT} points"));
+    }
+    let memo = memo.trim().to_string();
+    if memo.chars().count() > MAX_POINTS_MEMO_LENGTH {
+        return Err(format!(
+            "Memo must be at most {MAX_POINTS_MEMO_LENGTH} characters"
+        ));
+    }
+
+    with_state_mut(|state| {
+        if !state.users.contains_key(&user_id) {
+            return Err("User does not exist".to_string());
+        }
+        let details = format!("{amount} points: {memo}");
+        let now = time();
+        let granted = credit_points(
+            state,
+            user_id,
+            amount,
+            PointsTransactionKind::Granted { by: admin_id, memo },
+            now,
+        );
+        record_transaction(
+            state,
+            user_id,
+            TransactionRecord::new(
+                TransactionKind::PointsGranted,
+                TransactionAsset::Points,
+                amount,
+                now,
+            ),
+        );
+        record_audit_event(
+            state,
+            admin_id,
+            AuditAction::PointsGranted,
+            Some(user_id),
+            details,
+        );
+        Ok(granted.balance)
+    })
+}
+
+/// Configures the sale of points for an ICRC-1 token
+///
+/// # Arguments
+/// * `config` - Ledger and price, or None to stop sales
+///
+/// # Security
+/// * Admins only
+/// * Recorded in the audit log
+#[update]
+pub async fn set_points_sale_config(config: Option<PointsSaleConfig>) -> Result<(), String> {
+    let admin_id = authenticate_user()?;
+    require_admin(&admin_id)?;
+
+    if config
+        .as_ref()
+        .is_some_and(|config| config.price_per_point == 0)
+    {
+        return Err("Price per point must be positive".to_string());
+    }
+
+    with_state_mut(|state| {
+        let details = match &config {
+            Some(config) => format!("{}: {} per point", config.ledger, config.price_per_point),
+            None => "disabled".to_string(),
+        };
+        state.points_sale_config = config;
+        record_audit_event(
+            state,
+            admin_id,
+            AuditAction::PointsSaleConfigured,
+            None,
+            details,
+        );
+    });
+    Ok(())
+}
+
+/// Gets the point sale settings, if points can be bought
+#[query]
+pub fn get_points_sale_config() -> Option<PointsSaleConfig> {
+    with_state(|state| state.points_sale_config.clone())
+}
+
+/// Gets the account the caller deposits tokens into before `purchase_points`
+#[query]
+pub fn get_points_deposit_account() -> Result<ledger::Account, String> {
+    let user_id = authenticate_user()?;
+    Ok(ledger::canister_account(points::deposit_subaccount(
+        &user_id,
+    )))
+}
+
+/// Converts tokens in the caller's deposit account into points
+///
+/// # Behavior
+/// - Buys as many whole points as the deposit covers after the ledger fee
+/// - Payment moves to the canister's sales subaccount; any remainder stays
+///   in the deposit account for a later purchase
+///
+/// # Security
+/// * Rate limited (10 purchases per hour)
+/// * One purchase per user at a time
+#[update]
+pub async fn purchase_points() -> Result<PointsTransaction, String> {
+    let user_id = authenticate_user()?;
+    check_rate_limit(&user_id, "purchase_points", 10, 3_600)?;
+    let config =
+        with_state(|state| state.points_sale_config.clone()).ok_or("Points are not for sale")?;
+
+    if !with_state_mut(|state| state.points_purchases_in_progress.insert(user_id)) {
+        return Err("A purchase is already in progress".to_string());
+    }
+    let payment = pay_for_points(&config, points::deposit_subaccount(&user_id)).await;
+
+    with_state_mut(|state| {
+        state.points_purchases_in_progress.remove(&user_id);
+        let (amount, block_index) = payment?;
+        let now = time();
+        record_transaction(
+            state,
+            user_id,
+            TransactionRecord {
+                block_index: Some(block_index.clone()),
+                ..TransactionRecord::new(
+                    TransactionKind::PointsPurchased,
+                    TransactionAsset::Token {
+                        ledger: config.ledger,
+                    },
+                    amount * config.price_per_point,
+                    now,
+                )
+            },
+        );
+        Ok(credit_points(
+            state,
+            user_id,
+            amount,
+            PointsTransactionKind::Purchased { block_index },
+            now,
+        ))
+    })
+}
+
+/// Internal function to pay for as many points as a deposit covers
+///
+/// # Returns
+/// The number of points paid for and the ledger block of the payment
+async fn pay_for_points(
+    config: &PointsSaleConfig,
+    deposit_subaccount: [u8; 32],
+) -> Result<(u64, Nat), String> {
+    let deposit =
+        ledger::balance_of(config.ledger, ledger::canister_account(deposit_subaccount)).await?;
+    let fee = ledger::fee(config.ledger).await?;
+
+    let amount = deposit.saturating_sub(fee) / config.price_per_point;
+    if amount == 0 {
+        return Err(format!(
+            "Deposit at least {} plus the ledger fee of {fee} to buy a point",
+            config.price_per_point
+        ));
+    }
+
+    let price = amount * config.price_per_point;
+    begin_tracked_transfer(config.ledger, ReconciledAccount::PointsSales);
+    let result = ledger::transfer(
+        config.ledger,
+        deposit_subaccount,
+        ledger::canister_account(points::SALES_SUBACCOUNT),
+        price,
+        None,
+    )
+    .await;
+    let received = if result.is_ok() { price } else { 0 };
+    end_tracked_transfer(config.ledger, ReconciledAccount::PointsSales, received, 0);
+    Ok((amount, result?))
+}
+
+/// Internal function to read a user's points balance
+fn points_balance(state: &SocialNetworkState, user_id: UserId) -> u64 {
+    state.points_balances.get(&user_id).copied().unwrap_or(0)
+}
+
+/// Internal function to add points to a user's balance and history
+fn credit_points(
+    state: &mut SocialNetworkState,
+    user_id: UserId,
+    amount: u64,
+    kind: PointsTransactionKind,
+    now: u64,
+) -> PointsTransaction {
+    let balance = state.points_balances.entry(user_id).or_default();
+    *balance = balance.saturating_add(amount);
+    let balance = *balance;
+    push_points_transaction(state, user_id, kind, amount, balance, now)
+}
+
+/// Internal function to take points from a user's balance and log it
+///
+/// Callers check the balance first; the debit saturates at zero.
+fn debit_points(
+    state: &mut SocialNetworkState,
+    user_id: UserId,
+    amount: u64,
+    kind: PointsTransactionKind,
+    now: u64,
+) -> PointsTransaction {
+    let balance = state.points_balances.entry(user_id).or_default();
+    *balance = balance.saturating_sub(amount);
+    let balance = *balance;
+    push_points_transaction(state, user_id, kind, amount, balance, now)
+}
+
+/// Internal function to append to a user's bounded points history
+fn push_points_transaction(
+    state: &mut SocialNetworkState,
+    user_id: UserId,
+    kind: PointsTransactionKind,
+    amount: u64,
+    balance: u64,
+    now: u64,
+) -> PointsTransaction {
+    let transaction = PointsTransaction {
+        kind,
+        amount,
+        balance,
+        timestamp: now,
+    };
+    let history = state.points_history.entry(user_id).or_default();
+    history.push(transaction.clone());
+    if history.len() > MAX_POINTS_HISTORY {
+        history.remove(0);
+    }
+    transaction
+}
+
+/// Internal function to credit engagement points to a post author
+///
+/// Earnings are capped at POINTS_DAILY_EARN_LIMIT per day, and repeated
+/// earnings on the same day extend the latest `Earned` history entry.
+fn earn_points(state: &mut SocialNetworkState, user_id: UserId, reward: u64, now: u64) {
+    let today = now / NANOS_PER_DAY;
+    let usage = state
+        .points_daily_usage
+        .entry(user_id)
+        .or_default()
+        .on(today);
+    let amount = reward.min(POINTS_DAILY_EARN_LIMIT.saturating_sub(usage.earned));
+    if amount == 0 {
+        return;
+    }
+    usage.earned += amount;
+
+    let balance = state.points_balances.entry(user_id).or_default();
+    *balance = balance.saturating_add(amount);
+    let balance = *balance;
+
+    match state
+        .points_history
+        .get_mut(&user_id)
+        .and_then(|history| history.last_mut())
+    {
+        Some(last)
+            if last.kind == PointsTransactionKind::Earned
+                && last.timestamp / NANOS_PER_DAY == today =>
+        {
+            last.amount += amount;
+            last.balance = balance;
+            last.timestamp = now;
+        }
+        _ => {
+            push_points_transaction(
+                state,
+                user_id,
+                PointsTransactionKind::Earned,
+                amount,
+                balance,
+                now,
+            );
+        }
+    }
+}
+
+// ============================================================================
+// CREATOR EARNINGS
+// ============================================================================
+
+/// Configures the ledger creator earnings are held in
+///
+/// # Security
+/// * Admins only
+/// * Recorded in the audit log
+#[update]
+pub async fn set_earnings_config(config: EarningsConfig) -> Result<(), String> {
+    let admin_id = authenticate_user()?;
+    require_admin(&admin_id)?;
+
+    with_state_mut(|state| {
+        let details = format!(
+            "{}: minimum withdrawal {}",
+            config.ledger, config.min_withdrawal
+        );
+        state.earnings_config = Some(config);
+        record_audit_event(
+            state,
+            admin_id,
+            AuditAction::EarningsConfigured,
+            None,
+            details,
+        );
+    });
+    Ok(())
+}
+
+/// Gets the creator earnings settings, if withdrawals are enabled
+#[query]
+pub fn get_earnings_config() -> Option<EarningsConfig> {
+    with_state(|state| state.earnings_config.clone())
+}
+
+/// Gets the canister-held account where the caller's earnings accrue
+#[query]
+pub fn get_my_earnings_account() -> Result<ledger::Account, String> {
+    let user_id = authenticate_user()?;
+    Ok(ledger::canister_account(earnings::earnings_subaccount(
+        &user_id,
+    )))
+}
+
+/// Gets the caller's earnings balance and how much can be withdrawn
+#[update]
+pub async fn get_my_earnings_balance() -> Result<EarningsBalance, String> {
+    let user_id = authenticate_user()?;
+    let config = with_state(|state| state.earnings_config.clone())
+        .ok_or("Creator earnings are not configured")?;
+
+    let account = ledger::canister_account(earnings::earnings_subaccount(&user_id));
+    let balance = ledger::balance_of(config.ledger, account).await?;
+    let fee = ledger::fee(config.ledger).await?;
+    Ok(EarningsBalance {
+        balance,
+        fee,
+        withdrawable: earnings::withdrawable(balance, fee),
+    })
+}
+
+/// Withdraws earnings from the caller's earnings account
+///
+/// # Arguments
+/// * `amount` - Amount the destination receives; the ledger fee is charged on top
+/// * `to` - Destination account
+///
+/// # Errors
+/// - "Minimum withdrawal is {min}" - `amount` is below the configured threshold
+/// - "Insufficient earnings: ..." - `amount` plus the fee exceeds the balance
+///
+/// # Security
+/// * Rate limited (5 withdrawals per hour)
+/// * One withdrawal per creator at a time
+#[update]
+pub async fn withdraw_earnings(amount: u64, to: ledger::Account) -> Result<Withdrawal, String> {
+    let user_id = authenticate_user()?;
+    check_rate_limit(&user_id, "withdraw_earnings", 5, 3_600)?;
+    let config = with_state(|state| state.earnings_config.clone())
+        .ok_or("Creator earnings are not configured")?;
+
+    if amount == 0 || amount < config.min_withdrawal {
+        return Err(format!(
+            "Minimum withdrawal is {}",
+            config.min_withdrawal.max(1)
+        ));
+    }
+    if to
+        .subaccount
+        .as_ref()
+        .is_some_and(|subaccount| subaccount.len() != 32)
+    {
+        return Err("Subaccount must be 32 bytes".to_string());
+    }
+
+    if !with_state_mut(|state| state.withdrawals_in_progress.insert(user_id)) {
+        return Err("A withdrawal is already in progress".to_string());
+    }
+    let result = send_earnings(&config, user_id, amount, to).await;
+
+    with_state_mut(|state| {
+        state.withdrawals_in_progress.remove(&user_id);
+        let withdrawal = result?;
+        record_transaction(
+            state,
+            user_id,
+            TransactionRecord {
+                block_index: Some(withdrawal.block_index.clone()),
+                ..TransactionRecord::new(
+                    TransactionKind::Withdrawal,
+                    TransactionAsset::Token {
+                        ledger: config.ledger,
+                    },
+                    withdrawal.amount,
+                    withdrawal.timestamp,
+                )
+            },
+        );
+        let history = state.withdrawal_history.entry(user_id).or_default();
+        history.push(withdrawal.clone());
+        if history.len() > MAX_WITHDRAWAL_HISTORY {
+            history.remove(0);
+        }
+        Ok(withdrawal)
+    })
+}
+
+/// Gets the caller's withdrawal history, newest first
+#[query]
+pub fn get_my_withdrawals() -> Result<Vec<Withdrawal>, String> {
+    let user_id = authenticate_user()?;
+    Ok(with_state(|state| {
+        state
+            .withdrawal_history
+            .get(&user_id)
+            .map(|history| history.iter().rev().cloned().collect())
+            .unwrap_or_default()
+    }))
+}
+
+/// Internal function to check a creator's balance and transfer a withdrawal
+async fn send_earnings(
+    config: &EarningsConfig,
+    user_id: UserId,
+    amount: u64,
+    to: ledger::Account,
+) -> Result<Withdrawal, String> {
+    let subaccount = earnings::earnings_subaccount(&user_id);
+    let balance = ledger::balance_of(config.ledger, ledger::canister_account(subaccount)).await?;
+    let fee = ledger::fee(config.ledger).await?;
+    if amount > earnings::withdrawable(balance, fee) {
+        return Err(format!(
+            "Insufficient earnings: balance {balance}, ledger fee {fee}"
+        ));
+    }
+
+    let account = ReconciledAccount::Earnings { creator: user_id };
+    begin_tracked_transfer(config.ledger, account);
+    let result = ledger::transfer(config.ledger, subaccount, to.clone(), amount, None).await;
+    let sent = if result.is_ok() { amount + fee } else { 0 };
+    end_tracked_transfer(config.ledger, account, 0, sent);
+    let block_index = result?;
+    Ok(Withdrawal {
+        amount,
+        fee,
+        to,
+        block_index,
+        timestamp: time(),
+    })
+}
+
+// ============================================================================
+// SUBSCRIPTIONS
+// ============================================================================
+
+/// Creates a subscription tier for the caller
+///
+/// # Arguments
+/// * `name` - Tier name (1-MAX_TIER_NAME_LENGTH characters)
+/// * `price` - Price per period in earnings ledger base units; 0 for a free tier
+/// * `perks` - What subscribers get (up to MAX_TIER_PERKS_LENGTH characters)
+#[update]
+pub async fn create_subscription_tier(
+    name: String,
+    price: u64,
+    perks: String,
+) -> Result<SubscriptionTier, String> {
+    let user_id = authenticate_user()?;
+    check_rate_limit(&user_id, "create_subscription_tier", 10, 3_600)?;
+    validate_tier_name(&name)?;
+    validate_tier_perks(&perks)?;
+
+    with_state_mut(|state| {
+        if !state.users.contains_key(&user_id) {
+            return Err("User does not exist".to_string());
+        }
+        let tiers = state.subscription_tiers.entry(user_id).or_default();
+        if tiers.len() >= MAX_SUBSCRIPTION_TIERS {
+            return Err(format!(
+                "A creator can have at most {MAX_SUBSCRIPTION_TIERS} tiers"
+            ));
+        }
+
+        let tier = SubscriptionTier {
+            id: tiers.len() as u64 + 1,
+            name: name.trim().to_string(),
+            price,
+            perks: perks.trim().to_string(),
+            archived: false,
+            created_at: time(),
+        };
+        tiers.push(tier.clone());
+        Ok(tier)
+    })
+}
+
+/// Updates one of the caller's subscription tiers
+///
+/// # Behavior
+/// - Subscribers keep the price they agreed to; a lower price applies to
+///   them from their next renewal
+/// - A higher price stops their subscriptions (and pending downgrades to the
+///   tier) from renewing, so nobody is charged more without subscribing again
+#[update]
+pub async fn update_subscription_tier(
+    tier_id: u64,
+    name: String,
+    price: u64,
+    perks: String,
+) -> Result<SubscriptionTier, String> {
+    let user_id = authenticate_user()?;
+    validate_tier_name(&name)?;
+    validate_tier_perks(&perks)?;
+
+    with_state_mut(|state| {
+        let tier = state
+            .subscription_tiers
+            .get_mut(&user_id)
+            .and_then(|tiers| tiers.iter_mut().find(|tier| tier.id == tier_id))
+            .ok_or("Tier not found")?;
+        tier.name = name.trim().to_string();
+        tier.price = price;
+        tier.perks = perks.trim().to_string();
+        let tier = tier.clone();
+
+        for subscription in state
+            .subscriptions
+            .values_mut()
+            .filter(|subscription| subscription.creator == user_id)
+        {
+            // Price of the tier the subscription renews into
+            let agreed = match subscription.next_tier_id {
+                Some(next_tier_id) if next_tier_id == tier_id => subscription.next_price.as_mut(),
+                None if subscription.tier_id == tier_id => Some(&mut subscription.price),
+                _ => None,
+            };
+            let Some(agreed) = agreed else {
+                continue;
+            };
+            if price > *agreed {
+                subscription.auto_renew = false;
+            } else {
+                *agreed = price;
+            }
+        }
+        Ok(tier)
+    })
+}
+
+/// Closes one of the caller's tiers to new subscribers
+///
+/// Current subscribers keep the tier and continue to renew.
+#[update]
+pub async fn archive_subscription_tier(tier_id: u64) -> Result<(), String> {
+    let user_id = authenticate_user()?;
+    with_state_mut(|state| {
+        let tier = state
+            .subscription_tiers
+            .get_mut(&user_id)
+            .and_then(|tiers| tiers.iter_mut().find(|tier| tier.id == tier_id))
+            .ok_or("Tier not found")?;
+        tier.archived = true;
+        Ok(())
+    })
+}
+
+/// Lists a creator's open subscription tiers, cheapest first
+#[query]
+pub fn get_subscription_tiers(creator: Principal) -> Vec<SubscriptionTier> {
+    with_state(|state| {
+        let mut tiers: Vec<SubscriptionTier> = state
+            .subscription_tiers
+            .get(&UserId(creator))
+            .map(|tiers| {
+                tiers
+                    .iter()
+                    .filter(|tier| !tier.archived)
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default();
+        tiers.sort_by_key(|tier| tier.price);
+        tiers
+    })
+}
+
+/// Subscribes the caller to a creator's tier
+///
+/// # Behavior
+/// - The first period is charged immediately and the subscription renews
+///   every SUBSCRIPTION_PERIOD_DAYS until cancelled
+/// - Payments go to the creator's earnings account via an ICRC-2 approval
+///   the caller has given this canister
+/// - Subscribing again to a cancelled but still active tier resumes renewal
+///   without a charge, at the tier's current price
+#[update]
+pub async fn subscribe(creator: Principal, tier_id: u64) -> Result<Subscription, String> {
+    let user_id = authenticate_user()?;
+    check_rate_limit(&user_id, "subscribe", 10, 3_600)?;
+    let creator_id = UserId(creator);
+    let key = (user_id, creator_id);
+    let ledger = earnings_ledger()?;
+
+    let price = with_state_mut(|state| {
+        if creator_id == user_id {
+            return Err("You cannot subscribe to yourself".to_string());
+        }
+        if is_blocked_between(state, creator_id, user_id) {
+            return Err("Interaction not allowed".to_string());
+        }
+        let now = time();
+        if let Some(subscription) = state
+            .subscriptions
+            .get(&key)
+            .filter(|subscription| subscription.is_active(now))
+        {
+            if subscription.tier_id != tier_id || subscription.auto_renew {
+                return Err(
+                    "Already subscribed; use change_subscription_tier to switch tiers".to_string(),
+                );
+            }
+            let price = creator_tier(state, creator_id, tier_id)
+                .ok_or("Tier not found")?
+                .price;
+            if let Some(subscription) = state.subscriptions.get_mut(&key) {
+                subscription.auto_renew = true;
+                subscription.price = price;
+                subscription.next_tier_id = None;
+                subscription.next_price = None;
+            }
+            return Ok(None);
+        }
+
+        let tier = creator_tier(state, creator_id, tier_id)
+            .filter(|tier| !tier.archived)
+            .ok_or("Tier not found")?;
+        let price = tier.price;
+        if !state.subscription_payments_in_progress.insert(key) {
+            return Err("A payment for this subscription is already in progress".to_string());
+        }
+        Ok(Some(price))
+    })?;
+
+    let Some(price) = price else {
+        return with_state(|state| Ok(state.subscriptions[&key].clone()));
+    };
+    let payment = charge_to_earnings(ledger, user_id, creator_id, price).await;
+
+    with_state_mut(|state| {
+        state.subscription_payments_in_progress.remove(&key);
+        let block_index = payment?;
+        let now = time();
+        record_subscription_charge(state, ledger, key, price, block_index, now);
+        let subscription = Subscription {
+            subscriber: user_id,
+            creator: creator_id,
+            tier_id,
+            started_at: now,
+            price,
+            current_period_end: now + subscription_period(),
+            next_tier_id: None,
+            next_price: None,
+            auto_renew: true,
+        };
+        state.subscriptions.insert(key, subscription.clone());
+        Ok(subscription)
+    })
+}
+
+/// Moves the caller's active subscription to another tier
+///
+/// # Proration
+/// - Upgrades (pricier tiers) apply immediately; the price difference is
+///   charged for the rest of the current period
+/// - Downgrades apply at the next renewal, with no refund; access to the
+///   current tier lasts until then
+/// - Choosing the current tier cancels a pending downgrade
+#[update]
+pub async fn change_subscription_tier(
+    creator: Principal,
+    tier_id: u64,
+) -> Result<Subscription, String> {
+    let user_id = authenticate_user()?;
+    check_rate_limit(&user_id, "change_subscription_tier", 10, 3_600)?;
+    let creator_id = UserId(creator);
+    let key = (user_id, creator_id);
+    let ledger = earnings_ledger()?;
+
+    let charge = with_state_mut(|state| {
+        let now = time();
+        let subscription = state
+            .subscriptions
+            .get(&key)
+            .filter(|subscription| subscription.is_active(now))
+            .ok_or("No active subscription to this creator")?;
+        let current_price = subscription.price;
+        let new_price = creator_tier(state, creator_id, tier_id)
+            .filter(|tier| !tier.archived || tier.id == subscription.tier_id)
+            .ok_or("Tier not found")?
+            .price;
+
+        if tier_id == subscription.tier_id || new_price <= current_price {
+            let next_tier_id = (tier_id != subscription.tier_id).then_some(tier_id);
+            if let Some(subscription) = state.subscriptions.get_mut(&key) {
+                subscription.next_tier_id = next_tier_id;
+                subscription.next_price = next_tier_id.map(|_| new_price);
+            }
+            return Ok(None);
+        }
+
+        let remaining = subscription.current_period_end.saturating_sub(now);
+        let charge = ((new_price - current_price) as u128 * remaining as u128
+            / subscription_period() as u128) as u64;
+        if !state.subscription_payments_in_progress.insert(key) {
+            return Err("A payment for this subscription is already in progress".to_string());
+        }
+        Ok(Some((charge, new_price)))
+    })?;
+
+    if let Some((charge, new_price)) = charge {
+        let payment = charge_to_earnings(ledger, user_id, creator_id, charge).await;
+        with_state_mut(|state| {
+            state.subscription_payments_in_progress.remove(&key);
+            let block_index = payment?;
+            record_subscription_charge(state, ledger, key, charge, block_index, time());
+            let subscription = state
+                .subscriptions
+                .get_mut(&key)
+                .ok_or("No active subscription to this creator")?;
+            subscription.tier_id = tier_id;
+            subscription.price = new_price;
+            subscription.next_tier_id = None;
+            subscription.next_price = None;
+            Ok::<(), String>(())
+        })?;
+    }
+
+    with_state(|state| Ok(state.subscriptions[&key].clone()))
+}
+
+/// Stops the caller's subscription from renewing
+///
+/// Access continues until the end of the paid period.
+#[update]
+pub async fn cancel_subscription(creator: Principal) -> Result<Subscription, String> {
+    let user_id = authenticate_user()?;
+    with_state_mut(|state| {
+        let subscription = state
+            .subscriptions
+            .get_mut(&(user_id, UserId(creator)))
+            .ok_or("No subscription to this creator")?;
+        subscription.auto_renew = false;
+        subscription.next_tier_id = None;
+        subscription.next_price = None;
+        Ok(subscription.clone())
+    })
+}
+
+/// Gets the caller's subscriptions, lapsed ones included
+#[query]
+pub fn get_my_subscriptions() -> Result<Vec<Subscription>, String> {
+    let user_id = authenticate_user()?;
+    Ok(with_state(|state| {
+        state
+            .subscriptions
+            .iter()
+            .filter(|((subscriber, _), _)| *subscriber == user_id)
+            .map(|(_, subscription)| subscription.clone())
+            .collect()
+    }))
+}
+
+/// Gets the caller's active subscribers
+#[query]
+pub fn get_my_subscribers() -> Result<Vec<Subscription>, String> {
+    let user_id = authenticate_user()?;
+    let now = time();
+    Ok(with_state(|state| {
+        state
+            .subscriptions
+            .values()
+            .filter(|subscription| subscription.creator == user_id && subscription.is_active(now))
+            .cloned()
+            .collect()
+    }))
+}
+
+/// Restricts one of the caller's posts to subscribers
+///
+/// # Arguments
+/// * `tier_id` - Cheapest of the caller's tiers that unlocks the post;
+///   `None` opens it again
+///
+/// # Behavior
+/// - Tier-gated posts are left out of RSS feeds, ActivityPub and read replicas
+/// - Other viewers see the post without content
+#[update]
+pub async fn set_post_subscriber_tier(post_id: PostId, tier_id: Option<u64>) -> Result<(), String> {
+    let user_id = authenticate_user()?;
+    with_state_mut(|state| {
+        if tier_id.is_some_and(|tier_id| creator_tier(state, user_id, tier_id).is_none()) {
+            return Err("Tier not found".to_string());
+        }
+        let post = state.posts.get_mut(&post_id).ok_or("Post not found")?;
+        if post.author_id != user_id {
+            return Err("Only the author can gate a post".to_string());
+        }
+        let was_syndicated = post.is_syndicated();
+        post.subscriber_tier = tier_id;
+        post.updated_at = time();
+        sync_replica_post(state, post_id, was_syndicated);
+        Ok(())
+    })
+}
+
+/// Internal function to renew subscriptions whose period ends before the next run
+///
+/// Renewals are charged the price the subscriber agreed to. Renewals that
+/// cannot be charged stop auto-renewing, so the subscription lapses at the
+/// end of the paid period.
+async fn renew_subscriptions() {
+    let Ok(ledger) = earnings_ledger() else {
+        return;
+    };
+    let horizon = time() + SUBSCRIPTION_RENEWAL_INTERVAL_SECONDS * 1_000_000_000;
+
+    let due: Vec<((UserId, UserId), u64, u64)> = with_state_mut(|state| {
+        let due: Vec<_> = state
+            .subscriptions
+            .iter()
+            .filter(|(key, subscription)| {
+                subscription.auto_renew
+                    && subscription.current_period_end <= horizon
+                    && !state.subscription_payments_in_progress.contains(key)
+            })
+            .map(|(key, subscription)| {
+                let (tier_id, agreed) = match subscription.next_tier_id {
+                    Some(next_tier_id) => (next_tier_id, subscription.next_price),
+                    None => (subscription.tier_id, Some(subscription.price)),
+                };
+                let price = creator_tier(state, key.1, tier_id).and(agreed);
+                (*key, tier_id, price)
+            })
+            .collect();
+
+        due.into_iter()
+            .filter_map(|(key, tier_id, price)| match price {
+                Some(price) => {
+                    state.subscription_payments_in_progress.insert(key);
+                    Some((key, tier_id, price))
+                }
+                None => {
+                    if let Some(subscription) = state.subscriptions.get_mut(&key) {
+                        subscription.auto_renew = false;
+                    }
+                    None
+                }
+            })
+            .collect()
+    });
+
+    for (key, tier_id, price) in due {
+        let payment = charge_to_earnings(ledger, key.0, key.1, price).await;
+        with_state_mut(|state| {
+            state.subscription_payments_in_progress.remove(&key);
+            let Some(subscription) = state.subscriptions.get_mut(&key) else {
+                return;
+            };
+            let block_index = match payment {
+                Ok(block_index) if subscription.auto_renew => block_index,
+                _ => {
+                    subscription.auto_renew = false;
+                    return;
+                }
+            };
+            let now = time();
+            subscription.current_period_end =
+                subscription.current_period_end.max(now) + subscription_period();
+            subscription.tier_id = tier_id;
+            subscription.price = price;
+            subscription.next_tier_id = None;
+            subscription.next_price = None;
+            record_subscription_charge(state, ledger, key, price, block_index, now);
+        });
+    }
+}
+
+/// Internal function to charge a user and pay a creator's earnings account
+///
+/// # Returns
+/// The ledger block of the payment, or `None` when `amount` is zero
+async fn charge_to_earnings(
+    ledger: Principal,
+    payer: UserId,
+    creator: UserId,
+    amount: u64,
+) -> Result<Option<Nat>, String> {
+    if amount == 0 {
+        return Ok(None);
+    }
+    let account = ReconciledAccount::Earnings { creator };
+    let to = ledger::canister_account(earnings::earnings_subaccount(&creator));
+    begin_tracked_transfer(ledger, account);
+    let result = ledger::transfer_from(ledger, payer.0, to, amount, None).await;
+    let received = if result.is_ok() { amount } else { 0 };
+    end_tracked_transfer(ledger, account, received, 0);
+    result.map(Some)
+}
+
+/// Internal function to get the ledger subscriptions are paid in
+fn earnings_ledger() -> Result<Principal, String> {
+    with_state(|state| state.earnings_config.as_ref().map(|config| config.ledger))
+        .ok_or_else(|| "Creator earnings are not configured".to_string())
+}
+
+/// Internal function to look up one of a creator's tiers
+fn creator_tier(
+    state: &SocialNetworkState,
+    creator: UserId,
+    tier_id: u64,
+) -> Option<&SubscriptionTier> {
+    state
+        .subscription_tiers
+        .get(&creator)?
+        .iter()
+        .find(|tier| tier.id == tier_id)
+}
+
+/// Internal function to check a viewer's subscription against a post's tier
+///
+/// Any active tier priced at or above the required tier qualifies.
+fn has_subscriber_access(
+    state: &SocialNetworkState,
+    viewer_id: UserId,
+    creator: UserId,
+    required_tier_id: u64,
+    now: u64,
+) -> bool {
+    let Some(subscription) = state
+        .subscriptions
+        .get(&(viewer_id, creator))
+        .filter(|subscription| subscription.is_active(now))
+    else {
+        return false;
+    };
+    match (
+        creator_tier(state, creator, subscription.tier_id),
+        creator_tier(state, creator, required_tier_id),
+    ) {
+        (Some(held), Some(required)) => held.price >= required.price,
+        _ => false,
+    }
+}
+
+/// Internal function to check paid access to a post
+///
+/// Buyers of a paid post and subscribers qualifying for its tier both count.
+fn has_paid_access(state: &SocialNetworkState, viewer_id: UserId, post: &Post, now: u64) -> bool {
+    (post.unlock_price.is_some() && state.post_unlocks.contains(&(viewer_id, post.id)))
+        || post.subscriber_tier.is_some_and(|tier_id| {
+            has_subscriber_access(state, viewer_id, post.author_id, tier_id, now)
+        })
+}
+
+/// Length of a subscription period in nanoseconds
+fn subscription_period() -> u64 {
+    SUBSCRIPTION_PERIOD_DAYS * NANOS_PER_DAY
+}
+
+// ============================================================================
+// PROMOTED POSTS
+// ============================================================================
+
+/// Configures pricing and eligibility for promoted posts
+///
+/// # Arguments
+/// * `config` - Prices and rules, or None to stop new promotions
+///
+/// # Security
+/// * Admins only
+/// * Recorded in the audit log
+#[update]
+pub async fn set_promotion_config(config: Option<PromotionConfig>) -> Result<(), String> {
+    let admin_id = authenticate_user()?;
+    require_admin(&admin_id)?;
+
+    if let Some(config) = &config {
+        if config.ledger.is_some() && config.token_price_per_day == 0 {
+            return Err("Token price per day must be positive".to_string());
+        }
+        if config.ledger.is_none() && config.points_per_day == 0 {
+            return Err("Accept points, tokens or both".to_string());
+        }
+    }
+
+    with_state_mut(|state| {
+        let details = match &config {
+            Some(config) => format!(
+                "{} points/day, {} tokens/day on {:?}, minimum reputation {}",
+                config.points_per_day,
+                config.token_price_per_day,
+                config.ledger,
+                config.min_reputation
+            ),
+            None => "disabled".to_string(),
+        };
+        state.promotion_config = config;
+        record_audit_event(
+            state,
+            admin_id,
+            AuditAction::PromotionConfigured,
+            None,
+            details,
+        );
+    });
+    Ok(())
+}
+
+/// Gets the promoted post settings, if promotions are available
+#[query]
+pub fn get_promotion_config() -> Option<PromotionConfig> {
+    with_state(|state| state.promotion_config.clone())
+}
+
+/// Promotes one of the caller's posts in the explore feed
+///
+/// # Arguments
+/// * `payment` - Pay in points or in tokens on the promotion ledger
+/// * `budget` - Amount to spend; the promotion lasts `budget` divided by the
+///   day price, between MIN_PROMOTION_SECONDS and MAX_PROMOTION_DAYS
+///
+/// # Behavior
+/// - Token payments are charged via an ICRC-2 approval and fund the creator
+///   fund treasury
+/// - The post is labeled as promoted wherever the promotion places it
+///
+/// # Eligibility
+/// - Public posts without gates, prices, link warnings or blocklist flags
+/// - Authors without active strikes and with at least `min_reputation`
+/// - Posts an admin has not barred from promotion
+///
+/// # Security
+/// * Rate limited (10 promotions per hour)
+#[update]
+pub async fn promote_post(
+    post_id: PostId,
+    payment: PromotionPayment,
+    budget: u64,
+) -> Result<Promotion, String> {
+    let user_id = authenticate_user()?;
+    check_rate_limit(&user_id, "promote_post", 10, 3_600)?;
+    let config =
+        with_state(|state| state.promotion_config.clone()).ok_or("Promotions are not available")?;
+
+    let price_per_day = match payment {
+        PromotionPayment::Points => config.points_per_day,
+        PromotionPayment::Token if config.ledger.is_some() => config.token_price_per_day,
+        PromotionPayment::Token => 0,
+    };
+    if price_per_day == 0 {
+        return Err("This payment method is not accepted for promotions".to_string());
+    }
+    let duration = (budget as u128 * NANOS_PER_DAY as u128 / price_per_day as u128)
+        .min(u64::MAX as u128) as u64;
+    if !(MIN_PROMOTION_SECONDS * 1_000_000_000..=MAX_PROMOTION_DAYS * NANOS_PER_DAY)
+        .contains(&duration)
+    {
+        return Err(format!(
+            "Budget must buy between {} minutes and {MAX_PROMOTION_DAYS} days",
+            MIN_PROMOTION_SECONDS / 60
+        ));
+    }
+
+    let paid_in_points = with_state_mut(|state| {
+        let now = time();
+        let post = state.posts.get(&post_id).ok_or("Post not found")?;
+        if post.author_id != user_id {
+            return Err("Only the author can promote a post".to_string());
+        }
+        check_promotion_eligibility(state, &config, post, now)?;
+        if state.promotions_in_progress.contains(&post_id)
+            || state
+                .promotions
+                .get(&post_id)
+                .is_some_and(|promotion| promotion.is_active(now))
+        {
+            return Err("Post is already promoted".to_string());
+        }
+
+        if payment == PromotionPayment::Token {
+            state.promotions_in_progress.insert(post_id);
+            return Ok(None);
+        }
+        if points_balance(state, user_id) < budget {
+            return Err("Insufficient points".to_string());
+        }
+        debit_points(
+            state,
+            user_id,
+            budget,
+            PointsTransactionKind::Promotion { post_id },
+            now,
+        );
+        record_transaction(
+            state,
+            user_id,
+            TransactionRecord {
+                post_id: Some(post_id),
+                ..TransactionRecord::new(
+                    TransactionKind::PromotionPaid,
+                    TransactionAsset::Points,
+                    budget,
+                    now,
+                )
+            },
+        );
+        Ok(Some(start_promotion(
+            state, post_id, user_id, payment, budget, duration, now,
+        )))
+    })?;
+    if let Some(promotion) = paid_in_points {
+        return Ok(promotion);
+    }
+
+    let charge = match config.ledger {
+        Some(ledger) => {
+            let treasury = ledger::canister_account(creator_fund::TREASURY_SUBACCOUNT);
+            ledger::transfer_from(ledger, user_id.0, treasury, budget, None)
+                .await
+                .map(|block_index| (ledger, block_index))
+        }
+        None => Err("This payment method is not accepted for promotions".to_string()),
+    };
+    with_state_mut(|state| {
+        state.promotions_in_progress.remove(&post_id);
+        let (ledger, block_index) = charge?;
+        record_transaction(
+            state,
+            user_id,
+            TransactionRecord {
+                post_id: Some(post_id),
+                block_index: Some(block_index),
+                ..TransactionRecord::new(
+                    TransactionKind::PromotionPaid,
+                    TransactionAsset::Token { ledger },
+                    budget,
+                    time(),
+                )
+            },
+        );
+        Ok(start_promotion(
+            state,
+            post_id,
+            user_id,
+            payment,
+            budget,
+            duration,
+            time(),
+        ))
+    })
+}
+
+/// Ends a post's promotion early and bars the post from future promotion
+///
+/// # Security
+/// * Admins only
+/// * Recorded in the audit log
+/// * The sponsor is not refunded
+#[update]
+pub async fn end_promotion(post_id: PostId, reason: String) -> Result<(), String> {
+    let admin_id = authenticate_user()?;
+    require_admin(&admin_id)?;
+    validate_review_note(&reason)?;
+
+    with_state_mut(|state| {
+        let author_id = state
+            .posts
+            .get(&post_id)
+            .map(|post| post.author_id)
+            .ok_or("Post not found")?;
+        let now = time();
+        if let Some(promotion) = state
+            .promotions
+            .get_mut(&post_id)
+            .filter(|promotion| promotion.is_active(now))
+        {
+            promotion.ends_at = now;
+            promotion.ended_by = Some(admin_id);
+        }
+        state.unpromotable_posts.insert(post_id);
+        record_audit_event(
+            state,
+            admin_id,
+            AuditAction::PromotionEnded,
+            Some(author_id),
+            format!("post {}: {}", post_id.0, reason.trim()),
+        );
+        Ok(())
+    })
+}
+
+/// Gets the caller's promotions, newest first
+#[query]
+pub fn get_my_promotions() -> Result<Vec<Promotion>, String> {
+    let user_id = authenticate_user()?;
+    Ok(with_state(|state| {
+        let mut promotions: Vec<Promotion> = state
+            .promotions
+            .values()
+            .filter(|promotion| promotion.sponsor == user_id)
+            .cloned()
+            .collect();
+        promotions.sort_by_key(|promotion| std::cmp::Reverse(promotion.started_at));
+        promotions
+    }))
+}
+
+/// Trending public posts with active promotions mixed in
+///
+/// # Feed Algorithm
+/// 1. Public posts ranked by engagement over the last TRENDING_WINDOW_DAYS
+/// 2. Every PROMOTED_SLOT_INTERVAL-th slot, starting with the first, holds an
+///    active promotion (oldest first), marked `promoted`
+/// 3. Respects blocks, language preferences and post visibility; authors who
+///    turned off `searchable` are left out
+///
+/// Anonymous viewers are served from the hot cache when it covers the page.
+#[query]
+pub fn get_explore_feed(limit: Option<usize>, offset: Option<usize>) -> Result<FeedPage, String> {
+    check_query_quota("get_explore_feed")?;
+    let limit = limit.unwrap_or(DEFAULT_FEED_LIMIT).min(MAX_FEED_LIMIT);
+    let offset = offset.unwrap_or(0);
+    let caller_id = current_viewer();
+
+    Ok(with_state(|state| {
+        let cached = caller_id.is_none().then(|| {
+            state.hot_cache.explore_page(offset, limit, |post_id| {
+                state.posts.get(&post_id).is_some_and(|post| {
+                    post.is_syndicated() && is_discoverable(state, post.author_id)
+                })
+            })
+        });
+        match cached {
+            Some(Some(page)) => page,
+            _ => explore_feed(state, caller_id, time(), offset, limit),
+        }
+    }))
+}
+
+/// Internal function to compute a page of the explore feed for a viewer
+fn explore_feed(
+    state: &SocialNetworkState,
+    caller_id: Option<UserId>,
+    now: u64,
+    offset: usize,
+    limit: usize,
+) -> FeedPage {
+    let listed = explore_listing(state, caller_id, now);
+    let total = listed.len() as u64;
+    let (page, next_cursor) = page_at(listed.into_iter(), offset, limit);
+    let posts = page
+        .into_iter()
+        .filter_map(|(post, promoted)| {
+            let author = state.users.get(&post.author_id)?;
+            let is_liked = caller_id.is_some_and(|user_id| {
+                state
+                    .post_likes
+                    .get(&post.id)
+                    .is_some_and(|likes| likes.contains(&user_id))
+            });
+            let post = post_for_viewer(state, caller_id, post);
+            Some(FeedPost {
+                kind: feed_item_kind(&post),
+                post,
+                author: author.clone(),
+                is_liked,
+                promoted,
+            })
+        })
+        .collect();
+
+    FeedPage {
+        posts,
+        total: Some(total),
+        has_more: next_cursor.is_some(),
+        next_cursor,
+    }
+}
+
+/// Internal function to rank the posts listed on the explore feed, each
+/// marked whether it fills a promoted slot
+fn explore_listing(
+    state: &SocialNetworkState,
+    caller_id: Option<UserId>,
+    now: u64,
+) -> Vec<(&Post, bool)> {
+    let is_listed = |post: &Post| {
+        post.is_syndicated()
+            && is_discoverable(state, post.author_id)
+            && can_view_post(state, caller_id, post)
+            && matches_language_preference(state, caller_id, post)
+            && !caller_id.is_some_and(|viewer| is_blocked_between(state, viewer, post.author_id))
+    };
+
+    let mut promotions: Vec<&Promotion> = state
+        .promotions
+        .values()
+        .filter(|promotion| promotion.is_active(now))
+        .collect();
+    promotions.sort_by_key(|promotion| promotion.started_at);
+    let promoted: Vec<&Post> = promotions
+        .into_iter()
+        .filter_map(|promotion| state.posts.get(&promotion.post_id))
+        .filter(|post| is_listed(post) && is_promotable_post(state, post))
+        .collect();
+
+    let first_day = (now / NANOS_PER_DAY).saturating_sub(TRENDING_WINDOW_DAYS - 1);
+    let mut trending: Vec<(u64, &Post)> = state
+        .posts
+        .values()
+        .filter(|post| !promoted.iter().any(|promoted| promoted.id == post.id))
+        .filter(|post| is_listed(post))
+        .filter_map(|post| {
+            let score = state
+                .post_engagement_daily
+                .get(&post.id)?
+                .range(first_day..)
+                .fold(0u64, |total, (_, counts)| {
+                    total.saturating_add(creator_fund::engagement_points(counts))
+                });
+            (score > 0).then_some((score, post))
+        })
+        .collect();
+    trending.sort_by_key(|(score, post)| std::cmp::Reverse((*score, post.created_at)));
+
+    let organic = trending.into_iter().map(|(_, post)| post).collect();
+    feed_filter::interleave_promoted(organic, promoted, PROMOTED_SLOT_INTERVAL)
+}
+
+/// Internal function to recompute the responses cached for anonymous viewers
+fn refresh_hot_cache(state: &mut SocialNetworkState, now: u64) {
+    let explore_limit = HOT_FEED_PAGES.saturating_mul(DEFAULT_FEED_LIMIT);
+    let explore = explore_feed(state, None, now, 0, explore_limit);
+
+    let mut popular: Vec<&UserProfile> = state
+        .users
+        .values()
+        .filter(|profile| profile.privacy_settings.is_discoverable())
+        .collect();
+    popular.sort_by_key(|profile| std::cmp::Reverse(profile.follower_count));
+    let profiles = popular
+        .into_iter()
+        .take(HOT_PROFILE_COUNT)
+        .map(|profile| profile_for_viewer(state, profile, None))
+        .collect();
+
+    state.hot_cache = hot_cache::HotCache::new(explore, profiles);
+}
+
+/// Internal function to record a paid promotion
+fn start_promotion(
+    state: &mut SocialNetworkState,
+    post_id: PostId,
+    sponsor: UserId,
+    payment: PromotionPayment,
+    budget: u64,
+    duration: u64,
+    now: u64,
+) -> Promotion {
+    let promotion = Promotion {
+        post_id,
+        sponsor,
+        payment,
+        budget,
+        started_at: now,
+        ends_at: now.saturating_add(duration),
+        ended_by: None,
+    };
+    state.promotions.insert(post_id, promotion.clone());
+    promotion
+}
+
+/// Internal function to check that a post and its author may be promoted
+fn check_promotion_eligibility(
+    state: &SocialNetworkState,
+    config: &PromotionConfig,
+    post: &Post,
+    now: u64,
+) -> Result<(), String> {
+    if !post.is_syndicated() {
+        return Err("Only public posts without gates or prices can be promoted".to_string());
+    }
+    if !is_promotable_post(state, post) {
+        return Err("This post is not eligible for promotion".to_string());
+    }
+
+    let has_strikes = state
+        .moderation_strikes
+        .get(&post.author_id)
+        .is_some_and(|strikes| *strikes > 0);
+    let is_reputable = reputation_for(state, &post.author_id, now)
+        .is_some_and(|reputation| reputation.score >= config.min_reputation);
+    if has_strikes || !is_reputable {
+        return Err("Your account is not eligible to promote posts".to_string());
+    }
+    Ok(())
+}
+
+/// Internal function to check a post's content against the promotion rules
+///
+/// Also applied when serving promotions, so posts flagged after purchase stop
+/// being shown.
+fn is_promotable_post(state: &SocialNetworkState, post: &Post) -> bool {
+    post.is_syndicated()
+        && post.link_warnings.is_empty()
+        && !state.flagged_posts.contains_key(&post.id)
+        && !state.unpromotable_posts.contains(&post.id)
+}
+
+// ============================================================================
+// FUNDRAISERS
+// ============================================================================
+
+/// Turns one of the caller's public posts into a fundraiser
+///
+/// # Arguments
+/// * `goal` - Amount to raise in earnings ledger base units
+/// * `deadline` - When contributions close (at most MAX_FUNDRAISER_DAYS away)
+///
+/// # Behavior
+/// - Contributions are held in an escrow subaccount of the canister
+/// - After the deadline the escrow is released to the author if the goal
+///   was met, otherwise every contributor is refunded; ledger fees are
+///   deducted from each transfer
+#[update]
+pub async fn start_fundraiser(
+    post_id: PostId,
+    goal: u64,
+    deadline: u64,
+) -> Result<Fundraiser, String> {
+    let user_id = authenticate_user()?;
+    let ledger = earnings_ledger()?;
+    let now = time();
+
+    if goal == 0 {
+        return Err("Goal must be positive".to_string());
+    }
+    if deadline <= now || deadline > now + MAX_FUNDRAISER_DAYS * NANOS_PER_DAY {
+        return Err(format!(
+            "Deadline must be within the next {MAX_FUNDRAISER_DAYS} days"
+        ));
+    }
+
+    with_state_mut(|state| {
+        let post = state.posts.get_mut(&post_id).ok_or("Post not found")?;
+        if post.author_id != user_id {
+            return Err("Only the author can start a fundraiser".to_string());
+        }
+        if !matches!(post.visibility, PostVisibility::Public) {
+            return Err("Only public posts can raise funds".to_string());
+        }
+        if post.fundraiser.is_some() {
+            return Err("Post already has a fundraiser".to_string());
+        }
+
+        let fundraiser = Fundraiser {
+            ledger,
+            goal,
+            deadline,
+            raised: 0,
+            contributor_count: 0,
+            status: FundraiserStatus::Open,
+        };
+        post.fundraiser = Some(fundraiser.clone());
+        post.updated_at = now;
+        state.unsettled_fundraisers.insert(post_id);
+        Ok(fundraiser)
+    })
+}
+
+/// Contributes to a fundraiser, holding the amount in escrow
+///
+/// # Arguments
+/// * `amount` - Charged via an ICRC-2 approval; must exceed the ledger fee
+///   so it can be refunded
+///
+/// # Security
+/// * Rate limited (20 contributions per hour)
+/// * Authors cannot contribute to their own fundraisers
+#[update]
+pub async fn contribute_to_fundraiser(post_id: PostId, amount: u64) -> Result<Fundraiser, String> {
+    let user_id = authenticate_user()?;
+    check_rate_limit(&user_id, "contribute_to_fundraiser", 20, 3_600)?;
+    let key = (post_id, user_id);
+
+    let ledger = with_state_mut(|state| {
+        let post = state
+            .posts
+            .get(&post_id)
+            .filter(|post| can_view_post(state, Some(user_id), post))
+            .ok_or("Post not found")?;
+        let fundraiser = post.fundraiser.as_ref().ok_or("Post has no fundraiser")?;
+        if fundraiser.status != FundraiserStatus::Open || time() >= fundraiser.deadline {
+            return Err("Fundraiser is closed".to_string());
+        }
+        if post.author_id == user_id {
+            return Err("You cannot contribute to your own fundraiser".to_string());
+        }
+        if is_blocked_between(state, post.author_id, user_id) {
+            return Err("Interaction not allowed".to_string());
+        }
+        let ledger = fundraiser.ledger;
+        if !state.contributions_in_progress.insert(key) {
+            return Err("A contribution is already in progress".to_string());
+        }
+        Ok(ledger)
+    })?;
+
+    let payment = escrow_contribution(ledger, post_id, user_id, amount).await;
+
+    with_state_mut(|state| {
+        state.contributions_in_progress.remove(&key);
+        let block_index = payment?;
+
+        let now = time();
+        let author_id = state.posts.get(&post_id).map(|post| post.author_id);
+        record_transaction(
+            state,
+            user_id,
+            TransactionRecord {
+                counterparty: author_id,
+                post_id: Some(post_id),
+                block_index: Some(block_index),
+                ..TransactionRecord::new(
+                    TransactionKind::FundraiserContribution,
+                    TransactionAsset::Token { ledger },
+                    amount,
+                    now,
+                )
+            },
+        );
+        let is_new = !state.fundraiser_contributions.contains_key(&key);
+        let contribution =
+            state
+                .fundraiser_contributions
+                .entry(key)
+                .or_insert(FundraiserContribution {
+                    post_id,
+                    amount: 0,
+                    contributed_at: now,
+                    refund_block_index: None,
+                });
+        contribution.amount = contribution.amount.saturating_add(amount);
+        contribution.contributed_at = now;
+
+        let fundraiser = state
+            .posts
+            .get_mut(&post_id)
+            .and_then(|post| post.fundraiser.as_mut())
+            .ok_or("Post has no fundraiser")?;
+        fundraiser.raised = fundraiser.raised.saturating_add(amount);
+        if is_new {
+            fundraiser.contributor_count += 1;
+        }
+        Ok(fundraiser.clone())
+    })
+}
+
+/// Gets the caller's fundraiser contributions
+#[query]
+pub fn get_my_fundraiser_contributions() -> Result<Vec<FundraiserContribution>, String> {
+    let user_id = authenticate_user()?;
+    Ok(with_state(|state| {
+        state
+            .fundraiser_contributions
+            .iter()
+            .filter(|((_, contributor), _)| *contributor == user_id)
+            .map(|(_, contribution)| contribution.clone())
+            .collect()
+    }))
+}
+
+/// Internal function to charge a contribution into a fundraiser's escrow
+///
+/// # Returns
+/// The ledger block of the payment
+async fn escrow_contribution(
+    ledger: Principal,
+    post_id: PostId,
+    contributor: UserId,
+    amount: u64,
+) -> Result<Nat, String> {
+    let fee = ledger::fee(ledger).await?;
+    if amount <= fee {
+        return Err(format!("Contribution must exceed the ledger fee of {fee}"));
+    }
+    let account = ReconciledAccount::FundraiserEscrow { post_id };
+    let escrow = ledger::canister_account(fundraiser::escrow_subaccount(post_id));
+    begin_tracked_transfer(ledger, account);
+    let result = ledger::transfer_from(ledger, contributor.0, escrow, amount, None).await;
+    let received = if result.is_ok() { amount } else { 0 };
+    end_tracked_transfer(ledger, account, received, 0);
+    result
+}
+
+/// Internal function to close fundraisers past their deadline and move funds
+///
+/// Payouts and refunds that fail are retried on the next run with the same
+/// memo and `created_at_time`, so the ledger never executes one twice. Once
+/// a retry falls outside the ledger's deduplication window, it only goes
+/// ahead if the escrow still holds everything owed from it.
+async fn settle_fundraisers() {
+    let now = time();
+    let due: Vec<(PostId, UserId, Fundraiser)> = with_state_mut(|state| {
+        let mut due = Vec::new();
+        for post_id in state.unsettled_fundraisers.clone() {
+            if state.fundraisers_settling.contains(&post_id)
+                || state
+                    .contributions_in_progress
+                    .iter()
+                    .any(|(id, _)| *id == post_id)
+            {
+                continue;
+            }
+            let Some(post) = state.posts.get_mut(&post_id) else {
+                continue;
+            };
+            let author_id = post.author_id;
+            let Some(fundraiser) = post.fundraiser.as_mut() else {
+                continue;
+            };
+            if fundraiser.status == FundraiserStatus::Open {
+                if now < fundraiser.deadline {
+                    continue;
+                }
+                fundraiser.status = fundraiser::outcome(fundraiser.raised, fundraiser.goal);
+            }
+            due.push((post_id, author_id, fundraiser.clone()));
+        }
+        for (post_id, _, _) in &due {
+            state.fundraisers_settling.insert(*post_id);
+        }
+        due
+    });
+
+    for (post_id, author_id, fundraiser) in due {
+        if !refresh_escrow_transfer_times(post_id, &fundraiser, now).await {
+            with_state_mut(|state| state.fundraisers_settling.remove(&post_id));
+            continue;
+        }
+        let settled = match ledger::fee(fundraiser.ledger).await {
+            Ok(fee) if fundraiser.status == FundraiserStatus::Releasing => {
+                release_fundraiser(post_id, author_id, &fundraiser, fee).await
+            }
+            Ok(fee) => refund_fundraiser(post_id, &fundraiser, fee).await,
+            Err(_) => None,
+        };
+        with_state_mut(|state| {
+            state.fundraisers_settling.remove(&post_id);
+            let Some(status) = settled else {
+                return;
+            };
+            if let Some(fundraiser) = state
+                .posts
+                .get_mut(&post_id)
+                .and_then(|post| post.fundraiser.as_mut())
+            {
+                fundraiser.status = status;
+            }
+            state.unsettled_fundraisers.remove(&post_id);
+        });
+    }
+}
+
+/// Internal function to restart escrow transfers whose earlier attempts the
+/// ledger no longer deduplicates
+///
+/// The escrow only shrinks when a transfer executes, so if it still covers
+/// everything owed from it, no earlier attempt went through and new
+/// attempts are safe. Otherwise the fundraiser is held for admin review.
+///
+/// # Returns
+/// Whether settlement may go ahead
+async fn refresh_escrow_transfer_times(post_id: PostId, fundraiser: &Fundraiser, now: u64) -> bool {
+    let window_start = now.saturating_sub(LEDGER_DEDUP_WINDOW_SECONDS * 1_000_000_000);
+    let (expired, owed) = with_state(|state| {
+        let expired = state
+            .escrow_transfer_times
+            .range((post_id, None)..)
+            .take_while(|((id, _), _)| *id == post_id)
+            .any(|(_, &created_at)| created_at < window_start);
+        let owed = match fundraiser.status {
+            FundraiserStatus::Releasing => fundraiser.raised,
+            _ => state
+                .fundraiser_contributions
+                .iter()
+                .filter(|((id, _), contribution)| {
+                    *id == post_id && contribution.refund_block_index.is_none()
+                })
+                .fold(0u64, |total, (_, contribution)| {
+                    total.saturating_add(contribution.amount)
+                }),
+        };
+        (expired, owed)
+    });
+    if !expired {
+        return true;
+    }
+
+    let escrow = ledger::canister_account(fundraiser::escrow_subaccount(post_id));
+    let Ok(balance) = ledger::balance_of(fundraiser.ledger, escrow).await else {
+        return false;
+    };
+    with_state_mut(|state| {
+        if balance < owed {
+            log_event(
+                state,
+                LogLevel::Error,
+                LogCategory::System,
+                None,
+                format!(
+                    "Fundraiser escrow of post {} holds {balance} of {owed} owed; \
+                     an earlier transfer may have gone through",
+                    post_id.0
+                ),
+            );
+            return false;
+        }
+        state
+            .escrow_transfer_times
+            .retain(|(id, _), _| *id != post_id);
+        true
+    })
+}
+
+/// Internal function to look up the `created_at_time` of an escrow transfer,
+/// starting it at `now` on the first attempt
+fn escrow_transfer_time(post_id: PostId, refunded: Option<UserId>, now: u64) -> u64 {
+    with_state_mut(|state| {
+        *state
+            .escrow_transfer_times
+            .entry((post_id, refunded))
+            .or_insert(now)
+    })
+}
+
+/// Internal function to pay a successful fundraiser's escrow to its author
+///
+/// # Returns
+/// The final status, or `None` if the transfer should be retried
+async fn release_fundraiser(
+    post_id: PostId,
+    author_id: UserId,
+    fundraiser: &Fundraiser,
+    fee: u64,
+) -> Option<FundraiserStatus> {
+    let author = ledger::Account {
+        owner: author_id.0,
+        subaccount: None,
+    };
+    let amount = fundraiser.raised.saturating_sub(fee);
+    let account = ReconciledAccount::FundraiserEscrow { post_id };
+    let created_at_time = escrow_transfer_time(post_id, None, time());
+    begin_tracked_transfer(fundraiser.ledger, account);
+    let result = ledger::transfer_once(
+        fundraiser.ledger,
+        fundraiser::escrow_subaccount(post_id),
+        author,
+        amount,
+        fundraiser::transfer_memo(post_id, None),
+        created_at_time,
+    )
+    .await;
+    let sent = if result.is_ok() { amount + fee } else { 0 };
+    end_tracked_transfer(fundraiser.ledger, account, 0, sent);
+    let block_index = result.ok()?;
+
+    with_state_mut(|state| {
+        state.escrow_transfer_times.remove(&(post_id, None));
+        record_transaction(
+            state,
+            author_id,
+            TransactionRecord {
+                post_id: Some(post_id),
+                block_index: Some(block_index.clone()),
+                ..TransactionRecord::new(
+                    TransactionKind::FundraiserPayout,
+                    TransactionAsset::Token {
+                        ledger: fundraiser.ledger,
+                    },
+                    amount,
+                    time(),
+                )
+            },
+        );
+    });
+    Some(FundraiserStatus::Released { block_index })
+}
+
+/// Internal function to return a failed fundraiser's contributions
+///
+/// # Returns
+/// `Refunded` once every contribution has been returned, otherwise `None`
+async fn refund_fundraiser(
+    post_id: PostId,
+    fundraiser: &Fundraiser,
+    fee: u64,
+) -> Option<FundraiserStatus> {
+    let pending: Vec<(UserId, u64)> = with_state(|state| {
+        state
+            .fundraiser_contributions
+            .iter()
+            .filter(|((id, _), contribution)| {
+                *id == post_id && contribution.refund_block_index.is_none()
+            })
+            .map(|((_, contributor), contribution)| (*contributor, contribution.amount))
+            .collect()
+    });
+
+    let mut complete = true;
+    for (contributor, amount) in pending {
+        let refund_amount = amount.saturating_sub(fee);
+        let account = ReconciledAccount::FundraiserEscrow { post_id };
+        let created_at_time = escrow_transfer_time(post_id, Some(contributor), time());
+        begin_tracked_transfer(fundraiser.ledger, account);
+        let refund = ledger::transfer_once(
+            fundraiser.ledger,
+            fundraiser::escrow_subaccount(post_id),
+            ledger::Account {
+                owner: contributor.0,
+                subaccount: None,
+            },
+            refund_amount,
+            fundraiser::transfer_memo(post_id, Some(contributor)),
+            created_at_time,
+        )
+        .await;
+        let sent = if refund.is_ok() {
+            refund_amount + fee
+        } else {
+            0
+        };
+        end_tracked_transfer(fundraiser.ledger, account, 0, sent);
+        match refund {
+            Ok(block_index) => with_state_mut(|state| {
+                state
+                    .escrow_transfer_times
+                    .remove(&(post_id, Some(contributor)));
+                if let Some(contribution) = state
+                    .fundraiser_contributions
+                    .get_mut(&(post_id, contributor))
+                {
+                    contribution.refund_block_index = Some(block_index.clone());
+                }
+                record_transaction(
+                    state,
+                    contributor,
+                    TransactionRecord {
+                        post_id: Some(post_id),
+                        block_index: Some(block_index),
+                        ..TransactionRecord::new(
+                            TransactionKind::FundraiserRefund,
+                            TransactionAsset::Token {
+                                ledger: fundraiser.ledger,
+                            },
+                            refund_amount,
+                            time(),
+                        )
+                    },
+                );
+            }),
+            Err(_) => complete = false,
+        }
+    }
+    complete.then_some(FundraiserStatus::Refunded)
+}
+
+// ============================================================================
+// TRANSACTION LOG
+// ============================================================================
+
+/// Lists the caller's monetary events, newest first
+///
+/// # Arguments
+/// * `filter` - Kinds, asset and time range to include (default: everything)
+/// * `limit` - Maximum number of entries to return (optional)
+/// * `offset` - Number of matching entries to skip for pagination (optional)
+///
+/// # Behavior
+/// - Covers tips, point purchases and grants, subscriptions, post sales,
+///   promotions, fundraisers, creator fund claims and withdrawals
+/// - Points earned from engagement are listed only in `get_my_points`
+#[query]
+pub fn get_my_transactions(
+    filter: Option<TransactionFilter>,
+    limit: Option<usize>,
+    offset: Option<usize>,
+) -> Result<TransactionsPage, String> {
+    let user_id = authenticate_user()?;
+    let filter = filter.unwrap_or_default();
+    let limit = limit
+        .unwrap_or(DEFAULT_CONNECTIONS_LIMIT)
+        .min(MAX_CONNECTIONS_LIMIT);
+    let offset = offset.unwrap_or(0);
+
+    Ok(with_state(|state| {
+        // Logs are capped at MAX_TRANSACTION_LOG entries, so counting matches is cheap
+        let matching = || {
+            state
+                .transaction_log
+                .get(&user_id)
+                .into_iter()
+                .flat_map(|log| log.iter().rev())
+                .filter(|record| filter.matches(record))
+        };
+        let (page, next_cursor) = page_at(matching(), offset, limit);
+        TransactionsPage {
+            transactions: page.into_iter().cloned().collect(),
+            total: Some(matching().count() as u64),
+            has_more: next_cursor.is_some(),
+            next_cursor,
+        }
+    }))
+}
+
+/// Internal function to append to a user's bounded transaction log
+fn record_transaction(state: &mut SocialNetworkState, user_id: UserId, record: TransactionRecord) {
+    let log = state.transaction_log.entry(user_id).or_default();
+    log.push(record);
+    if log.len() > MAX_TRANSACTION_LOG {
+        log.remove(0);
+    }
+}
+
+/// Internal function to log a payment between two users on both sides
+///
+/// `record` describes the payer's side; the payee's copy gets `payee_kind`,
+/// and each side names the other as counterparty.
+fn record_transfer(
+    state: &mut SocialNetworkState,
+    payer: UserId,
+    payee: UserId,
+    payee_kind: TransactionKind,
+    record: TransactionRecord,
+) {
+    let received = TransactionRecord {
+        kind: payee_kind,
+        counterparty: Some(payer),
+        ..record.clone()
+    };
+    record_transaction(
+        state,
+        payer,
+        TransactionRecord {
+            counterparty: Some(payee),
+            ..record
+        },
+    );
+    record_transaction(state, payee, received);
+}
+
+/// Internal function to log a subscription payment, if one was charged
+fn record_subscription_charge(
+    state: &mut SocialNetworkState,
+    ledger: Principal,
+    (subscriber, creator): (UserId, UserId),
+    amount: u64,
+    block_index: Option<Nat>,
+    now: u64,
+) {
+    if block_index.is_none() {
+        return;
+    }
+    record_transfer(
+        state,
+        subscriber,
+        creator,
+        TransactionKind::SubscriptionIncome,
+        TransactionRecord {
+            block_index,
+            ..TransactionRecord::new(
+                TransactionKind::SubscriptionCharged,
+                TransactionAsset::Token { ledger },
+                amount,
+                now,
+            )
+        },
+    );
+}
+
+// ============================================================================
+// LEDGER RECONCILIATION
+// ============================================================================
+
+/// Lists discrepancies found between recorded balances and the ledgers
+///
+/// # Arguments
+/// * `include_resolved` - Also list alerts already resolved (default: false)
+/// * `limit` - Maximum number of alerts to return (optional)
+/// * `offset` - Number of alerts to skip for pagination (optional)
+///
+/// # Security
+/// * Admins only
+#[query]
+pub fn get_ledger_alerts(
+    include_resolved: Option<bool>,
+    limit: Option<usize>,
+    offset: Option<usize>,
+) -> Result<LedgerAlertsPage, String> {
+    let user_id = authenticate_user()?;
+    require_admin(&user_id)?;
+
+    let include_resolved = include_resolved.unwrap_or(false);
+    let limit = limit
+        .unwrap_or(DEFAULT_CONNECTIONS_LIMIT)
+        .min(MAX_CONNECTIONS_LIMIT);
+    let offset = offset.unwrap_or(0);
+
+    Ok(with_state(|state| {
+        let alerts = state
+            .ledger_alerts
+            .values()
+            .rev()
+            .filter(|alert| include_resolved || alert.resolved_at.is_none());
+        let (page, next_cursor) = page_at(alerts, offset, limit);
+        LedgerAlertsPage {
+            alerts: page.into_iter().cloned().collect(),
+            total: include_resolved.then_some(state.ledger_alerts.len() as u64),
+            has_more: next_cursor.is_some(),
+            next_cursor,
+        }
+    }))
+}
+
+/// Closes a ledger alert after investigation
+///
+/// # Arguments
+/// * `alert_id` - Alert to resolve
+/// * `note` - What was found (optional)
+/// * `adopt_ledger_balance` - Correct the recorded balance by the difference
+///   the alert reported, e.g. after a deposit made outside the platform
+///
+/// # Security
+/// * Admins only
+/// * Recorded in the audit log
+#[update]
+pub async fn resolve_ledger_alert(
+    alert_id: u64,
+    note: Option<String>,
+    adopt_ledger_balance: bool,
+) -> Result<LedgerAlert, String> {
+    let admin_id = authenticate_user()?;
+    require_admin(&admin_id)?;
+    if let Some(ref note_text) = note {
+        validate_review_note(note_text)?;
+    }
+
+    with_state_mut(|state| {
+        let alert = state
+            .ledger_alerts
+            .get_mut(&alert_id)
+            .ok_or("Ledger alert not found")?;
+        if alert.resolved_at.is_some() {
+            return Err("Ledger alert is already resolved".to_string());
+        }
+        alert.resolved_by = Some(admin_id);
+        alert.resolved_at = Some(time());
+        alert.resolution_note = note;
+        let alert = alert.clone();
+
+        if adopt_ledger_balance {
+            let recorded = state
+                .recorded_balances
+                .entry((alert.ledger, alert.account))
+                .or_default();
+            *recorded = (*recorded as i128 + alert.actual as i128 - alert.expected as i128)
+                .clamp(0, u64::MAX as i128) as u64;
+        }
+
+        record_audit_event(
+            state,
+            admin_id,
+            AuditAction::LedgerAlertResolved,
+            None,
+            format!(
+                "alert {alert_id}: {:?} expected {} actual {}, adopted: {adopt_ledger_balance}",
+                alert.account, alert.expected, alert.actual
+            ),
+        );
+        Ok(alert)
+    })
+}
+
+/// Checks recorded balances against the ledgers now instead of waiting for the timer
+///
+/// # Returns
+/// The number of accounts found out of balance
+///
+/// # Security
+/// * Admins only
+#[update]
+pub async fn run_ledger_reconciliation() -> Result<u64, String> {
+    let admin_id = authenticate_user()?;
+    require_admin(&admin_id)?;
+    if with_state(|state| state.reconciliation_running) {
+        return Err("A reconciliation is already running".to_string());
+    }
+    Ok(reconcile_ledgers().await)
+}
+
+/// Internal function to compare every recorded balance with its ledger
+///
+/// Accounts with a transfer in flight, or whose record changed while the
+/// ledger was queried, are skipped until the next run.
+async fn reconcile_ledgers() -> u64 {
+    let accounts: Option<Vec<((Principal, ReconciledAccount), u64)>> = with_state_mut(|state| {
+        if std::mem::replace(&mut state.reconciliation_running, true) {
+            return None;
+        }
+        Some(
+            state
+                .recorded_balances
+                .iter()
+                .map(|(key, balance)| (*key, *balance))
+                .collect(),
+        )
+    });
+    let Some(accounts) = accounts else {
+        return 0;
+    };
+
+    let mut discrepancies = 0;
+    for ((ledger, account), expected) in accounts {
+        let actual = ledger::balance_of(
+            ledger,
+            ledger::canister_account(reconciled_subaccount(account)),
+        )
+        .await;
+        let Ok(actual) = actual else {
+            continue;
+        };
+
+        let out_of_balance = with_state_mut(|state| {
+            let key = (ledger, account);
+            let settled = !state.ledger_transfers_in_flight.contains_key(&key)
+                && state.recorded_balances.get(&key) == Some(&expected);
+            if !settled || actual == expected {
+                return false;
+            }
+            flag_ledger_discrepancy(state, ledger, account, expected, actual, time());
+            log_event(
+                state,
+                LogLevel::Error,
+                LogCategory::Payments,
+                None,
+                format!("{account:?} on {ledger}: recorded {expected}, ledger {actual}"),
+            );
+            true
+        });
+        if out_of_balance {
+            discrepancies += 1;
+        }
+    }
+
+    with_state_mut(|state| state.reconciliation_running = false);
+    discrepancies
+}
+
+/// Internal function to open an alert, or refresh the account's open one
+fn flag_ledger_discrepancy(
+    state: &mut SocialNetworkState,
+    ledger: Principal,
+    account: ReconciledAccount,
+    expected: u64,
+    actual: u64,
+    now: u64,
+) {
+    let open = state.ledger_alerts.values_mut().find(|alert| {
+        alert.ledger == ledger && alert.account == account && alert.resolved_at.is_none()
+    });
+    if let Some(alert) = open {
+        alert.expected = expected;
+        alert.actual = actual;
+        alert.last_seen_at = now;
+        return;
+    }
+
+    let id = state.next_ledger_alert_id;
+    state.next_ledger_alert_id = state.next_ledger_alert_id.saturating_add(1);
+    state.ledger_alerts.insert(
+        id,
+        LedgerAlert {
+            id,
+            ledger,
+            account,
+            expected,
+            actual,
+            detected_at: now,
+            last_seen_at: now,
+            resolved_by: None,
+            resolved_at: None,
+            resolution_note: None,
+        },
+    );
+}
+
+/// Internal function to find the canister subaccount behind a reconciled account
+fn reconciled_subaccount(account: ReconciledAccount) -> [u8; 32] {
+    match account {
+        ReconciledAccount::Earnings { creator } => earnings::earnings_subaccount(&creator),
+        ReconciledAccount::FundraiserEscrow { post_id } => fundraiser::escrow_subaccount(post_id),
+        ReconciledAccount::PointsSales => points::SALES_SUBACCOUNT,
+    }
+}
+
+/// Internal function to mark a transfer touching a reconciled account as pending
+fn begin_tracked_transfer(ledger: Principal, account: ReconciledAccount) {
+    with_state_mut(|state| {
+        *state
+            .ledger_transfers_in_flight
+            .entry((ledger, account))
+            .or_default() += 1;
+    });
+}
+
+/// Internal function to finish a tracked transfer and apply it to the record
+///
+/// Pass zero for both amounts when the transfer failed.
+fn end_tracked_transfer(ledger: Principal, account: ReconciledAccount, received: u64, sent: u64) {
+    with_state_mut(|state| {
+        let key = (ledger, account);
+        if let Some(count) = state.ledger_transfers_in_flight.get_mut(&key) {
+            *count -= 1;
+            if *count == 0 {
+                state.ledger_transfers_in_flight.remove(&key);
+            }
+        }
+        if received > 0 || sent > 0 {
+            let recorded = state.recorded_balances.entry(key).or_default();
+            *recorded = recorded.saturating_add(received).saturating_sub(sent);
+        }
+    });
+}
+
+// ============================================================================
+// LOGGING
+// ============================================================================
+
+/// Gets log entries, oldest first
+///
+/// # Arguments
+/// * `since` - Only entries at or after this time (default: all retained)
+/// * `level` - Minimum severity (default: Info)
+///
+/// # Behavior
+/// - At most MAX_CONNECTIONS_LIMIT entries are returned; pass the last
+///   timestamp seen as `since` to continue, skipping already seen IDs
+///
+/// # Security
+/// * Admins only
+#[query]
+pub fn get_logs(since: Option<u64>, level: Option<LogLevel>) -> Result<Vec<LogEntry>, String> {
+    let user_id = authenticate_user()?;
+    require_admin(&user_id)?;
+
+    Ok(with_state(|state| {
+        state
+            .logs
+            .query(since.unwrap_or(0), level.unwrap_or(LogLevel::Info))
+            .take(MAX_CONNECTIONS_LIMIT)
+            .cloned()
+            .collect()
+    }))
+}
+
+/// Internal function to record an event in the canister log
+///
+/// Entries are mirrored to the replica's canister log.
+fn log_event(
+    state: &mut SocialNetworkState,
+    level: LogLevel,
+    category: LogCategory,
+    principal: Option<Principal>,
+    message: String,
+) {
+    ic_cdk::println!("[{level:?}] {category:?}: {message}");
+    state.logs.push(level, category, principal, message, time());
+}
+
+// ============================================================================
+// POST ARCHIVE
+// ============================================================================
+
+/// Configures moving cold posts to an archive canister
+///
+/// # Behavior
+/// - Once a day, posts older than `archive_after_days` are sent with their
+///   comments to the archive canister, in batches of `MAX_ARCHIVE_BATCH`
+/// - A stub with the post's counters and metadata stays here; `get_post`
+///   fetches the content back from the archive
+/// - Archived posts cannot be commented on or minted; deleting one purges
+///   the archived copy along with the stub
+/// - `None` stops archiving; posts already archived stay readable
+///
+/// # Security
+/// * Admins only
+/// * Recorded in the audit log
+#[update]
+pub async fn set_archive_config(config: Option<ArchiveConfig>) -> Result<(), String> {
+    let admin_id = authenticate_user()?;
+    require_admin(&admin_id)?;
+
+    if let Some(config) = &config {
+        if config.archive_after_days < MIN_ARCHIVE_AFTER_DAYS {
+            return Err(format!(
+                "Posts can be archived after {MIN_ARCHIVE_AFTER_DAYS} days at the earliest"
+            ));
+        }
+    }
+
+    with_state_mut(|state| {
+        let details = match &config {
+            Some(config) => format!(
+                "{} after {} days",
+                config.canister_id, config.archive_after_days
+            ),
+            None => "disabled".to_string(),
+        };
+        state.archive_config = config;
+        record_audit_event(
+            state,
+            admin_id,
+            AuditAction::ArchiveConfigured,
+            None,
+            details,
+        );
+    });
+    Ok(())
+}
+
+/// Gets the archive settings, if archiving is enabled
+#[query]
+pub fn get_archive_config() -> Option<ArchiveConfig> {
+    with_state(|state| state.archive_config.clone())
+}
+
+/// Internal function to check whether a post is archived or being archived
+fn is_archived(state: &SocialNetworkState, post_id: PostId) -> bool {
+    state.archived_posts.contains_key(&post_id) || state.posts_being_archived.contains(&post_id)
+}
+
+/// Internal function to move one batch of cold posts to the archive canister
+///
+/// Posts are only stubbed once the archive canister has accepted them.
+async fn archive_cold_posts() {
+    let Some(config) = with_state(|state| state.archive_config.clone()) else {
+        return;
+    };
+    let now = time();
+
+    let batch: Vec<ArchivedPost> = with_state_mut(|state| {
+        let post_ids: Vec<PostId> = state
+            .posts
+            .values()
+            .take_while(|post| archive::is_cold(post.created_at, now, config.archive_after_days))
+            .filter(|post| !is_archived(state, post.id))
+            .take(MAX_ARCHIVE_BATCH)
+            .map(|post| post.id)
+            .collect();
+
+        post_ids
+            .into_iter()
+            .map(|post_id| {
+                state.posts_being_archived.insert(post_id);
+                let comments = state
+                    .post_comments
+                    .get(&post_id)
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|comment_id| state.comments.get(comment_id))
+                    .cloned()
+                    .collect();
+                ArchivedPost {
+                    post: inflated_post(state, &state.posts[&post_id]),
+                    comments,
+                }
+            })
+            .collect()
+    });
+    if batch.is_empty() {
+        return;
+    }
+
+    let archived: Vec<(PostId, u64)> = batch
+        .iter()
+        .map(|archived| (archived.post.id, archived.post.content.len() as u64))
+        .collect();
+    let result = archive::store(config.canister_id, batch).await;
+
+    with_state_mut(|state| {
+        for &(post_id, _) in &archived {
+            state.posts_being_archived.remove(&post_id);
+        }
+        if let Err(error) = result {
+            log_event(state, LogLevel::Error, LogCategory::System, None, error);
+            return;
+        }
+
+        for &(post_id, original_len) in &archived {
+            if let Some(post) = state.posts.get_mut(&post_id) {
+                archive::stub(post);
+            }
+            if let Some(compressed) = state.compressed_post_bodies.remove(&post_id) {
+                let stats = &mut state.compression_stats;
+                stats.compressed_posts = stats.compressed_posts.saturating_sub(1);
+                stats.original_bytes = stats.original_bytes.saturating_sub(original_len);
+                stats.stored_bytes = stats.stored_bytes.saturating_sub(compressed.len() as u64);
+            }
+            for comment_id in state.post_comments.remove(&post_id).unwrap_or_default() {
+                if let Some(comment) = state.comments.remove(&comment_id) {
+                    if let Some(comments) = state.user_comments.get_mut(&comment.author_id) {
+                        comments.remove(&comment_id);
+                    }
+                    state
+                        .archived_comments
+                        .insert(comment_id, (post_id, comment.author_id));
+                }
+            }
+            state.archived_posts.insert(post_id, config.canister_id);
+        }
+        log_event(
+            state,
+            LogLevel::Info,
+            LogCategory::System,
+            None,
+            format!(
+                "Archived {} posts to {}",
+                archived.len(),
+                config.canister_id
+            ),
+        );
+    });
+}
+
+// ============================================================================
+// READ REPLICAS
+// ============================================================================
+
+/// Uploads the wasm module installed on newly spawned read replicas
+///
+/// Replicas run the same module as this canister, started in replica mode.
+///
+/// # Security
+/// * Admins only
+/// * Recorded in the audit log
+#[update]
+pub async fn set_replica_wasm(wasm_module: Vec<u8>) -> Result<(), String> {
+    let admin_id = authenticate_user()?;
+    require_admin(&admin_id)?;
+
+    if wasm_module.is_empty() {
+        return Err("Wasm module is empty".to_string());
+    }
+
+    with_state_mut(|state| {
+        let details = format!("{} bytes", wasm_module.len());
+        state.replica_wasm = Some(wasm_module);
+        record_audit_event(
+            state,
+            admin_id,
+            AuditAction::ReplicaWasmUploaded,
+            None,
+            details,
+        );
+    });
+    Ok(())
+}
+
+/// Creates a read-only replica canister that serves feed and search queries
+///
+/// # Arguments
+/// * `cycles` - Cycles moved from this canister to the replica
+///
+/// # Behavior
+/// - The replica is first filled with all profiles and public posts, then
+///   kept current with new posts and profile changes every
+///   `REPLICA_SYNC_INTERVAL_SECONDS`
+/// - Replicas reject update calls; writes stay on this canister
+/// - The calling admin becomes a controller of the replica
+///
+/// # Returns
+/// The replica's canister id
+///
+/// # Security
+/// * Admins only
+/// * Recorded in the audit log
+#[update]
+pub async fn spawn_read_replica(cycles: u128) -> Result<Principal, String> {
+    let admin_id = authenticate_user()?;
+    require_admin(&admin_id)?;
+
+    let wasm_module = with_state(|state| {
+        if state.read_replicas.len() >= MAX_READ_REPLICAS {
+            return Err(format!("At most {MAX_READ_REPLICAS} read replicas"));
+        }
+        state
+            .replica_wasm
+            .clone()
+            .ok_or_else(|| "Replica wasm module is not uploaded".to_string())
+    })?;
+
+    let canister_id = replica::spawn(wasm_module, vec![admin_id.0], cycles).await?;
+
+    with_state_mut(|state| {
+        let replica = ReadReplica {
+            canister_id,
+            created_at: time(),
+            synced_seq: state.replica_deltas.next_seq(),
+            backfill: Some(ReplicaBackfill::Profiles { after: None }),
+            last_error: None,
+            last_synced_at: None,
+        };
+        state.read_replicas.insert(canister_id, replica);
+        record_audit_event(
+            state,
+            admin_id,
+            AuditAction::ReadReplicaSpawned,
+            None,
+            format!("{canister_id} with {cycles} cycles"),
+        );
+    });
+    Ok(canister_id)
+}
+
+/// Stops syncing a read replica
+///
+/// The replica canister itself is left running; its controllers can delete it.
+///
+/// # Security
+/// * Admins only
+/// * Recorded in the audit log
+#[update]
+pub async fn remove_read_replica(canister_id: Principal) -> Result<(), String> {
+    let admin_id = authenticate_user()?;
+    require_admin(&admin_id)?;
+
+    with_state_mut(|state| {
+        if state
+            .mirror_config
+            .as_ref()
+            .is_some_and(|mirror| mirror.canister_id == canister_id)
+        {
+            return Err("Remove the mirror with set_mirror_config".to_string());
+        }
+        state
+            .read_replicas
+            .remove(&canister_id)
+            .ok_or("Read replica not found")?;
+        let synced = state
+            .read_replicas
+            .values()
+            .map(|replica| replica.synced_seq)
+            .min()
+            .unwrap_or(state.replica_deltas.next_seq());
+        state.replica_deltas.trim(synced);
+        record_audit_event(
+            state,
+            admin_id,
+            AuditAction::ReadReplicaRemoved,
+            None,
+            canister_id.to_string(),
+        );
+        Ok(())
+    })
+}
+
+/// Lists read replicas and how far each is synced
+///
+/// # Security
+/// * Admins only
+#[query]
+pub fn get_read_replicas() -> Result<Vec<ReadReplica>, String> {
+    let user_id = authenticate_user()?;
+    require_admin(&user_id)?;
+
+    Ok(with_state(|state| {
+        state.read_replicas.values().cloned().collect()
+    }))
+}
+
+/// Configures a standby mirror of public posts and profiles on another subnet
+///
+/// # Behavior
+/// - The mirror is synced like a read replica: first filled with all profiles
+///   and public posts, then sent new changes every `REPLICA_SYNC_INTERVAL_SECONDS`
+/// - Clients look it up with `get_mirror_status` and read from it when this
+///   canister or its subnet is unreachable
+/// - Replacing or disabling (`None`) the mirror stops syncing the old one;
+///   its controllers can delete it
+///
+/// # Security
+/// * Admins only
+/// * Recorded in the audit log
+#[update]
+pub async fn set_mirror_config(config: Option<MirrorConfig>) -> Result<(), String> {
+    let admin_id = authenticate_user()?;
+    require_admin(&admin_id)?;
+
+    if let Some(config) = &config {
+        if config.canister_id == ic_cdk::id() {
+            return Err("A canister cannot mirror itself".to_string());
+        }
+    }
+
+    with_state_mut(|state| {
+        if let Some(previous) = state.mirror_config.take() {
+            state.read_replicas.remove(&previous.canister_id);
+        }
+        let details = match &config {
+            Some(config) => {
+                let replica = ReadReplica {
+                    canister_id: config.canister_id,
+                    created_at: time(),
+                    synced_seq: state.replica_deltas.next_seq(),
+                    backfill: Some(ReplicaBackfill::Profiles { after: None }),
+                    last_error: None,
+                    last_synced_at: None,
+                };
+                state.read_replicas.insert(config.canister_id, replica);
+                format!("{} on subnet {}", config.canister_id, config.subnet)
+            }
+            None => "disabled".to_string(),
+        };
+        state.mirror_config = config;
+        record_audit_event(
+            state,
+            admin_id,
+            AuditAction::MirrorConfigured,
+            None,
+            details,
+        );
+    });
+    Ok(())
+}
+
+/// Gets the standby mirror to read from when this canister is unreachable
+///
+/// Clients should fetch this while the canister is reachable and keep it for
+/// failover. The mirror serves the same read endpoints for public content.
+#[query]
+pub fn get_mirror_status() -> Option<MirrorStatus> {
+    with_state(|state| {
+        let config = state.mirror_config.as_ref()?;
+        let replica = state.read_replicas.get(&config.canister_id)?;
+        Some(MirrorStatus {
+            canister_id: config.canister_id,
+            subnet: config.subnet,
+            caught_up: replica.backfill.is_none()
+                && replica.synced_seq == state.replica_deltas.next_seq(),
+            last_synced_at: replica.last_synced_at,
+        })
+    })
+}
+
+/// Applies changes pushed by the primary canister
+///
+/// Every delta is an upsert, so a batch resent after a failed reply is harmless.
+///
+/// # Security
+/// * Only callable by the primary this canister replicates
+#[update]
+pub async fn apply_replica_deltas(deltas: Vec<StateDelta>) -> Result<(), String> {
+    with_state_mut(|state| {
+        if state.replica_of != Some(caller()) {
+            return Err("Only the primary canister can apply deltas".to_string());
+        }
+        for delta in deltas {
+            apply_state_delta(state, delta);
+        }
+        Ok(())
+    })
+}
+
+/// Internal function to apply one delta from the primary to a replica
+fn apply_state_delta(state: &mut SocialNetworkState, delta: StateDelta) {
+    match delta {
+        StateDelta::ProfileUpdated(profile) => {
+            if let Some(previous) = state.users.get(&profile.id) {
+                state
+                    .handle_index
+                    .remove(&normalize_handle(&previous.handle));
+            }
+            state
+                .handle_index
+                .insert(normalize_handle(&profile.handle), profile.id);
+            state.user_posts.entry(profile.id).or_default();
+            state.users.insert(profile.id, *profile);
+        }
+        StateDelta::PostCreated(post) => {
+            let (post_id, author_id) = (post.id, post.author_id);
+            if feed_filter::has_media(&post.content) {
+                state
+                    .user_media_posts
+                    .entry(author_id)
+                    .or_default()
+                    .insert(post_id);
+            }
+            index_post_location(state, &post);
+            index_post_sources(state, &post);
+            if state.posts.insert(post_id, *post).is_none() {
+                state.post_likes.insert(post_id, BTreeSet::new());
+                state.post_comments.insert(post_id, Vec::new());
+                state.user_posts.entry(author_id).or_default().push(post_id);
+            }
+            state.next_post_id = state.next_post_id.max(post_id.0.saturating_add(1));
+        }
+        StateDelta::PostDeleted(post_id) => {
+            if let Some(post) = state.posts.remove(&post_id) {
+                state.deletion_log.record(post_id, post.author_id, time());
+                if let Some(post_ids) = state.user_posts.get_mut(&post.author_id) {
+                    post_ids.retain(|id| *id != post_id);
+                }
+                if let Some(post_ids) = state.user_media_posts.get_mut(&post.author_id) {
+                    post_ids.remove(&post_id);
+                }
+            }
+        }
+    }
+}
+
+/// Internal function to add or drop a post on read replicas once a gate
+/// change makes it syndicated or takes it out of syndication
+fn sync_replica_post(state: &mut SocialNetworkState, post_id: PostId, was_syndicated: bool) {
+    let Some(post) = state.posts.get(&post_id) else {
+        return;
+    };
+    match (was_syndicated, post.is_syndicated()) {
+        (false, true) => record_replica_delta(state, |state| {
+            StateDelta::PostCreated(Box::new(inflated_post(state, &state.posts[&post_id])))
+        }),
+        (true, false) => record_replica_delta(state, |_| StateDelta::PostDeleted(post_id)),
+        _ => {}
+    }
+}
+
+/// Internal function to queue a change for the read replicas
+///
+/// Takes a closure so nothing is cloned while there are no replicas. Replicas
+/// that fell behind the bounded delta log are copied again from scratch.
+fn record_replica_delta(
+    state: &mut SocialNetworkState,
+    delta: impl FnOnce(&SocialNetworkState) -> StateDelta,
+) {
+    if state.read_replicas.is_empty() {
+        return;
+    }
+
+    let delta = delta(state);
+    state.replica_deltas.push(delta);
+    let next_seq = state.replica_deltas.next_seq();
+    for replica in state.read_replicas.values_mut() {
+        if !state.replica_deltas.covers(replica.synced_seq) {
+            replica.synced_seq = next_seq;
+            replica.backfill = Some(ReplicaBackfill::Profiles { after: None });
+        }
+    }
+}
+
+/// Internal function to queue a successfully changed profile for the read replicas
+///
+/// Also records the change in the event journal.
+fn replicate_profile(result: Result<UserProfile, String>) -> Result<UserProfile, String> {
+    if let Ok(profile) = &result {
+        with_state_mut(|state| {
+            record_replica_delta(state, |_| {
+                StateDelta::ProfileUpdated(Box::new(profile.clone()))
+            });
+            state.journal.push(
+                JournalEventKind::ProfileUpdated {
+                    user_id: profile.id,
+                },
+                time(),
+            );
+        });
+    }
+    result
+}
+
+/// Internal function to pick the next batch for a replica
+///
+/// # Returns
+/// The deltas to send and the replica's position once they are applied
+fn next_replica_batch(
+    state: &SocialNetworkState,
+    replica: &ReadReplica,
+) -> (Vec<StateDelta>, u64, Option<ReplicaBackfill>) {
+    use std::ops::Bound::{Excluded, Unbounded};
+
+    match &replica.backfill {
+        Some(ReplicaBackfill::Profiles { after }) => {
+            let start = after.map_or(Unbounded, Excluded);
+            let profiles: Vec<&UserProfile> = state
+                .users
+                .range((start, Unbounded))
+                .map(|(_, profile)| profile)
+                .take(MAX_REPLICA_BATCH)
+                .collect();
+            let backfill = match profiles.last() {
+                Some(last) if profiles.len() == MAX_REPLICA_BATCH => ReplicaBackfill::Profiles {
+                    after: Some(last.id),
+                },
+                _ => ReplicaBackfill::Posts { after: None },
+            };
+            let deltas = profiles
+                .into_iter()
+                .map(|profile| StateDelta::ProfileUpdated(Box::new(profile.clone())))
+                .collect();
+            (deltas, replica.synced_seq, Some(backfill))
+        }
+        Some(ReplicaBackfill::Posts { after }) => {
+            let start = after.map_or(Unbounded, Excluded);
+            let posts: Vec<&Post> = state
+                .posts
+                .range((start, Unbounded))
+                .map(|(_, post)| post)
+                .take(MAX_REPLICA_BATCH)
+                .collect();
+            let backfill = match posts.last() {
+                Some(last) if posts.len() == MAX_REPLICA_BATCH => Some(ReplicaBackfill::Posts {
+                    after: Some(last.id),
+                }),
+                _ => None,
+            };
+            let deltas = posts
+                .into_iter()
+                .filter(|post| post.is_syndicated())
+                .map(|post| StateDelta::PostCreated(Box::new(inflated_post(state, post))))
+                .collect();
+            (deltas, replica.synced_seq, backfill)
+        }
+        None => {
+            let deltas = state
+                .replica_deltas
+                .since(replica.synced_seq, MAX_REPLICA_BATCH);
+            let synced_seq = replica.synced_seq + deltas.len() as u64;
+            (deltas, synced_seq, None)
+        }
+    }
+}
+
+/// Internal function to push one batch to every read replica
+///
+/// Runs on a timer; a replica's position only advances once it has accepted
+/// the batch, and not if it was reset meanwhile.
+async fn sync_read_replicas() {
+    let replicas = with_state_mut(|state| {
+        if state.replica_sync_in_flight {
+            return Vec::new();
+        }
+        state.replica_sync_in_flight = !state.read_replicas.is_empty();
+        state.read_replicas.values().cloned().collect::<Vec<_>>()
+    });
+    if replicas.is_empty() {
+        return;
+    }
+
+    for replica in replicas {
+        let (deltas, synced_seq, backfill) =
+            with_state(|state| next_replica_batch(state, &replica));
+        if deltas.is_empty() && backfill == replica.backfill {
+            continue;
+        }
+
+        let result = replica::push(replica.canister_id, deltas).await;
+
+        with_state_mut(|state| {
+            let Some(current) = state.read_replicas.get_mut(&replica.canister_id) else {
+                return;
+            };
+            if current.synced_seq != replica.synced_seq || current.backfill != replica.backfill {
+                return;
+            }
+            match result {
+                Ok(()) => {
+                    current.synced_seq = synced_seq;
+                    current.backfill = backfill;
+                    current.last_error = None;
+                    current.last_synced_at = Some(time());
+                }
+                Err(error) => {
+                    current.last_error = Some(error.clone());
+                    log_event(state, LogLevel::Warn, LogCategory::System, None, error);
+                }
+            }
+        });
+    }
+
+    with_state_mut(|state| {
+        let synced = state
+            .read_replicas
+            .values()
+            .map(|replica| replica.synced_seq)
+            .min()
+            .unwrap_or(state.replica_deltas.next_seq());
+        state.replica_deltas.trim(synced);
+        state.replica_sync_in_flight = false;
+    });
+}
+
+// ============================================================================
+// API VERSIONING
+// ============================================================================
+//
+// Breaking changes ship as a new method named `<method>_v<major>` next to the
+// old one, which stays listed in DEPRECATED_METHODS until that major version
+// is released. Additions only bump the minor version.
+
+/// Gets the Candid interface version and the methods scheduled for removal
+#[query]
+pub fn get_api_version() -> ApiVersion {
+    ApiVersion {
+        major: API_VERSION_MAJOR,
+        minor: API_VERSION_MINOR,
+        deprecated: DEPRECATED_METHODS
+            .iter()
+            .map(|&(method, replacement, removed_in)| DeprecatedMethod {
+                method: method.to_string(),
+                replacement: replacement.to_string(),
+                removed_in,
+            })
+            .collect(),
+    }
+}
+
+// ============================================================================
+// DEVELOPER APPS
+// ============================================================================
+
+/// Proposes an application that calls the canister on the caller's behalf
+///
+/// # Arguments
+/// * `principal` - Identity the application signs its calls with; its key
+///   pair stays with the developer
+/// * `name` - Display name (1-64 chars)
+/// * `scope` - What the application may do (see `AppScope`)
+///
+/// # Returns
+/// * `Ok(DeveloperApp)` - The pending registration
+/// * `Err(String)` - Validation error or the principal is already in use
+///
+/// # Behavior
+/// Nothing changes for `principal` until it calls `accept_app_registration`
+/// with the caller as owner, proving the developer holds its key.
+///
+/// # Security
+/// * The principal must not belong to a user or another application
+/// * At most MAX_APPS_PER_USER applications per user, pending ones included
+/// * Methods not open to applications reject calls from them
+#[update]
+pub async fn register_app(
+    principal: Principal,
+    name: String,
+    scope: AppScope,
+) -> Result<DeveloperApp, String> {
+    let user_id = authenticate_user()?;
+    validate_app_name(&name)?;
+    if principal == Principal::anonymous() || principal == user_id.0 {
+        return Err("Invalid application principal".to_string());
+    }
+
+    check_rate_limit(&user_id, "register_app", 5, 86_400)?; // 5 registrations per day
+
+    with_state_mut(|state| {
+        if !state.users.contains_key(&user_id) {
+            return Err("Profile not found".to_string());
+        }
+        if state.users.contains_key(&UserId(principal)) || state.apps.contains_key(&principal) {
+            return Err("Principal is already in use".to_string());
+        }
+        if state.pending_apps.contains_key(&(principal, user_id)) {
+            return Err("Application is already awaiting acceptance".to_string());
+        }
+        let registered = state
+            .apps
+            .values()
+            .chain(state.pending_apps.values())
+            .filter(|app| app.owner == user_id)
+            .count();
+        if registered >= MAX_APPS_PER_USER {
+            return Err(format!("At most {MAX_APPS_PER_USER} applications per user"));
+        }
+
+        let app = DeveloperApp {
+            principal,
+            owner: user_id,
+            name,
+            scope,
+            created_at: time(),
+        };
+        state.pending_apps.insert((principal, user_id), app.clone());
+        Ok(app)
+    })
+}
+
+/// Accepts a registration proposed for the calling principal, which becomes
+/// an application acting for `owner`
+///
+/// # Returns
+/// * `Ok(DeveloperApp)` - The registration, now in effect
+/// * `Err(String)` - No registration was proposed by `owner`, or the caller
+///   is a user or already an application
+///
+/// # Security
+/// * Must be called by the application principal itself
+/// * Other owners' proposals for the principal are discarded
+#[update]
+pub async fn accept_app_registration(owner: Principal) -> Result<DeveloperApp, String> {
+    let principal = ic_cdk::caller();
+    if principal == Principal::anonymous() {
+        return Err(SocialNetworkError::AuthenticationRequired.into());
+    }
+    if let Some(primary) = with_state(|state| state.replica_of) {
+        return Err(format!("This canister is a read-only replica of {primary}"));
+    }
+
+    with_state_mut(|state| {
+        if state.users.contains_key(&UserId(principal)) || state.apps.contains_key(&principal) {
+            return Err("Principal is already in use".to_string());
+        }
+        let app = state
+            .pending_apps
+            .remove(&(principal, UserId(owner)))
+            .ok_or("No registration proposed by this owner")?;
+        if !state.users.contains_key(&app.owner) {
+            return Err("Profile not found".to_string());
+        }
+        state
+            .pending_apps
+            .retain(|(pending, _), _| *pending != principal);
+        state.apps.insert(principal, app.clone());
+        Ok(app)
+    })
+}
+
+/// Changes the scope of one of the caller's applications
+#[update]
+pub async fn set_app_scope(principal: Principal, scope: AppScope) -> Result<DeveloperApp, String> {
+    let user_id = authenticate_user()?;
+
+    with_state_mut(|state| {
+        let app = state
+            .apps
+            .get_mut(&principal)
+            .filter(|app| app.owner == user_id)
+            .ok_or("Application not found")?;
+        app.scope = scope;
+        Ok(app.clone())
+    })
+}
+
+/// Revokes one of the caller's applications; its principal is rejected from then on
+///
+/// Also withdraws a registration still awaiting acceptance.
+#[update]
+pub async fn revoke_app(principal: Principal) -> Result<(), String> {
+    let user_id = authenticate_user()?;
+
+    with_state_mut(|state| {
+        if state.pending_apps.remove(&(principal, user_id)).is_some() {
+            return Ok(());
+        }
+        state
+            .apps
+            .get(&principal)
+            .filter(|app| app.owner == user_id)
+            .ok_or("Application not found")?;
+        state.apps.remove(&principal);
+        state.app_usage.remove(&principal);
+        state.app_throttles.remove(&principal);
+        Ok(())
+    })
+}
+
+/// Throttles an application to a share of its usual rate limits
+///
+/// # Arguments
+/// * `principal` - Application to throttle
+/// * `percent` - 0-100; 0 rejects every rate-limited call. `None` lifts the
+///   throttle the caller set
+///
+/// # Behavior
+/// - Owners and admins each set their own throttle; the lower one applies,
+///   so an owner cannot lift an admin's throttle
+/// - The user's direct calls are unaffected
+///
+/// # Security
+/// * The application's owner or an admin
+/// * Admin throttles are recorded in the audit log
+#[update]
+pub async fn set_app_throttle(principal: Principal, percent: Option<u8>) -> Result<(), String> {
+    let user_id = authenticate_user()?;
+    if percent.is_some_and(|percent| percent > 100) {
+        return Err("Throttle must be between 0 and 100 percent".to_string());
+    }
+
+    with_state_mut(|state| {
+        let owner = state
+            .apps
+            .get(&principal)
+            .map(|app| app.owner)
+            .ok_or("Application not found")?;
+        let throttle = state.app_throttles.entry(principal).or_default();
+        if owner == user_id {
+            throttle.owner_percent = percent;
+        } else if is_admin(&user_id) {
+            throttle.admin_percent = percent;
+            record_audit_event(
+                state,
+                user_id,
+                AuditAction::AppThrottled,
+                Some(owner),
+                match percent {
+                    Some(percent) => format!("{principal}: {percent}%"),
+                    None => format!("{principal}: lifted"),
+                },
+            );
+        } else {
+            return Err("Application not found".to_string());
+        }
+        if state.app_throttles.get(&principal).is_some_and(|throttle| {
+            throttle.owner_percent.is_none() && throttle.admin_percent.is_none()
+        }) {
+            state.app_throttles.remove(&principal);
+        }
+        Ok(())
+    })
+}
+
+/// Gets the usage dashboard of one of the caller's applications
+///
+/// Only rate-limited calls are counted; reads made with query calls cannot
+/// be recorded.
+#[query]
+pub fn get_app_usage(principal: Principal) -> Result<AppUsageReport, String> {
+    let user_id = authenticate_user()?;
+
+    with_state(|state| {
+        let app = state
+            .apps
+            .get(&principal)
+            .filter(|app| app.owner == user_id)
+            .ok_or("Application not found")?;
+        let usage = state.app_usage.get(&principal).cloned().unwrap_or_default();
+
+        let mut calls_by_action: Vec<(String, u64)> = usage.calls_by_action.into_iter().collect();
+        calls_by_action.sort_by_key(|(_, calls)| std::cmp::Reverse(*calls));
+
+        let days: BTreeSet<u64> = usage
+            .daily_calls
+            .keys()
+            .chain(usage.daily_throttled.keys())
+            .copied()
+            .collect();
+        let days = days
+            .into_iter()
+            .map(|day| AppDailyUsage {
+                day,
+                calls: usage.daily_calls.get(&day).copied().unwrap_or(0),
+                throttled: usage.daily_throttled.get(&day).copied().unwrap_or(0),
+            })
+            .collect();
+
+        Ok(AppUsageReport {
+            app: app.clone(),
+            throttle: state
+                .app_throttles
+                .get(&principal)
+                .cloned()
+                .unwrap_or_default(),
+            calls_by_action,
+            days,
+            last_call_at: usage.last_call_at,
+        })
+    })
+}
+
+/// Internal function to count a rate-limited call by an application
+fn record_app_call(
+    state: &mut SocialNetworkState,
+    app: Principal,
+    action: &str,
+    allowed: bool,
+    now: u64,
+) {
+    let usage = state.app_usage.entry(app).or_default();
+    usage.last_call_at = Some(now);
+    let (bumped, other) = if allowed {
+        *usage.calls_by_action.entry(action.to_string()).or_default() += 1;
+        (&mut usage.daily_calls, &mut usage.daily_throttled)
+    } else {
+        (&mut usage.daily_throttled, &mut usage.daily_calls)
+    };
+    bump_daily_counter(bumped, now);
+
+    let first_day = (now / NANOS_PER_DAY).saturating_sub(ACTIVITY_WINDOW_DAYS.saturating_sub(1));
+    other.retain(|&day, _| day >= first_day);
+}
+
+/// Lists the caller's registered applications
+#[query]
+pub fn get_my_apps() -> Result<Vec<DeveloperApp>, String> {
+    let user_id = authenticate_user()?;
+    Ok(with_state(|state| {
+        state
+            .apps
+            .values()
+            .filter(|app| app.owner == user_id)
+            .cloned()
+            .collect()
+    }))
+}
+
+/// Lists the caller's registrations awaiting acceptance by their application
+#[query]
+pub fn get_my_pending_apps() -> Result<Vec<DeveloperApp>, String> {
+    let user_id = authenticate_user()?;
+    Ok(with_state(|state| {
+        state
+            .pending_apps
+            .values()
+            .filter(|app| app.owner == user_id)
+            .cloned()
+            .collect()
+    }))
+}
+
+// ============================================================================
+// FACT CHECKS
+// ============================================================================
+
+/// Appoints a fact-checker, who can label public posts
+///
+/// # Security
+/// * Admins only
+/// * The user must be a verified organization or journalist
+/// * Recorded in the audit log
+#[update]
+pub async fn add_fact_checker(user_id: Principal) -> Result<(), String> {
+    let admin_id = authenticate_user()?;
+    require_admin(&admin_id)?;
+    let checker_id = UserId(user_id);
+
+    with_state_mut(|state| {
+        let profile = state.users.get(&checker_id).ok_or("User does not exist")?;
+        if !is_fact_check_status(&profile.verification_status) {
+            return Err("Fact-checkers must be verified organizations or journalists".to_string());
+        }
+        if !state.fact_checkers.insert(checker_id) {
+            return Err("User is already a fact-checker".to_string());
+        }
+
+        record_audit_event(
+            state,
+            admin_id,
+            AuditAction::FactCheckerAdded,
+            Some(checker_id),
+            String::new(),
+        );
+        Ok(())
+    })
+}
+
+/// Removes a fact-checker; labels they already applied stay on their posts
+///
+/// # Security
+/// * Admins only
+/// * Recorded in the audit log
+#[update]
+pub async fn remove_fact_checker(user_id: Principal) -> Result<(), String> {
+    let admin_id = authenticate_user()?;
+    require_admin(&admin_id)?;
+    let checker_id = UserId(user_id);
+
+    with_state_mut(|state| {
+        if !state.fact_checkers.remove(&checker_id) {
+            return Err("User is not a fact-checker".to_string());
+        }
+
+        record_audit_event(
+            state,
+            admin_id,
+            AuditAction::FactCheckerRemoved,
+            Some(checker_id),
+            String::new(),
+        );
+        Ok(())
+    })
+}
+
+/// Labels a public post with a fact-check verdict
+///
+/// Replaces the caller's earlier label on the post. The post's author sees new
+/// labels in `get_activity_summary` and can appeal them.
+///
+/// # Arguments
+/// * `post_id` - Public post to label
+/// * `verdict` - Disputed, MissingContext or Verified
+/// * `evidence_url` - https link to the supporting material
+///
+/// # Security
+/// * Appointed fact-checkers who are still verified organizations or journalists
+/// * Authors cannot label their own posts
+/// * Labels under appeal or overturned on appeal cannot be changed
+/// * Rate limited
+#[update]
+pub async fn apply_fact_check(
+    post_id: PostId,
+    verdict: FactCheckVerdict,
+    evidence_url: String,
+) -> Result<(), String> {
+    let checker_id = authenticate_user()?;
+    validate_evidence_url(&evidence_url).map_err(invalid("apply_fact_check"))?;
+    check_rate_limit(&checker_id, "apply_fact_check", 30, 3600)?;
+
+    with_state_mut(|state| {
+        if !can_fact_check(state, checker_id) {
+            return Err("Only appointed fact-checkers can label posts".to_string());
+        }
+        let post = state.posts.get(&post_id).ok_or("Post not found")?;
+        if !matches!(post.visibility, PostVisibility::Public) {
+            return Err("Only public posts can be fact-checked".to_string());
+        }
+        if post.author_id == checker_id {
+            return Err("You cannot fact-check your own post".to_string());
+        }
+        let author_id = post.author_id;
+
+        let label = FactCheckLabel {
+            post_id,
+            checker: checker_id,
+            verdict,
+            evidence_url,
+            applied_at: time(),
+            appeal: None,
+        };
+        let labels = state.fact_checks.entry(post_id).or_default();
+        match labels.iter_mut().find(|label| label.checker == checker_id) {
+            Some(existing) if !existing.in_force() => {
+                return Err("This label was overturned on appeal".to_string());
+            }
+            Some(existing)
+                if existing
+                    .appeal
+                    .as_ref()
+                    .is_some_and(|appeal| appeal.status == AppealStatus::Pending) =>
+            {
+                return Err("This label is under appeal".to_string());
+            }
+            Some(existing) => *existing = label,
+            None => labels.push(label),
+        }
+        state
+            .fact_checked_posts
+            .entry(author_id)
+            .or_default()
+            .insert(post_id);
+        Ok(())
+    })
+}
+
+/// Withdraws the caller's fact-check label from a post
+#[update]
+pub async fn withdraw_fact_check(post_id: PostId) -> Result<(), String> {
+    let checker_id = authenticate_user()?;
+
+    with_state_mut(|state| {
+        let labels = state
+            .fact_checks
+            .get_mut(&post_id)
+            .ok_or("Label not found")?;
+        let before = labels.len();
+        labels.retain(|label| label.checker != checker_id);
+        if labels.len() == before {
+            return Err("Label not found".to_string());
+        }
+
+        if labels.is_empty() {
+            state.fact_checks.remove(&post_id);
+            if let Some(author_id) = state.posts.get(&post_id).map(|post| post.author_id) {
+                if let Some(post_ids) = state.fact_checked_posts.get_mut(&author_id) {
+                    post_ids.remove(&post_id);
+                }
+            }
+        }
+        Ok(())
+    })
+}
+
+/// Appeals a fact-check label on one of the caller's posts
+///
+/// # Arguments
+/// * `post_id` - The labelled post
+/// * `checker` - Fact-checker whose label is appealed
+/// * `reason` - Why the label is wrong (1-1,000 characters)
+///
+/// # Security
+/// * Post author only; each label can be appealed once
+/// * Decided by a moderator other than the fact-checker
+#[update]
+pub async fn appeal_fact_check(
+    post_id: PostId,
+    checker: Principal,
+    reason: String,
+) -> Result<(), String> {
+    let user_id = authenticate_user()?;
+    validate_appeal_reason(&reason).map_err(invalid("appeal_fact_check"))?;
+
+    with_state_mut(|state| {
+        let post = state.posts.get(&post_id).ok_or("Post not found")?;
+        if post.author_id != user_id {
+            return Err("Only the post's author can appeal its labels".to_string());
+        }
+        let label = state
+            .fact_checks
+            .get_mut(&post_id)
+            .and_then(|labels| labels.iter_mut().find(|label| label.checker.0 == checker))
+            .ok_or("Label not found")?;
+        if label.appeal.is_some() {
+            return Err("This label has already been appealed".to_string());
+        }
+
+        label.appeal = Some(FactCheckAppeal {
+            reason,
+            filed_at: time(),
+            status: AppealStatus::Pending,
+            resolved_by: None,
+            resolved_at: None,
+        });
+        Ok(())
+    })
+}
+
+/// Gets fact-check labels on the caller's posts, newest posts first
+///
+/// Includes labels overturned on appeal, which are no longer shown on the posts.
+#[query]
+pub fn get_my_fact_checks(
+    limit: Option<usize>,
+    offset: Option<usize>,
+) -> Result<FactChecksPage, String> {
+    let user_id = authenticate_for(AppScope::ReadOnly)?;
+    let limit = limit
+        .unwrap_or(DEFAULT_CONNECTIONS_LIMIT)
+        .min(MAX_CONNECTIONS_LIMIT);
+    let offset = offset.unwrap_or(0);
+
+    Ok(with_state(|state| {
+        let labels = state
+            .fact_checked_posts
+            .get(&user_id)
+            .into_iter()
+            .flat_map(|post_ids| post_ids.iter().rev())
+            .filter_map(|post_id| state.fact_checks.get(post_id))
+            .flatten();
+        let (page, next_cursor) = page_at(labels, offset, limit);
+        FactChecksPage {
+            labels: page.into_iter().cloned().collect(),
+            total: None,
+            has_more: next_cursor.is_some(),
+            next_cursor,
+        }
+    }))
+}
+
+/// Gets the review queue of labels with a pending appeal, oldest posts first
+///
+/// # Security
+/// * Moderators and admins only
+#[query]
+pub fn get_pending_fact_check_appeals(
+    limit: Option<usize>,
+    offset: Option<usize>,
+) -> Result<FactChecksPage, String> {
+    let user_id = authenticate_for(AppScope::Moderate)?;
+    require_moderator(&user_id)?;
+
+    let limit = limit
+        .unwrap_or(DEFAULT_CONNECTIONS_LIMIT)
+        .min(MAX_CONNECTIONS_LIMIT);
+    let offset = offset.unwrap_or(0);
+
+    Ok(with_state(|state| {
+        let pending = state.fact_checks.values().flatten().filter(|label| {
+            label
+                .appeal
+                .as_ref()
+                .is_some_and(|appeal| appeal.status == AppealStatus::Pending)
+        });
+        let (page, next_cursor) = page_at(pending, offset, limit);
+        FactChecksPage {
+            labels: page.into_iter().cloned().collect(),
+            total: None,
+            has_more: next_cursor.is_some(),
+            next_cursor,
+        }
+    }))
+}
+
+/// Decides a pending appeal against a fact-check label
+///
+/// # Arguments
+/// * `post_id` - The labelled post
+/// * `checker` - Fact-checker whose label was appealed
+/// * `uphold` - Keep the label (true) or withdraw it from the post (false)
+///
+/// # Security
+/// * Moderators and admins only, other than the fact-checker
+/// * Recorded in the audit log
+#[update]
+pub async fn resolve_fact_check_appeal(
+    post_id: PostId,
+    checker: Principal,
+    uphold: bool,
+) -> Result<(), String> {
+    let moderator_id = authenticate_for(AppScope::Moderate)?;
+    require_moderator(&moderator_id)?;
+    if moderator_id.0 == checker {
+        return Err("Fact-checkers cannot decide appeals against their own labels".to_string());
+    }
+
+    with_state_mut(|state| {
+        let author_id = state
+            .posts
+            .get(&post_id)
+            .map(|post| post.author_id)
+            .ok_or("Post not found")?;
+        let appeal = state
+            .fact_checks
+            .get_mut(&post_id)
+            .and_then(|labels| labels.iter_mut().find(|label| label.checker.0 == checker))
+            .and_then(|label| label.appeal.as_mut())
+            .filter(|appeal| appeal.status == AppealStatus::Pending)
+            .ok_or("No pending appeal against this label")?;
+
+        appeal.status = if uphold {
+            AppealStatus::Upheld
+        } else {
+            AppealStatus::Overturned
+        };
+        appeal.resolved_by = Some(moderator_id);
+        appeal.resolved_at = Some(time());
+        let status = appeal.status;
+
+        record_audit_event(
+            state,
+            moderator_id,
+            AuditAction::FactCheckAppealResolved,
+            Some(author_id),
+            format!("post {} label by {checker}: {status:?}", post_id.0),
+        );
+        Ok(())
+    })
+}
+
+/// Internal function to check whether a verification status may fact-check
+fn is_fact_check_status(status: &VerificationStatus) -> bool {
+    matches!(
+        status,
+        VerificationStatus::Organization | VerificationStatus::Journalist
+    )
+}
+
+/// Internal function to check that a user is an appointed fact-checker who is
+/// still verified
+fn can_fact_check(state: &SocialNetworkState, user_id: UserId) -> bool {
+    state.fact_checkers.contains(&user_id)
+        && state
+            .users
+            .get(&user_id)
+            .is_some_and(|profile| is_fact_check_status(&profile.verification_status))
+}
+
+// ============================================================================
+// PERMANENT EXPORT
+// ============================================================================
+
+/// Configures the permanence service public posts are exported to
+///
+/// # Behavior
+/// - Every `EXPORT_INTERVAL_SECONDS`, up to `MAX_EXPORT_BATCH` queued posts of
+///   authors who opted in are signed and uploaded, and the returned identifier
+///   and bundle hash are recorded on the post
+/// - `None` stops exporting; queued posts wait for a new configuration
+///
+/// # Security
+/// * Admins only; the API key and private key are never returned by any endpoint
+/// * Recorded in the audit log (endpoint only)
+#[update]
+pub async fn set_export_config(config: Option<ExportConfig>) -> Result<(), String> {
+    let admin_id = authenticate_user()?;
+    require_admin(&admin_id)?;
+
+    if let Some(config) = &config {
+        if !config.endpoint.starts_with("https://")
+            || config.endpoint.len() > MAX_OUTCALL_ENDPOINT_LENGTH
+        {
+            return Err(format!(
+                "Endpoint must be an https URL of at most {MAX_OUTCALL_ENDPOINT_LENGTH} characters"
+            ));
+        }
+        let private_key = rsa::RsaPrivateKey::from_pem(&config.private_key_pem)?;
+        if private_key.public_key() != rsa::RsaPublicKey::from_pem(&config.public_key_pem)? {
+            return Err("Public key does not match the private key".to_string());
+        }
+    }
+
+    with_state_mut(|state| {
+        let details = match &config {
+            Some(config) => config.endpoint.clone(),
+            None => "disabled".to_string(),
+        };
+        state.export_config = config;
+        record_audit_event(
+            state,
+            admin_id,
+            AuditAction::ExportConfigured,
+            None,
+            details,
+        );
+    });
+    Ok(())
+}
+
+/// Gets the public key (PEM) that verifies exported bundles, if exporting is enabled
+#[query]
+pub fn get_export_public_key() -> Option<String> {
+    with_state(|state| {
+        state
+            .export_config
+            .as_ref()
+            .map(|config| config.public_key_pem.clone())
+    })
+}
+
+/// Opts the caller in or out of exporting their public posts off the IC
+///
+/// Opting in queues every public, ungated post not exported yet; later ones
+/// are queued as they are created. Copies already exported are permanent and
+/// stay listed on the posts after opting out.
+///
+/// # Returns
+/// * `Ok(u64)` - Number of the caller's posts waiting to be exported
+#[update]
+pub async fn set_permanent_export(enabled: bool) -> Result<u64, String> {
+    let user_id = authenticate_user()?;
+
+    with_state_mut(|state| {
+        let post_ids = state.user_posts.get(&user_id).cloned().unwrap_or_default();
+        if enabled {
+            state.export_authors.insert(user_id);
+            for post_id in &post_ids {
+                let exportable = state
+                    .posts
+                    .get(post_id)
+                    .is_some_and(|post| post.permanent_copy.is_none() && post.is_syndicated())
+                    && !is_archived(state, *post_id);
+                if exportable {
+                    state.pending_exports.insert(*post_id);
+                }
+            }
+        } else {
+            state.export_authors.remove(&user_id);
+            for post_id in &post_ids {
+                state.pending_exports.remove(post_id);
+            }
+        }
+
+        Ok(post_ids
+            .iter()
+            .filter(|post_id| state.pending_exports.contains(post_id))
+            .count() as u64)
+    })
+}
+
+/// Gets whether the caller exports their public posts off the IC
+#[query]
+pub fn get_my_permanent_export() -> Result<bool, String> {
+    let user_id = authenticate_user()?;
+    Ok(with_state(|state| state.export_authors.contains(&user_id)))
+}
+
+/// Internal function to export one batch of queued posts
+///
+/// Posts that stopped qualifying (made private, gated, archived, or whose
+/// author opted out) are dropped from the queue; failed uploads are retried on
+/// the next run.
+async fn export_public_posts() {
+    use sha2::{Digest, Sha256};
+
+    let Some(config) = with_state(|state| state.export_config.clone()) else {
+        return;
+    };
+    if is_low_on_cycles() {
+        return;
+    }
+    let canister_id = ic_cdk::id();
+
+    let batch: Vec<(PostId, Vec<u8>)> = with_state_mut(|state| {
+        let post_ids: Vec<PostId> = state
+            .pending_exports
+            .iter()
+            .take(MAX_EXPORT_BATCH)
+            .copied()
+            .collect();
+        post_ids
+            .into_iter()
+            .filter_map(|post_id| {
+                state.pending_exports.remove(&post_id);
+                let post = state.posts.get(&post_id)?;
+                let exportable = post.permanent_copy.is_none()
+                    && post.is_syndicated()
+                    && state.export_authors.contains(&post.author_id)
+                    && !is_archived(state, post_id);
+                exportable.then(|| {
+                    let post = inflated_post(state, post);
+                    (post_id, permanence::payload(&post, canister_id))
+                })
+            })
+            .collect()
+    });
+
+    let mut exported = 0;
+    for (post_id, payload) in batch {
+        let result = match permanence::signed_bundle(&payload, &config.private_key_pem) {
+            Ok(bundle) => {
+                let bundle_hash = Sha256::digest(&bundle).to_vec();
+                permanence::upload(&config, bundle)
+                    .await
+                    .map(|locator| (locator, bundle_hash))
+            }
+            Err(error) => Err(error),
+        };
+
+        with_state_mut(|state| match result {
+            Ok((locator, bundle_hash)) => {
+                if let Some(post) = state.posts.get_mut(&post_id) {
+                    post.permanent_copy = Some(PermanentCopy {
+                        locator,
+                        bundle_hash,
+                        exported_at: time(),
+                    });
+                    exported += 1;
+                }
+            }
+            Err(error) => {
+                state.pending_exports.insert(post_id);
+                log_event(
+                    state,
+                    LogLevel::Warn,
+                    LogCategory::System,
+                    None,
+                    format!("Exporting post {} failed: {error}", post_id.0),
+                );
+            }
+        });
+    }
+
+    if exported > 0 {
+        with_state_mut(|state| {
+            log_event(
+                state,
+                LogLevel::Info,
+                LogCategory::System,
+                None,
+                format!("Exported {exported} posts to {}", config.endpoint),
+            );
+        });
+    }
+}
+
+// ============================================================================
+// PRIVATE NOTES
+// ============================================================================
+
+/// Returns the vetKD public key used to verify private notes keys
+#[update]
+pub async fn get_notes_public_key() -> Result<Vec<u8>, String> {
+    authenticate_user()?;
+    vetkd::notes_public_key(vetkd_key_name()).await
+}
+
+/// Derives the caller's key for encrypting their private notes
+///
+/// # Arguments
+/// * `transport_public_key` - Caller's ephemeral transport public key
+///
+/// # Returns
+/// * `Ok(Vec<u8>)` - Notes key encrypted under the transport key
+/// * `Err(String)` - Key derivation failed
+///
+/// # Security
+/// * Only the caller can derive their own notes key
+/// * Rate limited since each derivation costs cycles
+#[update]
+pub async fn get_notes_key(transport_public_key: Vec<u8>) -> Result<Vec<u8>, String> {
+    let user_id = authenticate_user()?;
+    check_rate_limit(&user_id, "get_notes_key", 20, 3_600)?; // 20 derivations per hour
+
+    vetkd::derive_notes_key(
+        vetkd_key_name(),
+        vetkd::notes_key_input(&user_id),
+        transport_public_key,
+    )
+    .await
+}
+
+/// Stores a new encrypted note-to-self
+///
+/// # Arguments
+/// * `ciphertext` - Note encrypted client-side (1-65,536 bytes)
+///
+/// # Security
+/// * Notes are only ever returned to their owner and are never posted
+/// * At most MAX_NOTES_PER_USER notes per user
+/// * Delegated applications cannot read or write notes
+#[update]
+pub async fn create_note(ciphertext: Vec<u8>) -> Result<PrivateNote, String> {
+    let user_id = authenticate_user()?;
+    validate_note_ciphertext(&ciphertext).map_err(invalid("create_note"))?;
+    check_rate_limit(&user_id, "create_note", 60, 3_600)?;
+
+    with_state_mut(|state| {
+        if state
+            .private_notes
+            .get(&user_id)
+            .is_some_and(|notes| notes.len() >= MAX_NOTES_PER_USER)
+        {
+            return Err(format!("At most {MAX_NOTES_PER_USER} notes allowed"));
+        }
+
+        let now = time();
+        let note = PrivateNote {
+            id: state.next_note_id,
+            ciphertext,
+            created_at: now,
+            updated_at: now,
+        };
+        state.next_note_id = state.next_note_id.saturating_add(1);
+        state
+            .private_notes
+            .entry(user_id)
+            .or_default()
+            .insert(note.id, note.clone());
+        Ok(note)
+    })
+}
+
+/// Replaces the ciphertext of one of the caller's notes
+#[update]
+pub async fn update_note(note_id: u64, ciphertext: Vec<u8>) -> Result<PrivateNote, String> {
+    let user_id = authenticate_user()?;
+    validate_note_ciphertext(&ciphertext).map_err(invalid("update_note"))?;
+    check_rate_limit(&user_id, "update_note", 120, 3_600)?;
+
+    with_state_mut(|state| {
+        let note = state
+            .private_notes
+            .get_mut(&user_id)
+            .and_then(|notes| notes.get_mut(&note_id))
+            .ok_or("Note not found")?;
+        note.ciphertext = ciphertext;
+        note.updated_at = time();
+        Ok(note.clone())
+    })
+}
+
+/// Deletes one of the caller's notes
+#[update]
+pub async fn delete_note(note_id: u64) -> Result<(), String> {
+    let user_id = authenticate_user()?;
+
+    with_state_mut(|state| {
+        let notes = state
+            .private_notes
+            .get_mut(&user_id)
+            .ok_or("Note not found")?;
+        notes.remove(&note_id).ok_or("Note not found")?;
+        if notes.is_empty() {
+            state.private_notes.remove(&user_id);
+        }
+        Ok(())
+    })
+}
+
+/// Gets the caller's notes, most recently updated first
+#[query]
+pub fn get_my_notes(limit: Option<usize>, offset: Option<usize>) -> Result<NotesPage, String> {
+    let user_id = authenticate_user()?;
+    let limit = limit
+        .unwrap_or(DEFAULT_CONNECTIONS_LIMIT)
+        .min(MAX_CONNECTIONS_LIMIT);
+    let offset = offset.unwrap_or(0);
+
+    Ok(with_state(|state| {
+        let mut notes: Vec<&PrivateNote> = state
+            .private_notes
+            .get(&user_id)
+            .into_iter()
+            .flat_map(|notes| notes.values())
+            .collect();
+        notes.sort_by(|a, b| b.updated_at.cmp(&a.updated_at).then(b.id.cmp(&a.id)));
+        let total = notes.len() as u64;
+
+        let (page, next_cursor) = page_at(notes.into_iter(), offset, limit);
+        NotesPage {
+            notes: page.into_iter().cloned().collect(),
+            total: Some(total),
+            has_more: next_cursor.is_some(),
+            next_cursor,
+        }
+    }))
+}
+
+// ============================================================================
+// TIME CAPSULES
+// ============================================================================
+
+/// Seals a post until `reveal_at`, when it is published automatically
+///
+/// Until then the content is withheld from every endpoint. Rescheduling the
+/// reveal before it passes works as a dead man's switch.
+///
+/// # Arguments
+/// * `content` - Post content (1-10,000 characters)
+/// * `visibility` - Who can see the post once published (Public, FollowersOnly, Unlisted)
+/// * `reveal_at` - Publication time (nanoseconds), at most MAX_TIME_CAPSULE_DAYS ahead
+///
+/// # Returns
+/// * `Ok(TimeCapsule)` - The sealed capsule, without its content
+/// * `Err(String)` - Validation error or too many sealed capsules
+///
+/// # Security
+/// * At most MAX_TIME_CAPSULES_PER_USER sealed capsules per user
+/// * Link-heavy content is held to the `post_links` gate, as for posts
+/// * Rate limited
+#[update]
+pub async fn create_time_capsule(
+    content: String,
+    visibility: Option<PostVisibility>,
+    reveal_at: u64,
+) -> Result<TimeCapsule, String> {
+    let user_id = authenticate_user()?;
+    validate_post_content(&content).map_err(invalid("create_time_capsule"))?;
+    validate_reveal_at(reveal_at, time()).map_err(invalid("create_time_capsule"))?;
+    check_link_gate(user_id, &content)?;
+    check_rate_limit(&user_id, "create_time_capsule", 10, 3_600)?;
+    ensure_user_profile(user_id).await?;
+
+    with_state_mut(|state| {
+        let sealed = state
+            .user_time_capsules
+            .get(&user_id)
+            .into_iter()
+            .flatten()
+            .filter(|id| state.sealed_contents.contains_key(id))
+            .count();
+        if sealed >= MAX_TIME_CAPSULES_PER_USER {
+            return Err(format!(
+                "At most {MAX_TIME_CAPSULES_PER_USER} sealed time capsules allowed"
+            ));
+        }
+
+        let capsule = TimeCapsule {
+            id: state.next_time_capsule_id,
+            author: user_id,
+            visibility: visibility.unwrap_or(PostVisibility::Public),
+            created_at: time(),
+            reveal_at,
+            published_post_id: None,
+        };
+        state.next_time_capsule_id = state.next_time_capsule_id.saturating_add(1);
+        state.sealed_contents.insert(capsule.id, content);
+        state.time_capsule_reveals.insert((reveal_at, capsule.id));
+        state
+            .user_time_capsules
+            .entry(user_id)
+            .or_default()
+            .insert(capsule.id);
+        state.time_capsules.insert(capsule.id, capsule.clone());
+        Ok(capsule)
+    })
+}
+
+/// Moves the reveal time of one of the caller's sealed time capsules
+///
+/// # Arguments
+/// * `capsule_id` - A sealed capsule of the caller
+/// * `reveal_at` - New publication time, at most MAX_TIME_CAPSULE_DAYS ahead
+#[update]
+pub async fn reschedule_time_capsule(
+    capsule_id: u64,
+    reveal_at: u64,
+) -> Result<TimeCapsule, String> {
+    let user_id = authenticate_user()?;
+    validate_reveal_at(reveal_at, time()).map_err(invalid("reschedule_time_capsule"))?;
+
+    with_state_mut(|state| {
+        let capsule = sealed_capsule_mut(state, capsule_id, user_id)?;
+        let previous = std::mem::replace(&mut capsule.reveal_at, reveal_at);
+        let capsule = capsule.clone();
+        state.time_capsule_reveals.remove(&(previous, capsule_id));
+        state.time_capsule_reveals.insert((reveal_at, capsule_id));
+        Ok(capsule)
+    })
+}
+
+/// Cancels one of the caller's sealed time capsules, discarding its content
+#[update]
+pub async fn cancel_time_capsule(capsule_id: u64) -> Result<(), String> {
+    let user_id = authenticate_user()?;
+
+    with_state_mut(|state| {
+        let reveal_at = sealed_capsule_mut(state, capsule_id, user_id)?.reveal_at;
+        state.time_capsules.remove(&capsule_id);
+        state.sealed_contents.remove(&capsule_id);
+        state.time_capsule_reveals.remove(&(reveal_at, capsule_id));
+        if let Some(ids) = state.user_time_capsules.get_mut(&user_id) {
+            ids.remove(&capsule_id);
+        }
+        Ok(())
+    })
+}
+
+/// Gets the caller's time capsules, sealed and published, newest first
+///
+/// Sealed content is not included.
+#[query]
+pub fn get_my_time_capsules() -> Result<Vec<TimeCapsule>, String> {
+    let user_id = authenticate_user()?;
+    Ok(with_state(|state| {
+        state
+            .user_time_capsules
+            .get(&user_id)
+            .into_iter()
+            .flat_map(|ids| ids.iter().rev())
+            .filter_map(|id| state.time_capsules.get(id))
+            .cloned()
+            .collect()
+    }))
+}
+
+/// Internal function to look up a capsule the user sealed and that is not yet published
+fn sealed_capsule_mut(
+    state: &mut SocialNetworkState,
+    capsule_id: u64,
+    user_id: UserId,
+) -> Result<&mut TimeCapsule, String> {
+    let capsule = state
+        .time_capsules
+        .get_mut(&capsule_id)
+        .filter(|capsule| capsule.author == user_id)
+        .ok_or("Time capsule not found")?;
+    if capsule.published_post_id.is_some() {
+        return Err("Time capsule was already revealed".to_string());
+    }
+    Ok(capsule)
+}
+
+/// Internal function to publish every time capsule whose reveal time passed
+///
+/// Runs on a timer, so a capsule appears up to
+/// `TIME_CAPSULE_CHECK_INTERVAL_SECONDS` after its reveal time.
+fn reveal_time_capsules() {
+    let now = time();
+    let post_ids: Vec<PostId> = with_state_mut(|state| {
+        let due: Vec<(u64, u64)> = state
+            .time_capsule_reveals
+            .range(..=(now, u64::MAX))
+            .copied()
+            .collect();
+
+        due.into_iter()
+            .filter_map(|key| {
+                state.time_capsule_reveals.remove(&key);
+                let content = state.sealed_contents.remove(&key.1)?;
+                let capsule = state.time_capsules.get(&key.1)?.clone();
+                let post_id = insert_post(
+                    state,
+                    capsule.author,
+                    content,
+                    capsule.visibility,
+                    None,
+                    TextFormat::Plain,
+                    PostExtras::default(),
+                );
+                if let Some(capsule) = state.time_capsules.get_mut(&key.1) {
+                    capsule.published_post_id = Some(post_id);
+                }
+                Some(post_id)
+            })
+            .collect()
+    });
+
+    for post_id in post_ids {
+        federate_post(post_id);
+        check_post_links(post_id);
+    }
+}
+
+// ============================================================================
+// POLLS
+// ============================================================================
+
+/// Attaches a poll to one of the caller's posts
+///
+/// # Arguments
+/// * `options` - MIN_POLL_OPTIONS-MAX_POLL_OPTIONS distinct options (ranked polls need three)
+/// * `mode` - Single choice, multi-select with a choice limit, or ranked choice
+/// * `closes_at` - When voting ends (at most MAX_POLL_DAYS away)
+///
+/// # Returns
+/// * `Ok(Poll)` - The open poll
+/// * `Err(String)` - Validation error, or the post already has a poll
+#[update]
+pub async fn start_poll(
+    post_id: PostId,
+    options: Vec<String>,
+    mode: PollMode,
+    closes_at: u64,
+) -> Result<Poll, String> {
+    let user_id = authenticate_for(AppScope::Post)?;
+    let now = time();
+    validate_poll(&options, mode).map_err(invalid("start_poll"))?;
+    if closes_at <= now || closes_at > now + MAX_POLL_DAYS * NANOS_PER_DAY {
+        return Err(format!("Polls must close within {MAX_POLL_DAYS} days"));
+    }
+    check_rate_limit(&user_id, "start_poll", 10, 3_600)?;
+
+    with_state_mut(|state| {
+        let post = state.posts.get_mut(&post_id).ok_or("Post not found")?;
+        if post.author_id != user_id {
+            return Err("Only the author can start a poll".to_string());
+        }
+        if post.poll.is_some() {
+            return Err("Post already has a poll".to_string());
+        }
+
+        let poll = Poll {
+            options,
+            mode,
+            closes_at,
+            voter_count: 0,
+        };
+        post.poll = Some(poll.clone());
+        post.updated_at = now;
+        Ok(poll)
+    })
+}
+
+/// Casts the caller's ballot in a poll
+///
+/// # Arguments
+/// * `choices` - Option indices; one for single-choice polls, up to
+///   `max_choices` for multi-select, and in order of preference for ranked polls
+///
+/// # Behavior
+/// - Voters can replace their ballot until the poll closes
+#[update]
+pub async fn vote_in_poll(post_id: PostId, choices: Vec<u32>) -> Result<Poll, String> {
+    let user_id = authenticate_for(AppScope::Post)?;
+    check_rate_limit(&user_id, "vote_in_poll", 30, 60)?;
+
+    let (option_count, mode) = with_state(|state| {
+        let post = state
+            .posts
+            .get(&post_id)
+            .filter(|post| can_view_post(state, Some(user_id), post))
+            .ok_or("Post not found")?;
+        if is_blocked_between(state, post.author_id, user_id) {
+            return Err("Interaction not allowed".to_string());
+        }
+        let poll = post.poll.as_ref().ok_or("Post has no poll")?;
+        if time() >= poll.closes_at {
+            return Err("Poll is closed".to_string());
+        }
+        Ok((poll.options.len(), poll.mode))
+    })?;
+    // Validated outside the state borrow: `invalid` records the error in state
+    validate_ballot(&choices, option_count, mode).map_err(invalid("vote_in_poll"))?;
+
+    with_state_mut(|state| {
+        let is_new = state
+            .poll_ballots
+            .entry(post_id)
+            .or_default()
+            .insert(user_id, choices)
+            .is_none();
+        let poll = state
+            .posts
+            .get_mut(&post_id)
+            .and_then(|post| post.poll.as_mut())
+            .ok_or("Post has no poll")?;
+        if is_new {
+            poll.voter_count = poll.voter_count.saturating_add(1);
+        }
+        Ok(poll.clone())
+    })
+}
+
+/// Gets a poll's tally, counted on every call
+///
+/// # Returns
+/// * `Ok(PollResults)` - Votes per option and the leading option; ranked polls
+///   also list each instant-runoff round
+/// * `Err(String)` - Post not found or has no poll
+#[query]
+pub fn get_poll_results(post_id: PostId) -> Result<PollResults, String> {
+    let viewer = current_viewer();
+    with_state(|state| {
+        let post = state
+            .posts
+            .get(&post_id)
+            .filter(|post| can_view_post(state, viewer, post))
+            .ok_or("Post not found")?;
+        let poll = post.poll.clone().ok_or("Post has no poll")?;
+        let ballots = state.poll_ballots.get(&post_id);
+        let tally = polls::tally(
+            poll.mode,
+            poll.options.len(),
+            ballots
+                .into_iter()
+                .flat_map(|ballots| ballots.values())
+                .map(Vec::as_slice),
+        );
+
+        Ok(PollResults {
+            post_id,
+            closed: time() >= poll.closes_at,
+            votes: tally.votes,
+            rounds: tally.rounds,
+            winner: tally.winner,
+            my_ballot: viewer
+                .and_then(|viewer_id| ballots.and_then(|ballots| ballots.get(&viewer_id)))
+                .cloned(),
+            poll,
+        })
+    })
+}
+
+// ============================================================================
+// LIVE SESSIONS
+// ============================================================================
+
+/// Schedules a live session hosted by the caller
+///
+/// The audio or video stays on the external service behind `stream_url`; the
+/// canister only makes the session discoverable and collects RSVPs.
+///
+/// # Arguments
+/// * `title` - Session title (1-100 characters)
+/// * `scheduled_at` - Start time (nanoseconds), at most MAX_SESSION_SCHEDULE_DAYS ahead
+/// * `co_hosts` - Up to MAX_SESSION_CO_HOSTS other users hosting alongside the caller
+/// * `stream_url` - https link to the stream, which can be added later
+///
+/// # Behavior
+/// - Followers of the host and co-hosts see the session counted in
+///   `get_activity_summary` and listed by `get_upcoming_sessions`
+/// - At most MAX_UPCOMING_SESSIONS_PER_USER upcoming sessions per host
+#[update]
+pub async fn schedule_live_session(
+    title: String,
+    scheduled_at: u64,
+    co_hosts: Vec<UserId>,
+    stream_url: Option<String>,
+) -> Result<LiveSession, String> {
+    let user_id = authenticate_user()?;
+    let now = time();
+    validate_live_session(&title, scheduled_at, stream_url.as_deref(), now)
+        .map_err(invalid("schedule_live_session"))?;
+    check_rate_limit(&user_id, "schedule_live_session", 10, 3_600)?;
+    ensure_user_profile(user_id).await?;
+
+    with_state_mut(|state| {
+        let listed_since = live_session_listing_start(now);
+        let upcoming = state
+            .live_sessions
+            .values()
+            .filter(|session| session.host == user_id && session.scheduled_at >= listed_since)
+            .count();
+        if upcoming >= MAX_UPCOMING_SESSIONS_PER_USER {
+            return Err(format!(
+                "You can host at most {MAX_UPCOMING_SESSIONS_PER_USER} upcoming sessions"
+            ));
+        }
+        let co_hosts = live_session_co_hosts(state, user_id, co_hosts)?;
+
+        let session = LiveSession {
+            id: state.next_live_session_id,
+            host: user_id,
+            co_hosts,
+            title,
+            scheduled_at,
+            stream_url,
+            created_at: now,
+            rsvp_count: 0,
+            rsvped: false,
+        };
+        state.next_live_session_id += 1;
+        index_live_session(state, &session);
+        state.live_sessions.insert(session.id, session.clone());
+        Ok(session)
+    })
+}
+
+/// Updates the title, time, co-hosts or stream link of a session the caller hosts
+///
+/// RSVPs carry over to the new time.
+#[update]
+pub async fn update_live_session(
+    session_id: u64,
+    title: String,
+    scheduled_at: u64,
+    co_hosts: Vec<UserId>,
+    stream_url: Option<String>,
+) -> Result<LiveSession, String> {
+    let user_id = authenticate_user()?;
+    let now = time();
+    validate_live_session(&title, scheduled_at, stream_url.as_deref(), now)
+        .map_err(invalid("update_live_session"))?;
+
+    with_state_mut(|state| {
+        let co_hosts = live_session_co_hosts(state, user_id, co_hosts)?;
+        let session = hosted_live_session(state, session_id, user_id)?;
+        unindex_live_session(state, &session);
+
+        let session = LiveSession {
+            title,
+            scheduled_at,
+            co_hosts,
+            stream_url,
+            ..session
+        };
+        index_live_session(state, &session);
+        state.live_sessions.insert(session_id, session.clone());
+        Ok(session)
+    })
+}
+
+/// Cancels a session the caller hosts, dropping its RSVPs
+#[update]
+pub async fn cancel_live_session(session_id: u64) -> Result<(), String> {
+    let user_id = authenticate_user()?;
+
+    with_state_mut(|state| {
+        let session = hosted_live_session(state, session_id, user_id)?;
+        unindex_live_session(state, &session);
+        state.live_sessions.remove(&session_id);
+        state.live_session_rsvps.remove(&session_id);
+        Ok(())
+    })
+}
+
+/// Adds or withdraws the caller's RSVP to a live session
+///
+/// # Arguments
+/// * `attending` - `true` to RSVP, `false` to withdraw
+#[update]
+pub async fn rsvp_live_session(session_id: u64, attending: bool) -> Result<LiveSession, String> {
+    let user_id = authenticate_user()?;
+    check_rate_limit(&user_id, "rsvp_live_session", 30, 60)?;
+    let now = time();
+
+    with_state_mut(|state| {
+        let session = state
+            .live_sessions
+            .get(&session_id)
+            .filter(|session| !is_blocked_between(state, session.host, user_id))
+            .ok_or("Session not found")?;
+        if session.scheduled_at < live_session_listing_start(now) {
+            return Err("Session has ended".to_string());
+        }
+
+        let rsvps = state.live_session_rsvps.entry(session_id).or_default();
+        let changed = if attending {
+            rsvps.insert(user_id)
+        } else {
+            rsvps.remove(&user_id)
+        };
+        if rsvps.is_empty() {
+            state.live_session_rsvps.remove(&session_id);
+        }
+
+        let session = state
+            .live_sessions
+            .get_mut(&session_id)
+            .ok_or("Session not found")?;
+        if changed {
+            session.rsvp_count = if attending {
+                session.rsvp_count.saturating_add(1)
+            } else {
+                session.rsvp_count.saturating_sub(1)
+            };
+        }
+        Ok(LiveSession {
+            rsvped: attending,
+            ..session.clone()
+        })
+    })
+}
+
+/// Lists live sessions that are upcoming or may still be live, soonest first
+///
+/// # Arguments
+/// * `host` - Only sessions this user hosts or co-hosts
+///
+/// # Behavior
+/// - Without `host`, signed-in viewers get sessions involving users they
+///   follow, their own and those they RSVPed to; anonymous viewers get every
+///   upcoming session
+/// - Sessions stay listed LIVE_SESSION_LISTING_SECONDS past their start
+/// - Sessions of hosts blocked by or blocking the viewer are left out
+#[query]
+pub fn get_upcoming_sessions(
+    host: Option<UserId>,
+    limit: Option<usize>,
+    offset: Option<usize>,
+) -> LiveSessionsPage {
+    let viewer = current_viewer();
+    let limit = limit
+        .unwrap_or(DEFAULT_CONNECTIONS_LIMIT)
+        .min(MAX_CONNECTIONS_LIMIT);
+    let offset = offset.unwrap_or(0);
+    let listed_since = live_session_listing_start(time());
+
+    with_state(|state| {
+        let involves = |session: &LiveSession, user_id: UserId| {
+            session.host == user_id || session.co_hosts.contains(&user_id)
+        };
+        let sessions: Vec<&LiveSession> = state
+            .live_session_schedule
+            .range((listed_since, 0)..)
+            .filter_map(|(_, id)| state.live_sessions.get(id))
+            .filter(|session| match (host, viewer) {
+                (Some(host_id), _) => involves(session, host_id),
+                (None, Some(viewer_id)) => {
+                    involves(session, viewer_id)
+                        || has_rsvped(state, session.id, viewer_id)
+                        || std::iter::once(&session.host)
+                            .chain(&session.co_hosts)
+                            .any(|user_id| is_follower_of(state, viewer_id, *user_id))
+                }
+                (None, None) => true,
+            })
+            .filter(|session| {
+                viewer.is_none_or(|viewer_id| !is_blocked_between(state, session.host, viewer_id))
+            })
+            .collect();
+        let total = sessions.len() as u64;
+
+        let (page, next_cursor) = page_at(sessions.into_iter(), offset, limit);
+        LiveSessionsPage {
+            sessions: page
+                .into_iter()
+                .map(|session| live_session_for_viewer(state, session, viewer))
+                .collect(),
+            total: Some(total),
+            has_more: next_cursor.is_some(),
+            next_cursor,
+        }
+    })
+}
+
+/// Internal function to check a live session's title, start time and stream link
+fn validate_live_session(
+    title: &str,
+    scheduled_at: u64,
+    stream_url: Option<&str>,
+    now: u64,
+) -> Result<(), String> {
+    validate_session_title(title)?;
+    if scheduled_at <= now || scheduled_at - now > MAX_SESSION_SCHEDULE_DAYS * NANOS_PER_DAY {
+        return Err(format!(
+            "Sessions must start within the next {MAX_SESSION_SCHEDULE_DAYS} days"
+        ));
+    }
+    stream_url.map_or(Ok(()), validate_stream_url)
+}
+
+/// Internal function to compute the earliest start time of sessions still listed
+fn live_session_listing_start(now: u64) -> u64 {
+    now.saturating_sub(LIVE_SESSION_LISTING_SECONDS * 1_000_000_000)
+}
+
+/// Internal function to check the co-hosts a host invited
+///
+/// Duplicates are dropped; every co-host must have a profile and neither side
+/// may have blocked the other.
+fn live_session_co_hosts(
+    state: &SocialNetworkState,
+    host: UserId,
+    mut co_hosts: Vec<UserId>,
+) -> Result<Vec<UserId>, String> {
+    co_hosts.sort();
+    co_hosts.dedup();
+    if co_hosts.len() > MAX_SESSION_CO_HOSTS {
+        return Err(format!(
+            "A session can have at most {MAX_SESSION_CO_HOSTS} co-hosts"
+        ));
+    }
+    for co_host in &co_hosts {
+        if *co_host == host {
+            return Err("You cannot co-host your own session".to_string());
+        }
+        if !state.users.contains_key(co_host) || is_blocked_between(state, host, *co_host) {
+            return Err("Co-host not found".to_string());
+        }
+    }
+    Ok(co_hosts)
+}
+
+/// Internal function to look up a session the user hosts
+fn hosted_live_session(
+    state: &SocialNetworkState,
+    session_id: u64,
+    user_id: UserId,
+) -> Result<LiveSession, String> {
+    state
+        .live_sessions
+        .get(&session_id)
+        .filter(|session| session.host == user_id)
+        .cloned()
+        .ok_or_else(|| "Session not found".to_string())
+}
+
+/// Internal function to index a session by start time and by every host
+fn index_live_session(state: &mut SocialNetworkState, session: &LiveSession) {
+    state
+        .live_session_schedule
+        .insert((session.scheduled_at, session.id));
+    for user_id in std::iter::once(&session.host).chain(&session.co_hosts) {
+        state
+            .user_live_sessions
+            .entry(*user_id)
+            .or_default()
+            .insert(session.id);
+    }
+}
+
+/// Internal function to undo `index_live_session`
+fn unindex_live_session(state: &mut SocialNetworkState, session: &LiveSession) {
+    state
+        .live_session_schedule
+        .remove(&(session.scheduled_at, session.id));
+    for user_id in std::iter::once(&session.host).chain(&session.co_hosts) {
+        if let Some(ids) = state.user_live_sessions.get_mut(user_id) {
+            ids.remove(&session.id);
+            if ids.is_empty() {
+                state.user_live_sessions.remove(user_id);
+            }
+        }
+    }
+}
+
+/// Internal function to check whether the user RSVPed to a session
+fn has_rsvped(state: &SocialNetworkState, session_id: u64, user_id: UserId) -> bool {
+    state
+        .live_session_rsvps
+        .get(&session_id)
+        .is_some_and(|rsvps| rsvps.contains(&user_id))
+}
+
+/// Internal function to copy a session with the viewer's RSVP filled in
+fn live_session_for_viewer(
+    state: &SocialNetworkState,
+    session: &LiveSession,
+    viewer: Option<UserId>,
+) -> LiveSession {
+    LiveSession {
+        rsvped: viewer.is_some_and(|viewer_id| has_rsvped(state, session.id, viewer_id)),
+        ..session.clone()
+    }
+}
+
+// ============================================================================
+// AUDIO CLIPS
+// ============================================================================
+
+/// Starts a chunked upload of a voice note, discarding any unfinished one
+///
+/// # Arguments
+/// * `total_bytes` - Size of the clip (max MAX_AUDIO_CLIP_BYTES)
+///
+/// # Behavior
+/// - Send the data with `upload_audio_chunk`, then call `finish_audio_upload`
+/// - Each user has at most one upload in progress
+#[update]
+pub async fn begin_audio_upload(total_bytes: u64) -> Result<(), String> {
+    let user_id = authenticate_user()?;
+    check_rate_limit(&user_id, "begin_audio_upload", 10, 3_600)?;
+    require_cycles_headroom("Audio upload")?;
+
+    if total_bytes == 0 || total_bytes > MAX_AUDIO_CLIP_BYTES as u64 {
+        return Err(format!(
+            "Audio clips must be at most {} KB",
+            MAX_AUDIO_CLIP_BYTES / 1024
+        ));
+    }
+
+    with_state_mut(|state| {
+        state.audio_uploads.insert(
+            user_id,
+            AudioUpload {
+                total_bytes,
+                data: Vec::new(),
+            },
+        );
+    });
+    Ok(())
+}
+
+/// Appends a chunk (max MAX_UPLOAD_CHUNK_BYTES) to the caller's audio upload
+///
+/// # Returns
+/// * `Ok(u64)` - Bytes received so far
+#[update]
+pub async fn upload_audio_chunk(chunk: Vec<u8>) -> Result<u64, String> {
+    let user_id = authenticate_user()?;
+    if chunk.is_empty() || chunk.len() > MAX_UPLOAD_CHUNK_BYTES {
+        return Err(format!("Chunks must be 1-{MAX_UPLOAD_CHUNK_BYTES} bytes"));
+    }
+
+    with_state_mut(|state| {
+        let upload = state
+            .audio_uploads
+            .get_mut(&user_id)
+            .ok_or("No audio upload in progress")?;
+        if (upload.data.len() + chunk.len()) as u64 > upload.total_bytes {
+            return Err("Chunk exceeds the declared upload size".to_string());
+        }
+        upload.data.extend_from_slice(&chunk);
+        Ok(upload.data.len() as u64)
+    })
+}
+
+/// Completes the caller's audio upload and stores the clip
+///
+/// # Arguments
+/// * `duration_ms` - Clip duration (at most MAX_AUDIO_DURATION_SECONDS)
+/// * `waveform` - 1-MAX_WAVEFORM_SAMPLES peak amplitudes for rendering
+/// * `alt_text` - Description or transcript; required when the community rule or
+///   the caller's preference demands alt text
+///
+/// # Behavior
+/// - The clip is kept in stable memory until attached with `attach_audio_clip`;
+///   a clip finished earlier and never attached is deleted
+/// - The format (MP3, Ogg, WebM or WAV) is detected from the data, not trusted
+///   from the client
+#[update]
+pub async fn finish_audio_upload(
+    duration_ms: u32,
+    waveform: Vec<u8>,
+    alt_text: Option<String>,
+) -> Result<AudioClip, String> {
+    let user_id = authenticate_user()?;
+    validate_audio_metadata(duration_ms, &waveform).map_err(invalid("finish_audio_upload"))?;
+    match &alt_text {
+        Some(alt_text) => validate_alt_text(alt_text).map_err(invalid("finish_audio_upload"))?,
+        None if alt_text_required(user_id) => {
+            return Err("Audio clips need alt text".to_string());
+        }
+        None => {}
+    }
+
+    let upload = with_state(|state| state.audio_uploads.get(&user_id).cloned())
+        .ok_or("No audio upload in progress")?;
+    if upload.data.len() as u64 != upload.total_bytes {
+        return Err(format!(
+            "Upload incomplete: {} of {} bytes received",
+            upload.data.len(),
+            upload.total_bytes
+        ));
+    }
+    let content_type =
+        audio_store::sniff_audio_type(&upload.data).ok_or("Audio must be MP3, Ogg, WebM or WAV")?;
+
+    let clip = AudioClip {
+        asset_id: security_utils::generate_secure_id(),
+        content_type: content_type.to_string(),
+        size_bytes: upload.total_bytes,
+        duration_ms,
+        waveform,
+        alt_text,
+    };
+    audio_store::insert(
+        clip.asset_id,
+        audio_store::StoredAudio {
+            owner: user_id,
+            content_type: clip.content_type.clone(),
+            data: upload.data,
+        },
+    );
+
+    let previous = with_state_mut(|state| {
+        state.audio_uploads.remove(&user_id);
+        state.unattached_audio.insert(user_id, clip.clone())
+    });
+    if let Some(previous) = previous {
+        audio_store::remove(previous.asset_id);
+    }
+    Ok(clip)
+}
+
+/// Attaches the caller's finished audio clip to one of their posts
+///
+/// # Behavior
+/// - Feed items of the post get the `Audio` kind
+/// - Clips of public, ungated posts are also served at `/audio/{asset_id}`
+#[update]
+pub async fn attach_audio_clip(post_id: PostId, asset_id: u64) -> Result<AudioClip, String> {
+    let user_id = authenticate_user()?;
+
+    with_state_mut(|state| {
+        let clip = state
+            .unattached_audio
+            .get(&user_id)
+            .filter(|clip| clip.asset_id == asset_id)
+            .cloned()
+            .ok_or("Audio clip not found")?;
+        let post = state.posts.get_mut(&post_id).ok_or("Post not found")?;
+        if post.author_id != user_id {
+            return Err("Only the author can attach audio".to_string());
+        }
+        if post.audio.is_some() {
+            return Err("Post already has an audio clip".to_string());
+        }
+
+        post.audio = Some(clip.clone());
+        post.updated_at = time();
+        state.unattached_audio.remove(&user_id);
+        state.audio_posts.insert(asset_id, post_id);
+        Ok(clip)
+    })
+}
+
+/// Gets the audio data of a post's voice note
+///
+/// For viewers of non-public or gated posts, whose clips are not served over
+/// HTTP. Gated posts must be unlocked first (see `unlock_post`).
+#[query]
+pub fn get_post_audio(post_id: PostId) -> Result<Vec<u8>, String> {
+    let viewer = current_viewer();
+    let asset_id = with_state(|state| {
+        let post = state
+            .posts
+            .get(&post_id)
+            .filter(|post| can_view_post(state, viewer, post))
+            .ok_or("Post not found")?;
+        if !has_unlocked_post(state, viewer, post, time()) {
+            return Err(SocialNetworkError::Unauthorized(
+                "unlock the post before playing its audio".to_string(),
+            )
+            .into());
+        }
+        post.audio
+            .as_ref()
+            .map(|clip| clip.asset_id)
+            .ok_or_else(|| "Post has no audio clip".to_string())
+    })?;
+    audio_store::get(asset_id)
+        .map(|clip| clip.data)
+        .ok_or_else(|| "Audio clip not found".to_string())
+}
+
+/// Internal function to classify a post for feed layouts
+///
+/// Takes the post as sent to the viewer, so withheld content reveals nothing.
+fn feed_item_kind(post: &Post) -> FeedItemKind {
+    if post.audio.is_some() {
+        FeedItemKind::Audio
+    } else if feed_filter::has_media(&post.content) {
+        FeedItemKind::Media
+    } else {
+        FeedItemKind::Text
+    }
+}
+
+/// Internal function to serve the voice note of a public, ungated post
+fn audio_response(asset_id: u64) -> HttpResponse {
+    let public = with_state(|state| {
+        state
+            .audio_posts
+            .get(&asset_id)
+            .and_then(|post_id| state.posts.get(post_id))
+            .is_some_and(Post::is_syndicated)
+    });
+    public
+        .then(|| audio_store::get(asset_id))
+        .flatten()
+        .map(|clip| HttpResponse::immutable(&clip.content_type, clip.data))
+        .unwrap_or_else(HttpResponse::not_found)
+}
+
+// ============================================================================
+// ACCESSIBILITY
+// ============================================================================
+
+/// Sets whether the caller's own posts must describe their media
+///
+/// # Behavior
+/// - When set, `create_post` rejects posts linking to images or videos
+///   without alt text, and `finish_audio_upload` clips without one
+#[update]
+pub async fn set_require_alt_text(required: bool) -> Result<(), String> {
+    let user_id = authenticate_user()?;
+    with_state_mut(|state| {
+        if required {
+            state.alt_text_required_users.insert(user_id);
+        } else {
+            state.alt_text_required_users.remove(&user_id);
+        }
+    });
+    Ok(())
+}
+
+/// Sets the community rule requiring alt text on every user's media
+///
+/// # Security
+/// * Admins only
+/// * Recorded in the audit log
+#[update]
+pub async fn set_alt_text_rule(required: bool) -> Result<(), String> {
+    let admin_id = authenticate_user()?;
+    require_admin(&admin_id)?;
+
+    with_state_mut(|state| {
+        state.alt_text_required = required;
+        record_audit_event(
+            state,
+            admin_id,
+            AuditAction::AltTextRuleChanged,
+            None,
+            format!("required: {required}"),
+        );
+    });
+    Ok(())
+}
+
+/// Gets whether the caller's posts must describe their media, by the community
+/// rule or their own preference
+#[query]
+pub fn get_alt_text_required() -> Result<bool, String> {
+    let user_id = authenticate_user()?;
+    Ok(alt_text_required(user_id))
+}
+
+/// Internal function to check whether a user's media must carry alt text
+fn alt_text_required(user_id: UserId) -> bool {
+    with_state(|state| state.alt_text_required || state.alt_text_required_users.contains(&user_id))
+}
+
+// ============================================================================
+// QUERY QUOTAS
+// ============================================================================
+
+/// Sets or removes the call quota of an expensive query
+///
+/// # Arguments
+/// * `endpoint` - One of QUOTA_GUARDED_QUERIES
+/// * `quota` - New allowance, or `None` to lift the quota
+///
+/// # Behavior
+/// Only replicated calls are counted: update-mode calls, replicated queries
+/// and calls from other canisters. Plain query calls cannot record usage, so
+/// they are refused once counted calls use up the quota but never use any of
+/// it themselves; a client making only plain queries is not limited.
+///
+/// # Security
+/// * Admins only
+/// * Recorded in the audit log
+#[update]
+pub async fn set_query_quota(endpoint: String, quota: Option<QueryQuota>) -> Result<(), String> {
+    let admin_id = authenticate_user()?;
+    require_admin(&admin_id)?;
+    if let Some(quota) = &quota {
+        validate_query_quota(&endpoint, quota).map_err(invalid("set_query_quota"))?;
+    }
+
+    with_state_mut(|state| {
+        let details = match &quota {
+            Some(quota) => format!(
+                "{endpoint}: {} per principal, {} anonymous per {}s",
+                quota.per_principal, quota.anonymous_total, quota.window_seconds
+            ),
+            None => format!("{endpoint}: unlimited"),
+        };
+        match quota {
+            Some(quota) => state.query_quotas.insert(endpoint.clone(), quota),
+            None => state.query_quotas.remove(&endpoint),
+        };
+        state
+            .query_usage
+            .retain(|(_, counted_endpoint), _| state.query_quotas.contains_key(counted_endpoint));
+        record_audit_event(
+            state,
+            admin_id,
+            AuditAction::QueryQuotaChanged,
+            None,
+            details,
+        );
+    });
+    Ok(())
+}
+
+/// Gets the call quotas in force, so clients can pace their requests
+#[query]
+pub fn get_query_quotas() -> Vec<(String, QueryQuota)> {
+    with_state(|state| {
+        state
+            .query_quotas
+            .iter()
+            .map(|(endpoint, quota)| (endpoint.clone(), quota.clone()))
+            .collect()
+    })
+}
+
+// ============================================================================
+// ACTION GATES
+// ============================================================================
+
+/// Internal function to enforce the account standing gate of an action
+///
+/// Actions without a configured gate are always allowed. Users without a
+/// profile count as new accounts with no posts.
+fn check_action_gate(user_id: UserId, action: &str) -> Result<(), String> {
+    let now = time();
+    with_state(|state| {
+        let Some(gate) = state.action_gates.get(action) else {
+            return Ok(());
+        };
+        let (account_age_days, tier) = reputation_for(state, &user_id, now)
+            .map_or((0, TrustTier::New), |reputation| {
+                (reputation.account_age_days, reputation.trust_tier)
+            });
+        // Imported posts do not count toward standing
+        let post_count = state
+            .users
+            .get(&user_id)
+            .map_or(0, |profile| profile.post_count)
+            .saturating_sub(
+                state
+                    .imports
+                    .get(&user_id)
+                    .map_or(0, |status| status.posts_imported),
+            );
+        match gate_shortfall(gate, account_age_days, post_count, &tier) {
+            Some(requirement) => Err(SocialNetworkError::Unauthorized(format!(
+                "{action} requires {requirement}"
+            ))
+            .into()),
+            None => Ok(()),
+        }
+    })
+}
+
+/// Internal function to apply the `post_links` gate to link-heavy posts
+fn check_link_gate(user_id: UserId, content: &str) -> Result<(), String> {
+    let links = entities::extract_entities(content)
+        .iter()
+        .filter(|entity| entity.kind == EntityKind::Link)
+        .count();
+    if links > UNGATED_POST_LINKS {
+        check_action_gate(user_id, "post_links")?;
+    }
+    Ok(())
+}
+
+/// Sets or removes the account standing required for an action
+///
+/// # Arguments
+/// * `action` - One of TRUST_GATEABLE_ACTIONS
+/// * `gate` - Minimum account age, post count and trust tier, or `None` to
+///   open the action to every account
+///
+/// # Security
+/// * Admins only
+/// * Recorded in the audit log
+#[update]
+pub async fn set_action_gate(action: String, gate: Option<ActionGate>) -> Result<(), String> {
+    let admin_id = authenticate_user()?;
+    require_admin(&admin_id)?;
+    if let Some(gate) = &gate {
+        validate_action_gate(&action, gate).map_err(invalid("set_action_gate"))?;
+    }
+
+    with_state_mut(|state| {
+        let details = match &gate {
+            Some(gate) => format!(
+                "{action}: {} days, {} posts, tier {:?}",
+                gate.min_account_age_days, gate.min_posts, gate.min_trust_tier
+            ),
+            None => format!("{action}: open"),
+        };
+        match gate {
+            Some(gate) => state.action_gates.insert(action, gate),
+            None => state.action_gates.remove(&action),
+        };
+        record_audit_event(
+            state,
+            admin_id,
+            AuditAction::ActionGateChanged,
+            None,
+            details,
+        );
+    });
+    Ok(())
+}
+
+/// Gets the account standing required for each gated action
+#[query]
+pub fn get_action_gates() -> Vec<(String, ActionGate)> {
+    with_state(|state| {
+        state
+            .action_gates
+            .iter()
+            .map(|(action, gate)| (action.clone(), gate.clone()))
+            .collect()
+    })
+}
+
+// ============================================================================
+// SOFT DELETION
+// ============================================================================
+
+/// Deletes one of the caller's posts, leaving it restorable for a while
+///
+/// # Behavior
+/// - The post and its comments disappear from every query at once
+/// - `restore_post` brings it back within DELETION_UNDO_DAYS; after that the
+///   purge timer removes it for good, and asks the archive canister to delete
+///   its copy if the post was archived
+///
+/// # Security
+/// * Only the author can delete a post
+/// * Posts being archived and posts with a mint, promotion or fundraiser
+///   still in progress cannot be deleted
+#[update]
+pub async fn delete_post(post_id: PostId) -> Result<(), String> {
+    let user_id = authenticate_user()?;
+
+    with_state_mut(|state| {
+        let post = state.posts.get(&post_id).ok_or("Post not found")?;
+        if post.author_id != user_id {
+            return Err("Only the author can delete a post".to_string());
+        }
+        if state.posts_being_archived.contains(&post_id) {
+            return Err("Post is being archived; try again shortly".to_string());
+        }
+        if state.posts_being_minted.contains(&post_id)
+            || state.promotions_in_progress.contains(&post_id)
+            || state.unsettled_fundraisers.contains(&post_id)
+        {
+            return Err("Post has a mint, promotion or fundraiser in progress".to_string());
+        }
+
+        let now = time();
+        let Some(post) = state.posts.remove(&post_id) else {
+            return Err("Post not found".to_string());
+        };
+        if let Some(post_ids) = state.user_posts.get_mut(&user_id) {
+            post_ids.retain(|id| *id != post_id);
+        }
+        if let Some(post_ids) = state.user_media_posts.get_mut(&user_id) {
+            post_ids.remove(&post_id);
+        }
+        state.pending_exports.remove(&post_id);
+        if let Some(profile) = state.users.get_mut(&user_id) {
+            profile.post_count = profile.post_count.saturating_sub(1);
+            profile.updated_at = now;
+        }
+        if post.is_syndicated() {
+            record_replica_delta(state, |_| StateDelta::PostDeleted(post_id));
+            state
+                .journal
+                .push(JournalEventKind::PostDeleted { post_id }, now);
+        }
+        state.deletion_log.record(post_id, user_id, now);
+        state.deleted_posts.insert(
+            post_id,
+            DeletedPost {
+                post,
+                deleted_at: now,
+                purge_at: now.saturating_add(DELETION_UNDO_DAYS.saturating_mul(NANOS_PER_DAY)),
+            },
+        );
+        Ok(())
+    })
+}
+
+/// Restores one of the caller's deleted posts, with its comments
+///
+/// # Returns
+/// * `Ok(Post)` - The post, back in feeds and on the profile
+/// * `Err(String)` - Not a deleted post of the caller, or already purged
+#[update]
+pub async fn restore_post(post_id: PostId) -> Result<Post, String> {
+    let user_id = authenticate_user()?;
+
+    with_state_mut(|state| {
+        let post = state
+            .deleted_posts
+            .remove(&post_id)
+            .filter(|deleted| deleted.post.author_id == user_id)
+            .map(|deleted| deleted.post);
+        let Some(mut post) = post else {
+            return Err("Deleted post not found".to_string());
+        };
+
+        let now = time();
+        post.updated_at = now;
+        let post_ids = state.user_posts.entry(user_id).or_default();
+        let position = post_ids.partition_point(|id| *id < post_id);
+        post_ids.insert(position, post_id);
+        if feed_filter::has_media(&post_content(state, &post)) {
+            state
+                .user_media_posts
+                .entry(user_id)
+                .or_default()
+                .insert(post_id);
+        }
+        if state.export_authors.contains(&user_id) && post.is_syndicated() {
+            state.pending_exports.insert(post_id);
+        }
+        if let Some(profile) = state.users.get_mut(&user_id) {
+            profile.post_count = profile.post_count.saturating_add(1);
+            profile.updated_at = now;
+        }
+        let syndicated = post.is_syndicated();
+        state.posts.insert(post_id, post);
+        if syndicated {
+            record_replica_delta(state, |state| {
+                StateDelta::PostCreated(Box::new(inflated_post(state, &state.posts[&post_id])))
+            });
+            state
+                .journal
+                .push(JournalEventKind::PostCreated { post_id }, now);
+        }
+        Ok(post_for_viewer(
+            state,
+            Some(user_id),
+            &state.posts[&post_id],
+        ))
+    })
+}
+
+/// Deletes one of the caller's comments, leaving it restorable for a while
+///
+/// # Behavior
+/// The comment disappears at once; `restore_comment` brings it back within
+/// DELETION_UNDO_DAYS, after which the purge timer removes it for good.
+/// Comments on archived posts are deleted from the archive canister and
+/// cannot be restored.
+#[update]
+pub async fn delete_comment(comment_id: CommentId) -> Result<(), String> {
+    let user_id = authenticate_user()?;
+
+    with_state_mut(|state| {
+        if let Some(&(post_id, author_id)) = state.archived_comments.get(&comment_id) {
+            if author_id != user_id {
+                return Err("Comment not found".to_string());
+            }
+            state.archived_comments.remove(&comment_id);
+            if let Some(post) = commented_post_mut(state, post_id) {
+                post.comment_count = post.comment_count.saturating_sub(1);
+            }
+            if let Some(&archive) = state.archived_posts.get(&post_id) {
+                state
+                    .pending_archive_deletions
+                    .push((archive, ArchiveDeletion::Comment(post_id, comment_id)));
+            }
+            return Ok(());
+        }
+
+        let comment = state
+            .comments
+            .get(&comment_id)
+            .filter(|comment| comment.author_id == user_id)
+            .ok_or("Comment not found")?;
+        if state.posts_being_archived.contains(&comment.post_id) {
+            return Err("Post is being archived; try again shortly".to_string());
+        }
+
+        let now = time();
+        let Some(comment) = state.comments.remove(&comment_id) else {
+            return Err("Comment not found".to_string());
+        };
+        if let Some(comment_ids) = state.post_comments.get_mut(&comment.post_id) {
+            comment_ids.retain(|id| *id != comment_id);
+        }
+        if let Some(comment_ids) = state.user_comments.get_mut(&user_id) {
+            comment_ids.remove(&comment_id);
+        }
+        if let Some(post) = commented_post_mut(state, comment.post_id) {
+            post.comment_count = post.comment_count.saturating_sub(1);
+        }
+        state.deleted_comments.insert(
+            comment_id,
+            DeletedComment {
+                comment,
+                deleted_at: now,
+                purge_at: now.saturating_add(DELETION_UNDO_DAYS.saturating_mul(NANOS_PER_DAY)),
+            },
+        );
+        Ok(())
+    })
+}
+
+/// Restores one of the caller's deleted comments
+///
+/// # Returns
+/// * `Ok(Comment)` - The comment, back under its post
+/// * `Err(String)` - Not a deleted comment of the caller, or already purged
+#[update]
+pub async fn restore_comment(comment_id: CommentId) -> Result<Comment, String> {
+    let user_id = authenticate_user()?;
+
+    with_state_mut(|state| {
+        let comment = state
+            .deleted_comments
+            .remove(&comment_id)
+            .filter(|deleted| deleted.comment.author_id == user_id)
+            .map(|deleted| deleted.comment);
+        let Some(comment) = comment else {
+            return Err("Deleted comment not found".to_string());
+        };
+
+        let comment_ids = state.post_comments.entry(comment.post_id).or_default();
+        let position = comment_ids.partition_point(|id| *id < comment_id);
+        comment_ids.insert(position, comment_id);
+        state
+            .user_comments
+            .entry(user_id)
+            .or_default()
+            .insert(comment_id);
+        if let Some(post) = commented_post_mut(state, comment.post_id) {
+            post.comment_count = post.comment_count.saturating_add(1);
+        }
+        state.comments.insert(comment_id, comment.clone());
+        Ok(comment_for_viewer(state, Some(user_id), &comment))
+    })
+}
+
+/// Lists the caller's deleted posts that can still be restored
+#[query]
+pub fn get_deleted_posts() -> Vec<DeletedPost> {
+    let Some(user_id) = current_viewer() else {
+        return Vec::new();
+    };
+    with_state(|state| {
+        state
+            .deleted_posts
+            .values()
+            .filter(|deleted| deleted.post.author_id == user_id)
+            .map(|deleted| DeletedPost {
+                post: inflated_post(state, &deleted.post),
+                ..deleted.clone()
+            })
+            .collect()
+    })
+}
+
+/// Lists the caller's deleted comments that can still be restored
+#[query]
+pub fn get_deleted_comments() -> Vec<DeletedComment> {
+    let Some(user_id) = current_viewer() else {
+        return Vec::new();
+    };
+    with_state(|state| {
+        state
+            .deleted_comments
+            .values()
+            .filter(|deleted| deleted.comment.author_id == user_id)
+            .cloned()
+            .collect()
+    })
+}
+
+/// Internal function to find the post a comment belongs to, deleted or not
+fn commented_post_mut(state: &mut SocialNetworkState, post_id: PostId) -> Option<&mut Post> {
+    match state.posts.get_mut(&post_id) {
+        Some(post) => Some(post),
+        None => state
+            .deleted_posts
+            .get_mut(&post_id)
+            .map(|deleted| &mut deleted.post),
+    }
+}
+
+/// Internal function to permanently remove deleted content past its undo window
+fn purge_deleted_content(state: &mut SocialNetworkState, now: u64) {
+    let due_posts: Vec<PostId> = state
+        .deleted_posts
+        .iter()
+        .filter(|(_, deleted)| deleted.purge_at <= now)
+        .map(|(post_id, _)| *post_id)
+        .collect();
+    let due_comments: Vec<CommentId> = state
+        .deleted_comments
+        .iter()
+        .filter(|(_, deleted)| deleted.purge_at <= now)
+        .map(|(comment_id, _)| *comment_id)
+        .collect();
+    if due_posts.is_empty() && due_comments.is_empty() {
+        return;
+    }
+
+    for comment_id in &due_comments {
+        state.deleted_comments.remove(comment_id);
+    }
+    for post_id in &due_posts {
+        if let Some(deleted) = state.deleted_posts.remove(post_id) {
+            purge_post(state, &deleted.post);
+        }
+    }
+    log_event(
+        state,
+        LogLevel::Info,
+        LogCategory::System,
+        None,
+        format!(
+            "Purged {} deleted posts and {} deleted comments",
+            due_posts.len(),
+            due_comments.len()
+        ),
+    );
+}
+
+/// Internal function to drop a purged post's content and the indexes keyed by it
+///
+/// Entries other users hold (likes, unlocks) only name the post and are
+/// skipped once it no longer exists.
+fn purge_post(state: &mut SocialNetworkState, post: &Post) {
+    let post_id = post.id;
+    if let Some(archive) = state.archived_posts.remove(&post_id) {
+        state
+            .archived_comments
+            .retain(|_, (archived_id, _)| *archived_id != post_id);
+        state
+            .pending_archive_deletions
+            .push((archive, ArchiveDeletion::Post(post_id)));
+    }
+    if let Some(compressed) = state.compressed_post_bodies.get(&post_id) {
+        let original_len = compression::decompress(compressed).len() as u64;
+        let stored_len = compressed.len() as u64;
+        state.compressed_post_bodies.remove(&post_id);
+        let stats = &mut state.compression_stats;
+        stats.compressed_posts = stats.compressed_posts.saturating_sub(1);
+        stats.original_bytes = stats.original_bytes.saturating_sub(original_len);
+        stats.stored_bytes = stats.stored_bytes.saturating_sub(stored_len);
+    }
+
+    for comment_id in state.post_comments.remove(&post_id).unwrap_or_default() {
+        if let Some(comment) = state.comments.remove(&comment_id) {
+            if let Some(comments) = state.user_comments.get_mut(&comment.author_id) {
+                comments.remove(&comment_id);
+            }
+        }
+    }
+    state
+        .deleted_comments
+        .retain(|_, deleted| deleted.comment.post_id != post_id);
+    state.post_likes.remove(&post_id);
+    state.poll_ballots.remove(&post_id);
+    state.post_view_counts.remove(&post_id);
+    state.post_engagement_daily.remove(&post_id);
+    state
+        .post_translations
+        .retain(|(translated_id, _), _| *translated_id != post_id);
+    if let Some(totals) = state.author_post_totals.get_mut(&post.author_id) {
+        totals.remove(&post_id);
+    }
+
+    if state.fact_checks.remove(&post_id).is_some() {
+        if let Some(post_ids) = state.fact_checked_posts.get_mut(&post.author_id) {
+            post_ids.remove(&post_id);
+        }
+    }
+    if let Some(point) = post
+        .location
+        .as_ref()
+        .and_then(|location| location.coordinates)
+    {
+        if let Some(post_ids) = state.located_posts.get_mut(&point.cell()) {
+            post_ids.remove(&post_id);
+        }
+    }
+    for source in &post.sources {
+        if let Some(post_ids) = state.cited_sources.get_mut(&citation_key(&source.url)) {
+            post_ids.remove(&post_id);
+        }
+    }
+    if let Some(root_id) = state.thread_roots.remove(&post_id) {
+        if let Some(mut post_ids) = state.threads.remove(&root_id) {
+            post_ids.retain(|id| *id != post_id);
+            // A purged first post hands the thread on to the next one
+            if let Some(&new_root_id) = post_ids.first() {
+                for &id in &post_ids {
+                    state.thread_roots.insert(id, new_root_id);
+                }
+                state.threads.insert(new_root_id, post_ids);
+            }
+        }
+    }
+    if let Some(clip) = &post.audio {
+        state.audio_posts.remove(&clip.asset_id);
+        audio_store::remove(clip.asset_id);
+    }
+}
+
+/// Internal function to ask archive canisters to delete purged copies
+///
+/// Failed calls stay queued for the next purge run.
+async fn delete_archived_copies() {
+    let deletions = with_state_mut(|state| std::mem::take(&mut state.pending_archive_deletions));
+    for (archive, deletion) in deletions {
+        if let Err(error) = archive::delete(archive, &deletion).await {
+            with_state_mut(|state| {
+                log_event(state, LogLevel::Error, LogCategory::System, None, error);
+                state.pending_archive_deletions.push((archive, deletion));
+            });
+        }
+    }
+}
+
+// ============================================================================
+// TRANSPARENCY REPORTS
+// ============================================================================
+
+/// Logs a government or legal takedown request for transparency reporting
+///
+/// Record the request once it has been decided; any takedown itself is done
+/// through the usual moderation tools.
+///
+/// # Security
+/// * Admins only
+/// * Recorded in the audit log
+#[update]
+pub async fn log_legal_request(request: LegalRequest) -> Result<(), String> {
+    let admin_id = authenticate_user()?;
+    require_admin(&admin_id)?;
+    validate_legal_request(&request).map_err(invalid("log_legal_request"))?;
+
+    with_state_mut(|state| {
+        let action = if request.complied {
+            AuditAction::LegalRequestComplied
+        } else {
+            AuditAction::LegalRequestRejected
+        };
+        record_audit_event(
+            state,
+            admin_id,
+            action,
+            request.target.map(UserId),
+            format!(
+                "{} ({}): {}",
+                request.authority.trim(),
+                request.jurisdiction.trim(),
+                request.summary
+            ),
+        );
+    });
+    Ok(())
+}
+
+/// Gets the public moderation transparency report for a period
+///
+/// # Behavior
+/// - Actions and legal requests are counted from the audit log
+/// - Appeals are counted by when they were filed and when they were decided
+/// - Flags and fact-check labels stand in for reports; there is no separate
+///   user reporting flow
+#[query]
+pub fn get_transparency_report(period: ReportPeriod) -> Result<TransparencyReport, String> {
+    let (start, end) =
+        transparency::period_bounds(&period).map_err(invalid("get_transparency_report"))?;
+    let within = |timestamp: u64| (start..end).contains(&timestamp);
+
+    Ok(with_state(|state| {
+        let first = state
+            .audit_log
+            .partition_point(|entry| entry.timestamp < start);
+        let last = state
+            .audit_log
+            .partition_point(|entry| entry.timestamp < end);
+        let (actions, legal_requests, legal_requests_complied) =
+            transparency::summarize(state.audit_log[first..last].iter());
+
+        let labels = state.fact_checks.values().flatten();
+        let appeals = labels.clone().filter_map(|label| label.appeal.as_ref());
+        let decided = |status: AppealStatus| {
+            appeals
+                .clone()
+                .filter(|appeal| appeal.status == status)
+                .filter(|appeal| appeal.resolved_at.is_some_and(within))
+                .count() as u64
+        };
+
+        TransparencyReport {
+            period_start: start,
+            period_end: end,
+            posts_flagged: state
+                .flagged_posts
+                .values()
+                .filter(|flagged| within(flagged.flagged_at))
+                .count() as u64,
+            fact_check_labels: labels
+                .clone()
+                .filter(|label| within(label.applied_at))
+                .count() as u64,
+            actions,
+            appeals_filed: appeals
+                .clone()
+                .filter(|appeal| within(appeal.filed_at))
+                .count() as u64,
+            appeals_upheld: decided(AppealStatus::Upheld),
+            appeals_overturned: decided(AppealStatus::Overturned),
+            legal_requests,
+            legal_requests_complied,
+        }
+    }))
+}
+
+// ============================================================================
+// POST NOTIFICATIONS
+// ============================================================================
+
+/// Internal function to tell a post's author, and its commenters about new
+/// comments, of a like or comment
+///
+/// Skips the actor, users who muted the post and users blocked either way.
+fn notify_post_activity(
+    state: &mut SocialNetworkState,
+    post_id: PostId,
+    actor: UserId,
+    kind: PostActivityKind,
+    now: u64,
+) {
+    let Some(author_id) = state.posts.get(&post_id).map(|post| post.author_id) else {
+        return;
+    };
+    let mut recipients = BTreeSet::from([author_id]);
+    if kind == PostActivityKind::Comment {
+        recipients.extend(
+            state
+                .post_comments
+                .get(&post_id)
+                .into_iter()
+                .flatten()
+                .filter_map(|comment_id| state.comments.get(comment_id))
+                .map(|comment| comment.author_id),
+        );
+    }
+    recipients.retain(|&recipient| {
+        recipient != actor
+            && !state.muted_post_activity.contains(&(recipient, post_id))
+            && !is_blocked_between(state, recipient, actor)
+    });
+
+    for recipient in recipients {
+        push_post_activity(
+            state,
+            recipient,
+            PostActivity {
+                post_id,
+                actor,
+                kind,
+                created_at: now,
+            },
+        );
+    }
+}
+
+/// Internal function to tell users mentioned in a new post, if they can see it
+///
+/// Mentions excluded by `mention_audience` were already dropped from the post's
+/// entities.
+fn notify_mentions(state: &mut SocialNetworkState, post_id: PostId, now: u64) {
+    let Some(post) = state.posts.get(&post_id) else {
+        return;
+    };
+    let actor = post.author_id;
+    let mentioned: BTreeSet<UserId> = post
+        .entities
+        .iter()
+        .filter_map(|entity| entity.user_id)
+        .filter(|&user_id| user_id != actor && can_view_post(state, Some(user_id), post))
+        .collect();
+
+    for recipient in mentioned {
+        push_post_activity(
+            state,
+            recipient,
+            PostActivity {
+                post_id,
+                actor,
+                kind: PostActivityKind::Mention,
+                created_at: now,
+            },
+        );
+    }
+}
+
+/// Internal function to append to a user's bounded post activity list
+///
+/// Comments and mentions are also pushed to the recipient's devices.
+fn push_post_activity(state: &mut SocialNetworkState, recipient: UserId, activity: PostActivity) {
+    let push_kind = match activity.kind {
+        PostActivityKind::Like => None,
+        PostActivityKind::Comment => Some(PushKind::Comment),
+        PostActivityKind::Mention => Some(PushKind::Mention),
+    };
+    if let Some(kind) = push_kind {
+        queue_push(
+            state,
+            recipient,
+            PushNotification {
+                kind,
+                actor: activity.actor,
+                post_id: Some(activity.post_id),
+                conversation_id: None,
+                created_at: activity.created_at,
+            },
+        );
+    }
+
+    let recent = state.recent_post_activity.entry(recipient).or_default();
+    recent.push_back(activity);
+    if recent.len() > MAX_RECENT_POST_ACTIVITY {
+        recent.pop_front();
+    }
+}
+
+/// Gets recent likes and comments on posts the caller wrote or commented on,
+/// and posts mentioning the caller, newest first
+///
+/// # Behavior
+/// Keeps the latest MAX_RECENT_POST_ACTIVITY entries; nothing is recorded for
+/// posts the caller muted.
+#[query]
+pub fn get_post_activity(
+    limit: Option<usize>,
+    offset: Option<usize>,
+) -> Result<PostActivityPage, String> {
+    let user_id = authenticate_for(AppScope::ReadOnly)?;
+    let limit = limit
+        .unwrap_or(DEFAULT_CONNECTIONS_LIMIT)
+        .min(MAX_CONNECTIONS_LIMIT);
+    let offset = offset.unwrap_or(0);
+
+    Ok(with_state(|state| {
+        let recent = state.recent_post_activity.get(&user_id);
+        let (page, next_cursor) = page_at(
+            recent.into_iter().flat_map(|recent| recent.iter().rev()),
+            offset,
+            limit,
+        );
+        PostActivityPage {
+            entries: page.into_iter().cloned().collect(),
+            total: Some(recent.map_or(0, VecDeque::len) as u64),
+            has_more: next_cursor.is_some(),
+            next_cursor,
+        }
+    }))
+}
+
+/// Stops telling the caller about further likes and comments on a post
+///
+/// The people involved are not muted; their activity elsewhere is still
+/// reported.
+#[update]
+pub async fn mute_post_notifications(post_id: PostId) -> Result<(), String> {
+    let user_id = authenticate_user()?;
+
+    with_state_mut(|state| {
+        let visible = state
+            .posts
+            .get(&post_id)
+            .is_some_and(|post| can_view_post(state, Some(user_id), post));
+        if !visible {
+            return Err("Post not found".to_string());
+        }
+        state.muted_post_activity.insert((user_id, post_id));
+        Ok(())
+    })
+}
+
+/// Resumes likes and comments on a post the caller muted
+#[update]
+pub async fn unmute_post_notifications(post_id: PostId) -> Result<(), String> {
+    let user_id = authenticate_user()?;
+
+    with_state_mut(|state| {
+        if !state.muted_post_activity.remove(&(user_id, post_id)) {
+            return Err("Post is not muted".to_string());
+        }
+        Ok(())
+    })
+}
+
+/// Lists the posts whose notifications the caller muted
+#[query]
+pub fn get_muted_posts() -> Result<Vec<PostId>, String> {
+    let user_id = authenticate_user()?;
+    Ok(with_state(|state| {
+        state
+            .muted_post_activity
+            .range((user_id, PostId(0))..=(user_id, PostId(u64::MAX)))
+            .map(|(_, post_id)| *post_id)
+            .collect()
+    }))
+}
+
+// ============================================================================
+// PUSH NOTIFICATIONS
+// ============================================================================
+
+/// Configures the relay push notifications are delivered through
+///
+/// # Behavior
+/// - `Some(config)` starts pushing comments, mentions and direct messages to
+///   registered devices
+/// - `None` stops pushing and drops queued notifications
+///
+/// # Security
+/// * Admins only; the API key is never returned by any endpoint
+/// * Recorded in the audit log (relay URL only)
+#[update]
+pub async fn set_push_config(config: Option<PushConfig>) -> Result<(), String> {
+    let admin_id = authenticate_user()?;
+    require_admin(&admin_id)?;
+
+    if let Some(config) = &config {
+        if !config.relay_url.starts_with("https://")
+            || config.relay_url.len() > MAX_OUTCALL_ENDPOINT_LENGTH
+        {
+            return Err(format!(
+                "Relay URL must be an https URL of at most {MAX_OUTCALL_ENDPOINT_LENGTH} characters"
+            ));
+        }
+    }
+
+    with_state_mut(|state| {
+        let details = match &config {
+            Some(config) => config.relay_url.clone(),
+            None => {
+                state.pending_pushes.clear();
+                "disabled".to_string()
+            }
+        };
+        state.push_config = config;
+        record_audit_event(
+            state,
+            admin_id,
+            AuditAction::PushRelayConfigured,
+            None,
+            details,
+        );
+    });
+    Ok(())
+}
+
+/// Gets whether push notifications are enabled, so clients know to offer them
+#[query]
+pub fn is_push_enabled() -> bool {
+    with_state(|state| state.push_config.is_some())
+}
+
+/// Registers a device to receive the caller's push notifications
+///
+/// # Behavior
+/// Registering an endpoint twice is a no-op; at most MAX_PUSH_ENDPOINTS
+/// devices are kept per user.
+#[update]
+pub async fn register_push_endpoint(endpoint: PushEndpoint) -> Result<(), String> {
+    let user_id = authenticate_user()?;
+    validation::validate_push_endpoint(&endpoint)?;
+
+    with_state_mut(|state| {
+        let endpoints = state.push_endpoints.entry(user_id).or_default();
+        if endpoints.contains(&endpoint) {
+            return Ok(());
+        }
+        if endpoints.len() >= MAX_PUSH_ENDPOINTS {
+            return Err(format!(
+                "At most {MAX_PUSH_ENDPOINTS} push endpoints can be registered"
+            ));
+        }
+        endpoints.push(endpoint);
+        Ok(())
+    })
+}
+
+/// Stops pushing the caller's notifications to a device
+///
+/// Notifications still queued for the device are dropped.
+#[update]
+pub async fn unregister_push_endpoint(endpoint: PushEndpoint) -> Result<(), String> {
+    let user_id = authenticate_user()?;
+
+    with_state_mut(|state| {
+        if !remove_push_endpoint(state, user_id, &endpoint) {
+            return Err("Push endpoint not registered".to_string());
+        }
+        Ok(())
+    })
+}
+
+/// Lists the devices the caller receives push notifications on
+#[query]
+pub fn get_my_push_endpoints() -> Result<Vec<PushEndpoint>, String> {
+    let user_id = authenticate_user()?;
+    Ok(with_state(|state| {
+        state
+            .push_endpoints
+            .get(&user_id)
+            .cloned()
+            .unwrap_or_default()
+    }))
+}
+
+/// Internal function to queue a notification for each of a user's devices
+///
+/// Nothing is queued while push is disabled, once the user had
+/// MAX_PUSHES_PER_HOUR notifications queued in the last hour, or while the
+/// queue is full.
+fn queue_push(state: &mut SocialNetworkState, recipient: UserId, notification: PushNotification) {
+    if state.push_config.is_none() {
+        return;
+    }
+    let Some(endpoints) = state.push_endpoints.get(&recipient).cloned() else {
+        return;
+    };
+    if state.pending_pushes.len() + endpoints.len() > MAX_PENDING_PUSHES {
+        return;
+    }
+
+    let hour_ago = notification
+        .created_at
+        .saturating_sub(3_600 * 1_000_000_000);
+    let recent = state.recent_pushes.entry(recipient).or_default();
+    recent.retain(|&queued_at| queued_at > hour_ago);
+    if recent.len() >= MAX_PUSHES_PER_HOUR {
+        return;
+    }
+    recent.push(notification.created_at);
+
+    for endpoint in endpoints {
+        let id = state.next_push_id;
+        state.next_push_id = state.next_push_id.saturating_add(1);
+        state.pending_pushes.insert(
+            id,
+            PendingPush {
+                id,
+                recipient,
+                endpoint,
+                notification: notification.clone(),
+                attempts: 0,
+                next_attempt_at: notification.created_at,
+            },
+        );
+    }
+}
+
+/// Internal function to forget a user's device and its queued notifications
+///
+/// # Returns
+/// Whether the endpoint was registered
+fn remove_push_endpoint(
+    state: &mut SocialNetworkState,
+    user_id: UserId,
+    endpoint: &PushEndpoint,
+) -> bool {
+    let Some(endpoints) = state.push_endpoints.get_mut(&user_id) else {
+        return false;
+    };
+    let registered = endpoints.len();
+    endpoints.retain(|registered| registered != endpoint);
+    let removed = endpoints.len() < registered;
+    if endpoints.is_empty() {
+        state.push_endpoints.remove(&user_id);
+    }
+    state
+        .pending_pushes
+        .retain(|_, push| push.recipient != user_id || push.endpoint != *endpoint);
+    removed
+}
+
+/// Internal function to deliver one batch of due push notifications
+///
+/// Failed deliveries are retried with exponential backoff until
+/// MAX_PUSH_ATTEMPTS; endpoints the relay reports gone are unregistered.
+async fn dispatch_push_notifications() {
+    let Some(config) = with_state(|state| state.push_config.clone()) else {
+        return;
+    };
+    if is_low_on_cycles() {
+        return;
+    }
+    let now = time();
+
+    let batch: Vec<PendingPush> = with_state_mut(|state| {
+        let due: Vec<u64> = state
+            .pending_pushes
+            .values()
+            .filter(|push| push.next_attempt_at <= now)
+            .take(MAX_PUSH_BATCH)
+            .map(|push| push.id)
+            .collect();
+        due.into_iter()
+            .filter_map(|id| state.pending_pushes.remove(&id))
+            .collect()
+    });
+
+    for mut push in batch {
+        let result = push::send(&config, &push).await;
+
+        with_state_mut(|state| match result {
+            Ok(push::Delivery::Delivered) => {}
+            Ok(push::Delivery::EndpointGone) => {
+                remove_push_endpoint(state, push.recipient, &push.endpoint);
+            }
+            Err(error) => {
+                push.attempts += 1;
+                if push.attempts < MAX_PUSH_ATTEMPTS {
+                    push.next_attempt_at =
+                        time().saturating_add(push::retry_delay(push.attempts) * 1_000_000_000);
+                    state.pending_pushes.insert(push.id, push);
+                } else {
+                    log_event(
+                        state,
+                        LogLevel::Warn,
+                        LogCategory::System,
+                        None,
+                        format!("Dropping push notification {}: {error}", push.id),
+                    );
+                }
+            }
+        });
+    }
+}
+
+// ============================================================================
+// WEBHOOKS
+// ============================================================================
+
+/// Registers a URL to receive events about the caller's own content
+///
+/// # Arguments
+/// * `url` - HTTPS URL payloads are POSTed to
+/// * `events` - New comments on the caller's posts, new followers and/or tips
+///   received
+///
+/// # Returns
+/// The webhook, including the secret that signs its payloads
+///
+/// # Behavior
+/// Each payload is JSON `{id, event, created_at, data}` signed with
+/// HMAC-SHA256 over `"{timestamp}.{body}"`, sent as
+/// `X-Decentra-Signature: sha256=<hex>` with `X-Decentra-Timestamp`
+/// (seconds). Failed deliveries are retried with exponential backoff.
+///
+/// # Security
+/// * Verified creators and organizations only
+/// * At most MAX_WEBHOOKS per user
+#[update]
+pub async fn register_webhook(url: String, events: Vec<WebhookEvent>) -> Result<Webhook, String> {
+    let user_id = authenticate_user()?;
+    validation::validate_webhook(&url, &events)?;
+    if !with_state(|state| can_use_webhooks(state, user_id)) {
+        return Err("Webhooks are available to verified creators and organizations".to_string());
+    }
+    let secret = security_utils::generate_secure_token();
+
+    with_state_mut(|state| {
+        let webhooks = state.webhooks.entry(user_id).or_default();
+        if webhooks.len() >= MAX_WEBHOOKS {
+            return Err(format!("At most {MAX_WEBHOOKS} webhooks can be registered"));
+        }
+        let webhook = Webhook {
+            id: state.next_webhook_id,
+            url,
+            events,
+            secret,
+            created_at: time(),
+        };
+        webhooks.push(webhook.clone());
+        state.next_webhook_id = state.next_webhook_id.saturating_add(1);
+        Ok(webhook)
+    })
+}
+
+/// Deletes one of the caller's webhooks, its delivery log and queued deliveries
+#[update]
+pub async fn delete_webhook(webhook_id: u64) -> Result<(), String> {
+    let user_id = authenticate_user()?;
+
+    with_state_mut(|state| {
+        let webhooks = state
+            .webhooks
+            .get_mut(&user_id)
+            .ok_or("Webhook not found")?;
+        let index = webhooks
+            .iter()
+            .position(|webhook| webhook.id == webhook_id)
+            .ok_or("Webhook not found")?;
+        webhooks.remove(index);
+        if webhooks.is_empty() {
+            state.webhooks.remove(&user_id);
+        }
+        state.webhook_deliveries.remove(&webhook_id);
+        state
+            .pending_webhook_deliveries
+            .retain(|_, delivery| delivery.webhook_id != webhook_id);
+        Ok(())
+    })
+}
+
+/// Lists the caller's webhooks
+#[query]
+pub fn get_my_webhooks() -> Result<Vec<Webhook>, String> {
+    let user_id = authenticate_user()?;
+    Ok(with_state(|state| {
+        state.webhooks.get(&user_id).cloned().unwrap_or_default()
+    }))
+}
+
+/// Gets the delivery log of one of the caller's webhooks, newest first
+///
+/// # Behavior
+/// Keeps the latest MAX_WEBHOOK_DELIVERY_LOG deliveries per webhook.
+#[query]
+pub fn get_webhook_deliveries(
+    webhook_id: u64,
+    limit: Option<usize>,
+    offset: Option<usize>,
+) -> Result<WebhookDeliveryPage, String> {
+    let user_id = authenticate_user()?;
+    let limit = limit
+        .unwrap_or(DEFAULT_CONNECTIONS_LIMIT)
+        .min(MAX_CONNECTIONS_LIMIT);
+    let offset = offset.unwrap_or(0);
+
+    with_state(|state| {
+        let owned = state
+            .webhooks
+            .get(&user_id)
+            .is_some_and(|webhooks| webhooks.iter().any(|webhook| webhook.id == webhook_id));
+        if !owned {
+            return Err("Webhook not found".to_string());
+        }
+
+        let log = state.webhook_deliveries.get(&webhook_id);
+        let (page, next_cursor) = page_at(
+            log.into_iter().flat_map(|log| log.iter().rev()),
+            offset,
+            limit,
+        );
+        Ok(WebhookDeliveryPage {
+            entries: page.into_iter().cloned().collect(),
+            total: Some(log.map_or(0, VecDeque::len) as u64),
+            has_more: next_cursor.is_some(),
+            next_cursor,
+        })
+    })
+}
+
+/// Internal function to check whether a user may register webhooks
+fn can_use_webhooks(state: &SocialNetworkState, user_id: UserId) -> bool {
+    state.users.get(&user_id).is_some_and(|profile| {
+        matches!(
+            profile.verification_status,
+            VerificationStatus::Verified | VerificationStatus::Organization
+        )
+    })
+}
+
+/// Internal function to queue an event for each of a user's webhooks
+/// subscribed to it
+///
+/// Nothing is queued for users who lost their verification, or while the
+/// delivery queue is full.
+fn queue_webhook_event(
+    state: &mut SocialNetworkState,
+    owner: UserId,
+    data: webhook::EventData,
+    now: u64,
+) {
+    let event = data.event();
+    let webhook_ids: Vec<u64> = state
+        .webhooks
+        .get(&owner)
+        .into_iter()
+        .flatten()
+        .filter(|webhook| webhook.events.contains(&event))
+        .map(|webhook| webhook.id)
+        .collect();
+    if webhook_ids.is_empty() || !can_use_webhooks(state, owner) {
+        return;
+    }
+
+    for webhook_id in webhook_ids {
+        if state.pending_webhook_deliveries.len() >= MAX_PENDING_WEBHOOK_DELIVERIES {
+            return;
+        }
+        let delivery_id = state.next_webhook_delivery_id;
+        state.next_webhook_delivery_id = state.next_webhook_delivery_id.saturating_add(1);
+
+        state.pending_webhook_deliveries.insert(
+            delivery_id,
+            PendingWebhookDelivery {
+                owner,
+                webhook_id,
+                delivery_id,
+                body: webhook::payload(delivery_id, &data, now),
+                attempts: 0,
+                next_attempt_at: now,
+            },
+        );
+        let log = state.webhook_deliveries.entry(webhook_id).or_default();
+        log.push_back(WebhookDelivery {
+            id: delivery_id,
+            event: event.clone(),
+            status: WebhookDeliveryStatus::Pending,
+            attempts: 0,
+            response_status: None,
+            last_error: None,
+            created_at: now,
+            last_attempt_at: None,
+        });
+        if log.len() > MAX_WEBHOOK_DELIVERY_LOG {
+            log.pop_front();
+        }
+    }
+}
+
+/// Internal function to attempt one batch of due webhook deliveries
+///
+/// Failed attempts are retried with exponential backoff until
+/// MAX_WEBHOOK_ATTEMPTS, then marked failed in the delivery log.
+async fn dispatch_webhook_deliveries() {
+    if is_low_on_cycles() {
+        return;
+    }
+    let now = time();
+
+    let batch: Vec<(PendingWebhookDelivery, Webhook)> = with_state_mut(|state| {
+        let due: Vec<u64> = state
+            .pending_webhook_deliveries
+            .values()
+            .filter(|delivery| delivery.next_attempt_at <= now)
+            .take(MAX_WEBHOOK_BATCH)
+            .map(|delivery| delivery.delivery_id)
+            .collect();
+        due.into_iter()
+            .filter_map(|delivery_id| {
+                let delivery = state.pending_webhook_deliveries.remove(&delivery_id)?;
+                let webhook = state
+                    .webhooks
+                    .get(&delivery.owner)?
+                    .iter()
+                    .find(|webhook| webhook.id == delivery.webhook_id)?
+                    .clone();
+                Some((delivery, webhook))
+            })
+            .collect()
+    });
+
+    for (mut delivery, webhook) in batch {
+        let timestamp = time() / 1_000_000_000;
+        let result = webhook::send(
+            &webhook.url,
+            &webhook.secret,
+            delivery.delivery_id,
+            &delivery.body,
+            timestamp,
+        )
+        .await;
+
+        with_state_mut(|state| {
+            let now = time();
+            delivery.attempts += 1;
+            let status = match &result {
+                Ok(_) => WebhookDeliveryStatus::Delivered,
+                Err(_) if delivery.attempts < MAX_WEBHOOK_ATTEMPTS => {
+                    WebhookDeliveryStatus::Pending
+                }
+                Err(_) => WebhookDeliveryStatus::Failed,
+            };
+
+            if let Some(entry) = state
+                .webhook_deliveries
+                .get_mut(&delivery.webhook_id)
+                .and_then(|log| {
+                    log.iter_mut()
+                        .rev()
+                        .find(|entry| entry.id == delivery.delivery_id)
+                })
+            {
+                entry.status = status.clone();
+                entry.attempts = delivery.attempts;
+                entry.last_attempt_at = Some(now);
+                match &result {
+                    Ok(response_status) => {
+                        entry.response_status = Some(*response_status);
+                        entry.last_error = None;
+                    }
+                    Err(error) => entry.last_error = Some(error.clone()),
+                }
+            }
+
+            if status == WebhookDeliveryStatus::Pending {
+                delivery.next_attempt_at =
+                    now.saturating_add(push::retry_delay(delivery.attempts) * 1_000_000_000);
+                state
+                    .pending_webhook_deliveries
+                    .insert(delivery.delivery_id, delivery);
+            }
+        });
+    }
+}
+
+// ============================================================================
+// EVENT JOURNAL
+// ============================================================================
+
+/// Gets changes to public data in order, so indexers and mirrors can sync
+/// incrementally
+///
+/// # Arguments
+/// * `since_seq` - First sequence number wanted; 0 on the first call, then
+///   the previous page's `next_seq`
+/// * `limit` - Events to return (default and maximum MAX_JOURNAL_PAGE)
+///
+/// # Behavior
+/// Covers public, ungated posts created, restored or deleted, and profile
+/// updates. Each event carries the post or profile as anonymous viewers see
+/// it now, so replaying the journal yields current public data. Only the
+/// latest MAX_JOURNAL_ENTRIES events are held; a `since_seq` below
+/// `first_seq` means events were missed.
+#[query]
+pub fn get_events(since_seq: u64, limit: Option<usize>) -> Result<JournalPage, String> {
+    check_query_quota("get_events")?;
+    let limit = limit.unwrap_or(MAX_JOURNAL_PAGE).min(MAX_JOURNAL_PAGE);
+
+    Ok(with_state(|state| {
+        let events: Vec<JournalEvent> = state
+            .journal
+            .since(since_seq, limit)
+            .map(|(seq, entry)| {
+                let (post, profile) = match &entry.kind {
+                    JournalEventKind::PostCreated { post_id } => (
+                        state
+                            .posts
+                            .get(post_id)
+                            .filter(|post| post.is_syndicated())
+                            .map(|post| post_for_viewer(state, None, post)),
+                        None,
+                    ),
+                    JournalEventKind::PostDeleted { .. } => (None, None),
+                    JournalEventKind::ProfileUpdated { user_id } => (
+                        None,
+                        state
+                            .users
+                            .get(user_id)
+                            .map(|profile| profile_for_viewer(state, profile, None)),
+                    ),
+                };
+                JournalEvent {
+                    seq,
+                    timestamp: entry.timestamp,
+                    kind: entry.kind.clone(),
+                    post,
+                    profile,
+                }
+            })
+            .collect();
+
+        let next_seq = events
+            .last()
+            .map_or(since_seq.max(state.journal.first_seq()), |event| {
+                event.seq + 1
+            });
+        JournalPage {
+            has_more: next_seq < state.journal.next_seq(),
+            next_seq,
+            first_seq: state.journal.first_seq(),
+            events,
+        }
+    }))
+}
+
+// ============================================================================
+// ARCHIVE IMPORTS
+// ============================================================================
+
+/// Imports one chunk of a Twitter or Mastodon export parsed by the client
+///
+/// # Behavior
+/// - Posts are created with their original timestamps and marked with
+///   their source; they are not federated and their mentions are not
+///   linked, since those name accounts on the original platform
+/// - Posts already imported (same platform and original id) are skipped, so
+///   a failed upload can be resent
+/// - Each post passes the content, link gate and `create_post` rate limit
+///   checks of a new post; posts over the rate limit are returned as
+///   `deferred`
+/// - Followed handles are queued and suggested once a user holds the handle
+///   (matched by local part, so `@alice@mastodon.social` suggests `alice`);
+///   nothing is followed until the importer confirms the suggestion with
+///   `confirm_import_follow`. Unmatched handles are dropped after
+///   IMPORT_FOLLOW_RETENTION_DAYS
+///
+/// # Security
+/// * Rate limited (30 chunks per hour)
+/// * At most MAX_IMPORTED_POSTS posts and MAX_IMPORT_FOLLOWS follows per user
+/// * Imported posts do not count toward action gate requirements
+#[update]
+pub async fn import_archive_chunk(chunk: ImportChunk) -> Result<ImportChunkResult, String> {
+    let user_id = authenticate_user()?;
+    validation::validate_import_chunk(&chunk).map_err(invalid("import_archive_chunk"))?;
+    check_rate_limit(&user_id, "import_archive_chunk", 30, 3_600)?;
+    ensure_user_profile(user_id).await?;
+
+    let now = time();
+    let visibility = chunk.visibility.unwrap_or(PostVisibility::Public);
+    let status = with_state(|state| state.imports.get(&user_id).cloned().unwrap_or_default());
+    if !chunk.posts.is_empty() && status.posts_imported >= MAX_IMPORTED_POSTS {
+        return Err(format!(
+            "At most {MAX_IMPORTED_POSTS} posts can be imported"
+        ));
+    }
+    let mut result = ImportChunkResult {
+        imported: 0,
+        duplicates: 0,
+        rejected: Vec::new(),
+        deferred: Vec::new(),
+        follows_queued: 0,
+    };
+
+    let require_alt_text = alt_text_required(user_id);
+    for post in chunk.posts {
+        let key = (user_id, chunk.platform, post.original_id.clone());
+        if with_state(|state| state.imported_posts.contains(&key)) {
+            result.duplicates += 1;
+            continue;
+        }
+        if !result.deferred.is_empty() {
+            result.deferred.push(post.original_id);
+            continue;
+        }
+        let media_links: Vec<&str> = feed_filter::media_links(&post.content).collect();
+        if status.posts_imported + result.imported >= MAX_IMPORTED_POSTS
+            || validate_exported_post(&post, now).is_err()
+            || validate_media_alt_texts(&[], &media_links, require_alt_text).is_err()
+            || check_link_gate(user_id, &post.content).is_err()
+        {
+            result.rejected.push(post.original_id);
+            continue;
+        }
+        if check_rate_limit(&user_id, "create_post", 10, 300).is_err() {
+            result.deferred.push(post.original_id);
+            continue;
+        }
+
+        let post_id = with_state_mut(|state| {
+            state.imported_posts.insert(key);
+            state.imports.entry(user_id).or_default().posts_imported += 1;
+            insert_post(
+                state,
+                user_id,
+                post.content,
+                visibility.clone(),
+                post.language,
+                TextFormat::Plain,
+                PostExtras {
+                    imported: Some(ImportSource {
+                        platform: chunk.platform,
+                        original_id: post.original_id,
+                        imported_at: now,
+                    }),
+                    created_at: Some(post.created_at),
+                    ..PostExtras::default()
+                },
+            )
+        });
+        check_post_links(post_id);
+        result.imported += 1;
+    }
+
+    with_state_mut(|state| {
+        for handle in chunk.following {
+            if status.follows_queued + result.follows_queued >= MAX_IMPORT_FOLLOWS {
+                break;
+            }
+            let Some(handle) = import_follow_handle(&handle) else {
+                continue;
+            };
+            if let btree_map::Entry::Vacant(entry) =
+                state.pending_import_follows.entry((user_id, handle))
+            {
+                entry.insert(PendingImportFollow {
+                    queued_at: now,
+                    matched: None,
+                });
+                result.follows_queued += 1;
+            }
+        }
+
+        state.imports.entry(user_id).or_default().follows_queued += result.follows_queued;
+        Ok(result)
+    })
+}
+
+/// Gets the caller's archive import totals
+#[query]
+pub fn get_import_status() -> Result<ImportStatus, String> {
+    let user_id = authenticate_user()?;
+    Ok(with_state(|state| {
+        let mut status = state.imports.get(&user_id).cloned().unwrap_or_default();
+        for pending in pending_import_follows_of(state, user_id).map(|(_, pending)| pending) {
+            match pending.matched {
+                Some(_) => status.follows_suggested += 1,
+                None => status.follows_pending += 1,
+            }
+        }
+        status
+    }))
+}
+
+/// Lists users matching handles the caller followed in imported archives
+///
+/// # Arguments
+/// * `limit` - Maximum number of suggestions (default and maximum
+///   MAX_IMPORT_FOLLOW_BATCH); confirmed and dismissed suggestions drop out,
+///   so calling again returns the next ones
+#[query]
+pub fn get_import_follow_suggestions(
+    limit: Option<usize>,
+) -> Result<Vec<ImportFollowSuggestion>, String> {
+    let user_id = authenticate_user()?;
+    let limit = limit
+        .unwrap_or(MAX_IMPORT_FOLLOW_BATCH)
+        .min(MAX_IMPORT_FOLLOW_BATCH);
+    Ok(with_state(|state| {
+        pending_import_follows_of(state, user_id)
+            .filter_map(|((_, handle), pending)| {
+                let profile = state.users.get(&pending.matched?)?;
+                Some(ImportFollowSuggestion {
+                    handle: handle.clone(),
+                    user: profile_for_viewer(state, profile, Some(user_id)),
+                })
+            })
+            .take(limit)
+            .collect()
+    }))
+}
+
+/// Follows the user suggested for a handle from an imported archive
+///
+/// # Behavior
+/// - Sends a follow request instead when the user requires approval
+/// - The suggestion is only removed once the follow succeeds
+#[update]
+pub async fn confirm_import_follow(handle: String) -> Result<(), String> {
+    let user_id = authenticate_user()?;
+    let key = (user_id, normalize_handle(&handle));
+    let target = with_state(|state| {
+        state
+            .pending_import_follows
+            .get(&key)
+            .and_then(|pending| pending.matched)
+    })
+    .ok_or("No suggestion for that handle")?;
+
+    request_follow(user_id, target, None)?;
+    with_state_mut(|state| {
+        state.pending_import_follows.remove(&key);
+        state.imports.entry(user_id).or_default().follows_made += 1;
+    });
+    Ok(())
+}
+
+/// Drops a handle from an imported archive without following anyone
+#[update]
+pub async fn dismiss_import_follow(handle: String) -> Result<(), String> {
+    let user_id = authenticate_user()?;
+    with_state_mut(|state| {
+        state
+            .pending_import_follows
+            .remove(&(user_id, normalize_handle(&handle)))
+            .map(|_| ())
+            .ok_or_else(|| "Handle not found".to_string())
+    })
+}
+
+/// Internal function to list a user's queued import follows
+fn pending_import_follows_of(
+    state: &SocialNetworkState,
+    user_id: UserId,
+) -> impl Iterator<Item = (&(UserId, String), &PendingImportFollow)> {
+    state
+        .pending_import_follows
+        .range((user_id, String::new())..)
+        .take_while(move |((importer, _), _)| *importer == user_id)
+}
+
+/// Internal function to reduce a followed account from an archive to a
+/// normalized local handle
+fn import_follow_handle(handle: &str) -> Option<String> {
+    let local = handle.trim().trim_start_matches('@').split('@').next()?;
+    validate_username(local).ok()?;
+    Some(normalize_handle(local))
+}
+
+/// Internal function to match a batch of queued import handles to users
+///
+/// Walks the queue from where the previous run stopped, so every entry is
+/// checked in turn. Matches are only suggested; entries whose handle now
+/// names the importer or someone they already follow are dropped, as are
+/// unmatched entries past their retention.
+fn resolve_import_follows() {
+    use std::ops::Bound::{Excluded, Unbounded};
+
+    let now = time();
+    with_state_mut(|state| {
+        let start = state
+            .import_follow_cursor
+            .take()
+            .map_or(Unbounded, Excluded);
+        let batch: Vec<((UserId, String), u64)> = state
+            .pending_import_follows
+            .range((start, Unbounded))
+            .take(MAX_IMPORT_FOLLOW_BATCH)
+            .map(|(key, pending)| (key.clone(), pending.queued_at))
+            .collect();
+        if batch.len() == MAX_IMPORT_FOLLOW_BATCH {
+            state.import_follow_cursor = batch.last().map(|(key, _)| key.clone());
+        }
+
+        let retention = IMPORT_FOLLOW_RETENTION_DAYS * NANOS_PER_DAY;
+        for (key, queued_at) in batch {
+            let (importer, handle) = &key;
+            let target = resolve_handle(state, handle, now);
+            let settled = target.is_some_and(|target| {
+                target == *importer || is_follower_of(state, *importer, target)
+            });
+            if settled || (target.is_none() && now.saturating_sub(queued_at) >= retention) {
+                state.pending_import_follows.remove(&key);
+            } else if let Some(pending) = state.pending_import_follows.get_mut(&key) {
+                pending.matched = target;
+            }
+        }
+    });
+}
+
+// Export Candid interface
+ic_cdk::export_candid!();
+
+// ============================================================================
+// DISCOVERABILITY TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BASE_URL: &str = "https://decentra.example";
+
+    fn profile(id: u8, handle: &str, searchable: bool) -> UserProfile {
+        UserProfile {
+            id: UserId(Principal::from_slice(&[id])),
+            handle: handle.to_string(),
+            display_name: handle.to_string(),
+            bio: String::new(),
+            avatar: String::new(),
+            banner: String::new(),
+            theme: ProfileTheme::default(),
+            created_at: 0,
+            updated_at: 0,
+            follower_count: 0,
+            following_count: 0,
+            post_count: 0,
+            privacy_settings: PrivacySettings {
+                searchable,
+                location_audience: InteractionAudience::Everyone,
+                ..PrivacySettings::default()
+            },
+            verification_status: VerificationStatus::Unverified,
+            personhood: None,
+            nft_avatar: None,
+            version: 0,
+        }
+    }
+
+    fn post(id: u64, author_id: UserId, content: &str) -> Post {
+        Post {
+            id: PostId(id),
+            author_id,
+            content: content.to_string(),
+            rendered_content: String::new(),
+            created_at: 0,
+            updated_at: 0,
+            like_count: 0,
+            comment_count: 0,
+            repost_count: 0,
+            tips_received: 0,
+            edited_at: None,
+            visibility: PostVisibility::Public,
+            language: None,
+            filtered: None,
+            link_warnings: Vec::new(),
+            thread: None,
+            format: TextFormat::Plain,
+            entities: Vec::new(),
+            custom_emoji: Vec::new(),
+            fact_checks: Vec::new(),
+            nft: None,
+            token_gate: None,
+            subscriber_tier: None,
+            unlock_price: None,
+            fundraiser: None,
+            poll: None,
+            audio: None,
+            media_alt_texts: Vec::new(),
+            location: None,
+            sources: Vec::new(),
+            permanent_copy: None,
+            contributors: Vec::new(),
+            imported: None,
+            archived: false,
+        }
+    }
+
+    /// State with a searchable user "alice" (post 1) and an opted-out user
+    /// "bob" (post 2), both with public profiles and a liked, located post
+    /// tagged #news
+    fn discoverability_state() -> SocialNetworkState {
+        let mut state = SocialNetworkState::default();
+        for (id, user) in [
+            (1, profile(1, "alice", true)),
+            (2, profile(2, "bob", false)),
+        ] {
+            state.handle_index.insert(user.handle.clone(), user.id);
+            state
+                .posts
+                .insert(PostId(id), post(id, user.id, "Breaking #news"));
+            state
+                .post_engagement_daily
+                .entry(PostId(id))
+                .or_default()
+                .insert(
+                    0,
+                    EngagementCounts {
+                        likes: 1,
+                        ..EngagementCounts::default()
+                    },
+                );
+            state
+                .located_posts
+                .entry((0, 0))
+                .or_default()
+                .insert(PostId(id));
+            state.users.insert(user.id, user);
+        }
+        state
+    }
+
+    fn with_discoverability_state<T>(f: impl FnOnce() -> T) -> T {
+        STATE.with(|state| *state.borrow_mut() = discoverability_state());
+        f()
+    }
+
+    fn body(response: HttpResponse) -> String {
+        String::from_utf8(response.body).unwrap()
+    }
+
+    #[test]
+    fn test_tag_feed_leaves_out_opted_out_authors() {
+        let feed = body(with_discoverability_state(|| {
+            tag_feed_response("news", BASE_URL)
+        }));
+        assert!(feed.contains(&format!("{BASE_URL}/posts/1")));
+        assert!(!feed.contains(&format!("{BASE_URL}/posts/2")));
+    }
+
+    #[test]
+    fn test_sitemap_leaves_out_opted_out_users() {
+        let sitemap = body(with_discoverability_state(|| sitemap_response(BASE_URL)));
+        assert!(sitemap.contains(&format!("{BASE_URL}/users/alice")));
+        assert!(sitemap.contains(&format!("{BASE_URL}/posts/1")));
+        assert!(!sitemap.contains(&format!("{BASE_URL}/users/bob")));
+        assert!(!sitemap.contains(&format!("{BASE_URL}/posts/2")));
+    }
+
+    #[test]
+    fn test_explore_leaves_out_opted_out_authors() {
+        let state = discoverability_state();
+        let listed: Vec<PostId> = explore_listing(&state, None, 0)
+            .into_iter()
+            .map(|(post, _)| post.id)
+            .collect();
+        assert_eq!(listed, vec![PostId(1)]);
+    }
+
+    #[test]
+    fn test_hot_cache_leaves_out_opted_out_users() {
+        let mut state = discoverability_state();
+        // Rendering posts reads the canister clock, so leave the explore
+        // feed empty
+        state.post_engagement_daily.clear();
+        refresh_hot_cache(&mut state, 0);
+
+        let alice = &state.users[&UserId(Principal::from_slice(&[1]))];
+        let bob = &state.users[&UserId(Principal::from_slice(&[2]))];
+        assert!(state.hot_cache.profile(alice).is_some());
+        assert!(state.hot_cache.profile(bob).is_none());
+    }
+
+    #[test]
+    fn test_webfinger_leaves_out_opted_out_users() {
+        let state = discoverability_state();
+        let domain = "decentra.example";
+        let found = |handle: &str| {
+            webfinger_profile(&state, &format!("acct:{handle}@{domain}"), domain, 0)
+                .map(|profile| profile.handle.clone())
+        };
+        assert_eq!(found("alice").as_deref(), Some("alice"));
+        assert_eq!(found("bob"), None);
+    }
+
+    #[test]
+    fn test_posts_near_leaves_out_opted_out_authors() {
+        let state = discoverability_state();
+        let region = GeoRegion {
+            south_west: GeoPoint {
+                latitude: -1.0,
+                longitude: -1.0,
+            },
+            north_east: GeoPoint {
+                latitude: 1.0,
+                longitude: 1.0,
+            },
+        };
+        let listed: Vec<PostId> = posts_near(&state, &region, None)
+            .into_iter()
+            .map(|(post, _)| post.id)
+            .collect();
+        assert_eq!(listed, vec![PostId(1)]);
+    }
+
+    #[test]
+    fn test_username_check_proof_is_bound_to_caller_and_bucket() {
+        STATE.with(|state| {
+            *state.borrow_mut() = SocialNetworkState {
+                username_check_difficulty: 8,
+                ..SocialNetworkState::default()
+            }
+        });
+        let bucket_length = USERNAME_CHECK_POW_BUCKET_SECONDS * 1_000_000_000;
+        let now = 10 * bucket_length;
+        let solve = |caller: Principal| {
+            let subject = username_check_subject(caller, username_check_bucket(now), "Alice");
+            (0..)
+                .find(|&nonce| security_utils::verify_proof_of_work(&subject, nonce, 8))
+                .unwrap()
+        };
+        let anonymous = Principal::anonymous();
+        let nonce = solve(anonymous);
+
+        assert!(screen_username_check(anonymous, "alice", Some(nonce), now).is_ok());
+        assert!(
+            screen_username_check(anonymous, "alice", Some(nonce), now + bucket_length).is_ok()
+        );
+        assert!(
+            screen_username_check(anonymous, "alice", Some(nonce), now + 2 * bucket_length)
+                .is_err()
+        );
+        assert!(screen_username_check(anonymous, "alice", None, now).is_err());
+
+        let signed_in = Principal::from_slice(&[1]);
+        assert!(screen_username_check(signed_in, "alice", None, now).is_ok());
+    }
+
+    #[test]
+    fn test_purging_thread_root_re_roots_thread() {
+        let author_id = UserId(Principal::from_slice(&[1]));
+        let mut state = SocialNetworkState::default();
+        for id in 1..=3 {
+            state
+                .posts
+                .insert(PostId(id), post(id, author_id, &format!("part {id}")));
+            state.thread_roots.insert(PostId(id), PostId(1));
+        }
+        state
+            .threads
+            .insert(PostId(1), vec![PostId(1), PostId(2), PostId(3)]);
+
+        let root = state.posts.remove(&PostId(1)).unwrap();
+        purge_post(&mut state, &root);
+
+        assert_eq!(
+            state.threads.get(&PostId(2)),
+            Some(&vec![PostId(2), PostId(3)])
+        );
+        assert!(!state.threads.contains_key(&PostId(1)));
+        let position = thread_position(&state, PostId(3)).unwrap();
+        assert_eq!((position.root_id, position.index), (PostId(2), 1));
+    }
+}
@@ -0,0 +1,195 @@
+use crate::types::{LinkSafetyConfig, LinkWarning};
+use ic_cdk::api::management_canister::http_request::{
+    http_request, CanisterHttpRequestArgument, HttpHeader, HttpMethod,
+    HttpResponse as OutcallResponse, TransformArgs, TransformContext,
+};
+use serde_json::{json, Value};
+
+/// Maximum number of links checked per post
+pub const MAX_LINKS_CHECKED: usize = 10;
+
+/// Canister query that reduces safety responses to their deterministic part
+pub const TRANSFORM_FUNCTION: &str = "transform_link_safety_response";
+
+/// Threat categories requested from the safety service
+const THREAT_TYPES: [&str; 4] = [
+    "MALWARE",
+    "SOCIAL_ENGINEERING",
+    "UNWANTED_SOFTWARE",
+    "POTENTIALLY_HARMFUL_APPLICATION",
+];
+
+/// Largest safety response read by outcalls
+const MAX_SAFETY_RESPONSE_BYTES: u64 = 16 * 1024;
+
+/// Cycles attached to each safety outcall (unused cycles are refunded)
+const LINK_SAFETY_OUTCALL_CYCLES: u128 = 20_000_000_000;
+
+// ============================================================================
+// SAFE BROWSING API
+// ============================================================================
+
+/// Extracts distinct http(s) links from post content
+pub fn extract_urls(content: &str) -> Vec<String> {
+    let mut urls: Vec<String> = Vec::new();
+    for token in content.split_whitespace() {
+        let Some(start) = token.find("https://").or_else(|| token.find("http://")) else {
+            continue;
+        };
+        let url = token[start..].trim_end_matches(|c: char| ".,;:!?)]}>\"'".contains(c));
+        if !urls.iter().any(|existing| existing == url) {
+            urls.push(url.to_string());
+        }
+        if urls.len() == MAX_LINKS_CHECKED {
+            break;
+        }
+    }
+    urls
+}
+
+/// Builds a Safe Browsing v4 `threatMatches:find` request body
+pub fn request_body(urls: &[String]) -> Vec<u8> {
+    let entries: Vec<Value> = urls.iter().map(|url| json!({ "url": url })).collect();
+    json!({
+        "client": { "clientId": "decentra", "clientVersion": "1.0" },
+        "threatInfo": {
+            "threatTypes": THREAT_TYPES,
+            "platformTypes": ["ANY_PLATFORM"],
+            "threatEntryTypes": ["URL"],
+            "threatEntries": entries,
+        },
+    })
+    .to_string()
+    .into_bytes()
+}
+
+/// Extracts flagged links from a `threatMatches:find` response
+///
+/// An empty object means no link matched.
+pub fn parse_response(body: &[u8]) -> Result<Vec<LinkWarning>, String> {
+    let response: Value =
+        serde_json::from_slice(body).map_err(|_| "Invalid link safety response")?;
+    let Some(matches) = response["matches"].as_array() else {
+        return Ok(Vec::new());
+    };
+
+    let mut warnings: Vec<LinkWarning> = matches
+        .iter()
+        .filter_map(|entry| {
+            Some(LinkWarning {
+                url: entry["threat"]["url"].as_str()?.to_string(),
+                threat_type: entry["threatType"].as_str()?.to_string(),
+            })
+        })
+        .collect();
+    warnings.sort_by(|a, b| (&a.url, &a.threat_type).cmp(&(&b.url, &b.threat_type)));
+    warnings.dedup_by(|a, b| a.url == b.url && a.threat_type == b.threat_type);
+    Ok(warnings)
+}
+
+/// Checks links against the configured safety service
+pub async fn check_urls(
+    config: &LinkSafetyConfig,
+    urls: &[String],
+) -> Result<Vec<LinkWarning>, String> {
+    let separator = if config.endpoint.contains('?') {
+        '&'
+    } else {
+        '?'
+    };
+    let request = CanisterHttpRequestArgument {
+        url: format!("{}{separator}key={}", config.endpoint, config.api_key),
+        max_response_bytes: Some(MAX_SAFETY_RESPONSE_BYTES),
+        method: HttpMethod::POST,
+        headers: vec![HttpHeader {
+            name: "Content-Type".to_string(),
+            value: "application/json".to_string(),
+        }],
+        body: Some(request_body(urls)),
+        transform: Some(TransformContext::from_name(
+            TRANSFORM_FUNCTION.to_string(),
+            Vec::new(),
+        )),
+    };
+
+    let (response,) = http_request(request, LINK_SAFETY_OUTCALL_CYCLES)
+        .await
+        .map_err(|(code, msg)| format!("Link safety request failed: {code:?} {msg}"))?;
+    if response.status != 200u16 {
+        return Err(format!(
+            "Link safety service returned status {}",
+            response.status
+        ));
+    }
+    parse_response(&response.body)
+}
+
+/// Keeps only the flagged links so replicas agree despite varying metadata
+/// (such as cache durations) in the service response
+pub fn transform_response(args: TransformArgs) -> OutcallResponse {
+    let body = match parse_response(&args.response.body) {
+        Ok(warnings) => {
+            let matches: Vec<Value> = warnings
+                .iter()
+                .map(|warning| {
+                    json!({ "threatType": warning.threat_type, "threat": { "url": warning.url } })
+                })
+                .collect();
+            json!({ "matches": matches }).to_string().into_bytes()
+        }
+        Err(_) => Vec::new(),
+    };
+
+    OutcallResponse {
+        status: args.response.status,
+        headers: Vec::new(),
+        body,
+    }
+}
+
+// ============================================================================
+// LINK SAFETY TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_urls() {
+        let urls = extract_urls(
+            "Read https://news.example/a, and (http://blog.example/b). Again https://news.example/a",
+        );
+        assert_eq!(
+            urls,
+            vec!["https://news.example/a", "http://blog.example/b"]
+        );
+        assert!(extract_urls("no links at all").is_empty());
+    }
+
+    #[test]
+    fn test_request_body() {
+        let body: Value =
+            serde_json::from_slice(&request_body(&["https://a.example".to_string()])).unwrap();
+        assert_eq!(
+            body["threatInfo"]["threatEntries"][0]["url"],
+            "https://a.example"
+        );
+    }
+
+    #[test]
+    fn test_parse_response() {
+        assert!(parse_response(b"{}").unwrap().is_empty());
+
+        let body = br#"{"matches":[
+            {"threatType":"MALWARE","threat":{"url":"https://bad.example"},"cacheDuration":"300s"},
+            {"threatType":"MALWARE","threat":{"url":"https://bad.example"},"cacheDuration":"299s"}
+        ]}"#;
+        let warnings = parse_response(body).unwrap();
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].url, "https://bad.example");
+        assert_eq!(warnings[0].threat_type, "MALWARE");
+
+        assert!(parse_response(b"<html>").is_err());
+    }
+}
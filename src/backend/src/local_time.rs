@@ -0,0 +1,120 @@
+use crate::http::{civil_from_days, days_from_civil};
+use crate::types::NANOS_PER_DAY;
+
+const NANOS_PER_MINUTE: i64 = 60_000_000_000;
+const NANOS_PER_HOUR: u64 = 3_600_000_000_000;
+
+/// Shifts a UTC timestamp onto the wall clock at `utc_offset_minutes`
+fn to_local(timestamp: u64, utc_offset_minutes: i16) -> u64 {
+    timestamp.saturating_add_signed(i64::from(utc_offset_minutes) * NANOS_PER_MINUTE)
+}
+
+/// Shifts a wall-clock time at `utc_offset_minutes` back to UTC
+fn to_utc(local: u64, utc_offset_minutes: i16) -> u64 {
+    local.saturating_add_signed(-i64::from(utc_offset_minutes) * NANOS_PER_MINUTE)
+}
+
+/// Day number since the Unix epoch on the local calendar
+pub fn local_day(timestamp: u64, utc_offset_minutes: i16) -> u64 {
+    to_local(timestamp, utc_offset_minutes) / NANOS_PER_DAY
+}
+
+/// Parses a local `YYYY-MM-DDTHH:MM` date-time into a UTC timestamp
+///
+/// # Returns
+/// Nanoseconds since the epoch, or `None` for malformed or impossible dates
+pub fn parse_local_date_time(text: &str, utc_offset_minutes: i16) -> Option<u64> {
+    let bytes = text.as_bytes();
+    if bytes.len() != 16
+        || bytes[4] != b'-'
+        || bytes[7] != b'-'
+        || bytes[10] != b'T'
+        || bytes[13] != b':'
+    {
+        return None;
+    }
+    let field = |range: std::ops::Range<usize>| {
+        bytes[range].iter().try_fold(0u64, |value, &b| {
+            b.is_ascii_digit().then(|| value * 10 + u64::from(b - b'0'))
+        })
+    };
+    let (year, month, day) = (field(0..4)?, field(5..7)?, field(8..10)?);
+    let (hour, minute) = (field(11..13)?, field(14..16)?);
+    if year < 1970 || !(1..=12).contains(&month) || hour > 23 || minute > 59 {
+        return None;
+    }
+
+    // Round-tripping rejects days past the end of the month
+    let days = days_from_civil(year, month, day);
+    if day == 0 || civil_from_days(days) != (year, month, day) {
+        return None;
+    }
+    let local = days * NANOS_PER_DAY + hour * NANOS_PER_HOUR + minute * 60_000_000_000;
+    Some(to_utc(local, utc_offset_minutes))
+}
+
+/// Most recent time, at or before `now`, that the local clock read `hour`:00
+pub fn last_local_hour(now: u64, utc_offset_minutes: i16, hour: u8) -> u64 {
+    let local = to_local(now, utc_offset_minutes);
+    let boundary = local / NANOS_PER_DAY * NANOS_PER_DAY + u64::from(hour) * NANOS_PER_HOUR;
+    let boundary = if boundary > local {
+        boundary.saturating_sub(NANOS_PER_DAY)
+    } else {
+        boundary
+    };
+    to_utc(boundary, utc_offset_minutes)
+}
+
+// ============================================================================
+// LOCAL TIME TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HOUR: u64 = NANOS_PER_HOUR;
+
+    #[test]
+    fn test_local_day() {
+        // 23:00 UTC on day 100 is already day 101 in UTC+2 and still day 100 in UTC-5
+        let now = 100 * NANOS_PER_DAY + 23 * HOUR;
+        assert_eq!(local_day(now, 0), 100);
+        assert_eq!(local_day(now, 120), 101);
+        assert_eq!(local_day(now, -300), 100);
+        assert_eq!(local_day(2 * HOUR, -300), 0);
+    }
+
+    #[test]
+    fn test_parse_local_date_time() {
+        let utc = parse_local_date_time("2024-03-01T09:30", 0).unwrap();
+        assert_eq!(utc, 1_709_285_400 * 1_000_000_000);
+        assert_eq!(
+            parse_local_date_time("2024-03-01T09:30", 60),
+            Some(utc - HOUR)
+        );
+        assert_eq!(
+            parse_local_date_time("2024-03-01T09:30", -300),
+            Some(utc + 5 * HOUR)
+        );
+        assert!(parse_local_date_time("2024-02-29T00:00", 0).is_some());
+        assert!(parse_local_date_time("2023-02-29T00:00", 0).is_none());
+        assert!(parse_local_date_time("2024-13-01T00:00", 0).is_none());
+        assert!(parse_local_date_time("2024-03-01T24:00", 0).is_none());
+        assert!(parse_local_date_time("2024-3-01T09:30", 0).is_none());
+        assert!(parse_local_date_time("2024-03-01T09:30:00", 0).is_none());
+        assert!(parse_local_date_time("2024-03-01 09:30", 0).is_none());
+    }
+
+    #[test]
+    fn test_last_local_hour() {
+        let day = 100 * NANOS_PER_DAY;
+        // 10:00 UTC: today's 08:00 UTC has passed, but 08:00 in UTC-5 (13:00 UTC) has not
+        assert_eq!(last_local_hour(day + 10 * HOUR, 0, 8), day + 8 * HOUR);
+        assert_eq!(
+            last_local_hour(day + 10 * HOUR, -300, 8),
+            day - NANOS_PER_DAY + 13 * HOUR
+        );
+        assert_eq!(last_local_hour(day + 8 * HOUR, 0, 8), day + 8 * HOUR);
+    }
+}
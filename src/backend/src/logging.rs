@@ -0,0 +1,125 @@
+use crate::types::{
+    AuditAction, LogCategory, LogEntry, LogLevel, MAX_LOG_ENTRIES, MAX_LOG_MESSAGE_LENGTH,
+};
+use candid::{CandidType, Deserialize, Principal};
+
+/// Bounded in-canister log; the oldest entries are dropped first
+#[derive(CandidType, Deserialize, Clone, Debug, Default)]
+pub struct LogBuffer {
+    entries: Vec<LogEntry>,
+    next_id: u64,
+}
+
+impl LogBuffer {
+    /// Appends an entry, truncating long messages
+    pub fn push(
+        &mut self,
+        level: LogLevel,
+        category: LogCategory,
+        principal: Option<Principal>,
+        message: String,
+        timestamp: u64,
+    ) {
+        let message = match message.char_indices().nth(MAX_LOG_MESSAGE_LENGTH) {
+            Some((end, _)) => format!("{}…", &message[..end]),
+            None => message,
+        };
+        self.entries.push(LogEntry {
+            id: self.next_id,
+            timestamp,
+            level,
+            category,
+            principal,
+            message,
+        });
+        self.next_id += 1;
+
+        if self.entries.len() > MAX_LOG_ENTRIES {
+            let excess = self.entries.len() - MAX_LOG_ENTRIES;
+            self.entries.drain(..excess);
+        }
+    }
+
+    /// Entries at or after `since` with at least `min_level`, oldest first
+    pub fn query(&self, since: u64, min_level: LogLevel) -> impl Iterator<Item = &LogEntry> {
+        let start = self
+            .entries
+            .partition_point(|entry| entry.timestamp < since);
+        self.entries[start..]
+            .iter()
+            .filter(move |entry| entry.level >= min_level)
+    }
+}
+
+/// Log category of an audited action
+pub fn audit_category(action: &AuditAction) -> LogCategory {
+    if matches!(
+        action,
+        AuditAction::VerificationApproved
+            | AuditAction::VerificationRejected
+            | AuditAction::StrikeIssued
+            | AuditAction::StrikeRevoked
+            | AuditAction::BlockedDomainAdded
+            | AuditAction::BlockedDomainRemoved
+            | AuditAction::CustomEmojiRemoved
+            | AuditAction::FactCheckAppealResolved
+    ) {
+        LogCategory::Moderation
+    } else {
+        LogCategory::Admin
+    }
+}
+
+// ============================================================================
+// LOGGING TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn log(buffer: &mut LogBuffer, level: LogLevel, timestamp: u64) {
+        buffer.push(
+            level,
+            LogCategory::System,
+            None,
+            format!("at {timestamp}"),
+            timestamp,
+        );
+    }
+
+    #[test]
+    fn test_query_filters_by_time_and_level() {
+        let mut buffer = LogBuffer::default();
+        log(&mut buffer, LogLevel::Info, 10);
+        log(&mut buffer, LogLevel::Warn, 20);
+        log(&mut buffer, LogLevel::Debug, 30);
+        log(&mut buffer, LogLevel::Error, 40);
+
+        let ids: Vec<u64> = buffer.query(20, LogLevel::Info).map(|e| e.id).collect();
+        assert_eq!(ids, vec![1, 3]);
+        assert_eq!(buffer.query(0, LogLevel::Debug).count(), 4);
+        assert_eq!(buffer.query(41, LogLevel::Debug).count(), 0);
+    }
+
+    #[test]
+    fn test_buffer_is_bounded() {
+        let mut buffer = LogBuffer::default();
+        for timestamp in 0..(MAX_LOG_ENTRIES as u64 + 5) {
+            log(&mut buffer, LogLevel::Info, timestamp);
+        }
+        let entries: Vec<&LogEntry> = buffer.query(0, LogLevel::Debug).collect();
+        assert_eq!(entries.len(), MAX_LOG_ENTRIES);
+        assert_eq!(entries[0].id, 5);
+
+        buffer.push(
+            LogLevel::Info,
+            LogCategory::System,
+            None,
+            "x".repeat(MAX_LOG_MESSAGE_LENGTH + 10),
+            u64::MAX,
+        );
+        let last = buffer.query(u64::MAX, LogLevel::Debug).next().unwrap();
+        assert_eq!(last.message.chars().count(), MAX_LOG_MESSAGE_LENGTH + 1);
+    }
+}
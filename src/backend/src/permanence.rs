@@ -0,0 +1,149 @@
+use crate::rsa::RsaPrivateKey;
+use crate::types::{ExportConfig, Post};
+use candid::Principal;
+use data_encoding::BASE64;
+use ic_cdk::api::management_canister::http_request::{
+    http_request, CanisterHttpRequestArgument, HttpHeader, HttpMethod, TransformContext,
+};
+use serde_json::{json, Value};
+
+/// Largest permanence service response read by outcalls
+const MAX_EXPORT_RESPONSE_BYTES: u64 = 16 * 1024;
+
+/// Cycles attached to each export outcall (unused cycles are refunded)
+const EXPORT_OUTCALL_CYCLES: u128 = 30_000_000_000;
+
+// ============================================================================
+// SIGNED BUNDLES
+// ============================================================================
+
+/// Serializes the public fields of a post (content uncompressed)
+///
+/// Object keys are sorted, so the same post always yields the same bytes.
+pub fn payload(post: &Post, canister_id: Principal) -> Vec<u8> {
+    let sources: Vec<Value> = post
+        .sources
+        .iter()
+        .map(|source| {
+            json!({
+                "url": source.url,
+                "title": source.title,
+                "archived_sha256": source.archived_sha256,
+            })
+        })
+        .collect();
+    json!({
+        "canister": canister_id.to_text(),
+        "id": post.id.0,
+        "author": post.author_id.0.to_text(),
+        "content": post.content,
+        "language": post.language,
+        "created_at": post.created_at,
+        "sources": sources,
+    })
+    .to_string()
+    .into_bytes()
+}
+
+/// Wraps a payload with an RSA-SHA256 signature over its exact bytes
+///
+/// The payload is embedded as a string so verifiers check the signed bytes
+/// rather than a re-serialization.
+pub fn signed_bundle(payload: &[u8], private_key_pem: &str) -> Result<Vec<u8>, String> {
+    let signature = RsaPrivateKey::from_pem(private_key_pem)?.sign_sha256(payload)?;
+    let payload = std::str::from_utf8(payload).map_err(|_| "Payload is not UTF-8")?;
+    Ok(json!({
+        "algorithm": "rsa-sha256",
+        "payload": payload,
+        "signature": BASE64.encode(&signature),
+    })
+    .to_string()
+    .into_bytes())
+}
+
+/// Extracts where the service stored a bundle
+///
+/// Accepts `cid` (IPFS), `IpfsHash` (pinning services) or `id` (Arweave).
+pub fn parse_response(body: &[u8]) -> Result<String, String> {
+    let response: Value = serde_json::from_slice(body).map_err(|_| "Invalid export response")?;
+    if let Some(error) = response["error"].as_str() {
+        return Err(format!("Export failed: {error}"));
+    }
+    ["cid", "IpfsHash", "id"]
+        .iter()
+        .find_map(|key| response[key].as_str())
+        .filter(|locator| !locator.is_empty())
+        .map(str::to_string)
+        .ok_or_else(|| "Export response has no content identifier".to_string())
+}
+
+// ============================================================================
+// PERMANENCE SERVICE
+// ============================================================================
+
+/// Uploads a signed bundle through an HTTPS outcall
+///
+/// Responses go through the shared header-stripping transform, so the service
+/// must return the same body to every replica (content-addressed identifiers do).
+pub async fn upload(config: &ExportConfig, bundle: Vec<u8>) -> Result<String, String> {
+    let mut headers = vec![HttpHeader {
+        name: "Content-Type".to_string(),
+        value: "application/json".to_string(),
+    }];
+    if !config.api_key.is_empty() {
+        headers.push(HttpHeader {
+            name: "Authorization".to_string(),
+            value: format!("Bearer {}", config.api_key),
+        });
+    }
+    let request = CanisterHttpRequestArgument {
+        url: config.endpoint.clone(),
+        max_response_bytes: Some(MAX_EXPORT_RESPONSE_BYTES),
+        method: HttpMethod::POST,
+        headers,
+        body: Some(bundle),
+        transform: Some(TransformContext::from_name(
+            crate::activitypub::TRANSFORM_FUNCTION.to_string(),
+            Vec::new(),
+        )),
+    };
+
+    let (response,) = http_request(request, EXPORT_OUTCALL_CYCLES)
+        .await
+        .map_err(|(code, msg)| format!("Export request failed: {code:?} {msg}"))?;
+    if response.status != 200u16 && response.status != 201u16 {
+        return Err(format!(
+            "Export service returned status {}",
+            response.status
+        ));
+    }
+    parse_response(&response.body)
+}
+
+// ============================================================================
+// PERMANENT EXPORT TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_response() {
+        assert_eq!(
+            parse_response(
+                br#"{"cid":"bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi"}"#
+            )
+            .as_deref(),
+            Ok("bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi")
+        );
+        assert_eq!(
+            parse_response(br#"{"IpfsHash":"QmYwAPJzv5CZsnA"}"#).as_deref(),
+            Ok("QmYwAPJzv5CZsnA")
+        );
+        assert_eq!(parse_response(br#"{"id":"tx1"}"#).as_deref(), Ok("tx1"));
+        assert!(parse_response(br#"{"cid":""}"#).is_err());
+        assert!(parse_response(br#"{"error":"unauthorized"}"#).is_err());
+        assert!(parse_response(b"not json").is_err());
+    }
+}
@@ -0,0 +1,95 @@
+use crate::types::{EngagementEvent, UserId};
+use candid::{CandidType, Deserialize};
+use sha2::{Digest, Sha256};
+
+/// Subaccount of the canister receiving point purchase proceeds
+pub const SALES_SUBACCOUNT: [u8; 32] = *b"decentra-points-sales\0\0\0\0\0\0\0\0\0\0\0";
+
+/// Domain separator for per-user purchase deposit subaccounts
+const DEPOSIT_DOMAIN: &[u8] = b"decentra-points-deposit";
+
+/// Points earned by a post's author for one engagement event
+///
+/// Likes can be toggled off and on again, so only comments and reposts earn.
+pub fn engagement_reward(event: EngagementEvent) -> u64 {
+    match event {
+        EngagementEvent::Comment => 2,
+        EngagementEvent::Repost => 3,
+        EngagementEvent::Like | EngagementEvent::Unlike | EngagementEvent::View => 0,
+    }
+}
+
+/// Subaccount of the canister where `user` deposits tokens to buy points
+pub fn deposit_subaccount(user: &UserId) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(DEPOSIT_DOMAIN);
+    hasher.update(user.0.as_slice());
+    hasher.finalize().into()
+}
+
+/// Points a user earned and sent on one day, for the daily limits
+#[derive(CandidType, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct DailyUsage {
+    /// Day (days since epoch) the counters belong to
+    pub day: u64,
+    pub earned: u64,
+    pub sent: u64,
+}
+
+impl DailyUsage {
+    /// Counters for `day`, reset when a new day has started
+    pub fn on(&mut self, day: u64) -> &mut Self {
+        if self.day != day {
+            *self = Self {
+                day,
+                ..Self::default()
+            };
+        }
+        self
+    }
+}
+
+// ============================================================================
+// POINTS TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use candid::Principal;
+
+    #[test]
+    fn test_daily_usage_resets_on_new_day() {
+        let mut usage = DailyUsage::default();
+        usage.on(10).sent = 500;
+        usage.on(10).earned += 4;
+        assert_eq!(usage.on(10).sent, 500);
+        assert_eq!(usage.on(10).earned, 4);
+
+        assert_eq!(
+            *usage.on(11),
+            DailyUsage {
+                day: 11,
+                earned: 0,
+                sent: 0
+            }
+        );
+    }
+
+    #[test]
+    fn test_deposit_subaccounts_are_per_user() {
+        let alice = UserId(Principal::from_slice(&[1; 29]));
+        let bob = UserId(Principal::from_slice(&[2; 29]));
+        assert_eq!(deposit_subaccount(&alice), deposit_subaccount(&alice));
+        assert_ne!(deposit_subaccount(&alice), deposit_subaccount(&bob));
+        assert_ne!(deposit_subaccount(&alice), SALES_SUBACCOUNT);
+    }
+
+    #[test]
+    fn test_only_effortful_engagement_earns() {
+        assert_eq!(engagement_reward(EngagementEvent::Like), 0);
+        assert_eq!(engagement_reward(EngagementEvent::View), 0);
+        assert_eq!(engagement_reward(EngagementEvent::Comment), 2);
+        assert_eq!(engagement_reward(EngagementEvent::Repost), 3);
+    }
+}
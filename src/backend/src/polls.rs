@@ -0,0 +1,197 @@
+use crate::types::{PollMode, RunoffRound};
+
+/// Outcome of counting a poll's ballots
+#[derive(Debug, PartialEq, Eq)]
+pub struct Tally {
+    /// Voters per option; first preferences for ranked polls
+    pub votes: Vec<u64>,
+
+    /// Instant-runoff rounds, for ranked polls only
+    pub rounds: Vec<RunoffRound>,
+
+    pub winner: Option<u32>,
+}
+
+/// Counts validated ballots under the poll's mode
+///
+/// Single-choice and multi-select polls are won by the option with the most
+/// voters; ranked polls by instant runoff.
+pub fn tally<'a>(
+    mode: PollMode,
+    option_count: usize,
+    ballots: impl Iterator<Item = &'a [u32]>,
+) -> Tally {
+    let ballots: Vec<&[u32]> = ballots.collect();
+    let mut votes = vec![0u64; option_count];
+    for ballot in &ballots {
+        let counted = match mode {
+            PollMode::RankedChoice => &ballot[..ballot.len().min(1)],
+            _ => ballot,
+        };
+        for &choice in counted {
+            votes[choice as usize] += 1;
+        }
+    }
+
+    match mode {
+        PollMode::RankedChoice => {
+            let (rounds, winner) = instant_runoff(option_count, &ballots);
+            Tally {
+                votes,
+                rounds,
+                winner,
+            }
+        }
+        _ => Tally {
+            winner: plurality_winner(&votes),
+            votes,
+            rounds: Vec::new(),
+        },
+    }
+}
+
+/// Option with strictly the most votes
+fn plurality_winner(votes: &[u64]) -> Option<u32> {
+    let top = *votes.iter().max()?;
+    let mut leaders = votes.iter().enumerate().filter(|(_, count)| **count == top);
+    match (leaders.next(), leaders.next()) {
+        (Some((index, _)), None) if top > 0 => Some(index as u32),
+        _ => None,
+    }
+}
+
+/// Runs instant-runoff rounds until an option holds a majority of the
+/// ballots still counting
+///
+/// Each round the option with the fewest ballots is eliminated and those
+/// ballots move to their next remaining preference. Ties for last place are
+/// broken by first-round counts, then by dropping the later-listed option.
+fn instant_runoff(option_count: usize, ballots: &[&[u32]]) -> (Vec<RunoffRound>, Option<u32>) {
+    let mut remaining = vec![true; option_count];
+    let mut rounds: Vec<RunoffRound> = Vec::new();
+
+    loop {
+        let mut counts = vec![0u64; option_count];
+        let mut exhausted = 0;
+        for ballot in ballots {
+            match ballot.iter().find(|choice| remaining[**choice as usize]) {
+                Some(&choice) => counts[choice as usize] += 1,
+                None => exhausted += 1,
+            }
+        }
+        let continuing: u64 = counts.iter().sum();
+        if continuing == 0 {
+            rounds.push(RunoffRound {
+                counts,
+                exhausted,
+                eliminated: None,
+            });
+            return (rounds, None);
+        }
+
+        let leader = (0..option_count)
+            .filter(|&option| remaining[option])
+            .max_by_key(|&option| (counts[option], std::cmp::Reverse(option)))
+            .unwrap_or_default();
+        if counts[leader] * 2 > continuing {
+            rounds.push(RunoffRound {
+                counts,
+                exhausted,
+                eliminated: None,
+            });
+            return (rounds, Some(leader as u32));
+        }
+
+        let first_round = rounds.first().map_or(&counts, |round| &round.counts);
+        let loser = (0..option_count)
+            .filter(|&option| remaining[option])
+            .min_by_key(|&option| {
+                (
+                    counts[option],
+                    first_round[option],
+                    std::cmp::Reverse(option),
+                )
+            })
+            .unwrap_or_default();
+        remaining[loser] = false;
+        rounds.push(RunoffRound {
+            counts,
+            exhausted,
+            eliminated: Some(loser as u32),
+        });
+    }
+}
+
+// ============================================================================
+// POLL TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn count(mode: PollMode, option_count: usize, ballots: &[&[u32]]) -> Tally {
+        tally(mode, option_count, ballots.iter().copied())
+    }
+
+    #[test]
+    fn test_single_and_multi_select_tally() {
+        let single = count(PollMode::SingleChoice, 3, &[&[0], &[2], &[2]]);
+        assert_eq!(single.votes, vec![1, 0, 2]);
+        assert_eq!(single.winner, Some(2));
+        assert!(single.rounds.is_empty());
+
+        let multi = count(
+            PollMode::MultiSelect { max_choices: 2 },
+            3,
+            &[&[0, 1], &[1, 2], &[0]],
+        );
+        assert_eq!(multi.votes, vec![2, 2, 1]);
+        assert_eq!(multi.winner, None);
+
+        assert_eq!(count(PollMode::SingleChoice, 2, &[]).winner, None);
+    }
+
+    #[test]
+    fn test_instant_runoff_transfers_preferences() {
+        // Option 2 leads on first preferences, but option 1 wins once option
+        // 0 is eliminated and its ballots move to their second preference
+        let ballots: &[&[u32]] = &[&[2], &[2], &[2], &[2], &[1], &[1], &[1], &[0, 1], &[0, 1]];
+        let result = count(PollMode::RankedChoice, 3, ballots);
+        assert_eq!(result.votes, vec![2, 3, 4]);
+        assert_eq!(result.winner, Some(1));
+        assert_eq!(
+            result.rounds,
+            vec![
+                RunoffRound {
+                    counts: vec![2, 3, 4],
+                    exhausted: 0,
+                    eliminated: Some(0),
+                },
+                RunoffRound {
+                    counts: vec![0, 5, 4],
+                    exhausted: 0,
+                    eliminated: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_instant_runoff_exhausts_ballots() {
+        let ballots: &[&[u32]] = &[&[0], &[0], &[1], &[1], &[2]];
+        let result = count(PollMode::RankedChoice, 3, ballots);
+        assert_eq!(result.rounds[0].eliminated, Some(2));
+        assert_eq!(result.rounds[1].exhausted, 1);
+        // 2-2 among continuing ballots: option 1 drops on the tie-break
+        assert_eq!(result.rounds[1].eliminated, Some(1));
+        assert_eq!(result.winner, Some(0));
+    }
+
+    #[test]
+    fn test_instant_runoff_without_ballots() {
+        let result = count(PollMode::RankedChoice, 3, &[]);
+        assert_eq!(result.winner, None);
+        assert_eq!(result.rounds.len(), 1);
+    }
+}
@@ -0,0 +1,165 @@
+use crate::types::{PendingPush, PushConfig, PushEndpoint, PushKind};
+use ic_cdk::api::management_canister::http_request::{
+    http_request, CanisterHttpRequestArgument, HttpHeader, HttpMethod, TransformContext,
+};
+use serde_json::{json, Value};
+
+/// Largest relay response read by outcalls
+const MAX_PUSH_RESPONSE_BYTES: u64 = 2 * 1024;
+
+/// Cycles attached to each push outcall (unused cycles are refunded)
+const PUSH_OUTCALL_CYCLES: u128 = 20_000_000_000;
+
+/// Delay before the first retry; doubles with each further failure
+const PUSH_RETRY_BASE_SECONDS: u64 = 60;
+
+/// Longest delay between retries
+const MAX_PUSH_RETRY_SECONDS: u64 = 3_600;
+
+/// Result of handing a notification to the relay
+#[derive(Debug, PartialEq, Eq)]
+pub enum Delivery {
+    Delivered,
+
+    /// The relay reports the device unsubscribed; the endpoint should be dropped
+    EndpointGone,
+}
+
+/// Builds the JSON body sent to the relay for one notification
+///
+/// Carries who did what and where, never post or message content; clients
+/// fetch that once opened.
+pub fn payload(push: &PendingPush) -> Vec<u8> {
+    let notification = &push.notification;
+    let kind = match notification.kind {
+        PushKind::Comment => "comment",
+        PushKind::Mention => "mention",
+        PushKind::DirectMessage => "direct_message",
+    };
+    let mut body = json!({
+        "id": push.id,
+        "kind": kind,
+        "actor": notification.actor.0.to_text(),
+        "post_id": notification.post_id.map(|post_id| post_id.0),
+        "conversation_id": notification.conversation_id,
+        "created_at": notification.created_at,
+    });
+    match &push.endpoint {
+        PushEndpoint::WebPush { subscription } => {
+            body["web_push"] = serde_json::from_str(subscription).unwrap_or(Value::Null);
+        }
+        PushEndpoint::RelayToken { token } => {
+            body["token"] = Value::String(token.clone());
+        }
+    }
+    body.to_string().into_bytes()
+}
+
+/// Seconds to wait before retrying after `attempts` failed deliveries
+pub fn retry_delay(attempts: u32) -> u64 {
+    let doublings = attempts.saturating_sub(1).min(16);
+    (PUSH_RETRY_BASE_SECONDS << doublings).min(MAX_PUSH_RETRY_SECONDS)
+}
+
+/// Hands a notification to the relay through an HTTPS outcall
+///
+/// Every replica makes the request, so the relay must deduplicate on the
+/// `Idempotency-Key` header.
+pub async fn send(config: &PushConfig, push: &PendingPush) -> Result<Delivery, String> {
+    let mut headers = vec![
+        HttpHeader {
+            name: "Content-Type".to_string(),
+            value: "application/json".to_string(),
+        },
+        HttpHeader {
+            name: "Idempotency-Key".to_string(),
+            value: format!("push-{}", push.id),
+        },
+    ];
+    if !config.api_key.is_empty() {
+        headers.push(HttpHeader {
+            name: "Authorization".to_string(),
+            value: format!("Bearer {}", config.api_key),
+        });
+    }
+    let request = CanisterHttpRequestArgument {
+        url: config.relay_url.clone(),
+        max_response_bytes: Some(MAX_PUSH_RESPONSE_BYTES),
+        method: HttpMethod::POST,
+        headers,
+        body: Some(payload(push)),
+        transform: Some(TransformContext::from_name(
+            crate::activitypub::TRANSFORM_FUNCTION.to_string(),
+            Vec::new(),
+        )),
+    };
+
+    let (response,) = http_request(request, PUSH_OUTCALL_CYCLES)
+        .await
+        .map_err(|(code, msg)| format!("Push request failed: {code:?} {msg}"))?;
+    if response.status == 404u16 || response.status == 410u16 {
+        return Ok(Delivery::EndpointGone);
+    }
+    if response.status < 200u16 || response.status >= 300u16 {
+        return Err(format!("Push relay returned status {}", response.status));
+    }
+    Ok(Delivery::Delivered)
+}
+
+// ============================================================================
+// PUSH RELAY TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{PostId, PushNotification, UserId};
+    use candid::Principal;
+
+    fn pending(endpoint: PushEndpoint) -> PendingPush {
+        PendingPush {
+            id: 7,
+            recipient: UserId(Principal::anonymous()),
+            endpoint,
+            notification: PushNotification {
+                kind: PushKind::Mention,
+                actor: UserId(Principal::anonymous()),
+                post_id: Some(PostId(42)),
+                conversation_id: None,
+                created_at: 1,
+            },
+            attempts: 0,
+            next_attempt_at: 0,
+        }
+    }
+
+    #[test]
+    fn test_payload() {
+        let body: Value = serde_json::from_slice(&payload(&pending(PushEndpoint::RelayToken {
+            token: "device-1".to_string(),
+        })))
+        .unwrap();
+        assert_eq!(body["kind"], "mention");
+        assert_eq!(body["post_id"], 42);
+        assert_eq!(body["token"], "device-1");
+        assert!(body["conversation_id"].is_null());
+
+        let subscription =
+            r#"{"endpoint":"https://push.example/abc","keys":{"p256dh":"k","auth":"a"}}"#;
+        let body: Value = serde_json::from_slice(&payload(&pending(PushEndpoint::WebPush {
+            subscription: subscription.to_string(),
+        })))
+        .unwrap();
+        assert_eq!(body["web_push"]["endpoint"], "https://push.example/abc");
+        assert!(body.get("token").is_none());
+    }
+
+    #[test]
+    fn test_retry_delay() {
+        assert_eq!(retry_delay(1), 60);
+        assert_eq!(retry_delay(2), 120);
+        assert_eq!(retry_delay(4), 480);
+        assert_eq!(retry_delay(10), MAX_PUSH_RETRY_SECONDS);
+        assert_eq!(retry_delay(u32::MAX), MAX_PUSH_RETRY_SECONDS);
+    }
+}
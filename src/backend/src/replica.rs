@@ -0,0 +1,215 @@
+use crate::types::{CanisterArgs, StateDelta, MAX_REPLICA_DELTAS};
+use candid::{CandidType, Deserialize, Principal};
+use ic_cdk::api::call::call_with_payment128;
+use std::collections::VecDeque;
+
+/// Deltas not yet applied by every read replica, numbered in order
+#[derive(CandidType, Deserialize, Clone, Debug, Default)]
+pub struct DeltaLog {
+    deltas: VecDeque<StateDelta>,
+
+    /// Sequence number of the first delta held
+    first_seq: u64,
+}
+
+impl DeltaLog {
+    /// Appends a delta, dropping the oldest beyond `MAX_REPLICA_DELTAS`
+    pub fn push(&mut self, delta: StateDelta) {
+        self.deltas.push_back(delta);
+        if self.deltas.len() > MAX_REPLICA_DELTAS {
+            self.deltas.pop_front();
+            self.first_seq += 1;
+        }
+    }
+
+    /// Sequence number the next delta will get
+    pub fn next_seq(&self) -> u64 {
+        self.first_seq + self.deltas.len() as u64
+    }
+
+    /// Whether every delta from `seq` on is still held
+    pub fn covers(&self, seq: u64) -> bool {
+        seq >= self.first_seq
+    }
+
+    /// Up to `limit` deltas starting at `seq`
+    pub fn since(&self, seq: u64, limit: usize) -> Vec<StateDelta> {
+        let start = seq.saturating_sub(self.first_seq) as usize;
+        self.deltas
+            .iter()
+            .skip(start)
+            .take(limit)
+            .cloned()
+            .collect()
+    }
+
+    /// Drops deltas before `seq`, once every replica has applied them
+    pub fn trim(&mut self, seq: u64) {
+        let count = seq
+            .saturating_sub(self.first_seq)
+            .min(self.deltas.len() as u64);
+        self.deltas.drain(..count as usize);
+        self.first_seq += count;
+    }
+}
+
+// ============================================================================
+// MANAGEMENT CANISTER INTERFACE
+// ============================================================================
+
+#[derive(CandidType)]
+struct CanisterSettings {
+    controllers: Option<Vec<Principal>>,
+}
+
+#[derive(CandidType)]
+struct CreateCanisterArgs {
+    settings: Option<CanisterSettings>,
+}
+
+#[derive(CandidType, Deserialize)]
+struct CanisterIdRecord {
+    canister_id: Principal,
+}
+
+#[derive(CandidType, Deserialize)]
+enum InstallMode {
+    #[serde(rename = "install")]
+    Install,
+}
+
+#[derive(CandidType)]
+struct InstallCodeArgs {
+    mode: InstallMode,
+    canister_id: Principal,
+    wasm_module: Vec<u8>,
+    arg: Vec<u8>,
+}
+
+/// Creates a canister running `wasm_module` as a read replica of this canister
+///
+/// # Arguments
+/// * `controllers` - Controllers besides this canister
+/// * `cycles` - Cycles moved from this canister to the new one
+///
+/// # Returns
+/// The new replica's canister id
+pub async fn spawn(
+    wasm_module: Vec<u8>,
+    controllers: Vec<Principal>,
+    cycles: u128,
+) -> Result<Principal, String> {
+    let primary = ic_cdk::id();
+    let args = CreateCanisterArgs {
+        settings: Some(CanisterSettings {
+            controllers: Some([vec![primary], controllers].concat()),
+        }),
+    };
+    let (created,): (CanisterIdRecord,) = call_with_payment128(
+        Principal::management_canister(),
+        "create_canister",
+        (args,),
+        cycles,
+    )
+    .await
+    .map_err(|(code, msg)| format!("create_canister failed: {code:?} {msg}"))?;
+
+    let arg = candid::encode_one(Some(CanisterArgs {
+        replica_of: Some(primary),
+        ..CanisterArgs::default()
+    }))
+    .map_err(|error| format!("Encoding replica args failed: {error}"))?;
+    let install = InstallCodeArgs {
+        mode: InstallMode::Install,
+        canister_id: created.canister_id,
+        wasm_module,
+        arg,
+    };
+    ic_cdk::call::<_, ()>(Principal::management_canister(), "install_code", (install,))
+        .await
+        .map_err(|(code, msg)| format!("install_code failed: {code:?} {msg}"))?;
+
+    Ok(created.canister_id)
+}
+
+/// Sends a batch of deltas to a replica
+pub async fn push(replica: Principal, deltas: Vec<StateDelta>) -> Result<(), String> {
+    let (result,): (Result<(), String>,) = ic_cdk::call(replica, "apply_replica_deltas", (deltas,))
+        .await
+        .map_err(|(code, message)| format!("Replica call failed: {code:?} {message}"))?;
+    result.map_err(|error| format!("Replica rejected deltas: {error}"))
+}
+
+// ============================================================================
+// READ REPLICA TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{PrivacySettings, ProfileTheme, UserId, UserProfile, VerificationStatus};
+
+    fn delta(post_count: u64) -> StateDelta {
+        StateDelta::ProfileUpdated(Box::new(UserProfile {
+            id: UserId(Principal::anonymous()),
+            handle: String::new(),
+            display_name: String::new(),
+            bio: String::new(),
+            avatar: String::new(),
+            banner: String::new(),
+            theme: ProfileTheme::default(),
+            created_at: 0,
+            updated_at: 0,
+            follower_count: 0,
+            following_count: 0,
+            post_count,
+            privacy_settings: PrivacySettings::default(),
+            verification_status: VerificationStatus::Unverified,
+            personhood: None,
+            nft_avatar: None,
+            version: 0,
+        }))
+    }
+
+    fn post_counts(deltas: Vec<StateDelta>) -> Vec<u64> {
+        deltas
+            .into_iter()
+            .map(|delta| match delta {
+                StateDelta::ProfileUpdated(profile) => profile.post_count,
+                StateDelta::PostCreated(post) => post.id.0,
+                StateDelta::PostDeleted(post_id) => post_id.0,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_since_and_trim() {
+        let mut log = DeltaLog::default();
+        for n in 0..5 {
+            log.push(delta(n));
+        }
+        assert_eq!(log.next_seq(), 5);
+        assert_eq!(post_counts(log.since(1, 2)), vec![1, 2]);
+
+        log.trim(3);
+        assert!(!log.covers(2));
+        assert!(log.covers(3));
+        assert_eq!(post_counts(log.since(3, 10)), vec![3, 4]);
+        assert!(log.since(5, 10).is_empty());
+
+        log.trim(10);
+        assert_eq!(log.next_seq(), 5);
+        assert!(log.covers(5));
+    }
+
+    #[test]
+    fn test_log_is_bounded() {
+        let mut log = DeltaLog::default();
+        for n in 0..(MAX_REPLICA_DELTAS as u64 + 3) {
+            log.push(delta(n));
+        }
+        assert!(!log.covers(2));
+        assert!(log.covers(3));
+        assert_eq!(post_counts(log.since(3, 1)), vec![3]);
+    }
+}
@@ -0,0 +1,43 @@
+use crate::ledger::Account;
+use crate::types::TokenGate;
+use candid::{Nat, Principal};
+use num_traits::Zero;
+
+// ============================================================================
+// TOKEN GATE LOOKUPS
+// ============================================================================
+
+/// Checks whether `principal` (default subaccount) holds the gate's asset
+///
+/// # Returns
+/// * `Ok(bool)` - Whether the holding requirement is met
+/// * `Err(String)` - The ledger or collection could not be queried
+pub async fn holds(gate: &TokenGate, principal: Principal) -> Result<bool, String> {
+    let account = Account {
+        owner: principal,
+        subaccount: None,
+    };
+
+    match gate {
+        TokenGate::Icrc1 {
+            ledger,
+            min_balance,
+        } => {
+            let (balance,): (Nat,) = ic_cdk::call(*ledger, "icrc1_balance_of", (account,))
+                .await
+                .map_err(|(code, message)| {
+                    format!("ICRC-1 ledger call failed: {code:?} {message}")
+                })?;
+            Ok(balance >= *min_balance)
+        }
+        TokenGate::Icrc7 { collection } => {
+            let (balances,): (Vec<Nat>,) =
+                ic_cdk::call(*collection, "icrc7_balance_of", (vec![account],))
+                    .await
+                    .map_err(|(code, message)| {
+                        format!("ICRC-7 collection call failed: {code:?} {message}")
+                    })?;
+            Ok(balances.first().is_some_and(|balance| !balance.0.is_zero()))
+        }
+    }
+}
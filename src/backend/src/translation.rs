@@ -0,0 +1,111 @@
+use crate::types::TranslationConfig;
+use ic_cdk::api::management_canister::http_request::{
+    http_request, CanisterHttpRequestArgument, HttpHeader, HttpMethod, TransformContext,
+};
+use serde_json::{json, Value};
+
+/// Largest translation response read by outcalls
+const MAX_TRANSLATION_RESPONSE_BYTES: u64 = 128 * 1024;
+
+/// Cycles attached to each translation outcall (unused cycles are refunded)
+const TRANSLATION_OUTCALL_CYCLES: u128 = 30_000_000_000;
+
+// ============================================================================
+// TRANSLATION API
+// ============================================================================
+
+/// Builds a LibreTranslate-compatible request body
+///
+/// The source language is auto-detected by the translation service.
+pub fn request_body(text: &str, target_lang: &str, api_key: &str) -> Vec<u8> {
+    let mut body = json!({
+        "q": text,
+        "source": "auto",
+        "target": target_lang,
+        "format": "text",
+    });
+    if !api_key.is_empty() {
+        body["api_key"] = Value::String(api_key.to_string());
+    }
+    body.to_string().into_bytes()
+}
+
+/// Extracts the translated text from a translation service response
+pub fn parse_response(body: &[u8]) -> Result<String, String> {
+    let response: Value =
+        serde_json::from_slice(body).map_err(|_| "Invalid translation response")?;
+    if let Some(error) = response["error"].as_str() {
+        return Err(format!("Translation failed: {error}"));
+    }
+    response["translatedText"]
+        .as_str()
+        .map(str::to_string)
+        .ok_or_else(|| "Translation response has no translatedText".to_string())
+}
+
+/// Translates `text` into `target_lang` through an HTTPS outcall
+///
+/// Responses go through the shared header-stripping transform so replicas
+/// reach consensus on the body.
+pub async fn translate(
+    config: &TranslationConfig,
+    text: &str,
+    target_lang: &str,
+) -> Result<String, String> {
+    let request = CanisterHttpRequestArgument {
+        url: config.endpoint.clone(),
+        max_response_bytes: Some(MAX_TRANSLATION_RESPONSE_BYTES),
+        method: HttpMethod::POST,
+        headers: vec![HttpHeader {
+            name: "Content-Type".to_string(),
+            value: "application/json".to_string(),
+        }],
+        body: Some(request_body(text, target_lang, &config.api_key)),
+        transform: Some(TransformContext::from_name(
+            crate::activitypub::TRANSFORM_FUNCTION.to_string(),
+            Vec::new(),
+        )),
+    };
+
+    let (response,) = http_request(request, TRANSLATION_OUTCALL_CYCLES)
+        .await
+        .map_err(|(code, msg)| format!("Translation request failed: {code:?} {msg}"))?;
+    if response.status != 200u16 {
+        return Err(format!(
+            "Translation service returned status {}",
+            response.status
+        ));
+    }
+    parse_response(&response.body)
+}
+
+// ============================================================================
+// TRANSLATION TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_request_body() {
+        let body: Value = serde_json::from_slice(&request_body("Hola", "en", "")).unwrap();
+        assert_eq!(body["q"], "Hola");
+        assert_eq!(body["source"], "auto");
+        assert_eq!(body["target"], "en");
+        assert!(body.get("api_key").is_none());
+
+        let keyed: Value = serde_json::from_slice(&request_body("Hola", "en", "secret")).unwrap();
+        assert_eq!(keyed["api_key"], "secret");
+    }
+
+    #[test]
+    fn test_parse_response() {
+        assert_eq!(
+            parse_response(br#"{"translatedText":"Hello"}"#).as_deref(),
+            Ok("Hello")
+        );
+        assert!(parse_response(br#"{"error":"quota exceeded"}"#).is_err());
+        assert!(parse_response(b"not json").is_err());
+    }
+}
@@ -0,0 +1,171 @@
+use crate::http::days_from_civil;
+use crate::types::{
+    AuditAction, AuditLogEntry, ModerationActionCount, ReportPeriod, NANOS_PER_DAY,
+};
+use std::collections::BTreeMap;
+
+/// Start and end of a report period, in nanoseconds (end exclusive)
+///
+/// # Returns
+/// The bounds, or an error for months, quarters or years out of range
+pub fn period_bounds(period: &ReportPeriod) -> Result<(u64, u64), String> {
+    let (year, first_month, months) = match *period {
+        ReportPeriod::Month { year, month } if (1..=12).contains(&month) => (year, month, 1),
+        ReportPeriod::Quarter { year, quarter } if (1..=4).contains(&quarter) => {
+            (year, (quarter - 1) * 3 + 1, 3)
+        }
+        ReportPeriod::Year(year) => (year, 1, 12),
+        ReportPeriod::Month { .. } => return Err("Month must be 1-12".to_string()),
+        ReportPeriod::Quarter { .. } => return Err("Quarter must be 1-4".to_string()),
+    };
+    if !(1970..=9999).contains(&year) {
+        return Err("Year must be 1970-9999".to_string());
+    }
+
+    let day_of = |year: u32, month: u8| days_from_civil(u64::from(year), u64::from(month), 1);
+    let last_month = first_month + months;
+    let end_day = if last_month > 12 {
+        day_of(year + 1, last_month - 12)
+    } else {
+        day_of(year, last_month)
+    };
+    Ok((
+        day_of(year, first_month) * NANOS_PER_DAY,
+        end_day * NANOS_PER_DAY,
+    ))
+}
+
+/// Public category of an audit action, for actions taken against content or
+/// accounts
+///
+/// Configuration and staffing changes have no category and are left out.
+pub fn action_category(action: &AuditAction) -> Option<&'static str> {
+    match action {
+        AuditAction::StrikeIssued => Some("strike_issued"),
+        AuditAction::StrikeRevoked => Some("strike_revoked"),
+        AuditAction::VerificationRevoked => Some("verification_revoked"),
+        AuditAction::BlockedDomainAdded => Some("domain_blocked"),
+        AuditAction::BlockedDomainRemoved => Some("domain_unblocked"),
+        AuditAction::CustomEmojiRemoved => Some("emoji_removed"),
+        AuditAction::FactCheckAppealResolved => Some("fact_check_appeal_resolved"),
+        AuditAction::LegalRequestComplied => Some("legal_takedown"),
+        _ => None,
+    }
+}
+
+/// Counts the moderation actions and legal requests in audit log entries
+///
+/// # Returns
+/// Actions by category (sorted by category), legal requests logged, and how
+/// many of those were complied with
+pub fn summarize<'a>(
+    entries: impl Iterator<Item = &'a AuditLogEntry>,
+) -> (Vec<ModerationActionCount>, u64, u64) {
+    let mut actions: BTreeMap<&str, u64> = BTreeMap::new();
+    let (mut legal_requests, mut complied) = (0u64, 0u64);
+    for entry in entries {
+        if let Some(category) = action_category(&entry.action) {
+            *actions.entry(category).or_default() += 1;
+        }
+        match entry.action {
+            AuditAction::LegalRequestComplied => {
+                legal_requests += 1;
+                complied += 1;
+            }
+            AuditAction::LegalRequestRejected => legal_requests += 1,
+            _ => {}
+        }
+    }
+
+    let actions = actions
+        .into_iter()
+        .map(|(category, count)| ModerationActionCount {
+            category: category.to_string(),
+            count,
+        })
+        .collect();
+    (actions, legal_requests, complied)
+}
+
+// ============================================================================
+// TRANSPARENCY REPORT TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::UserId;
+    use candid::Principal;
+
+    fn entry(action: AuditAction) -> AuditLogEntry {
+        AuditLogEntry {
+            id: 0,
+            actor: UserId(Principal::anonymous()),
+            action,
+            target: None,
+            details: String::new(),
+            timestamp: 0,
+        }
+    }
+
+    #[test]
+    fn test_period_bounds() {
+        let day = |days: u64| days * NANOS_PER_DAY;
+        // 2024-01-01 is day 19723 and 2024 is a leap year
+        assert_eq!(
+            period_bounds(&ReportPeriod::Month {
+                year: 2024,
+                month: 2
+            }),
+            Ok((day(19_754), day(19_783)))
+        );
+        assert_eq!(
+            period_bounds(&ReportPeriod::Quarter {
+                year: 2024,
+                quarter: 4
+            }),
+            Ok((day(19_997), day(20_089)))
+        );
+        assert_eq!(
+            period_bounds(&ReportPeriod::Year(2024)),
+            Ok((day(19_723), day(20_089)))
+        );
+        assert!(period_bounds(&ReportPeriod::Month {
+            year: 2024,
+            month: 13
+        })
+        .is_err());
+        assert!(period_bounds(&ReportPeriod::Quarter {
+            year: 2024,
+            quarter: 0
+        })
+        .is_err());
+        assert!(period_bounds(&ReportPeriod::Year(1969)).is_err());
+    }
+
+    #[test]
+    fn test_summarize() {
+        let entries = [
+            entry(AuditAction::StrikeIssued),
+            entry(AuditAction::StrikeIssued),
+            entry(AuditAction::ModeratorAdded),
+            entry(AuditAction::LegalRequestComplied),
+            entry(AuditAction::LegalRequestRejected),
+        ];
+        let (actions, legal_requests, complied) = summarize(entries.iter());
+        assert_eq!(
+            actions,
+            vec![
+                ModerationActionCount {
+                    category: "legal_takedown".to_string(),
+                    count: 1,
+                },
+                ModerationActionCount {
+                    category: "strike_issued".to_string(),
+                    count: 2,
+                },
+            ]
+        );
+        assert_eq!((legal_requests, complied), (2, 1));
+    }
+}
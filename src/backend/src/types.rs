@@ -1,5 +1,6 @@
-use candid::{CandidType, Deserialize, Principal};
-use std::collections::BTreeSet;
+use crate::errors::ErrorCategory;
+use candid::{CandidType, Deserialize, Nat, Principal};
+use std::collections::{BTreeMap, BTreeSet};
 
 // ============================================================================
 // STRONG TYPED IDS
@@ -27,15 +28,24 @@ pub struct UserProfile {
     /// Unique user identifier (Internet Identity Principal)
     pub id: UserId,
 
-    /// User-chosen display name (3-50 characters)
-    pub username: String,
+    /// Unique handle used for mentions and lookups (3-50 characters, case-insensitive)
+    pub handle: String,
+
+    /// Freely editable display name (1-64 characters, unicode)
+    pub display_name: String,
 
     /// Optional user biography (max 500 characters)
     pub bio: String,
 
-    /// Avatar URL or emoji
+    /// Emoji, or `asset:{id}` for an image uploaded with `upload_avatar`
     pub avatar: String,
 
+    /// Profile banner image URL (empty when unset)
+    pub banner: String,
+
+    /// Profile appearance options
+    pub theme: ProfileTheme,
+
     /// Profile creation timestamp
     pub created_at: u64,
 
@@ -56,6 +66,76 @@ pub struct UserProfile {
 
     /// Account verification status
     pub verification_status: VerificationStatus,
+
+    /// Proof-of-personhood credential, shown as a "human" indicator
+    pub personhood: Option<PersonhoodProof>,
+
+    /// NFT chosen as avatar; clients show a "verified NFT avatar" marker while `verified`
+    pub nft_avatar: Option<NftAvatar>,
+
+    /// Incremented on every owner edit; used as an optimistic concurrency token
+    pub version: u64,
+}
+
+impl UserProfile {
+    /// Records an owner edit, bumping the concurrency version
+    pub fn record_edit(&mut self, now: u64) {
+        self.version = self.version.saturating_add(1);
+        self.updated_at = now;
+    }
+}
+
+/// Small set of appearance options for profile pages
+#[derive(CandidType, Deserialize, Clone, Debug, Default)]
+pub struct ProfileTheme {
+    /// Accent color used for links and buttons on the profile
+    pub accent: ThemeAccent,
+
+    /// Background style of the profile page
+    pub background: ThemeBackground,
+}
+
+#[derive(CandidType, Deserialize, Clone, Debug, Default)]
+pub enum ThemeAccent {
+    #[default]
+    Blue,
+    Purple,
+    Green,
+    Orange,
+    Pink,
+    Gray,
+}
+
+#[derive(CandidType, Deserialize, Clone, Debug, Default)]
+pub enum ThemeBackground {
+    #[default]
+    Default, // Follows the viewer's light/dark preference
+    Light,
+    Dark,
+    Gradient, // Gradient derived from the accent color
+}
+
+/// Record of a handle change, kept for cooldowns and redirects
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct HandleChange {
+    /// Handle before the change
+    pub old_handle: String,
+
+    /// Handle after the change
+    pub new_handle: String,
+
+    /// When the change happened
+    pub changed_at: u64,
+}
+
+/// Vacated handle that still resolves to its previous owner for a while
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct HandleRedirect {
+    /// User the old handle redirects to
+    pub user_id: UserId,
+
+    /// When the redirect (and the reservation against re-registration) lapses
+    pub expires_at: u64,
 }
 
 /// Privacy control settings for user profiles
@@ -70,8 +150,26 @@ pub struct PrivacySettings {
     /// Whether to show follower/following lists
     pub show_social_graph: bool,
 
-    /// Whether to appear in search results
+    /// Whether to appear in search results, trending and explore surfaces,
+    /// the sitemap and federation lookups
     pub searchable: bool,
+
+    /// Who can comment on the user's posts
+    pub comment_audience: InteractionAudience,
+
+    /// Whose mentions of the user link to their profile and notify them;
+    /// other mentions are kept as plain text
+    pub mention_audience: InteractionAudience,
+
+    /// Who can see which posts the user has liked
+    pub likes_visibility: InteractionAudience,
+
+    /// Who can tip the user
+    pub tip_audience: InteractionAudience,
+
+    /// Who can see the locations attached to the user's posts; Everyone also
+    /// lists public posts in `get_posts_near`
+    pub location_audience: InteractionAudience,
 }
 
 impl Default for PrivacySettings {
@@ -81,10 +179,23 @@ impl Default for PrivacySettings {
             message_privacy: MessagePrivacy::FollowersOnly,
             show_social_graph: true,
             searchable: true,
+            comment_audience: InteractionAudience::Everyone,
+            mention_audience: InteractionAudience::Everyone,
+            likes_visibility: InteractionAudience::Everyone,
+            tip_audience: InteractionAudience::Everyone,
+            location_audience: InteractionAudience::FollowersOnly,
         }
     }
 }
 
+impl PrivacySettings {
+    /// Whether discovery surfaces may list the user: the profile is public
+    /// and the user has not opted out of search
+    pub fn is_discoverable(&self) -> bool {
+        matches!(self.profile_visibility, ProfileVisibility::Public) && self.searchable
+    }
+}
+
 #[derive(CandidType, Deserialize, Clone, Debug)]
 pub enum ProfileVisibility {
     Public,        // Anyone can view
@@ -99,7 +210,15 @@ pub enum MessagePrivacy {
     Nobody,        // No direct messages allowed
 }
 
-#[derive(CandidType, Deserialize, Clone, Debug)]
+/// Audience allowed to perform an interaction with a user or their content
+#[derive(CandidType, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub enum InteractionAudience {
+    Everyone,      // Any non-blocked user
+    FollowersOnly, // Only the user's followers
+    Nobody,        // Only the user themselves
+}
+
+#[derive(CandidType, Deserialize, Clone, Debug, PartialEq, Eq)]
 pub enum VerificationStatus {
     Unverified,    // Regular user
     Verified,      // Verified identity (blue checkmark)
@@ -109,220 +228,3803 @@ pub enum VerificationStatus {
 }
 
 // ============================================================================
-// POST TYPES
+// VERIFICATION TYPES
 // ============================================================================
 
-/// Social media post with engagement metrics
+/// Application for a verified account status, reviewed by moderators
 #[derive(CandidType, Deserialize, Clone, Debug)]
-pub struct Post {
-    /// Unique post identifier
-    pub id: PostId,
+pub struct VerificationRequest {
+    /// Unique request identifier
+    pub id: u64,
 
-    /// User who created the post
-    pub author_id: UserId,
+    /// User applying for verification
+    pub applicant: UserId,
 
-    /// Post content (1-10,000 characters)
-    pub content: String,
+    /// Verification status being applied for
+    pub requested_status: VerificationStatus,
 
-    /// Post creation timestamp
+    /// Supporting evidence supplied by the applicant (links, credentials)
+    pub evidence: String,
+
+    /// When the application was submitted
     pub created_at: u64,
 
-    /// Last modification timestamp
-    pub updated_at: u64,
+    /// Review state of the application
+    pub status: VerificationRequestStatus,
 
-    /// Number of likes on this post
-    pub like_count: u64,
+    /// Moderator or admin who reviewed the application
+    pub reviewed_by: Option<UserId>,
 
-    /// Number of comments on this post
-    pub comment_count: u64,
+    /// When the application was reviewed
+    pub reviewed_at: Option<u64>,
 
-    /// Who can view this post
-    pub visibility: PostVisibility,
-    pub(crate) comments_count: u32,
-    pub(crate) likes_count: u32,
-    pub(crate) reposts_count: u32,
-    pub(crate) tips_received: u64,
-    pub(crate) edited_at: Option<u64>,
+    /// Optional note left by the reviewer
+    pub review_note: Option<String>,
 }
 
-/// Post visibility and privacy controls
-#[derive(CandidType, Deserialize, Clone, Debug)]
-pub enum PostVisibility {
-    /// Anyone can view and interact
-    Public,
+#[derive(CandidType, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub enum VerificationRequestStatus {
+    Pending,
+    Approved,
+    Rejected,
+}
 
-    /// Only followers can view and interact
-    FollowersOnly,
+// ============================================================================
+// ORGANIZATION TYPES
+// ============================================================================
 
-    /// Unlisted - only viewable with direct link, no feed visibility
-    Unlisted,
+/// Custom emoji registered by a verified organization for use in its posts
+#[derive(CandidType, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct CustomEmoji {
+    /// Organization that owns the emoji
+    pub org_id: UserId,
+
+    /// Code written in posts as `:shortcode:`
+    pub shortcode: String,
+
+    /// Image asset (https URL from a trusted domain)
+    pub asset_url: String,
+
+    /// Who registered the emoji (the org account or an org admin)
+    pub added_by: UserId,
+
+    /// When the emoji was registered
+    pub added_at: u64,
 }
 
-/// Enhanced post data including author information for feeds
+/// Team member acting on behalf of an organization account
 #[derive(CandidType, Deserialize, Clone, Debug)]
-pub struct FeedPost {
-    /// The post data
-    pub post: Post,
+pub struct OrgMember {
+    /// Organization account the member belongs to
+    pub org_id: UserId,
 
-    /// Author profile information
-    pub author: UserProfile,
+    /// Member principal
+    pub member_id: UserId,
 
-    /// Whether the current viewer has liked this post
-    pub is_liked: bool,
+    /// Member's role within the organization
+    pub role: OrgRole,
+
+    /// Who added the member (the org account or an org admin)
+    pub added_by: UserId,
+
+    /// When the member was added
+    pub added_at: u64,
+}
+
+#[derive(CandidType, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub enum OrgRole {
+    Admin,   // Manage members, post and respond as the org
+    Editor,  // Post and respond as the org
+    Analyst, // Read-only access to org membership and analytics
+}
+
+impl OrgRole {
+    /// Whether this role may publish posts and comments as the organization
+    pub fn can_author(&self) -> bool {
+        matches!(self, OrgRole::Admin | OrgRole::Editor)
+    }
 }
 
 // ============================================================================
-// COMMENT TYPES
+// DELEGATION & DRAFT TYPES
 // ============================================================================
 
-/// Comment on a post
+/// Permission for a delegate to submit drafts on behalf of an owner
 #[derive(CandidType, Deserialize, Clone, Debug)]
-pub struct Comment {
-    /// Unique comment identifier
-    pub id: CommentId,
+pub struct PostingDelegation {
+    /// Account whose posts the delegate may draft
+    pub owner: UserId,
 
-    /// Post this comment belongs to
-    pub post_id: PostId,
+    /// Principal allowed to submit drafts
+    pub delegate: UserId,
 
-    /// User who created the comment
-    pub author_id: UserId,
+    /// When the delegation was granted
+    pub granted_at: u64,
+
+    /// Optional expiry timestamp
+    pub expires_at: Option<u64>,
+}
+
+impl PostingDelegation {
+    /// Whether the delegation is still in effect at the given time
+    pub fn is_active(&self, now: u64) -> bool {
+        self.expires_at.map(|expiry| now < expiry).unwrap_or(true)
+    }
+}
+
+/// Action a user can let another principal perform as them
+#[derive(CandidType, Deserialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Capability {
+    Post,
+    Comment,
+    ReadMessages,
+    SendMessages,
+}
+
+/// Actions a user allowed another principal to perform as them
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct CapabilityGrant {
+    /// Account the grantee acts as
+    pub owner: UserId,
+
+    /// Principal allowed to act
+    pub grantee: UserId,
 
-    /// Comment content (1-500 characters)
+    pub capabilities: BTreeSet<Capability>,
+    pub granted_at: u64,
+
+    /// Optional expiry timestamp
+    pub expires_at: Option<u64>,
+}
+
+impl CapabilityGrant {
+    /// Whether the grant covers `capability` at the given time
+    pub fn allows(&self, capability: Capability, now: u64) -> bool {
+        self.capabilities.contains(&capability)
+            && self.expires_at.map(|expiry| now < expiry).unwrap_or(true)
+    }
+}
+
+/// Post prepared by a delegate that the owner must approve before publishing
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct Draft {
+    /// Unique draft identifier
+    pub id: u64,
+
+    /// Account the post will be published as
+    pub owner: UserId,
+
+    /// Delegate who submitted the draft (the owner for their own drafts)
+    pub submitted_by: UserId,
+
+    /// Draft content (1-10,000 characters)
     pub content: String,
 
-    /// Comment creation timestamp
+    /// Users the owner invited to view or edit the draft
+    pub collaborators: BTreeMap<UserId, DraftRole>,
+
+    /// Incremented by every edit; the last edit wins
+    pub version: u64,
+
+    /// Users whose edits shaped the content, in order of first contribution
+    pub contributors: Vec<UserId>,
+
+    /// Visibility the post will be published with
+    pub visibility: PostVisibility,
+
+    /// Draft creation timestamp
     pub created_at: u64,
 
-    /// Last modification timestamp
+    /// Last status change timestamp
     pub updated_at: u64,
+
+    /// Approval state of the draft
+    pub status: DraftStatus,
+
+    /// Post created when the draft was approved
+    pub published_post_id: Option<PostId>,
+}
+
+#[derive(CandidType, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DraftRole {
+    Viewer,
+    Editor,
+}
+
+#[derive(CandidType, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub enum DraftStatus {
+    PendingApproval,
+    Published,
+    Rejected,
 }
 
 // ============================================================================
-// STATISTICS TYPES
+// WHISTLEBLOWER INBOX TYPES
 // ============================================================================
 
-/// Platform-wide statistics
+/// Anonymous submission delivered to a journalist's secure inbox
+///
+/// Deliberately carries no sender field: the submitting principal is never
+/// stored alongside the submission.
 #[derive(CandidType, Deserialize, Clone, Debug)]
-pub struct PlatformStats {
-    /// Total number of registered users
-    pub total_users: u64,
+pub struct WhistleblowerSubmission {
+    /// Unique submission identifier
+    pub id: u64,
 
-    /// Total number of posts created
-    pub total_posts: u64,
+    /// Journalist whose inbox holds the submission
+    pub journalist: UserId,
 
-    /// Total number of likes across all posts
-    pub total_likes: u64,
+    /// Message body (1-10,000 characters)
+    pub message: String,
 
-    /// Total number of comments across all posts
-    pub total_comments: u64,
+    /// Attached documents
+    pub attachments: Vec<SubmissionAttachment>,
+
+    /// Submission time, rounded down to the hour to hinder timing correlation
+    pub submitted_at: u64,
+
+    /// Whether the journalist has read the submission
+    pub read: bool,
+}
+
+/// Document attached to a whistleblower submission
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct SubmissionAttachment {
+    /// Original file name
+    pub file_name: String,
+
+    /// MIME type reported by the client
+    pub mime_type: String,
+
+    /// Raw (typically client-side encrypted) file contents
+    pub data: Vec<u8>,
 }
 
 // ============================================================================
-// SOCIAL NETWORK CONSTANTS
+// DIRECT MESSAGE TYPES
 // ============================================================================
 
-/// Maximum post content length (characters)
-pub const MAX_POST_CONTENT: usize = 10_000;
+/// Direct (one-to-one) or group conversation
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct Conversation {
+    /// Unique conversation identifier
+    pub id: u64,
 
-/// Minimum post content length (characters)
-pub const MIN_POST_CONTENT: usize = 1;
+    /// Direct or group conversation
+    pub kind: ConversationKind,
 
-/// Maximum comment content length (characters)
-pub const MAX_COMMENT_CONTENT: usize = 500;
+    /// Current participants (direct: the two users in ascending principal order)
+    pub participants: Vec<UserId>,
 
-/// Minimum comment content length (characters)
-pub const MIN_COMMENT_CONTENT: usize = 1;
+    /// Group title (groups only)
+    pub title: Option<String>,
 
-/// Maximum username length (characters)
-pub const MAX_USERNAME_LENGTH: usize = 50;
+    /// Maximum number of messages retained; older messages are pruned (groups only)
+    pub retention_limit: Option<u64>,
 
-/// Minimum username length (characters)
-pub const MIN_USERNAME_LENGTH: usize = 3;
+    /// When the conversation was started
+    pub created_at: u64,
 
-/// Maximum bio length (characters)
-pub const MAX_BIO_LENGTH: usize = 500;
+    /// Timestamp of the most recent message
+    pub last_message_at: u64,
 
-/// Maximum avatar length (characters) - for URLs or long emoji sequences
-pub const MAX_AVATAR_LENGTH: usize = 200;
+    /// Number of messages in the conversation
+    pub message_count: u64,
 
-/// Default feed limit for pagination
-pub const DEFAULT_FEED_LIMIT: usize = 10;
+    /// Asset new members must hold (groups only)
+    pub token_gate: Option<TokenGate>,
+}
 
-/// Maximum feed limit to prevent resource exhaustion
-pub const MAX_FEED_LIMIT: usize = 50;
+#[derive(CandidType, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub enum ConversationKind {
+    Direct,
+    Group,
+}
 
-// ============================================================================
-// SOCIAL GRAPH TYPES
-// ============================================================================
+/// Member of a group conversation
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct GroupMember {
+    /// Member user
+    pub user_id: UserId,
 
-/// Social relationship between users
+    /// Member's role in the group
+    pub role: GroupRole,
+
+    /// When the member joined
+    pub joined_at: u64,
+}
+
+#[derive(CandidType, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub enum GroupRole {
+    Owner,  // Created the group; manages roles and settings
+    Admin,  // Adds/removes members and changes retention
+    Member, // Reads and sends messages
+}
+
+impl GroupRole {
+    /// Whether this role may add/remove members and change group settings
+    pub fn can_manage(&self) -> bool {
+        matches!(self, GroupRole::Owner | GroupRole::Admin)
+    }
+}
+
+/// Message in a direct or group conversation
 #[derive(CandidType, Deserialize, Clone, Debug)]
-pub struct FollowRelationship {
-    /// User who is following
-    pub follower: UserId,
+pub struct DirectMessage {
+    /// Unique message identifier
+    pub id: u64,
 
-    /// User being followed
-    pub following: UserId,
+    /// Conversation this message belongs to
+    pub conversation_id: u64,
 
-    /// When the follow relationship was created
-    pub created_at: u64,
+    /// User who sent the message
+    pub sender: UserId,
 
-    /// Whether the relationship is mutual (both users follow each other)
-    pub is_mutual: bool,
+    /// Message payload (plain text or client-side encrypted)
+    pub content: MessageContent,
+
+    /// When the message was sent
+    pub sent_at: u64,
 }
 
-/// Social connection metadata for efficient queries
-#[derive(CandidType, Deserialize, Clone, Debug, Default)]
-pub struct SocialConnections {
-    /// Set of users this user follows
-    pub following: BTreeSet<UserId>,
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub enum MessageContent {
+    /// Plain text message (1-2,000 characters)
+    Text(String),
 
-    /// Set of users following this user
-    pub followers: BTreeSet<UserId>,
+    /// Ciphertext encrypted with a vetKD-derived conversation key;
+    /// the canister never sees the plaintext
+    Encrypted(Vec<u8>),
+}
 
-    /// Set of users this user has blocked
-    pub blocked: BTreeSet<UserId>,
+/// Conversation as seen by one participant, with per-viewer unread state
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct ConversationSummary {
+    /// The conversation
+    pub conversation: Conversation,
 
-    /// Set of users who have blocked this user
-    pub blocked_by: BTreeSet<UserId>,
+    /// The other participant (direct conversations only)
+    pub peer: Option<UserId>,
+
+    /// Most recent message, if any
+    pub last_message: Option<DirectMessage>,
+
+    /// Messages from the peer the viewer hasn't read yet
+    pub unread_count: u64,
 }
 
-/// Follow request for users with private profiles
+// ============================================================================
+// AUDIT LOG TYPES
+// ============================================================================
+
+/// Privileged action recorded in the audit log
 #[derive(CandidType, Deserialize, Clone, Debug)]
-pub struct FollowRequest {
-    /// Unique request identifier
+pub struct AuditLogEntry {
+    /// Sequential entry identifier
     pub id: u64,
 
-    /// User requesting to follow
-    pub requester: UserId,
+    /// Admin or moderator who performed the action
+    pub actor: UserId,
 
-    /// User being requested to follow
-    pub target: UserId,
+    /// What was done
+    pub action: AuditAction,
 
-    /// When the request was created
-    pub created_at: u64,
+    /// User affected by the action, if any
+    pub target: Option<UserId>,
 
-    /// Status of the request
-    pub status: FollowRequestStatus,
+    /// Free-form context (reason, request id, new status)
+    pub details: String,
 
-    /// Optional message with the request
-    pub message: Option<String>,
+    /// When the action was performed
+    pub timestamp: u64,
 }
 
-#[derive(CandidType, Deserialize, Clone, Debug)]
-pub enum FollowRequestStatus {
-    Pending,
-    Approved,
-    Rejected,
-    Cancelled,
+#[derive(CandidType, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub enum AuditAction {
+    ModeratorAdded,
+    ModeratorRemoved,
+    VerificationApproved,
+    VerificationRejected,
+    VerificationGranted,
+    VerificationRevoked,
+    FederationConfigured,
+    FederationDisabled,
+    StrikeIssued,
+    StrikeRevoked,
+    PersonhoodProviderConfigured,
+    PersonhoodRequirementChanged,
+    TranslationConfigured,
+    BlockedDomainAdded,
+    BlockedDomainRemoved,
+    LinkSafetyConfigured,
+    CustomEmojiRemoved,
+    TrustedImageDomainAdded,
+    TrustedImageDomainRemoved,
+    NftMintingConfigured,
+    GovernanceConfigured,
+    GovernanceActionExecuted,
+    CreatorFundConfigured,
+    CreatorFundDistributed,
+    PointsGranted,
+    PointsSaleConfigured,
+    EarningsConfigured,
+    PromotionConfigured,
+    PromotionEnded,
+    LedgerAlertResolved,
+    CyclesThresholdChanged,
+    ArchiveConfigured,
+    ReplicaWasmUploaded,
+    ReadReplicaSpawned,
+    ReadReplicaRemoved,
+    FactCheckerAdded,
+    FactCheckerRemoved,
+    FactCheckAppealResolved,
+    MirrorConfigured,
+    ExportConfigured,
+    AltTextRuleChanged,
+    UsernameCheckDifficultyChanged,
+    QueryQuotaChanged,
+    ActionGateChanged,
+    LegalRequestComplied,
+    LegalRequestRejected,
+    PushRelayConfigured,
+    AppThrottled,
 }
 
-// Add social graph limits and constants
-/// Maximum number of users one can follow to prevent spam
-pub const MAX_FOLLOWING_LIMIT: usize = 10_000;
+// ============================================================================
+// POST TYPES
+// ============================================================================
 
-/// Maximum number of pending follow requests
-pub const MAX_PENDING_REQUESTS: usize = 100;
+/// Social media post with engagement metrics
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct Post {
+    /// Unique post identifier
+    pub id: PostId,
+
+    /// User who created the post
+    pub author_id: UserId,
+
+    /// Post content (1-10,000 characters), stored exactly as written
+    pub content: String,
+
+    /// HTML-escaped `content` for rendering (filled in responses)
+    pub rendered_content: String,
+
+    /// Post creation timestamp
+    pub created_at: u64,
+
+    /// Last modification timestamp
+    pub updated_at: u64,
+
+    /// Number of likes on this post
+    pub like_count: u64,
+
+    /// Number of comments on this post
+    pub comment_count: u64,
+
+    /// Number of reposts of this post
+    pub repost_count: u64,
+
+    /// Total tips received
+    pub tips_received: u64,
+
+    /// When the content was last edited, if ever
+    pub edited_at: Option<u64>,
+
+    /// Who can view this post
+    pub visibility: PostVisibility,
+
+    /// Language tag declared by the author or detected from the content
+    pub language: Option<String>,
+
+    /// Set in responses when the viewer's word filter altered the content
+    pub filtered: Option<WordFilterMode>,
+
+    /// Links flagged by the external link safety check
+    pub link_warnings: Vec<LinkWarning>,
+
+    /// Position within a multi-part thread (filled in responses)
+    pub thread: Option<ThreadPosition>,
+
+    /// How clients should render `content`
+    pub format: TextFormat,
+
+    /// Links, mentions and hashtags found in `content`, in order
+    pub entities: Vec<PostEntity>,
+
+    /// Author's custom emoji used in `content` (filled in responses)
+    pub custom_emoji: Vec<CustomEmoji>,
+
+    /// Fact-check labels in force (filled in responses)
+    pub fact_checks: Vec<FactCheckLabel>,
+
+    /// NFT minted from this post, if the author opted in
+    pub nft: Option<PostNft>,
+
+    /// Asset viewers must hold; content is withheld in responses until the
+    /// viewer passes the check with `unlock_post`
+    pub token_gate: Option<TokenGate>,
+
+    /// Author's subscription tier required to see the content; subscribers
+    /// of that tier or any pricier one qualify
+    pub subscriber_tier: Option<u64>,
+
+    /// One-off price to unlock the content with `unlock_post` (earnings
+    /// ledger base units); buyers keep access permanently
+    pub unlock_price: Option<u64>,
+
+    /// Fundraiser collecting contributions through this post
+    pub fundraiser: Option<Fundraiser>,
+
+    /// Poll attached by the author; tallies come from `get_poll_results`
+    pub poll: Option<Poll>,
+
+    /// Voice note attached by the author
+    pub audio: Option<AudioClip>,
+
+    /// Descriptions of the images and videos linked in `content`
+    pub media_alt_texts: Vec<MediaAltText>,
+
+    /// Coarse location attached by the author; shown only to the author's
+    /// `location_audience`
+    pub location: Option<PostLocation>,
+
+    /// Sources the author cites, listed apart from the content
+    pub sources: Vec<Citation>,
+
+    /// Signed copy exported to the permanence service, if the author opted in
+    pub permanent_copy: Option<PermanentCopy>,
+
+    /// Users besides the author who edited the draft the post was published from
+    pub contributors: Vec<UserId>,
+
+    /// Platform the post was first published on, for posts imported from an
+    /// archive (`created_at` is then the original time)
+    pub imported: Option<ImportSource>,
+
+    /// Whether the content was moved to the archive canister; listings carry
+    /// the post with empty content, and `get_post` fetches it back
+    pub archived: bool,
+}
+
+impl Post {
+    /// Whether the post may be published outside the canister (RSS, ActivityPub)
+    pub fn is_syndicated(&self) -> bool {
+        matches!(self.visibility, PostVisibility::Public)
+            && self.token_gate.is_none()
+            && self.subscriber_tier.is_none()
+            && self.unlock_price.is_none()
+    }
+}
+
+/// Asset a user must hold to access gated posts or join gated groups
+#[derive(CandidType, Deserialize, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum TokenGate {
+    /// At least `min_balance` of an ICRC-1 token
+    Icrc1 { ledger: Principal, min_balance: Nat },
+
+    /// Any token of an ICRC-7 collection
+    Icrc7 { collection: Principal },
+}
+
+/// Cached outcome of a token gate lookup
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct TokenGateCheck {
+    pub passed: bool,
+    pub checked_at: u64,
+}
+
+/// Provenance of a post minted as an NFT
+#[derive(CandidType, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct PostNft {
+    /// ICRC-7 collection holding the token
+    pub collection: Principal,
+
+    pub token_id: Nat,
+
+    /// SHA-256 of the post content at mint time
+    pub content_hash: Vec<u8>,
+
+    pub minted_at: u64,
+}
+
+/// ICRC-7 collection posts are minted into
+///
+/// `method` must accept a `PostMintRequest` and reply with
+/// `variant { Ok : nat; Err : text }` carrying the new token id.
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct NftMintConfig {
+    pub collection: Principal,
+    pub method: String,
+}
+
+/// Formatting of post content
+#[derive(CandidType, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub enum TextFormat {
+    /// Plain text
+    #[default]
+    Plain,
+
+    /// Sanctioned markdown subset (emphasis, code, lists, quotes, links)
+    Markdown,
+}
+
+/// Structured span of post content clients can render without re-parsing
+#[derive(CandidType, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct PostEntity {
+    pub kind: EntityKind,
+
+    /// Start offset in characters (inclusive)
+    pub start: u32,
+
+    /// End offset in characters (exclusive)
+    pub end: u32,
+
+    /// Matched text, e.g. `https://...`, `@alice` or `#icp`
+    pub value: String,
+
+    /// Mentioned user, resolved from the handle when the post was created
+    pub user_id: Option<UserId>,
+}
+
+#[derive(CandidType, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EntityKind {
+    Link,
+    Mention,
+    Hashtag,
+}
+
+/// Where a post sits within its thread
+#[derive(CandidType, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ThreadPosition {
+    /// First post of the thread
+    pub root_id: PostId,
+
+    /// Zero-based position of this post in the thread
+    pub index: u32,
+
+    /// Number of posts currently in the thread
+    pub length: u32,
+}
+
+/// Coarse location of a post: a named place, rounded coordinates, or both
+#[derive(CandidType, Deserialize, Clone, Debug, PartialEq)]
+pub struct PostLocation {
+    /// Place name as entered by the author (e.g. "Lagos")
+    pub place: Option<String>,
+
+    /// Rounded to LOCATION_GRID_DEGREES when stored
+    pub coordinates: Option<GeoPoint>,
+}
+
+#[derive(CandidType, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct GeoPoint {
+    pub latitude: f64,
+    pub longitude: f64,
+}
+
+impl GeoPoint {
+    /// The point snapped to the location grid
+    pub fn coarse(self) -> Self {
+        let snap = |degrees: f64| (degrees / LOCATION_GRID_DEGREES).round() * LOCATION_GRID_DEGREES;
+        Self {
+            latitude: snap(self.latitude),
+            longitude: snap(self.longitude),
+        }
+    }
+
+    /// Grid cell containing the point, as (latitude, longitude) indices
+    pub fn cell(self) -> (i32, i32) {
+        (
+            (self.latitude / LOCATION_GRID_DEGREES).round() as i32,
+            (self.longitude / LOCATION_GRID_DEGREES).round() as i32,
+        )
+    }
+}
+
+/// Source cited by a post (e.g. a document behind a news report)
+#[derive(CandidType, Deserialize, Clone, Debug, PartialEq)]
+pub struct Citation {
+    /// https link to the source
+    pub url: String,
+    pub title: String,
+
+    /// Lowercase hex SHA-256 of an archived copy, so readers can check the
+    /// source they find still matches the one cited
+    pub archived_sha256: Option<String>,
+}
+
+/// Description of a media link for screen readers
+#[derive(CandidType, Deserialize, Clone, Debug, PartialEq)]
+pub struct MediaAltText {
+    /// Media link exactly as it appears in the post content
+    pub url: String,
+    pub alt_text: String,
+}
+
+/// Area searched by `get_posts_near`, bounds inclusive
+#[derive(CandidType, Deserialize, Clone, Copy, Debug)]
+pub struct GeoRegion {
+    pub south_west: GeoPoint,
+    pub north_east: GeoPoint,
+}
+
+/// Post visibility and privacy controls
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub enum PostVisibility {
+    /// Anyone can view and interact
+    Public,
+
+    /// Only followers can view and interact
+    FollowersOnly,
+
+    /// Unlisted - only viewable with direct link, no feed visibility
+    Unlisted,
+}
+
+/// Enhanced post data including author information for feeds
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct FeedPost {
+    /// The post data
+    pub post: Post,
+
+    /// Author profile information
+    pub author: UserProfile,
+
+    /// Whether the current viewer has liked this post
+    pub is_liked: bool,
+
+    /// Whether the post is shown as a paid promotion
+    pub promoted: bool,
+
+    /// What the post mainly carries, for clients to pick a layout
+    pub kind: FeedItemKind,
+}
+
+/// Main content of a feed item
+#[derive(CandidType, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FeedItemKind {
+    Text,
+    /// Links to an image, video or audio file
+    Media,
+    /// Carries a voice note
+    Audio,
+}
+
+// ============================================================================
+// COMMENT TYPES
+// ============================================================================
+
+/// Comment on a post
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct Comment {
+    /// Unique comment identifier
+    pub id: CommentId,
+
+    /// Post this comment belongs to
+    pub post_id: PostId,
+
+    /// User who created the comment
+    pub author_id: UserId,
+
+    /// Comment content (1-500 characters), stored exactly as written
+    pub content: String,
+
+    /// HTML-escaped `content` for rendering (filled in responses)
+    pub rendered_content: String,
+
+    /// Comment creation timestamp
+    pub created_at: u64,
+
+    /// Last modification timestamp
+    pub updated_at: u64,
+
+    /// Set in responses when the viewer's word filter altered the content
+    pub filtered: Option<WordFilterMode>,
+}
+
+/// Per-user filters applied to the social feed
+#[derive(CandidType, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct FeedPreferences {
+    /// Hide reposts ("RT @handle ..." posts)
+    pub exclude_reposts: bool,
+
+    /// Only show posts linking to images, video or audio
+    pub media_only: bool,
+
+    /// Only show original posts from directly followed users (no own posts, no boosts)
+    pub direct_follows_only: bool,
+}
+
+/// Display and accessibility preferences that follow a user across devices
+///
+/// Mostly stored for clients to apply; the canister itself uses the locale,
+/// the time settings and `hide_engagement_counts`.
+#[derive(CandidType, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct UserSettings {
+    /// Avoid animations and autoplay
+    pub reduced_motion: bool,
+
+    /// Render text at a larger size
+    pub large_text: bool,
+
+    /// Keep media behind a warning collapsed until tapped
+    pub auto_collapse_sensitive_media: bool,
+
+    /// Feed opened by default
+    pub default_feed_sort: FeedSort,
+
+    /// Language for error messages; English when unset or untranslated
+    pub locale: Option<String>,
+
+    /// Offset from UTC in minutes (e.g. -300 for UTC-5); UTC when unset
+    ///
+    /// A fixed offset, not a named zone: clients update it when daylight
+    /// saving time starts or ends.
+    pub utc_offset_minutes: Option<i16>,
+
+    /// Local hour (0-23) at which the daily activity digest closes
+    /// (DEFAULT_DIGEST_HOUR when unset)
+    pub digest_hour: Option<u8>,
+
+    /// Leave like and repost counts out of posts sent to this user
+    pub hide_engagement_counts: bool,
+}
+
+/// Order of the feed a client opens by default
+#[derive(CandidType, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FeedSort {
+    /// Followed users' posts, newest first (`get_social_feed`)
+    #[default]
+    Latest,
+    /// Trending public posts (`get_explore_feed`)
+    Trending,
+}
+
+/// Per-user opt-in word filter applied to other users' posts and comments
+#[derive(CandidType, Deserialize, Clone, Debug, Default)]
+pub struct WordFilterSettings {
+    /// Whether the filter is applied
+    pub enabled: bool,
+
+    /// How matching content is presented
+    pub mode: WordFilterMode,
+
+    /// Extra words filtered in addition to the default dictionary
+    pub custom_words: Vec<String>,
+}
+
+/// Presentation of content matched by the word filter
+#[derive(CandidType, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub enum WordFilterMode {
+    /// Matching words are replaced with asterisks
+    #[default]
+    Mask,
+    /// Content containing a matching word is withheld entirely
+    Collapse,
+}
+
+// ============================================================================
+// URL BLOCKLIST TYPES
+// ============================================================================
+
+/// Domain whose links are rejected in new posts and comments
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct BlockedDomain {
+    /// Lowercase domain; subdomains are blocked too
+    pub domain: String,
+
+    /// Why the domain was blocked
+    pub reason: String,
+
+    /// Moderator who added the entry
+    pub added_by: UserId,
+
+    pub added_at: u64,
+}
+
+/// Safety service used to check links in new posts
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct LinkSafetyConfig {
+    /// HTTPS Safe Browsing v4 compatible `threatMatches:find` endpoint
+    pub endpoint: String,
+
+    /// API key appended to requests (never returned by queries)
+    pub api_key: String,
+}
+
+/// Warning label attached to a post whose link was flagged
+#[derive(CandidType, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct LinkWarning {
+    /// Flagged link
+    pub url: String,
+
+    /// Threat category reported by the safety service (e.g. "MALWARE")
+    pub threat_type: String,
+}
+
+/// Existing post found linking to a blocked domain by the retro-scan job
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct FlaggedPost {
+    pub post_id: PostId,
+
+    /// Blocked domain the post links to
+    pub domain: String,
+
+    pub flagged_at: u64,
+}
+
+// ============================================================================
+// LIKED POSTS TYPES
+// ============================================================================
+
+/// Per-user index of liked posts, ordered by when they were liked
+#[derive(CandidType, Deserialize, Clone, Debug, Default)]
+pub struct LikedPostsIndex {
+    /// (liked_at, post) pairs for newest-first pagination
+    pub by_time: BTreeSet<(u64, PostId)>,
+
+    /// When each post was liked, to remove entries on unlike
+    pub liked_at: BTreeMap<PostId, u64>,
+}
+
+impl LikedPostsIndex {
+    /// Records a like
+    pub fn insert(&mut self, post_id: PostId, now: u64) {
+        if let Some(previous) = self.liked_at.insert(post_id, now) {
+            self.by_time.remove(&(previous, post_id));
+        }
+        self.by_time.insert((now, post_id));
+    }
+
+    /// Removes a like
+    pub fn remove(&mut self, post_id: PostId) {
+        if let Some(liked_at) = self.liked_at.remove(&post_id) {
+            self.by_time.remove(&(liked_at, post_id));
+        }
+    }
+}
+
+/// Position in a liked-posts listing (exclusive)
+#[derive(CandidType, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LikeCursor {
+    pub liked_at: u64,
+    pub post_id: PostId,
+}
+
+// ============================================================================
+// PAGE TYPES
+// ============================================================================
+//
+// List endpoints return pages carrying `total` when the full count is known
+// without a scan, `has_more`, and a `next_cursor` to pass back.
+
+/// Page of liked posts, newest like first
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct LikedPostsPage {
+    pub posts: Vec<Post>,
+    pub total: Option<u64>,
+    pub has_more: bool,
+
+    /// Pass back to fetch the next page; `None` when exhausted
+    pub next_cursor: Option<LikeCursor>,
+}
+
+/// Page of a user's comments, newest first
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct CommentsPage {
+    pub comments: Vec<Comment>,
+    pub total: Option<u64>,
+    pub has_more: bool,
+
+    /// Pass back to fetch the next page; `None` when exhausted
+    pub next_cursor: Option<CommentId>,
+}
+
+/// Page of posts, newest first
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct PostsPage {
+    pub posts: Vec<Post>,
+    pub total: Option<u64>,
+    pub has_more: bool,
+
+    /// Pass back to fetch the next page; `None` when exhausted
+    pub next_cursor: Option<PostId>,
+}
+
+/// Page of a user's posts, newest first
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct UserPostsPage {
+    pub posts: Vec<Post>,
+    pub total: Option<u64>,
+    pub has_more: bool,
+
+    /// Offset of the next page; `None` when exhausted
+    pub next_cursor: Option<u64>,
+}
+
+/// Page of a post's comments, oldest first
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct PostCommentsPage {
+    pub comments: Vec<Comment>,
+    pub total: Option<u64>,
+    pub has_more: bool,
+
+    /// Offset of the next page; `None` when exhausted
+    pub next_cursor: Option<u64>,
+}
+
+/// Page of followers or followed accounts
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct ProfilesPage {
+    pub profiles: Vec<UserProfile>,
+    pub total: Option<u64>,
+    pub has_more: bool,
+
+    /// Offset of the next page; `None` when exhausted
+    pub next_cursor: Option<u64>,
+}
+
+/// Page of a feed
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct FeedPage {
+    pub posts: Vec<FeedPost>,
+    pub total: Option<u64>,
+    pub has_more: bool,
+
+    /// Offset of the next page; `None` when exhausted
+    pub next_cursor: Option<u64>,
+}
+
+/// Changes to a social feed since an earlier sync
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct FeedUpdates {
+    /// Posts created since the sync, newest first
+    pub posts: Vec<FeedPost>,
+
+    /// Older posts updated since the sync
+    pub updated: Vec<PostId>,
+
+    /// Posts deleted since the sync; drop them from the feed
+    pub deleted: Vec<PostId>,
+
+    /// More changed than was listed
+    pub truncated: bool,
+
+    /// Pass back as `since` on the next sync
+    pub sync_cursor: u64,
+}
+
+/// Page of pending verification requests
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct VerificationRequestsPage {
+    pub requests: Vec<VerificationRequest>,
+    pub total: Option<u64>,
+    pub has_more: bool,
+
+    /// Offset of the next page; `None` when exhausted
+    pub next_cursor: Option<u64>,
+}
+
+/// Page of the audit log, newest first
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct AuditLogPage {
+    pub entries: Vec<AuditLogEntry>,
+    pub total: Option<u64>,
+    pub has_more: bool,
+
+    /// Offset of the next page; `None` when exhausted
+    pub next_cursor: Option<u64>,
+}
+
+/// Page of a whistleblower inbox, newest first
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct WhistleblowerInboxPage {
+    pub submissions: Vec<WhistleblowerSubmission>,
+    pub total: Option<u64>,
+    pub has_more: bool,
+
+    /// Offset of the next page; `None` when exhausted
+    pub next_cursor: Option<u64>,
+}
+
+/// Page of conversations, most recently active first
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct ConversationsPage {
+    pub conversations: Vec<ConversationSummary>,
+    pub total: Option<u64>,
+    pub has_more: bool,
+
+    /// Offset of the next page; `None` when exhausted
+    pub next_cursor: Option<u64>,
+}
+
+/// Page of followers on other ActivityPub servers
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct RemoteFollowersPage {
+    pub followers: Vec<RemoteFollower>,
+    pub total: Option<u64>,
+    pub has_more: bool,
+
+    /// Offset of the next page; `None` when exhausted
+    pub next_cursor: Option<u64>,
+}
+
+/// Page of flagged posts, newest first
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct FlaggedPostsPage {
+    pub posts: Vec<FlaggedPost>,
+    pub total: Option<u64>,
+    pub has_more: bool,
+
+    /// Offset of the next page; `None` when exhausted
+    pub next_cursor: Option<u64>,
+}
+
+/// Page of the transaction log, newest first
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct TransactionsPage {
+    pub transactions: Vec<TransactionRecord>,
+    pub total: Option<u64>,
+    pub has_more: bool,
+
+    /// Offset of the next page; `None` when exhausted
+    pub next_cursor: Option<u64>,
+}
+
+/// Page of ledger alerts, newest first
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct LedgerAlertsPage {
+    pub alerts: Vec<LedgerAlert>,
+    pub total: Option<u64>,
+    pub has_more: bool,
+
+    /// Offset of the next page; `None` when exhausted
+    pub next_cursor: Option<u64>,
+}
+
+/// Page of a conversation's messages, newest first
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct MessagesPage {
+    pub messages: Vec<DirectMessage>,
+    pub total: Option<u64>,
+    pub has_more: bool,
+
+    /// Pass back as `before` to fetch the next page; `None` when exhausted
+    pub next_cursor: Option<u64>,
+}
+
+// ============================================================================
+// STATISTICS TYPES
+// ============================================================================
+
+/// Platform-wide statistics
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct PlatformStats {
+    /// Total number of registered users
+    pub total_users: u64,
+
+    /// Total number of posts created
+    pub total_posts: u64,
+
+    /// Total number of likes across all posts
+    pub total_likes: u64,
+
+    /// Total number of comments across all posts
+    pub total_comments: u64,
+}
+
+/// Platform statistics including rolling activity metrics
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct ExtendedPlatformStats {
+    /// All-time totals
+    pub totals: PlatformStats,
+
+    /// Distinct principals making update calls today
+    pub daily_active_users: u64,
+
+    /// Distinct principals making update calls in the last 7 days
+    pub weekly_active_users: u64,
+
+    /// Distinct principals making update calls in the last 30 days
+    pub monthly_active_users: u64,
+
+    /// New profiles per day, oldest first
+    pub signups_per_day: Vec<DailyCount>,
+
+    /// New posts per day, oldest first
+    pub posts_per_day: Vec<DailyCount>,
+}
+
+/// Counter value for one day of a time series
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct DailyCount {
+    /// Day number since the Unix epoch (UTC)
+    pub day: u64,
+
+    /// Count for that day
+    pub count: u64,
+}
+
+// ============================================================================
+// FEDERATION TYPES
+// ============================================================================
+
+/// Seed material for secure identifiers and tokens
+///
+/// Queries cannot await `raw_rand`, so the seed is refreshed from the
+/// management canister by a timer and stretched with SHA-256 in between.
+#[derive(CandidType, Deserialize, Clone, Debug, Default)]
+pub struct EntropyPool {
+    pub seed: Vec<u8>,
+    pub counter: u64,
+    pub last_refreshed: Option<u64>,
+}
+
+/// Translation service used by `translate_post`
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct TranslationConfig {
+    /// HTTPS endpoint accepting LibreTranslate-style JSON requests
+    pub endpoint: String,
+
+    /// API key sent with each request (never returned by queries)
+    pub api_key: String,
+}
+
+/// Cached machine translation of a post
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct PostTranslation {
+    pub post_id: PostId,
+
+    /// Language the post was translated into
+    pub target_lang: String,
+
+    /// Translated post content
+    pub content: String,
+
+    /// When the translation was fetched
+    pub translated_at: u64,
+}
+
+/// Post returned together with its translation
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct TranslatedPost {
+    pub original: Post,
+    pub translation: PostTranslation,
+}
+
+/// Instance-wide ActivityPub settings
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct FederationConfig {
+    /// Public domain that routes to this canister's HTTP gateway
+    pub domain: String,
+
+    /// PEM RSA key used to sign outgoing deliveries (never returned by queries)
+    pub private_key_pem: String,
+
+    /// PEM public half advertised on actor documents
+    pub public_key_pem: String,
+}
+
+/// Account on another ActivityPub server following a local user
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct RemoteFollower {
+    /// Remote actor URI
+    pub actor_id: String,
+
+    /// Inbox deliveries for this follower go to (shared inbox when advertised)
+    pub inbox: String,
+
+    /// When the Follow activity was accepted
+    pub followed_at: u64,
+}
+
+/// Cached remote actor used to verify HTTP signatures
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct RemoteActor {
+    /// Actor URI
+    pub id: String,
+
+    /// Actor inbox
+    pub inbox: String,
+
+    /// Server-wide shared inbox, if advertised
+    pub shared_inbox: Option<String>,
+
+    /// Key identifier referenced by the actor's signatures
+    pub key_id: String,
+
+    /// PEM RSA public key
+    pub public_key_pem: String,
+
+    /// When the actor document was fetched
+    pub fetched_at: u64,
+}
+
+// ============================================================================
+// ANALYTICS TYPES
+// ============================================================================
+
+/// Engagement counters aggregated over one day
+#[derive(CandidType, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct EngagementCounts {
+    /// Net likes gained (unlikes subtract)
+    pub likes: u64,
+
+    /// Comments added
+    pub comments: u64,
+
+    /// Reposts made
+    pub reposts: u64,
+
+    /// Deduplicated views
+    pub views: u64,
+}
+
+/// Kind of engagement event fed into analytics
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EngagementEvent {
+    Like,
+    Unlike,
+    Comment,
+    Repost,
+    View,
+}
+
+impl EngagementCounts {
+    /// Applies a single engagement event to the counters
+    pub fn apply(&mut self, event: EngagementEvent) {
+        match event {
+            EngagementEvent::Like => self.likes = self.likes.saturating_add(1),
+            EngagementEvent::Unlike => self.likes = self.likes.saturating_sub(1),
+            EngagementEvent::Comment => self.comments = self.comments.saturating_add(1),
+            EngagementEvent::Repost => self.reposts = self.reposts.saturating_add(1),
+            EngagementEvent::View => self.views = self.views.saturating_add(1),
+        }
+    }
+}
+
+/// Engagement counters for one day of a time series
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct DailyEngagement {
+    /// Day number since the Unix epoch on the author's calendar (see `utc_offset_minutes`)
+    pub day: u64,
+
+    /// Counters for that day
+    pub counts: EngagementCounts,
+}
+
+/// Daily snapshot of an account's follower count
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct FollowerSample {
+    /// Day number since the Unix epoch on the user's calendar (see `utc_offset_minutes`)
+    pub day: u64,
+
+    /// Follower count when sampled
+    pub follower_count: u64,
+}
+
+/// Lifetime engagement totals for one post
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct PostPerformance {
+    /// Post the totals belong to
+    pub post_id: PostId,
+
+    /// Lifetime counters
+    pub totals: EngagementCounts,
+}
+
+// ============================================================================
+// PROOF OF PERSONHOOD TYPES
+// ============================================================================
+
+/// Attestation from a personhood provider that an account is a unique human
+#[derive(CandidType, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct PersonhoodProof {
+    /// Provider canister that vouched for the account
+    pub provider: Principal,
+
+    /// When the proof was obtained
+    pub verified_at: u64,
+
+    /// When the proof lapses and must be renewed
+    pub expires_at: u64,
+}
+
+impl PersonhoodProof {
+    /// Whether the proof is still in force at `now`
+    pub fn is_valid(&self, now: u64) -> bool {
+        now < self.expires_at
+    }
+}
+
+/// ICRC-7 token used as a profile avatar
+#[derive(CandidType, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct NftAvatar {
+    /// ICRC-7 collection canister
+    pub collection: Principal,
+
+    pub token_id: Nat,
+
+    /// Whether the profile owner held the token at the last check
+    pub verified: bool,
+
+    /// When ownership was last checked
+    pub checked_at: u64,
+}
+
+/// Canister consulted for personhood checks
+///
+/// `method` must accept the account principal and reply with a single
+/// `bool` (`(principal) -> (bool)`).
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct PersonhoodProvider {
+    pub canister_id: Principal,
+    pub method: String,
+}
+
+// ============================================================================
+// REPUTATION TYPES
+// ============================================================================
+
+/// Account reputation with the signals it was derived from
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct ReputationScore {
+    /// Account the score belongs to
+    pub user_id: UserId,
+
+    /// Overall score (0-100)
+    pub score: u32,
+
+    /// Whole days since the profile was created
+    pub account_age_days: u64,
+
+    /// Verification tier at the time of scoring
+    pub verification_status: VerificationStatus,
+
+    /// Lifetime engagement received across the account's posts
+    pub received_engagement: EngagementCounts,
+
+    /// Current follower count
+    pub follower_count: u64,
+
+    /// Active moderation strikes
+    pub moderation_strikes: u32,
+
+    /// Standing used to scale rate limits
+    pub trust_tier: TrustTier,
+
+    /// Percentage of base rate limits granted
+    pub rate_limit_percent: u32,
+
+    /// Whether the account may vote on community notes
+    pub community_notes_eligible: bool,
+}
+
+/// Account standing used to scale rate limits
+///
+/// Variants are ordered from least to most trusted.
+#[derive(CandidType, Deserialize, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum TrustTier {
+    /// Active moderation strikes
+    Restricted,
+    /// Young, unverified account
+    New,
+    Standard,
+    /// Long-standing or well-reputed account
+    Established,
+    /// High-reputation account
+    Trusted,
+}
+
+/// Account standing required before a gated action is allowed
+#[derive(CandidType, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ActionGate {
+    /// Whole days since the profile was created
+    pub min_account_age_days: u64,
+
+    /// Posts the account must have published
+    pub min_posts: u64,
+
+    /// Lowest trust tier let through, if any
+    pub min_trust_tier: Option<TrustTier>,
+}
+
+// ============================================================================
+// CREATOR FUND TYPES
+// ============================================================================
+
+/// Settings for periodic creator fund distributions
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct CreatorFundConfig {
+    /// ICRC-1 ledger of the reward token
+    pub ledger: Principal,
+
+    /// Amount distributed each period (ledger base units)
+    pub pool_per_period: u64,
+
+    /// Days between distributions
+    pub period_days: u64,
+
+    /// Largest share of a pool one creator can receive
+    pub max_share_percent: u8,
+
+    /// Minimum reputation score to qualify for rewards
+    pub min_reputation: u32,
+}
+
+/// Reward credited to a creator by one distribution
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct CreatorFundReward {
+    /// Last day (days since epoch) of the period the reward covers
+    pub period_end_day: u64,
+
+    /// Engagement points earned in the period
+    pub points: u64,
+
+    pub amount: u64,
+}
+
+/// Rewards paid out from the treasury to a creator
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct CreatorFundClaim {
+    /// Amount the destination received
+    pub amount: u64,
+
+    /// Ledger fee deducted from the claimed rewards
+    pub fee: u64,
+    pub to: crate::ledger::Account,
+
+    /// Ledger block of the transfer
+    pub block_index: Nat,
+    pub claimed_at: u64,
+}
+
+/// A creator's unclaimed balance with reward and payout history (newest first)
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct CreatorFundStatement {
+    pub claimable: u64,
+    pub rewards: Vec<CreatorFundReward>,
+    pub claims: Vec<CreatorFundClaim>,
+}
+
+// ============================================================================
+// POINTS TYPES
+// ============================================================================
+
+/// Why a user's points balance changed
+#[derive(CandidType, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub enum PointsTransactionKind {
+    /// Engagement on the user's posts; earnings on one day share an entry
+    Earned,
+    /// Bought with tokens; `block_index` is the ledger transfer of the payment
+    Purchased {
+        block_index: Nat,
+    },
+    /// Promotional grant from an admin
+    Granted {
+        by: UserId,
+        memo: String,
+    },
+    TipSent {
+        post_id: PostId,
+        to: UserId,
+    },
+    TipReceived {
+        post_id: PostId,
+        from: UserId,
+    },
+    /// Paid for promoting a post
+    Promotion {
+        post_id: PostId,
+    },
+}
+
+/// Entry in a user's points history
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct PointsTransaction {
+    pub kind: PointsTransactionKind,
+    pub amount: u64,
+
+    /// Balance after the transaction
+    pub balance: u64,
+    pub timestamp: u64,
+}
+
+/// A user's points balance, today's usage and history (newest first)
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct PointsAccount {
+    pub balance: u64,
+    pub earned_today: u64,
+    pub sent_today: u64,
+    pub transactions: Vec<PointsTransaction>,
+}
+
+/// Settings for buying points with an ICRC-1 token
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct PointsSaleConfig {
+    /// ICRC-1 ledger of the payment token
+    pub ledger: Principal,
+
+    /// Price of one point (ledger base units)
+    pub price_per_point: u64,
+}
+
+// ============================================================================
+// CREATOR EARNINGS TYPES
+// ============================================================================
+
+/// Settings for creator earnings held by the canister
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct EarningsConfig {
+    /// ICRC-1 ledger earnings are paid in
+    pub ledger: Principal,
+
+    /// Smallest amount a creator can withdraw (ledger base units)
+    pub min_withdrawal: u64,
+}
+
+/// A creator's earnings account balance
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct EarningsBalance {
+    pub balance: u64,
+
+    /// Ledger fee charged to each withdrawal
+    pub fee: u64,
+
+    /// Largest amount that can be withdrawn now
+    pub withdrawable: u64,
+}
+
+/// Earnings transferred out by a creator
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct Withdrawal {
+    pub amount: u64,
+    pub fee: u64,
+    pub to: crate::ledger::Account,
+
+    /// Ledger block of the transfer
+    pub block_index: Nat,
+    pub timestamp: u64,
+}
+
+// ============================================================================
+// SUBSCRIPTION TYPES
+// ============================================================================
+
+/// Paid membership level offered by a creator
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct SubscriptionTier {
+    /// Identifier, unique among the creator's tiers
+    pub id: u64,
+    pub name: String,
+
+    /// Price per SUBSCRIPTION_PERIOD_DAYS (earnings ledger base units)
+    pub price: u64,
+
+    /// What subscribers get, shown on the tier listing
+    pub perks: String,
+
+    /// Archived tiers take no new subscribers; existing ones keep renewing
+    pub archived: bool,
+    pub created_at: u64,
+}
+
+/// A subscriber's membership with one creator
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct Subscription {
+    pub subscriber: UserId,
+    pub creator: UserId,
+    pub tier_id: u64,
+    pub started_at: u64,
+
+    /// Price of `tier_id` the subscriber agreed to, charged at each renewal
+    pub price: u64,
+
+    /// Access lasts until this time unless the subscription renews
+    pub current_period_end: u64,
+
+    /// Cheaper tier the subscription moves to at the next renewal
+    pub next_tier_id: Option<u64>,
+
+    /// Price of `next_tier_id` the subscriber agreed to
+    pub next_price: Option<u64>,
+
+    /// Whether the next period is charged automatically
+    pub auto_renew: bool,
+}
+
+impl Subscription {
+    pub fn is_active(&self, now: u64) -> bool {
+        now < self.current_period_end
+    }
+}
+
+// ============================================================================
+// PROMOTION TYPES
+// ============================================================================
+
+/// How a post promotion is paid for
+#[derive(CandidType, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PromotionPayment {
+    /// Internal points, debited from the author's balance
+    Points,
+    /// Tokens on the promotion ledger, charged via an ICRC-2 approval
+    Token,
+}
+
+/// Pricing and eligibility rules for promoted posts
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct PromotionConfig {
+    /// Ledger for token payments (None accepts points only)
+    pub ledger: Option<Principal>,
+
+    /// Token price of one day of promotion (ledger base units)
+    pub token_price_per_day: u64,
+
+    /// Points price of one day of promotion (0 disables point payments)
+    pub points_per_day: u64,
+
+    /// Minimum author reputation to promote posts
+    pub min_reputation: u32,
+}
+
+/// A paid boost of a post in the explore feed
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct Promotion {
+    pub post_id: PostId,
+    pub sponsor: UserId,
+    pub payment: PromotionPayment,
+
+    /// Amount paid; divided by the day price it sets how long the boost lasts
+    pub budget: u64,
+    pub started_at: u64,
+    pub ends_at: u64,
+
+    /// Admin who ended the promotion early, if any
+    pub ended_by: Option<UserId>,
+}
+
+impl Promotion {
+    pub fn is_active(&self, now: u64) -> bool {
+        now < self.ends_at
+    }
+}
+
+// ============================================================================
+// FUNDRAISER TYPES
+// ============================================================================
+
+/// Lifecycle of a fundraiser
+#[derive(CandidType, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub enum FundraiserStatus {
+    /// Accepting contributions until the deadline
+    Open,
+    /// Goal met; the escrow is being paid out to the author
+    Releasing,
+    /// Escrow paid out; `block_index` is the ledger transfer
+    Released { block_index: Nat },
+    /// Goal missed; contributions are being returned
+    Refunding,
+    /// Every contribution has been returned
+    Refunded,
+}
+
+/// Goal-based fundraiser attached to a post, with contributions in escrow
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct Fundraiser {
+    /// ICRC-1 ledger contributions are made in
+    pub ledger: Principal,
+
+    /// Amount to raise (ledger base units)
+    pub goal: u64,
+
+    /// Contributions close at this time
+    pub deadline: u64,
+
+    /// Total held in escrow
+    pub raised: u64,
+    pub contributor_count: u64,
+    pub status: FundraiserStatus,
+}
+
+/// A user's escrowed contribution to one fundraiser
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct FundraiserContribution {
+    pub post_id: PostId,
+    pub amount: u64,
+    pub contributed_at: u64,
+
+    /// Ledger block of the refund, once returned
+    pub refund_block_index: Option<Nat>,
+}
+
+// ============================================================================
+// TRANSACTION LOG TYPES
+// ============================================================================
+
+/// Kind of monetary event in a user's transaction log
+#[derive(CandidType, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TransactionKind {
+    TipSent,
+    TipReceived,
+    PointsPurchased,
+    PointsGranted,
+    SubscriptionCharged,
+    SubscriptionIncome,
+    PostUnlocked,
+    PostSold,
+    PromotionPaid,
+    FundraiserContribution,
+    FundraiserPayout,
+    FundraiserRefund,
+    CreatorFundClaim,
+    Withdrawal,
+}
+
+/// What a transaction was denominated in
+#[derive(CandidType, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub enum TransactionAsset {
+    /// Internal points
+    Points,
+    /// ICRC-1 token, in ledger base units
+    Token { ledger: Principal },
+}
+
+/// Entry in a user's transaction log
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct TransactionRecord {
+    pub kind: TransactionKind,
+    pub asset: TransactionAsset,
+    pub amount: u64,
+
+    /// Other user involved (tip recipient, creator, subscriber, ...)
+    pub counterparty: Option<UserId>,
+
+    /// Post the transaction relates to
+    pub post_id: Option<PostId>,
+
+    /// Ledger block of the transfer, for token transactions
+    pub block_index: Option<Nat>,
+    pub timestamp: u64,
+}
+
+impl TransactionRecord {
+    /// Record without counterparty, post or ledger block
+    pub fn new(
+        kind: TransactionKind,
+        asset: TransactionAsset,
+        amount: u64,
+        timestamp: u64,
+    ) -> Self {
+        Self {
+            kind,
+            asset,
+            amount,
+            counterparty: None,
+            post_id: None,
+            block_index: None,
+            timestamp,
+        }
+    }
+}
+
+/// Criteria for listing transactions; empty fields match everything
+#[derive(CandidType, Deserialize, Clone, Debug, Default)]
+pub struct TransactionFilter {
+    /// Only these kinds
+    pub kinds: Vec<TransactionKind>,
+
+    /// Only this asset
+    pub asset: Option<TransactionAsset>,
+
+    /// Only transactions at or after this time
+    pub since: Option<u64>,
+
+    /// Only transactions before this time
+    pub until: Option<u64>,
+}
+
+impl TransactionFilter {
+    pub fn matches(&self, record: &TransactionRecord) -> bool {
+        (self.kinds.is_empty() || self.kinds.contains(&record.kind))
+            && self
+                .asset
+                .as_ref()
+                .is_none_or(|asset| *asset == record.asset)
+            && self.since.is_none_or(|since| record.timestamp >= since)
+            && self.until.is_none_or(|until| record.timestamp < until)
+    }
+}
+
+// ============================================================================
+// LEDGER RECONCILIATION TYPES
+// ============================================================================
+
+/// Canister-held account whose balance the platform keeps its own record of
+#[derive(CandidType, Deserialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ReconciledAccount {
+    /// A creator's earnings account
+    Earnings { creator: UserId },
+
+    /// A fundraiser's escrow account
+    FundraiserEscrow { post_id: PostId },
+
+    /// The account receiving point purchase proceeds
+    PointsSales,
+}
+
+/// Mismatch between a recorded balance and the ledger, awaiting admin review
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct LedgerAlert {
+    pub id: u64,
+    pub ledger: Principal,
+    pub account: ReconciledAccount,
+
+    /// Balance according to the platform's records
+    pub expected: u64,
+
+    /// Balance reported by the ledger
+    pub actual: u64,
+    pub detected_at: u64,
+
+    /// Last reconciliation that still found the mismatch
+    pub last_seen_at: u64,
+    pub resolved_by: Option<UserId>,
+    pub resolved_at: Option<u64>,
+    pub resolution_note: Option<String>,
+}
+
+// ============================================================================
+// CYCLES MONITORING TYPES
+// ============================================================================
+
+/// Canister cycle balance at one point in time
+#[derive(CandidType, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct CyclesSample {
+    pub timestamp: u64,
+    pub balance: u128,
+}
+
+/// Operational metrics of the backend canister
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct CanisterMetrics {
+    /// Current cycle balance
+    pub cycles_balance: u128,
+
+    /// Balance below which optional features are paused
+    pub low_cycles_threshold: u128,
+
+    /// Whether the balance is below the threshold
+    pub low_cycles: bool,
+
+    /// Average cycles burned per day over the sampled period
+    pub daily_cycles_burn: Option<u128>,
+
+    /// Days until the balance runs out at the current burn rate
+    pub estimated_days_remaining: Option<u64>,
+
+    /// Sampled balances, oldest first
+    pub cycles_history: Vec<CyclesSample>,
+
+    /// Failed update calls by endpoint and error category, since install
+    pub error_counts: Vec<ErrorCount>,
+
+    /// Savings from storing long post bodies compressed
+    pub compression: CompressionStats,
+}
+
+/// Totals for post bodies stored compressed
+#[derive(CandidType, Deserialize, Clone, Debug, Default)]
+pub struct CompressionStats {
+    pub compressed_posts: u64,
+
+    /// Size of the compressed bodies before compression, in bytes
+    pub original_bytes: u64,
+
+    /// Size of the compressed bodies as stored, in bytes
+    pub stored_bytes: u64,
+}
+
+/// Number of failed update calls of one endpoint in one error category
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct ErrorCount {
+    /// Endpoint, rate-limited action or access check that failed
+    pub endpoint: String,
+    pub category: ErrorCategory,
+    pub count: u64,
+}
+
+// ============================================================================
+// LOGGING TYPES
+// ============================================================================
+
+/// Severity of a log entry, from least to most severe
+#[derive(CandidType, Deserialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LogLevel {
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+/// Subsystem a log entry comes from
+#[derive(CandidType, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LogCategory {
+    /// Rejected callers and missing privileges
+    Auth,
+    RateLimit,
+
+    /// Audited moderator actions
+    Moderation,
+
+    /// Audited admin and governance actions
+    Admin,
+    Payments,
+    System,
+}
+
+/// One entry of the in-canister log
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct LogEntry {
+    pub id: u64,
+    pub timestamp: u64,
+    pub level: LogLevel,
+    pub category: LogCategory,
+
+    /// Principal the event concerns, if any
+    pub principal: Option<Principal>,
+    pub message: String,
+}
+
+// ============================================================================
+// POST ARCHIVE TYPES
+// ============================================================================
+
+/// Archive canister that cold posts are moved to
+///
+/// The archive canister must implement
+/// `archive_posts : (vec ArchivedPost) -> (variant { Ok; Err : text })`,
+/// `get_archived_post : (nat64) -> (opt ArchivedPost) query`,
+/// `delete_archived_post : (nat64) -> (variant { Ok; Err : text })` and
+/// `delete_archived_comment : (nat64, nat64) -> (variant { Ok; Err : text })`.
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct ArchiveConfig {
+    pub canister_id: Principal,
+
+    /// Posts at least this many days old are archived
+    pub archive_after_days: u64,
+}
+
+/// A post and its comments as stored in the archive canister
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct ArchivedPost {
+    pub post: Post,
+    pub comments: Vec<Comment>,
+}
+
+/// Copy the archive canister still has to delete
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub enum ArchiveDeletion {
+    /// A purged post, with its comments
+    Post(PostId),
+
+    /// A comment deleted from an archived post
+    Comment(PostId, CommentId),
+}
+
+// ============================================================================
+// READ REPLICA TYPES
+// ============================================================================
+
+/// Change on the primary that read replicas apply to their copy
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub enum StateDelta {
+    /// A public, ungated post was created or had its gates removed (content
+    /// uncompressed)
+    PostCreated(Box<Post>),
+
+    /// A profile was created or changed
+    ProfileUpdated(Box<UserProfile>),
+
+    /// A public, ungated post was deleted by its author or gated
+    PostDeleted(PostId),
+}
+
+/// Read-only replica canister spawned by this canister
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct ReadReplica {
+    pub canister_id: Principal,
+    pub created_at: u64,
+
+    /// Sequence number of the next delta the replica needs
+    pub synced_seq: u64,
+
+    /// Existing data still to copy; deltas are sent once this is `None`
+    pub backfill: Option<ReplicaBackfill>,
+
+    /// Error of the most recent sync attempt, cleared on success
+    pub last_error: Option<String>,
+
+    /// When the replica last accepted a batch
+    pub last_synced_at: Option<u64>,
+}
+
+/// Standby replica on another subnet that keeps public content reachable
+///
+/// Replicas spawned by this canister land on its own subnet, so the mirror is
+/// created elsewhere by its controllers (e.g. through the cycles minting
+/// canister's subnet selection) and installed with `replica_of` set to this
+/// canister.
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct MirrorConfig {
+    pub canister_id: Principal,
+
+    /// Subnet the mirror runs on, as reported by its controllers
+    pub subnet: Principal,
+}
+
+/// Where clients can read public content while this canister is unreachable
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct MirrorStatus {
+    pub canister_id: Principal,
+    pub subnet: Principal,
+
+    /// Whether the mirror holds every public post and profile change so far
+    pub caught_up: bool,
+
+    /// When the mirror last accepted a batch
+    pub last_synced_at: Option<u64>,
+}
+
+/// Copy position of a replica that is being filled with existing data
+#[derive(CandidType, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub enum ReplicaBackfill {
+    /// Copying profiles after `after`, then public posts
+    Profiles { after: Option<UserId> },
+
+    /// Copying public posts after `after`
+    Posts { after: Option<PostId> },
+}
+
+// ============================================================================
+// ACTIVITY TYPES
+// ============================================================================
+
+/// Like, comment or mention a user is notified about
+#[derive(CandidType, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct PostActivity {
+    pub post_id: PostId,
+
+    /// Who liked, commented or mentioned the user
+    pub actor: UserId,
+
+    pub kind: PostActivityKind,
+    pub created_at: u64,
+}
+
+#[derive(CandidType, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PostActivityKind {
+    Like,
+    Comment,
+    Mention,
+}
+
+/// Page of the caller's post activity, newest first
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct PostActivityPage {
+    pub entries: Vec<PostActivity>,
+    pub total: Option<u64>,
+    pub has_more: bool,
+
+    /// Offset of the next page; `None` when exhausted
+    pub next_cursor: Option<u64>,
+}
+
+/// Counts of what happened since a client last polled
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct ActivitySummary {
+    /// Unread direct messages across all conversations
+    pub unread_messages: u64,
+
+    /// Follows gained since the cursor (at most `MAX_RECENT_FOLLOWS`)
+    pub new_followers: u64,
+
+    /// Fact-check labels applied to the caller's posts since the cursor
+    pub new_fact_checks: u64,
+
+    /// Posts by followed users since the cursor, before feed filters
+    pub new_feed_posts: u64,
+
+    /// Upcoming live sessions followed users scheduled since the cursor
+    pub new_live_sessions: u64,
+
+    /// Likes on posts the caller wrote since the cursor, except on muted posts
+    /// (at most `MAX_RECENT_POST_ACTIVITY` likes and comments together)
+    pub new_likes: u64,
+
+    /// Comments on posts the caller wrote or commented on since the cursor,
+    /// except on muted posts
+    pub new_comments: u64,
+
+    /// Posts mentioning the caller since the cursor
+    pub new_mentions: u64,
+
+    /// Pass back as `since` on the next poll
+    pub cursor: u64,
+}
+
+/// Activity over the caller's last completed local day
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct ActivityDigest {
+    /// Start of the period (nanoseconds)
+    pub period_start: u64,
+
+    /// End of the period, the last time the local clock read the digest hour
+    pub period_end: u64,
+
+    /// Counts within the period; unread messages are as of now
+    pub summary: ActivitySummary,
+
+    /// When the next digest period closes
+    pub next_digest_at: u64,
+}
+
+// ============================================================================
+// API VERSION TYPES
+// ============================================================================
+
+/// Version of the Candid interface, for clients to check compatibility
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct ApiVersion {
+    /// Bumped when a method is removed or changes incompatibly
+    pub major: u32,
+
+    /// Bumped when methods or optional fields are added
+    pub minor: u32,
+
+    /// Methods still served for older clients
+    pub deprecated: Vec<DeprecatedMethod>,
+}
+
+/// Method superseded by a versioned variant or another endpoint
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct DeprecatedMethod {
+    pub method: String,
+    pub replacement: String,
+
+    /// Major version that removes the method
+    pub removed_in: u32,
+}
+
+// ============================================================================
+// DEVELOPER APP TYPES
+// ============================================================================
+
+/// What a registered application may do on its owner's behalf
+///
+/// Each scope includes the ones before it.
+#[derive(CandidType, Deserialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum AppScope {
+    /// Read feeds and profiles as the owner
+    ReadOnly,
+
+    /// Also post, comment and like
+    Post,
+
+    /// Also use moderation endpoints, if the owner is a moderator
+    Moderate,
+}
+
+/// Bot or third-party client acting for a user under its own principal
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct DeveloperApp {
+    /// Principal the application signs its calls with
+    pub principal: Principal,
+
+    /// User its calls are attributed to
+    pub owner: UserId,
+
+    pub name: String,
+    pub scope: AppScope,
+    pub created_at: u64,
+}
+
+/// Rate-limited calls made by an application
+#[derive(CandidType, Deserialize, Clone, Debug, Default)]
+pub struct AppUsage {
+    /// Allowed calls by action
+    pub calls_by_action: BTreeMap<String, u64>,
+
+    /// Allowed and throttled calls per day, over the last ACTIVITY_WINDOW_DAYS
+    pub daily_calls: BTreeMap<u64, u64>,
+    pub daily_throttled: BTreeMap<u64, u64>,
+
+    pub last_call_at: Option<u64>,
+}
+
+/// Calls an application made on one day
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct AppDailyUsage {
+    /// Day number since the Unix epoch (UTC)
+    pub day: u64,
+
+    pub calls: u64,
+
+    /// Calls rejected by the application's rate limits
+    pub throttled: u64,
+}
+
+/// Throttles on an application, as percentages of its usual rate limits
+///
+/// The lower of the two applies; 0 rejects every rate-limited call.
+#[derive(CandidType, Deserialize, Clone, Debug, Default)]
+pub struct AppThrottle {
+    /// Set by the application's owner
+    pub owner_percent: Option<u8>,
+
+    /// Set by an admin; the owner cannot lift it
+    pub admin_percent: Option<u8>,
+}
+
+impl AppThrottle {
+    /// Percentage of its usual rate limits the application gets
+    pub fn percent(&self) -> u8 {
+        self.owner_percent
+            .into_iter()
+            .chain(self.admin_percent)
+            .min()
+            .unwrap_or(100)
+    }
+}
+
+/// Usage dashboard of an application, for its owner
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct AppUsageReport {
+    pub app: DeveloperApp,
+
+    /// Throttles in force on the application
+    pub throttle: AppThrottle,
+
+    /// Allowed calls by action, most frequent first
+    pub calls_by_action: Vec<(String, u64)>,
+
+    /// Days with calls, oldest first
+    pub days: Vec<AppDailyUsage>,
+
+    pub last_call_at: Option<u64>,
+}
+
+// ============================================================================
+// FACT-CHECK TYPES
+// ============================================================================
+
+#[derive(CandidType, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FactCheckVerdict {
+    Disputed,
+    MissingContext,
+    Verified,
+}
+
+/// Label attached to a public post by a fact-checker
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct FactCheckLabel {
+    pub post_id: PostId,
+
+    /// Verified organization or journalist who applied the label
+    pub checker: UserId,
+
+    pub verdict: FactCheckVerdict,
+
+    /// https link to the supporting material
+    pub evidence_url: String,
+
+    pub applied_at: u64,
+
+    /// The post author's appeal, if they filed one
+    pub appeal: Option<FactCheckAppeal>,
+}
+
+impl FactCheckLabel {
+    /// Whether the label is shown on the post (not overturned on appeal)
+    pub fn in_force(&self) -> bool {
+        self.appeal
+            .as_ref()
+            .map(|appeal| appeal.status != AppealStatus::Overturned)
+            .unwrap_or(true)
+    }
+}
+
+/// Post author's appeal against a fact-check label, decided by a moderator
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct FactCheckAppeal {
+    pub reason: String,
+    pub filed_at: u64,
+    pub status: AppealStatus,
+    pub resolved_by: Option<UserId>,
+    pub resolved_at: Option<u64>,
+}
+
+#[derive(CandidType, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AppealStatus {
+    Pending,
+
+    /// The label stands
+    Upheld,
+
+    /// The label is withdrawn from the post
+    Overturned,
+}
+
+/// Page of fact-check labels
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct FactChecksPage {
+    pub labels: Vec<FactCheckLabel>,
+    pub total: Option<u64>,
+    pub has_more: bool,
+
+    /// Offset of the next page; `None` when exhausted
+    pub next_cursor: Option<u64>,
+}
+
+// ============================================================================
+// PERMANENT EXPORT TYPES
+// ============================================================================
+
+/// Permanence service public posts are exported to
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct ExportConfig {
+    /// HTTPS endpoint of an IPFS pinning or Arweave bundling gateway that
+    /// stores posted JSON and answers with its `cid`, `IpfsHash` or `id`
+    pub endpoint: String,
+
+    /// Bearer token sent with each upload (never returned by queries)
+    pub api_key: String,
+
+    /// RSA key (PKCS#1 or PKCS#8 PEM) signing each bundle
+    pub private_key_pem: String,
+
+    /// Matching public key, published by `get_export_public_key`
+    pub public_key_pem: String,
+}
+
+/// Off-IC copy of a post, recorded once the permanence service stored it
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct PermanentCopy {
+    /// IPFS CID or Arweave transaction id returned by the service
+    pub locator: String,
+
+    /// SHA-256 of the exported bundle
+    pub bundle_hash: Vec<u8>,
+
+    pub exported_at: u64,
+}
+
+// ============================================================================
+// PRIVATE NOTE TYPES
+// ============================================================================
+
+/// Note visible only to its owner, encrypted client-side (e.g. with the key
+/// from `get_notes_key`); the canister never sees the plaintext
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct PrivateNote {
+    pub id: u64,
+    pub ciphertext: Vec<u8>,
+    pub created_at: u64,
+    pub updated_at: u64,
+}
+
+/// Page of the caller's private notes, most recently updated first
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct NotesPage {
+    pub notes: Vec<PrivateNote>,
+    pub total: Option<u64>,
+    pub has_more: bool,
+
+    /// Offset of the next page; `None` when exhausted
+    pub next_cursor: Option<u64>,
+}
+
+// ============================================================================
+// TIME CAPSULE TYPES
+// ============================================================================
+
+/// Post sealed until its reveal time, then published by a timer
+///
+/// The sealed content is never returned by any endpoint, not even to the author.
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct TimeCapsule {
+    pub id: u64,
+    pub author: UserId,
+
+    /// Visibility the post will be published with
+    pub visibility: PostVisibility,
+
+    pub created_at: u64,
+
+    /// When the post is published, unless the author reschedules it first
+    pub reveal_at: u64,
+
+    /// Post created at reveal; `None` while sealed
+    pub published_post_id: Option<PostId>,
+}
+
+// ============================================================================
+// POLL TYPES
+// ============================================================================
+
+/// How voters mark a poll's options
+#[derive(CandidType, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PollMode {
+    /// Exactly one option
+    SingleChoice,
+    /// Between one and `max_choices` distinct options
+    MultiSelect { max_choices: u32 },
+    /// Distinct options in order of preference, tallied by instant runoff
+    RankedChoice,
+}
+
+/// Poll attached to a post
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct Poll {
+    pub options: Vec<String>,
+    pub mode: PollMode,
+
+    /// Votes are accepted until this time
+    pub closes_at: u64,
+
+    pub voter_count: u64,
+}
+
+/// One counting round of an instant-runoff tally
+#[derive(CandidType, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct RunoffRound {
+    /// Ballots counting toward each option (0 once eliminated)
+    pub counts: Vec<u64>,
+
+    /// Ballots whose ranked options were all eliminated
+    pub exhausted: u64,
+
+    /// Option dropped after this round; `None` in the final round
+    pub eliminated: Option<u32>,
+}
+
+/// Server-side tally of a poll
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct PollResults {
+    pub post_id: PostId,
+    pub poll: Poll,
+
+    /// Whether voting has closed
+    pub closed: bool,
+
+    /// Voters per option; first preferences for ranked polls
+    pub votes: Vec<u64>,
+
+    /// Instant-runoff rounds, for ranked polls only
+    pub rounds: Vec<RunoffRound>,
+
+    /// Leading option (index into `options`); `None` without votes or on a tie
+    pub winner: Option<u32>,
+
+    /// Options the caller picked, in their order
+    pub my_ballot: Option<Vec<u32>>,
+}
+
+// ============================================================================
+// LIVE SESSION TYPES
+// ============================================================================
+
+/// Scheduled live audio or video session
+///
+/// The stream itself runs on an external service; the canister lists the
+/// session and collects RSVPs.
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct LiveSession {
+    pub id: u64,
+    pub host: UserId,
+    pub co_hosts: Vec<UserId>,
+    pub title: String,
+    pub scheduled_at: u64,
+
+    /// Link to the external stream, which the host may add later
+    pub stream_url: Option<String>,
+
+    pub created_at: u64,
+    pub rsvp_count: u64,
+
+    /// Whether the viewer RSVPed (filled in responses)
+    pub rsvped: bool,
+}
+
+/// Page of live sessions, soonest first
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct LiveSessionsPage {
+    pub sessions: Vec<LiveSession>,
+    pub total: Option<u64>,
+    pub has_more: bool,
+
+    /// Offset of the next page; `None` when exhausted
+    pub next_cursor: Option<u64>,
+}
+
+// ============================================================================
+// AUDIO TYPES
+// ============================================================================
+
+/// Uploaded audio clip attached to a post as a voice note
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct AudioClip {
+    /// Stable memory asset; public posts' clips are served at `/audio/{asset_id}`
+    pub asset_id: u64,
+
+    /// Detected from the data, not trusted from the client
+    pub content_type: String,
+
+    pub size_bytes: u64,
+
+    /// Duration reported by the uploader (milliseconds)
+    pub duration_ms: u32,
+
+    /// Peak amplitudes (0-255) for drawing a waveform without fetching the audio
+    pub waveform: Vec<u8>,
+
+    /// Description or transcript for listeners who cannot play the clip
+    pub alt_text: Option<String>,
+}
+
+/// Audio clip being uploaded in chunks
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct AudioUpload {
+    /// Size declared when the upload started
+    pub total_bytes: u64,
+
+    pub data: Vec<u8>,
+}
+
+// ============================================================================
+// QUERY QUOTA TYPES
+// ============================================================================
+
+/// Call allowance of an expensive query within a rolling window
+#[derive(CandidType, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct QueryQuota {
+    /// Calls allowed to each signed-in principal (users and applications)
+    pub per_principal: u32,
+
+    /// Calls allowed to all anonymous callers together
+    pub anonymous_total: u32,
+
+    pub window_seconds: u64,
+}
+
+/// Proof of work anonymous username checks must carry
+#[derive(CandidType, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct UsernameCheckChallenge {
+    /// Leading zero bits required; 0 when no proof is needed
+    pub difficulty: u8,
+
+    /// Current time bucket, part of the subject the proof is computed over
+    pub bucket: u64,
+}
+
+// ============================================================================
+// DELETION TYPES
+// ============================================================================
+
+/// Post deleted by its author, recoverable until it is purged
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct DeletedPost {
+    pub post: Post,
+    pub deleted_at: u64,
+
+    /// When the purge timer removes the post for good
+    pub purge_at: u64,
+}
+
+/// Comment deleted by its author, recoverable until it is purged
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct DeletedComment {
+    pub comment: Comment,
+    pub deleted_at: u64,
+
+    /// When the purge timer removes the comment for good
+    pub purge_at: u64,
+}
+
+// ============================================================================
+// TRANSPARENCY TYPES
+// ============================================================================
+
+/// Calendar period (UTC) a transparency report covers
+#[derive(CandidType, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub enum ReportPeriod {
+    Month { year: u32, month: u8 },
+    Quarter { year: u32, quarter: u8 },
+    Year(u32),
+}
+
+/// Moderation actions of one category within a report period
+#[derive(CandidType, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ModerationActionCount {
+    /// e.g. "strike_issued", "domain_blocked", "legal_takedown"
+    pub category: String,
+    pub count: u64,
+}
+
+/// Public summary of moderation activity over a period
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct TransparencyReport {
+    /// Start of the period (nanoseconds)
+    pub period_start: u64,
+
+    /// End of the period, exclusive (nanoseconds)
+    pub period_end: u64,
+
+    /// Posts flagged for linking to blocked domains
+    pub posts_flagged: u64,
+
+    /// Labels applied by fact-checkers
+    pub fact_check_labels: u64,
+
+    /// Actions taken against content or accounts, from the audit log
+    pub actions: Vec<ModerationActionCount>,
+
+    /// Appeals filed against fact-check labels
+    pub appeals_filed: u64,
+
+    /// Appeals decided in the period that kept the label
+    pub appeals_upheld: u64,
+
+    /// Appeals decided in the period that withdrew the label
+    pub appeals_overturned: u64,
+
+    /// Government and legal takedown requests logged by admins
+    pub legal_requests: u64,
+
+    /// Legal requests the platform acted on
+    pub legal_requests_complied: u64,
+}
+
+/// Government or legal takedown request received by the platform
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct LegalRequest {
+    /// Government body, court or rights holder making the request
+    pub authority: String,
+
+    /// Country or region whose law the request invokes
+    pub jurisdiction: String,
+
+    /// Account the request concerns, if any
+    pub target: Option<Principal>,
+
+    /// Whether the platform acted on the request
+    pub complied: bool,
+
+    /// What was requested and done, without personal data
+    pub summary: String,
+}
+
+// ============================================================================
+// PUSH NOTIFICATION TYPES
+// ============================================================================
+
+/// Relay service push notifications are handed to
+///
+/// The relay does the Web Push encryption (VAPID) or forwards to its own
+/// mobile push provider, so the canister never holds device keys.
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct PushConfig {
+    /// HTTPS endpoint notifications are POSTed to
+    pub relay_url: String,
+
+    /// Bearer token sent with each notification (never returned by queries)
+    pub api_key: String,
+}
+
+/// Device a user asked to receive push notifications on
+#[derive(CandidType, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub enum PushEndpoint {
+    /// Browser `PushSubscription` serialized with `JSON.stringify`
+    WebPush { subscription: String },
+
+    /// Device token issued by the relay service
+    RelayToken { token: String },
+}
+
+/// What a push notification is about; only high-priority activity is pushed
+#[derive(CandidType, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub enum PushKind {
+    Comment,
+    Mention,
+    DirectMessage,
+}
+
+/// Notification sent to a device, without post or message content
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct PushNotification {
+    pub kind: PushKind,
+    pub actor: UserId,
+    pub post_id: Option<PostId>,
+    pub conversation_id: Option<u64>,
+    pub created_at: u64,
+}
+
+/// Notification waiting to be delivered to one endpoint
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct PendingPush {
+    pub id: u64,
+    pub recipient: UserId,
+    pub endpoint: PushEndpoint,
+    pub notification: PushNotification,
+
+    /// Failed deliveries so far
+    pub attempts: u32,
+
+    /// Earliest time (nanoseconds) of the next delivery attempt
+    pub next_attempt_at: u64,
+}
+
+// ============================================================================
+// WEBHOOK TYPES
+// ============================================================================
+
+/// Event about a creator's own content a webhook can subscribe to
+#[derive(CandidType, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub enum WebhookEvent {
+    NewComment,
+    NewFollower,
+    TipReceived,
+}
+
+/// URL a verified creator or organization receives events at
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct Webhook {
+    pub id: u64,
+
+    /// HTTPS URL payloads are POSTed to
+    pub url: String,
+
+    pub events: Vec<WebhookEvent>,
+
+    /// Key for the HMAC-SHA256 `X-Decentra-Signature` header, generated by
+    /// the canister and shown only to the owner
+    pub secret: String,
+
+    pub created_at: u64,
+}
+
+/// Outcome of a webhook delivery so far
+#[derive(CandidType, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub enum WebhookDeliveryStatus {
+    /// Waiting for its first attempt or a retry
+    Pending,
+    Delivered,
+
+    /// Dropped after MAX_WEBHOOK_ATTEMPTS failed attempts
+    Failed,
+}
+
+/// Entry in a webhook's delivery log
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct WebhookDelivery {
+    /// Also sent as the `X-Decentra-Delivery` header
+    pub id: u64,
+    pub event: WebhookEvent,
+    pub status: WebhookDeliveryStatus,
+    pub attempts: u32,
+
+    /// Response status of the last attempt, if the receiver answered
+    pub response_status: Option<u16>,
+
+    pub last_error: Option<String>,
+    pub created_at: u64,
+    pub last_attempt_at: Option<u64>,
+}
+
+/// Delivery waiting for its next attempt
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct PendingWebhookDelivery {
+    pub owner: UserId,
+    pub webhook_id: u64,
+    pub delivery_id: u64,
+
+    /// Serialized payload, identical across retries so signatures stay
+    /// comparable
+    pub body: String,
+
+    pub attempts: u32,
+
+    /// Earliest time (nanoseconds) of the next attempt
+    pub next_attempt_at: u64,
+}
+
+/// Page of a webhook's delivery log, newest first
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct WebhookDeliveryPage {
+    pub entries: Vec<WebhookDelivery>,
+    pub total: Option<u64>,
+    pub has_more: bool,
+
+    /// Offset of the next page; `None` when exhausted
+    pub next_cursor: Option<u64>,
+}
+
+// ============================================================================
+// EVENT JOURNAL TYPES
+// ============================================================================
+
+/// Change to public data recorded in the event journal
+#[derive(CandidType, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub enum JournalEventKind {
+    /// A public post was created, or restored after deletion
+    PostCreated { post_id: PostId },
+
+    /// A public post was deleted by its author
+    PostDeleted { post_id: PostId },
+
+    /// A profile was created or changed
+    ProfileUpdated { user_id: UserId },
+}
+
+/// Journal entry with the public data it refers to, as of the query
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct JournalEvent {
+    pub seq: u64,
+    pub timestamp: u64,
+    pub kind: JournalEventKind,
+
+    /// The post as anonymous viewers see it; `None` once it is no longer
+    /// public
+    pub post: Option<Post>,
+
+    /// The profile as anonymous viewers see it
+    pub profile: Option<UserProfile>,
+}
+
+/// Page of the event journal, oldest first
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct JournalPage {
+    pub events: Vec<JournalEvent>,
+
+    /// `since_seq` for the next call
+    pub next_seq: u64,
+
+    /// Oldest sequence number still held; a `since_seq` below it means
+    /// events were missed and the indexer should resync in full
+    pub first_seq: u64,
+
+    pub has_more: bool,
+}
+
+// ============================================================================
+// ARCHIVE IMPORT TYPES
+// ============================================================================
+
+/// Platform an archive was exported from
+#[derive(CandidType, Deserialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ImportPlatform {
+    Twitter,
+    Mastodon,
+}
+
+/// Post from another platform's export, parsed by the client
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct ExportedPost {
+    /// Id on the original platform; a post is imported once per id
+    pub original_id: String,
+
+    pub content: String,
+
+    /// Original publication time (nanoseconds)
+    pub created_at: u64,
+
+    pub language: Option<String>,
+}
+
+/// One chunk of a parsed export
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct ImportChunk {
+    pub platform: ImportPlatform,
+    pub posts: Vec<ExportedPost>,
+
+    /// Handles of followed accounts (`alice`, `@alice` or `@alice@instance`)
+    pub following: Vec<String>,
+
+    /// Visibility of the imported posts (default Public)
+    pub visibility: Option<PostVisibility>,
+}
+
+/// Where an imported post was first published
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct ImportSource {
+    pub platform: ImportPlatform,
+    pub original_id: String,
+    pub imported_at: u64,
+}
+
+/// Outcome of one import chunk
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct ImportChunkResult {
+    pub imported: u64,
+
+    /// Posts imported by an earlier chunk
+    pub duplicates: u64,
+
+    /// Original ids of posts that failed validation or exceeded the import
+    /// quota
+    pub rejected: Vec<String>,
+
+    /// Original ids of posts over the posting rate limit; send them again later
+    pub deferred: Vec<String>,
+
+    /// Followed handles queued for resolution
+    pub follows_queued: u64,
+}
+
+/// Caller's archive import totals
+#[derive(CandidType, Deserialize, Clone, Debug, Default)]
+pub struct ImportStatus {
+    pub posts_imported: u64,
+    pub follows_queued: u64,
+
+    /// Suggestions the importer confirmed and followed (or sent a follow
+    /// request)
+    pub follows_made: u64,
+
+    /// Queued handles not matched yet
+    pub follows_pending: u64,
+
+    /// Matched handles awaiting the importer's confirmation
+    pub follows_suggested: u64,
+}
+
+/// Followed handle from an archive, waiting for a match and confirmation
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct PendingImportFollow {
+    pub queued_at: u64,
+
+    /// User holding the handle when last checked
+    pub matched: Option<UserId>,
+}
+
+/// User matching a handle followed in an imported archive
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct ImportFollowSuggestion {
+    /// Handle as queued (normalized local part)
+    pub handle: String,
+
+    pub user: UserProfile,
+}
+
+// ============================================================================
+// GOVERNANCE TYPES
+// ============================================================================
+
+/// Init and upgrade arguments
+#[derive(CandidType, Deserialize, Clone, Debug, Default)]
+pub struct CanisterArgs {
+    /// SNS governance canister allowed to execute proposals
+    pub sns_governance: Option<Principal>,
+
+    /// Replace all admins with the governance canister (the DAO hand-over)
+    pub transfer_admin_to_governance: Option<bool>,
+
+    /// Run as a read-only replica of this primary canister
+    pub replica_of: Option<Principal>,
+}
+
+/// Moderation parameters controlled by governance
+#[derive(CandidType, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ModerationPolicy {
+    /// Reputation points deducted per active moderation strike
+    pub strike_penalty: u32,
+
+    /// Minimum reputation required to vote on community notes
+    pub min_reputation_for_community_notes: u32,
+}
+
+impl Default for ModerationPolicy {
+    fn default() -> Self {
+        Self {
+            strike_penalty: REPUTATION_STRIKE_PENALTY,
+            min_reputation_for_community_notes: MIN_REPUTATION_FOR_COMMUNITY_NOTES,
+        }
+    }
+}
+
+/// Change executed by an SNS proposal
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub enum GovernanceAction {
+    SetModerationPolicy(ModerationPolicy),
+    AddAdmin(Principal),
+    RemoveAdmin(Principal),
+    AddModerator(Principal),
+    RemoveModerator(Principal),
+    SetPersonhoodRequirement { action: String, required: bool },
+}
+
+// ============================================================================
+// SOCIAL NETWORK CONSTANTS
+// ============================================================================
+
+/// Maximum post content length (characters)
+pub const MAX_POST_CONTENT: usize = 10_000;
+
+/// Minimum post content length (characters)
+pub const MIN_POST_CONTENT: usize = 1;
+
+/// Maximum comment content length (characters)
+pub const MAX_COMMENT_CONTENT: usize = 500;
+
+/// Minimum comment content length (characters)
+pub const MIN_COMMENT_CONTENT: usize = 1;
+
+/// Maximum username length (characters)
+pub const MAX_USERNAME_LENGTH: usize = 50;
+
+/// Minimum username length (characters)
+pub const MIN_USERNAME_LENGTH: usize = 3;
+
+/// Minimum days between handle changes
+pub const HANDLE_CHANGE_COOLDOWN_DAYS: u64 = 30;
+
+/// Days a vacated handle keeps redirecting and stays reserved for its previous owner
+pub const HANDLE_REDIRECT_DAYS: u64 = 90;
+
+/// Maximum display name length (characters)
+pub const MAX_DISPLAY_NAME_LENGTH: usize = 64;
+
+/// Maximum bio length (characters)
+pub const MAX_BIO_LENGTH: usize = 500;
+
+/// Maximum avatar length (characters) - for emoji sequences and asset references
+pub const MAX_AVATAR_LENGTH: usize = 200;
+
+/// Maximum size of an uploaded avatar image (bytes)
+pub const MAX_AVATAR_IMAGE_BYTES: usize = 64 * 1024;
+
+/// Maximum verification evidence length (characters)
+pub const MAX_VERIFICATION_EVIDENCE_LENGTH: usize = 2_000;
+
+/// Maximum review note length (characters)
+pub const MAX_REVIEW_NOTE_LENGTH: usize = 500;
+
+/// Maximum number of team members per organization account
+pub const MAX_ORG_MEMBERS: usize = 50;
+
+/// Maximum number of custom emoji per organization
+pub const MAX_CUSTOM_EMOJI_PER_ORG: usize = 50;
+
+/// Maximum custom emoji shortcode length (characters)
+pub const MAX_EMOJI_SHORTCODE_LENGTH: usize = 32;
+
+/// Image hosts trusted for banners, emoji assets and post media until an
+/// admin edits the list (subdomains are trusted too)
+pub const DEFAULT_TRUSTED_IMAGE_DOMAINS: [&str; 9] = [
+    "imgur.com",
+    "github.com",
+    "githubusercontent.com",
+    "gravatar.com",
+    "avatar.com",
+    "cloudinary.com",
+    "cloudflare.com",
+    "unsplash.com",
+    "pexels.com",
+];
+
+/// Maximum number of trusted image domains
+pub const MAX_TRUSTED_IMAGE_DOMAINS: usize = 100;
+
+/// Maximum number of posting delegates per user
+pub const MAX_POSTING_DELEGATES: usize = 20;
+
+/// Maximum principals a user can grant capabilities to
+pub const MAX_CAPABILITY_GRANTS: usize = 20;
+
+/// Maximum number of drafts awaiting approval per owner
+pub const MAX_PENDING_DRAFTS: usize = 100;
+
+/// Maximum number of collaborators invited to a draft
+pub const MAX_DRAFT_COLLABORATORS: usize = 10;
+
+/// Maximum whistleblower submission message length (characters)
+pub const MAX_SUBMISSION_MESSAGE_LENGTH: usize = 10_000;
+
+/// Maximum number of attachments per whistleblower submission
+pub const MAX_SUBMISSION_ATTACHMENTS: usize = 5;
+
+/// Maximum total attachment size per submission (bytes), below the ingress limit
+pub const MAX_SUBMISSION_ATTACHMENT_BYTES: usize = 1_500_000;
+
+/// Maximum number of submissions held in a journalist's inbox
+pub const MAX_INBOX_SUBMISSIONS: usize = 500;
+
+/// Maximum plain text direct message length (characters)
+pub const MAX_MESSAGE_LENGTH: usize = 2_000;
+
+/// Maximum encrypted direct message payload size (bytes)
+pub const MAX_MESSAGE_CIPHERTEXT_BYTES: usize = 16_384;
+
+/// Maximum number of members in a group conversation
+pub const MAX_GROUP_MEMBERS: usize = 100;
+
+/// Maximum group title length (characters)
+pub const MAX_GROUP_TITLE_LENGTH: usize = 100;
+
+/// Smallest allowed group message retention limit
+pub const MIN_GROUP_RETENTION: u64 = 10;
+
+/// Largest allowed (and default) group message retention limit
+pub const MAX_GROUP_RETENTION: u64 = 10_000;
+
+/// Default page size for message history
+pub const DEFAULT_MESSAGES_LIMIT: usize = 50;
+
+/// Maximum page size for message history
+pub const MAX_MESSAGES_LIMIT: usize = 200;
+
+/// Maximum banner URL length (characters)
+pub const MAX_BANNER_LENGTH: usize = 300;
+
+/// Nanoseconds in one day (IC timestamps are in nanoseconds)
+pub const NANOS_PER_DAY: u64 = 86_400 * 1_000_000_000;
+
+/// Default number of days returned by analytics time series
+pub const DEFAULT_ANALYTICS_DAYS: u64 = 30;
+
+/// Days of analytics history retained and returned at most
+pub const MAX_ANALYTICS_DAYS: u64 = 365;
+
+/// Days of platform activity buckets retained (also the MAU window)
+pub const ACTIVITY_WINDOW_DAYS: u64 = 30;
+
+/// Maximum number of entries returned by top-post queries
+pub const MAX_TOP_POSTS_LIMIT: usize = 50;
+
+/// Accounts younger than this (days) and unverified are rate limited as New
+pub const NEW_ACCOUNT_DAYS: u64 = 7;
+
+/// Account age (days) after which an account counts as Established
+pub const ESTABLISHED_ACCOUNT_DAYS: u64 = 90;
+
+/// Maximum reputation score
+pub const MAX_REPUTATION_SCORE: u32 = 100;
+
+/// Default reputation points deducted per active moderation strike
+pub const REPUTATION_STRIKE_PENALTY: u32 = 15;
+
+/// Default minimum reputation required to vote on community notes
+pub const MIN_REPUTATION_FOR_COMMUNITY_NOTES: u32 = 50;
+
+/// Days a proof of personhood stays valid before it must be renewed
+pub const PERSONHOOD_PROOF_VALIDITY_DAYS: u64 = 365;
+
+/// How often NFT avatar ownership is re-verified (seconds)
+pub const NFT_AVATAR_REVERIFY_INTERVAL_SECONDS: u64 = 6 * 3_600;
+
+/// Maximum token ids sent in one ICRC-7 owner query
+pub const ICRC7_QUERY_BATCH_SIZE: usize = 20;
+
+/// How long a token gate lookup result is reused (seconds)
+pub const TOKEN_GATE_CACHE_SECONDS: u64 = 600;
+
+/// Longest allowed creator fund period (days)
+pub const MAX_CREATOR_FUND_PERIOD_DAYS: u64 = 90;
+
+/// Minimum engagement points in a period to earn creator fund rewards
+pub const MIN_CREATOR_FUND_POINTS: u64 = 10;
+
+/// Rewards and claims kept in each creator's fund history
+pub const MAX_CREATOR_FUND_HISTORY: usize = 100;
+
+/// Points a user can earn from engagement per day
+pub const POINTS_DAILY_EARN_LIMIT: u64 = 200;
+
+/// Points a user can tip per day
+pub const POINTS_DAILY_SEND_LIMIT: u64 = 10_000;
+
+/// Largest single tip in points
+pub const MAX_POINTS_TIP: u64 = 1_000;
+
+/// Largest promotional grant in points
+pub const MAX_POINTS_GRANT: u64 = 100_000;
+
+/// Maximum length of a grant memo
+pub const MAX_POINTS_MEMO_LENGTH: usize = 200;
+
+/// Transactions kept in each user's points history
+pub const MAX_POINTS_HISTORY: usize = 200;
+
+/// Withdrawals kept in each creator's history
+pub const MAX_WITHDRAWAL_HISTORY: usize = 100;
+
+/// Length of one subscription billing period
+pub const SUBSCRIPTION_PERIOD_DAYS: u64 = 30;
+
+/// Maximum number of tiers per creator, archived ones included
+pub const MAX_SUBSCRIPTION_TIERS: usize = 10;
+
+/// Maximum length of a subscription tier name
+pub const MAX_TIER_NAME_LENGTH: usize = 50;
+
+/// Maximum length of a subscription tier perks description
+pub const MAX_TIER_PERKS_LENGTH: usize = 1_000;
+
+/// How often due subscriptions are renewed
+pub const SUBSCRIPTION_RENEWAL_INTERVAL_SECONDS: u64 = 3_600;
+
+/// Shortest promotion a budget can buy
+pub const MIN_PROMOTION_SECONDS: u64 = 3_600;
+
+/// Longest promotion a budget can buy
+pub const MAX_PROMOTION_DAYS: u64 = 30;
+
+/// Every n-th explore feed slot, starting with the first, is promoted
+pub const PROMOTED_SLOT_INTERVAL: usize = 5;
+
+/// Days of engagement that rank posts in the explore feed
+pub const TRENDING_WINDOW_DAYS: u64 = 7;
+
+/// Longest time a fundraiser can stay open
+pub const MAX_FUNDRAISER_DAYS: u64 = 90;
+
+/// How often fundraisers past their deadline are settled
+pub const FUNDRAISER_SETTLEMENT_INTERVAL_SECONDS: u64 = 3_600;
+
+/// Age up to which ledgers are trusted to deduplicate a retried transfer by
+/// its `created_at_time` (an hour short of the usual 24-hour window)
+pub const LEDGER_DEDUP_WINDOW_SECONDS: u64 = 82_800;
+
+/// Entries kept in each user's transaction log
+pub const MAX_TRANSACTION_LOG: usize = 1_000;
+
+/// How often recorded balances are checked against the ledgers
+pub const LEDGER_RECONCILIATION_INTERVAL_SECONDS: u64 = 21_600;
+
+/// How often the cycle balance is sampled
+pub const CYCLES_SAMPLE_INTERVAL_SECONDS: u64 = 3_600;
+
+/// Cycle balance samples kept (30 days of hourly samples)
+pub const MAX_CYCLES_SAMPLES: usize = 720;
+
+/// Default balance below which optional features are paused (1T cycles)
+pub const DEFAULT_LOW_CYCLES_THRESHOLD: u128 = 1_000_000_000_000;
+
+/// Entries kept in the in-canister log
+pub const MAX_LOG_ENTRIES: usize = 5_000;
+
+/// Longer log messages are truncated to this many characters
+pub const MAX_LOG_MESSAGE_LENGTH: usize = 500;
+
+/// Post bodies of at least this many bytes are stored compressed
+pub const COMPRESSION_THRESHOLD_BYTES: usize = 1_024;
+
+/// How often cold posts are moved to the archive canister
+pub const ARCHIVE_INTERVAL_SECONDS: u64 = 86_400;
+
+/// Youngest age, in days, the archive can be configured to take posts at
+pub const MIN_ARCHIVE_AFTER_DAYS: u64 = 30;
+
+/// Posts moved to the archive canister per call
+pub const MAX_ARCHIVE_BATCH: usize = 100;
+
+/// How often pending deltas are pushed to read replicas
+pub const REPLICA_SYNC_INTERVAL_SECONDS: u64 = 10;
+
+/// Deltas or copied records sent to a replica per call
+pub const MAX_REPLICA_BATCH: usize = 200;
+
+/// Deltas kept for replicas; a replica that falls further behind is re-copied
+pub const MAX_REPLICA_DELTAS: usize = 10_000;
+
+/// Maximum number of read replicas
+pub const MAX_READ_REPLICAS: usize = 10;
+
+/// How often cached responses for anonymous viewers are recomputed
+pub const HOT_CACHE_REFRESH_SECONDS: u64 = 30;
+
+/// Explore feed pages (of `DEFAULT_FEED_LIMIT` posts) kept in the cache
+pub const HOT_FEED_PAGES: usize = 5;
+
+/// Most followed profiles kept in the cache
+pub const HOT_PROFILE_COUNT: usize = 100;
+
+/// Recent follows kept per user for activity summaries
+pub const MAX_RECENT_FOLLOWS: usize = 500;
+
+/// Likes, comments and mentions kept per user for activity polling
+pub const MAX_RECENT_POST_ACTIVITY: usize = 500;
+
+/// Candid interface version; the major part changes on breaking changes
+pub const API_VERSION_MAJOR: u32 = 1;
+pub const API_VERSION_MINOR: u32 = 0;
+
+/// Methods kept for older clients as (method, replacement, major version removing it)
+pub const DEPRECATED_METHODS: &[(&str, &str, u32)] = &[("get_user_feed", "get_social_feed", 2)];
+
+/// Maximum applications a user can register
+pub const MAX_APPS_PER_USER: usize = 10;
+
+/// Maximum length of an application name
+pub const MAX_APP_NAME_LENGTH: usize = 64;
+
+/// Share of a user's rate limits their applications may use together
+pub const APP_RATE_LIMIT_PERCENT: u32 = 50;
+
+/// Post coordinates are rounded to this many degrees (about 11 km)
+pub const LOCATION_GRID_DEGREES: f64 = 0.1;
+
+/// Maximum length of a post's place name
+pub const MAX_PLACE_NAME_LENGTH: usize = 100;
+
+/// Maximum height and width of a `get_posts_near` region, in degrees
+pub const MAX_REGION_DEGREES: f64 = 5.0;
+
+/// Maximum number of sources cited by a post
+pub const MAX_POST_SOURCES: usize = 10;
+
+/// Maximum length of a cited source's title
+pub const MAX_CITATION_TITLE_LENGTH: usize = 200;
+
+/// Maximum length of a post author's appeal against a fact-check label
+pub const MAX_APPEAL_REASON_LENGTH: usize = 1_000;
+
+/// How often queued public posts are exported to the permanence service
+pub const EXPORT_INTERVAL_SECONDS: u64 = 600;
+
+/// Posts exported per run, one outcall each
+pub const MAX_EXPORT_BATCH: usize = 10;
+
+/// Maximum number of private notes per user
+pub const MAX_NOTES_PER_USER: usize = 200;
+
+/// Maximum encrypted private note size (bytes)
+pub const MAX_NOTE_CIPHERTEXT_BYTES: usize = 65_536;
+
+/// Maximum number of sealed time capsules per user
+pub const MAX_TIME_CAPSULES_PER_USER: usize = 20;
+
+/// Furthest a time capsule can be sealed for, in days
+pub const MAX_TIME_CAPSULE_DAYS: u64 = 3_650;
+
+/// How often due time capsules are published
+pub const TIME_CAPSULE_CHECK_INTERVAL_SECONDS: u64 = 60;
+
+/// Fewest options a poll can offer (ranked polls need one more to have a runoff)
+pub const MIN_POLL_OPTIONS: usize = 2;
+
+/// Most options a poll can offer
+pub const MAX_POLL_OPTIONS: usize = 10;
+
+/// Maximum length of a poll option
+pub const MAX_POLL_OPTION_LENGTH: usize = 100;
+
+/// Longest a poll can stay open
+pub const MAX_POLL_DAYS: u64 = 30;
+
+/// Maximum length of a live session title
+pub const MAX_SESSION_TITLE_LENGTH: usize = 100;
+
+/// Maximum co-hosts of a live session
+pub const MAX_SESSION_CO_HOSTS: usize = 5;
+
+/// Maximum upcoming live sessions a user can host
+pub const MAX_UPCOMING_SESSIONS_PER_USER: usize = 20;
+
+/// How far ahead a live session can be scheduled
+pub const MAX_SESSION_SCHEDULE_DAYS: u64 = 90;
+
+/// How long a session stays listed after its start, while it may still be live
+pub const LIVE_SESSION_LISTING_SECONDS: u64 = 4 * 3_600;
+
+/// Maximum size of an audio clip (bytes), small enough for one query reply
+pub const MAX_AUDIO_CLIP_BYTES: usize = 2 * 1024 * 1024;
+
+/// Maximum size of one upload chunk (bytes), below the ingress limit
+pub const MAX_UPLOAD_CHUNK_BYTES: usize = 1_000_000;
+
+/// Longest audio clip (seconds)
+pub const MAX_AUDIO_DURATION_SECONDS: u32 = 140;
+
+/// Maximum waveform samples stored with an audio clip
+pub const MAX_WAVEFORM_SAMPLES: usize = 256;
+
+/// Maximum length of a media description
+pub const MAX_ALT_TEXT_LENGTH: usize = 1_500;
+
+/// Most negative UTC offset a user can set (UTC-12:00), in minutes
+pub const MIN_UTC_OFFSET_MINUTES: i16 = -720;
+
+/// Most positive UTC offset a user can set (UTC+14:00), in minutes
+pub const MAX_UTC_OFFSET_MINUTES: i16 = 840;
+
+/// Local hour at which daily digests close unless the user picks another
+pub const DEFAULT_DIGEST_HOUR: u8 = 8;
+
+/// Username availability checks per signed-in caller per minute
+pub const USERNAME_CHECKS_PER_MINUTE: u32 = 20;
+
+/// Length of the time buckets username check proofs of work are bound to
+/// (seconds); a proof is accepted in its own bucket and the next one
+pub const USERNAME_CHECK_POW_BUCKET_SECONDS: u64 = 300;
+
+/// Highest proof-of-work difficulty admins can demand of username checks (bits)
+pub const MAX_USERNAME_CHECK_DIFFICULTY: u8 = 24;
+
+/// Most rounds a username check answer is delayed by
+pub const MAX_USERNAME_CHECK_JITTER_ROUNDS: u8 = 2;
+
+/// How often expired username check quota entries are pruned
+pub const USERNAME_CHECK_PRUNE_INTERVAL_SECONDS: u64 = 300;
+
+/// Queries that admins can put under a call quota
+pub const QUOTA_GUARDED_QUERIES: [&str; 5] = [
+    "get_social_feed",
+    "get_explore_feed",
+    "get_followers",
+    "get_following",
+    "get_events",
+];
+
+/// Longest window a query quota can count calls over (seconds)
+pub const MAX_QUERY_QUOTA_WINDOW_SECONDS: u64 = 86_400;
+
+/// Actions admins can put behind an account standing gate:
+/// - `follow_user`: following more than UNGATED_DAILY_FOLLOWS accounts a day
+/// - `post_links`: posts with more than UNGATED_POST_LINKS links
+/// - `create_group_conversation`: starting group conversations
+pub const TRUST_GATEABLE_ACTIONS: [&str; 3] =
+    ["follow_user", "post_links", "create_group_conversation"];
+
+/// Follows a day any account may make before the `follow_user` gate applies
+pub const UNGATED_DAILY_FOLLOWS: usize = 50;
+
+/// Links a post may carry before the `post_links` gate applies
+pub const UNGATED_POST_LINKS: usize = 2;
+
+/// Longest account age an action gate may demand
+pub const MAX_GATE_ACCOUNT_AGE_DAYS: u64 = 365;
+
+/// Days a deleted post or comment can be restored before it is purged
+pub const DELETION_UNDO_DAYS: u64 = 30;
+
+/// How often deleted content past its undo window is purged
+pub const DELETION_PURGE_INTERVAL_SECONDS: u64 = 3_600;
+
+/// Post deletions held for `get_feed_updates`
+pub const MAX_DELETION_LOG_ENTRIES: usize = 10_000;
+
+/// Longest authority or jurisdiction name on a logged legal request
+pub const MAX_LEGAL_AUTHORITY_LENGTH: usize = 100;
+
+/// Push endpoints a user may register
+pub const MAX_PUSH_ENDPOINTS: usize = 5;
+
+/// Longest web-push subscription or relay token accepted
+pub const MAX_PUSH_ENDPOINT_LENGTH: usize = 1_024;
+
+/// Push notifications queued for a user per hour; later ones are not pushed
+pub const MAX_PUSHES_PER_HOUR: usize = 30;
+
+/// Largest push queue; notifications created while it is full are not pushed
+pub const MAX_PENDING_PUSHES: usize = 10_000;
+
+/// How often queued push notifications are dispatched
+pub const PUSH_DISPATCH_INTERVAL_SECONDS: u64 = 30;
+
+/// Push notifications delivered per dispatch
+pub const MAX_PUSH_BATCH: usize = 20;
+
+/// Delivery attempts before a push notification is dropped
+pub const MAX_PUSH_ATTEMPTS: u32 = 5;
+
+/// Webhooks a user may register
+pub const MAX_WEBHOOKS: usize = 5;
+
+/// Deliveries kept in each webhook's log
+pub const MAX_WEBHOOK_DELIVERY_LOG: usize = 100;
+
+/// Largest webhook delivery queue; events raised while it is full are not
+/// delivered
+pub const MAX_PENDING_WEBHOOK_DELIVERIES: usize = 10_000;
+
+/// How often queued webhook deliveries are attempted
+pub const WEBHOOK_DISPATCH_INTERVAL_SECONDS: u64 = 30;
+
+/// Webhook deliveries attempted per dispatch
+pub const MAX_WEBHOOK_BATCH: usize = 20;
+
+/// Delivery attempts before a webhook delivery is marked failed
+pub const MAX_WEBHOOK_ATTEMPTS: u32 = 5;
+
+/// Entries held in the event journal
+pub const MAX_JOURNAL_ENTRIES: usize = 100_000;
+
+/// Events returned per `get_events` call
+pub const MAX_JOURNAL_PAGE: usize = 100;
+
+/// Posts accepted per import chunk
+pub const MAX_IMPORT_CHUNK_POSTS: usize = 100;
+
+/// Followed handles accepted per import chunk
+pub const MAX_IMPORT_CHUNK_FOLLOWS: usize = 500;
+
+/// Posts a user may import in total
+pub const MAX_IMPORTED_POSTS: u64 = 20_000;
+
+/// Followed handles a user may queue in total
+pub const MAX_IMPORT_FOLLOWS: u64 = 5_000;
+
+/// Longest original post id accepted
+pub const MAX_IMPORT_ORIGINAL_ID_LENGTH: usize = 64;
+
+/// Earliest original publication time accepted (2006-01-01, seconds)
+pub const EARLIEST_IMPORT_TIMESTAMP_SECONDS: u64 = 1_136_073_600;
+
+/// How often queued import follows are matched against handles
+pub const IMPORT_FOLLOW_INTERVAL_SECONDS: u64 = 600;
+
+/// Queued import follows checked per run
+pub const MAX_IMPORT_FOLLOW_BATCH: usize = 200;
+
+/// Days an unmatched import follow stays queued, waiting for its handle to
+/// be claimed
+pub const IMPORT_FOLLOW_RETENTION_DAYS: u64 = 30;
+
+/// Maximum length of a personhood provider method name
+pub const MAX_PERSONHOOD_METHOD_LENGTH: usize = 64;
+
+/// Maximum length of the NFT collection's mint method name
+pub const MAX_NFT_MINT_METHOD_LENGTH: usize = 64;
+
+/// Rate-limited actions that admins may restrict to proven humans
+pub const PERSONHOOD_GATEABLE_ACTIONS: &[&str] = &[
+    "create_post",
+    "like_post",
+    "add_comment",
+    "send_message",
+    "record_post_view",
+    "apply_for_verification",
+    "submit_to_journalist",
+    "create_group_conversation",
+];
+
+/// Maximum length of a language tag (e.g. "en", "pt-BR", "zh-Hant")
+pub const MAX_LANGUAGE_CODE_LENGTH: usize = 12;
+
+/// Maximum number of preferred languages per user
+pub const MAX_PREFERRED_LANGUAGES: usize = 10;
+
+/// Maximum number of custom words in a user's word filter
+pub const MAX_CUSTOM_FILTER_WORDS: usize = 100;
+
+/// Maximum length of a custom filter word (characters)
+pub const MAX_FILTER_WORD_LENGTH: usize = 50;
+
+/// Maximum length of a blocked domain
+pub const MAX_DOMAIN_LENGTH: usize = 253;
+
+/// Number of posts checked per run of the blocklist retro-scan job
+pub const BLOCKLIST_SCAN_BATCH_SIZE: usize = 500;
+
+/// Maximum length of admin-configured outcall endpoint URLs
+pub const MAX_OUTCALL_ENDPOINT_LENGTH: usize = 300;
+
+/// Maximum number of IDs accepted by batch fetch endpoints
+pub const MAX_BATCH_FETCH_SIZE: usize = 100;
+
+/// How often the entropy pool is reseeded from `raw_rand` (seconds)
+pub const ENTROPY_REFRESH_INTERVAL_SECONDS: u64 = 3_600;
+
+/// Maximum number of parts accepted by `create_thread`
+pub const MAX_THREAD_PARTS: usize = 25;
+
+/// Maximum number of posts a thread can grow to
+pub const MAX_THREAD_LENGTH: usize = 100;
+
+/// Default feed limit for pagination
+pub const DEFAULT_FEED_LIMIT: usize = 10;
+
+/// Maximum feed limit to prevent resource exhaustion
+pub const MAX_FEED_LIMIT: usize = 50;
+
+// ============================================================================
+// SOCIAL GRAPH TYPES
+// ============================================================================
+
+/// Social relationship between users
+#[allow(dead_code)]
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct FollowRelationship {
+    /// User who is following
+    pub follower: UserId,
+
+    /// User being followed
+    pub following: UserId,
+
+    /// When the follow relationship was created
+    pub created_at: u64,
+
+    /// Whether the relationship is mutual (both users follow each other)
+    pub is_mutual: bool,
+}
+
+/// Social connection metadata for efficient queries
+#[derive(CandidType, Deserialize, Clone, Debug, Default)]
+pub struct SocialConnections {
+    /// Set of users this user follows
+    pub following: BTreeSet<UserId>,
+
+    /// Set of users following this user
+    pub followers: BTreeSet<UserId>,
+
+    /// Set of users this user has blocked
+    pub blocked: BTreeSet<UserId>,
+
+    /// Set of users who have blocked this user
+    pub blocked_by: BTreeSet<UserId>,
+}
+
+/// Follow request for users with private profiles
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct FollowRequest {
+    /// Unique request identifier
+    pub id: u64,
+
+    /// User requesting to follow
+    pub requester: UserId,
+
+    /// User being requested to follow
+    pub target: UserId,
+
+    /// When the request was created
+    pub created_at: u64,
+
+    /// Status of the request
+    pub status: FollowRequestStatus,
+
+    /// Optional message with the request
+    pub message: Option<String>,
+}
+
+/// Accounts the viewer follows that also follow a target, for profile social proof
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct FollowersYouKnow {
+    /// First matching profiles, up to the requested limit
+    pub profiles: Vec<UserProfile>,
+
+    /// Total number of matching accounts
+    pub total: u64,
+}
+
+/// Overlap between the viewer's and a target's social graph
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct CommonFollowCounts {
+    /// Accounts the viewer follows that also follow the target
+    pub followers_you_know: u64,
+
+    /// Accounts both the viewer and the target follow
+    pub common_following: u64,
+}
+
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub enum FollowRequestStatus {
+    Pending,
+    Approved,
+    Rejected,
+    Cancelled,
+}
+
+// Add social graph limits and constants
+/// Maximum number of users one can follow to prevent spam
+pub const MAX_FOLLOWING_LIMIT: usize = 10_000;
+
+/// Maximum number of pending follow requests
+pub const MAX_PENDING_REQUESTS: usize = 100;
+
+/// Maximum length of a message attached to a follow request
+pub const MAX_FOLLOW_REQUEST_MESSAGE_LENGTH: usize = 280;
 
 /// Default limit for social connections pagination
 pub const DEFAULT_CONNECTIONS_LIMIT: usize = 20;
@@ -1,4 +1,5 @@
 use crate::types::*;
+use std::collections::BTreeMap;
 
 /// Validates username according to deCentra security standards
 ///
@@ -52,163 +53,1250 @@ pub fn validate_username(username: &str) -> Result<(), String> {
     }
 
     // Reserved words check
-    let reserved_words = [
-        "admin",
-        "administrator",
-        "mod",
-        "moderator",
-        "system",
-        "root",
-        "api",
-        "www",
-        "mail",
-        "email",
-        "support",
-        "help",
-        "info",
-        "news",
-        "blog",
-        "decentra",
-        "backend",
-        "frontend",
-        "canister",
-        "icp",
-        "dfinity",
-        "anonymous",
-        "null",
-        "undefined",
-        "true",
-        "false",
-        "test",
-        "demo",
-    ];
+    if is_reserved_username(username) {
+        return Err("Username is reserved and cannot be used".to_string());
+    }
+
+    Ok(())
+}
+
+/// Handles kept for the platform, in lowercase
+const RESERVED_USERNAMES: [&str; 28] = [
+    "admin",
+    "administrator",
+    "mod",
+    "moderator",
+    "system",
+    "root",
+    "api",
+    "www",
+    "mail",
+    "email",
+    "support",
+    "help",
+    "info",
+    "news",
+    "blog",
+    "decentra",
+    "backend",
+    "frontend",
+    "canister",
+    "icp",
+    "dfinity",
+    "anonymous",
+    "null",
+    "undefined",
+    "true",
+    "false",
+    "test",
+    "demo",
+];
+
+/// Whether a handle is kept for the platform (case-insensitive)
+pub fn is_reserved_username(username: &str) -> bool {
+    RESERVED_USERNAMES.contains(&username.to_lowercase().as_str())
+}
+
+/// Validates a user's display name
+///
+/// # Rules
+/// - Length: 1-64 characters (unicode allowed)
+/// - No control characters
+/// - No malicious content patterns
+pub fn validate_display_name(display_name: &str) -> Result<(), String> {
+    if display_name.trim().is_empty() {
+        return Err("Display name cannot be empty".to_string());
+    }
+
+    if display_name.chars().count() > MAX_DISPLAY_NAME_LENGTH {
+        return Err(format!(
+            "Display name must be at most {MAX_DISPLAY_NAME_LENGTH} characters"
+        ));
+    }
+
+    if display_name.chars().any(char::is_control) {
+        return Err("Display name cannot contain control characters".to_string());
+    }
+
+    if contains_malicious_patterns(display_name) {
+        return Err("Display name contains potentially harmful content".to_string());
+    }
+
+    Ok(())
+}
+
+/// Validates user bio content
+///
+/// # Rules
+/// - Maximum 500 characters
+/// - No malicious content patterns
+/// - Basic XSS prevention
+pub fn validate_bio(bio: &str) -> Result<(), String> {
+    if bio.len() > MAX_BIO_LENGTH {
+        return Err(format!("Bio must be less than {MAX_BIO_LENGTH} characters"));
+    }
+
+    // Basic XSS prevention
+    if contains_malicious_patterns(bio) {
+        return Err("Bio contains potentially harmful content".to_string());
+    }
+
+    Ok(())
+}
+
+/// Validates avatar content (emoji or short text)
+///
+/// # Rules
+/// - Maximum 200 characters
+/// - No URLs; images are uploaded with `upload_avatar` and stored on-chain
+/// - No `asset:` references; those are only assigned by `upload_avatar`
+/// - No malicious patterns
+pub fn validate_avatar(avatar: &str) -> Result<(), String> {
+    if avatar.len() > MAX_AVATAR_LENGTH {
+        return Err(format!(
+            "Avatar must be less than {MAX_AVATAR_LENGTH} characters"
+        ));
+    }
+
+    if avatar.contains("://") {
+        return Err("Avatar images must be uploaded with upload_avatar".to_string());
+    }
+    if avatar.starts_with(crate::avatar_store::ASSET_PREFIX) {
+        return Err("Avatar assets can only be set by upload_avatar".to_string());
+    }
+
+    // Check for malicious patterns
+    if contains_malicious_patterns(avatar) {
+        return Err("Avatar contains potentially harmful content".to_string());
+    }
+
+    Ok(())
+}
+
+/// Validates a profile banner URL
+///
+/// # Rules
+/// - Empty string clears the banner
+/// - Maximum 300 characters
+/// - Must be an https URL from a trusted image domain
+/// - No malicious patterns
+pub fn validate_banner(banner: &str) -> Result<(), String> {
+    if banner.is_empty() {
+        return Ok(());
+    }
+
+    if banner.len() > MAX_BANNER_LENGTH {
+        return Err(format!(
+            "Banner URL must be less than {MAX_BANNER_LENGTH} characters"
+        ));
+    }
+
+    if !is_valid_url(banner) {
+        return Err("Invalid banner URL format".to_string());
+    }
+
+    if !is_trusted_image_url(banner) {
+        return Err("Banner URL must be from a trusted domain".to_string());
+    }
+
+    if contains_malicious_patterns(banner) {
+        return Err("Banner contains potentially harmful content".to_string());
+    }
+
+    Ok(())
+}
+
+/// Validates a custom emoji registration
+///
+/// # Rules
+/// - Shortcode of 2 to MAX_EMOJI_SHORTCODE_LENGTH lowercase letters, digits or `_`
+/// - Asset must be an https URL from a trusted image domain
+pub fn validate_custom_emoji(shortcode: &str, asset_url: &str) -> Result<(), String> {
+    if !(2..=MAX_EMOJI_SHORTCODE_LENGTH).contains(&shortcode.len())
+        || !shortcode
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+    {
+        return Err(format!(
+            "Emoji shortcode must be 2-{MAX_EMOJI_SHORTCODE_LENGTH} lowercase letters, digits or underscores"
+        ));
+    }
+
+    if asset_url.len() > MAX_AVATAR_LENGTH || !is_valid_url(asset_url) {
+        return Err("Invalid emoji asset URL".to_string());
+    }
+    if !is_trusted_image_url(asset_url) {
+        return Err("Emoji asset must be from a trusted domain".to_string());
+    }
+
+    Ok(())
+}
+
+/// Validates post content according to deCentra standards
+///
+/// # Rules
+/// - Length: 1-10,000 characters
+/// - No excessive whitespace
+/// - Basic spam detection
+/// - No links to moderator-blocked domains
+/// - Linked images, video and audio must be on trusted image domains
+///
+/// Markup is not rejected: content is stored verbatim and exposed with an
+/// HTML-escaped rendering (see `security_utils::escape_html`).
+pub fn validate_post_content(content: &str) -> Result<(), String> {
+    let trimmed = content.trim();
+
+    // Length validation
+    if trimmed.len() < MIN_POST_CONTENT {
+        return Err("Post content cannot be empty".to_string());
+    }
+
+    if content.len() > MAX_POST_CONTENT {
+        return Err(format!(
+            "Post content must be less than {MAX_POST_CONTENT} characters"
+        ));
+    }
+
+    // Spam detection - repetitive content
+    if is_likely_spam(content) {
+        return Err("Post appears to be spam or repetitive content".to_string());
+    }
+
+    if let Some(domain) = blocked_domain_in_content(content) {
+        return Err(format!("Post links to blocked domain: {domain}"));
+    }
+
+    if let Some(host) =
+        crate::feed_filter::media_hosts(content).find(|host| !is_trusted_image_host(host))
+    {
+        return Err(format!(
+            "Post media must be hosted on a trusted domain: {host}"
+        ));
+    }
+
+    Ok(())
+}
+
+/// Validates comment content
+///
+/// # Rules
+/// - Length: 1-500 characters
+/// - Basic spam detection
+/// - No links to moderator-blocked domains
+///
+/// Like posts, comments are stored verbatim and rendered escaped.
+pub fn validate_comment_content(content: &str) -> Result<(), String> {
+    let trimmed = content.trim();
+
+    // Length validation
+    if trimmed.len() < MIN_COMMENT_CONTENT {
+        return Err("Comment cannot be empty".to_string());
+    }
+
+    if content.len() > MAX_COMMENT_CONTENT {
+        return Err(format!(
+            "Comment must be less than {MAX_COMMENT_CONTENT} characters"
+        ));
+    }
+
+    // Basic spam detection
+    if is_likely_spam(content) {
+        return Err("Comment appears to be spam or repetitive content".to_string());
+    }
+
+    if let Some(domain) = blocked_domain_in_content(content) {
+        return Err(format!("Comment links to blocked domain: {domain}"));
+    }
+
+    Ok(())
+}
+
+/// Validates a message attached to a follow request
+///
+/// # Rules
+/// - Length: 1-280 characters
+/// - No spam or malicious patterns
+pub fn validate_follow_request_message(message: &str) -> Result<(), String> {
+    if message.trim().is_empty() {
+        return Err("Follow request message cannot be empty".to_string());
+    }
+
+    if message.chars().count() > MAX_FOLLOW_REQUEST_MESSAGE_LENGTH {
+        return Err(format!(
+            "Follow request message must be at most {MAX_FOLLOW_REQUEST_MESSAGE_LENGTH} characters"
+        ));
+    }
+
+    if is_likely_spam(message) {
+        return Err("Follow request message appears to be spam".to_string());
+    }
+
+    if contains_malicious_patterns(message) {
+        return Err("Follow request message contains potentially harmful content".to_string());
+    }
+
+    Ok(())
+}
+
+/// Validates evidence supplied with a verification application
+///
+/// # Rules
+/// - Length: 1-2,000 characters
+/// - No malicious content
+pub fn validate_verification_evidence(evidence: &str) -> Result<(), String> {
+    if evidence.trim().is_empty() {
+        return Err("Verification evidence cannot be empty".to_string());
+    }
+
+    if evidence.len() > MAX_VERIFICATION_EVIDENCE_LENGTH {
+        return Err(format!(
+            "Verification evidence must be less than {MAX_VERIFICATION_EVIDENCE_LENGTH} characters"
+        ));
+    }
+
+    if contains_malicious_patterns(evidence) {
+        return Err("Verification evidence contains potentially harmful content".to_string());
+    }
+
+    Ok(())
+}
+
+/// Validates a moderator's review note or revocation reason
+///
+/// # Rules
+/// - Maximum 500 characters
+/// - No malicious content
+pub fn validate_review_note(note: &str) -> Result<(), String> {
+    if note.len() > MAX_REVIEW_NOTE_LENGTH {
+        return Err(format!(
+            "Review note must be less than {MAX_REVIEW_NOTE_LENGTH} characters"
+        ));
+    }
+
+    if contains_malicious_patterns(note) {
+        return Err("Review note contains potentially harmful content".to_string());
+    }
+
+    Ok(())
+}
+
+/// Validates a whistleblower submission before it is delivered
+///
+/// # Rules
+/// - Message: 1-10,000 characters
+/// - At most 5 attachments totalling 1.5 MB
+/// - Attachment names and MIME types must be non-empty and short
+pub fn validate_whistleblower_submission(
+    message: &str,
+    attachments: &[SubmissionAttachment],
+) -> Result<(), String> {
+    if message.trim().is_empty() {
+        return Err("Submission message cannot be empty".to_string());
+    }
+
+    if message.len() > MAX_SUBMISSION_MESSAGE_LENGTH {
+        return Err(format!(
+            "Submission message must be less than {MAX_SUBMISSION_MESSAGE_LENGTH} characters"
+        ));
+    }
+
+    if attachments.len() > MAX_SUBMISSION_ATTACHMENTS {
+        return Err(format!(
+            "Submissions can include at most {MAX_SUBMISSION_ATTACHMENTS} attachments"
+        ));
+    }
+
+    let total_bytes: usize = attachments.iter().map(|a| a.data.len()).sum();
+    if total_bytes > MAX_SUBMISSION_ATTACHMENT_BYTES {
+        return Err(format!(
+            "Attachments must total less than {MAX_SUBMISSION_ATTACHMENT_BYTES} bytes"
+        ));
+    }
+
+    for attachment in attachments {
+        if attachment.file_name.trim().is_empty() || attachment.file_name.len() > 255 {
+            return Err("Attachment file name must be 1-255 characters".to_string());
+        }
+        if attachment.mime_type.trim().is_empty() || attachment.mime_type.len() > 100 {
+            return Err("Attachment MIME type must be 1-100 characters".to_string());
+        }
+        if attachment.data.is_empty() {
+            return Err("Attachment cannot be empty".to_string());
+        }
+    }
+
+    Ok(())
+}
+
+/// Validates a direct message payload
+///
+/// # Rules
+/// - Text: 1-2,000 characters, no malicious content
+/// - Encrypted: 1-16,384 bytes (content is opaque to the canister)
+pub fn validate_message_content(content: &MessageContent) -> Result<(), String> {
+    match content {
+        MessageContent::Text(text) => {
+            if text.trim().is_empty() {
+                return Err("Message cannot be empty".to_string());
+            }
+            if text.len() > MAX_MESSAGE_LENGTH {
+                return Err(format!(
+                    "Message must be less than {MAX_MESSAGE_LENGTH} characters"
+                ));
+            }
+            if contains_malicious_patterns(text) {
+                return Err("Message contains potentially harmful content".to_string());
+            }
+        }
+        MessageContent::Encrypted(ciphertext) => {
+            if ciphertext.is_empty() {
+                return Err("Message cannot be empty".to_string());
+            }
+            if ciphertext.len() > MAX_MESSAGE_CIPHERTEXT_BYTES {
+                return Err(format!(
+                    "Message must be less than {MAX_MESSAGE_CIPHERTEXT_BYTES} bytes"
+                ));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Validates an encrypted private note
+///
+/// # Rules
+/// - Ciphertext: 1-65,536 bytes
+pub fn validate_note_ciphertext(ciphertext: &[u8]) -> Result<(), String> {
+    if ciphertext.is_empty() {
+        return Err("Note cannot be empty".to_string());
+    }
+    if ciphertext.len() > MAX_NOTE_CIPHERTEXT_BYTES {
+        return Err(format!(
+            "Note must be at most {MAX_NOTE_CIPHERTEXT_BYTES} bytes"
+        ));
+    }
+    Ok(())
+}
+
+/// Validates when a time capsule is revealed
+///
+/// # Rules
+/// - After `now` and at most MAX_TIME_CAPSULE_DAYS ahead
+pub fn validate_reveal_at(reveal_at: u64, now: u64) -> Result<(), String> {
+    if reveal_at <= now {
+        return Err("Reveal time must be in the future".to_string());
+    }
+    if reveal_at - now > MAX_TIME_CAPSULE_DAYS.saturating_mul(NANOS_PER_DAY) {
+        return Err(format!(
+            "Reveal time must be within {MAX_TIME_CAPSULE_DAYS} days"
+        ));
+    }
+    Ok(())
+}
+
+/// Validates the options and mode of a new poll
+///
+/// # Rules
+/// - MIN_POLL_OPTIONS-MAX_POLL_OPTIONS options; ranked polls need at least three
+/// - Options: 1-100 characters, distinct, no malicious content
+/// - Multi-select: `max_choices` between 2 and the number of options
+pub fn validate_poll(options: &[String], mode: PollMode) -> Result<(), String> {
+    let min_options = match mode {
+        PollMode::RankedChoice => MIN_POLL_OPTIONS + 1,
+        _ => MIN_POLL_OPTIONS,
+    };
+    if options.len() < min_options || options.len() > MAX_POLL_OPTIONS {
+        return Err(format!(
+            "This poll needs {min_options}-{MAX_POLL_OPTIONS} options"
+        ));
+    }
+    for (index, option) in options.iter().enumerate() {
+        if option.trim().is_empty() {
+            return Err("Poll options cannot be empty".to_string());
+        }
+        if option.chars().count() > MAX_POLL_OPTION_LENGTH {
+            return Err(format!(
+                "Poll options must be at most {MAX_POLL_OPTION_LENGTH} characters"
+            ));
+        }
+        if contains_malicious_patterns(option) {
+            return Err("Poll option contains potentially harmful content".to_string());
+        }
+        if options[..index].contains(option) {
+            return Err("Poll options must be distinct".to_string());
+        }
+    }
+    if let PollMode::MultiSelect { max_choices } = mode {
+        if max_choices < 2 || max_choices as usize > options.len() {
+            return Err(format!(
+                "Multi-select polls allow 2-{} choices",
+                options.len()
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Validates a ballot against a poll's mode
+///
+/// # Rules
+/// - Option indices in range and distinct
+/// - Single choice: exactly one option
+/// - Multi-select: 1 to `max_choices` options
+/// - Ranked choice: 1 to every option, most preferred first
+pub fn validate_ballot(choices: &[u32], option_count: usize, mode: PollMode) -> Result<(), String> {
+    let max_choices = match mode {
+        PollMode::SingleChoice => 1,
+        PollMode::MultiSelect { max_choices } => max_choices as usize,
+        PollMode::RankedChoice => option_count,
+    };
+    if choices.is_empty() || choices.len() > max_choices {
+        return Err(format!("Pick between 1 and {max_choices} options"));
+    }
+    for (index, choice) in choices.iter().enumerate() {
+        if *choice as usize >= option_count {
+            return Err("Invalid poll option".to_string());
+        }
+        if choices[..index].contains(choice) {
+            return Err("Each option can be picked only once".to_string());
+        }
+    }
+    Ok(())
+}
+
+/// Validates a live session title
+///
+/// # Rules
+/// - Length: 1-100 characters
+/// - No malicious content
+pub fn validate_session_title(title: &str) -> Result<(), String> {
+    if title.trim().is_empty() {
+        return Err("Session title cannot be empty".to_string());
+    }
+
+    if title.chars().count() > MAX_SESSION_TITLE_LENGTH {
+        return Err(format!(
+            "Session title must be at most {MAX_SESSION_TITLE_LENGTH} characters"
+        ));
+    }
+
+    if contains_malicious_patterns(title) {
+        return Err("Session title contains potentially harmful content".to_string());
+    }
+
+    Ok(())
+}
+
+/// Validates the link to a live session's external stream
+///
+/// # Rules
+/// - https link, not on the domain blocklist
+pub fn validate_stream_url(url: &str) -> Result<(), String> {
+    if !is_valid_url(url) {
+        return Err("Stream URL must be a valid https link".to_string());
+    }
+    if let Some(domain) = blocked_domain_in_content(url) {
+        return Err(format!("Stream links to blocked domain {domain}"));
+    }
+    Ok(())
+}
+
+/// Validates the metadata reported with an audio clip
+///
+/// # Rules
+/// - Duration: up to MAX_AUDIO_DURATION_SECONDS, not zero
+/// - Waveform: 1-256 samples
+pub fn validate_audio_metadata(duration_ms: u32, waveform: &[u8]) -> Result<(), String> {
+    if duration_ms == 0 || duration_ms > MAX_AUDIO_DURATION_SECONDS.saturating_mul(1_000) {
+        return Err(format!(
+            "Audio clips must be at most {MAX_AUDIO_DURATION_SECONDS} seconds"
+        ));
+    }
+
+    if waveform.is_empty() || waveform.len() > MAX_WAVEFORM_SAMPLES {
+        return Err(format!(
+            "Waveform must have 1-{MAX_WAVEFORM_SAMPLES} samples"
+        ));
+    }
+
+    Ok(())
+}
+
+/// Validates a description of media for screen readers
+///
+/// # Rules
+/// - Length: 1-1,500 characters
+/// - No malicious content
+pub fn validate_alt_text(alt_text: &str) -> Result<(), String> {
+    if alt_text.trim().is_empty() {
+        return Err("Alt text cannot be empty".to_string());
+    }
+
+    if alt_text.chars().count() > MAX_ALT_TEXT_LENGTH {
+        return Err(format!(
+            "Alt text must be at most {MAX_ALT_TEXT_LENGTH} characters"
+        ));
+    }
+
+    if contains_malicious_patterns(alt_text) {
+        return Err("Alt text contains potentially harmful content".to_string());
+    }
+
+    Ok(())
+}
+
+/// Validates the alt text given for the media links of a post
+///
+/// # Rules
+/// - Each entry describes a distinct link from `media_links`
+/// - Each alt text passes `validate_alt_text`
+/// - When `required`, every media link is described
+pub fn validate_media_alt_texts(
+    alt_texts: &[MediaAltText],
+    media_links: &[&str],
+    required: bool,
+) -> Result<(), String> {
+    for (index, entry) in alt_texts.iter().enumerate() {
+        if !media_links.contains(&entry.url.as_str()) {
+            return Err("Alt text must describe a media link in the post".to_string());
+        }
+        if alt_texts[..index]
+            .iter()
+            .any(|other| other.url == entry.url)
+        {
+            return Err("Each media link can be described only once".to_string());
+        }
+        validate_alt_text(&entry.alt_text)?;
+    }
+
+    if required
+        && media_links
+            .iter()
+            .any(|link| !alt_texts.iter().any(|entry| entry.url == *link))
+    {
+        return Err("Every image and video needs alt text".to_string());
+    }
+
+    Ok(())
+}
+
+/// Validates a user's timezone settings
+///
+/// # Rules
+/// - UTC offset between MIN_UTC_OFFSET_MINUTES and MAX_UTC_OFFSET_MINUTES, in
+///   whole quarter hours (every zone in use is)
+/// - Digest hour 0-23
+pub fn validate_timezone(
+    utc_offset_minutes: Option<i16>,
+    digest_hour: Option<u8>,
+) -> Result<(), String> {
+    if let Some(offset) = utc_offset_minutes {
+        if !(MIN_UTC_OFFSET_MINUTES..=MAX_UTC_OFFSET_MINUTES).contains(&offset) || offset % 15 != 0
+        {
+            return Err(
+                "UTC offset must be whole quarter hours between UTC-12:00 and UTC+14:00"
+                    .to_string(),
+            );
+        }
+    }
+    if digest_hour.is_some_and(|hour| hour > 23) {
+        return Err("Digest hour must be between 0 and 23".to_string());
+    }
+    Ok(())
+}
+
+/// Validates a call quota for an expensive query
+///
+/// # Rules
+/// - Endpoint listed in QUOTA_GUARDED_QUERIES
+/// - At least one call per principal and for anonymous callers
+/// - Window of 1 second to MAX_QUERY_QUOTA_WINDOW_SECONDS
+pub fn validate_query_quota(endpoint: &str, quota: &QueryQuota) -> Result<(), String> {
+    if !QUOTA_GUARDED_QUERIES.contains(&endpoint) {
+        return Err(format!(
+            "Quotas apply only to {}",
+            QUOTA_GUARDED_QUERIES.join(", ")
+        ));
+    }
+    if quota.per_principal == 0 || quota.anonymous_total == 0 {
+        return Err("Quotas must allow at least one call".to_string());
+    }
+    if !(1..=MAX_QUERY_QUOTA_WINDOW_SECONDS).contains(&quota.window_seconds) {
+        return Err(format!(
+            "Quota window must be 1-{MAX_QUERY_QUOTA_WINDOW_SECONDS} seconds"
+        ));
+    }
+    Ok(())
+}
+
+/// Validates an account standing gate for an action
+///
+/// # Rules
+/// - Action listed in TRUST_GATEABLE_ACTIONS
+/// - At least one requirement set
+/// - Account age of at most MAX_GATE_ACCOUNT_AGE_DAYS
+pub fn validate_action_gate(action: &str, gate: &ActionGate) -> Result<(), String> {
+    if !TRUST_GATEABLE_ACTIONS.contains(&action) {
+        return Err(format!(
+            "Gates apply only to {}",
+            TRUST_GATEABLE_ACTIONS.join(", ")
+        ));
+    }
+    if gate.min_account_age_days == 0 && gate.min_posts == 0 && gate.min_trust_tier.is_none() {
+        return Err("A gate must set at least one requirement".to_string());
+    }
+    if gate.min_account_age_days > MAX_GATE_ACCOUNT_AGE_DAYS {
+        return Err(format!(
+            "Account age requirement cannot exceed {MAX_GATE_ACCOUNT_AGE_DAYS} days"
+        ));
+    }
+    Ok(())
+}
+
+/// Validates a legal takedown request before it is logged
+///
+/// # Rules
+/// - Authority and jurisdiction of 1-100 characters
+/// - Summary of 1-500 characters with no malicious content
+pub fn validate_legal_request(request: &LegalRequest) -> Result<(), String> {
+    for (field, value) in [
+        ("Authority", &request.authority),
+        ("Jurisdiction", &request.jurisdiction),
+    ] {
+        let length = value.trim().chars().count();
+        if length == 0 || length > MAX_LEGAL_AUTHORITY_LENGTH {
+            return Err(format!(
+                "{field} must be 1-{MAX_LEGAL_AUTHORITY_LENGTH} characters"
+            ));
+        }
+    }
+    if request.summary.trim().is_empty() {
+        return Err("Summary cannot be empty".to_string());
+    }
+    validate_review_note(&request.summary)
+}
+
+/// Validates a push endpoint before it is registered
+///
+/// # Rules
+/// - At most 1024 characters
+/// - Web-push subscriptions are JSON with an `https://` endpoint and
+///   `p256dh` and `auth` keys
+/// - Relay tokens are non-empty and contain no whitespace
+pub fn validate_push_endpoint(endpoint: &PushEndpoint) -> Result<(), String> {
+    let value = match endpoint {
+        PushEndpoint::WebPush { subscription } => subscription,
+        PushEndpoint::RelayToken { token } => token,
+    };
+    if value.len() > MAX_PUSH_ENDPOINT_LENGTH {
+        return Err(format!(
+            "Push endpoint cannot exceed {MAX_PUSH_ENDPOINT_LENGTH} characters"
+        ));
+    }
+
+    match endpoint {
+        PushEndpoint::WebPush { subscription } => {
+            let subscription: serde_json::Value = serde_json::from_str(subscription)
+                .map_err(|_| "Subscription must be JSON".to_string())?;
+            let url = subscription["endpoint"].as_str().unwrap_or_default();
+            if !url.starts_with("https://") {
+                return Err("Subscription endpoint must use https".to_string());
+            }
+            for key in ["p256dh", "auth"] {
+                if subscription["keys"][key].as_str().is_none_or(str::is_empty) {
+                    return Err(format!("Subscription is missing the {key} key"));
+                }
+            }
+        }
+        PushEndpoint::RelayToken { token } => {
+            if token.is_empty() || token.chars().any(char::is_whitespace) {
+                return Err("Relay token must be non-empty with no whitespace".to_string());
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Validates a webhook before it is registered
+///
+/// # Rules
+/// - URL starts with `https://` and is at most 300 characters
+/// - At least one event, none repeated
+pub fn validate_webhook(url: &str, events: &[WebhookEvent]) -> Result<(), String> {
+    if !url.starts_with("https://") || url.len() > MAX_OUTCALL_ENDPOINT_LENGTH {
+        return Err(format!(
+            "Webhook URL must be an https URL of at most {MAX_OUTCALL_ENDPOINT_LENGTH} characters"
+        ));
+    }
+    if events.is_empty() {
+        return Err("Subscribe to at least one event".to_string());
+    }
+    if events
+        .iter()
+        .enumerate()
+        .any(|(index, event)| events[..index].contains(event))
+    {
+        return Err("Events must not repeat".to_string());
+    }
+    Ok(())
+}
+
+/// Validates the size of an archive import chunk
+///
+/// # Rules
+/// - At most 100 posts and 500 followed handles
+/// - Not empty
+pub fn validate_import_chunk(chunk: &ImportChunk) -> Result<(), String> {
+    if chunk.posts.len() > MAX_IMPORT_CHUNK_POSTS {
+        return Err(format!(
+            "A chunk can hold at most {MAX_IMPORT_CHUNK_POSTS} posts"
+        ));
+    }
+    if chunk.following.len() > MAX_IMPORT_CHUNK_FOLLOWS {
+        return Err(format!(
+            "A chunk can hold at most {MAX_IMPORT_CHUNK_FOLLOWS} followed accounts"
+        ));
+    }
+    if chunk.posts.is_empty() && chunk.following.is_empty() {
+        return Err("Chunk is empty".to_string());
+    }
+    Ok(())
+}
+
+/// Validates a post from another platform's export before it is imported
+///
+/// # Rules
+/// - Original id of 1-64 printable ASCII characters
+/// - Published between 2006-01-01 and `now`
+/// - Content and language pass the checks for new posts
+pub fn validate_exported_post(post: &ExportedPost, now: u64) -> Result<(), String> {
+    if post.original_id.is_empty()
+        || post.original_id.len() > MAX_IMPORT_ORIGINAL_ID_LENGTH
+        || !post.original_id.chars().all(|c| c.is_ascii_graphic())
+    {
+        return Err(format!(
+            "Original id must be 1-{MAX_IMPORT_ORIGINAL_ID_LENGTH} printable characters"
+        ));
+    }
+    let earliest = EARLIEST_IMPORT_TIMESTAMP_SECONDS * 1_000_000_000;
+    if post.created_at < earliest || post.created_at > now {
+        return Err("Original timestamp is out of range".to_string());
+    }
+    validate_post_content(&post.content)?;
+    if let Some(code) = &post.language {
+        validate_language_code(code)?;
+    }
+    Ok(())
+}
+
+/// Validates a group conversation title
+///
+/// # Rules
+/// - Length: 1-100 characters
+/// - No malicious content
+pub fn validate_group_title(title: &str) -> Result<(), String> {
+    if title.trim().is_empty() {
+        return Err("Group title cannot be empty".to_string());
+    }
+
+    if title.len() > MAX_GROUP_TITLE_LENGTH {
+        return Err(format!(
+            "Group title must be less than {MAX_GROUP_TITLE_LENGTH} characters"
+        ));
+    }
+
+    if contains_malicious_patterns(title) {
+        return Err("Group title contains potentially harmful content".to_string());
+    }
+
+    Ok(())
+}
+
+/// Validates a group message retention limit
+///
+/// # Rules
+/// - Between 10 and 10,000 messages
+pub fn validate_group_retention(limit: u64) -> Result<(), String> {
+    if !(MIN_GROUP_RETENTION..=MAX_GROUP_RETENTION).contains(&limit) {
+        return Err(format!(
+            "Retention limit must be between {MIN_GROUP_RETENTION} and {MAX_GROUP_RETENTION} messages"
+        ));
+    }
+
+    Ok(())
+}
+
+/// Validates a subscription tier name
+///
+/// # Rules
+/// - Length: 1-50 characters
+/// - No malicious content
+pub fn validate_tier_name(name: &str) -> Result<(), String> {
+    if name.trim().is_empty() {
+        return Err("Tier name cannot be empty".to_string());
+    }
+
+    if name.chars().count() > MAX_TIER_NAME_LENGTH {
+        return Err(format!(
+            "Tier name must be at most {MAX_TIER_NAME_LENGTH} characters"
+        ));
+    }
+
+    if contains_malicious_patterns(name) {
+        return Err("Tier name contains potentially harmful content".to_string());
+    }
+
+    Ok(())
+}
+
+/// Validates a subscription tier perks description
+///
+/// # Rules
+/// - Length: 0-1,000 characters
+/// - No malicious content
+pub fn validate_tier_perks(perks: &str) -> Result<(), String> {
+    if perks.chars().count() > MAX_TIER_PERKS_LENGTH {
+        return Err(format!(
+            "Perks must be at most {MAX_TIER_PERKS_LENGTH} characters"
+        ));
+    }
+
+    if contains_malicious_patterns(perks) {
+        return Err("Perks contain potentially harmful content".to_string());
+    }
+
+    Ok(())
+}
+
+/// Validates a language tag such as `en`, `pt-BR` or `zh-Hant`
+///
+/// # Validation Rules
+/// - 2-3 letter primary language subtag
+/// - Optional subtags of 2-8 alphanumeric characters separated by `-`
+/// - At most MAX_LANGUAGE_CODE_LENGTH characters overall
+pub fn validate_language_code(code: &str) -> Result<(), String> {
+    let invalid = || Err(format!("Invalid language code: {code}"));
+    if code.len() > MAX_LANGUAGE_CODE_LENGTH {
+        return invalid();
+    }
 
-    if reserved_words.contains(&username.to_lowercase().as_str()) {
-        return Err("Username is reserved and cannot be used".to_string());
+    let mut subtags = code.split('-');
+    let primary = subtags.next().unwrap_or_default();
+    if !(2..=3).contains(&primary.len()) || !primary.chars().all(|c| c.is_ascii_alphabetic()) {
+        return invalid();
+    }
+    if !subtags
+        .all(|tag| (2..=8).contains(&tag.len()) && tag.chars().all(|c| c.is_ascii_alphanumeric()))
+    {
+        return invalid();
     }
 
     Ok(())
 }
 
-/// Validates user bio content
+/// Validates post content written in the sanctioned markdown subset
 ///
-/// # Rules
-/// - Maximum 500 characters
-/// - No malicious content patterns
-/// - Basic XSS prevention
-pub fn validate_bio(bio: &str) -> Result<(), String> {
-    if bio.len() > MAX_BIO_LENGTH {
-        return Err(format!("Bio must be less than {MAX_BIO_LENGTH} characters"));
+/// # Validation Rules
+/// - Emphasis, inline code, code fences, lists, quotes and links are allowed
+/// - No raw HTML tags or comments
+/// - No embedded images (`![alt](url)`)
+/// - Link targets must be http(s) URLs
+/// - Code fences must be closed
+pub fn validate_markdown(content: &str) -> Result<(), String> {
+    let mut chars = content.char_indices().peekable();
+    while let Some((index, c)) = chars.next() {
+        let next = chars.peek().map(|(_, next)| *next);
+        match (c, next) {
+            ('<', Some(next)) if next.is_ascii_alphabetic() || next == '/' || next == '!' => {
+                return Err("Raw HTML is not allowed in markdown posts".to_string());
+            }
+            ('!', Some('[')) => {
+                return Err("Embedded images are not allowed in markdown posts".to_string());
+            }
+            (']', Some('(')) => {
+                let target = content[index + 2..].split(')').next().unwrap_or_default();
+                if !target.starts_with("https://") && !target.starts_with("http://") {
+                    return Err(format!(
+                        "Markdown link target must be an http(s) URL: {target}"
+                    ));
+                }
+            }
+            _ => {}
+        }
     }
 
-    // Basic XSS prevention
-    if contains_malicious_patterns(bio) {
-        return Err("Bio contains potentially harmful content".to_string());
+    let fences = content
+        .lines()
+        .filter(|line| line.trim_start().starts_with("```"))
+        .count();
+    if fences % 2 != 0 {
+        return Err("Markdown code fence is not closed".to_string());
     }
 
     Ok(())
 }
 
-/// Validates avatar content (URL or emoji)
+/// Validates custom word filter entries
 ///
-/// # Rules
-/// - Maximum 200 characters
-/// - Valid URL format if it's a URL
-/// - No malicious patterns
-pub fn validate_avatar(avatar: &str) -> Result<(), String> {
-    if avatar.len() > MAX_AVATAR_LENGTH {
+/// # Validation Rules
+/// - At most MAX_CUSTOM_FILTER_WORDS entries
+/// - Each a single word of 2 to MAX_FILTER_WORD_LENGTH letters or digits
+pub fn validate_filter_words(words: &[String]) -> Result<(), String> {
+    if words.len() > MAX_CUSTOM_FILTER_WORDS {
         return Err(format!(
-            "Avatar must be less than {MAX_AVATAR_LENGTH} characters"
+            "At most {MAX_CUSTOM_FILTER_WORDS} custom filter words allowed"
         ));
     }
 
-    // If it looks like a URL, validate URL format
-    if avatar.starts_with("http://") || avatar.starts_with("https://") {
-        if !is_valid_url(avatar) {
-            return Err("Invalid avatar URL format".to_string());
+    for word in words {
+        let length = word.chars().count();
+        if !(2..=MAX_FILTER_WORD_LENGTH).contains(&length)
+            || !word.chars().all(|c| c.is_alphanumeric())
+        {
+            return Err(format!(
+                "Filter words must be single words of 2-{MAX_FILTER_WORD_LENGTH} letters or digits"
+            ));
         }
+    }
 
-        // Check for safe domains (basic whitelist approach)
-        if !is_safe_avatar_url(avatar) {
-            return Err("Avatar URL must be from a trusted domain".to_string());
-        }
+    Ok(())
+}
+
+/// Validates a domain for the URL blocklist
+///
+/// # Validation Rules
+/// - Lowercase ASCII letters, digits, hyphens and dots
+/// - At least two labels, none empty or hyphen-edged
+/// - At most MAX_DOMAIN_LENGTH characters
+pub fn validate_domain(domain: &str) -> Result<(), String> {
+    let labels: Vec<&str> = domain.split('.').collect();
+    let valid = domain.len() <= MAX_DOMAIN_LENGTH
+        && labels.len() >= 2
+        && labels.iter().all(|label| {
+            !label.is_empty()
+                && !label.starts_with('-')
+                && !label.ends_with('-')
+                && label
+                    .chars()
+                    .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+        });
+
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("Invalid domain: {domain}"))
     }
+}
 
-    // Check for malicious patterns
-    if contains_malicious_patterns(avatar) {
-        return Err("Avatar contains potentially harmful content".to_string());
+/// Validates a developer application name
+///
+/// # Rules
+/// - Length: 1-64 characters
+/// - No malicious content
+pub fn validate_app_name(name: &str) -> Result<(), String> {
+    if name.trim().is_empty() {
+        return Err("App name cannot be empty".to_string());
+    }
+
+    if name.chars().count() > MAX_APP_NAME_LENGTH {
+        return Err(format!(
+            "App name must be at most {MAX_APP_NAME_LENGTH} characters"
+        ));
+    }
+
+    if contains_malicious_patterns(name) {
+        return Err("App name contains potentially harmful content".to_string());
     }
 
     Ok(())
 }
 
-/// Validates post content according to deCentra standards
+/// Validates a location attached to a post
 ///
 /// # Rules
-/// - Length: 1-10,000 characters
-/// - No excessive whitespace
-/// - Basic spam detection
-/// - Malicious content prevention
-pub fn validate_post_content(content: &str) -> Result<(), String> {
-    let trimmed = content.trim();
+/// - A place name, coordinates, or both
+/// - Place name: 1-100 characters, no malicious content
+/// - Latitude within ±90 and longitude within ±180 degrees
+pub fn validate_post_location(location: &PostLocation) -> Result<(), String> {
+    if location.place.is_none() && location.coordinates.is_none() {
+        return Err("Location needs a place name or coordinates".to_string());
+    }
 
-    // Length validation
-    if trimmed.len() < MIN_POST_CONTENT {
-        return Err("Post content cannot be empty".to_string());
+    if let Some(place) = &location.place {
+        if place.trim().is_empty() {
+            return Err("Place name cannot be empty".to_string());
+        }
+        if place.chars().count() > MAX_PLACE_NAME_LENGTH {
+            return Err(format!(
+                "Place name must be at most {MAX_PLACE_NAME_LENGTH} characters"
+            ));
+        }
+        if contains_malicious_patterns(place) {
+            return Err("Place name contains potentially harmful content".to_string());
+        }
     }
 
-    if content.len() > MAX_POST_CONTENT {
+    if let Some(point) = location.coordinates {
+        validate_geo_point(point)?;
+    }
+
+    Ok(())
+}
+
+/// Validates a region searched by location
+///
+/// # Rules
+/// - Valid corners, south-west below and left of north-east
+/// - At most MAX_REGION_DEGREES high and wide
+pub fn validate_geo_region(region: &GeoRegion) -> Result<(), String> {
+    validate_geo_point(region.south_west)?;
+    validate_geo_point(region.north_east)?;
+
+    let height = region.north_east.latitude - region.south_west.latitude;
+    let width = region.north_east.longitude - region.south_west.longitude;
+    if height < 0.0 || width < 0.0 {
+        return Err("Region corners are out of order".to_string());
+    }
+    if height > MAX_REGION_DEGREES || width > MAX_REGION_DEGREES {
         return Err(format!(
-            "Post content must be less than {MAX_POST_CONTENT} characters"
+            "Region must be at most {MAX_REGION_DEGREES} degrees across"
         ));
     }
 
-    // Spam detection - repetitive content
-    if is_likely_spam(content) {
-        return Err("Post appears to be spam or repetitive content".to_string());
+    Ok(())
+}
+
+/// Validates the sources cited by a post
+///
+/// # Rules
+/// - At most MAX_POST_SOURCES, each cited once
+/// - URL: https, not on the domain blocklist
+/// - Title: 1-200 characters, no malicious content
+/// - Archived copy hash: 64 lowercase hex characters
+pub fn validate_citations(sources: &[Citation]) -> Result<(), String> {
+    if sources.len() > MAX_POST_SOURCES {
+        return Err(format!("Posts can cite at most {MAX_POST_SOURCES} sources"));
     }
 
-    // Malicious content check
-    if contains_malicious_patterns(content) {
-        return Err("Post contains potentially harmful content".to_string());
+    for (index, source) in sources.iter().enumerate() {
+        if !is_valid_url(&source.url) {
+            return Err("Source URL must be a valid https link".to_string());
+        }
+        if let Some(domain) = blocked_domain_in_content(&source.url) {
+            return Err(format!("Source links to blocked domain {domain}"));
+        }
+        if sources[..index].iter().any(|other| other.url == source.url) {
+            return Err("Each source can be cited only once".to_string());
+        }
+
+        if source.title.trim().is_empty() {
+            return Err("Source title cannot be empty".to_string());
+        }
+        if source.title.chars().count() > MAX_CITATION_TITLE_LENGTH {
+            return Err(format!(
+                "Source title must be at most {MAX_CITATION_TITLE_LENGTH} characters"
+            ));
+        }
+        if contains_malicious_patterns(&source.title) {
+            return Err("Source title contains potentially harmful content".to_string());
+        }
+
+        if let Some(hash) = &source.archived_sha256 {
+            if hash.len() != 64 || !hash.bytes().all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f')) {
+                return Err("Archived copy hash must be a hex SHA-256 digest".to_string());
+            }
+        }
     }
 
     Ok(())
 }
 
-/// Validates comment content
+/// Validates the link to material supporting a fact-check label
 ///
 /// # Rules
-/// - Length: 1-500 characters
-/// - No malicious content
-/// - Basic spam detection
-pub fn validate_comment_content(content: &str) -> Result<(), String> {
-    let trimmed = content.trim();
+/// - https link, not on the domain blocklist
+pub fn validate_evidence_url(url: &str) -> Result<(), String> {
+    if !is_valid_url(url) {
+        return Err("Evidence URL must be a valid https link".to_string());
+    }
+    if let Some(domain) = blocked_domain_in_content(url) {
+        return Err(format!("Evidence links to blocked domain {domain}"));
+    }
+    Ok(())
+}
 
-    // Length validation
-    if trimmed.len() < MIN_COMMENT_CONTENT {
-        return Err("Comment cannot be empty".to_string());
+/// Validates a post author's appeal against a fact-check label
+///
+/// # Rules
+/// - Length: 1-1,000 characters
+/// - No malicious content
+pub fn validate_appeal_reason(reason: &str) -> Result<(), String> {
+    if reason.trim().is_empty() {
+        return Err("Appeal reason cannot be empty".to_string());
     }
 
-    if content.len() > MAX_COMMENT_CONTENT {
+    if reason.chars().count() > MAX_APPEAL_REASON_LENGTH {
         return Err(format!(
-            "Comment must be less than {MAX_COMMENT_CONTENT} characters"
+            "Appeal reason must be at most {MAX_APPEAL_REASON_LENGTH} characters"
         ));
     }
 
-    // Basic spam detection
-    if is_likely_spam(content) {
-        return Err("Comment appears to be spam or repetitive content".to_string());
+    if contains_malicious_patterns(reason) {
+        return Err("Appeal reason contains potentially harmful content".to_string());
     }
 
-    // Malicious content check
-    if contains_malicious_patterns(content) {
-        return Err("Comment contains potentially harmful content".to_string());
-    }
+    Ok(())
+}
 
+fn validate_geo_point(point: GeoPoint) -> Result<(), String> {
+    if !(-90.0..=90.0).contains(&point.latitude) || !(-180.0..=180.0).contains(&point.longitude) {
+        return Err("Coordinates are out of range".to_string());
+    }
     Ok(())
 }
 
+/// Finds the first link in `content` whose host is on the blocklist
+///
+/// Every whitespace-separated token is treated as a potential link (with or
+/// without a scheme), and subdomains of a blocked domain match as well.
+pub fn find_blocked_domain(
+    content: &str,
+    blocklist: &BTreeMap<String, BlockedDomain>,
+) -> Option<String> {
+    if blocklist.is_empty() {
+        return None;
+    }
+
+    content.split_whitespace().find_map(|token| {
+        let token = token
+            .trim_start_matches(|c: char| !c.is_alphanumeric())
+            .to_lowercase();
+        let without_scheme = token
+            .strip_prefix("https://")
+            .or_else(|| token.strip_prefix("http://"))
+            .unwrap_or(&token);
+        let host = without_scheme
+            .split(['/', '?', '#', ':'])
+            .next()
+            .unwrap_or_default()
+            .trim_end_matches(|c: char| !c.is_alphanumeric());
+
+        let mut candidate = host;
+        loop {
+            if blocklist.contains_key(candidate) {
+                return Some(candidate.to_string());
+            }
+            candidate = candidate.split_once('.')?.1;
+        }
+    })
+}
+
+/// Checks content against the moderator-maintained blocklist in canister state
+fn blocked_domain_in_content(content: &str) -> Option<String> {
+    crate::with_state(|state| find_blocked_domain(content, &state.blocked_domains))
+}
+
 // ============================================================================
 // SECURITY HELPER FUNCTIONS
 // ============================================================================
@@ -268,23 +1356,37 @@ fn is_valid_url(url: &str) -> bool {
         && !url.contains('\r')
 }
 
-/// Checks if avatar URL is from a trusted domain
-fn is_safe_avatar_url(url: &str) -> bool {
-    // Whitelist of safe domains for avatars
-    let safe_domains = [
-        "imgur.com",
-        "i.imgur.com",
-        "github.com",
-        "githubusercontent.com",
-        "gravatar.com",
-        "avatar.com",
-        "cloudinary.com",
-        "cloudflare.com",
-        "unsplash.com",
-        "pexels.com",
-    ];
+/// Checks if an image URL's host is on the trusted image domain list
+fn is_trusted_image_url(url: &str) -> bool {
+    let host = url
+        .strip_prefix("https://")
+        .unwrap_or(url)
+        .split(['/', '?', '#', ':'])
+        .next()
+        .unwrap_or_default()
+        .to_lowercase();
+    is_trusted_image_host(&host)
+}
 
-    safe_domains.iter().any(|domain| url.contains(domain))
+/// Checks a lowercase host (or one of its parent domains) against the
+/// admin-maintained trusted image domains
+fn is_trusted_image_host(host: &str) -> bool {
+    crate::with_state(|state| {
+        let mut candidate = host;
+        loop {
+            let trusted = match &state.trusted_image_domains {
+                Some(domains) => domains.contains(candidate),
+                None => DEFAULT_TRUSTED_IMAGE_DOMAINS.contains(&candidate),
+            };
+            if trusted {
+                return true;
+            }
+            match candidate.split_once('.') {
+                Some((_, parent)) => candidate = parent,
+                None => return false,
+            }
+        }
+    })
 }
 
 /// Detects likely spam content using basic heuristics
@@ -373,6 +1475,101 @@ fn has_excessive_special_chars(content: &str) -> bool {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_content_keeps_markup_characters() {
+        assert!(validate_post_content("Is 1 < 2? Try `if a > b {}` in your form").is_ok());
+        assert!(validate_comment_content("<b>bold</b> & more").is_ok());
+    }
+
+    #[test]
+    fn test_post_media_must_be_trusted() {
+        assert!(validate_post_content("Sunset https://i.imgur.com/sunset.jpg").is_ok());
+        assert!(validate_post_content("Read https://news.example/story").is_ok());
+        assert!(validate_post_content("Sunset https://cdn.example/sunset.jpg").is_err());
+    }
+
+    #[test]
+    fn test_markdown_validation() {
+        assert!(validate_markdown("**bold** _it_ `a < b` [docs](https://x.io)\n> quote").is_ok());
+        assert!(validate_markdown("```\nlet a = 1;\n```").is_ok());
+        assert!(validate_markdown("<b>bold</b>").is_err());
+        assert!(validate_markdown("![cat](https://x.io/cat.png)").is_err());
+        assert!(validate_markdown("[click](data:text/html,hi)").is_err());
+        assert!(validate_markdown("```\nunclosed").is_err());
+    }
+
+    #[test]
+    fn test_custom_emoji_validation() {
+        let asset = "https://images.unsplash.com/party.png";
+        assert!(validate_custom_emoji("party_parrot", asset).is_ok());
+        assert!(validate_custom_emoji("x", asset).is_err());
+        assert!(validate_custom_emoji("Party", asset).is_err());
+        assert!(validate_custom_emoji("party", "https://evil.example/p.png").is_err());
+        assert!(validate_custom_emoji("party", "http://images.unsplash.com/p.png").is_err());
+    }
+
+    #[test]
+    fn test_find_blocked_domain() {
+        let mut blocklist = BTreeMap::new();
+        blocklist.insert(
+            "evil.example".to_string(),
+            BlockedDomain {
+                domain: "evil.example".to_string(),
+                reason: "phishing".to_string(),
+                added_by: UserId(candid::Principal::anonymous()),
+                added_at: 0,
+            },
+        );
+
+        let found = |content: &str| find_blocked_domain(content, &blocklist);
+        assert_eq!(
+            found("see https://evil.example/login").as_deref(),
+            Some("evil.example")
+        );
+        assert_eq!(
+            found("(http://cdn.EVIL.example:443/x)").as_deref(),
+            Some("evil.example")
+        );
+        assert_eq!(
+            found("bare evil.example, too").as_deref(),
+            Some("evil.example")
+        );
+        assert_eq!(found("https://notevil.example and good.example"), None);
+        assert_eq!(found("no links here."), None);
+    }
+
+    #[test]
+    fn test_validate_domain() {
+        assert!(validate_domain("evil.example").is_ok());
+        assert!(validate_domain("sub.evil-site.co.uk").is_ok());
+        assert!(validate_domain("localhost").is_err());
+        assert!(validate_domain("Evil.example").is_err());
+        assert!(validate_domain("-bad.example").is_err());
+        assert!(validate_domain("bad..example").is_err());
+    }
+
+    #[test]
+    fn test_validate_filter_words() {
+        assert!(validate_filter_words(&["spoiler".to_string()]).is_ok());
+        assert!(validate_filter_words(&["two words".to_string()]).is_err());
+        assert!(validate_filter_words(&["x".to_string()]).is_err());
+        assert!(
+            validate_filter_words(&vec!["word".to_string(); MAX_CUSTOM_FILTER_WORDS + 1]).is_err()
+        );
+    }
+
+    #[test]
+    fn test_validate_language_code() {
+        assert!(validate_language_code("en").is_ok());
+        assert!(validate_language_code("pt-BR").is_ok());
+        assert!(validate_language_code("zh-Hant").is_ok());
+        assert!(validate_language_code("e").is_err());
+        assert!(validate_language_code("english").is_err());
+        assert!(validate_language_code("en-").is_err());
+        assert!(validate_language_code("en_US").is_err());
+        assert!(validate_language_code("en-<script>").is_err());
+    }
+
     #[test]
     fn test_username_validation() {
         // Valid usernames
@@ -390,6 +1587,37 @@ mod tests {
         assert!(validate_username("alice@bob").is_err()); // Invalid character
     }
 
+    #[test]
+    fn test_display_name_validation() {
+        assert!(validate_display_name("Alice Doe").is_ok());
+        assert!(validate_display_name("Amélie 🌍 Müller").is_ok());
+        assert!(validate_display_name(&"é".repeat(MAX_DISPLAY_NAME_LENGTH)).is_ok());
+        assert!(validate_display_name(&"é".repeat(MAX_DISPLAY_NAME_LENGTH + 1)).is_err());
+        assert!(validate_display_name("   ").is_err());
+        assert!(validate_display_name("tab\there").is_err());
+    }
+
+    #[test]
+    fn test_follow_request_message_validation() {
+        assert!(validate_follow_request_message("Hi, we met at the conference!").is_ok());
+        assert!(validate_follow_request_message("  ").is_err());
+        assert!(validate_follow_request_message(
+            &"a".repeat(MAX_FOLLOW_REQUEST_MESSAGE_LENGTH + 1)
+        )
+        .is_err());
+        assert!(validate_follow_request_message("<script>alert(1)</script>").is_err());
+    }
+
+    #[test]
+    fn test_banner_validation() {
+        assert!(validate_banner("").is_ok());
+        assert!(validate_banner("https://i.imgur.com/banner.png").is_ok());
+        assert!(validate_banner("http://i.imgur.com/banner.png").is_err()); // Not https
+        assert!(validate_banner("https://evil.example/banner.png").is_err()); // Untrusted domain
+        assert!(validate_banner("https://evil.example/imgur.com/b.png").is_err()); // Host must match
+        assert!(validate_banner("🌄").is_err()); // Banners are images only
+    }
+
     #[test]
     fn test_spam_detection() {
         assert!(is_likely_spam("AAAAAAAAAAAAA")); // Excessive repetition
@@ -398,6 +1626,433 @@ mod tests {
         assert!(!is_likely_spam("This is normal content")); // Normal content
     }
 
+    #[test]
+    fn test_verification_evidence_validation() {
+        assert!(validate_verification_evidence("https://example.org/press-card").is_ok());
+        assert!(validate_verification_evidence("   ").is_err());
+        assert!(
+            validate_verification_evidence(&"a".repeat(MAX_VERIFICATION_EVIDENCE_LENGTH + 1))
+                .is_err()
+        );
+        assert!(validate_verification_evidence("<script>alert(1)</script>").is_err());
+    }
+
+    #[test]
+    fn test_whistleblower_submission_validation() {
+        let document = SubmissionAttachment {
+            file_name: "memo.pdf".to_string(),
+            mime_type: "application/pdf".to_string(),
+            data: vec![1, 2, 3],
+        };
+        assert!(
+            validate_whistleblower_submission("See attached", std::slice::from_ref(&document))
+                .is_ok()
+        );
+        assert!(validate_whistleblower_submission("  ", &[]).is_err());
+
+        let oversized = SubmissionAttachment {
+            data: vec![0; MAX_SUBMISSION_ATTACHMENT_BYTES + 1],
+            ..document.clone()
+        };
+        assert!(validate_whistleblower_submission("See attached", &[oversized]).is_err());
+
+        let too_many = vec![document; MAX_SUBMISSION_ATTACHMENTS + 1];
+        assert!(validate_whistleblower_submission("See attached", &too_many).is_err());
+    }
+
+    #[test]
+    fn test_message_content_validation() {
+        assert!(validate_message_content(&MessageContent::Text("hi there".to_string())).is_ok());
+        assert!(validate_message_content(&MessageContent::Text(" ".to_string())).is_err());
+        assert!(validate_message_content(&MessageContent::Encrypted(vec![7; 32])).is_ok());
+        assert!(validate_message_content(&MessageContent::Encrypted(Vec::new())).is_err());
+        assert!(validate_message_content(&MessageContent::Encrypted(vec![
+            0;
+            MAX_MESSAGE_CIPHERTEXT_BYTES
+                + 1
+        ]))
+        .is_err());
+    }
+
+    #[test]
+    fn test_note_ciphertext_validation() {
+        assert!(validate_note_ciphertext(&[7; 48]).is_ok());
+        assert!(validate_note_ciphertext(&vec![0; MAX_NOTE_CIPHERTEXT_BYTES]).is_ok());
+        assert!(validate_note_ciphertext(&[]).is_err());
+        assert!(validate_note_ciphertext(&vec![0; MAX_NOTE_CIPHERTEXT_BYTES + 1]).is_err());
+    }
+
+    #[test]
+    fn test_reveal_at_validation() {
+        let now = 1_000 * NANOS_PER_DAY;
+        assert!(validate_reveal_at(now + 1, now).is_ok());
+        assert!(validate_reveal_at(now + MAX_TIME_CAPSULE_DAYS * NANOS_PER_DAY, now).is_ok());
+        assert!(validate_reveal_at(now, now).is_err());
+        assert!(validate_reveal_at(now - 1, now).is_err());
+        assert!(validate_reveal_at(now + MAX_TIME_CAPSULE_DAYS * NANOS_PER_DAY + 1, now).is_err());
+    }
+
+    #[test]
+    fn test_poll_validation() {
+        let options = |n: usize| (0..n).map(|i| format!("Option {i}")).collect::<Vec<_>>();
+        assert!(validate_poll(&options(2), PollMode::SingleChoice).is_ok());
+        assert!(validate_poll(&options(1), PollMode::SingleChoice).is_err());
+        assert!(validate_poll(&options(MAX_POLL_OPTIONS + 1), PollMode::SingleChoice).is_err());
+        assert!(validate_poll(&options(2), PollMode::RankedChoice).is_err());
+        assert!(validate_poll(&options(3), PollMode::RankedChoice).is_ok());
+        assert!(validate_poll(&options(3), PollMode::MultiSelect { max_choices: 3 }).is_ok());
+        assert!(validate_poll(&options(3), PollMode::MultiSelect { max_choices: 1 }).is_err());
+        assert!(validate_poll(&options(3), PollMode::MultiSelect { max_choices: 4 }).is_err());
+        let duplicated = vec!["Yes".to_string(), "No".to_string(), "Yes".to_string()];
+        assert!(validate_poll(&duplicated, PollMode::SingleChoice).is_err());
+        let blank = vec!["Yes".to_string(), " ".to_string()];
+        assert!(validate_poll(&blank, PollMode::SingleChoice).is_err());
+    }
+
+    #[test]
+    fn test_ballot_validation() {
+        assert!(validate_ballot(&[1], 3, PollMode::SingleChoice).is_ok());
+        assert!(validate_ballot(&[0, 1], 3, PollMode::SingleChoice).is_err());
+        assert!(validate_ballot(&[], 3, PollMode::SingleChoice).is_err());
+        assert!(validate_ballot(&[3], 3, PollMode::SingleChoice).is_err());
+        let multi = PollMode::MultiSelect { max_choices: 2 };
+        assert!(validate_ballot(&[0, 2], 3, multi).is_ok());
+        assert!(validate_ballot(&[0, 1, 2], 3, multi).is_err());
+        assert!(validate_ballot(&[2, 2], 3, multi).is_err());
+        assert!(validate_ballot(&[2, 0, 1], 3, PollMode::RankedChoice).is_ok());
+        assert!(validate_ballot(&[2], 3, PollMode::RankedChoice).is_ok());
+        assert!(validate_ballot(&[2, 0, 2], 3, PollMode::RankedChoice).is_err());
+    }
+
+    #[test]
+    fn test_live_session_validation() {
+        assert!(validate_session_title("Weekly newsroom Q&A").is_ok());
+        assert!(validate_session_title(" ").is_err());
+        assert!(validate_session_title(&"a".repeat(MAX_SESSION_TITLE_LENGTH + 1)).is_err());
+        assert!(validate_stream_url("https://stream.example.org/live/42").is_ok());
+        assert!(validate_stream_url("http://stream.example.org/live").is_err());
+        assert!(validate_stream_url("rtmp://stream.example.org").is_err());
+    }
+
+    #[test]
+    fn test_audio_metadata_validation() {
+        assert!(validate_audio_metadata(12_500, &[0, 40, 255, 90]).is_ok());
+        assert!(validate_audio_metadata(MAX_AUDIO_DURATION_SECONDS * 1_000, &[1]).is_ok());
+        assert!(validate_audio_metadata(0, &[1]).is_err());
+        assert!(validate_audio_metadata(MAX_AUDIO_DURATION_SECONDS * 1_000 + 1, &[1]).is_err());
+        assert!(validate_audio_metadata(1_000, &[]).is_err());
+        assert!(validate_audio_metadata(1_000, &[0; MAX_WAVEFORM_SAMPLES + 1]).is_err());
+    }
+
+    #[test]
+    fn test_alt_text_validation() {
+        let link = "https://cdn.example/chart.png";
+        let described = |url: &str| MediaAltText {
+            url: url.to_string(),
+            alt_text: "Bar chart of turnout by district".to_string(),
+        };
+        assert!(validate_media_alt_texts(&[described(link)], &[link], true).is_ok());
+        assert!(validate_media_alt_texts(&[], &[link], false).is_ok());
+        assert!(validate_media_alt_texts(&[], &[link], true).is_err());
+        assert!(validate_media_alt_texts(
+            &[described("https://other.example/x.png")],
+            &[link],
+            false
+        )
+        .is_err());
+        assert!(
+            validate_media_alt_texts(&[described(link), described(link)], &[link], false).is_err()
+        );
+        assert!(validate_alt_text(" ").is_err());
+        assert!(validate_alt_text(&"a".repeat(MAX_ALT_TEXT_LENGTH + 1)).is_err());
+    }
+
+    #[test]
+    fn test_validate_query_quota() {
+        let quota = QueryQuota {
+            per_principal: 60,
+            anonymous_total: 600,
+            window_seconds: 60,
+        };
+        assert!(validate_query_quota("get_followers", &quota).is_ok());
+        assert!(validate_query_quota("get_events", &quota).is_ok());
+        assert!(validate_query_quota("get_post", &quota).is_err());
+        assert!(validate_query_quota(
+            "get_followers",
+            &QueryQuota {
+                anonymous_total: 0,
+                ..quota.clone()
+            }
+        )
+        .is_err());
+        assert!(validate_query_quota(
+            "get_followers",
+            &QueryQuota {
+                window_seconds: MAX_QUERY_QUOTA_WINDOW_SECONDS + 1,
+                ..quota
+            }
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_validate_action_gate() {
+        let gate = ActionGate {
+            min_account_age_days: 7,
+            min_posts: 0,
+            min_trust_tier: None,
+        };
+        assert!(validate_action_gate("follow_user", &gate).is_ok());
+        assert!(validate_action_gate("create_post", &gate).is_err());
+        assert!(validate_action_gate(
+            "post_links",
+            &ActionGate {
+                min_account_age_days: 0,
+                ..gate.clone()
+            }
+        )
+        .is_err());
+        assert!(validate_action_gate(
+            "post_links",
+            &ActionGate {
+                min_account_age_days: MAX_GATE_ACCOUNT_AGE_DAYS + 1,
+                ..gate
+            }
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_validate_legal_request() {
+        let request = LegalRequest {
+            authority: "District Court".to_string(),
+            jurisdiction: "DE".to_string(),
+            target: None,
+            complied: true,
+            summary: "Removal order for one post".to_string(),
+        };
+        assert!(validate_legal_request(&request).is_ok());
+        assert!(validate_legal_request(&LegalRequest {
+            authority: " ".to_string(),
+            ..request.clone()
+        })
+        .is_err());
+        assert!(validate_legal_request(&LegalRequest {
+            jurisdiction: "x".repeat(MAX_LEGAL_AUTHORITY_LENGTH + 1),
+            ..request.clone()
+        })
+        .is_err());
+        assert!(validate_legal_request(&LegalRequest {
+            summary: String::new(),
+            ..request
+        })
+        .is_err());
+    }
+
+    #[test]
+    fn test_validate_push_endpoint() {
+        let web_push = |subscription: &str| PushEndpoint::WebPush {
+            subscription: subscription.to_string(),
+        };
+        assert!(validate_push_endpoint(&web_push(
+            r#"{"endpoint":"https://push.example/abc","keys":{"p256dh":"BNc","auth":"tBH"}}"#
+        ))
+        .is_ok());
+        assert!(validate_push_endpoint(&web_push(
+            r#"{"endpoint":"http://push.example/abc","keys":{"p256dh":"BNc","auth":"tBH"}}"#
+        ))
+        .is_err());
+        assert!(validate_push_endpoint(&web_push(
+            r#"{"endpoint":"https://push.example/abc","keys":{"p256dh":"BNc"}}"#
+        ))
+        .is_err());
+        assert!(validate_push_endpoint(&web_push("not json")).is_err());
+
+        let token = |token: String| PushEndpoint::RelayToken { token };
+        assert!(validate_push_endpoint(&token("fcm:abc123".to_string())).is_ok());
+        assert!(validate_push_endpoint(&token(String::new())).is_err());
+        assert!(validate_push_endpoint(&token("a b".to_string())).is_err());
+        assert!(validate_push_endpoint(&token("x".repeat(MAX_PUSH_ENDPOINT_LENGTH + 1))).is_err());
+    }
+
+    #[test]
+    fn test_validate_webhook() {
+        let events = [WebhookEvent::NewComment, WebhookEvent::TipReceived];
+        assert!(validate_webhook("https://hooks.example/decentra", &events).is_ok());
+        assert!(validate_webhook("http://hooks.example/decentra", &events).is_err());
+        assert!(validate_webhook("https://hooks.example/decentra", &[]).is_err());
+        assert!(validate_webhook(
+            "https://hooks.example/decentra",
+            &[WebhookEvent::NewFollower, WebhookEvent::NewFollower]
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_validate_exported_post() {
+        let now = 1_700_000_000 * 1_000_000_000;
+        let post = ExportedPost {
+            original_id: "1234567890".to_string(),
+            content: "Hello from 2015".to_string(),
+            created_at: 1_420_070_400 * 1_000_000_000,
+            language: Some("en".to_string()),
+        };
+        assert!(validate_exported_post(&post, now).is_ok());
+        assert!(validate_exported_post(
+            &ExportedPost {
+                original_id: String::new(),
+                ..post.clone()
+            },
+            now
+        )
+        .is_err());
+        assert!(validate_exported_post(
+            &ExportedPost {
+                created_at: now + 1,
+                ..post.clone()
+            },
+            now
+        )
+        .is_err());
+        assert!(validate_exported_post(
+            &ExportedPost {
+                created_at: 0,
+                ..post.clone()
+            },
+            now
+        )
+        .is_err());
+        assert!(validate_exported_post(
+            &ExportedPost {
+                content: String::new(),
+                ..post
+            },
+            now
+        )
+        .is_err());
+
+        let chunk = ImportChunk {
+            platform: ImportPlatform::Mastodon,
+            posts: Vec::new(),
+            following: vec!["@alice@mastodon.social".to_string()],
+            visibility: None,
+        };
+        assert!(validate_import_chunk(&chunk).is_ok());
+        assert!(validate_import_chunk(&ImportChunk {
+            following: Vec::new(),
+            ..chunk.clone()
+        })
+        .is_err());
+        assert!(validate_import_chunk(&ImportChunk {
+            following: vec!["bob".to_string(); MAX_IMPORT_CHUNK_FOLLOWS + 1],
+            ..chunk
+        })
+        .is_err());
+    }
+
+    #[test]
+    fn test_validate_timezone() {
+        assert!(validate_timezone(None, None).is_ok());
+        assert!(validate_timezone(Some(-300), Some(8)).is_ok());
+        assert!(validate_timezone(Some(345), Some(0)).is_ok()); // Nepal, UTC+5:45
+        assert!(validate_timezone(Some(MAX_UTC_OFFSET_MINUTES), None).is_ok());
+        assert!(validate_timezone(Some(MIN_UTC_OFFSET_MINUTES - 15), None).is_err());
+        assert!(validate_timezone(Some(100), None).is_err());
+        assert!(validate_timezone(None, Some(24)).is_err());
+    }
+
+    #[test]
+    fn test_group_settings_validation() {
+        assert!(validate_group_title("Investigations desk").is_ok());
+        assert!(validate_group_title("").is_err());
+        assert!(validate_group_retention(MIN_GROUP_RETENTION).is_ok());
+        assert!(validate_group_retention(MAX_GROUP_RETENTION).is_ok());
+        assert!(validate_group_retention(MIN_GROUP_RETENTION - 1).is_err());
+        assert!(validate_group_retention(MAX_GROUP_RETENTION + 1).is_err());
+    }
+
+    #[test]
+    fn test_subscription_tier_validation() {
+        assert!(validate_tier_name("Supporter").is_ok());
+        assert!(validate_tier_name("  ").is_err());
+        assert!(validate_tier_name(&"a".repeat(MAX_TIER_NAME_LENGTH + 1)).is_err());
+        assert!(validate_tier_perks("").is_ok());
+        assert!(validate_tier_perks("<script>alert(1)</script>").is_err());
+    }
+
+    #[test]
+    fn test_location_validation() {
+        let point = |latitude, longitude| GeoPoint {
+            latitude,
+            longitude,
+        };
+        let location = |place: Option<&str>, coordinates| PostLocation {
+            place: place.map(str::to_string),
+            coordinates,
+        };
+
+        assert!(validate_post_location(&location(Some("Lagos"), None)).is_ok());
+        assert!(validate_post_location(&location(None, Some(point(6.5, 3.4)))).is_ok());
+        assert!(validate_post_location(&location(None, None)).is_err());
+        assert!(validate_post_location(&location(Some(" "), None)).is_err());
+        assert!(validate_post_location(&location(None, Some(point(91.0, 0.0)))).is_err());
+        assert!(validate_post_location(&location(None, Some(point(0.0, f64::NAN)))).is_err());
+
+        let region = |south_west, north_east| GeoRegion {
+            south_west,
+            north_east,
+        };
+        assert!(validate_geo_region(&region(point(6.0, 3.0), point(7.0, 4.0))).is_ok());
+        assert!(validate_geo_region(&region(point(7.0, 3.0), point(6.0, 4.0))).is_err());
+        assert!(validate_geo_region(&region(point(0.0, 0.0), point(10.0, 1.0))).is_err());
+
+        assert_eq!(point(6.4649, 3.4061).coarse().cell(), (65, 34));
+        assert_eq!(point(-0.04, -0.06).cell(), (0, -1));
+    }
+
+    #[test]
+    fn test_citation_validation() {
+        let source = |url: &str, title: &str, hash: Option<String>| Citation {
+            url: url.to_string(),
+            title: title.to_string(),
+            archived_sha256: hash,
+        };
+        let report = source("https://example.org/report.pdf", "Audit report", None);
+
+        assert!(validate_citations(&[]).is_ok());
+        assert!(validate_citations(std::slice::from_ref(&report)).is_ok());
+        assert!(validate_citations(&[source(&report.url, "Audit", Some("ab".repeat(32)))]).is_ok());
+        assert!(
+            validate_citations(&[source(&report.url, "Audit", Some("AB".repeat(32)))]).is_err()
+        );
+        assert!(
+            validate_citations(&[source(&report.url, "Audit", Some("ab".repeat(16)))]).is_err()
+        );
+        assert!(validate_citations(&[source("http://example.org/a", "Audit", None)]).is_err());
+        assert!(validate_citations(&[source(&report.url, " ", None)]).is_err());
+        assert!(validate_citations(&[report.clone(), report.clone()]).is_err());
+        assert!(validate_citations(&vec![report; MAX_POST_SOURCES + 1]).is_err());
+    }
+
+    #[test]
+    fn test_fact_check_validation() {
+        assert!(validate_evidence_url("https://factcheck.example.org/claims/42").is_ok());
+        assert!(validate_evidence_url("http://factcheck.example.org/claims/42").is_err());
+        assert!(validate_evidence_url("https://bad link.org").is_err());
+
+        assert!(validate_appeal_reason("The quote is accurate; see the full video").is_ok());
+        assert!(validate_appeal_reason("  ").is_err());
+        assert!(validate_appeal_reason(&"a".repeat(MAX_APPEAL_REASON_LENGTH + 1)).is_err());
+    }
+
+    #[test]
+    fn test_app_name_validation() {
+        assert!(validate_app_name("Weather bot").is_ok());
+        assert!(validate_app_name("").is_err());
+        assert!(validate_app_name(&"a".repeat(MAX_APP_NAME_LENGTH + 1)).is_err());
+        assert!(validate_app_name("<script>alert(1)</script>").is_err());
+    }
+
     #[test]
     fn test_malicious_content_detection() {
         assert!(contains_malicious_patterns("<script>alert('xss')</script>"));
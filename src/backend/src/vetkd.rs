@@ -0,0 +1,204 @@
+use crate::types::UserId;
+use candid::{CandidType, Deserialize, Principal};
+use ic_cdk::api::call::call_with_payment128;
+
+/// Default vetKD master key on mainnet (`dfx_test_key` for local replicas)
+pub const DEFAULT_VETKD_KEY_NAME: &str = "key_1";
+
+/// Domain separator for keys derived for direct message conversations
+const DM_KEY_CONTEXT: &[u8] = b"decentra_direct_messages_v1";
+
+/// Domain separator for keys derived for users' private notes
+const NOTES_KEY_CONTEXT: &[u8] = b"decentra_private_notes_v1";
+
+/// Cycles attached to `vetkd_derive_key` (mainnet price for `key_1`)
+const VETKD_DERIVE_KEY_CYCLES: u128 = 26_153_846_153;
+
+// ============================================================================
+// MANAGEMENT CANISTER TYPES
+// ============================================================================
+
+#[derive(CandidType, Deserialize, Clone, Debug)]
+enum VetKdCurve {
+    #[serde(rename = "bls12_381_g2")]
+    Bls12381G2,
+}
+
+#[derive(CandidType, Deserialize, Clone, Debug)]
+struct VetKdKeyId {
+    curve: VetKdCurve,
+    name: String,
+}
+
+#[derive(CandidType, Deserialize, Clone, Debug)]
+struct VetKdPublicKeyArgs {
+    canister_id: Option<Principal>,
+    context: Vec<u8>,
+    key_id: VetKdKeyId,
+}
+
+#[derive(CandidType, Deserialize, Clone, Debug)]
+struct VetKdPublicKeyResult {
+    public_key: Vec<u8>,
+}
+
+#[derive(CandidType, Deserialize, Clone, Debug)]
+struct VetKdDeriveKeyArgs {
+    input: Vec<u8>,
+    context: Vec<u8>,
+    key_id: VetKdKeyId,
+    transport_public_key: Vec<u8>,
+}
+
+#[derive(CandidType, Deserialize, Clone, Debug)]
+struct VetKdDeriveKeyResult {
+    encrypted_key: Vec<u8>,
+}
+
+// ============================================================================
+// KEY DERIVATION
+// ============================================================================
+
+/// Builds the vetKD derivation input shared by both participants of a conversation
+///
+/// The input is independent of argument order, so both sides derive the same key
+/// before the first message is ever sent.
+pub fn conversation_key_input(a: &UserId, b: &UserId) -> Vec<u8> {
+    let (first, second) = if a <= b { (a, b) } else { (b, a) };
+    let mut input = Vec::new();
+    for principal in [first.0, second.0] {
+        let bytes = principal.as_slice();
+        input.push(bytes.len() as u8);
+        input.extend_from_slice(bytes);
+    }
+    input
+}
+
+/// Builds the vetKD derivation input for a user's private notes key
+pub fn notes_key_input(user: &UserId) -> Vec<u8> {
+    user.0.as_slice().to_vec()
+}
+
+/// Fetches the canister's derived vetKD public key for direct messages
+///
+/// Clients use it to verify keys returned by [`derive_conversation_key`].
+pub async fn dm_public_key(key_name: String) -> Result<Vec<u8>, String> {
+    public_key(key_name, DM_KEY_CONTEXT).await
+}
+
+/// Fetches the canister's derived vetKD public key for private notes
+///
+/// Clients use it to verify keys returned by [`derive_notes_key`].
+pub async fn notes_public_key(key_name: String) -> Result<Vec<u8>, String> {
+    public_key(key_name, NOTES_KEY_CONTEXT).await
+}
+
+/// Derives a conversation key encrypted under the caller's transport key
+///
+/// # Arguments
+/// * `key_name` - vetKD master key name
+/// * `input` - Derivation input from [`conversation_key_input`]
+/// * `transport_public_key` - Client's ephemeral BLS transport key
+pub async fn derive_conversation_key(
+    key_name: String,
+    input: Vec<u8>,
+    transport_public_key: Vec<u8>,
+) -> Result<Vec<u8>, String> {
+    derive_key(key_name, DM_KEY_CONTEXT, input, transport_public_key).await
+}
+
+/// Derives a private notes key encrypted under the caller's transport key
+///
+/// # Arguments
+/// * `key_name` - vetKD master key name
+/// * `input` - Derivation input from [`notes_key_input`]
+/// * `transport_public_key` - Client's ephemeral BLS transport key
+pub async fn derive_notes_key(
+    key_name: String,
+    input: Vec<u8>,
+    transport_public_key: Vec<u8>,
+) -> Result<Vec<u8>, String> {
+    derive_key(key_name, NOTES_KEY_CONTEXT, input, transport_public_key).await
+}
+
+async fn public_key(key_name: String, context: &[u8]) -> Result<Vec<u8>, String> {
+    let args = VetKdPublicKeyArgs {
+        canister_id: None,
+        context: context.to_vec(),
+        key_id: key_id(key_name),
+    };
+
+    let (result,): (VetKdPublicKeyResult,) = call_with_payment128(
+        Principal::management_canister(),
+        "vetkd_public_key",
+        (args,),
+        0,
+    )
+    .await
+    .map_err(|(code, msg)| format!("vetkd_public_key failed: {code:?} {msg}"))?;
+
+    Ok(result.public_key)
+}
+
+async fn derive_key(
+    key_name: String,
+    context: &[u8],
+    input: Vec<u8>,
+    transport_public_key: Vec<u8>,
+) -> Result<Vec<u8>, String> {
+    let args = VetKdDeriveKeyArgs {
+        input,
+        context: context.to_vec(),
+        key_id: key_id(key_name),
+        transport_public_key,
+    };
+
+    let (result,): (VetKdDeriveKeyResult,) = call_with_payment128(
+        Principal::management_canister(),
+        "vetkd_derive_key",
+        (args,),
+        VETKD_DERIVE_KEY_CYCLES,
+    )
+    .await
+    .map_err(|(code, msg)| format!("vetkd_derive_key failed: {code:?} {msg}"))?;
+
+    Ok(result.encrypted_key)
+}
+
+fn key_id(name: String) -> VetKdKeyId {
+    VetKdKeyId {
+        curve: VetKdCurve::Bls12381G2,
+        name,
+    }
+}
+
+// ============================================================================
+// VETKD TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_conversation_key_input_is_symmetric() -> Result<(), Box<dyn std::error::Error>> {
+        let alice = UserId(
+            Principal::from_text("rdmx6-jaaaa-aaaaa-aaadq-cai")
+                .map_err(|_| "Invalid test principal")?,
+        );
+        let bob = UserId(
+            Principal::from_text("ryjl3-tyaaa-aaaaa-aaaba-cai")
+                .map_err(|_| "Invalid test principal")?,
+        );
+
+        assert_eq!(
+            conversation_key_input(&alice, &bob),
+            conversation_key_input(&bob, &alice)
+        );
+        assert_ne!(
+            conversation_key_input(&alice, &bob),
+            conversation_key_input(&alice, &alice)
+        );
+        Ok(())
+    }
+}
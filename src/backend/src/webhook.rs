@@ -0,0 +1,220 @@
+use crate::types::{CommentId, PostId, UserId, WebhookEvent};
+use data_encoding::HEXLOWER;
+use ic_cdk::api::management_canister::http_request::{
+    http_request, CanisterHttpRequestArgument, HttpHeader, HttpMethod, TransformContext,
+};
+use serde_json::json;
+use sha2::{Digest, Sha256};
+
+/// Largest webhook response read by outcalls; only the status is used
+const MAX_WEBHOOK_RESPONSE_BYTES: u64 = 1024;
+
+/// Cycles attached to each webhook outcall (unused cycles are refunded)
+const WEBHOOK_OUTCALL_CYCLES: u128 = 20_000_000_000;
+
+/// SHA-256 block size in bytes
+const BLOCK_SIZE: usize = 64;
+
+/// What happened, with the ids a receiver needs to look it up
+pub enum EventData {
+    Comment {
+        post_id: PostId,
+        comment_id: CommentId,
+        author: UserId,
+    },
+    Follower {
+        follower: UserId,
+    },
+    Tip {
+        post_id: PostId,
+        from: UserId,
+        amount: u64,
+    },
+}
+
+impl EventData {
+    pub fn event(&self) -> WebhookEvent {
+        match self {
+            EventData::Comment { .. } => WebhookEvent::NewComment,
+            EventData::Follower { .. } => WebhookEvent::NewFollower,
+            EventData::Tip { .. } => WebhookEvent::TipReceived,
+        }
+    }
+}
+
+/// Name of an event in payloads
+pub fn event_name(event: &WebhookEvent) -> &'static str {
+    match event {
+        WebhookEvent::NewComment => "comment.created",
+        WebhookEvent::NewFollower => "follower.added",
+        WebhookEvent::TipReceived => "tip.received",
+    }
+}
+
+/// Serializes an event for delivery
+pub fn payload(delivery_id: u64, data: &EventData, created_at: u64) -> String {
+    let data_json = match data {
+        EventData::Comment {
+            post_id,
+            comment_id,
+            author,
+        } => json!({
+            "post_id": post_id.0,
+            "comment_id": comment_id.0,
+            "author": author.0.to_text(),
+        }),
+        EventData::Follower { follower } => json!({ "follower": follower.0.to_text() }),
+        EventData::Tip {
+            post_id,
+            from,
+            amount,
+        } => json!({
+            "post_id": post_id.0,
+            "from": from.0.to_text(),
+            "amount": amount,
+            "asset": "points",
+        }),
+    };
+    json!({
+        "id": delivery_id,
+        "event": event_name(&data.event()),
+        "created_at": created_at,
+        "data": data_json,
+    })
+    .to_string()
+}
+
+/// HMAC-SHA256 (RFC 2104)
+fn hmac_sha256(key: &[u8], message: &[u8]) -> [u8; 32] {
+    let mut block = [0u8; BLOCK_SIZE];
+    if key.len() > BLOCK_SIZE {
+        block[..32].copy_from_slice(&Sha256::digest(key));
+    } else {
+        block[..key.len()].copy_from_slice(key);
+    }
+
+    let mut inner = Sha256::new();
+    inner.update(block.map(|byte| byte ^ 0x36));
+    inner.update(message);
+    let mut outer = Sha256::new();
+    outer.update(block.map(|byte| byte ^ 0x5c));
+    outer.update(inner.finalize());
+    outer.finalize().into()
+}
+
+/// Value of the `X-Decentra-Signature` header
+///
+/// Signs `"{timestamp}.{body}"`, so receivers can reject replayed deliveries
+/// by checking `X-Decentra-Timestamp`.
+pub fn signature(secret: &str, timestamp: u64, body: &str) -> String {
+    let signed = format!("{timestamp}.{body}");
+    format!(
+        "sha256={}",
+        HEXLOWER.encode(&hmac_sha256(secret.as_bytes(), signed.as_bytes()))
+    )
+}
+
+/// Delivers a payload through an HTTPS outcall
+///
+/// Every replica makes the request, so receivers should deduplicate on the
+/// `X-Decentra-Delivery` header.
+///
+/// # Returns
+/// The response status, or an error for failed requests and non-2xx statuses
+pub async fn send(
+    url: &str,
+    secret: &str,
+    delivery_id: u64,
+    body: &str,
+    timestamp: u64,
+) -> Result<u16, String> {
+    let headers = vec![
+        HttpHeader {
+            name: "Content-Type".to_string(),
+            value: "application/json".to_string(),
+        },
+        HttpHeader {
+            name: "X-Decentra-Delivery".to_string(),
+            value: delivery_id.to_string(),
+        },
+        HttpHeader {
+            name: "X-Decentra-Timestamp".to_string(),
+            value: timestamp.to_string(),
+        },
+        HttpHeader {
+            name: "X-Decentra-Signature".to_string(),
+            value: signature(secret, timestamp, body),
+        },
+    ];
+    let request = CanisterHttpRequestArgument {
+        url: url.to_string(),
+        max_response_bytes: Some(MAX_WEBHOOK_RESPONSE_BYTES),
+        method: HttpMethod::POST,
+        headers,
+        body: Some(body.as_bytes().to_vec()),
+        transform: Some(TransformContext::from_name(
+            crate::activitypub::TRANSFORM_FUNCTION.to_string(),
+            Vec::new(),
+        )),
+    };
+
+    let (response,) = http_request(request, WEBHOOK_OUTCALL_CYCLES)
+        .await
+        .map_err(|(code, msg)| format!("Webhook request failed: {code:?} {msg}"))?;
+    let status = u16::try_from(response.status.0).unwrap_or(u16::MAX);
+    if !(200..300).contains(&status) {
+        return Err(format!("Webhook returned status {status}"));
+    }
+    Ok(status)
+}
+
+// ============================================================================
+// WEBHOOK TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use candid::Principal;
+    use serde_json::Value;
+
+    #[test]
+    fn test_hmac_sha256() {
+        // RFC 4231 test cases 2 and 6
+        assert_eq!(
+            HEXLOWER.encode(&hmac_sha256(b"Jefe", b"what do ya want for nothing?")),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+        assert_eq!(
+            HEXLOWER.encode(&hmac_sha256(
+                &[0xaa; 131],
+                b"Test Using Larger Than Block-Size Key - Hash Key First"
+            )),
+            "60e431591ee0b67f0d8a26aacbf5b77f8e0bc6213728c5140546040f0ee37f54"
+        );
+    }
+
+    #[test]
+    fn test_signature() {
+        let signed = signature("secret", 1_700_000_000, "{}");
+        assert!(signed.starts_with("sha256="));
+        assert_eq!(signed.len(), 7 + 64);
+        assert_ne!(signed, signature("secret", 1_700_000_001, "{}"));
+        assert_ne!(signed, signature("other", 1_700_000_000, "{}"));
+    }
+
+    #[test]
+    fn test_payload() {
+        let data = EventData::Tip {
+            post_id: PostId(3),
+            from: UserId(Principal::anonymous()),
+            amount: 25,
+        };
+        let body: Value = serde_json::from_str(&payload(9, &data, 1)).unwrap();
+        assert_eq!(body["id"], 9);
+        assert_eq!(body["event"], "tip.received");
+        assert_eq!(body["data"]["post_id"], 3);
+        assert_eq!(body["data"]["amount"], 25);
+        assert_eq!(body["data"]["from"], "2vxsx-fae");
+    }
+}
@@ -0,0 +1,110 @@
+/// Maintained default dictionary for the opt-in word filter
+///
+/// Entries are matched as whole words, including common inflections
+/// (see [`INFLECTION_SUFFIXES`]). This list is for viewer comfort only;
+/// abusive or dangerous content is rejected separately at validation time.
+pub const DEFAULT_FILTERED_WORDS: &[&str] = &[
+    "arse",
+    "arsehole",
+    "asshole",
+    "bastard",
+    "bitch",
+    "bollocks",
+    "bullshit",
+    "crap",
+    "cunt",
+    "dickhead",
+    "fuck",
+    "motherfucker",
+    "piss",
+    "shit",
+    "slut",
+    "twat",
+    "wanker",
+    "whore",
+];
+
+/// Suffixes under which a dictionary word still matches
+const INFLECTION_SUFFIXES: &[&str] = &["", "s", "es", "ed", "er", "ers", "ing", "y"];
+
+/// Whether a single lowercase word matches a filtered term
+fn word_matches(word: &str, term: &str) -> bool {
+    word.strip_prefix(term)
+        .is_some_and(|suffix| INFLECTION_SUFFIXES.contains(&suffix))
+}
+
+/// Masks filtered words in `text` with asterisks
+///
+/// # Arguments
+/// * `text` - Content to filter
+/// * `custom_words` - User-supplied additions to the default dictionary (lowercase)
+///
+/// # Returns
+/// `Some(masked)` when at least one word matched, otherwise `None`
+pub fn mask_filtered_words(text: &str, custom_words: &[String]) -> Option<String> {
+    let is_filtered = |word: &str| {
+        let word = word.to_lowercase();
+        DEFAULT_FILTERED_WORDS
+            .iter()
+            .copied()
+            .chain(custom_words.iter().map(String::as_str))
+            .any(|term| word_matches(&word, term))
+    };
+
+    let mut masked = String::with_capacity(text.len());
+    let mut word = String::new();
+    let mut matched = false;
+    let mut flush = |word: &mut String, masked: &mut String| {
+        if is_filtered(word) {
+            matched = true;
+            masked.extend(std::iter::repeat_n('*', word.chars().count()));
+        } else {
+            masked.push_str(word);
+        }
+        word.clear();
+    };
+
+    for c in text.chars() {
+        if c.is_alphanumeric() {
+            word.push(c);
+        } else {
+            flush(&mut word, &mut masked);
+            masked.push(c);
+        }
+    }
+    flush(&mut word, &mut masked);
+
+    matched.then_some(masked)
+}
+
+// ============================================================================
+// WORD FILTER TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mask_default_dictionary() {
+        assert_eq!(
+            mask_filtered_words("What the FUCK, that's bullshit!", &[]).as_deref(),
+            Some("What the ****, that's ********!")
+        );
+        assert_eq!(
+            mask_filtered_words("Stop fucking around", &[]).as_deref(),
+            Some("Stop ******* around")
+        );
+        assert_eq!(mask_filtered_words("Scunthorpe and Dickens", &[]), None);
+        assert_eq!(mask_filtered_words("Hello world", &[]), None);
+    }
+
+    #[test]
+    fn test_mask_custom_words() {
+        let custom = vec!["spoiler".to_string()];
+        assert_eq!(
+            mask_filtered_words("No spoilers please", &custom).as_deref(),
+            Some("No ******** please")
+        );
+    }
+}
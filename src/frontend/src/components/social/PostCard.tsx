@@ -81,7 +81,7 @@ export default function PostCard({
         [],
         []
       );
-      setComments(postComments);
+      setComments(postComments.comments);
     } catch (error) {
       console.error('Error loading comments:', error);
       setComments([]);
@@ -113,7 +113,11 @@ export default function PostCard({
 
     setIsSubmittingComment(true);
     try {
-      const result = await backend.add_comment(post.post.id, newComment.trim());
+      const result = await backend.add_comment(
+        post.post.id,
+        newComment.trim(),
+        []
+      );
       if ('Ok' in result) {
         setNewComment('');
         loadComments(); // Reload comments to show the new one
@@ -85,7 +85,7 @@ export default function UserConnectionsDisplay({
       }
 
       if ('Ok' in result) {
-        const newUsers = result.Ok;
+        const newUsers = result.Ok.profiles;
 
         if (reset) {
           if (activeTab === 'followers') {
@@ -101,7 +101,7 @@ export default function UserConnectionsDisplay({
           }
         }
 
-        setHasMore(newUsers.length === ITEMS_PER_PAGE);
+        setHasMore(result.Ok.has_more);
         setOffset(currentOffset + newUsers.length);
       } else {
         setError(result.Err);
@@ -1,6 +1,6 @@
 import { backend } from '../../../../declarations/backend';
 import type { Identity } from '@dfinity/agent';
-import type { Principal } from '@dfinity/principal';
+import { Principal } from '@dfinity/principal';
 import type {
   UserProfile,
   ProfileVisibility,
@@ -8,6 +8,10 @@ import type {
   VerificationStatus,
 } from '../types';
 import { isOk } from '../types';
+import {
+  solveProofOfWork,
+  usernameCheckSubject,
+} from '../utils/proof-of-work';
 
 /**
  * Authentication service that interfaces with the backend canister
@@ -107,7 +111,8 @@ export class AuthService {
 
   /**
    * Update the current user's profile
-   * Matches the backend's update_user_profile(username, bio?, avatar?) signature
+   * Matches the backend's update_user_profile(display_name?, bio?, avatar?,
+   * expected_version?) signature; omitted fields are left unchanged
    */
   async updateUserProfile(
     username: string,
@@ -120,9 +125,10 @@ export class AuthService {
       }
 
       const result = await backend.update_user_profile(
-        username,
+        [username],
         bio ? [bio] : [],
-        avatar ? [avatar] : []
+        avatar ? [avatar] : [],
+        []
       );
 
       if (isOk(result)) {
@@ -232,11 +238,28 @@ export class AuthService {
     return BigInt(date.getTime()) * 1_000_000n;
   } /**
    * Check if a username is available for registration
-   * Uses real-time validation to provide immediate feedback
+   * Uses real-time validation to provide immediate feedback; callers without
+   * a profile must attach a proof of work for the handle
    */
   async checkUsernameAvailability(username: string): Promise<boolean> {
     try {
-      const result = await backend.check_username_availability(username);
+      // Only anonymous callers are asked for a proof of work
+      const caller = this.getCurrentPrincipal() ?? Principal.anonymous();
+      const { difficulty, bucket } =
+        await backend.get_username_check_challenge();
+      const proofOfWork: [] | [bigint] =
+        caller.isAnonymous() && difficulty > 0
+          ? [
+              await solveProofOfWork(
+                usernameCheckSubject(caller.toText(), bucket, username),
+                difficulty
+              ),
+            ]
+          : [];
+      const result = await backend.check_username_availability(
+        username,
+        proofOfWork
+      );
 
       if (isOk(result)) {
         return result.Ok;
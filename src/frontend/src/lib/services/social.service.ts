@@ -213,7 +213,7 @@ export class SocialNetworkService {
         validLimit > 0 ? [BigInt(validLimit)] : []
       );
 
-      return result || [];
+      return result?.posts ?? [];
     } catch (error) {
       if (error instanceof SocialNetworkServiceError) {
         throw error;
@@ -395,7 +395,7 @@ export class SocialNetworkService {
         );
       }
 
-      const canisterPosts = result.Ok;
+      const canisterPosts = result.Ok.posts;
 
       if (canisterPosts.length === 0) {
         return [];
@@ -614,7 +614,7 @@ export class SocialNetworkService {
       // Validate comment content
       this.validateContent(content);
 
-      const result = await backend.add_comment(postId, content);
+      const result = await backend.add_comment(postId, content, []);
 
       if (isOk(result)) {
         return result.Ok;
@@ -663,7 +663,7 @@ export class SocialNetworkService {
         validLimit > 0 ? [BigInt(validLimit)] : []
       );
 
-      return result || [];
+      return result?.comments ?? [];
     } catch (error) {
       if (error instanceof SocialNetworkServiceError) {
         throw error;
@@ -816,7 +816,7 @@ export class SocialNetworkService {
       );
 
       if (isOk(result)) {
-        return result.Ok;
+        return result.Ok.profiles;
       } else {
         throw new SocialNetworkServiceError(
           'FOLLOWERS_FETCH_FAILED',
@@ -858,7 +858,7 @@ export class SocialNetworkService {
       );
 
       if (isOk(result)) {
-        return result.Ok;
+        return result.Ok.profiles;
       } else {
         throw new SocialNetworkServiceError(
           'FOLLOWING_FETCH_FAILED',
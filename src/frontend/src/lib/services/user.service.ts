@@ -214,9 +214,10 @@ export class UserService {
       }
 
       const result = await this.actor.update_user_profile(
-        updateData.username,
+        [updateData.username],
         updateData.bio ? [updateData.bio] : [],
-        updateData.avatar ? [updateData.avatar] : []
+        updateData.avatar ? [updateData.avatar] : [],
+        []
       );
 
       if ('Ok' in result) {
@@ -27,9 +27,9 @@ export type {
 // Import types we need for interfaces
 import type {
   UserProfile,
-  Post,
   PostVisibility,
   PlatformStats,
+  UserPostsPage,
   Result_1,
   Result_2,
   Result_3,
@@ -48,9 +48,10 @@ export interface UserService {
     avatar?: string
   ) => Promise<Result_3>;
   update_user_profile: (
-    username: string,
+    displayName?: string,
     bio?: string,
-    avatar?: string
+    avatar?: string,
+    expectedVersion?: bigint
   ) => Promise<Result_3>;
   get_user_profile: (userId: Principal) => Promise<UserProfile | undefined>;
   get_my_profile: () => Promise<UserProfile | undefined>;
@@ -84,7 +85,7 @@ export interface UserService {
     userId: Principal,
     offset?: bigint,
     limit?: bigint
-  ) => Promise<Post[]>;
+  ) => Promise<UserPostsPage>;
   get_social_feed: (offset?: bigint, limit?: bigint) => Promise<Result_6>;
   get_user_feed: (offset?: bigint, limit?: bigint) => Promise<Result_6>;
 
@@ -0,0 +1,60 @@
+/**
+ * Proof of work for username availability checks
+ * Mirrors the backend's security_utils::verify_proof_of_work
+ */
+
+/**
+ * Normalizes a handle the way the backend does before hashing it
+ */
+export function normalizeHandle(handle: string): string {
+  return handle.trim().replace(/^@+/, '').toLowerCase();
+}
+
+/**
+ * Builds the subject a username check proof of work is computed over,
+ * binding it to the caller and the backend's current time bucket
+ */
+export function usernameCheckSubject(
+  caller: string,
+  bucket: bigint,
+  handle: string
+): string {
+  return `${caller}:${bucket}:${normalizeHandle(handle)}`;
+}
+
+/**
+ * Counts the leading zero bits of a hash
+ */
+function leadingZeroBits(hash: Uint8Array): number {
+  let bits = 0;
+  for (const byte of hash) {
+    if (byte === 0) {
+      bits += 8;
+      continue;
+    }
+    return bits + Math.clz32(byte) - 24;
+  }
+  return bits;
+}
+
+/**
+ * Finds a nonce whose SHA-256 over the subject followed by the big-endian
+ * nonce starts with `difficultyBits` zero bits
+ */
+export async function solveProofOfWork(
+  subject: string,
+  difficultyBits: number
+): Promise<bigint> {
+  const prefix = new TextEncoder().encode(subject);
+  const message = new Uint8Array(prefix.length + 8);
+  message.set(prefix);
+  const view = new DataView(message.buffer);
+
+  for (let nonce = 0n; ; nonce++) {
+    view.setBigUint64(prefix.length, nonce);
+    const hash = new Uint8Array(await crypto.subtle.digest('SHA-256', message));
+    if (leadingZeroBits(hash) >= difficultyBits) {
+      return nonce;
+    }
+  }
+}